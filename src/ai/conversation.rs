@@ -7,6 +7,26 @@ use super::intent::{Entity, Intent, ParsedInput};
 /// State of an ongoing conversation
 #[derive(Debug, Clone, Default)]
 pub struct ConversationState {
+    /// Context that outlives any one operation
+    pub context: ConversationContext,
+    /// The operation being worked on, dropped when it completes or is cancelled
+    pub operation: OperationState,
+    /// Conversation history (last N turns)
+    pub history: Vec<ConversationTurn>,
+}
+
+/// What the user has been talking about, kept across operations
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConversationContext {
+    /// Last account mentioned
+    pub last_account: Option<String>,
+    /// Last asset mentioned
+    pub last_asset: Option<String>,
+}
+
+/// The multi-turn operation in progress, if any
+#[derive(Debug, Clone, Default)]
+pub struct OperationState {
     /// Current intent being worked on
     pub current_intent: Option<Intent>,
     /// Entities collected so far
@@ -15,12 +35,20 @@ pub struct ConversationState {
     pub missing_entities: Vec<String>,
     /// Is a confirmation pending?
     pub confirmation_pending: bool,
-    /// Last account mentioned (for context)
-    pub last_account: Option<String>,
-    /// Last asset mentioned (for context)
-    pub last_asset: Option<String>,
-    /// Conversation history (last N turns)
-    pub history: Vec<ConversationTurn>,
+}
+
+impl ConversationContext {
+    /// Remember `entity` if `field` names an account or an asset
+    fn remember(&mut self, field: &str, entity: &Entity) {
+        let Entity::String(value) = entity else {
+            return;
+        };
+        match field {
+            "account" | "from_account" => self.last_account = Some(value.clone()),
+            "asset" => self.last_asset = Some(value.clone()),
+            _ => {}
+        }
+    }
 }
 
 /// A single turn in the conversation
@@ -44,8 +72,7 @@ impl ConversationState {
     /// Update state from shell context
     pub fn from_shell_context(last_account: Option<String>, last_asset: Option<String>) -> Self {
         Self {
-            last_account,
-            last_asset,
+            context: ConversationContext { last_account, last_asset },
             ..Default::default()
         }
     }
@@ -59,39 +86,40 @@ impl ConversationState {
         }
     }
 
-    /// Clear current operation state (after completion or cancellation)
+    /// Clear current operation state (after completion or cancellation).
+    /// The context layer is kept.
     pub fn clear_operation(&mut self) {
-        self.current_intent = None;
-        self.collected_entities.clear();
-        self.missing_entities.clear();
-        self.confirmation_pending = false;
+        self.operation = OperationState::default();
     }
 
     /// Update context from parsed input
     pub fn update_from_parsed(&mut self, parsed: &ParsedInput) {
-        // Update last known account
-        if let Some(account) = parsed.get_string("account") {
-            self.last_account = Some(account.to_string());
-        }
-        if let Some(account) = parsed.get_string("from_account") {
-            self.last_account = Some(account.to_string());
+        // In this order, so a transfer's source account wins over `account`
+        for field in ["account", "from_account", "asset"] {
+            if let Some(entity) = parsed.entities.get(field) {
+                self.context.remember(field, entity);
+            }
         }
+    }
 
-        // Update last known asset
-        if let Some(asset) = parsed.get_string("asset") {
-            self.last_asset = Some(asset.to_string());
-        }
+    /// Record an entity the user supplied for the current operation,
+    /// remembering accounts and assets in the context
+    pub fn collect(&mut self, field: &str, entity: Entity) {
+        self.context.remember(field, &entity);
+        self.operation.collected_entities.insert(field.to_string(), entity);
+        self.operation.missing_entities.retain(|f| f != field);
     }
 
     /// Get context summary for display
     pub fn context_summary(&self) -> Option<String> {
         let mut parts = Vec::new();
 
-        if let Some(ref intent) = self.current_intent {
+        if let Some(ref intent) = self.operation.current_intent {
             parts.push(format!("intent: {:?}", intent));
         }
-        if !self.collected_entities.is_empty() {
+        if !self.operation.collected_entities.is_empty() {
             let entities: Vec<String> = self
+                .operation
                 .collected_entities
                 .iter()
                 .map(|(k, v)| format!("{}={}", k, v))
@@ -210,12 +238,12 @@ impl ConversationManager {
         }
 
         // Update state with new intent/entities
-        self.state.current_intent = Some(parsed.intent.clone());
-        self.state.missing_entities = parsed.missing.clone();
+        self.state.operation.current_intent = Some(parsed.intent.clone());
+        self.state.operation.missing_entities = parsed.missing.clone();
 
         // Merge entities
         for (key, value) in parsed.entities.iter() {
-            self.state.collected_entities.insert(key.clone(), value.clone());
+            self.state.operation.collected_entities.insert(key.clone(), value.clone());
         }
 
         // Apply context defaults
@@ -239,7 +267,7 @@ impl ConversationManager {
 
         // All entities collected - build confirmation if needed
         if parsed.intent.requires_confirmation() {
-            self.state.confirmation_pending = true;
+            self.state.operation.confirmation_pending = true;
             let (summary, details) = self.build_confirmation_summary(&parsed.intent);
             let command = self.build_command(&parsed.intent);
             return ConversationAction::Confirm {
@@ -261,7 +289,7 @@ impl ConversationManager {
 
         match input_lower.as_str() {
             "y" | "yes" | "" => {
-                if let Some(ref intent) = self.state.current_intent.clone() {
+                if let Some(ref intent) = self.state.operation.current_intent.clone() {
                     let command = self.build_command(intent);
                     self.state.clear_operation();
                     ConversationAction::Execute { command }
@@ -330,8 +358,8 @@ impl ConversationManager {
     fn apply_context_defaults(&mut self, parsed: &ParsedInput) {
         // If account is missing and we have a last_account, use it
         if parsed.missing.contains(&"account".to_string()) {
-            if let Some(ref account) = self.state.last_account {
-                self.state.collected_entities.insert(
+            if let Some(ref account) = self.state.context.last_account {
+                self.state.operation.collected_entities.insert(
                     "account".to_string(),
                     Entity::String(account.clone()),
                 );
@@ -344,7 +372,7 @@ impl ConversationManager {
         let required = intent.required_entities();
         required
             .into_iter()
-            .filter(|e| !self.state.collected_entities.contains_key(*e))
+            .filter(|e| !self.state.operation.collected_entities.contains_key(*e))
             .map(|s| s.to_string())
             .collect()
     }
@@ -405,30 +433,30 @@ impl ConversationManager {
             _ => "EXECUTE",
         };
 
-        if let Some(Entity::String(asset)) = self.state.collected_entities.get("asset") {
+        if let Some(Entity::String(asset)) = self.state.operation.collected_entities.get("asset") {
             details.push(("Asset".to_string(), asset.clone()));
         }
-        if let Some(Entity::Number(qty)) = self.state.collected_entities.get("quantity") {
+        if let Some(Entity::Number(qty)) = self.state.operation.collected_entities.get("quantity") {
             details.push(("Quantity".to_string(), qty.to_string()));
         }
-        if let Some(Entity::Number(price)) = self.state.collected_entities.get("price") {
+        if let Some(Entity::Number(price)) = self.state.operation.collected_entities.get("price") {
             details.push(("Price".to_string(), format!("${:.2}", price)));
         }
-        if let Some(Entity::String(account)) = self.state.collected_entities.get("account") {
+        if let Some(Entity::String(account)) = self.state.operation.collected_entities.get("account") {
             details.push(("Account".to_string(), account.clone()));
         }
-        if let Some(Entity::String(from)) = self.state.collected_entities.get("from_account") {
+        if let Some(Entity::String(from)) = self.state.operation.collected_entities.get("from_account") {
             details.push(("From".to_string(), from.clone()));
         }
-        if let Some(Entity::String(to)) = self.state.collected_entities.get("to_account") {
+        if let Some(Entity::String(to)) = self.state.operation.collected_entities.get("to_account") {
             details.push(("To".to_string(), to.clone()));
         }
 
         // Calculate total if buy/sell
         if matches!(intent, Intent::TxBuy | Intent::TxSell) {
             if let (Some(Entity::Number(qty)), Some(Entity::Number(price))) = (
-                self.state.collected_entities.get("quantity"),
-                self.state.collected_entities.get("price"),
+                self.state.operation.collected_entities.get("quantity"),
+                self.state.operation.collected_entities.get("price"),
             ) {
                 let total = qty * price;
                 details.push(("Total".to_string(), format!("${:.2}", total)));
@@ -443,7 +471,7 @@ impl ConversationManager {
     fn build_command(&self, intent: &Intent) -> String {
         let parsed = ParsedInput {
             intent: intent.clone(),
-            entities: self.state.collected_entities.clone(),
+            entities: self.state.operation.collected_entities.clone(),
             missing: vec![],
             confidence: 1.0,
             raw_input: String::new(),
@@ -458,3 +486,64 @@ impl Default for ConversationManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buy(entities: &[(&str, Entity)]) -> ParsedInput {
+        let entities: HashMap<String, Entity> = entities.iter().map(|(k, v)| (k.to_string(), v.clone())).collect();
+        let missing = Intent::TxBuy
+            .required_entities()
+            .into_iter()
+            .filter(|e| !entities.contains_key(*e))
+            .map(|e| e.to_string())
+            .collect();
+        ParsedInput {
+            intent: Intent::TxBuy,
+            entities,
+            missing,
+            confidence: 0.9,
+            raw_input: "bought some bitcoin".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cancel_keeps_context() {
+        let mut manager = ConversationManager::new();
+        manager.process(buy(&[
+            ("asset", Entity::String("BTC".to_string())),
+            ("account", Entity::String("Binance".to_string())),
+        ]));
+        assert!(manager.state().operation.current_intent.is_some());
+
+        manager.state_mut().clear_operation();
+
+        let state = manager.state();
+        assert!(state.operation.current_intent.is_none());
+        assert!(state.operation.collected_entities.is_empty());
+        assert_eq!(state.context.last_account.as_deref(), Some("Binance"));
+        assert_eq!(state.context.last_asset.as_deref(), Some("BTC"));
+    }
+
+    #[test]
+    fn test_collected_account_survives_cancellation() {
+        let mut manager = ConversationManager::new();
+        manager.process(buy(&[("asset", Entity::String("ETH".to_string()))]));
+
+        // The account is given in answer to a clarifying question
+        manager.state_mut().collect("account", Entity::String("Ledger".to_string()));
+        assert!(!manager.state().operation.missing_entities.contains(&"account".to_string()));
+
+        let action = manager.handle_confirmation("n");
+        assert!(matches!(action, ConversationAction::Cancel { .. }));
+        assert_eq!(manager.state().context.last_account.as_deref(), Some("Ledger"));
+
+        // The next operation starts from that account
+        manager.process(buy(&[("asset", Entity::String("BTC".to_string()))]));
+        assert!(matches!(
+            manager.state().operation.collected_entities.get("account"),
+            Some(Entity::String(account)) if account == "Ledger"
+        ));
+    }
+}
@@ -202,15 +202,16 @@ IMPORTANT:
     fn build_context(&self, context: &ConversationState) -> String {
         let mut ctx_parts = Vec::new();
 
-        if let Some(ref account) = context.last_account {
+        if let Some(ref account) = context.context.last_account {
             ctx_parts.push(format!("Last used account: {}", account));
         }
-        if let Some(ref asset) = context.last_asset {
+        if let Some(ref asset) = context.context.last_asset {
             ctx_parts.push(format!("Last mentioned asset: {}", asset));
         }
 
-        if !context.collected_entities.is_empty() {
+        if !context.operation.collected_entities.is_empty() {
             let entities: Vec<String> = context
+                .operation
                 .collected_entities
                 .iter()
                 .map(|(k, v)| format!("{}: {}", k, v))
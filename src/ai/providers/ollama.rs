@@ -95,8 +95,9 @@ impl OllamaProvider {
         let input_lower = input.to_lowercase();
         let _words: Vec<&str> = input_lower.split_whitespace().collect();
 
-        // Price check patterns
-        if input_lower.contains("price") || input_lower.contains("worth") || input_lower.starts_with("how much") {
+        // Price check patterns ("$500 worth of BTC" in a trade is an amount, not a question)
+        let is_trade = ["bought", "buy", "purchased", "sold", "sell"].iter().any(|w| input_lower.contains(w));
+        if input_lower.contains("price") || (input_lower.contains("worth") && !is_trade) || input_lower.starts_with("how much") {
             let symbols = self.extract_symbols(input);
             return Ok(ParsedInput {
                 intent: Intent::PriceCheck,
@@ -136,15 +137,18 @@ impl OllamaProvider {
                 missing.push("asset".to_string());
             }
 
+            // Extract price first: "$500 worth" needs it to work out the quantity
+            let price = self.extract_price(input);
+
             // Extract quantity
-            if let Some(qty) = self.extract_quantity(input) {
+            if let Some(qty) = self.extract_quantity_with_price(input, price) {
                 entities.insert("quantity".to_string(), Entity::Number(qty));
             } else {
                 missing.push("quantity".to_string());
             }
 
             // Extract price
-            if let Some(price) = self.extract_price(input) {
+            if let Some(price) = price {
                 entities.insert("price".to_string(), Entity::Number(price));
             } else {
                 missing.push("price".to_string());
@@ -177,13 +181,15 @@ impl OllamaProvider {
                 missing.push("asset".to_string());
             }
 
-            if let Some(qty) = self.extract_quantity(input) {
+            let price = self.extract_price(input);
+
+            if let Some(qty) = self.extract_quantity_with_price(input, price) {
                 entities.insert("quantity".to_string(), Entity::Number(qty));
             } else {
                 missing.push("quantity".to_string());
             }
 
-            if let Some(price) = self.extract_price(input) {
+            if let Some(price) = price {
                 entities.insert("price".to_string(), Entity::Number(price));
             } else {
                 missing.push("price".to_string());
@@ -293,6 +299,24 @@ impl OllamaProvider {
 
     /// Extract quantity from text
     pub fn extract_quantity(&self, input: &str) -> Option<f64> {
+        self.extract_quantity_with_price(input, None)
+    }
+
+    /// Extract quantity from text, working out "$500 worth" from `price` when it is known.
+    /// Written numbers ("half a bitcoin", "two thousand") and fractions ("¼ BTC") are
+    /// turned into digits first.
+    pub fn extract_quantity_with_price(&self, input: &str, price: Option<f64>) -> Option<f64> {
+        let input = normalize_written_numbers(input);
+
+        // A dollar amount is only a quantity once divided by the price
+        if let Ok(re) = regex::Regex::new(DOLLARS_WORTH) {
+            if let Some(caps) = re.captures(&input) {
+                let amount = caps[1].replace(',', "").parse::<f64>().ok()?;
+                let amount = if caps.get(2).is_some() { amount * 1000.0 } else { amount };
+                return price.filter(|p| *p > 0.0).map(|p| amount / p);
+            }
+        }
+
         // Look for patterns like "0.1", "0.5 BTC", etc.
         let re_patterns = [
             r"(\d+\.?\d*)\s*(?:btc|eth|sol|ada|doge|xrp|dot|avax|matic|ltc|link)",
@@ -301,11 +325,9 @@ impl OllamaProvider {
             r"sold\s+(\d+\.?\d*)",
         ];
 
-        let input_lower = input.to_lowercase();
-
         for pattern in re_patterns {
             if let Ok(re) = regex::Regex::new(pattern) {
-                if let Some(caps) = re.captures(&input_lower) {
+                if let Some(caps) = re.captures(&input) {
                     if let Some(m) = caps.get(1) {
                         if let Ok(qty) = m.as_str().parse::<f64>() {
                             return Some(qty);
@@ -329,8 +351,13 @@ impl OllamaProvider {
 
     /// Extract price from text
     pub fn extract_price(&self, input: &str) -> Option<f64> {
-        let input_clean = input.replace(',', "").replace('$', "");
-        let input_lower = input_clean.to_lowercase();
+        // "500 dollars worth" is the amount spent, not the price
+        let input = normalize_written_numbers(input);
+        let input = match regex::Regex::new(DOLLARS_WORTH) {
+            Ok(re) => re.replace_all(&input, " ").into_owned(),
+            Err(_) => input,
+        };
+        let input_lower = input.replace([',', '$'], "");
 
         // Look for price patterns
         let patterns = [
@@ -469,12 +496,12 @@ impl OllamaProvider {
         prompt.push_str("Parse this crypto portfolio command into JSON.\n\n");
         prompt.push_str("INTENTS: price.check, tx.buy, tx.sell, portfolio.view, holdings.list, sync, help, unclear\n\n");
 
-        if context.last_account.is_some() || context.last_asset.is_some() {
+        if context.context.last_account.is_some() || context.context.last_asset.is_some() {
             prompt.push_str("CONTEXT:\n");
-            if let Some(ref account) = context.last_account {
+            if let Some(ref account) = context.context.last_account {
                 prompt.push_str(&format!("- Last account: {}\n", account));
             }
-            if let Some(ref asset) = context.last_asset {
+            if let Some(ref asset) = context.context.last_asset {
                 prompt.push_str(&format!("- Last asset: {}\n", asset));
             }
             prompt.push('\n');
@@ -530,10 +557,14 @@ impl AiProvider for OllamaProvider {
         }
     }
 
+    /// Ollama is reachable and the configured model has been pulled
     async fn health_check(&self) -> bool {
         let url = format!("{}/api/tags", self.base_url);
-        match self.client.get(&url).send().await {
-            Ok(response) => response.status().is_success(),
+        let Ok(response) = self.client.get(&url).send().await else {
+            return false;
+        };
+        match response.json::<OllamaTags>().await {
+            Ok(tags) => tags.has_model(&self.config.model),
             Err(_) => false,
         }
     }
@@ -554,12 +585,208 @@ struct OllamaRequest {
     options: Option<OllamaOptions>,
 }
 
+/// Number words understood by the rule-based parser
+const NUMBER_WORDS: &[(&str, f64)] = &[
+    ("zero", 0.0),
+    ("one", 1.0),
+    ("two", 2.0),
+    ("three", 3.0),
+    ("four", 4.0),
+    ("five", 5.0),
+    ("six", 6.0),
+    ("seven", 7.0),
+    ("eight", 8.0),
+    ("nine", 9.0),
+    ("ten", 10.0),
+    ("eleven", 11.0),
+    ("twelve", 12.0),
+    ("thirteen", 13.0),
+    ("fourteen", 14.0),
+    ("fifteen", 15.0),
+    ("sixteen", 16.0),
+    ("seventeen", 17.0),
+    ("eighteen", 18.0),
+    ("nineteen", 19.0),
+    ("twenty", 20.0),
+    ("thirty", 30.0),
+    ("forty", 40.0),
+    ("fifty", 50.0),
+    ("sixty", 60.0),
+    ("seventy", 70.0),
+    ("eighty", 80.0),
+    ("ninety", 90.0),
+];
+
+/// A dollar amount spent, as in "$500 worth" or "2k dollars worth"
+const DOLLARS_WORTH: &str = r"\$?\s*(\d[\d,]*(?:\.\d+)?)\s*(k\b)?\s*(?:dollars?|usd)?\s+worth\b";
+
+/// Multipliers that close a group, as in "two thousand five hundred"
+const SCALE_WORDS: &[(&str, f64)] = &[("thousand", 1_000.0), ("million", 1_000_000.0)];
+
+const FRACTION_WORDS: &[(&str, f64)] = &[
+    ("half", 0.5),
+    ("halves", 0.5),
+    ("quarter", 0.25),
+    ("quarters", 0.25),
+    ("third", 1.0 / 3.0),
+    ("thirds", 1.0 / 3.0),
+];
+
+const FRACTION_CHARS: &[(char, f64)] = &[
+    ('¼', 0.25),
+    ('½', 0.5),
+    ('¾', 0.75),
+    ('⅓', 1.0 / 3.0),
+    ('⅔', 2.0 / 3.0),
+    ('⅛', 0.125),
+];
+
+fn lookup(table: &[(&str, f64)], word: &str) -> Option<f64> {
+    table.iter().find(|(w, _)| *w == word).map(|(_, n)| *n)
+}
+
+/// Lowercase `input` and replace written numbers and fraction characters
+/// with digits: "one and a half BTC" becomes "1.5 btc", "half a bitcoin"
+/// becomes "0.5 bitcoin" and "1½ eth" becomes "1.5 eth"
+fn normalize_written_numbers(input: &str) -> String {
+    let lower = input.to_lowercase();
+    let mut text = String::new();
+    let mut chars = lower.chars().peekable();
+    while let Some(c) = chars.next() {
+        let Some(&(_, fraction)) = FRACTION_CHARS.iter().find(|(f, _)| *f == c) else {
+            text.push(c);
+            continue;
+        };
+        // A whole number right before the fraction is its integer part
+        let digits = text.len() - text.trim_end_matches(|d: char| d.is_ascii_digit()).len();
+        let whole: f64 = text[text.len() - digits..].parse().unwrap_or(0.0);
+        text.truncate(text.len() - digits);
+        text.push_str(&(whole + fraction).to_string());
+        if chars.peek().is_some_and(|next| next.is_alphanumeric()) {
+            text.push(' ');
+        }
+    }
+
+    // "twenty-five" reads as "twenty five"
+    let words: Vec<String> = text
+        .split_whitespace()
+        .flat_map(|w| {
+            let parts: Vec<&str> = w.split('-').collect();
+            if parts.len() > 1 && parts.iter().all(|p| lookup(NUMBER_WORDS, p).is_some()) {
+                parts.into_iter().map(str::to_string).collect()
+            } else {
+                vec![w.to_string()]
+            }
+        })
+        .collect();
+
+    let mut out = Vec::with_capacity(words.len());
+    let mut i = 0;
+    while i < words.len() {
+        match parse_number_phrase(&words[i..]) {
+            Some((value, consumed)) => {
+                out.push(value.to_string());
+                i += consumed;
+            }
+            None => {
+                out.push(words[i].clone());
+                i += 1;
+            }
+        }
+    }
+    out.join(" ")
+}
+
+/// Read a written number at the start of `words`, returning its value and
+/// how many words it used (including a trailing "a"/"of a" after a fraction)
+fn parse_number_phrase(words: &[String]) -> Option<(f64, usize)> {
+    let word = |i: usize| words.get(i).map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()));
+    let is_article = |w: Option<&str>| matches!(w, Some("a" | "an"));
+    let starts_number = |w: Option<&str>| {
+        w.is_some_and(|w| {
+            lookup(NUMBER_WORDS, w).is_some()
+                || lookup(SCALE_WORDS, w).is_some()
+                || lookup(FRACTION_WORDS, w).is_some()
+                || w == "hundred"
+        })
+    };
+
+    let mut total = 0.0;
+    let mut current = 0.0;
+    let mut found = false;
+    let mut after_and = false;
+    let mut i = 0;
+
+    while let Some(w) = word(i) {
+        if let Some(n) = lookup(NUMBER_WORDS, w) {
+            current += n;
+        } else if w == "hundred" {
+            current = if current > 0.0 { current } else { 1.0 } * 100.0;
+        } else if let Some(scale) = lookup(SCALE_WORDS, w) {
+            total += if current > 0.0 { current } else { 1.0 } * scale;
+            current = 0.0;
+        } else if let Some(fraction) = lookup(FRACTION_WORDS, w) {
+            let whole = total + current;
+            let value = if !found {
+                fraction
+            } else if after_and {
+                whole + fraction
+            } else {
+                // "three quarters", "two thirds"
+                whole * fraction
+            };
+            i += 1;
+            // "half a bitcoin", "a quarter of an ETH"
+            if word(i) == Some("of") {
+                i += 1;
+            }
+            if is_article(word(i)) {
+                i += 1;
+            }
+            return Some((value, i));
+        } else if is_article(Some(w)) && starts_number(word(i + 1)) {
+            // "a hundred", "a half", "and a half"
+            i += 1;
+            continue;
+        } else if w == "and" && found && (starts_number(word(i + 1)) || is_article(word(i + 1))) {
+            after_and = true;
+            i += 1;
+            continue;
+        } else {
+            break;
+        }
+        found = true;
+        i += 1;
+    }
+
+    found.then_some((total + current, i))
+}
+
 #[derive(Debug, Serialize)]
 struct OllamaOptions {
     temperature: f32,
     num_predict: i32,
 }
 
+/// `GET /api/tags`: the models pulled on the Ollama instance
+#[derive(Debug, Deserialize)]
+pub struct OllamaTags {
+    pub models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaModel {
+    pub name: String,
+}
+
+impl OllamaTags {
+    /// Whether `model` is installed; a name without a tag means `:latest`, as in `ollama run`
+    pub fn has_model(&self, model: &str) -> bool {
+        let wanted = if model.contains(':') { model.to_string() } else { format!("{}:latest", model) };
+        self.models.iter().any(|m| m.name == wanted)
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct OllamaResponse {
     response: String,
@@ -0,0 +1,75 @@
+//! Color detection
+//!
+//! Decides whether ANSI colors should be used, following the NO_COLOR
+//! convention (https://no-color.org) plus our own `CRYPTOFOLIO_NO_COLOR`.
+
+use is_terminal::IsTerminal;
+use std::io::stdout;
+
+/// Check whether colored output should be used for the current process
+///
+/// Colors are disabled when any of the following hold:
+/// - `NO_COLOR` is set (any value)
+/// - `CRYPTOFOLIO_NO_COLOR` is set (any value)
+/// - `TERM` is `dumb`
+/// - stdout is not a terminal
+pub fn should_use_color() -> bool {
+    should_use_color_with(|key| std::env::var(key).ok(), stdout().is_terminal())
+}
+
+/// Color detection with injectable environment lookup and TTY state
+pub fn should_use_color_with<F>(env: F, is_tty: bool) -> bool
+where
+    F: Fn(&str) -> Option<String>,
+{
+    if env("NO_COLOR").is_some() || env("CRYPTOFOLIO_NO_COLOR").is_some() {
+        return false;
+    }
+
+    if env("TERM").map(|t| t == "dumb").unwrap_or(false) {
+        return false;
+    }
+
+    is_tty
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_from(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |key| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+        }
+    }
+
+    #[test]
+    fn test_color_enabled_on_tty_without_overrides() {
+        assert!(should_use_color_with(env_from(&[]), true));
+    }
+
+    #[test]
+    fn test_color_disabled_when_not_tty() {
+        assert!(!should_use_color_with(env_from(&[]), false));
+    }
+
+    #[test]
+    fn test_no_color_disables_regardless_of_value() {
+        assert!(!should_use_color_with(env_from(&[("NO_COLOR", "1")]), true));
+        assert!(!should_use_color_with(env_from(&[("NO_COLOR", "")]), true));
+        assert!(!should_use_color_with(env_from(&[("NO_COLOR", "false")]), true));
+    }
+
+    #[test]
+    fn test_cryptofolio_no_color_disables() {
+        assert!(!should_use_color_with(env_from(&[("CRYPTOFOLIO_NO_COLOR", "1")]), true));
+    }
+
+    #[test]
+    fn test_dumb_terminal_disables() {
+        assert!(!should_use_color_with(env_from(&[("TERM", "dumb")]), true));
+        assert!(should_use_color_with(env_from(&[("TERM", "xterm-256color")]), true));
+    }
+}
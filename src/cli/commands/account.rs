@@ -1,17 +1,34 @@
 use chrono::Utc;
 use colored::Colorize;
+use rust_decimal::Decimal;
+use schemars::JsonSchema;
 use serde::Serialize;
 use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
-use crate::cli::{AccountCommands, AccountTypeArg, AddressCommands, GlobalOptions};
-use crate::cli::output::{print_header, print_kv, print_row, success, suggest_next};
+use crate::cli::commands::portfolio::fetch_price_map;
+use crate::cli::commands::sync::{fetch_exchange_balances, require_credentials};
+use crate::cli::commands::tx::{print_transaction_table, transaction_list_output, TransactionOutput};
+use crate::cli::{AccountCommands, AccountTypeArg, AddressCommands, ExchangeArg, GlobalOptions};
+use crate::cli::output::{
+    colors_enabled, format_currency, format_pnl, format_pnl_percent, format_quantity, hyperlink, print_header, print_kv, print_row,
+    success, suggest_next, warning,
+};
+use crate::config::AppConfig;
 use crate::core::account::{Account, AccountConfig, AccountType};
-use crate::db::AccountRepository;
+use crate::core::address::validate_address;
+use crate::core::holdings::{HoldingChangeSource, HoldingWithPrice};
+use crate::db::{AccountRepository, HoldingRepository, ListAccountsFilter, TransactionFilter, TransactionRepository};
 use crate::error::{CryptofolioError, Result};
+use crate::exchange::explorers::address_url;
+use crate::exchange::BinanceClient;
 
-#[derive(Serialize)]
-struct AccountListOutput {
+/// Number of transactions shown by `account show`
+const RECENT_TRANSACTIONS: i64 = 10;
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct AccountListOutput {
     name: String,
     account_type: String,
     category: String,
@@ -21,15 +38,35 @@ struct AccountListOutput {
 
 #[derive(Serialize)]
 struct AccountShowOutput {
+    account: AccountDetailsOutput,
+    holdings: Vec<AccountHoldingOutput>,
+    transactions: Vec<TransactionOutput>,
+}
+
+#[derive(Serialize)]
+struct AccountDetailsOutput {
     name: String,
     account_type: String,
     category: String,
     is_testnet: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exchange: Option<String>,
     sync_enabled: bool,
     created_at: String,
+    sync_settings: std::collections::HashMap<String, serde_json::Value>,
     addresses: Vec<AddressOutput>,
 }
 
+#[derive(Serialize)]
+struct AccountHoldingOutput {
+    asset: String,
+    quantity: String,
+    cost_basis: Option<String>,
+    price: Option<String>,
+    value: Option<String>,
+    unrealized_pnl: Option<String>,
+}
+
 #[derive(Serialize)]
 struct AddressOutput {
     blockchain: String,
@@ -42,12 +79,25 @@ pub async fn handle_account_command(command: AccountCommands, pool: &SqlitePool,
     let repo = AccountRepository::new(pool);
 
     match command {
-        AccountCommands::List => {
-            let accounts = repo.list_accounts().await?;
+        AccountCommands::List { account_type, category, sync_enabled } => {
+            let category_id = match category {
+                Some(category) => Some(resolve_category_id(&repo, category).await?),
+                None => None,
+            };
+            let filtered = !account_type.is_empty() || category_id.is_some() || sync_enabled;
+            let filter = ListAccountsFilter {
+                account_types: account_type.iter().map(AccountTypeArg::account_type).collect(),
+                category_id,
+                sync_enabled: sync_enabled.then_some(true),
+                name: None,
+            };
+            let accounts = repo.list_accounts(&filter).await?;
 
             if accounts.is_empty() {
                 if opts.json {
                     println!("[]");
+                } else if filtered {
+                    println!("No accounts match the given filters.");
                 } else {
                     println!("No accounts configured. Use 'cryptofolio account add' to create one.");
                 }
@@ -55,19 +105,7 @@ pub async fn handle_account_command(command: AccountCommands, pool: &SqlitePool,
             }
 
             if opts.json {
-                let mut output = Vec::new();
-                for account in accounts {
-                    let category = repo.get_category(&account.category_id).await?;
-                    let category_name = category.map(|c| c.name).unwrap_or_else(|| "-".to_string());
-
-                    output.push(AccountListOutput {
-                        name: account.name.clone(),
-                        account_type: account.account_type.display_name().to_string(),
-                        category: category_name,
-                        sync_enabled: account.sync_enabled,
-                        is_testnet: account.config.is_testnet,
-                    });
-                }
+                let output = account_list_output(accounts, &repo).await?;
                 println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
             } else {
                 print_header(&[("Name", 20), ("Type", 18), ("Category", 15), ("Sync", 6)]);
@@ -104,31 +142,13 @@ pub async fn handle_account_command(command: AccountCommands, pool: &SqlitePool,
             category,
             testnet,
             sync,
+            exchange,
         } => {
-            // Convert AccountTypeArg to AccountType
-            let acc_type = match account_type {
-                AccountTypeArg::Exchange => AccountType::Exchange,
-                AccountTypeArg::HardwareWallet => AccountType::HardwareWallet,
-                AccountTypeArg::SoftwareWallet => AccountType::SoftwareWallet,
-                AccountTypeArg::CustodialService => AccountType::CustodialService,
-                AccountTypeArg::Bank => AccountType::Bank,
-            };
+            let acc_type = account_type.account_type();
 
-            // Find or validate category
-            let cat = repo.get_category(&category).await?
-                .or_else(|| None);
+            let kraken = matches!(exchange, ExchangeArg::Kraken) && acc_type == AccountType::Exchange;
 
-            let category_id = if let Some(c) = cat {
-                c.id
-            } else {
-                // Try by name
-                let cat_by_name = repo.get_category_by_name(&category).await?;
-                if let Some(c) = cat_by_name {
-                    c.id
-                } else {
-                    return Err(CryptofolioError::CategoryNotFound(category));
-                }
-            };
+            let category_id = resolve_category_id(&repo, category).await?;
 
             let account = Account {
                 id: Uuid::new_v4().to_string(),
@@ -137,6 +157,8 @@ pub async fn handle_account_command(command: AccountCommands, pool: &SqlitePool,
                 account_type: acc_type,
                 config: AccountConfig {
                     is_testnet: testnet,
+                    exchange: kraken.then(|| AccountConfig::KRAKEN.to_string()),
+                    ..Default::default()
                 },
                 sync_enabled: sync,
                 created_at: Utc::now(),
@@ -177,27 +199,73 @@ pub async fn handle_account_command(command: AccountCommands, pool: &SqlitePool,
             success(&format!("Account '{}' removed", name));
         }
 
+        AccountCommands::Rename { old_name, new_name } => {
+            repo.update_account_name(&old_name, &new_name).await?;
+            success(&format!("Account renamed from '{}' to '{}'", old_name, new_name));
+        }
+
         AccountCommands::Show { name } => {
-            let account = repo.get_account(&name).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(name.clone()))?;
+            let account = repo.require_account(&name).await?;
 
             let category = repo.get_category(&account.category_id).await?;
             let addresses = repo.list_addresses(&account.id).await?;
 
+            let holdings = HoldingRepository::new(pool).list_by_account(&account.id).await?;
+            let holdings: Vec<HoldingWithPrice> = if holdings.is_empty() {
+                Vec::new()
+            } else {
+                let config = AppConfig::load()?;
+                let client = BinanceClient::new(
+                    opts.testnet || config.general.use_testnet,
+                    config.binance.api_key.clone(),
+                    config.binance.api_secret.clone(),
+                );
+                let assets: Vec<String> = holdings.iter().map(|h| h.asset.clone()).collect();
+                let price_map = fetch_price_map(&client, &assets, pool).await.prices;
+                holdings
+                    .into_iter()
+                    .map(|h| {
+                        let price = price_map.get(&h.asset.to_uppercase()).copied();
+                        HoldingWithPrice::from_holding(h, price)
+                    })
+                    .collect()
+            };
+
+            let filter = TransactionFilter {
+                account_id: Some(account.id.clone()),
+                limit: Some(RECENT_TRANSACTIONS),
+                ..Default::default()
+            };
+            let transactions = TransactionRepository::new(pool).list(&filter).await?;
+
             if opts.json {
-                let output = AccountShowOutput {
+                let account_output = AccountDetailsOutput {
                     name: account.name.clone(),
                     account_type: account.account_type.display_name().to_string(),
                     category: category.map(|c| c.name).unwrap_or_else(|| "-".to_string()),
                     is_testnet: account.config.is_testnet,
+                    exchange: account.config.exchange.clone(),
                     sync_enabled: account.sync_enabled,
                     created_at: account.created_at.to_rfc3339(),
+                    sync_settings: account.config.sync_settings.clone(),
                     addresses: addresses.iter().map(|a| AddressOutput {
                         blockchain: a.blockchain.clone(),
                         address: a.address.clone(),
                         label: a.label.clone(),
                     }).collect(),
                 };
+                let output = AccountShowOutput {
+                    account: account_output,
+                    holdings: holdings.iter().map(|h| AccountHoldingOutput {
+                        asset: h.holding.asset.clone(),
+                        quantity: h.holding.quantity.to_string(),
+                        cost_basis: h.holding.avg_cost_basis.map(|c| c.to_string()),
+                        price: h.current_price.map(|p| p.to_string()),
+                        value: h.current_value.map(|v| v.to_string()),
+                        unrealized_pnl: h.unrealized_pnl.map(|p| p.to_string()),
+                    }).collect(),
+                    transactions: transactions.iter().map(transaction_list_output).collect(),
+                };
                 println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
             } else {
                 println!();
@@ -206,23 +274,105 @@ pub async fn handle_account_command(command: AccountCommands, pool: &SqlitePool,
 
                 print_kv("Type", account.account_type.display_name());
                 print_kv("Category", &category.map(|c| c.name).unwrap_or_else(|| "-".to_string()));
+                if let Some(ref exchange) = account.config.exchange {
+                    print_kv("Exchange", exchange);
+                }
                 print_kv("Testnet", if account.config.is_testnet { "Yes" } else { "No" });
                 print_kv("Sync Enabled", if account.sync_enabled { "Yes" } else { "No" });
                 print_kv("Created", &account.created_at.format("%Y-%m-%d %H:%M").to_string());
 
+                if !account.config.sync_settings.is_empty() {
+                    print_sync_settings(&account.config);
+                }
+
                 if !addresses.is_empty() {
                     println!();
                     println!("{}", "Wallet Addresses:".bold());
                     for addr in addresses {
                         let label = addr.label.map(|l| format!(" ({})", l)).unwrap_or_default();
-                        println!("  {} {}{}", addr.blockchain.dimmed(), addr.address, label);
+                        let address = match address_url(&addr.blockchain, &addr.address) {
+                            Some(url) => hyperlink(&url, &addr.address),
+                            None => addr.address.clone(),
+                        };
+                        println!("  {} {}{}", addr.blockchain.dimmed(), address, label);
                     }
                 }
 
                 println!();
+                println!("{}", "Holdings:".bold());
+                if holdings.is_empty() {
+                    println!("  No holdings.");
+                } else {
+                    print_account_holdings(&holdings);
+                }
+
+                println!();
+                println!("{}", format!("Recent Transactions (last {}):", RECENT_TRANSACTIONS).bold());
+                if transactions.is_empty() {
+                    println!("  No transactions.");
+                } else {
+                    print_transaction_table(&transactions);
+                }
+
+                println!();
+            }
+        }
+
+        AccountCommands::SyncSettings { account, exclude_assets, rate_limit, clear } => {
+            let mut acc = repo.require_account(&account).await?;
+
+            let changed = clear || exclude_assets.is_some() || rate_limit.is_some();
+
+            if clear {
+                acc.config.sync_settings.clear();
+            }
+
+            if let Some(assets) = exclude_assets {
+                let assets: Vec<String> = assets
+                    .iter()
+                    .map(|a| a.trim().to_uppercase())
+                    .filter(|a| !a.is_empty())
+                    .collect();
+
+                if assets.is_empty() {
+                    acc.config.sync_settings.remove(AccountConfig::EXCLUDE_ASSETS);
+                } else {
+                    acc.config.sync_settings.insert(
+                        AccountConfig::EXCLUDE_ASSETS.to_string(),
+                        serde_json::json!(assets),
+                    );
+                }
+            }
+
+            if let Some(limit) = rate_limit {
+                if limit == 0 {
+                    acc.config.sync_settings.remove(AccountConfig::RATE_LIMIT);
+                } else {
+                    acc.config.sync_settings.insert(
+                        AccountConfig::RATE_LIMIT.to_string(),
+                        serde_json::json!(limit),
+                    );
+                }
+            }
+
+            if changed {
+                repo.update_config(&acc.id, &acc.config).await?;
+            }
+
+            if opts.json {
+                println!("{}", serde_json::to_string_pretty(&acc.config.sync_settings).unwrap_or_default());
+            } else {
+                if changed && !opts.quiet {
+                    success(&format!("Sync settings updated for '{}'", acc.name));
+                }
+                print_sync_settings(&acc.config);
             }
         }
 
+        AccountCommands::Verify { account, fix } => {
+            verify_account(&account, fix, pool, opts).await?;
+        }
+
         AccountCommands::Address { command } => {
             handle_address_command(command, pool).await?;
         }
@@ -231,6 +381,259 @@ pub async fn handle_account_command(command: AccountCommands, pool: &SqlitePool,
     Ok(())
 }
 
+/// How a local holding compares with the exchange's balance
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BalanceStatus {
+    Ok,
+    /// More recorded locally than the exchange holds
+    Over,
+    /// Less recorded locally than the exchange holds
+    Under,
+}
+
+impl BalanceStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            BalanceStatus::Ok => "OK",
+            BalanceStatus::Over => "OVER",
+            BalanceStatus::Under => "UNDER",
+        }
+    }
+}
+
+/// One asset of `account verify`
+#[derive(Debug)]
+struct BalanceCheck {
+    asset: String,
+    local: Decimal,
+    exchange: Decimal,
+}
+
+impl BalanceCheck {
+    /// Local minus exchange quantity
+    fn difference(&self) -> Decimal {
+        self.local - self.exchange
+    }
+
+    fn status(&self) -> BalanceStatus {
+        match self.local.cmp(&self.exchange) {
+            std::cmp::Ordering::Equal => BalanceStatus::Ok,
+            std::cmp::Ordering::Greater => BalanceStatus::Over,
+            std::cmp::Ordering::Less => BalanceStatus::Under,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct VerifyOutput {
+    account: String,
+    discrepancies: usize,
+    fixed: bool,
+    assets: Vec<VerifyAssetOutput>,
+}
+
+#[derive(Serialize)]
+struct VerifyAssetOutput {
+    asset: String,
+    local_quantity: String,
+    exchange_quantity: String,
+    difference: String,
+    status: &'static str,
+}
+
+/// Pair local holdings with exchange balances by asset, sorted by asset.
+/// Assets excluded from sync are left out, as are assets at zero on both sides.
+fn compare_balances(
+    local: &HashMap<String, Decimal>,
+    exchange: &[(String, Decimal)],
+    excluded: &[String],
+) -> Vec<BalanceCheck> {
+    let mut checks: BTreeMap<String, BalanceCheck> = BTreeMap::new();
+    for (asset, quantity) in exchange {
+        let asset = asset.to_uppercase();
+        let check = checks.entry(asset.clone()).or_insert_with(|| BalanceCheck {
+            local: local.get(&asset).copied().unwrap_or(Decimal::ZERO),
+            exchange: Decimal::ZERO,
+            asset,
+        });
+        check.exchange += *quantity;
+    }
+    for (asset, quantity) in local {
+        checks.entry(asset.clone()).or_insert_with(|| BalanceCheck {
+            asset: asset.clone(),
+            local: *quantity,
+            exchange: Decimal::ZERO,
+        });
+    }
+
+    checks
+        .into_values()
+        .filter(|c| !excluded.contains(&c.asset))
+        .filter(|c| !c.local.is_zero() || !c.exchange.is_zero())
+        .collect()
+}
+
+/// Compare an exchange account's holdings with its live balances, optionally
+/// setting mismatched holdings to what the exchange reports
+async fn verify_account(account: &str, fix: bool, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let acc = AccountRepository::new(pool).require_account(account).await?;
+    if acc.account_type != AccountType::Exchange {
+        return Err(CryptofolioError::InvalidInput(format!(
+            "'{}' is not an exchange account; verify compares holdings with exchange balances",
+            acc.name
+        )));
+    }
+
+    let config = AppConfig::load()?;
+    require_credentials(std::slice::from_ref(&acc), &config)?;
+    let human = !opts.quiet && !opts.json;
+    let fetched = fetch_exchange_balances(&acc, &config, human, opts).await?;
+
+    let holding_repo = HoldingRepository::new(pool);
+    let holdings = holding_repo.list_by_account(&acc.id).await?;
+    let local: HashMap<String, Decimal> = holdings.iter().map(|h| (h.asset.to_uppercase(), h.quantity)).collect();
+    let checks = compare_balances(&local, &fetched.balances, &acc.config.excluded_assets());
+    let mismatched: Vec<&BalanceCheck> = checks.iter().filter(|c| c.status() != BalanceStatus::Ok).collect();
+
+    if fix {
+        for check in &mismatched {
+            // Keep the cost basis of the holding being corrected
+            let cost = holdings
+                .iter()
+                .find(|h| h.asset.eq_ignore_ascii_case(&check.asset))
+                .and_then(|h| h.avg_cost_basis);
+            holding_repo
+                .set_quantity(&acc.id, &check.asset, check.exchange, cost, Some(check.local))
+                .await?;
+            holding_repo
+                .record_change(&acc.id, &check.asset, check.local, check.exchange, HoldingChangeSource::Sync, None)
+                .await?;
+        }
+    }
+
+    if opts.json {
+        let output = VerifyOutput {
+            account: acc.name.clone(),
+            discrepancies: mismatched.len(),
+            fixed: fix && !mismatched.is_empty(),
+            assets: checks
+                .iter()
+                .map(|c| VerifyAssetOutput {
+                    asset: c.asset.clone(),
+                    local_quantity: c.local.to_string(),
+                    exchange_quantity: c.exchange.to_string(),
+                    difference: c.difference().to_string(),
+                    status: c.status().as_str(),
+                })
+                .collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+        return Ok(());
+    }
+
+    if mismatched.is_empty() {
+        success(&format!("All {} holdings in '{}' match the exchange", checks.len(), acc.name));
+        return Ok(());
+    }
+
+    warning(&format!(
+        "{} of {} holdings in '{}' differ from the exchange",
+        mismatched.len(),
+        checks.len(),
+        acc.name
+    ));
+    print_balance_checks(&checks);
+
+    if fix {
+        success(&format!("Set {} holdings to the exchange's balances", mismatched.len()));
+    } else if !opts.quiet {
+        suggest_next(
+            &format!("cryptofolio account verify --account \"{}\" --fix", acc.name),
+            "Set mismatched holdings to the exchange's balances",
+        );
+    }
+
+    Ok(())
+}
+
+fn print_balance_checks(checks: &[BalanceCheck]) {
+    println!("{}", "-".repeat(76));
+    println!(
+        "  {:8}  {:>16}  {:>16}  {:>16}  {:>6}",
+        "Asset", "Local Qty", "Exchange Qty", "Difference", "Status"
+    );
+    println!("{}", "-".repeat(76));
+
+    for check in checks {
+        let difference = check.difference();
+        let sign = match difference.cmp(&Decimal::ZERO) {
+            std::cmp::Ordering::Greater => "+",
+            std::cmp::Ordering::Less => "-",
+            std::cmp::Ordering::Equal => "",
+        };
+        let status = format!("{:>6}", check.status().as_str());
+        let status = match check.status() {
+            BalanceStatus::Ok => status.green(),
+            BalanceStatus::Over | BalanceStatus::Under => status.yellow(),
+        };
+
+        println!(
+            "  {:8}  {:>16}  {:>16}  {:>16}  {}",
+            check.asset,
+            format_quantity(check.local),
+            format_quantity(check.exchange),
+            format!("{}{}", sign, format_quantity(difference.abs())),
+            status
+        );
+    }
+
+    println!("{}", "-".repeat(76));
+}
+
+/// The `account list --json` shape of `accounts`
+pub(crate) async fn account_list_output(
+    accounts: Vec<Account>,
+    repo: &AccountRepository<'_>,
+) -> Result<Vec<AccountListOutput>> {
+    let mut output = Vec::new();
+    for account in accounts {
+        let category = repo.get_category(&account.category_id).await?;
+        let category_name = category.map(|c| c.name).unwrap_or_else(|| "-".to_string());
+
+        output.push(AccountListOutput {
+            name: account.name,
+            account_type: account.account_type.display_name().to_string(),
+            category: category_name,
+            sync_enabled: account.sync_enabled,
+            is_testnet: account.config.is_testnet,
+        });
+    }
+    Ok(output)
+}
+
+/// Look up a category by id or, failing that, by name
+async fn resolve_category_id(repo: &AccountRepository<'_>, category: String) -> Result<String> {
+    if let Some(c) = repo.get_category(&category).await? {
+        return Ok(c.id);
+    }
+    match repo.get_category_by_name(&category).await? {
+        Some(c) => Ok(c.id),
+        None => Err(CryptofolioError::CategoryNotFound(category)),
+    }
+}
+
+fn print_sync_settings(config: &AccountConfig) {
+    let excluded = config.excluded_assets();
+    print_kv(
+        "Excluded Assets",
+        &if excluded.is_empty() { "-".to_string() } else { excluded.join(", ") },
+    );
+    print_kv(
+        "Rate Limit",
+        &config.rate_limit().map(|r| format!("{} req/s", r)).unwrap_or_else(|| "-".to_string()),
+    );
+}
+
 async fn handle_address_command(command: AddressCommands, pool: &SqlitePool) -> Result<()> {
     let repo = AccountRepository::new(pool);
 
@@ -240,17 +643,20 @@ async fn handle_address_command(command: AddressCommands, pool: &SqlitePool) ->
             blockchain,
             address,
             label,
+            no_validate,
         } => {
-            let acc = repo.get_account(&account).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(account.clone()))?;
+            let acc = repo.require_account(&account).await?;
+
+            if !no_validate {
+                validate_address(&blockchain, &address)?;
+            }
 
             repo.add_address(&acc.id, &blockchain, &address, label.as_deref()).await?;
             success(&format!("Address added to '{}'", account));
         }
 
         AddressCommands::List { account } => {
-            let acc = repo.get_account(&account).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(account.clone()))?;
+            let acc = repo.require_account(&account).await?;
 
             let addresses = repo.list_addresses(&acc.id).await?;
 
@@ -271,8 +677,7 @@ async fn handle_address_command(command: AddressCommands, pool: &SqlitePool) ->
         }
 
         AddressCommands::Remove { account, address } => {
-            let acc = repo.get_account(&account).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(account.clone()))?;
+            let acc = repo.require_account(&account).await?;
 
             repo.remove_address(&acc.id, &address).await?;
             success(&format!("Address removed from '{}'", account));
@@ -281,3 +686,77 @@ async fn handle_address_command(command: AddressCommands, pool: &SqlitePool) ->
 
     Ok(())
 }
+
+/// Print an account's holdings with the columns of the portfolio view
+fn print_account_holdings(holdings: &[HoldingWithPrice]) {
+    let with_color = colors_enabled();
+
+    println!("{}", "-".repeat(70));
+    println!("  {:8}  {:>12}  {:>12}  {:>12}  {:>15}", "Asset", "Quantity", "Price", "Value", "P&L");
+    println!("{}", "-".repeat(70));
+
+    for h in holdings {
+        let price_str = h.current_price.map(format_currency).unwrap_or_else(|| "-".to_string());
+        let value_str = h.current_value.map(format_currency).unwrap_or_else(|| "-".to_string());
+        let pnl_str = match (h.unrealized_pnl, h.unrealized_pnl_percent) {
+            (Some(pnl), Some(pct)) => format!(
+                "{} ({})",
+                format_pnl(pnl, with_color),
+                format_pnl_percent(pct, with_color)
+            ),
+            _ => "-".to_string(),
+        };
+
+        println!(
+            "  {:8}  {:>12}  {:>12}  {:>12}  {:>15}",
+            h.holding.asset,
+            format_quantity(h.holding.quantity),
+            price_str,
+            value_str,
+            pnl_str
+        );
+    }
+
+    println!("{}", "-".repeat(70));
+
+    let total: Decimal = holdings.iter().filter_map(|h| h.current_value).sum();
+    print_kv("Total Value", &format_currency(total));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_balances() {
+        let local: HashMap<String, Decimal> = [
+            ("BTC".to_string(), Decimal::new(5, 1)),
+            ("ETH".to_string(), Decimal::from(2)),
+            ("SOL".to_string(), Decimal::from(10)),
+            ("LDBTC".to_string(), Decimal::ONE),
+        ]
+        .into_iter()
+        .collect();
+        let exchange = vec![
+            ("BTC".to_string(), Decimal::new(45, 2)),
+            ("eth".to_string(), Decimal::from(2)),
+            ("BNB".to_string(), Decimal::ONE),
+            ("USDT".to_string(), Decimal::ZERO),
+            ("LDBTC".to_string(), Decimal::from(3)),
+        ];
+
+        let checks = compare_balances(&local, &exchange, &["LDBTC".to_string()]);
+        let summary: Vec<(&str, BalanceStatus)> = checks.iter().map(|c| (c.asset.as_str(), c.status())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("BNB", BalanceStatus::Under),
+                ("BTC", BalanceStatus::Over),
+                ("ETH", BalanceStatus::Ok),
+                ("SOL", BalanceStatus::Over),
+            ]
+        );
+        assert_eq!(checks[1].difference(), Decimal::new(5, 2));
+        assert_eq!(checks[3].exchange, Decimal::ZERO);
+    }
+}
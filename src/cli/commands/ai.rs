@@ -0,0 +1,249 @@
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::ai::{AiService, ConversationState, Entity, Intent, ParsedInput};
+use crate::cli::output::{print_json, success, warning};
+use crate::cli::{AiCommands, GlobalOptions};
+use crate::config::AppConfig;
+use crate::error::{CryptofolioError, Result};
+
+/// Below this a correct intent is still flagged, since the shell would be guessing
+const LOW_CONFIDENCE: f64 = 0.7;
+
+const BUY_CASES: &[(&str, Intent)] = &[
+    ("buy 0.1 BTC on Binance at 95000", Intent::TxBuy),
+    ("I bought 2 ETH for 3200 each in Ledger", Intent::TxBuy),
+    ("purchase 100 SOL at $150 on Coinbase", Intent::TxBuy),
+    ("add a buy of 0.5 btc at 60k", Intent::TxBuy),
+];
+
+const SELL_CASES: &[(&str, Intent)] = &[
+    ("sell 0.05 BTC on Binance at 98000", Intent::TxSell),
+    ("I sold 1 ETH for 3500 yesterday", Intent::TxSell),
+    ("dump 50 SOL at 180 from Coinbase", Intent::TxSell),
+    ("sell half my ETH in Ledger at 3400", Intent::TxSell),
+];
+
+const PRICE_CASES: &[(&str, Intent)] = &[
+    ("what's the price of bitcoin?", Intent::PriceCheck),
+    ("BTC price", Intent::PriceCheck),
+    ("how much are ETH and SOL right now", Intent::PriceCheck),
+];
+
+const PORTFOLIO_CASES: &[(&str, Intent)] = &[
+    ("show my portfolio", Intent::PortfolioView),
+    ("how much is my portfolio worth?", Intent::PortfolioView),
+    ("what's my total profit and loss", Intent::PortfolioView),
+];
+
+const UNCLEAR_CASES: &[(&str, Intent)] = &[
+    ("do the thing", Intent::Unclear),
+    ("hmm", Intent::Unclear),
+    ("that one again", Intent::Unclear),
+];
+
+const OUT_OF_SCOPE_CASES: &[(&str, Intent)] = &[
+    ("what's the weather in Paris?", Intent::OutOfScope),
+    ("write me a poem about the ocean", Intent::OutOfScope),
+    ("should I buy Tesla stock?", Intent::OutOfScope),
+];
+
+/// The checks `ai test` runs, grouped by what they exercise
+const SUITES: &[(&str, &[(&str, Intent)])] = &[
+    ("Buy", BUY_CASES),
+    ("Sell", SELL_CASES),
+    ("Price check", PRICE_CASES),
+    ("Portfolio", PORTFOLIO_CASES),
+    ("Unclear", UNCLEAR_CASES),
+    ("Out of scope", OUT_OF_SCOPE_CASES),
+];
+
+/// How one check came out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum CheckStatus {
+    Pass,
+    LowConfidence,
+    WrongIntent,
+    Error,
+}
+
+#[derive(Serialize)]
+struct CheckResult {
+    suite: &'static str,
+    input: &'static str,
+    expected: Intent,
+    intent: Option<Intent>,
+    confidence: Option<f64>,
+    entities: HashMap<String, Entity>,
+    status: CheckStatus,
+    error: Option<String>,
+}
+
+impl CheckResult {
+    fn new(suite: &'static str, input: &'static str, expected: &Intent, parsed: Result<ParsedInput>) -> Self {
+        let mut result = Self {
+            suite,
+            input,
+            expected: expected.clone(),
+            intent: None,
+            confidence: None,
+            entities: HashMap::new(),
+            status: CheckStatus::Error,
+            error: None,
+        };
+
+        match parsed {
+            Ok(parsed) => {
+                result.status = if parsed.intent != *expected {
+                    CheckStatus::WrongIntent
+                } else if parsed.confidence < LOW_CONFIDENCE {
+                    CheckStatus::LowConfidence
+                } else {
+                    CheckStatus::Pass
+                };
+                result.intent = Some(parsed.intent);
+                result.confidence = Some(parsed.confidence);
+                result.entities = parsed.entities;
+            }
+            Err(e) => result.error = Some(e.to_string()),
+        }
+
+        result
+    }
+}
+
+pub async fn handle_ai_command(command: AiCommands, opts: &GlobalOptions) -> Result<()> {
+    match command {
+        AiCommands::Test => run_checks(opts).await,
+    }
+}
+
+/// Run every check against the configured provider and report how each was understood
+async fn run_checks(opts: &GlobalOptions) -> Result<()> {
+    let config = AppConfig::load()?;
+    let service = AiService::new(&config)?;
+    if !service.is_available() {
+        return Err(CryptofolioError::Config(
+            "No AI provider available. Set ai.mode and a Claude API key or local model, then check with 'cryptofolio status --check'".into(),
+        ));
+    }
+
+    let human = !opts.json;
+    if human {
+        println!("Running AI intent checks (mode: {:?})", service.mode());
+    }
+
+    // Each input is parsed on its own, as the first thing said in a new conversation
+    let context = ConversationState::new();
+    let mut results = Vec::new();
+    for (suite, cases) in SUITES {
+        if human {
+            println!();
+            println!("{}", suite.bold());
+        }
+
+        for (input, expected) in cases.iter() {
+            let result = CheckResult::new(suite, input, expected, service.parse_input(input, &context).await);
+            if human {
+                print_result(&result);
+            }
+            results.push(result);
+        }
+    }
+
+    if opts.json {
+        return print_json(&results);
+    }
+
+    let count = |status: CheckStatus| results.iter().filter(|r| r.status == status).count();
+    let passed = count(CheckStatus::Pass) + count(CheckStatus::LowConfidence);
+    let summary = format!(
+        "{}/{} intents matched, {} with low confidence, {} errors",
+        passed,
+        results.len(),
+        count(CheckStatus::LowConfidence),
+        count(CheckStatus::Error)
+    );
+
+    println!();
+    if passed == results.len() && count(CheckStatus::LowConfidence) == 0 {
+        success(&summary);
+    } else {
+        warning(&summary);
+    }
+    Ok(())
+}
+
+/// One line per check, with any extracted entities underneath
+fn print_result(result: &CheckResult) {
+    let (label, intent) = match (&result.status, &result.intent) {
+        (CheckStatus::Error, _) | (_, None) => {
+            let message = result.error.clone().unwrap_or_default();
+            println!("  {:<6} {}", "ERROR".red(), result.input);
+            println!("         {}", message.red());
+            return;
+        }
+        (CheckStatus::Pass, Some(intent)) => ("ok".green(), intent_name(intent).green()),
+        (CheckStatus::LowConfidence, Some(intent)) => ("low".yellow(), intent_name(intent).yellow()),
+        (CheckStatus::WrongIntent, Some(intent)) => (
+            "FAIL".red(),
+            format!("{} (expected {})", intent_name(intent), intent_name(&result.expected)).red(),
+        ),
+    };
+
+    println!(
+        "  {:<6} {:<42} {} {:.2}",
+        label,
+        result.input,
+        intent,
+        result.confidence.unwrap_or_default()
+    );
+
+    if !result.entities.is_empty() {
+        let mut entities: Vec<String> = result.entities.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        entities.sort();
+        println!("         {}", entities.join(" ").dimmed());
+    }
+}
+
+/// The intent as the providers name it, e.g. `tx.buy`
+fn intent_name(intent: &Intent) -> String {
+    serde_json::to_value(intent)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{:?}", intent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suites_cover_expected_intents() {
+        let total: usize = SUITES.iter().map(|(_, cases)| cases.len()).sum();
+        assert!(total >= 20);
+        assert_eq!(intent_name(&Intent::TxBuy), "tx.buy");
+        assert_eq!(intent_name(&Intent::OutOfScope), "out_of_scope");
+    }
+
+    #[test]
+    fn test_check_result_status() {
+        let parsed = |intent: Intent, confidence: f64| {
+            Ok(ParsedInput {
+                intent,
+                entities: HashMap::new(),
+                missing: vec![],
+                confidence,
+                raw_input: String::new(),
+            })
+        };
+
+        let status = |p| CheckResult::new("Buy", "buy", &Intent::TxBuy, p).status;
+        assert_eq!(status(parsed(Intent::TxBuy, 0.9)), CheckStatus::Pass);
+        assert_eq!(status(parsed(Intent::TxBuy, 0.4)), CheckStatus::LowConfidence);
+        assert_eq!(status(parsed(Intent::TxSell, 0.9)), CheckStatus::WrongIntent);
+        assert_eq!(status(Err(CryptofolioError::Other("down".into()))), CheckStatus::Error);
+    }
+}
@@ -0,0 +1,298 @@
+use chrono::{Local, Utc};
+use colored::Colorize;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::cli::commands::portfolio::fetch_price_map;
+use crate::cli::output::{format_currency, info, print_header, print_row, success, warning};
+use crate::cli::{AlertCommands, GlobalOptions};
+use crate::config::AppConfig;
+use crate::db::alerts::{Alert, AlertDirection};
+use crate::db::AlertRepository;
+use crate::error::{CryptofolioError, Result};
+use crate::exchange::BinanceClient;
+
+#[derive(Serialize)]
+struct AlertOutput {
+    id: i64,
+    asset: String,
+    direction: String,
+    threshold: String,
+    command: Option<String>,
+    triggered_at: Option<String>,
+    active: bool,
+}
+
+pub async fn handle_alert_command(command: AlertCommands, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let repo = AlertRepository::new(pool);
+
+    match command {
+        AlertCommands::Add { asset, above, below, command } => {
+            let (direction, threshold) = match (above, below) {
+                (Some(price), _) => (AlertDirection::Above, price),
+                (None, Some(price)) => (AlertDirection::Below, price),
+                (None, None) => unreachable!("clap requires --above or --below"),
+            };
+            let threshold = Decimal::from_str(&threshold)
+                .ok()
+                .filter(|t| *t > Decimal::ZERO)
+                .ok_or(CryptofolioError::InvalidAmount(threshold))?;
+
+            let id = repo.insert(&asset, direction, threshold, command.as_deref()).await?;
+            success(&format!(
+                "Alert #{}: {} {} {}",
+                id,
+                asset.to_uppercase(),
+                direction.as_str(),
+                format_currency(threshold)
+            ));
+
+            if !opts.quiet {
+                info("Run 'cryptofolio alert watch' to start checking prices");
+            }
+        }
+
+        AlertCommands::List => {
+            let alerts = repo.list(false).await?;
+
+            if opts.json {
+                let output: Vec<AlertOutput> = alerts
+                    .iter()
+                    .map(|a| AlertOutput {
+                        id: a.id,
+                        asset: a.asset.clone(),
+                        direction: a.direction.as_str().to_string(),
+                        threshold: a.threshold.to_string(),
+                        command: a.command.clone(),
+                        triggered_at: a.triggered_at.map(|t| t.to_rfc3339()),
+                        active: a.active,
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+                return Ok(());
+            }
+
+            if alerts.is_empty() {
+                println!("No alerts. Use 'cryptofolio alert add' to create one.");
+                return Ok(());
+            }
+
+            print_header(&[("ID", 6), ("Asset", 8), ("Condition", 20), ("Status", 18), ("Command", 30)]);
+            for alert in alerts {
+                let condition = format!("{} {}", alert.direction.as_str(), format_currency(alert.threshold));
+                let status = match alert.triggered_at {
+                    Some(at) => format!("fired {}", at.with_timezone(&Local).format("%Y-%m-%d %H:%M")),
+                    None => "active".to_string(),
+                };
+                let default = if cfg!(target_os = "macos") { "(notification)" } else { "-" };
+                let command = alert.command.unwrap_or_else(|| default.to_string());
+                print_row(&[
+                    (&alert.id.to_string(), 6),
+                    (&alert.asset, 8),
+                    (&condition, 20),
+                    (&status, 18),
+                    (&command, 30),
+                ]);
+            }
+        }
+
+        AlertCommands::Delete { id } => {
+            repo.delete(id).await?;
+            success(&format!("Deleted alert #{}", id));
+        }
+
+        AlertCommands::Watch { interval, once } => {
+            if interval == 0 {
+                return Err(CryptofolioError::InvalidInput("--interval must be at least 1 minute".to_string()));
+            }
+            watch_alerts(interval, once, pool, opts).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check active alerts every `interval` minutes until none are left or Ctrl+C
+async fn watch_alerts(interval: u64, once: bool, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let config = AppConfig::load()?;
+    let client = BinanceClient::new(
+        opts.testnet || config.general.use_testnet,
+        config.binance.api_key.clone(),
+        config.binance.api_secret.clone(),
+    );
+    let repo = AlertRepository::new(pool);
+
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval * 60));
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        let check = async {
+            ticker.tick().await;
+
+            let alerts = repo.list(true).await?;
+            if alerts.is_empty() {
+                return Ok::<_, CryptofolioError>(0);
+            }
+
+            let assets: Vec<String> = alerts
+                .iter()
+                .map(|a| a.asset.clone())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            let prices = fetch_price_map(&client, &assets, pool).await.prices;
+
+            let crossed = crossed_alerts(&alerts, &prices);
+            for (alert, price) in &crossed {
+                fire_alert(alert, *price).await;
+                repo.mark_triggered(alert.id, Utc::now()).await?;
+            }
+
+            if !opts.quiet {
+                println!(
+                    "[{}] Checked {} alert(s), {} went off",
+                    Local::now().format("%H:%M:%S"),
+                    alerts.len(),
+                    crossed.len()
+                );
+            }
+            Ok(alerts.len() - crossed.len())
+        };
+
+        tokio::select! {
+            _ = &mut ctrl_c => return Ok(()),
+            remaining = check => {
+                if remaining? == 0 {
+                    if !opts.quiet {
+                        info("No active alerts left to watch");
+                    }
+                    return Ok(());
+                }
+            }
+        }
+
+        if once {
+            return Ok(());
+        }
+    }
+}
+
+/// Active alerts whose asset has a price on the alerting side of the threshold
+fn crossed_alerts<'a>(alerts: &'a [Alert], prices: &HashMap<String, Decimal>) -> Vec<(&'a Alert, Decimal)> {
+    alerts
+        .iter()
+        .filter(|a| a.active)
+        .filter_map(|a| prices.get(&a.asset.to_uppercase()).map(|p| (a, *p)))
+        .filter(|(a, price)| a.is_crossed(*price))
+        .collect()
+}
+
+fn alert_message(alert: &Alert, price: Decimal) -> String {
+    format!(
+        "{} is {} {} (now {})",
+        alert.asset,
+        alert.direction.as_str(),
+        format_currency(alert.threshold),
+        format_currency(price)
+    )
+}
+
+/// Report an alert and run its command; a failing command is only a warning
+async fn fire_alert(alert: &Alert, price: Decimal) {
+    let message = alert_message(alert, price);
+    println!("{} {}", "ALERT".yellow().bold(), message);
+
+    let Some(command) = alert.command.clone().or_else(|| default_notification_command(&message)) else {
+        return;
+    };
+
+    let status = shell_command(&command)
+        .env("CRYPTOFOLIO_ALERT_ASSET", &alert.asset)
+        .env("CRYPTOFOLIO_ALERT_PRICE", price.to_string())
+        .env("CRYPTOFOLIO_ALERT_MESSAGE", &message)
+        .status()
+        .await;
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => warning(&format!("Alert #{} command exited with {}", alert.id, status)),
+        Err(e) => warning(&format!("Alert #{} command failed to start: {}", alert.id, e)),
+    }
+}
+
+fn shell_command(command: &str) -> tokio::process::Command {
+    if cfg!(windows) {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// A desktop notification on macOS; elsewhere alerts without a command are only printed
+fn default_notification_command(message: &str) -> Option<String> {
+    if !cfg!(target_os = "macos") {
+        return None;
+    }
+    // The message sits inside AppleScript and shell quotes, so drop any quotes of its own
+    let message: String = message.chars().filter(|c| !matches!(c, '\'' | '"' | '\\')).collect();
+    Some(format!(
+        "osascript -e 'display notification \"{}\" with title \"Cryptofolio\"'",
+        message
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(id: i64, asset: &str, direction: AlertDirection, threshold: i64) -> Alert {
+        Alert {
+            id,
+            asset: asset.to_string(),
+            direction,
+            threshold: Decimal::from(threshold),
+            command: None,
+            triggered_at: None,
+            active: true,
+        }
+    }
+
+    #[test]
+    fn test_crossed_alerts() {
+        let alerts = vec![
+            alert(1, "BTC", AlertDirection::Above, 100_000),
+            alert(2, "BTC", AlertDirection::Below, 90_000),
+            alert(3, "ETH", AlertDirection::Below, 2_500),
+            // No price known
+            alert(4, "XYZ", AlertDirection::Above, 1),
+        ];
+        let prices = HashMap::from([
+            ("BTC".to_string(), Decimal::from(100_000)),
+            ("ETH".to_string(), Decimal::from(3_000)),
+        ]);
+
+        let ids: Vec<i64> = crossed_alerts(&alerts, &prices).iter().map(|(a, _)| a.id).collect();
+        assert_eq!(ids, vec![1]);
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_command_sees_alert_environment() {
+        let path = std::env::temp_dir().join(format!("cryptofolio-alert-{}", std::process::id()));
+        let mut btc = alert(1, "BTC", AlertDirection::Above, 100_000);
+        btc.command = Some(format!("echo \"$CRYPTOFOLIO_ALERT_ASSET $CRYPTOFOLIO_ALERT_PRICE\" > {}", path.display()));
+
+        fire_alert(&btc, Decimal::from(101_000)).await;
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap().trim(), "BTC 101000");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -3,7 +3,7 @@ use sqlx::SqlitePool;
 use crate::cli::{CategoryCommands, GlobalOptions};
 use crate::cli::output::{print_header, print_row, success};
 use crate::db::AccountRepository;
-use crate::error::Result;
+use crate::error::{CryptofolioError, Result};
 
 pub async fn handle_category_command(command: CategoryCommands, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
     let _ = opts; // Will be used for JSON output
@@ -39,6 +39,34 @@ pub async fn handle_category_command(command: CategoryCommands, pool: &SqlitePoo
             success(&format!("Category renamed from '{}' to '{}'", old_name, new_name));
         }
 
+        CategoryCommands::Reorder { name, position, interactive } => {
+            let categories = repo.list_categories().await?;
+            let names: Vec<String> = categories.into_iter().map(|c| c.name).collect();
+
+            let order = if interactive {
+                match prompt_order(&names)? {
+                    Some(order) => order,
+                    None => {
+                        println!("Cancelled.");
+                        return Ok(());
+                    }
+                }
+            } else {
+                let name = name.unwrap_or_default();
+                move_to_position(names, &name, position.unwrap_or(1))?
+            };
+
+            // Renumber every category so positions stay contiguous
+            for (i, name) in order.iter().enumerate() {
+                repo.set_category_order(name, i as i32 + 1).await?;
+            }
+
+            success("Category order updated");
+            for (i, name) in order.iter().enumerate() {
+                println!("  {}. {}", i + 1, name);
+            }
+        }
+
         CategoryCommands::Remove { name, yes } => {
             if !yes {
                 println!("This will delete category '{}'.", name);
@@ -62,3 +90,90 @@ pub async fn handle_category_command(command: CategoryCommands, pool: &SqlitePoo
 
     Ok(())
 }
+
+/// Move the category `name` to the 1-based `position`, clamped to the end of the list
+fn move_to_position(mut names: Vec<String>, name: &str, position: usize) -> Result<Vec<String>> {
+    if position == 0 {
+        return Err(CryptofolioError::InvalidInput("Positions start at 1".to_string()));
+    }
+
+    let index = names
+        .iter()
+        .position(|n| n.eq_ignore_ascii_case(name))
+        .ok_or_else(|| CryptofolioError::CategoryNotFound(name.to_string()))?;
+
+    let category = names.remove(index);
+    let position = position.min(names.len() + 1);
+    names.insert(position - 1, category);
+    Ok(names)
+}
+
+/// Show the categories as a numbered list and read their new order; None when left blank
+fn prompt_order(names: &[String]) -> Result<Option<Vec<String>>> {
+    use std::io::{self, Write};
+
+    for (i, name) in names.iter().enumerate() {
+        println!("  {}. {}", i + 1, name);
+    }
+    println!();
+    println!("Enter the numbers in their new order, e.g. '3 1 2'. Unlisted categories keep their relative order after them.");
+    print!("New order: ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    if input.trim().is_empty() {
+        return Ok(None);
+    }
+
+    parse_order(&input, names).map(Some)
+}
+
+/// Reorder `names` by a list of their 1-based numbers, appending any not mentioned
+fn parse_order(input: &str, names: &[String]) -> Result<Vec<String>> {
+    let mut picked: Vec<usize> = Vec::new();
+
+    for token in input.split(|c: char| c.is_whitespace() || c == ',').filter(|t| !t.is_empty()) {
+        let number: usize = token
+            .parse()
+            .ok()
+            .filter(|n| (1..=names.len()).contains(n))
+            .ok_or_else(|| {
+                CryptofolioError::InvalidInput(format!("'{}' is not a number between 1 and {}", token, names.len()))
+            })?;
+
+        if picked.contains(&(number - 1)) {
+            return Err(CryptofolioError::InvalidInput(format!("{} is listed more than once", number)));
+        }
+        picked.push(number - 1);
+    }
+
+    let rest = (0..names.len()).filter(|i| !picked.contains(i)).collect::<Vec<_>>();
+    Ok(picked.into_iter().chain(rest).map(|i| names[i].clone()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Vec<String> {
+        ["Banking", "Trading", "Cold Storage"].iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_move_to_position() {
+        assert_eq!(move_to_position(names(), "cold storage", 1).unwrap(), ["Cold Storage", "Banking", "Trading"]);
+        assert_eq!(move_to_position(names(), "Banking", 9).unwrap(), ["Trading", "Cold Storage", "Banking"]);
+        assert!(move_to_position(names(), "Banking", 0).is_err());
+        assert!(move_to_position(names(), "Missing", 1).is_err());
+    }
+
+    #[test]
+    fn test_parse_order() {
+        assert_eq!(parse_order("3, 1", &names()).unwrap(), ["Cold Storage", "Banking", "Trading"]);
+        assert!(parse_order("1 1", &names()).is_err());
+        assert!(parse_order("4", &names()).is_err());
+        assert!(parse_order("x", &names()).is_err());
+    }
+}
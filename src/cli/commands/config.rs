@@ -5,25 +5,30 @@ use std::io::{self, Write};
 use std::path::PathBuf;
 
 use crate::cli::{ConfigCommands, GlobalOptions};
-use crate::cli::output::{print_kv, success};
+use crate::cli::output::{print_json, print_kv, success, warning};
 use crate::config::secrets::{
     ensure_secure_permissions, is_secret_key, read_secret_from_env, read_secret_from_file,
     read_secret_from_stdin, read_secret_interactive, show_security_warning,
+    validate_binance_credential,
 };
+use crate::config::settings::{ConfigKey, CONFIG_KEYS};
 use crate::config::AppConfig;
 use crate::db::KeychainKeyRepository;
 use crate::error::{CryptofolioError, Result};
 
-#[cfg(target_os = "macos")]
-use crate::config::keychain::{get_keychain, KeychainSecurityLevel};
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use crate::config::keychain::{get_keychain, platform_name, KeychainSecurityLevel};
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 use crate::config::migration;
 
 #[derive(Serialize)]
 struct ConfigOutput {
     general: GeneralConfig,
     binance: BinanceConfig,
+    kraken: KrakenConfig,
     display: DisplayConfig,
+    ethereum: EthereumConfig,
+    bitcoin: BitcoinConfig,
     paths: PathsConfig,
 }
 
@@ -37,7 +42,17 @@ struct GeneralConfig {
 #[derive(Serialize)]
 struct BinanceConfig {
     api_key_configured: bool,
+    api_key_source: Option<String>,
     api_secret_configured: bool,
+    api_secret_source: Option<String>,
+}
+
+#[derive(Serialize)]
+struct KrakenConfig {
+    api_key_configured: bool,
+    api_key_source: Option<String>,
+    api_secret_configured: bool,
+    api_secret_source: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -46,6 +61,24 @@ struct DisplayConfig {
     decimals: u8,
     price_decimals: u8,
     thousands_separator: bool,
+    thousands_separator_char: char,
+    decimal_separator: char,
+    currency_symbol: String,
+    currency_symbol_after: bool,
+    shell_history_size: usize,
+}
+
+#[derive(Serialize)]
+struct EthereumConfig {
+    rpc_url: String,
+    tokens: Option<String>,
+}
+
+#[derive(Serialize)]
+struct BitcoinConfig {
+    electrum_url: Option<String>,
+    explorer_url: String,
+    gap_limit: u8,
 }
 
 #[derive(Serialize)]
@@ -54,6 +87,18 @@ struct PathsConfig {
     database: String,
 }
 
+#[derive(Serialize)]
+struct ConfigSchemaOutput {
+    keys: Vec<ConfigKeyOutput>,
+}
+
+#[derive(Serialize)]
+struct ConfigKeyOutput {
+    #[serde(flatten)]
+    key: &'static ConfigKey,
+    value: Option<String>,
+}
+
 pub async fn handle_config_command(
     command: ConfigCommands,
     pool: &SqlitePool,
@@ -62,6 +107,11 @@ pub async fn handle_config_command(
     match command {
         ConfigCommands::Show => {
             let config = AppConfig::load()?;
+            let source = |key: &str| config.secret_source(key).map(|s| s.to_string());
+            let binance_key = source("binance.api_key");
+            let binance_secret = source("binance.api_secret");
+            let kraken_key = source("kraken.api_key");
+            let kraken_secret = source("kraken.api_secret");
 
             if opts.json {
                 let output = ConfigOutput {
@@ -71,14 +121,36 @@ pub async fn handle_config_command(
                         currency: config.general.currency.clone(),
                     },
                     binance: BinanceConfig {
-                        api_key_configured: config.binance.api_key.is_some(),
-                        api_secret_configured: config.binance.api_secret.is_some(),
+                        api_key_configured: binance_key.is_some(),
+                        api_key_source: binance_key,
+                        api_secret_configured: binance_secret.is_some(),
+                        api_secret_source: binance_secret,
+                    },
+                    kraken: KrakenConfig {
+                        api_key_configured: kraken_key.is_some(),
+                        api_key_source: kraken_key,
+                        api_secret_configured: kraken_secret.is_some(),
+                        api_secret_source: kraken_secret,
                     },
                     display: DisplayConfig {
                         color: config.display.color,
                         decimals: config.display.decimals,
                         price_decimals: config.display.price_decimals,
                         thousands_separator: config.display.thousands_separator,
+                        thousands_separator_char: config.display.thousands_separator_char,
+                        decimal_separator: config.display.decimal_separator,
+                        currency_symbol: config.display.currency_symbol.clone(),
+                        currency_symbol_after: config.display.currency_symbol_after,
+                        shell_history_size: config.display.shell_history_size,
+                    },
+                    ethereum: EthereumConfig {
+                        rpc_url: config.ethereum.rpc_url.clone(),
+                        tokens: config.ethereum.tokens.clone(),
+                    },
+                    bitcoin: BitcoinConfig {
+                        electrum_url: config.bitcoin.electrum_url.clone(),
+                        explorer_url: config.bitcoin.explorer_url.clone(),
+                        gap_limit: config.bitcoin.gap_limit,
                     },
                     paths: PathsConfig {
                         config_dir: AppConfig::config_dir()?.display().to_string(),
@@ -112,22 +184,13 @@ pub async fn handle_config_command(
                 println!();
 
                 println!("{}", "[binance]".dimmed());
-                print_kv(
-                    "api_key",
-                    if config.binance.api_key.is_some() {
-                        "***configured***"
-                    } else {
-                        "-"
-                    },
-                );
-                print_kv(
-                    "api_secret",
-                    if config.binance.api_secret.is_some() {
-                        "***configured***"
-                    } else {
-                        "-"
-                    },
-                );
+                print_kv("api_key", &secret_status(binance_key.as_deref()));
+                print_kv("api_secret", &secret_status(binance_secret.as_deref()));
+                println!();
+
+                println!("{}", "[kraken]".dimmed());
+                print_kv("api_key", &secret_status(kraken_key.as_deref()));
+                print_kv("api_secret", &secret_status(kraken_secret.as_deref()));
                 println!();
 
                 println!("{}", "[display]".dimmed());
@@ -135,6 +198,22 @@ pub async fn handle_config_command(
                 print_kv("decimals", &config.display.decimals.to_string());
                 print_kv("price_decimals", &config.display.price_decimals.to_string());
                 print_kv("thousands_separator", if config.display.thousands_separator { "true" } else { "false" });
+                print_kv("thousands_separator_char", &config.display.thousands_separator_char.to_string());
+                print_kv("decimal_separator", &config.display.decimal_separator.to_string());
+                print_kv("currency_symbol", &config.display.currency_symbol);
+                print_kv("currency_symbol_after", if config.display.currency_symbol_after { "true" } else { "false" });
+                print_kv("shell_history_size", &config.display.shell_history_size.to_string());
+                println!();
+
+                println!("{}", "[ethereum]".dimmed());
+                print_kv("rpc_url", &config.ethereum.rpc_url);
+                print_kv("tokens", config.ethereum.tokens.as_deref().unwrap_or("-"));
+                println!();
+
+                println!("{}", "[bitcoin]".dimmed());
+                print_kv("electrum_url", config.bitcoin.electrum_url.as_deref().unwrap_or("-"));
+                print_kv("explorer_url", &config.bitcoin.explorer_url);
+                print_kv("gap_limit", &config.bitcoin.gap_limit.to_string());
                 println!();
 
                 // Show paths
@@ -152,6 +231,49 @@ pub async fn handle_config_command(
             }
         }
 
+        ConfigCommands::Get { key } => {
+            let config = AppConfig::load()?;
+
+            let value = match config.get(&key)? {
+                // Never print secrets; report only whether they are set
+                Some(_) if is_secret_key(&key) => "***configured***".to_string(),
+                Some(value) => value,
+                None => String::new(),
+            };
+
+            if opts.quiet {
+                println!("{}", value);
+            } else {
+                println!("{} = {}", key, value);
+            }
+        }
+
+        ConfigCommands::ListKeys => {
+            let config = AppConfig::load()?;
+
+            let mut keys = Vec::new();
+            for key in CONFIG_KEYS {
+                let value = match config.get(key.key)? {
+                    Some(_) if is_secret_key(key.key) => Some("***configured***".to_string()),
+                    value => value,
+                };
+                keys.push(ConfigKeyOutput { key, value });
+            }
+
+            if opts.json {
+                print_json(&ConfigSchemaOutput { keys })?;
+            } else {
+                for k in &keys {
+                    println!(
+                        "{} = {} ({})",
+                        k.key.key,
+                        k.value.as_deref().unwrap_or("-"),
+                        k.key.value_type.as_str()
+                    );
+                }
+            }
+        }
+
         ConfigCommands::Set { key, value } => {
             // Warn if user is trying to set a secret insecurely
             if is_secret_key(&key) {
@@ -205,8 +327,9 @@ pub async fn handle_config_command(
             secret_file,
             from_env,
             security_level,
+            no_validate,
         } => {
-            handle_set_secret_command(key, secret_file, from_env, security_level, pool).await?;
+            handle_set_secret_command(key, secret_file, from_env, security_level, no_validate, pool).await?;
         }
 
         ConfigCommands::UseTestnet => {
@@ -230,50 +353,86 @@ pub async fn handle_config_command(
             );
         }
 
+        ConfigCommands::Export { file, include_secrets } => {
+            let config = AppConfig::load()?;
+            config.export_to_file(&file, include_secrets)?;
+
+            success(&format!("Exported configuration to {}", file.display()));
+            if include_secrets && !opts.quiet {
+                warning("Secrets were written as placeholders; set them on the new machine with 'config set-secret'");
+            }
+        }
+
+        ConfigCommands::Import { file, overwrite } => {
+            if !file.exists() {
+                return Err(CryptofolioError::Config(format!("File not found: {}", file.display())));
+            }
+
+            let mut config = AppConfig::load()?;
+            let conflicts = config.import_from_file(&file, !overwrite)?;
+
+            for conflict in conflicts {
+                print!(
+                    "{}: replace '{}' with '{}'? [y/N] ",
+                    conflict.key, conflict.current, conflict.incoming
+                );
+                io::stdout().flush()?;
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                if input.trim().eq_ignore_ascii_case("y") {
+                    config.set(conflict.key, &conflict.incoming)?;
+                }
+            }
+
+            config.save()?;
+            success(&format!("Imported configuration from {}", file.display()));
+        }
+
         ConfigCommands::MigrateToKeychain => {
-            #[cfg(target_os = "macos")]
+            #[cfg(any(target_os = "macos", target_os = "linux"))]
             {
                 let keychain_repo = KeychainKeyRepository::new(pool.clone());
                 migration::run_migration(&keychain_repo).await?;
             }
 
-            #[cfg(not(target_os = "macos"))]
+            #[cfg(not(any(target_os = "macos", target_os = "linux")))]
             {
                 return Err(CryptofolioError::KeychainNotAvailable);
             }
         }
 
         ConfigCommands::KeychainStatus => {
-            #[cfg(target_os = "macos")]
+            #[cfg(any(target_os = "macos", target_os = "linux"))]
             {
                 handle_keychain_status_command(pool, opts).await?;
             }
 
-            #[cfg(not(target_os = "macos"))]
+            #[cfg(not(any(target_os = "macos", target_os = "linux")))]
             {
                 return Err(CryptofolioError::KeychainNotAvailable);
             }
         }
 
         ConfigCommands::UpgradeSecurity { key, to } => {
-            #[cfg(target_os = "macos")]
+            #[cfg(any(target_os = "macos", target_os = "linux"))]
             {
                 handle_upgrade_security_command(key, to, pool).await?;
             }
 
-            #[cfg(not(target_os = "macos"))]
+            #[cfg(not(any(target_os = "macos", target_os = "linux")))]
             {
                 return Err(CryptofolioError::KeychainNotAvailable);
             }
         }
 
         ConfigCommands::DowngradeSecurity { key, to } => {
-            #[cfg(target_os = "macos")]
+            #[cfg(any(target_os = "macos", target_os = "linux"))]
             {
                 handle_downgrade_security_command(key, to, pool).await?;
             }
 
-            #[cfg(not(target_os = "macos"))]
+            #[cfg(not(any(target_os = "macos", target_os = "linux")))]
             {
                 return Err(CryptofolioError::KeychainNotAvailable);
             }
@@ -288,6 +447,7 @@ async fn handle_set_secret_command(
     secret_file: Option<PathBuf>,
     from_env: Option<String>,
     security_level: Option<String>,
+    no_validate: bool,
     pool: &SqlitePool,
 ) -> Result<()> {
     // Read secret from appropriate source
@@ -310,8 +470,12 @@ async fn handle_set_secret_command(
         return Err(CryptofolioError::Config("Empty secret provided".into()));
     }
 
-    // Try to store in keychain on macOS
-    #[cfg(target_os = "macos")]
+    if !no_validate {
+        validate_binance_credential(&key, &secret)?;
+    }
+
+    // Try to store in the OS keychain (macOS Keychain or Linux Secret Service)
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
     {
         let keychain = get_keychain();
 
@@ -347,7 +511,8 @@ async fn handle_set_secret_command(
 
                 println!();
                 println!(
-                    "  ✓ Secret stored in macOS Keychain ({})",
+                    "  ✓ Secret stored in {} ({})",
+                    platform_name(),
                     level.as_display_str()
                 );
                 println!();
@@ -365,11 +530,11 @@ async fn handle_set_secret_command(
         }
     }
 
-    // Fall back to TOML storage (or only option on non-macOS)
-    #[cfg(not(target_os = "macos"))]
+    // Fall back to TOML storage (or only option on other platforms)
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         if security_level.is_some() {
-            eprintln!("Warning: --security-level is only supported on macOS");
+            eprintln!("Warning: --security-level is only supported on macOS and Linux");
             eprintln!();
         }
     }
@@ -395,7 +560,7 @@ async fn handle_set_secret_command(
     Ok(())
 }
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 async fn handle_keychain_status_command(pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
     use crate::db::keychain::StorageType;
 
@@ -472,7 +637,7 @@ async fn handle_keychain_status_command(pool: &SqlitePool, opts: &GlobalOptions)
     Ok(())
 }
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 async fn handle_upgrade_security_command(
     key: String,
     to: String,
@@ -522,7 +687,7 @@ async fn handle_upgrade_security_command(
     Ok(())
 }
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 async fn handle_downgrade_security_command(
     key: String,
     to: String,
@@ -583,6 +748,14 @@ async fn handle_downgrade_security_command(
 }
 
 /// Truncate string to max length with ellipsis
+/// How `config show` reports a secret, with where it is stored
+fn secret_status(source: Option<&str>) -> String {
+    match source {
+        Some(source) => format!("***configured*** ({})", source),
+        None => "-".to_string(),
+    }
+}
+
 fn truncate_string(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
@@ -1,12 +1,15 @@
+use chrono::{DateTime, Utc};
 use colored::Colorize;
 use rust_decimal::Decimal;
 use sqlx::SqlitePool;
 
+use crate::cli::commands::tx::parse_date_filter;
 use crate::cli::output;
 use crate::cli::CurrencyCommands;
 use crate::core::currency::{AssetType, Currency, ExchangeRate};
 use crate::db::currencies;
 use crate::error::{CryptofolioError, Result};
+use crate::exchange::{FiatRateProvider, FrankfurterClient};
 
 pub async fn handle_currency_command(pool: &SqlitePool, cmd: CurrencyCommands) -> Result<()> {
     match cmd {
@@ -45,7 +48,15 @@ pub async fn handle_currency_command(pool: &SqlitePool, cmd: CurrencyCommands) -
             set_exchange_rate(pool, &from, &to, rate_decimal, notes).await
         }
 
-        CurrencyCommands::ShowRate { from, to, history } => {
+        CurrencyCommands::AutoRate { from, to, notes } => {
+            auto_exchange_rate(pool, &FrankfurterClient::new(), &from, &to, notes).await
+        }
+
+        CurrencyCommands::RefreshRates => refresh_exchange_rates(pool, &FrankfurterClient::new()).await,
+
+        CurrencyCommands::ShowRate { from, to, history, limit, from_date } => {
+            let since = from_date.as_deref().map(parse_date_filter).transpose()?;
+            let history = history.then_some(RateHistoryFilter { since, limit });
             show_exchange_rate(pool, &from, &to, history, false).await
         }
     }
@@ -269,18 +280,112 @@ async fn set_exchange_rate(
     Ok(())
 }
 
+/// Fetch the current rate for a pair of fiat currencies and store it
+async fn auto_exchange_rate(
+    pool: &SqlitePool,
+    provider: &dyn FiatRateProvider,
+    from: &str,
+    to: &str,
+    notes: Option<String>,
+) -> Result<()> {
+    let from = from.to_uppercase();
+    let to = to.to_uppercase();
+
+    for code in [&from, &to] {
+        let currency = currencies::get_currency(pool, code)
+            .await?
+            .ok_or_else(|| CryptofolioError::NotFound(format!("Currency not found: {}", code)))?;
+        if !currency.is_fiat() {
+            return Err(CryptofolioError::InvalidInput(format!(
+                "{} is not a fiat currency; live rates are only fetched between fiat currencies",
+                code
+            )));
+        }
+    }
+
+    let rate = provider.get_rate(&from, &to).await?;
+    let mut exchange_rate = ExchangeRate::new_manual(&from, &to, rate, chrono::Utc::now());
+    exchange_rate.source = provider.name().to_string();
+    exchange_rate.notes = notes;
+
+    currencies::add_exchange_rate(pool, &exchange_rate).await?;
+
+    println!(
+        "{} Set exchange rate: {} {} = 1 {} ({})",
+        "✓".green(),
+        rate,
+        from.bright_cyan(),
+        to.bright_cyan(),
+        provider.name()
+    );
+
+    Ok(())
+}
+
+/// Fetch current rates for every pair of fiat currencies with a stored rate.
+/// A pair that can't be fetched is reported and the rest still refreshed.
+async fn refresh_exchange_rates(pool: &SqlitePool, provider: &dyn FiatRateProvider) -> Result<()> {
+    let fiat: Vec<String> = currencies::list_currencies(pool)
+        .await?
+        .into_iter()
+        .filter(|c| c.is_fiat())
+        .map(|c| c.code)
+        .collect();
+
+    let mut pairs: Vec<(String, String)> = currencies::list_all_exchange_rates(pool)
+        .await?
+        .into_iter()
+        .map(|r| (r.from_currency, r.to_currency))
+        .filter(|(from, to)| from != to && fiat.contains(from) && fiat.contains(to))
+        .collect();
+    pairs.sort();
+    pairs.dedup();
+
+    if pairs.is_empty() {
+        println!("No fiat exchange rates to refresh. Add one with 'cryptofolio currency set-rate'.");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for (from, to) in &pairs {
+        match provider.get_rate(from, to).await {
+            Ok(rate) => {
+                let mut exchange_rate = ExchangeRate::new_manual(from, to, rate, chrono::Utc::now());
+                exchange_rate.source = provider.name().to_string();
+                currencies::add_exchange_rate(pool, &exchange_rate).await?;
+                println!("{} {} {} = 1 {}", "✓".green(), rate, from.bright_cyan(), to.bright_cyan());
+            }
+            Err(e) => {
+                failed += 1;
+                output::warning(&format!("{}/{}: {}", from, to, e));
+            }
+        }
+    }
+
+    if failed == pairs.len() {
+        return Err(CryptofolioError::ExchangeApi("No exchange rates could be refreshed".to_string()));
+    }
+    Ok(())
+}
+
+/// Which historical rates `show-rate --history` lists
+struct RateHistoryFilter {
+    since: Option<DateTime<Utc>>,
+    limit: i64,
+}
+
 async fn show_exchange_rate(
     pool: &SqlitePool,
     from: &str,
     to: &str,
-    history: bool,
+    history: Option<RateHistoryFilter>,
     json: bool,
 ) -> Result<()> {
     let from = from.to_uppercase();
     let to = to.to_uppercase();
 
-    if history {
-        let rates = currencies::list_exchange_rates(pool, &from, &to).await?;
+    if let Some(filter) = history {
+        let rates = currencies::list_exchange_rates(pool, &from, &to, filter.since, Some(filter.limit)).await?;
 
         if rates.is_empty() {
             println!("No exchange rates found for {}/{}", from, to);
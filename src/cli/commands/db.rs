@@ -0,0 +1,187 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use sqlx::SqlitePool;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::cli::output::success;
+use crate::cli::{DbCommands, GlobalOptions};
+use crate::config::AppConfig;
+use crate::db::open_pool;
+use crate::error::{CryptofolioError, Result};
+
+/// Every SQLite database file starts with this header
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+pub async fn handle_db_command(command: DbCommands, pool: &SqlitePool, _opts: &GlobalOptions) -> Result<()> {
+    match command {
+        DbCommands::Backup { file, compress } => {
+            backup_database(pool, Path::new(&file), compress).await?;
+            success(&format!("Database backed up to {}", file));
+        }
+
+        DbCommands::Restore { file, yes } => {
+            // Check the backup before asking, so a bad file never gets that far
+            let data = read_backup(Path::new(&file))?;
+            let db_path = AppConfig::database_path()?;
+
+            if !yes {
+                println!("This will replace {} with '{}'.", db_path.display(), file);
+                println!("All current accounts, holdings and transactions will be lost.");
+                print!("Are you sure? [y/N] ");
+                io::stdout().flush()?;
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+
+                if !input.trim().eq_ignore_ascii_case("y") {
+                    println!("Cancelled.");
+                    return Ok(());
+                }
+            }
+
+            restore_database(pool, &data, &db_path).await?;
+            success(&format!("Database restored from {}", file));
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy the database to `path` with `VACUUM INTO`, which reads it in a single
+/// transaction, so writes from other connections can't leave the copy torn
+async fn backup_database(pool: &SqlitePool, path: &Path, compress: bool) -> Result<()> {
+    if path.exists() {
+        return Err(CryptofolioError::AlreadyExists(path.display().to_string()));
+    }
+
+    if !compress {
+        return vacuum_into(pool, path).await;
+    }
+
+    let uncompressed = sibling_path(path, "tmp");
+    vacuum_into(pool, &uncompressed).await?;
+
+    let result = (|| -> Result<()> {
+        let mut encoder = GzEncoder::new(File::create(path)?, Compression::default());
+        io::copy(&mut File::open(&uncompressed)?, &mut encoder)?;
+        encoder.finish()?;
+        Ok(())
+    })();
+    let _ = fs::remove_file(&uncompressed);
+    result
+}
+
+async fn vacuum_into(pool: &SqlitePool, path: &Path) -> Result<()> {
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().into_owned())
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Read a backup, unzipping it if needed, and check that it is a SQLite database
+fn read_backup(path: &Path) -> Result<Vec<u8>> {
+    let raw = fs::read(path)?;
+
+    let data = if raw.starts_with(GZIP_MAGIC) {
+        let mut data = Vec::new();
+        GzDecoder::new(raw.as_slice())
+            .read_to_end(&mut data)
+            .map_err(|e| CryptofolioError::InvalidInput(format!("{} is not a valid gzip file: {}", path.display(), e)))?;
+        data
+    } else {
+        raw
+    };
+
+    if !data.starts_with(SQLITE_HEADER) {
+        return Err(CryptofolioError::InvalidInput(format!(
+            "{} is not a SQLite database backup",
+            path.display()
+        )));
+    }
+
+    Ok(data)
+}
+
+/// Close `pool`, swap the database file at `db_path` for `data`, then open it
+/// once so a backup from an older version is migrated to the current schema
+async fn restore_database(pool: &SqlitePool, data: &[u8], db_path: &Path) -> Result<()> {
+    pool.close().await;
+
+    // Write next to the database first so the swap itself is a rename
+    let incoming = sibling_path(db_path, "restore");
+    fs::write(&incoming, data)?;
+
+    // Leftover journal files belong to the old database and would corrupt the new one
+    for suffix in ["wal", "shm", "journal"] {
+        match fs::remove_file(sibling_path(db_path, suffix)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    fs::rename(&incoming, db_path)?;
+
+    open_pool(db_path).await?.close().await;
+    Ok(())
+}
+
+/// `path` with `-suffix` appended to the file name, the way SQLite names its journals
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!("-{}", suffix));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn count_categories(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM categories").fetch_one(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore_round_trip() {
+        let dir = std::env::temp_dir().join(format!("cryptofolio-db-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let source = open_pool(&dir.join("source.sqlite")).await.unwrap();
+        sqlx::query("INSERT INTO categories (id, name) VALUES ('backup-test', 'Backup Test')")
+            .execute(&source)
+            .await
+            .unwrap();
+        let expected = count_categories(&source).await;
+
+        for (file, compress) in [("plain.sqlite", false), ("packed.sqlite.gz", true)] {
+            let backup = dir.join(file);
+            backup_database(&source, &backup, compress).await.unwrap();
+            assert_eq!(fs::read(&backup).unwrap().starts_with(GZIP_MAGIC), compress);
+            // Never overwrite an existing backup
+            assert!(backup_database(&source, &backup, compress).await.is_err());
+
+            let target_path = dir.join(format!("target-{}.sqlite", file));
+            let target = open_pool(&target_path).await.unwrap();
+            restore_database(&target, &read_backup(&backup).unwrap(), &target_path).await.unwrap();
+            assert!(target.is_closed());
+
+            let restored = open_pool(&target_path).await.unwrap();
+            assert_eq!(count_categories(&restored).await, expected);
+            restored.close().await;
+        }
+
+        source.close().await;
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_backup_rejects_other_files() {
+        let path = std::env::temp_dir().join(format!("cryptofolio-not-a-db-{}.txt", std::process::id()));
+        fs::write(&path, "account,asset\n").unwrap();
+        assert!(matches!(read_backup(&path), Err(CryptofolioError::InvalidInput(_))));
+        fs::remove_file(&path).unwrap();
+    }
+}
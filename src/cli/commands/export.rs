@@ -0,0 +1,140 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::cli::output::{info, success};
+use crate::cli::{ExportCommands, GlobalOptions};
+use crate::core::account::WalletAddress;
+use crate::db::{currencies, AccountRepository, HoldingRepository, ListAccountsFilter, TransactionRepository};
+use crate::error::Result;
+use crate::export::archive::{ARCHIVE_ACCOUNTS_FILE, ARCHIVE_CATEGORIES_FILE};
+use crate::export::ExportArchive;
+
+/// Version of the full export layout, bumped on incompatible changes
+pub(crate) const FULL_EXPORT_VERSION: u32 = 1;
+
+pub(crate) const MANIFEST_FILE: &str = "manifest.json";
+pub(crate) const CATEGORIES_FILE: &str = "categories.json";
+pub(crate) const ACCOUNTS_FILE: &str = "accounts.json";
+pub(crate) const WALLET_ADDRESSES_FILE: &str = "wallet_addresses.json";
+pub(crate) const HOLDINGS_FILE: &str = "holdings.json";
+pub(crate) const TRANSACTIONS_FILE: &str = "transactions.json";
+pub(crate) const EXCHANGE_RATES_FILE: &str = "exchange_rates.json";
+
+/// Describes a full export directory
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ExportManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: String,
+}
+
+pub async fn handle_export_command(
+    command: Option<ExportCommands>,
+    all: Option<String>,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    match command {
+        Some(ExportCommands::Full { output }) => handle_export_full(output, pool, opts).await,
+        None => handle_export_archive(all.unwrap_or_default(), pool, opts).await,
+    }
+}
+
+/// `export --all`: everything in one ZIP archive
+async fn handle_export_archive(file: String, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let account_repo = AccountRepository::new(pool);
+
+    let categories = account_repo.list_categories().await?;
+    let accounts = account_repo.list_accounts(&ListAccountsFilter::default()).await?;
+    let holdings = HoldingRepository::new(pool).list_all().await?;
+    let transactions = TransactionRepository::new(pool).list_chronological(None).await?;
+    let exchange_rates = currencies::list_all_exchange_rates(pool).await?;
+    let account_names: HashMap<String, String> =
+        accounts.iter().map(|a| (a.id.clone(), a.name.clone())).collect();
+
+    if !opts.quiet {
+        info(&format!("Exporting all data to '{}'...", file));
+    }
+
+    let mut archive = ExportArchive::new(BufWriter::new(File::create(&file)?));
+    archive.add_transactions(&transactions)?;
+    archive.add_holdings(&holdings, &account_names)?;
+    archive.add_json(ARCHIVE_ACCOUNTS_FILE, &accounts)?;
+    archive.add_json(ARCHIVE_CATEGORIES_FILE, &categories)?;
+    archive.add_exchange_rates(&exchange_rates)?;
+    archive.finish()?;
+
+    if !opts.quiet {
+        success(&format!(
+            "Exported {} accounts, {} holdings, {} transactions, {} exchange rates, {} categories",
+            accounts.len(),
+            holdings.len(),
+            transactions.len(),
+            exchange_rates.len(),
+            categories.len()
+        ));
+    }
+
+    Ok(())
+}
+
+async fn handle_export_full(output: String, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let account_repo = AccountRepository::new(pool);
+    let holding_repo = HoldingRepository::new(pool);
+    let tx_repo = TransactionRepository::new(pool);
+
+    let dir = Path::new(&output);
+    fs::create_dir_all(dir)?;
+
+    if !opts.quiet {
+        info(&format!("Exporting all data to '{}'...", output));
+    }
+
+    let categories = account_repo.list_categories().await?;
+    let accounts = account_repo.list_accounts(&ListAccountsFilter::default()).await?;
+    let mut addresses: Vec<WalletAddress> = Vec::new();
+    for acc in &accounts {
+        addresses.extend(account_repo.list_addresses(&acc.id).await?);
+    }
+    let holdings = holding_repo.list_all().await?;
+    let transactions = tx_repo.list_chronological(None).await?;
+    let exchange_rates = currencies::list_all_exchange_rates(pool).await?;
+
+    let manifest = ExportManifest {
+        format_version: FULL_EXPORT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: Utc::now().to_rfc3339(),
+    };
+
+    write_json(dir, MANIFEST_FILE, &manifest)?;
+    write_json(dir, CATEGORIES_FILE, &categories)?;
+    write_json(dir, ACCOUNTS_FILE, &accounts)?;
+    write_json(dir, WALLET_ADDRESSES_FILE, &addresses)?;
+    write_json(dir, HOLDINGS_FILE, &holdings)?;
+    write_json(dir, TRANSACTIONS_FILE, &transactions)?;
+    write_json(dir, EXCHANGE_RATES_FILE, &exchange_rates)?;
+
+    if !opts.quiet {
+        success(&format!(
+            "Exported {} accounts, {} holdings, {} transactions, {} exchange rates, {} categories",
+            accounts.len(),
+            holdings.len(),
+            transactions.len(),
+            exchange_rates.len(),
+            categories.len()
+        ));
+    }
+
+    Ok(())
+}
+
+fn write_json<T: Serialize>(dir: &Path, name: &str, data: &T) -> Result<()> {
+    let json = serde_json::to_string_pretty(data)?;
+    fs::write(dir.join(name), json)?;
+    Ok(())
+}
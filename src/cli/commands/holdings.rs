@@ -1,22 +1,93 @@
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use rust_decimal::Decimal;
+use schemars::JsonSchema;
 use serde::Serialize;
 use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
 use std::str::FromStr;
 
+use crate::cli::commands::portfolio::fetch_price_map;
+use crate::cli::commands::tx::parse_date_filter;
 use crate::cli::{HoldingsCommands, GlobalOptions};
-use crate::cli::output::{format_quantity, format_usd, print_header, print_row, success, suggest_next};
+use crate::cli::output::{
+    format_currency, format_pnl, format_pnl_percent, format_quantity, info, print_header, print_kv, print_row, success, suggest_next,
+    warning,
+};
+use crate::config::AppConfig;
+use crate::core::account::{Account, AccountConfig, AccountType};
+use crate::core::holdings::{
+    normalize_tags, rank_by_value, Holding, HoldingChangeSource, HoldingWithPrice, PnlFilter, YieldSummary,
+};
 use crate::core::transaction::Transaction;
-use crate::db::{AccountRepository, HoldingRepository, TransactionRepository};
+use crate::db::merge::MergePlan;
+use crate::db::{
+    AccountRepository, HoldingRepository, HoldingTagRepository, ListAccountsFilter, MergeRepository,
+    PortfolioRepository, TransactionRepository,
+};
 use crate::error::{CryptofolioError, Result};
+use crate::exchange::{BinanceClient, Exchange, KrakenClient};
+use crate::import::holdings_file::{AccountHoldings, HoldingEntry, HoldingsFile, HoldingsFileFormat};
 
-#[derive(Serialize)]
-struct HoldingOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct HoldingOutput {
     asset: String,
     quantity: String,
     cost_basis: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_value_usd: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unrealized_pnl_usd: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unrealized_pnl_pct: Option<String>,
     account: String,
+    account_name: String,
     account_id: String,
+    tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct HoldingHistoryOutput {
+    timestamp: String,
+    event: String,
+    delta: String,
+    balance: String,
+    source: String,
+}
+
+#[derive(Serialize)]
+struct YieldOutput {
+    account: String,
+    asset: String,
+    #[serde(flatten)]
+    summary: YieldSummary,
+}
+
+const HOLDINGS_CSV_HEADERS: [&str; 7] = [
+    "account",
+    "asset",
+    "quantity",
+    "avg_cost_basis",
+    "current_price",
+    "current_value",
+    "unrealized_pnl",
+];
+
+/// One row of `holdings list --csv`; unknown values are left empty
+#[derive(Serialize)]
+struct HoldingCsvRecord {
+    account: String,
+    asset: String,
+    quantity: String,
+    avg_cost_basis: Option<String>,
+    current_price: Option<String>,
+    current_value: Option<String>,
+    unrealized_pnl: Option<String>,
 }
 
 pub async fn handle_holdings_command(command: HoldingsCommands, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
@@ -25,59 +96,34 @@ pub async fn handle_holdings_command(command: HoldingsCommands, pool: &SqlitePoo
     let tx_repo = TransactionRepository::new(pool);
 
     match command {
-        HoldingsCommands::List { account } => {
-            let holdings = if let Some(account_name) = account {
-                let acc = account_repo.get_account(&account_name).await?
-                    .ok_or_else(|| CryptofolioError::AccountNotFound(account_name.clone()))?;
-                holding_repo.list_by_account(&acc.id).await?
+        HoldingsCommands::List {
+            account,
+            top,
+            by_value,
+            unrealized_gain_only,
+            unrealized_loss_only,
+            no_prices,
+            with_prices: _,
+            csv,
+            tag,
+        } => {
+            let pnl_filter = if unrealized_gain_only {
+                Some(PnlFilter::Gains)
+            } else if unrealized_loss_only {
+                Some(PnlFilter::Losses)
             } else {
-                holding_repo.list_all().await?
+                None
             };
-
-            if holdings.is_empty() {
-                if opts.json {
-                    println!("[]");
-                } else {
-                    println!("No holdings found.");
-                }
-                return Ok(());
-            }
-
-            if opts.json {
-                let mut output = Vec::new();
-                for holding in holdings {
-                    let account = account_repo.get_account_by_id(&holding.account_id).await?;
-                    let account_name = account.map(|a| a.name).unwrap_or_else(|| "-".to_string());
-
-                    output.push(HoldingOutput {
-                        asset: holding.asset.clone(),
-                        quantity: holding.quantity.to_string(),
-                        cost_basis: holding.avg_cost_basis.map(|c| c.to_string()),
-                        account: account_name,
-                        account_id: holding.account_id.clone(),
-                    });
-                }
-                println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
-            } else {
-                // Group by account if showing all
-                print_header(&[("Asset", 8), ("Quantity", 18), ("Cost Basis", 12), ("Account", 20)]);
-
-                for holding in holdings {
-                    let account = account_repo.get_account_by_id(&holding.account_id).await?;
-                    let account_name = account.map(|a| a.name).unwrap_or_else(|| "-".to_string());
-
-                    let cost_str = holding.avg_cost_basis
-                        .map(|c| format_usd(c))
-                        .unwrap_or_else(|| "-".to_string());
-
-                    print_row(&[
-                        (&holding.asset, 8),
-                        (&format_quantity(holding.quantity), 18),
-                        (&cost_str, 12),
-                        (&account_name, 20),
-                    ]);
-                }
-            }
+            let options = ListOptions {
+                account,
+                top,
+                by_value,
+                pnl_filter,
+                no_prices,
+                csv,
+                tag,
+            };
+            list_holdings(options, pool, opts).await?;
         }
 
         HoldingsCommands::Add {
@@ -85,38 +131,46 @@ pub async fn handle_holdings_command(command: HoldingsCommands, pool: &SqlitePoo
             quantity,
             account,
             cost,
+            date,
         } => {
-            let acc = account_repo.get_account(&account).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(account.clone()))?;
+            let acc = account_repo.require_account(&account).await?;
 
             let qty = Decimal::from_str(&quantity)
                 .map_err(|_| CryptofolioError::InvalidAmount(quantity.clone()))?;
 
-            let cost_per_unit = cost
+            let mut cost_per_unit = cost
                 .map(|c| Decimal::from_str(&c))
                 .transpose()
                 .map_err(|_| CryptofolioError::InvalidAmount("cost".to_string()))?;
 
-            holding_repo.add_quantity(&acc.id, &asset, qty, cost_per_unit).await?;
-
-            // Record transaction
-            let mut tx = Transaction::new_buy(&acc.id, &asset, qty, cost_per_unit.unwrap_or(Decimal::ZERO), Utc::now());
-            tx.notes = Some("Manual holding addition".to_string());
-            tx_repo.insert(&tx).await?;
+            let acquired_at = date.as_deref().map(parse_date_filter).transpose()?;
 
-            success(&format!("Added {} {} to '{}'", format_quantity(qty), asset.to_uppercase(), account));
-
-            if !opts.quiet {
-                suggest_next(
-                    "cryptofolio portfolio",
-                    "View your portfolio",
-                );
+            // A backdated holding without a cost takes the price recorded at the time
+            if let (Some(at), None) = (acquired_at, cost_per_unit) {
+                let day = at.format("%Y-%m-%d");
+                match PortfolioRepository::new(pool).price_before(&asset, at + Duration::days(1)).await? {
+                    Some((price, taken_at)) => {
+                        info(&format!(
+                            "Using {} price {} from the portfolio snapshot of {}",
+                            asset.to_uppercase(),
+                            format_currency(price),
+                            taken_at.format("%Y-%m-%d")
+                        ));
+                        cost_per_unit = Some(price);
+                    }
+                    None => warning(&format!(
+                        "No portfolio snapshot price for {} on or before {}; cost basis will be zero",
+                        asset.to_uppercase(),
+                        day
+                    )),
+                }
             }
+
+            add_holding(&acc, &asset, qty, cost_per_unit, acquired_at, pool, opts).await?;
         }
 
         HoldingsCommands::Remove { asset, quantity, account, yes } => {
-            let acc = account_repo.get_account(&account).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(account.clone()))?;
+            let acc = account_repo.require_account(&account).await?;
 
             let qty = Decimal::from_str(&quantity)
                 .map_err(|_| CryptofolioError::InvalidAmount(quantity.clone()))?;
@@ -136,7 +190,11 @@ pub async fn handle_holdings_command(command: HoldingsCommands, pool: &SqlitePoo
                 }
             }
 
+            let previous = holding_repo.get(&acc.id, &asset).await?.map(|h| h.quantity).unwrap_or(Decimal::ZERO);
             holding_repo.remove_quantity(&acc.id, &asset, qty).await?;
+            holding_repo
+                .record_change(&acc.id, &asset, previous, previous - qty, HoldingChangeSource::Manual, None)
+                .await?;
 
             success(&format!("Removed {} {} from '{}'", format_quantity(qty), asset.to_uppercase(), account));
         }
@@ -146,21 +204,81 @@ pub async fn handle_holdings_command(command: HoldingsCommands, pool: &SqlitePoo
             quantity,
             account,
             cost,
+            delta,
+            from_exchange,
+            yes,
+            dry_run,
         } => {
-            let acc = account_repo.get_account(&account).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(account.clone()))?;
+            let acc = account_repo.require_account(&account).await?;
 
-            let qty = Decimal::from_str(&quantity)
-                .map_err(|_| CryptofolioError::InvalidAmount(quantity.clone()))?;
+            let qty = match quantity {
+                Some(quantity) => Decimal::from_str(&quantity)
+                    .map_err(|_| CryptofolioError::InvalidAmount(quantity.clone()))?,
+                // clap requires a quantity unless --from-exchange is set
+                None => {
+                    let qty = fetch_exchange_balance(&acc, &asset, opts).await?;
+                    if !opts.quiet {
+                        info(&format!("'{}' reports {} {}", account, format_quantity(qty), asset.to_uppercase()));
+                    }
+                    qty
+                }
+            };
 
             let cost_per_unit = cost
                 .map(|c| Decimal::from_str(&c))
                 .transpose()
                 .map_err(|_| CryptofolioError::InvalidAmount("cost".to_string()))?;
 
-            holding_repo.set_quantity(&acc.id, &asset, qty, cost_per_unit).await?;
+            if delta {
+                return add_holding(&acc, &asset, qty, cost_per_unit, None, pool, opts).await;
+            }
+
+            let current = holding_repo.get(&acc.id, &asset).await?.map(|h| h.quantity);
+            let previous = current.unwrap_or(Decimal::ZERO);
+
+            if dry_run {
+                info(&format!(
+                    "[DRY RUN] Would set {} in '{}': {} -> {}",
+                    asset.to_uppercase(),
+                    account,
+                    format_quantity(previous),
+                    format_quantity(qty)
+                ));
+                return Ok(());
+            }
+
+            // Overwriting an existing quantity can't be undone, so show it first
+            if !yes && current.is_some_and(|q| q != qty) {
+                let asset_upper = asset.to_uppercase();
+                println!("Current: {} {} in '{}'", format_quantity(previous), asset_upper, account);
+                println!("New:     {} {}", format_quantity(qty), asset_upper);
+                print!("Overwrite? [y/N] ");
+                use std::io::{self, Write};
+                io::stdout().flush()?;
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
 
-            success(&format!("Set {} {} in '{}'", format_quantity(qty), asset.to_uppercase(), account));
+                if !input.trim().eq_ignore_ascii_case("y") {
+                    println!("Cancelled.");
+                    return Ok(());
+                }
+            }
+
+            // Fail rather than clobber a change made while the prompt was open
+            holding_repo.set_quantity(&acc.id, &asset, qty, cost_per_unit, Some(previous)).await?;
+            if previous != qty {
+                let source = if from_exchange { HoldingChangeSource::Sync } else { HoldingChangeSource::Manual };
+                holding_repo.record_change(&acc.id, &asset, previous, qty, source, None).await?;
+            }
+
+            success(&format!(
+                "Set {} {} in '{}' (was {})",
+                format_quantity(qty),
+                asset.to_uppercase(),
+                account,
+                format_quantity(previous)
+            ));
         }
 
         HoldingsCommands::Move {
@@ -169,16 +287,30 @@ pub async fn handle_holdings_command(command: HoldingsCommands, pool: &SqlitePoo
             from,
             to,
             yes,
+            dry_run,
         } => {
-            let from_acc = account_repo.get_account(&from).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(from.clone()))?;
+            let from_acc = account_repo.require_account(&from).await?;
 
-            let to_acc = account_repo.get_account(&to).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(to.clone()))?;
+            let to_acc = account_repo.require_account(&to).await?;
 
             let qty = Decimal::from_str(&quantity)
                 .map_err(|_| CryptofolioError::InvalidAmount(quantity.clone()))?;
 
+            // Get current holding to preserve cost basis
+            let holding = holding_repo.get(&from_acc.id, &asset).await?
+                .ok_or_else(|| CryptofolioError::AssetNotFound(asset.clone()))?;
+
+            if dry_run {
+                info(&format!(
+                    "[DRY RUN] Would move: {} {} from '{}' to '{}'",
+                    format_quantity(qty),
+                    asset.to_uppercase(),
+                    from,
+                    to
+                ));
+                return Ok(());
+            }
+
             if !yes {
                 println!("This will move {} {} from '{}' to '{}'.", format_quantity(qty), asset.to_uppercase(), from, to);
                 print!("Are you sure? [y/N] ");
@@ -194,18 +326,15 @@ pub async fn handle_holdings_command(command: HoldingsCommands, pool: &SqlitePoo
                 }
             }
 
-            // Get current holding to preserve cost basis
-            let holding = holding_repo.get(&from_acc.id, &asset).await?
-                .ok_or_else(|| CryptofolioError::AssetNotFound(asset.clone()))?;
-
             // Remove from source
             holding_repo.remove_quantity(&from_acc.id, &asset, qty).await?;
 
             // Add to destination (with same cost basis)
-            holding_repo.add_quantity(&to_acc.id, &asset, qty, holding.avg_cost_basis).await?;
+            holding_repo.add_quantity(&to_acc.id, &asset, qty, holding.avg_cost_basis, holding.acquired_at).await?;
 
-            // Record transfer transaction
-            let tx = Transaction::new_transfer(&from_acc.id, &to_acc.id, &asset, qty, Utc::now());
+            // Record transfer transaction, carrying the cost basis it moved with
+            let mut tx = Transaction::new_transfer(&from_acc.id, &to_acc.id, &asset.to_uppercase(), qty, Utc::now());
+            tx.price_usd = holding.avg_cost_basis;
             tx_repo.insert(&tx).await?;
 
             success(&format!(
@@ -216,7 +345,852 @@ pub async fn handle_holdings_command(command: HoldingsCommands, pool: &SqlitePoo
                 to
             ));
         }
+
+        HoldingsCommands::Merge { from, to, yes, dry_run } => {
+            merge_accounts(&from, &to, yes, dry_run, pool, opts).await?;
+        }
+
+        HoldingsCommands::Check { fix } => {
+            check_holdings(fix, pool, opts).await?;
+        }
+
+        HoldingsCommands::Tag { asset, account, tags } => {
+            let (holding_id, tags) = resolve_tag_target(&asset, &account, &tags, pool).await?;
+            let added = HoldingTagRepository::new(pool).add(holding_id, &tags).await?;
+
+            success(&format!(
+                "Tagged {} in '{}' with {} ({} new)",
+                asset.to_uppercase(),
+                account,
+                tags.join(", "),
+                added
+            ));
+        }
+
+        HoldingsCommands::History { account, asset } => {
+            let acc = account_repo.require_account(&account).await?;
+            let history = holding_repo.history(&acc.id, &asset).await?;
+
+            if opts.json {
+                let output: Vec<HoldingHistoryOutput> = history
+                    .iter()
+                    .map(|e| HoldingHistoryOutput {
+                        timestamp: e.timestamp.to_rfc3339(),
+                        event: e.event.display_name().to_string(),
+                        delta: e.delta.to_string(),
+                        balance: e.balance.to_string(),
+                        source: e.event.source(),
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+                return Ok(());
+            }
+
+            if history.is_empty() {
+                println!("No history for {} in '{}'.", asset.to_uppercase(), account);
+                return Ok(());
+            }
+
+            print_header(&[("Date", 18), ("Event", 14), ("Change", 16), ("Balance", 16), ("Source", 12)]);
+            for entry in &history {
+                let sign = if entry.delta.is_sign_negative() { "-" } else { "+" };
+                print_row(&[
+                    (&entry.timestamp.format("%Y-%m-%d %H:%M").to_string(), 18),
+                    (entry.event.display_name(), 14),
+                    (&format!("{}{}", sign, format_quantity(entry.delta.abs())), 16),
+                    (&format_quantity(entry.balance), 16),
+                    (&entry.event.source(), 12),
+                ]);
+            }
+        }
+
+        HoldingsCommands::Yield { account, asset } => {
+            let acc = account_repo.require_account(&account).await?;
+            let history = holding_repo.history(&acc.id, &asset).await?;
+            let summary = YieldSummary::from_history(&history, Utc::now());
+
+            if opts.json {
+                let output = YieldOutput { account, asset: asset.to_uppercase(), summary };
+                println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+                return Ok(());
+            }
+
+            if summary.reward_count == 0 {
+                println!("No staking rewards recorded for {} in '{}'.", asset.to_uppercase(), account);
+                suggest_next(
+                    &format!("cryptofolio tx stake-reward --account {} --asset {} --amount <AMOUNT>", account, asset.to_uppercase()),
+                    "Record a reward",
+                );
+                return Ok(());
+            }
+
+            let asset = asset.to_uppercase();
+            println!();
+            let payments = if summary.reward_count == 1 { "payment" } else { "payments" };
+            print_kv(
+                "Rewards",
+                &format!("{} {} ({} {})", format_quantity(summary.total_rewards), asset, summary.reward_count, payments),
+            );
+            if let (Some(first), Some(last)) = (summary.first_reward, summary.last_reward) {
+                print_kv("Period", &format!("{} to {}", first.format("%Y-%m-%d"), last.format("%Y-%m-%d")));
+            }
+            print_kv("Average Holding", &format!("{} {}", format_quantity(summary.average_holding), asset));
+            print_kv("Days Held", &summary.days_held.to_string());
+            let apy = summary
+                .annualized_yield_percent
+                .map(|p| format!("{}%", p))
+                .unwrap_or_else(|| "- (less than a day of history)".to_string());
+            print_kv("Annualized Yield", &apy);
+            println!();
+        }
+
+        HoldingsCommands::Untag { asset, account, tags } => {
+            let (holding_id, tags) = resolve_tag_target(&asset, &account, &tags, pool).await?;
+            let removed = HoldingTagRepository::new(pool).remove(holding_id, &tags).await?;
+
+            success(&format!(
+                "Removed {} of {} tags from {} in '{}'",
+                removed,
+                tags.len(),
+                asset.to_uppercase(),
+                account
+            ));
+        }
+
+        HoldingsCommands::Import { file, format, merge, skip_existing } => {
+            import_holdings_file(&file, format.as_deref(), merge, skip_existing, pool).await?;
+        }
+
+        HoldingsCommands::Export { file, format } => {
+            export_holdings_file(&file, format.as_deref(), pool).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Load a holdings file. Everything is checked before the first write, so a
+/// missing account or an unresolved conflict leaves the holdings untouched.
+async fn import_holdings_file(
+    file: &str,
+    format: Option<&str>,
+    merge: bool,
+    skip_existing: bool,
+    pool: &SqlitePool,
+) -> Result<()> {
+    let path = Path::new(file);
+    let format = HoldingsFileFormat::resolve(format, path)?;
+    let holdings_file = HoldingsFile::parse(&fs::read_to_string(path)?, format)?;
+
+    let account_repo = AccountRepository::new(pool);
+    let holding_repo = HoldingRepository::new(pool);
+
+    let mut planned = Vec::new();
+    let mut conflicts = Vec::new();
+    for entry in &holdings_file.accounts {
+        let account = account_repo.require_account(&entry.name).await?;
+        for holding in &entry.holdings {
+            let existing = holding_repo.get(&account.id, &holding.asset).await?;
+            if existing.is_some() && !merge && !skip_existing {
+                conflicts.push(format!("{}/{}", account.name, holding.asset.to_uppercase()));
+            }
+            planned.push((account.clone(), holding, existing));
+        }
+    }
+
+    if !conflicts.is_empty() {
+        return Err(CryptofolioError::AlreadyExists(format!(
+            "{} holding(s) already exist ({}). Use --merge to overwrite them or --skip-existing to keep them",
+            conflicts.len(),
+            conflicts.join(", ")
+        )));
+    }
+
+    let (mut added, mut updated, mut skipped) = (0, 0, 0);
+    for (account, holding, existing) in planned {
+        let previous = existing.as_ref().map(|h| h.quantity).unwrap_or(Decimal::ZERO);
+        let cost = match &existing {
+            Some(_) if skip_existing => {
+                skipped += 1;
+                continue;
+            }
+            Some(h) => {
+                if h.quantity == holding.quantity && (holding.cost.is_none() || holding.cost == h.avg_cost_basis) {
+                    continue;
+                }
+                updated += 1;
+                holding.cost.or(h.avg_cost_basis)
+            }
+            None if holding.quantity.is_zero() => continue,
+            None => {
+                added += 1;
+                holding.cost
+            }
+        };
+
+        holding_repo
+            .set_quantity(&account.id, &holding.asset, holding.quantity, cost, Some(previous))
+            .await?;
+        if previous != holding.quantity {
+            holding_repo
+                .record_change(&account.id, &holding.asset, previous, holding.quantity, HoldingChangeSource::Manual, None)
+                .await?;
+        }
+    }
+
+    success(&format!("Imported holdings from {}: {} added, {} updated", file, added, updated));
+    if skipped > 0 {
+        info(&format!("Skipped {} existing holding(s)", skipped));
+    }
+
+    Ok(())
+}
+
+/// Write every non-zero holding, grouped by account, to a holdings file
+async fn export_holdings_file(file: &str, format: Option<&str>, pool: &SqlitePool) -> Result<()> {
+    let path = Path::new(file);
+    let format = HoldingsFileFormat::resolve(format, path)?;
+
+    let holding_repo = HoldingRepository::new(pool);
+    let mut holdings_file = HoldingsFile::default();
+    let mut count = 0;
+    for account in AccountRepository::new(pool).list_accounts(&ListAccountsFilter::default()).await? {
+        let holdings: Vec<HoldingEntry> = holding_repo
+            .list_by_account(&account.id)
+            .await?
+            .into_iter()
+            .filter(|h| !h.quantity.is_zero())
+            .map(|h| HoldingEntry {
+                asset: h.asset,
+                quantity: h.quantity.normalize(),
+                cost: h.avg_cost_basis.map(|c| c.normalize()),
+            })
+            .collect();
+
+        if !holdings.is_empty() {
+            count += holdings.len();
+            holdings_file.accounts.push(AccountHoldings { name: account.name, holdings });
+        }
+    }
+
+    fs::write(path, holdings_file.to_string(format)?)?;
+    success(&format!(
+        "Exported {} holdings from {} accounts to {}",
+        count,
+        holdings_file.accounts.len(),
+        file
+    ));
+
+    Ok(())
+}
+
+/// Merge the account `from` into `to`, showing the plan first
+async fn merge_accounts(
+    from: &str,
+    to: &str,
+    yes: bool,
+    dry_run: bool,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    let account_repo = AccountRepository::new(pool);
+    let from_acc = account_repo.require_account(from).await?;
+    let to_acc = account_repo.require_account(to).await?;
+    let merge_repo = MergeRepository::new(pool);
+    let plan = merge_repo.plan(&from_acc, &to_acc).await?;
+
+    if !opts.quiet || dry_run {
+        print_merge_plan(&plan, &from_acc, &to_acc);
+    }
+
+    if dry_run {
+        info("[DRY RUN] No changes made");
+        return Ok(());
+    }
+
+    if !yes {
+        print!("Merge '{}' into '{}' and delete '{}'? [y/N] ", from_acc.name, to_acc.name, from_acc.name);
+        use std::io::{self, Write};
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let plan = merge_repo.merge(&from_acc, &to_acc).await?;
+    success(&format!(
+        "Merged '{}' into '{}' ({} holdings, {} transactions)",
+        from_acc.name,
+        to_acc.name,
+        plan.holdings.len(),
+        plan.transactions
+    ));
+
+    Ok(())
+}
+
+/// Account that `holdings check --fix` gives orphaned holdings and transactions to
+pub const UNKNOWN_ACCOUNT: &str = "[Unknown]";
+
+#[derive(Serialize)]
+struct CheckOutput {
+    orphaned_holdings: Vec<CheckHoldingOutput>,
+    orphaned_transactions: Vec<i64>,
+    negative_holdings: Vec<CheckHoldingOutput>,
+    fixed: bool,
+}
+
+#[derive(Serialize)]
+struct CheckHoldingOutput {
+    account_id: String,
+    asset: String,
+    quantity: String,
+}
+
+impl From<&Holding> for CheckHoldingOutput {
+    fn from(h: &Holding) -> Self {
+        Self {
+            account_id: h.account_id.clone(),
+            asset: h.asset.clone(),
+            quantity: h.quantity.to_string(),
+        }
+    }
+}
+
+/// Report holdings and transactions that refer to missing accounts, and
+/// negative holdings; with `fix`, repair them
+async fn check_holdings(fix: bool, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let account_repo = AccountRepository::new(pool);
+    let holding_repo = HoldingRepository::new(pool);
+    let tx_repo = TransactionRepository::new(pool);
+
+    let orphans = holding_repo.find_orphans().await?;
+    let orphan_txs = tx_repo.find_orphans().await?;
+    let negatives = holding_repo.find_negative().await?;
+    let names: HashMap<String, String> = account_repo
+        .list_accounts(&ListAccountsFilter::default())
+        .await?
+        .into_iter()
+        .map(|a| (a.id, a.name))
+        .collect();
+
+    let clean = orphans.is_empty() && orphan_txs.is_empty() && negatives.is_empty();
+    if !opts.json {
+        if clean {
+            success("No problems found");
+            return Ok(());
+        }
+        print_check_report(&orphans, &orphan_txs, &negatives, &names);
+    }
+
+    if fix && !clean {
+        if !orphans.is_empty() || !orphan_txs.is_empty() {
+            let unknown = unknown_account(&account_repo).await?;
+            for holding in &orphans {
+                holding_repo.move_to_account(holding, &unknown.id).await?;
+            }
+            tx_repo.reassign_orphans(&unknown.id).await?;
+        }
+
+        // Orphans that were negative are now in the unknown account, so look again
+        let negatives = holding_repo.find_negative().await?;
+        for holding in &negatives {
+            holding_repo
+                .set_quantity(&holding.account_id, &holding.asset, Decimal::ZERO, None, Some(holding.quantity))
+                .await?;
+            holding_repo
+                .record_change(
+                    &holding.account_id,
+                    &holding.asset,
+                    holding.quantity,
+                    Decimal::ZERO,
+                    HoldingChangeSource::Manual,
+                    None,
+                )
+                .await?;
+        }
+
+        if !opts.json && !opts.quiet {
+            success(&format!(
+                "Moved {} holdings and {} transactions to '{}', zeroed {} negative holdings",
+                orphans.len(),
+                orphan_txs.len(),
+                UNKNOWN_ACCOUNT,
+                negatives.len()
+            ));
+        }
+    } else if !fix && !clean && !opts.json && !opts.quiet {
+        suggest_next(
+            "cryptofolio holdings check --fix",
+            &format!("Move orphans to '{}' and zero negative holdings", UNKNOWN_ACCOUNT),
+        );
+    }
+
+    if opts.json {
+        let output = CheckOutput {
+            orphaned_holdings: orphans.iter().map(CheckHoldingOutput::from).collect(),
+            orphaned_transactions: orphan_txs.iter().map(|tx| tx.id).collect(),
+            negative_holdings: negatives.iter().map(CheckHoldingOutput::from).collect(),
+            fixed: fix && !clean,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
     }
 
     Ok(())
 }
+
+fn print_check_report(
+    orphans: &[Holding],
+    orphan_txs: &[Transaction],
+    negatives: &[Holding],
+    names: &HashMap<String, String>,
+) {
+    if !orphans.is_empty() {
+        println!("Holdings of missing accounts ({}):", orphans.len());
+        print_header(&[("Asset", 8), ("Quantity", 16), ("Account ID", 38)]);
+        for h in orphans {
+            print_row(&[(&h.asset, 8), (&format_quantity(h.quantity), 16), (&h.account_id, 38)]);
+        }
+        println!();
+    }
+
+    if !orphan_txs.is_empty() {
+        println!("Transactions of missing accounts ({}):", orphan_txs.len());
+        print_header(&[("ID", 6), ("Date", 12), ("Type", 16), ("Missing Account", 38)]);
+        for tx in orphan_txs {
+            let missing: Vec<&str> = [tx.from_account_id.as_deref(), tx.to_account_id.as_deref()]
+                .into_iter()
+                .flatten()
+                .filter(|id| !names.contains_key(*id))
+                .collect();
+            print_row(&[
+                (&tx.id.to_string(), 6),
+                (&tx.timestamp.format("%Y-%m-%d").to_string(), 12),
+                (tx.tx_type.as_str(), 16),
+                (&missing.join(", "), 38),
+            ]);
+        }
+        println!();
+    }
+
+    if !negatives.is_empty() {
+        println!("Negative holdings ({}):", negatives.len());
+        print_header(&[("Asset", 8), ("Quantity", 16), ("Account", 38)]);
+        for h in negatives {
+            let account = names.get(&h.account_id).unwrap_or(&h.account_id);
+            print_row(&[(&h.asset, 8), (&format_quantity(h.quantity), 16), (account, 38)]);
+        }
+        println!();
+    }
+}
+
+/// The `[Unknown]` account, created on first use
+async fn unknown_account(account_repo: &AccountRepository<'_>) -> Result<Account> {
+    if let Some(account) = account_repo.get_account(UNKNOWN_ACCOUNT).await? {
+        return Ok(account);
+    }
+
+    let category_id = account_repo
+        .list_categories()
+        .await?
+        .into_iter()
+        .next()
+        .map(|c| c.id)
+        .ok_or_else(|| CryptofolioError::Other("No categories to put the unknown account in".to_string()))?;
+    let account = Account {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: UNKNOWN_ACCOUNT.to_string(),
+        category_id,
+        account_type: AccountType::SoftwareWallet,
+        config: AccountConfig::default(),
+        sync_enabled: false,
+        created_at: Utc::now(),
+    };
+    account_repo.create_account(&account).await?;
+    info(&format!("Created account '{}'", UNKNOWN_ACCOUNT));
+    Ok(account)
+}
+
+fn print_merge_plan(plan: &MergePlan, from: &Account, to: &Account) {
+    println!("Merge plan: '{}' into '{}'", from.name, to.name);
+    println!();
+
+    if plan.holdings.is_empty() {
+        println!("  No holdings to move.");
+    } else {
+        print_header(&[("Asset", 8), ("Moving", 14), ("Existing", 14), ("After", 14), ("Avg Cost", 12)]);
+        for h in &plan.holdings {
+            let existing = h.existing.as_ref().map(|e| format_quantity(e.quantity)).unwrap_or_else(|| "-".to_string());
+            let cost = h.merged.avg_cost_basis.map(format_currency).unwrap_or_else(|| "-".to_string());
+            print_row(&[
+                (&h.source.asset, 8),
+                (&format_quantity(h.source.quantity), 14),
+                (&existing, 14),
+                (&format_quantity(h.merged.quantity), 14),
+                (&cost, 12),
+            ]);
+        }
+    }
+
+    println!();
+    println!(
+        "  {} transactions and {} wallet addresses move to '{}'",
+        plan.transactions, plan.addresses, to.name
+    );
+    println!("  '{}' will be deleted", from.name);
+    println!();
+}
+
+/// Live balance of one asset on an exchange account, using the same
+/// credentials and testnet setting as `sync`
+async fn fetch_exchange_balance(acc: &Account, asset: &str, opts: &GlobalOptions) -> Result<Decimal> {
+    if acc.account_type != AccountType::Exchange {
+        return Err(CryptofolioError::InvalidInput(format!(
+            "'{}' is not an exchange account; --from-exchange reads exchange balances",
+            acc.name
+        )));
+    }
+    let config = AppConfig::load()?;
+
+    if acc.config.is_kraken() {
+        if !config.has_kraken_credentials() {
+            return Err(CryptofolioError::AuthRequired(
+                "Kraken API credentials not configured. Use 'cryptofolio config set-secret kraken.api_key' and 'cryptofolio config set-secret kraken.api_secret'".into()
+            ));
+        }
+        let client = KrakenClient::new(config.get_secret("kraken.api_key")?, config.get_secret("kraken.api_secret")?);
+        let balances = client.get_balances().await?;
+        return Ok(balances
+            .iter()
+            .find(|b| b.asset.eq_ignore_ascii_case(asset))
+            .map(|b| b.total())
+            .unwrap_or(Decimal::ZERO));
+    }
+
+    if !config.has_binance_credentials() {
+        return Err(CryptofolioError::AuthRequired(
+            "Binance API credentials not configured. Use 'cryptofolio config set binance.api_key <key>' and 'cryptofolio config set binance.api_secret <secret>'".into()
+        ));
+    }
+    let is_testnet = opts.testnet || acc.config.is_testnet || config.general.use_testnet;
+    let client = BinanceClient::new(is_testnet, config.binance.api_key.clone(), config.binance.api_secret.clone());
+    client.get_single_balance(asset).await
+}
+
+/// Add to a holding and record the addition as a buy, dated `acquired_at` when given
+async fn add_holding(
+    account: &Account,
+    asset: &str,
+    qty: Decimal,
+    cost_per_unit: Option<Decimal>,
+    acquired_at: Option<DateTime<Utc>>,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    HoldingRepository::new(pool)
+        .add_quantity(&account.id, asset, qty, cost_per_unit, acquired_at)
+        .await?;
+
+    // Record transaction
+    let mut tx = Transaction::new_buy(
+        &account.id,
+        asset,
+        qty,
+        cost_per_unit.unwrap_or(Decimal::ZERO),
+        acquired_at.unwrap_or_else(Utc::now),
+    );
+    tx.notes = Some("Manual holding addition".to_string());
+    TransactionRepository::new(pool).insert(&tx).await?;
+
+    success(&format!("Added {} {} to '{}'", format_quantity(qty), asset.to_uppercase(), account.name));
+
+    if !opts.quiet {
+        suggest_next(
+            "cryptofolio portfolio",
+            "View your portfolio",
+        );
+    }
+
+    Ok(())
+}
+
+/// Look up the holding a tag command targets and normalize the given tags
+async fn resolve_tag_target(
+    asset: &str,
+    account: &str,
+    tags: &[String],
+    pool: &SqlitePool,
+) -> Result<(i64, Vec<String>)> {
+    let acc = AccountRepository::new(pool).require_account(account).await?;
+    let holding = HoldingRepository::new(pool)
+        .get(&acc.id, asset)
+        .await?
+        .ok_or_else(|| CryptofolioError::AssetNotFound(asset.to_uppercase()))?;
+
+    let tags = normalize_tags(tags);
+    if tags.is_empty() {
+        return Err(CryptofolioError::InvalidInput("No tags given".into()));
+    }
+
+    Ok((holding.id, tags))
+}
+
+/// Filters and output settings for `holdings list`
+struct ListOptions {
+    account: Vec<String>,
+    top: Option<usize>,
+    by_value: bool,
+    pnl_filter: Option<PnlFilter>,
+    no_prices: bool,
+    csv: bool,
+    tag: Option<String>,
+}
+
+async fn list_holdings(options: ListOptions, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let ListOptions {
+        account,
+        top,
+        by_value,
+        pnl_filter,
+        no_prices,
+        csv,
+        tag,
+    } = options;
+    let account_repo = AccountRepository::new(pool);
+    let holding_repo = HoldingRepository::new(pool);
+
+    let mut holdings = if account.is_empty() {
+        holding_repo.list_all().await?
+    } else {
+        let mut account_ids = Vec::new();
+        for account_name in &account {
+            account_ids.push(account_repo.require_account(account_name).await?.id);
+        }
+        holding_repo.list_by_accounts(&account_ids).await?
+    };
+
+    let tags_by_holding = HoldingTagRepository::new(pool).list_all().await?;
+    let tags_of = |holding_id: i64| tags_by_holding.get(&holding_id).cloned().unwrap_or_default();
+    if let Some(tag) = &tag {
+        let tag = tag.trim().to_lowercase();
+        holdings.retain(|h| tags_by_holding.get(&h.id).is_some_and(|tags| tags.contains(&tag)));
+    }
+
+    if holdings.is_empty() && !csv {
+        if opts.json {
+            println!("[]");
+        } else {
+            println!("No holdings found.");
+        }
+        return Ok(());
+    }
+
+    let total_count = holdings.len();
+    let ranked = top.is_some() || by_value;
+    let priced = !no_prices;
+    let mut pnl_counts = (0, 0);
+
+    // --no-prices keeps the listing offline; ranking and P&L filters refuse it
+    let holdings: Vec<HoldingWithPrice> = if priced {
+        let config = AppConfig::load()?;
+        let client = BinanceClient::new(
+            opts.testnet || config.general.use_testnet,
+            config.binance.api_key.clone(),
+            config.binance.api_secret.clone(),
+        );
+        let assets: Vec<String> = holdings
+            .iter()
+            .map(|h| h.asset.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let price_map = fetch_price_map(&client, &assets, pool).await.prices;
+
+        let mut with_prices: Vec<HoldingWithPrice> = holdings
+            .into_iter()
+            .map(|h| {
+                let price = price_map.get(&h.asset.to_uppercase()).copied();
+                HoldingWithPrice::from_holding(h, price)
+            })
+            .collect();
+
+        if let Some(filter) = pnl_filter {
+            let winning = with_prices.iter().filter(|h| PnlFilter::Gains.matches(h)).count();
+            let losing = with_prices.iter().filter(|h| PnlFilter::Losses.matches(h)).count();
+            pnl_counts = (winning, losing);
+            with_prices.retain(|h| filter.matches(h));
+        }
+
+        if ranked {
+            rank_by_value(&mut with_prices, top);
+        }
+        with_prices
+    } else {
+        holdings.into_iter().map(|h| HoldingWithPrice::from_holding(h, None)).collect()
+    };
+
+    if csv {
+        // Headers are written up front so an empty listing is still valid CSV
+        let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(std::io::stdout());
+        writer.write_record(HOLDINGS_CSV_HEADERS)?;
+        for h in holdings {
+            let account = account_repo.get_account_by_id(&h.holding.account_id).await?;
+            writer.serialize(HoldingCsvRecord {
+                account: account.map(|a| a.name).unwrap_or_default(),
+                asset: h.holding.asset.clone(),
+                quantity: h.holding.quantity.to_string(),
+                avg_cost_basis: h.holding.avg_cost_basis.map(|c| c.to_string()),
+                current_price: h.current_price.map(|p| p.to_string()),
+                current_value: h.current_value.map(|v| v.to_string()),
+                unrealized_pnl: h.unrealized_pnl.map(|p| p.to_string()),
+            })?;
+        }
+        writer.flush()?;
+        return Ok(());
+    }
+
+    if opts.json {
+        let output = holding_outputs(holdings, &tags_by_holding, &account_repo).await?;
+        println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+        return Ok(());
+    }
+
+    let mut columns = vec![("Asset", 8), ("Quantity", 18), ("Cost Basis", 12)];
+    if priced {
+        columns.extend([("Price", 12), ("Value", 14), ("P&L (USD)", 14), ("P&L (%)", 10)]);
+    }
+    columns.push(("Account", 20));
+    if opts.verbose {
+        columns.push(("Tags", 24));
+    }
+    print_header(&columns);
+
+    let shown_count = holdings.len();
+    for h in holdings {
+        let account = account_repo.get_account_by_id(&h.holding.account_id).await?;
+        let account_name = account.map(|a| a.name).unwrap_or_else(|| "-".to_string());
+
+        let cost_str = h.holding.avg_cost_basis
+            .map(format_currency)
+            .unwrap_or_else(|| "-".to_string());
+        let quantity_str = format_quantity(h.holding.quantity);
+
+        let price_str = h.current_price
+            .map(format_currency)
+            .unwrap_or_else(|| "-".to_string());
+        let value_str = h.current_value
+            .map(format_currency)
+            .unwrap_or_else(|| "-".to_string());
+        let (pnl_str, pnl_width) = pnl_cell(h.unrealized_pnl, format_pnl, 14);
+        let (pnl_pct_str, pnl_pct_width) = pnl_cell(h.unrealized_pnl_percent, format_pnl_percent, 10);
+        let tags_str = tags_of(h.holding.id).join(",");
+
+        let mut row = vec![(h.holding.asset.as_str(), 8), (quantity_str.as_str(), 18), (cost_str.as_str(), 12)];
+        if priced {
+            row.extend([
+                (price_str.as_str(), 12),
+                (value_str.as_str(), 14),
+                (pnl_str.as_str(), pnl_width),
+                (pnl_pct_str.as_str(), pnl_pct_width),
+            ]);
+        }
+        row.push((&account_name, 20));
+        if opts.verbose {
+            row.push((&tags_str, 24));
+        }
+        print_row(&row);
+    }
+
+    if opts.quiet {
+        return Ok(());
+    }
+
+    if let Some(filter) = pnl_filter {
+        let (winning, losing) = pnl_counts;
+        let unknown = total_count - winning - losing;
+        let (shown_label, hidden, hidden_label) = match filter {
+            PnlFilter::Gains => ("winning", losing, "losing"),
+            PnlFilter::Losses => ("losing", winning, "winning"),
+        };
+
+        let mut hidden_note = format!("hiding {} {}", hidden, hidden_label);
+        if unknown > 0 {
+            hidden_note.push_str(&format!(", {} without P&L", unknown));
+        }
+
+        println!();
+        println!("Showing {} {} positions ({})", shown_count, shown_label, hidden_note);
+    } else if let Some(n) = top {
+        if shown_count < total_count {
+            println!();
+            println!("Showing top {} of {} holdings", n, total_count);
+        }
+    }
+
+    Ok(())
+}
+
+/// A colored P&L cell and the column width that lines it up, since color
+/// codes count towards the padding without taking up screen space
+fn pnl_cell(value: Option<Decimal>, format: fn(Decimal, bool) -> String, width: usize) -> (String, usize) {
+    match value {
+        Some(value) => {
+            let cell = format(value, true);
+            let width = width + cell.len() - format(value, false).len();
+            (cell, width)
+        }
+        None => ("-".to_string(), width),
+    }
+}
+
+/// The `holdings list --json` output without prices, optionally for one account
+pub(crate) async fn holdings_output(account: Option<&str>, pool: &SqlitePool) -> Result<Vec<HoldingOutput>> {
+    let account_repo = AccountRepository::new(pool);
+    let holding_repo = HoldingRepository::new(pool);
+
+    let holdings = match account {
+        Some(name) => {
+            let account = account_repo.require_account(name).await?;
+            holding_repo.list_by_account(&account.id).await?
+        }
+        None => holding_repo.list_all().await?,
+    };
+    let holdings = holdings.into_iter().map(|h| HoldingWithPrice::from_holding(h, None)).collect();
+    let tags_by_holding = HoldingTagRepository::new(pool).list_all().await?;
+
+    holding_outputs(holdings, &tags_by_holding, &account_repo).await
+}
+
+/// The JSON shape of `holdings`, with account names and tags
+async fn holding_outputs(
+    holdings: Vec<HoldingWithPrice>,
+    tags_by_holding: &HashMap<i64, Vec<String>>,
+    account_repo: &AccountRepository<'_>,
+) -> Result<Vec<HoldingOutput>> {
+    let mut output = Vec::new();
+    for h in holdings {
+        let account = account_repo.get_account_by_id(&h.holding.account_id).await?;
+        let account_name = account.map(|a| a.name).unwrap_or_else(|| "-".to_string());
+
+        output.push(HoldingOutput {
+            asset: h.holding.asset.clone(),
+            quantity: h.holding.quantity.to_string(),
+            cost_basis: h.holding.avg_cost_basis.map(|c| c.to_string()),
+            current_value: h.current_value.map(|v| v.to_string()),
+            current_price: h.current_price.map(|p| p.to_string()),
+            current_value_usd: h.current_value.map(|v| v.round_dp(2).to_string()),
+            unrealized_pnl_usd: h.unrealized_pnl.map(|p| p.round_dp(2).to_string()),
+            unrealized_pnl_pct: h.unrealized_pnl_percent.map(|p| p.round_dp(2).to_string()),
+            account: account_name.clone(),
+            account_name,
+            account_id: h.holding.account_id.clone(),
+            tags: tags_by_holding.get(&h.holding.id).cloned().unwrap_or_default(),
+        });
+    }
+    Ok(output)
+}
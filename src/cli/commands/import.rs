@@ -1,17 +1,29 @@
 use chrono::{DateTime, Utc};
 use indicatif::{ProgressBar, ProgressStyle};
 use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use sqlx::SqlitePool;
-use std::fs::File;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 use std::path::Path;
 use std::str::FromStr;
 
-use crate::cli::output::{error, info, success, suggest_next};
+use crate::cli::commands::export::{
+    ExportManifest, ACCOUNTS_FILE, CATEGORIES_FILE, EXCHANGE_RATES_FILE, FULL_EXPORT_VERSION,
+    HOLDINGS_FILE, MANIFEST_FILE, TRANSACTIONS_FILE, WALLET_ADDRESSES_FILE,
+};
+use crate::cli::output::{
+    error, format_currency, format_quantity, info, print_header, print_row, success, suggest_next, warning,
+};
 use crate::cli::GlobalOptions;
+use crate::core::account::{Account, Category, WalletAddress};
+use crate::core::currency::ExchangeRate;
+use crate::core::holdings::Holding;
 use crate::core::transaction::{Transaction, TransactionType};
-use crate::db::{AccountRepository, HoldingRepository, TransactionRepository};
+use crate::db::{currencies, AccountRepository, HoldingRepository, TransactionRepository};
 use crate::error::{CryptofolioError, Result};
+use crate::import::{binance_csv, coinbase_pro, koinly, ledger_live};
 
 #[derive(Debug, Deserialize)]
 struct CsvTransaction {
@@ -34,43 +46,109 @@ struct CsvTransaction {
     to_quantity: Option<String>,
 }
 
+/// Formats accepted by `import --format`
+pub const IMPORT_FORMATS: &[&str] = &["csv", "binance-csv", "coinbase-pro", "ledger-live", "koinly"];
+
+/// Which account imported rows are recorded against
+pub enum ImportTarget {
+    /// Every row goes into this account
+    Account(String),
+    /// Each row names its account in this CSV column
+    Column(String),
+}
+
 pub async fn handle_import_command(
     file: String,
-    account: String,
+    target: ImportTarget,
     format: String,
+    dry_run: bool,
     pool: &SqlitePool,
     opts: &GlobalOptions,
 ) -> Result<()> {
-    if format != "csv" {
-        return Err(CryptofolioError::Config(format!("Unsupported format: {}. Only 'csv' is supported.", format)));
+    if !IMPORT_FORMATS.contains(&format.as_str()) {
+        return Err(CryptofolioError::Config(format!(
+            "Unsupported format: {}. Supported formats: {}",
+            format,
+            IMPORT_FORMATS.join(", ")
+        )));
+    }
+
+    if dry_run && format != "koinly" {
+        return Err(CryptofolioError::InvalidInput(
+            "--dry-run is only supported for koinly imports".to_string(),
+        ));
+    }
+
+    match format.as_str() {
+        "binance-csv" => return import_binance_csv(file, target, pool, opts).await,
+        "coinbase-pro" => return import_coinbase_pro(file, target, pool, opts).await,
+        "ledger-live" => return import_ledger_live(file, target, pool, opts).await,
+        "koinly" => return import_koinly(file, target, dry_run, pool, opts).await,
+        _ => {}
     }
 
     let account_repo = AccountRepository::new(pool);
     let holding_repo = HoldingRepository::new(pool);
     let tx_repo = TransactionRepository::new(pool);
 
-    // Get account
-    let acc = account_repo.get_account(&account).await?
-        .ok_or_else(|| CryptofolioError::AccountNotFound(account.clone()))?;
-
     // Check file exists
     let path = Path::new(&file);
     if !path.exists() {
         return Err(CryptofolioError::Config(format!("File not found: {}", file)));
     }
 
-    if !opts.quiet {
-        info(&format!("Importing from '{}' into '{}'...", file, account));
-    }
+    // Parse CSV (Windows tools often prepend a UTF-8 BOM, which would corrupt the first header)
+    let contents = std::fs::read(path)?;
+    let mut reader = csv::Reader::from_reader(strip_utf8_bom(&contents));
+    let headers = reader.headers()?.clone();
+
+    // Resolve accounts up front so a typo in any row fails before anything is written
+    let routing = match &target {
+        ImportTarget::Account(account) => {
+            let acc = account_repo.require_account(account).await?;
+            if !opts.quiet {
+                info(&format!("Importing from '{}' into '{}'...", file, account));
+            }
+            AccountRouting::Single(acc.id)
+        }
+        ImportTarget::Column(column) => {
+            let index = headers.iter().position(|h| h == column).ok_or_else(|| {
+                CryptofolioError::InvalidInput(format!("CSV has no '{}' column", column))
+            })?;
+
+            let mut account_ids = HashMap::new();
+            for (line_num, record) in reader.records().enumerate() {
+                let record = record?;
+                let name = record.get(index).unwrap_or("").trim();
+                if name.is_empty() {
+                    return Err(CryptofolioError::InvalidInput(format!(
+                        "Line {}: empty '{}' column",
+                        line_num + 2,
+                        column
+                    )));
+                }
+                if let Entry::Vacant(entry) = account_ids.entry(name.to_lowercase()) {
+                    entry.insert(account_repo.require_account(name).await?.id);
+                }
+            }
 
-    // Parse CSV
-    let file = File::open(path)?;
-    let mut reader = csv::Reader::from_reader(file);
+            if !opts.quiet {
+                info(&format!(
+                    "Importing from '{}' into {} account{} from column '{}'...",
+                    file,
+                    account_ids.len(),
+                    if account_ids.len() == 1 { "" } else { "s" },
+                    column
+                ));
+            }
+            AccountRouting::PerRow { index, account_ids }
+        }
+    };
 
     // Count total rows for progress (read once, then reset)
+    let mut reader = csv::Reader::from_reader(strip_utf8_bom(&contents));
     let total_rows = reader.records().count();
-    let file = File::open(path)?;
-    let mut reader = csv::Reader::from_reader(file);
+    let mut reader = csv::Reader::from_reader(strip_utf8_bom(&contents));
 
     let progress = if !opts.quiet && total_rows > 0 {
         let pb = ProgressBar::new(total_rows as u64);
@@ -86,10 +164,21 @@ pub async fn handle_import_command(
     let mut imported = 0;
     let mut errors = 0;
 
-    for (line_num, result) in reader.deserialize().enumerate() {
+    for (line_num, record) in reader.records().enumerate() {
         let line = line_num + 2; // +1 for header, +1 for 1-based
 
-        match process_row(result, &acc.id, &holding_repo, &tx_repo).await {
+        let account_id = record.as_ref().ok().and_then(|r| routing.account_id(r));
+        let result = record.and_then(|r| r.deserialize::<CsvTransaction>(Some(&headers)));
+
+        let outcome = match account_id {
+            Some(account_id) => process_row(result, account_id, &holding_repo, &tx_repo).await,
+            None => Err(match result {
+                Err(e) => CryptofolioError::Csv(e),
+                Ok(_) => CryptofolioError::InvalidInput("Row has no account".to_string()),
+            }),
+        };
+
+        match outcome {
             Ok(_) => {
                 imported += 1;
             }
@@ -122,6 +211,368 @@ pub async fn handle_import_command(
     Ok(())
 }
 
+/// Import a Binance trade history export, skipping trades already recorded
+async fn import_binance_csv(file: String, target: ImportTarget, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let ImportTarget::Account(account) = target else {
+        return Err(CryptofolioError::InvalidInput(
+            "--account-column is not supported for binance-csv imports; use --account".to_string(),
+        ));
+    };
+
+    let account_repo = AccountRepository::new(pool);
+    let tx_repo = TransactionRepository::new(pool);
+
+    let path = Path::new(&file);
+    if !path.exists() {
+        return Err(CryptofolioError::Config(format!("File not found: {}", file)));
+    }
+
+    let acc = account_repo.require_account(&account).await?;
+    let contents = std::fs::read(path)?;
+    let trades = binance_csv::parse_trades(strip_utf8_bom(&contents))?;
+
+    if !opts.quiet {
+        info(&format!("Importing Binance trades from '{}' into '{}'...", file, account));
+    }
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    let mut errors = 0;
+
+    for (line_num, trade) in trades.into_iter().enumerate() {
+        let line = line_num + 2; // +1 for header, +1 for 1-based
+
+        let trade = match trade {
+            Ok(trade) => trade,
+            Err(e) => {
+                error(&format!("Line {}: {}", line, e));
+                errors += 1;
+                continue;
+            }
+        };
+
+        if tx_repo.external_id_exists(&trade.external_id).await? {
+            skipped += 1;
+            continue;
+        }
+
+        tx_repo.insert(&trade.to_transaction(&acc.id)).await?;
+        imported += 1;
+    }
+
+    let mut summary = format!("Imported {} trades, skipped {} already imported", imported, skipped);
+    if errors > 0 {
+        println!();
+        summary.push_str(&format!(" ({} errors)", errors));
+    }
+    success(&summary);
+
+    if !opts.quiet && imported > 0 {
+        suggest_next(&format!("cryptofolio tx list --account {}", account), "View imported trades");
+    }
+
+    Ok(())
+}
+
+/// Import a Coinbase Pro account statement, skipping entries already recorded
+async fn import_coinbase_pro(file: String, target: ImportTarget, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let ImportTarget::Account(account) = target else {
+        return Err(CryptofolioError::InvalidInput(
+            "--account-column is not supported for coinbase-pro imports; use --account".to_string(),
+        ));
+    };
+
+    let account_repo = AccountRepository::new(pool);
+    let tx_repo = TransactionRepository::new(pool);
+
+    let path = Path::new(&file);
+    if !path.exists() {
+        return Err(CryptofolioError::Config(format!("File not found: {}", file)));
+    }
+
+    let acc = account_repo.require_account(&account).await?;
+    let contents = std::fs::read(path)?;
+    let entries = coinbase_pro::parse_entries(strip_utf8_bom(&contents))?;
+
+    if !opts.quiet {
+        info(&format!("Importing Coinbase Pro statement from '{}' into '{}'...", file, account));
+    }
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    let mut errors = 0;
+
+    for (line, entry) in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                error(&format!("Line {}: {}", line, e));
+                errors += 1;
+                continue;
+            }
+        };
+
+        if tx_repo.external_id_exists(&entry.external_id).await? {
+            skipped += 1;
+            continue;
+        }
+
+        tx_repo.insert(&entry.to_transaction(&acc.id)).await?;
+        imported += 1;
+    }
+
+    let mut summary = format!("Imported {} entries, skipped {} already imported", imported, skipped);
+    if errors > 0 {
+        println!();
+        summary.push_str(&format!(" ({} errors)", errors));
+    }
+    success(&summary);
+
+    if !opts.quiet && imported > 0 {
+        suggest_next(&format!("cryptofolio tx list --account {}", account), "View imported transactions");
+    }
+
+    Ok(())
+}
+
+/// Import a Ledger Live operations export, skipping operations already recorded
+async fn import_ledger_live(file: String, target: ImportTarget, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let ImportTarget::Account(account) = target else {
+        return Err(CryptofolioError::InvalidInput(
+            "--account-column is not supported for ledger-live imports; use --account".to_string(),
+        ));
+    };
+
+    let account_repo = AccountRepository::new(pool);
+    let tx_repo = TransactionRepository::new(pool);
+
+    let path = Path::new(&file);
+    if !path.exists() {
+        return Err(CryptofolioError::Config(format!("File not found: {}", file)));
+    }
+
+    let acc = account_repo.require_account(&account).await?;
+    let contents = std::fs::read(path)?;
+    let operations = ledger_live::parse_operations(strip_utf8_bom(&contents))?;
+
+    if !opts.quiet {
+        info(&format!("Importing Ledger Live operations from '{}' into '{}'...", file, account));
+    }
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    let mut errors = 0;
+
+    for (line_num, operation) in operations.into_iter().enumerate() {
+        let line = line_num + 2; // +1 for header, +1 for 1-based
+
+        let operation = match operation {
+            Ok(operation) => operation,
+            Err(e) => {
+                error(&format!("Line {}: {}", line, e));
+                errors += 1;
+                continue;
+            }
+        };
+
+        if tx_repo.external_id_exists(&operation.external_id).await? {
+            skipped += 1;
+            continue;
+        }
+
+        tx_repo.insert(&operation.to_transaction(&acc.id)).await?;
+        imported += 1;
+    }
+
+    let mut summary = format!("Imported {} operations, skipped {} already imported", imported, skipped);
+    if errors > 0 {
+        println!();
+        summary.push_str(&format!(" ({} errors)", errors));
+    }
+    success(&summary);
+
+    if !opts.quiet && imported > 0 {
+        suggest_next(&format!("cryptofolio tx list --account {}", account), "View imported operations");
+    }
+
+    Ok(())
+}
+
+/// Import a Koinly transaction export, updating holdings and skipping
+/// transactions already recorded
+async fn import_koinly(
+    file: String,
+    target: ImportTarget,
+    dry_run: bool,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    let ImportTarget::Account(account) = target else {
+        return Err(CryptofolioError::InvalidInput(
+            "--account-column is not supported for koinly imports; use --account".to_string(),
+        ));
+    };
+
+    let account_repo = AccountRepository::new(pool);
+    let holding_repo = HoldingRepository::new(pool);
+    let tx_repo = TransactionRepository::new(pool);
+
+    let path = Path::new(&file);
+    if !path.exists() {
+        return Err(CryptofolioError::Config(format!("File not found: {}", file)));
+    }
+
+    let acc = account_repo.require_account(&account).await?;
+    let contents = std::fs::read(path)?;
+    let transactions = koinly::parse_transactions(strip_utf8_bom(&contents))?;
+
+    if !opts.quiet {
+        info(&format!("Importing Koinly transactions from '{}' into '{}'...", file, account));
+    }
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    let mut errors = 0;
+    let mut preview = Vec::new();
+
+    for (line_num, transaction) in transactions.into_iter().enumerate() {
+        let line = line_num + 2; // +1 for header, +1 for 1-based
+
+        let transaction = match transaction {
+            Ok(transaction) => transaction,
+            Err(e) => {
+                error(&format!("Line {}: {}", line, e));
+                errors += 1;
+                continue;
+            }
+        };
+
+        if tx_repo.external_id_exists(&transaction.external_id).await? {
+            skipped += 1;
+            continue;
+        }
+
+        if dry_run {
+            preview.push(transaction);
+            continue;
+        }
+
+        match apply_koinly_transaction(&transaction, &acc.id, &holding_repo, &tx_repo).await {
+            Ok(()) => imported += 1,
+            Err(e) => {
+                error(&format!("Line {}: {}", line, e));
+                errors += 1;
+            }
+        }
+    }
+
+    if dry_run {
+        print_koinly_preview(&preview);
+        info(&format!(
+            "[DRY RUN] Would import {} transactions, skip {} already imported ({} errors)",
+            preview.len(),
+            skipped,
+            errors
+        ));
+        return Ok(());
+    }
+
+    let mut summary = format!("Imported {} transactions, skipped {} already imported", imported, skipped);
+    if errors > 0 {
+        println!();
+        summary.push_str(&format!(" ({} errors)", errors));
+    }
+    success(&summary);
+
+    if !opts.quiet && imported > 0 {
+        suggest_next(&format!("cryptofolio holdings list --account {}", account), "View imported holdings");
+    }
+
+    Ok(())
+}
+
+/// Apply a Koinly row to holdings, then record it. Acquisitions carry the
+/// row's cost basis; a missing balance fails the row before anything is written.
+async fn apply_koinly_transaction(
+    transaction: &koinly::KoinlyTransaction,
+    account_id: &str,
+    holding_repo: &HoldingRepository<'_>,
+    tx_repo: &TransactionRepository<'_>,
+) -> Result<()> {
+    let tx = transaction.to_transaction(account_id);
+    let changes = tx.holding_changes();
+
+    for change in changes.iter().filter(|c| c.quantity.is_sign_negative()) {
+        holding_repo.remove_quantity(&change.account_id, &change.asset, -change.quantity).await?;
+    }
+    for change in changes.iter().filter(|c| c.quantity.is_sign_positive()) {
+        holding_repo
+            .add_quantity(
+                &change.account_id,
+                &change.asset,
+                change.quantity,
+                transaction.cost_basis(),
+                Some(transaction.timestamp),
+            )
+            .await?;
+    }
+
+    tx_repo.insert(&tx).await?;
+    Ok(())
+}
+
+/// Table of the Koinly rows a dry run would import
+fn print_koinly_preview(transactions: &[koinly::KoinlyTransaction]) {
+    if transactions.is_empty() {
+        return;
+    }
+
+    let amount = |a: &Option<koinly::Amount>| {
+        a.as_ref()
+            .map(|(quantity, asset)| format!("{} {}", format_quantity(*quantity), asset))
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    print_header(&[("Date", 12), ("Type", 14), ("Sent", 22), ("Received", 22), ("Cost Basis", 14)]);
+    for transaction in transactions {
+        let cost_basis = transaction.cost_basis().map(format_currency).unwrap_or_else(|| "-".to_string());
+        print_row(&[
+            (&transaction.timestamp.format("%Y-%m-%d").to_string(), 12),
+            (transaction.tx_type.display_name(), 14),
+            (&amount(&transaction.sent), 22),
+            (&amount(&transaction.received), 22),
+            (&cost_basis, 14),
+        ]);
+    }
+    println!();
+}
+
+/// Account lookup for each CSV row
+enum AccountRouting {
+    Single(String),
+    PerRow {
+        index: usize,
+        account_ids: HashMap<String, String>,
+    },
+}
+
+impl AccountRouting {
+    fn account_id(&self, record: &csv::StringRecord) -> Option<&str> {
+        match self {
+            AccountRouting::Single(id) => Some(id),
+            AccountRouting::PerRow { index, account_ids } => record
+                .get(*index)
+                .and_then(|name| account_ids.get(&name.trim().to_lowercase()))
+                .map(|id| id.as_str()),
+        }
+    }
+}
+
+/// Strip a leading UTF-8 byte order mark, if present
+fn strip_utf8_bom(bytes: &[u8]) -> &[u8] {
+    bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes)
+}
+
 async fn process_row(
     result: std::result::Result<CsvTransaction, csv::Error>,
     account_id: &str,
@@ -173,14 +624,14 @@ async fn process_row(
 
     // Update holdings based on transaction type
     match tx_type {
-        TransactionType::Buy | TransactionType::Receive => {
-            holding_repo.add_quantity(account_id, &row.asset, quantity, price_usd).await?;
+        TransactionType::Buy | TransactionType::Receive | TransactionType::StakingReward => {
+            holding_repo.add_quantity(account_id, &row.asset, quantity, price_usd, Some(timestamp)).await?;
         }
         TransactionType::Sell => {
             holding_repo.remove_quantity(account_id, &row.asset, quantity).await?;
         }
         TransactionType::TransferIn => {
-            holding_repo.add_quantity(account_id, &row.asset, quantity, price_usd).await?;
+            holding_repo.add_quantity(account_id, &row.asset, quantity, price_usd, Some(timestamp)).await?;
         }
         TransactionType::TransferOut => {
             holding_repo.remove_quantity(account_id, &row.asset, quantity).await?;
@@ -188,7 +639,7 @@ async fn process_row(
         TransactionType::Swap => {
             if let (Some(to_asset), Some(to_qty)) = (&row.to_asset, to_quantity) {
                 holding_repo.remove_quantity(account_id, &row.asset, quantity).await?;
-                holding_repo.add_quantity(account_id, to_asset, to_qty, None).await?;
+                holding_repo.add_quantity(account_id, to_asset, to_qty, None, Some(timestamp)).await?;
             }
         }
         _ => {}
@@ -217,20 +668,20 @@ async fn process_row(
             _ => None,
         },
         to_account_id: match tx_type {
-            TransactionType::Buy | TransactionType::Receive | TransactionType::TransferIn | TransactionType::Swap => {
+            TransactionType::Buy | TransactionType::Receive | TransactionType::StakingReward | TransactionType::TransferIn | TransactionType::Swap => {
                 Some(account_id.to_string())
             }
             _ => None,
         },
         to_asset: match tx_type {
-            TransactionType::Buy | TransactionType::Receive | TransactionType::TransferIn => {
+            TransactionType::Buy | TransactionType::Receive | TransactionType::StakingReward | TransactionType::TransferIn => {
                 Some(row.asset.to_uppercase())
             }
             TransactionType::Swap => row.to_asset.map(|s| s.to_uppercase()),
             _ => None,
         },
         to_quantity: match tx_type {
-            TransactionType::Buy | TransactionType::Receive | TransactionType::TransferIn => {
+            TransactionType::Buy | TransactionType::Receive | TransactionType::StakingReward | TransactionType::TransferIn => {
                 Some(quantity)
             }
             TransactionType::Swap => to_quantity,
@@ -253,3 +704,217 @@ async fn process_row(
 
     Ok(())
 }
+
+/// Import a directory produced by `export full`.
+///
+/// In merge mode existing records are kept and only missing ones are added;
+/// in replace mode all portfolio data is deleted before importing. Either way
+/// nothing is written unless the whole import succeeds.
+pub async fn handle_import_full_command(
+    input: String,
+    replace: bool,
+    yes: bool,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    let dir = Path::new(&input);
+    if !dir.is_dir() {
+        return Err(CryptofolioError::Config(format!("Directory not found: {}", input)));
+    }
+
+    if let Some(manifest) = read_json_file::<ExportManifest>(dir, MANIFEST_FILE)? {
+        if manifest.format_version > FULL_EXPORT_VERSION {
+            return Err(CryptofolioError::InvalidInput(format!(
+                "Export format version {} is newer than supported version {}. Please upgrade cryptofolio.",
+                manifest.format_version, FULL_EXPORT_VERSION
+            )));
+        }
+    }
+
+    let categories: Vec<Category> = read_json_file(dir, CATEGORIES_FILE)?.unwrap_or_default();
+    let accounts: Vec<Account> = read_json_file(dir, ACCOUNTS_FILE)?.unwrap_or_default();
+    let addresses: Vec<WalletAddress> = read_json_file(dir, WALLET_ADDRESSES_FILE)?.unwrap_or_default();
+    let holdings: Vec<Holding> = read_json_file(dir, HOLDINGS_FILE)?.unwrap_or_default();
+    let transactions: Vec<Transaction> = read_json_file(dir, TRANSACTIONS_FILE)?.unwrap_or_default();
+    let exchange_rates: Vec<ExchangeRate> = read_json_file(dir, EXCHANGE_RATES_FILE)?.unwrap_or_default();
+
+    if replace && !yes {
+        println!("This will delete ALL existing accounts, holdings, transactions and exchange rates.");
+        print!("Are you sure? [y/N] ");
+        use std::io::{self, Write};
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    if !opts.quiet {
+        info(&format!(
+            "Importing from '{}' ({} mode)...",
+            input,
+            if replace { "replace" } else { "merge" }
+        ));
+    }
+
+    // Clear and import in one transaction so a failure keeps the existing data
+    let mut db_tx = pool.begin().await?;
+    if replace {
+        crate::db::clear_portfolio_data_in(&mut db_tx).await?;
+    }
+
+    let mut imported_categories = 0;
+    for category in &categories {
+        if AccountRepository::get_category_in(&mut db_tx, &category.id).await?.is_some()
+            || AccountRepository::get_category_by_name_in(&mut db_tx, &category.name).await?.is_some()
+        {
+            continue;
+        }
+        AccountRepository::create_category_in(&mut db_tx, &category.id, &category.name).await?;
+        imported_categories += 1;
+    }
+
+    let mut imported_accounts = 0;
+    let mut skipped_accounts = 0;
+    for acc in &accounts {
+        if AccountRepository::get_account_by_id_in(&mut db_tx, &acc.id).await?.is_some() {
+            continue;
+        }
+        if AccountRepository::get_account_in(&mut db_tx, &acc.name).await?.is_some() {
+            // A different account already uses this name; keep the existing one
+            warning(&format!("Skipping account '{}': name already in use", acc.name));
+            skipped_accounts += 1;
+            continue;
+        }
+        AccountRepository::create_account_in(&mut db_tx, acc).await?;
+        imported_accounts += 1;
+    }
+
+    for addr in &addresses {
+        if AccountRepository::get_account_by_id_in(&mut db_tx, &addr.account_id).await?.is_none() {
+            continue;
+        }
+        let existing = AccountRepository::list_addresses_in(&mut db_tx, &addr.account_id).await?;
+        if existing.iter().any(|a| a.blockchain == addr.blockchain && a.address == addr.address) {
+            continue;
+        }
+        AccountRepository::add_address_in(
+            &mut db_tx,
+            &addr.account_id,
+            &addr.blockchain,
+            &addr.address,
+            addr.label.as_deref(),
+        )
+        .await?;
+    }
+
+    let mut imported_holdings = 0;
+    for holding in &holdings {
+        if AccountRepository::get_account_by_id_in(&mut db_tx, &holding.account_id).await?.is_none() {
+            continue;
+        }
+        if HoldingRepository::get_in(&mut db_tx, &holding.account_id, &holding.asset).await?.is_some() {
+            continue;
+        }
+        HoldingRepository::upsert_in(&mut db_tx, holding).await?;
+        imported_holdings += 1;
+    }
+
+    let existing_keys: std::collections::HashSet<String> =
+        TransactionRepository::list_chronological_in(&mut db_tx, None)
+            .await?
+            .iter()
+            .map(transaction_key)
+            .collect();
+
+    let mut imported_transactions = 0;
+    for tx in &transactions {
+        if existing_keys.contains(&transaction_key(tx)) {
+            continue;
+        }
+        TransactionRepository::insert_in(&mut db_tx, tx).await?;
+        imported_transactions += 1;
+    }
+
+    for rate in &exchange_rates {
+        currencies::add_exchange_rate_in(&mut db_tx, rate).await?;
+    }
+
+    db_tx.commit().await?;
+
+    if !opts.quiet {
+        success(&format!(
+            "Imported {} accounts, {} holdings, {} transactions, {} exchange rates, {} categories",
+            imported_accounts,
+            imported_holdings,
+            imported_transactions,
+            exchange_rates.len(),
+            imported_categories
+        ));
+        if skipped_accounts > 0 {
+            warning(&format!("{} accounts skipped due to name conflicts", skipped_accounts));
+        }
+    }
+
+    Ok(())
+}
+
+/// Read a JSON file from an export directory, returning None if it is absent
+fn read_json_file<T: DeserializeOwned>(dir: &Path, name: &str) -> Result<Option<T>> {
+    let path = dir.join(name);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = std::fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&contents)?))
+}
+
+/// Identity used to detect transactions that were already imported
+fn transaction_key(tx: &Transaction) -> String {
+    format!(
+        "{}|{}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}",
+        tx.tx_type.as_str(),
+        tx.timestamp.to_rfc3339(),
+        tx.from_account_id,
+        tx.from_asset,
+        tx.from_quantity,
+        tx.to_account_id,
+        tx.to_asset,
+        tx.to_quantity
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_utf8_bom() {
+        assert_eq!(strip_utf8_bom(b"\xEF\xBB\xBFdate"), b"date");
+        assert_eq!(strip_utf8_bom(b"date"), b"date");
+        assert_eq!(strip_utf8_bom(b""), b"");
+    }
+
+    #[test]
+    fn test_csv_with_bom_parses_first_column() {
+        let path = std::env::temp_dir().join(format!("cryptofolio-bom-{}.csv", std::process::id()));
+        let mut contents = vec![0xEF, 0xBB, 0xBF];
+        contents.extend_from_slice(b"date,type,asset,quantity,price_usd\n2024-01-15,buy,BTC,0.5,45000\n");
+        std::fs::write(&path, &contents).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut reader = csv::Reader::from_reader(strip_utf8_bom(&bytes));
+        let rows: Vec<CsvTransaction> = reader.deserialize().collect::<std::result::Result<_, _>>().unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].date, "2024-01-15");
+        assert_eq!(rows[0].asset, "BTC");
+    }
+}
@@ -1,12 +1,17 @@
 use colored::Colorize;
+use futures_util::future::join_all;
+use rust_decimal::Decimal;
 use serde::Serialize;
 use sqlx::SqlitePool;
 
-use crate::cli::output::{format_price_change, format_quantity, format_usd, print_kv, print_section, warning};
-use crate::cli::GlobalOptions;
+use crate::cli::output::{
+    format_currency, format_price_change, format_quantity, print_header, print_kv, print_row, print_section, warning,
+};
+use crate::cli::{GlobalOptions, MarketSourceArg};
 use crate::config::AppConfig;
 use crate::error::Result;
-use crate::exchange::{BinanceClient, Exchange};
+use crate::exchange::{BinanceClient, KrakenClient};
+use crate::market::{CoinGeckoClient, MarketDataProvider};
 
 #[derive(Serialize)]
 struct MarketOutput {
@@ -14,6 +19,9 @@ struct MarketOutput {
     base_asset: String,
     quote_asset: String,
     price: String,
+    source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     ticker_24h: Option<Ticker24hOutput>,
 }
@@ -28,21 +36,66 @@ struct Ticker24hOutput {
     quote_volume: String,
 }
 
-pub async fn handle_market_command(symbol: String, show_24h: bool, _pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+/// One source's quote in `market --compare`
+#[derive(Serialize)]
+struct CompareOutput {
+    source: String,
+    price: Option<Decimal>,
+    /// Percent change over 24h
+    change_24h: Option<Decimal>,
+    /// Base asset volume over 24h
+    volume_24h: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+pub async fn handle_market_command(
+    symbol: String,
+    show_24h: bool,
+    source: MarketSourceArg,
+    compare: bool,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
     let config = AppConfig::load()?;
     let use_testnet = opts.testnet || config.general.use_testnet;
 
-    let client = BinanceClient::new(
-        use_testnet,
-        config.binance.api_key.clone(),
-        config.binance.api_secret.clone(),
-    );
-
-    if !opts.quiet && use_testnet {
-        warning("Testnet Mode");
+    if compare {
+        let providers: Vec<Box<dyn MarketDataProvider>> = vec![
+            Box::new(BinanceClient::new(
+                use_testnet,
+                config.binance.api_key.clone(),
+                config.binance.api_secret.clone(),
+            )),
+            Box::new(KrakenClient::new(config.kraken.api_key.clone(), config.kraken.api_secret.clone())),
+            Box::new(CoinGeckoClient::new(pool.clone())),
+        ];
+        if !opts.quiet && use_testnet && !opts.json {
+            warning("Testnet Mode (Binance prices)");
+        }
+        return compare_markets(&symbol, &providers, opts).await;
     }
 
-    let market = client.get_market_data(&symbol).await?;
+    let provider: Box<dyn MarketDataProvider> = match source {
+        MarketSourceArg::Binance => {
+            if !opts.quiet && use_testnet {
+                warning("Testnet Mode");
+            }
+            Box::new(BinanceClient::new(
+                use_testnet,
+                config.binance.api_key.clone(),
+                config.binance.api_secret.clone(),
+            ))
+        }
+        MarketSourceArg::Coingecko => Box::new(CoinGeckoClient::new(pool.clone())),
+    };
+
+    let market = provider.get_market_data(&symbol).await?;
+    let name = provider.get_asset_metadata(&market.base_asset).await?.map(|m| m.name);
+
+    if show_24h && market.ticker_24h.is_none() && !opts.quiet {
+        warning(&format!("24h statistics are not available from {}", provider.name()));
+    }
 
     if opts.json {
         let output = MarketOutput {
@@ -50,6 +103,8 @@ pub async fn handle_market_command(symbol: String, show_24h: bool, _pool: &Sqlit
             base_asset: market.base_asset.clone(),
             quote_asset: market.quote_asset.clone(),
             price: market.price.to_string(),
+            source: provider.name().to_string(),
+            name,
             ticker_24h: if show_24h {
                 market.ticker_24h.as_ref().map(|t| Ticker24hOutput {
                     price_change: t.price_change.to_string(),
@@ -69,7 +124,11 @@ pub async fn handle_market_command(symbol: String, show_24h: bool, _pool: &Sqlit
         println!("{}", format!("{} / {}", market.base_asset, market.quote_asset).bold());
         println!();
 
-        print_kv("Price", &format_usd(market.price));
+        if let Some(name) = &name {
+            print_kv("Name", name);
+        }
+        print_kv("Price", &format_currency(market.price));
+        print_kv("Source", provider.name());
 
         if show_24h {
             if let Some(ticker) = &market.ticker_24h {
@@ -77,10 +136,10 @@ pub async fn handle_market_command(symbol: String, show_24h: bool, _pool: &Sqlit
 
                 let change_str = format_price_change(ticker.price_change, ticker.price_change_percent, config.display.color);
                 print_kv("Change", &change_str);
-                print_kv("High", &format_usd(ticker.high_24h));
-                print_kv("Low", &format_usd(ticker.low_24h));
+                print_kv("High", &format_currency(ticker.high_24h));
+                print_kv("Low", &format_currency(ticker.low_24h));
                 print_kv("Volume", &format!("{} {}", format_quantity(ticker.volume), market.base_asset));
-                print_kv("Quote Volume", &format_usd(ticker.quote_volume));
+                print_kv("Quote Volume", &format_currency(ticker.quote_volume));
             }
         }
 
@@ -89,3 +148,53 @@ pub async fn handle_market_command(symbol: String, show_24h: bool, _pool: &Sqlit
 
     Ok(())
 }
+
+/// Fetch market data from every provider concurrently and show them side by side.
+/// A source that fails is listed without a price rather than failing the command.
+async fn compare_markets(symbol: &str, providers: &[Box<dyn MarketDataProvider>], opts: &GlobalOptions) -> Result<()> {
+    let results = join_all(providers.iter().map(|p| p.get_market_data(symbol))).await;
+
+    let rows: Vec<CompareOutput> = providers
+        .iter()
+        .zip(results)
+        .map(|(provider, result)| match result {
+            Ok(market) => CompareOutput {
+                source: provider.name().to_string(),
+                price: Some(market.price),
+                change_24h: market.ticker_24h.as_ref().map(|t| t.price_change_percent.round_dp(2)),
+                volume_24h: market.ticker_24h.as_ref().map(|t| t.volume),
+                error: None,
+            },
+            Err(e) => CompareOutput {
+                source: provider.name().to_string(),
+                price: None,
+                change_24h: None,
+                volume_24h: None,
+                error: Some(e.to_string()),
+            },
+        })
+        .collect();
+
+    if opts.json {
+        println!("{}", serde_json::to_string_pretty(&rows).unwrap_or_default());
+        return Ok(());
+    }
+
+    let asset = symbol.to_uppercase();
+    println!();
+    println!("{}", format!("{} prices", asset).bold());
+    println!();
+    print_header(&[("Source", 12), ("Price", 16), ("24h Change", 12), ("Volume", 24)]);
+    for row in &rows {
+        let price = row.price.map(format_currency).unwrap_or_else(|| "unavailable".to_string());
+        let change = row.change_24h.map(|c| format!("{:+}%", c)).unwrap_or_else(|| "-".to_string());
+        let volume = row
+            .volume_24h
+            .map(|v| format!("{} {}", format_quantity(v), asset))
+            .unwrap_or_else(|| "-".to_string());
+        print_row(&[(&row.source, 12), (&price, 16), (&change, 12), (&volume, 24)]);
+    }
+    println!();
+
+    Ok(())
+}
@@ -1,7 +1,11 @@
 pub mod account;
+pub mod ai;
+pub mod alert;
 pub mod category;
 pub mod config;
 pub mod currency;
+pub mod db;
+pub mod export;
 pub mod holdings;
 pub mod import;
 pub mod market;
@@ -9,17 +13,25 @@ pub mod portfolio;
 pub mod price;
 pub mod status;
 pub mod sync;
+pub mod tax;
 pub mod tx;
+pub mod watch;
 
 pub use account::handle_account_command;
+pub use ai::handle_ai_command;
+pub use alert::handle_alert_command;
 pub use category::handle_category_command;
 pub use config::handle_config_command;
 pub use currency::handle_currency_command;
+pub use db::handle_db_command;
+pub use export::handle_export_command;
 pub use holdings::handle_holdings_command;
-pub use import::handle_import_command;
+pub use import::{handle_import_command, handle_import_full_command, ImportTarget};
 pub use market::handle_market_command;
-pub use portfolio::handle_portfolio_command;
+pub use portfolio::{handle_portfolio_command, handle_portfolio_subcommand, PortfolioOptions};
 pub use price::handle_price_command;
 pub use status::run as handle_status_command;
 pub use sync::handle_sync_command;
+pub use tax::handle_tax_command;
 pub use tx::handle_tx_command;
+pub use watch::handle_watch_command;
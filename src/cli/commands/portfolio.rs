@@ -1,65 +1,1020 @@
+use chrono::{DateTime, Utc};
 use colored::Colorize;
 use rust_decimal::Decimal;
+use schemars::JsonSchema;
 use serde::Serialize;
 use sqlx::SqlitePool;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::str::FromStr;
 
-use crate::cli::output::{format_pnl, format_pnl_percent, format_quantity, format_usd, warning};
-use crate::cli::GlobalOptions;
+use crate::cli::commands::tx::{parse_date_filter, parse_trade, record_buy, record_sell, TradeArgs};
+use crate::cli::output::{
+    colors_enabled, format_currency, format_percent, format_pnl, format_pnl_percent, format_quantity, info, number_format,
+    print_header, print_json,
+    print_row, success, warning, without_color, write_output,
+};
+use crate::cli::{GlobalOptions, OutputFormat, PortfolioCommands};
 use crate::config::AppConfig;
+use crate::core::account::AccountType;
+use crate::core::currency::{is_usd_equivalent, AssetType, CurrencyConverter};
 use crate::core::holdings::HoldingWithPrice;
-use crate::core::portfolio::{Portfolio, PortfolioEntry};
-use crate::db::{AccountRepository, HoldingRepository};
-use crate::error::Result;
+use crate::core::pnl::{CostBasisMethod, LotDisposal, LotReport, OpenLot, PositionGain};
+use crate::core::portfolio::{
+    allocation_pct, fees_by_position, period_returns, BenchmarkComparison, InvestmentSummary, PeriodReturn, Portfolio,
+    PortfolioEntry, PortfolioSnapshot,
+};
+use crate::core::rebalance::{parse_targets, RebalancePlan, RebalanceTrade, TradeSide};
+use crate::core::transaction::Transaction;
+use crate::db::{
+    currencies, AccountRepository, HoldingRepository, HoldingTagRepository, ListAccountsFilter, PortfolioRepository,
+    RealizedGainRepository,
+    TransactionRepository,
+};
+use crate::error::{CryptofolioError, Result};
 use crate::exchange::{BinanceAlphaClient, BinanceClient, Exchange};
+use crate::market::{CoinGeckoClient, MarketDataProvider};
 
-#[derive(Serialize)]
-struct PortfolioOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PortfolioOutput {
+    generated_at: String,
+    /// Every source that priced a holding, comma-separated
+    price_source: String,
+    prices_fetched_at: String,
     total_value_usd: String,
+    /// Display currency from `--currency`, when it is not USD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<String>,
+    /// Total value in the display currency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_value: Option<String>,
     total_cost_basis: String,
     unrealized_pnl: String,
     unrealized_pnl_percent: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_invested: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_return: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_return_percent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_fees: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_basis: Option<CostBasisOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    benchmark: Option<BenchmarkOutput>,
     entries: Vec<PortfolioEntryOutput>,
 }
 
-#[derive(Serialize)]
-struct PortfolioEntryOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct BenchmarkOutput {
+    asset: String,
+    since: String,
+    portfolio_return_percent: String,
+    benchmark_return_percent: String,
+    alpha: String,
+    benchmark_start_price: String,
+    benchmark_price: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct CostBasisOutput {
+    method: String,
+    realized_gain: String,
+    unrealized_gain: String,
+    disposals: Vec<LotDisposal>,
+    open_lots: Vec<OpenLotOutput>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct OpenLotOutput {
+    #[serde(flatten)]
+    lot: OpenLot,
+    current_price: Option<String>,
+    unrealized_gain: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PortfolioEntryOutput {
     account_name: String,
     category_name: String,
+    allocation_pct: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_allocation_pct: Option<Decimal>,
+    /// Account value in the display currency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_value: Option<String>,
     holdings: Vec<HoldingOutput>,
 }
 
-#[derive(Serialize)]
-struct HoldingOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct HoldingOutput {
     asset: String,
     quantity: String,
     current_price: Option<String>,
+    /// Where `current_price` came from
+    price_source: Option<String>,
     current_value: Option<String>,
     cost_basis: Option<String>,
     unrealized_pnl: Option<String>,
     unrealized_pnl_percent: Option<String>,
+    /// Share of the total portfolio value
+    allocation_pct: Decimal,
+    /// Share of the account or category with `--by-account`/`--by-category`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_allocation_pct: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fees_usd: Option<String>,
+}
+
+#[derive(Serialize)]
+struct GainsOutput {
+    generated_at: String,
+    /// "realized" or "unrealized"
+    kind: String,
+    method: String,
+    from: Option<String>,
+    to: Option<String>,
+    total_gain: String,
+    positions: Vec<PositionGainOutput>,
+}
+
+#[derive(Serialize)]
+struct PositionGainOutput {
+    account_name: String,
+    category_name: String,
+    asset: String,
+    quantity: String,
+    cost_basis: String,
+    /// Sale proceeds for realized gains, current value for unrealized ones
+    value: Option<String>,
+    gain: Option<String>,
+    is_closed: bool,
+}
+
+/// Grouping used for bank accounts in `--net-worth` mode
+const CASH_CATEGORY_ID: &str = "cash";
+
+/// Options for the `portfolio` command
+#[derive(Default)]
+pub struct PortfolioOptions {
+    pub by_account: bool,
+    pub by_category: bool,
+    pub account: Option<String>,
+    pub category: Option<String>,
+    pub asset_type: Option<String>,
+    pub format: Option<OutputFormat>,
+    pub output_file: Option<String>,
+    pub append: bool,
+    pub total_invested: bool,
+    pub show_fees: bool,
+    pub net_worth: bool,
+    pub currency: Option<String>,
+    pub tag: Option<String>,
+    pub cost_basis: Option<String>,
+    pub auto_snapshot: bool,
+    pub unrealized_only: bool,
+    pub realized_only: bool,
+    pub benchmark: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
 }
 
 pub async fn handle_portfolio_command(
+    options: PortfolioOptions,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    let PortfolioOptions {
+        by_account,
+        by_category,
+        account,
+        category,
+        asset_type,
+        format,
+        output_file,
+        append,
+        total_invested,
+        show_fees,
+        net_worth,
+        currency,
+        tag,
+        cost_basis,
+        auto_snapshot,
+        unrealized_only,
+        realized_only,
+        benchmark,
+        from,
+        to,
+    } = options;
+    let asset_type = asset_type
+        .map(|t| {
+            AssetType::from_str(&t).ok_or_else(|| {
+                CryptofolioError::InvalidInput(format!("Invalid asset type: {}. Use fiat, crypto, or stablecoin", t))
+            })
+        })
+        .transpose()?;
+    let cost_basis = cost_basis
+        .map(|m| {
+            CostBasisMethod::parse(&m).ok_or_else(|| {
+                CryptofolioError::InvalidInput(format!("Invalid cost basis method: {}. Use fifo, lifo, or hifo", m))
+            })
+        })
+        .transpose()?;
+    let from = from.as_deref().map(parse_date_filter).transpose()?;
+    let to = to.as_deref().map(parse_date_filter).transpose()?;
+    let json = opts.json || format == Some(OutputFormat::Json);
+
+    let config = AppConfig::load()?;
+    let use_testnet = opts.testnet || config.general.use_testnet;
+
+    // The separate gain views match lots with the chosen method, FIFO by default
+    let gains_report = if unrealized_only || realized_only {
+        let method = cost_basis.unwrap_or_default();
+        Some(RealizedGainRepository::new(pool).report(method, from, to).await?)
+    } else {
+        None
+    };
+    // Accounts that sold everything have no holdings left but still have realized gains
+    let realized_accounts: Option<HashSet<String>> = gains_report
+        .as_ref()
+        .filter(|_| realized_only)
+        .map(|report| report.disposals.iter().map(|d| d.account_id.clone()).collect());
+
+    let filter = PortfolioFilter {
+        account: account.as_deref(),
+        category: category.as_deref(),
+        asset_type: asset_type.as_ref(),
+        tag: tag.as_deref(),
+        net_worth,
+        realized_accounts: realized_accounts.as_ref(),
+    };
+    let Some(mut live) = load_live_portfolio(&filter, &config, use_testnet, pool).await? else {
+        println!("No accounts configured. Use 'cryptofolio account add' to create one.");
+        return Ok(());
+    };
+    let display_currency = display_currency(currency, &config, pool, opts.quiet).await?;
+    if let Some(display) = &display_currency {
+        for entry in &mut live.portfolio.entries {
+            entry.display_value = Some(entry.total_value() * display.rate);
+        }
+    }
+    let LivePortfolio {
+        portfolio,
+        price_map,
+        included_account_ids,
+        prices_fetched_at,
+        ..
+    } = &live;
+
+    if let Some(report) = gains_report {
+        let output = GainsOutputOptions {
+            realized: realized_only,
+            from,
+            to,
+            output_file: output_file.as_deref(),
+            append,
+            with_color: config.display.color && output_file.is_none(),
+        };
+        return show_gains(&report, portfolio, price_map, &output, opts);
+    }
+
+    if portfolio.entries.is_empty() {
+        println!("No holdings found.");
+        return Ok(());
+    }
+
+    if auto_snapshot {
+        let repo = PortfolioRepository::new(pool);
+        let today = Utc::now().date_naive();
+        let latest = repo.list_snapshots(Some(1), None, None).await?;
+        if latest.first().is_none_or(|s| s.taken_at.date_naive() != today) {
+            let id = repo.save_snapshot(portfolio, *prices_fetched_at).await?;
+            if !opts.quiet && !json && format != Some(OutputFormat::Csv) {
+                info(&format!("Saved portfolio snapshot #{}", id));
+            }
+        }
+    }
+
+    let investment = if total_invested {
+        let transactions = TransactionRepository::new(pool).list_chronological(None).await?;
+        let included = transactions.iter().filter(|tx| {
+            tx.to_account_id.as_ref().is_some_and(|id| included_account_ids.contains(id))
+        });
+        Some(InvestmentSummary::from_transactions(included, price_map, portfolio.total_value_usd))
+    } else {
+        None
+    };
+
+    let benchmark = match benchmark {
+        Some(asset) => {
+            let client = BinanceClient::new(
+                use_testnet,
+                config.binance.api_key.clone(),
+                config.binance.api_secret.clone(),
+            );
+            Some(compare_with_benchmark(&asset.to_uppercase(), from, &live, &client, pool).await?)
+        }
+        None => None,
+    };
+
+    let fees = if show_fees {
+        let transactions = TransactionRepository::new(pool).list_with_fees().await?;
+        Some(fees_by_position(&transactions, price_map))
+    } else {
+        None
+    };
+    let position_fees = |account_id: &str, asset: &str| -> Option<Decimal> {
+        fees.as_ref().map(|f| {
+            f.get(&(account_id.to_string(), asset.to_uppercase()))
+                .copied()
+                .unwrap_or(Decimal::ZERO)
+        })
+    };
+    let total_fees = fees.as_ref().map(|_| {
+        portfolio
+            .entries
+            .iter()
+            .flat_map(|e| e.holdings.iter().filter_map(|h| position_fees(&e.account_id, &h.holding.asset)))
+            .sum::<Decimal>()
+    });
+
+    // Lots can move between accounts, so match against the full history and keep what was shown
+    let lots = match cost_basis {
+        Some(method) => {
+            let mut report = RealizedGainRepository::new(pool).report(method, None, None).await?;
+            report.disposals.retain(|d| included_account_ids.contains(&d.account_id));
+            report.open_lots.retain(|l| included_account_ids.contains(&l.account_id));
+            Some(report)
+        }
+        None => None,
+    };
+    let lot_gains = lots
+        .as_ref()
+        .map(|report| (report.method, report.realized_gain(), report.unrealized_gain(price_map)));
+
+    // Files get plain text; terminal colors would end up as escape codes
+    let with_color = config.display.color && output_file.is_none();
+    let category_order = category_order(pool).await?;
+    let view = PortfolioView {
+        investment: investment.as_ref(),
+        benchmark: benchmark.as_ref(),
+        currency: display_currency.as_ref(),
+        total_fees,
+        position_fees: &position_fees,
+        lot_gains,
+        by_account,
+        by_category,
+        category_order: &category_order,
+        with_color,
+    };
+
+    if format == Some(OutputFormat::Csv) {
+        // Appending to a file that already has rows continues its table
+        let has_rows = append
+            && output_file
+                .as_deref()
+                .is_some_and(|f| std::fs::metadata(f).is_ok_and(|m| m.len() > 0));
+        let csv = render_portfolio_csv(portfolio, !has_rows)?;
+        return write_output(&csv, output_file.as_deref(), append, opts.quiet);
+    }
+
+    // JSON output
+    if json {
+        let output = build_portfolio_output(&live, &view, lots);
+        // Appended snapshots are written one per line so the file stays parseable as JSON Lines
+        let mut json = if append {
+            serde_json::to_string(&output)?
+        } else {
+            serde_json::to_string_pretty(&output)?
+        };
+        json.push('\n');
+        return write_output(&json, output_file.as_deref(), append, opts.quiet);
+    }
+
+    if use_testnet && !opts.quiet && output_file.is_none() {
+        warning("Testnet Mode");
+    }
+
+    let mut out = String::new();
+    without_color(output_file.is_some(), || render_portfolio(&mut out, portfolio, &view))
+        .map_err(|e| CryptofolioError::Other(e.to_string()))?;
+
+    write_output(&out, output_file.as_deref(), append, opts.quiet)
+}
+
+const PORTFOLIO_CSV_HEADERS: [&str; 9] = [
+    "account",
+    "category",
+    "asset",
+    "quantity",
+    "avg_cost",
+    "current_price",
+    "current_value",
+    "unrealized_pnl",
+    "unrealized_pnl_pct",
+];
+
+/// One position in `portfolio --format csv`; unknown values are left empty
+#[derive(Serialize)]
+struct PortfolioCsvRecord<'a> {
+    account: &'a str,
+    category: &'a str,
+    asset: &'a str,
+    quantity: String,
+    avg_cost: Option<String>,
+    current_price: Option<String>,
+    current_value: Option<String>,
+    unrealized_pnl: Option<String>,
+    unrealized_pnl_pct: Option<String>,
+}
+
+fn render_portfolio_csv(portfolio: &Portfolio, with_header: bool) -> Result<String> {
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+    if with_header {
+        writer.write_record(PORTFOLIO_CSV_HEADERS)?;
+    }
+
+    for entry in &portfolio.entries {
+        for h in &entry.holdings {
+            writer.serialize(PortfolioCsvRecord {
+                account: &entry.account_name,
+                category: &entry.category_name,
+                asset: &h.holding.asset,
+                quantity: h.holding.quantity.to_string(),
+                avg_cost: h.holding.avg_cost_basis.map(|c| c.to_string()),
+                current_price: h.current_price.map(|p| p.to_string()),
+                current_value: h.current_value.map(|v| v.to_string()),
+                unrealized_pnl: h.unrealized_pnl.map(|p| p.to_string()),
+                unrealized_pnl_pct: h.unrealized_pnl_percent.map(|p| p.round_dp(2).to_string()),
+            })?;
+        }
+    }
+
+    let bytes = writer.into_inner().map_err(|e| CryptofolioError::Other(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| CryptofolioError::Other(e.to_string()))
+}
+
+pub async fn handle_portfolio_subcommand(
+    command: PortfolioCommands,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    match command {
+        PortfolioCommands::Snapshot => save_snapshot(pool, opts).await,
+        PortfolioCommands::History {
+            limit,
+            from,
+            to,
+            snapshot,
+            by_account,
+            by_category,
+        } => match snapshot {
+            Some(id) => show_snapshot(id, by_account, by_category, pool, opts).await,
+            None => show_history(limit, from, to, pool, opts).await,
+        },
+        PortfolioCommands::Rebalance {
+            target,
+            threshold,
+            account,
+            dry_run,
+            yes,
+        } => rebalance(&target, &threshold, account, dry_run, yes, pool, opts).await,
+    }
+}
+
+/// Show the trades that bring the portfolio to `target`, then record them in
+/// `account` once confirmed
+async fn rebalance(
+    target: &str,
+    threshold: &str,
+    account: Option<String>,
+    dry_run: bool,
+    yes: bool,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    let targets = parse_targets(target)?;
+    let threshold = Decimal::from_str(threshold)
+        .ok()
+        .filter(|t| *t >= Decimal::ZERO)
+        .ok_or_else(|| CryptofolioError::InvalidAmount(threshold.to_string()))?;
+
+    let config = AppConfig::load()?;
+    let use_testnet = opts.testnet || config.general.use_testnet;
+
+    let Some(mut live) = load_live_portfolio(&PortfolioFilter::default(), &config, use_testnet, pool).await? else {
+        println!("No accounts configured. Use 'cryptofolio account add' to create one.");
+        return Ok(());
+    };
+
+    // Target assets not held yet still need a price to size their buys
+    let unpriced: Vec<String> = targets
+        .iter()
+        .filter(|t| !live.price_map.contains_key(&t.asset))
+        .map(|t| t.asset.clone())
+        .collect();
+    if !unpriced.is_empty() {
+        let client = BinanceClient::new(use_testnet, config.binance.api_key.clone(), config.binance.api_secret.clone());
+        let fetched = fetch_price_map(&client, &unpriced, pool).await;
+        live.price_map.extend(fetched.prices);
+        live.price_sources.extend(fetched.sources);
+    }
+
+    let plan = RebalancePlan::new(&live.portfolio.asset_totals(), &live.price_map, &targets, threshold);
+
+    if opts.json {
+        print_json(&plan)?;
+    } else {
+        print_rebalance_plan(&plan);
+    }
+
+    if plan.trades.is_empty() {
+        return Ok(());
+    }
+    if dry_run {
+        if !opts.json {
+            info("[DRY RUN] No trades recorded");
+        }
+        return Ok(());
+    }
+
+    // clap requires --account unless --dry-run
+    let account = account.unwrap_or_default();
+    let account_id = AccountRepository::new(pool).require_account(&account).await?.id;
+    check_rebalance_trades(&plan, &account, &account_id, pool).await?;
+
+    if !yes {
+        if opts.json {
+            return Err(CryptofolioError::InvalidInput("Use --yes to record the trades with --json".into()));
+        }
+        print!("Record these {} trades in '{}'? [y/N] ", plan.trades.len(), account);
+        std::io::Write::flush(&mut std::io::stdout())?;
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    // Sells first, so the buys are funded by what they raise
+    let mut trades: Vec<&RebalanceTrade> = plan.trades.iter().collect();
+    trades.sort_by_key(|t| t.side == TradeSide::Buy);
+
+    for trade in trades {
+        let (Some(quantity), Some(price)) = (trade.quantity, trade.price_usd) else {
+            continue;
+        };
+        let args = TradeArgs {
+            asset: trade.asset.clone(),
+            quantity: quantity.to_string(),
+            account: account.clone(),
+            price: price.to_string(),
+            fee: None,
+            fee_asset: None,
+            notes: Some("Rebalance".to_string()),
+            max_decimals: None,
+        };
+        let parsed = parse_trade(args, pool, opts).await?;
+        match trade.side {
+            TradeSide::Sell => record_sell(&parsed, pool).await?,
+            TradeSide::Buy => record_buy(&parsed, pool).await?,
+        };
+
+        if !opts.quiet && !opts.json {
+            success(&format!(
+                "Recorded {}: {} {} @ {}",
+                trade.side.display_name().to_lowercase(),
+                format_quantity(quantity),
+                trade.asset,
+                format_currency(price)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Refuse to record anything unless every trade is priced and `account`
+/// holds enough of each asset being sold
+async fn check_rebalance_trades(plan: &RebalancePlan, account: &str, account_id: &str, pool: &SqlitePool) -> Result<()> {
+    let holding_repo = HoldingRepository::new(pool);
+
+    for trade in &plan.trades {
+        let Some(quantity) = trade.quantity.filter(|_| trade.price_usd.is_some()) else {
+            return Err(CryptofolioError::InvalidInput(format!(
+                "No price for {}, so its trade can't be recorded. Use --dry-run to see the plan",
+                trade.asset
+            )));
+        };
+
+        if trade.side == TradeSide::Sell {
+            let held = holding_repo
+                .get(account_id, &trade.asset)
+                .await?
+                .map(|h| h.quantity)
+                .unwrap_or(Decimal::ZERO);
+            if held < quantity {
+                return Err(CryptofolioError::InvalidInput(format!(
+                    "'{}' holds {} {}, not the {} the plan sells. Move it there first or use --dry-run",
+                    account,
+                    format_quantity(held),
+                    trade.asset,
+                    format_quantity(quantity)
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_rebalance_plan(plan: &RebalancePlan) {
+    println!("Total value: {}", format_currency(plan.total_value_usd));
+    println!();
+
+    print_header(&[("Asset", 10), ("Value", 16), ("Current", 10), ("Target", 10), ("Drift", 10)]);
+    for a in &plan.allocations {
+        print_row(&[
+            (&a.asset, 10),
+            (&format_currency(a.value_usd), 16),
+            (&format_percent(a.current_pct), 10),
+            (&format_percent(a.target_pct), 10),
+            (&format_pnl_percent(a.drift(), false), 10),
+        ]);
+    }
+    println!();
+
+    if plan.total_value_usd.is_zero() {
+        warning("No holdings could be priced, so there is nothing to rebalance");
+        return;
+    }
+    if plan.trades.is_empty() {
+        success(&format!(
+            "Every asset is within {} of its target; no trades needed",
+            format_percent(plan.threshold_pct)
+        ));
+        return;
+    }
+
+    println!("{}", "Suggested trades".bold());
+    print_header(&[("Side", 6), ("Asset", 10), ("Quantity", 18), ("Amount", 16), ("Price", 14)]);
+    for t in &plan.trades {
+        let side = match t.side {
+            TradeSide::Buy => t.side.display_name().green(),
+            TradeSide::Sell => t.side.display_name().red(),
+        };
+        print_row(&[
+            (&side.to_string(), 6),
+            (&t.asset, 10),
+            (&t.quantity.map(format_quantity).unwrap_or_else(|| "-".to_string()), 18),
+            (&format!("~{}", format_currency(t.amount_usd)), 16),
+            (&t.price_usd.map(format_currency).unwrap_or_else(|| "-".to_string()), 14),
+        ]);
+    }
+}
+
+async fn save_snapshot(pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let config = AppConfig::load()?;
+    let use_testnet = opts.testnet || config.general.use_testnet;
+
+    let Some(live) = load_live_portfolio(&PortfolioFilter::default(), &config, use_testnet, pool).await? else {
+        println!("No accounts configured. Use 'cryptofolio account add' to create one.");
+        return Ok(());
+    };
+
+    let id = PortfolioRepository::new(pool)
+        .save_snapshot(&live.portfolio, live.prices_fetched_at)
+        .await?;
+
+    if opts.json {
+        return print_json(&SnapshotOutput::new(&PortfolioSnapshot {
+            id,
+            total_value_usd: live.portfolio.total_value_usd,
+            total_cost_basis: live.portfolio.total_cost_basis,
+            taken_at: live.prices_fetched_at,
+        }));
+    }
+
+    if !opts.quiet {
+        success(&format!(
+            "Saved portfolio snapshot #{} ({})",
+            id,
+            format_currency(live.portfolio.total_value_usd)
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct SnapshotOutput {
+    id: i64,
+    taken_at: String,
+    total_value_usd: String,
+    total_cost_basis: String,
+}
+
+impl SnapshotOutput {
+    fn new(snapshot: &PortfolioSnapshot) -> Self {
+        Self {
+            id: snapshot.id,
+            taken_at: snapshot.taken_at.to_rfc3339(),
+            total_value_usd: snapshot.total_value_usd.to_string(),
+            total_cost_basis: snapshot.total_cost_basis.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct PeriodReturnOutput {
+    period: String,
+    start_value: String,
+    end_value: String,
+    change: String,
+    change_percent: Option<String>,
+}
+
+#[derive(Serialize)]
+struct HistoryOutput {
+    returns: Vec<PeriodReturnOutput>,
+    snapshots: Vec<SnapshotOutput>,
+}
+
+async fn show_history(
+    limit: i64,
+    from: Option<String>,
+    to: Option<String>,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    let from = from.as_deref().map(parse_date_filter).transpose()?;
+    let to = to.as_deref().map(parse_date_filter).transpose()?;
+
+    // Returns look back up to a year, so they use every snapshot in range rather than only those listed
+    let mut snapshots = PortfolioRepository::new(pool).list_snapshots(None, from, to).await?;
+    let returns = period_returns(&snapshots);
+    snapshots.truncate(limit.max(0) as usize);
+
+    if opts.json {
+        let output = HistoryOutput {
+            returns: returns
+                .iter()
+                .map(|r| PeriodReturnOutput {
+                    period: r.period.to_string(),
+                    start_value: r.start_value.to_string(),
+                    end_value: r.end_value.to_string(),
+                    change: r.change.to_string(),
+                    change_percent: r.change_percent.map(|p| p.to_string()),
+                })
+                .collect(),
+            snapshots: snapshots.iter().map(SnapshotOutput::new).collect(),
+        };
+        return print_json(&output);
+    }
+
+    if snapshots.is_empty() {
+        println!("No portfolio snapshots saved.");
+        println!("Use 'cryptofolio portfolio snapshot' or 'cryptofolio portfolio --auto-snapshot' to save one.");
+        return Ok(());
+    }
+
+    let with_color = AppConfig::load()?.display.color;
+
+    if !returns.is_empty() {
+        println!();
+        println!("{}", "RETURNS".bold());
+        for r in &returns {
+            println!("  {}", format_period_return(r, with_color));
+        }
+    }
+
+    println!();
+    print_header(&[("ID", 6), ("Taken", 20), ("Value", 16), ("Cost Basis", 16), ("P&L", 16)]);
+    for snapshot in &snapshots {
+        print_row(&[
+            (&snapshot.id.to_string(), 6),
+            (&snapshot.taken_at.format("%Y-%m-%d %H:%M:%S").to_string(), 20),
+            (&format_currency(snapshot.total_value_usd), 16),
+            (&format_currency(snapshot.total_cost_basis), 16),
+            (&format_pnl(snapshot.total_value_usd - snapshot.total_cost_basis, with_color), 16),
+        ]);
+    }
+    println!();
+
+    Ok(())
+}
+
+fn format_period_return(r: &PeriodReturn, with_color: bool) -> String {
+    let percent = r
+        .change_percent
+        .map(|p| format!(" ({})", format_pnl_percent(p, with_color)))
+        .unwrap_or_default();
+    format!(
+        "{:4} {}{}  [{} -> {}]",
+        r.period,
+        format_pnl(r.change, with_color),
+        percent,
+        format_currency(r.start_value),
+        format_currency(r.end_value)
+    )
+}
+
+/// Render a saved snapshot with the regular portfolio view
+async fn show_snapshot(
+    id: i64,
     by_account: bool,
     by_category: bool,
-    account: Option<String>,
-    category: Option<String>,
     pool: &SqlitePool,
     opts: &GlobalOptions,
 ) -> Result<()> {
+    let repo = PortfolioRepository::new(pool);
+    let snapshot = repo
+        .get_snapshot(id)
+        .await?
+        .ok_or_else(|| CryptofolioError::NotFound(format!("Portfolio snapshot #{}", id)))?;
+    let portfolio = Portfolio::from_entries(repo.list_snapshot_entries(id).await?);
+
+    if opts.json {
+        return print_json(&portfolio);
+    }
+
+    if !opts.quiet {
+        info(&format!("Snapshot #{} taken {}", id, snapshot.taken_at.format("%Y-%m-%d %H:%M:%S UTC")));
+    }
+
+    let category_order = category_order(pool).await?;
+    let mut out = String::new();
+    let view = PortfolioView {
+        investment: None,
+        benchmark: None,
+        currency: None,
+        total_fees: None,
+        position_fees: &|_, _| None,
+        lot_gains: None,
+        by_account,
+        by_category,
+        category_order: &category_order,
+        with_color: AppConfig::load()?.display.color,
+    };
+    render_portfolio(&mut out, &portfolio, &view).map_err(|e| CryptofolioError::Other(e.to_string()))?;
+    print!("{}", out);
+
+    Ok(())
+}
+
+/// The `--json` portfolio output, optionally filtered to one account or category.
+/// Returns None when no accounts exist.
+pub(crate) async fn portfolio_output(
+    account: Option<&str>,
+    category: Option<&str>,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<Option<PortfolioOutput>> {
     let config = AppConfig::load()?;
     let use_testnet = opts.testnet || config.general.use_testnet;
+
+    let filter = PortfolioFilter {
+        account,
+        category,
+        ..Default::default()
+    };
+    let Some(live) = load_live_portfolio(&filter, &config, use_testnet, pool).await? else {
+        return Ok(None);
+    };
+
+    let view = PortfolioView {
+        investment: None,
+        benchmark: None,
+        currency: None,
+        total_fees: None,
+        position_fees: &|_, _| None,
+        lot_gains: None,
+        by_account: false,
+        by_category: false,
+        category_order: &[],
+        with_color: false,
+    };
+    Ok(Some(build_portfolio_output(&live, &view, None)))
+}
+
+/// Compare the shown portfolio with holding `asset` over the same period.
+///
+/// Without `from` the period starts at the first transaction and the portfolio
+/// return is its value against everything invested. With `from` it starts at
+/// the first snapshot taken on or after that date, so money added since then
+/// counts as return.
+async fn compare_with_benchmark(
+    asset: &str,
+    from: Option<DateTime<Utc>>,
+    live: &LivePortfolio,
+    client: &BinanceClient,
+    pool: &SqlitePool,
+) -> Result<BenchmarkComparison> {
+    let current_value = live.portfolio.total_value_usd;
+
+    let (since, portfolio_return_percent) = match from {
+        Some(from) => {
+            let snapshots = PortfolioRepository::new(pool).list_snapshots(None, Some(from), None).await?;
+            // Newest first, so the last is the start of the period
+            let start = snapshots.last().ok_or_else(|| {
+                CryptofolioError::InvalidInput(format!(
+                    "No portfolio snapshot on or after {}. Take one with 'cryptofolio portfolio snapshot'",
+                    from.format("%Y-%m-%d")
+                ))
+            })?;
+            let change = if start.total_value_usd > Decimal::ZERO {
+                (current_value - start.total_value_usd) / start.total_value_usd * Decimal::from(100)
+            } else {
+                Decimal::ZERO
+            };
+            (start.taken_at, change)
+        }
+        None => {
+            let transactions = TransactionRepository::new(pool).list_chronological(None).await?;
+            let included: Vec<&Transaction> = transactions
+                .iter()
+                .filter(|tx| {
+                    [&tx.from_account_id, &tx.to_account_id]
+                        .into_iter()
+                        .any(|id| id.as_ref().is_some_and(|id| live.included_account_ids.contains(id)))
+                })
+                .collect();
+            let first = included.first().ok_or_else(|| {
+                CryptofolioError::InvalidInput("No transactions recorded; use --from to pick a start date".to_string())
+            })?;
+            let summary = InvestmentSummary::from_transactions(included.iter().copied(), &live.price_map, current_value);
+            (first.timestamp, summary.total_return_percent())
+        }
+    };
+
+    let benchmark_start_price = client.get_historical_price(asset, since).await?;
+    let benchmark_price = match live.price_map.get(asset) {
+        Some(price) => *price,
+        None => fetch_price_map(client, &[asset.to_string()], pool)
+            .await
+            .prices
+            .remove(asset)
+            .ok_or_else(|| CryptofolioError::NotFound(format!("{} price", asset)))?,
+    };
+
+    Ok(BenchmarkComparison {
+        asset: asset.to_string(),
+        since,
+        portfolio_return_percent,
+        benchmark_start_price,
+        benchmark_price,
+    })
+}
+
+/// Category ids in their configured display order
+async fn category_order(pool: &SqlitePool) -> Result<Vec<String>> {
+    let categories = AccountRepository::new(pool).list_categories().await?;
+    Ok(categories.into_iter().map(|c| c.id).collect())
+}
+
+/// Which accounts and holdings to value
+#[derive(Default)]
+struct PortfolioFilter<'a> {
+    account: Option<&'a str>,
+    category: Option<&'a str>,
+    asset_type: Option<&'a AssetType>,
+    tag: Option<&'a str>,
+    /// Include bank accounts, valuing fiat balances with stored exchange rates
+    net_worth: bool,
+    /// Keep these accounts even when they hold nothing, marked as closed
+    realized_accounts: Option<&'a HashSet<String>>,
+}
+
+/// Holdings valued at current prices
+struct LivePortfolio {
+    portfolio: Portfolio,
+    price_map: HashMap<String, Decimal>,
+    /// Where each price in `price_map` came from
+    price_sources: HashMap<String, PriceSource>,
+    included_account_ids: HashSet<String>,
+    prices_fetched_at: DateTime<Utc>,
+}
+
+/// Value the filtered holdings at live prices. Returns None when no accounts exist.
+async fn load_live_portfolio(
+    filter: &PortfolioFilter<'_>,
+    config: &AppConfig,
+    use_testnet: bool,
+    pool: &SqlitePool,
+) -> Result<Option<LivePortfolio>> {
     let account_repo = AccountRepository::new(pool);
     let holding_repo = HoldingRepository::new(pool);
 
     // Fetch all accounts and holdings
-    let accounts = account_repo.list_accounts().await?;
+    let accounts = account_repo.list_accounts(&ListAccountsFilter::default()).await?;
     let categories = account_repo.list_categories().await?;
 
     if accounts.is_empty() {
-        println!("No accounts configured. Use 'cryptofolio account add' to create one.");
-        return Ok(());
+        return Ok(None);
     }
 
     // Create category lookup
@@ -73,7 +1028,7 @@ pub async fn handle_portfolio_command(
     let unique_assets: Vec<String> = all_holdings
         .iter()
         .map(|h| h.asset.clone())
-        .collect::<std::collections::HashSet<_>>()
+        .collect::<HashSet<_>>()
         .into_iter()
         .collect();
 
@@ -84,49 +1039,69 @@ pub async fn handle_portfolio_command(
         config.binance.api_secret.clone(),
     );
 
-    let asset_refs: Vec<&str> = unique_assets.iter().map(|s| s.as_str()).collect();
-    let prices = client.get_prices(&asset_refs).await.unwrap_or_default();
+    let PriceMap { prices: mut price_map, sources: mut price_sources } =
+        fetch_price_map(&client, &unique_assets, pool).await;
 
-    let mut price_map: HashMap<String, Decimal> = prices
-        .into_iter()
-        .map(|p| (p.symbol.to_uppercase(), p.price))
-        .collect();
-
-    // Find assets without prices and try Binance Alpha API
-    let missing_assets: Vec<&str> = unique_assets
-        .iter()
-        .filter(|a| !price_map.contains_key(&a.to_uppercase()))
-        .map(|s| s.as_str())
-        .collect();
-
-    if !missing_assets.is_empty() {
-        let alpha_client = BinanceAlphaClient::new();
-        if let Ok(alpha_prices) = alpha_client.get_prices(&missing_assets).await {
-            for (symbol, price) in alpha_prices {
-                price_map.insert(symbol, price);
+    // Net worth counts cash: value fiat balances with the stored exchange rates
+    if filter.net_worth {
+        for asset in &unique_assets {
+            let asset = asset.to_uppercase();
+            if price_map.contains_key(&asset) {
+                continue;
+            }
+            let (price, source) = if is_usd_equivalent(&asset) {
+                (Some(Decimal::ONE), PriceSource::UsdPeg)
+            } else {
+                (currencies::get_usd_price(pool, &asset).await?, PriceSource::ExchangeRate)
+            };
+            if let Some(price) = price {
+                price_sources.insert(asset.clone(), source);
+                price_map.insert(asset, price);
             }
         }
     }
+    // Prices are fetched live on every run, so this is when they were retrieved
+    let prices_fetched_at = Utc::now();
+
+    let tagged_ids = match filter.tag {
+        Some(tag) => Some(HoldingTagRepository::new(pool).holding_ids_with_tag(&tag.trim().to_lowercase()).await?),
+        None => None,
+    };
 
     // Build portfolio entries
     let mut entries: Vec<PortfolioEntry> = Vec::new();
+    let mut included_account_ids: HashSet<String> = HashSet::new();
 
     for acc in &accounts {
+        // Bank accounts hold cash, which only counts toward net worth
+        let is_cash = acc.account_type == AccountType::Bank;
+        if is_cash && !filter.net_worth {
+            continue;
+        }
+
         // Apply filters
-        if let Some(ref filter_account) = account {
+        if let Some(filter_account) = filter.account {
             if acc.name.to_lowercase() != filter_account.to_lowercase() {
                 continue;
             }
         }
 
-        if let Some(ref filter_category) = category {
+        if let Some(filter_category) = filter.category {
             let cat_name = category_map.get(&acc.category_id).cloned().unwrap_or_default();
             if cat_name.to_lowercase() != filter_category.to_lowercase() {
                 continue;
             }
         }
 
-        let holdings = holding_repo.list_by_account(&acc.id).await?;
+        included_account_ids.insert(acc.id.clone());
+
+        let mut holdings = match filter.asset_type {
+            Some(asset_type) => holding_repo.list_by_account_and_asset_type(&acc.id, asset_type).await?,
+            None => holding_repo.list_by_account(&acc.id).await?,
+        };
+        if let Some(ids) = &tagged_ids {
+            holdings.retain(|h| ids.contains(&h.id));
+        }
         let holdings_with_price: Vec<HoldingWithPrice> = holdings
             .into_iter()
             .map(|h| {
@@ -135,184 +1110,737 @@ pub async fn handle_portfolio_command(
             })
             .collect();
 
-        if !holdings_with_price.is_empty() {
+        let is_closed = holdings_with_price.is_empty();
+        if !is_closed || filter.realized_accounts.is_some_and(|ids| ids.contains(&acc.id)) {
+            let (category_id, category_name) = if is_cash {
+                (CASH_CATEGORY_ID.to_string(), "Cash".to_string())
+            } else {
+                (
+                    acc.category_id.clone(),
+                    category_map.get(&acc.category_id).cloned().unwrap_or_else(|| "-".to_string()),
+                )
+            };
             entries.push(PortfolioEntry {
                 account_id: acc.id.clone(),
                 account_name: acc.name.clone(),
-                category_id: acc.category_id.clone(),
-                category_name: category_map.get(&acc.category_id).cloned().unwrap_or_else(|| "-".to_string()),
+                category_id,
+                category_name,
                 holdings: holdings_with_price,
+                snapshot_id: None,
+                is_closed,
+                display_value: None,
             });
         }
     }
 
-    let portfolio = Portfolio::from_entries(entries);
+    Ok(Some(LivePortfolio {
+        portfolio: Portfolio::from_entries(entries),
+        price_map,
+        price_sources,
+        included_account_ids,
+        prices_fetched_at,
+    }))
+}
 
-    if portfolio.entries.is_empty() {
-        println!("No holdings found.");
-        return Ok(());
+/// Where a price came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum PriceSource {
+    Binance,
+    BinanceAlpha,
+    CoinGecko,
+    /// Fiat valued from the stored exchange rates
+    ExchangeRate,
+    /// Stablecoin counted at $1
+    UsdPeg,
+}
+
+impl PriceSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceSource::Binance => "binance",
+            PriceSource::BinanceAlpha => "binance_alpha",
+            PriceSource::CoinGecko => "coingecko",
+            PriceSource::ExchangeRate => "exchange_rate",
+            PriceSource::UsdPeg => "usd_peg",
+        }
     }
+}
 
-    // JSON output
-    if opts.json {
-        let output = PortfolioOutput {
-            total_value_usd: portfolio.total_value_usd.to_string(),
-            total_cost_basis: portfolio.total_cost_basis.to_string(),
-            unrealized_pnl: portfolio.unrealized_pnl.to_string(),
-            unrealized_pnl_percent: portfolio.unrealized_pnl_percent.to_string(),
-            entries: portfolio.entries.iter().map(|e| PortfolioEntryOutput {
+/// USD prices keyed by uppercase asset, with the source of each
+#[derive(Debug, Default)]
+pub(crate) struct PriceMap {
+    pub prices: HashMap<String, Decimal>,
+    pub sources: HashMap<String, PriceSource>,
+}
+
+impl PriceMap {
+    pub fn insert(&mut self, asset: String, price: Decimal, source: PriceSource) {
+        self.sources.insert(asset.clone(), source);
+        self.prices.insert(asset, price);
+    }
+
+    pub fn contains(&self, asset: &str) -> bool {
+        self.prices.contains_key(asset)
+    }
+}
+
+/// Fetch current USD prices for `assets`, falling back to Binance Alpha and
+/// then CoinGecko for tokens not listed on the main exchange. Assets without
+/// a price are omitted.
+pub(crate) async fn fetch_price_map(
+    client: &BinanceClient,
+    assets: &[String],
+    pool: &SqlitePool,
+) -> PriceMap {
+    let asset_refs: Vec<&str> = assets.iter().map(|s| s.as_str()).collect();
+    let prices = Exchange::get_prices(client, &asset_refs).await.unwrap_or_default();
+
+    let mut price_map = PriceMap::default();
+    for p in prices {
+        price_map.insert(p.symbol.to_uppercase(), p.price, PriceSource::Binance);
+    }
+
+    // Find assets without prices and try Binance Alpha API
+    let missing_assets: Vec<&str> = assets
+        .iter()
+        .filter(|a| !price_map.contains(&a.to_uppercase()))
+        .map(|s| s.as_str())
+        .collect();
+
+    if !missing_assets.is_empty() {
+        let alpha_client = BinanceAlphaClient::new();
+        if let Ok(alpha_prices) = alpha_client.get_prices(&missing_assets).await {
+            for (symbol, price) in alpha_prices {
+                price_map.insert(symbol, price, PriceSource::BinanceAlpha);
+            }
+        }
+    }
+
+    // Fiat balances are valued from exchange rates, never from a token sharing their code
+    let mut missing_assets: Vec<&str> = Vec::new();
+    for asset in assets {
+        if price_map.contains(&asset.to_uppercase()) || is_usd_equivalent(asset) {
+            continue;
+        }
+        let is_fiat = matches!(currencies::get_currency(pool, asset).await, Ok(Some(c)) if c.is_fiat());
+        if !is_fiat {
+            missing_assets.push(asset);
+        }
+    }
+
+    if !missing_assets.is_empty() {
+        let coingecko = CoinGeckoClient::new(pool.clone());
+        if let Ok(prices) = MarketDataProvider::get_prices(&coingecko, &missing_assets).await {
+            for p in prices {
+                price_map.insert(p.symbol, p.price, PriceSource::CoinGecko);
+            }
+        }
+    }
+
+    price_map
+}
+
+/// The `--json` shape of a valued portfolio, with the extras `view` enables
+fn build_portfolio_output(live: &LivePortfolio, view: &PortfolioView, lots: Option<LotReport>) -> PortfolioOutput {
+    PortfolioOutput {
+        generated_at: Utc::now().to_rfc3339(),
+        price_source: price_source_summary(&live.price_sources),
+        prices_fetched_at: live.prices_fetched_at.to_rfc3339(),
+        total_value_usd: live.portfolio.total_value_usd.to_string(),
+        currency: view.currency.map(|c| c.code.clone()),
+        total_value: view.currency.map(|c| (live.portfolio.total_value_usd * c.rate).round_dp(2).to_string()),
+        total_cost_basis: live.portfolio.total_cost_basis.to_string(),
+        unrealized_pnl: live.portfolio.unrealized_pnl.to_string(),
+        unrealized_pnl_percent: live.portfolio.unrealized_pnl_percent.to_string(),
+        total_invested: view.investment.map(|i| i.total_invested.to_string()),
+        total_return: view.investment.map(|i| i.total_return().to_string()),
+        total_return_percent: view.investment.map(|i| i.total_return_percent().to_string()),
+        total_fees: view.total_fees.map(|f| f.to_string()),
+        benchmark: view.benchmark.map(|b| BenchmarkOutput {
+            asset: b.asset.clone(),
+            since: b.since.to_rfc3339(),
+            portfolio_return_percent: b.portfolio_return_percent.round_dp(2).to_string(),
+            benchmark_return_percent: b.benchmark_return_percent().round_dp(2).to_string(),
+            alpha: b.alpha().round_dp(2).to_string(),
+            benchmark_start_price: b.benchmark_start_price.to_string(),
+            benchmark_price: b.benchmark_price.to_string(),
+        }),
+        cost_basis: lots.map(|report| CostBasisOutput {
+            method: report.method.as_str().to_string(),
+            realized_gain: report.realized_gain().to_string(),
+            unrealized_gain: report.unrealized_gain(&live.price_map).to_string(),
+            disposals: report.disposals,
+            open_lots: report.open_lots.into_iter().map(|lot| {
+                let price = live.price_map.get(&lot.asset).copied();
+                OpenLotOutput {
+                    current_price: price.map(|p| p.to_string()),
+                    unrealized_gain: price.map(|p| lot.unrealized_gain(p).to_string()),
+                    lot,
+                }
+            }).collect(),
+        }),
+        entries: live.portfolio.entries.iter().map(|e| {
+            let group_value = group_value(&live.portfolio, e, view.by_account, view.by_category);
+            PortfolioEntryOutput {
                 account_name: e.account_name.clone(),
                 category_name: e.category_name.clone(),
+                allocation_pct: live.portfolio.allocation_pct(e.total_value()),
+                group_allocation_pct: group_value.map(|g| allocation_pct(e.total_value(), g)),
+                display_value: e.display_value.map(|v| v.round_dp(2).to_string()),
                 holdings: e.holdings.iter().map(|h| HoldingOutput {
                     asset: h.holding.asset.clone(),
                     quantity: h.holding.quantity.to_string(),
                     current_price: h.current_price.map(|p| p.to_string()),
+                    price_source: live
+                        .price_sources
+                        .get(&h.holding.asset.to_uppercase())
+                        .map(|s| s.as_str().to_string()),
                     current_value: h.current_value.map(|v| v.to_string()),
                     cost_basis: h.holding.avg_cost_basis.map(|c| c.to_string()),
                     unrealized_pnl: h.unrealized_pnl.map(|p| p.to_string()),
                     unrealized_pnl_percent: h.unrealized_pnl_percent.map(|p| p.to_string()),
+                    allocation_pct: live.portfolio.allocation_pct(holding_value(h)),
+                    group_allocation_pct: group_value.map(|g| allocation_pct(holding_value(h), g)),
+                    fees_usd: (view.position_fees)(&e.account_id, &h.holding.asset).map(|f| f.to_string()),
                 }).collect(),
-            }).collect(),
+            }
+        }).collect(),
+    }
+}
+
+/// The distinct sources in `sources`, comma-separated in a stable order
+fn price_source_summary(sources: &HashMap<String, PriceSource>) -> String {
+    let mut used: Vec<PriceSource> = sources.values().copied().collect::<HashSet<_>>().into_iter().collect();
+    used.sort();
+    used.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(",")
+}
+
+/// What to include when rendering the portfolio as text
+struct PortfolioView<'a> {
+    investment: Option<&'a InvestmentSummary>,
+    benchmark: Option<&'a BenchmarkComparison>,
+    /// Set when fees are shown
+    total_fees: Option<Decimal>,
+    position_fees: &'a dyn Fn(&str, &str) -> Option<Decimal>,
+    /// Realized and unrealized gains from lot matching, when a method was chosen
+    lot_gains: Option<(CostBasisMethod, Decimal, Decimal)>,
+    by_account: bool,
+    by_category: bool,
+    /// Category ids in display order
+    category_order: &'a [String],
+    with_color: bool,
+    /// Currency amounts are shown in; None for USD
+    currency: Option<&'a DisplayCurrency>,
+}
+
+impl PortfolioView<'_> {
+    /// A USD amount in the display currency
+    fn money(&self, usd: Decimal) -> String {
+        match self.currency {
+            Some(currency) => number_format().currency_with_symbol(usd * currency.rate, &currency.symbol),
+            None => format_currency(usd),
+        }
+    }
+
+    /// A USD gain or loss in the display currency, signed and colored like `format_pnl`
+    fn pnl(&self, usd: Decimal) -> String {
+        let Some(currency) = self.currency else {
+            return format_pnl(usd, self.with_color);
+        };
+        let formatted = if usd >= Decimal::ZERO {
+            format!("+{}", self.money(usd))
+        } else {
+            self.money(usd)
         };
-        println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+        if !self.with_color || !colors_enabled() {
+            return formatted;
+        }
+        match (usd * currency.rate).cmp(&Decimal::ZERO) {
+            std::cmp::Ordering::Greater => formatted.green().to_string(),
+            std::cmp::Ordering::Less => formatted.red().to_string(),
+            std::cmp::Ordering::Equal => formatted,
+        }
+    }
+}
+
+/// Currency chosen with `--currency` or `general.currency`
+struct DisplayCurrency {
+    code: String,
+    symbol: String,
+    /// Units of the currency per 1 USD
+    rate: Decimal,
+}
+
+/// The currency to show values in, None for USD. A code passed with
+/// `--currency` must be convertible; the configured default falls back to USD.
+async fn display_currency(
+    code: Option<String>,
+    config: &AppConfig,
+    pool: &SqlitePool,
+    quiet: bool,
+) -> Result<Option<DisplayCurrency>> {
+    let explicit = code.is_some();
+    let code = code.unwrap_or_else(|| config.general.currency.clone()).to_uppercase();
+    if is_usd_equivalent(&code) {
+        return Ok(None);
+    }
+
+    let resolved = async {
+        let currency = currencies::get_currency(pool, &code)
+            .await?
+            .ok_or_else(|| CryptofolioError::NotFound(format!("Currency {}", code)))?;
+        let rate = CurrencyConverter::convert(Decimal::ONE, "USD", &code, pool).await?;
+        Ok::<_, CryptofolioError>(DisplayCurrency {
+            code: currency.code,
+            symbol: currency.symbol,
+            rate,
+        })
+    }
+    .await;
+
+    match resolved {
+        Ok(currency) => Ok(Some(currency)),
+        Err(e) if !explicit => {
+            if !quiet {
+                warning(&format!("Showing values in USD ({})", e));
+            }
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Where and how to write `--realized-only` and `--unrealized-only` output
+struct GainsOutputOptions<'a> {
+    realized: bool,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    output_file: Option<&'a str>,
+    append: bool,
+    with_color: bool,
+}
+
+/// Show realized or unrealized gains per position of the accounts the filters kept
+fn show_gains(
+    report: &LotReport,
+    portfolio: &Portfolio,
+    price_map: &HashMap<String, Decimal>,
+    output: &GainsOutputOptions,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    let positions = if output.realized {
+        report.realized_by_position()
+    } else {
+        report.unrealized_by_position(price_map)
+    };
+
+    // Open lots only count for holdings still shown; sales count for every kept account
+    let mut rows: Vec<(&PortfolioEntry, &PositionGain, bool)> = Vec::new();
+    for entry in &portfolio.entries {
+        for position in positions.iter().filter(|p| p.account_id == entry.account_id) {
+            let held = entry.holdings.iter().any(|h| h.holding.asset.eq_ignore_ascii_case(&position.asset));
+            if held || output.realized {
+                rows.push((entry, position, entry.is_closed || !held));
+            }
+        }
+    }
+    let total_gain: Decimal = rows.iter().filter_map(|(_, p, _)| p.gain).sum();
+    let kind = if output.realized { "realized" } else { "unrealized" };
+
+    if opts.json {
+        let json_output = GainsOutput {
+            generated_at: Utc::now().to_rfc3339(),
+            kind: kind.to_string(),
+            method: report.method.as_str().to_string(),
+            from: output.from.map(|d| d.to_rfc3339()),
+            to: output.to.map(|d| d.to_rfc3339()),
+            total_gain: total_gain.to_string(),
+            positions: rows
+                .iter()
+                .map(|(entry, p, is_closed)| PositionGainOutput {
+                    account_name: entry.account_name.clone(),
+                    category_name: entry.category_name.clone(),
+                    asset: p.asset.clone(),
+                    quantity: p.quantity.to_string(),
+                    cost_basis: p.cost_basis.to_string(),
+                    value: p.value.map(|v| v.to_string()),
+                    gain: p.gain.map(|g| g.to_string()),
+                    is_closed: *is_closed,
+                })
+                .collect(),
+        };
+        let mut json = if output.append {
+            serde_json::to_string(&json_output)?
+        } else {
+            serde_json::to_string_pretty(&json_output)?
+        };
+        json.push('\n');
+        return write_output(&json, output.output_file, output.append, opts.quiet);
+    }
+
+    let mut out = String::new();
+    without_color(output.output_file.is_some(), || {
+        render_gains(&mut out, &rows, total_gain, report.method, output)
+    })
+    .map_err(|e| CryptofolioError::Other(e.to_string()))?;
+
+    write_output(&out, output.output_file, output.append, opts.quiet)
+}
+
+/// Render per-position gains as text
+fn render_gains(
+    out: &mut String,
+    rows: &[(&PortfolioEntry, &PositionGain, bool)],
+    total_gain: Decimal,
+    method: CostBasisMethod,
+    output: &GainsOutputOptions,
+) -> std::fmt::Result {
+    let with_color = output.with_color;
+    let (title, value_label) = if output.realized {
+        ("REALIZED GAINS", "Proceeds")
+    } else {
+        ("UNREALIZED GAINS", "Value")
+    };
+
+    writeln!(out)?;
+    writeln!(out, "{} ({})", title.bold(), method.as_str().to_uppercase())?;
+    writeln!(out, "{}", "=".repeat(70))?;
+    writeln!(out)?;
+
+    if output.from.is_some() || output.to.is_some() {
+        let date = |d: Option<DateTime<Utc>>| d.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "...".to_string());
+        writeln!(out, "  Period:          {} to {}", date(output.from), date(output.to))?;
+    }
+    writeln!(out, "  Total:           {}", format_pnl(total_gain, with_color))?;
+    writeln!(out)?;
+
+    if rows.is_empty() {
+        writeln!(out, "  No {} gains found.", if output.realized { "realized" } else { "unrealized" })?;
+        writeln!(out)?;
         return Ok(());
     }
 
-    // Print header
-    println!();
-    if use_testnet && !opts.quiet {
-        warning("Testnet Mode");
+    writeln!(out, "{}", "-".repeat(84))?;
+    writeln!(
+        out,
+        "  {:8}  {:>12}  {:>12}  {:>12}  {:>15}  {:>8}",
+        "Asset", "Quantity", "Cost Basis", value_label, "Gain", "Status"
+    )?;
+    writeln!(out, "{}", "-".repeat(84))?;
+
+    let mut current_account = None;
+    for (entry, position, is_closed) in rows {
+        if current_account != Some(&entry.account_id) {
+            writeln!(out, "  {}", entry.account_name.dimmed())?;
+            current_account = Some(&entry.account_id);
+        }
+
+        writeln!(
+            out,
+            "  {:8}  {:>12}  {:>12}  {:>12}  {:>15}  {:>8}",
+            position.asset,
+            format_quantity(position.quantity),
+            format_currency(position.cost_basis),
+            position.value.map(format_currency).unwrap_or_else(|| "-".to_string()),
+            position.gain.map(|g| format_pnl(g, with_color)).unwrap_or_else(|| "-".to_string()),
+            if *is_closed { "closed" } else { "open" }
+        )?;
     }
 
-    println!("{}", "PORTFOLIO OVERVIEW".bold());
-    println!("{}", "=".repeat(70));
-    println!();
+    writeln!(out, "{}", "-".repeat(84))?;
+    writeln!(out)?;
 
-    println!("  Total Value:     {}", format_usd(portfolio.total_value_usd).bold());
-    println!("  Cost Basis:      {}", format_usd(portfolio.total_cost_basis));
-    println!(
+    Ok(())
+}
+
+/// Render the portfolio overview as text
+fn render_portfolio(out: &mut String, portfolio: &Portfolio, view: &PortfolioView) -> std::fmt::Result {
+    let with_color = view.with_color;
+
+    writeln!(out)?;
+    writeln!(out, "{}", "PORTFOLIO OVERVIEW".bold())?;
+    writeln!(out, "{}", "=".repeat(70))?;
+    writeln!(out)?;
+
+    writeln!(out, "  Total Value:     {}", view.money(portfolio.total_value_usd).bold())?;
+    writeln!(out, "  Cost Basis:      {}", view.money(portfolio.total_cost_basis))?;
+    if let Some(currency) = view.currency {
+        writeln!(out, "  Exchange Rate:   {} {} per USD", currency.rate.round_dp(4), currency.code)?;
+    }
+    writeln!(
+        out,
         "  Unrealized P&L:  {} ({})",
-        format_pnl(portfolio.unrealized_pnl, config.display.color),
-        format_pnl_percent(portfolio.unrealized_pnl_percent, config.display.color)
-    );
-    println!();
+        view.pnl(portfolio.unrealized_pnl),
+        format_pnl_percent(portfolio.unrealized_pnl_percent, with_color)
+    )?;
+    if let Some(investment) = view.investment {
+        writeln!(out, "  Total Invested:  {}", view.money(investment.total_invested))?;
+        writeln!(
+            out,
+            "  Total Return:    {} ({})",
+            view.pnl(investment.total_return()),
+            format_pnl_percent(investment.total_return_percent(), with_color)
+        )?;
+    }
+    if let Some(total_fees) = view.total_fees {
+        writeln!(out, "  Fees Paid:       {}", view.money(total_fees))?;
+    }
+    if let Some((method, realized, unrealized)) = view.lot_gains {
+        let method = method.as_str().to_uppercase();
+        writeln!(out, "  Realized P&L:    {} ({})", view.pnl(realized), method)?;
+        writeln!(out, "  Lot Unrealized:  {} ({})", view.pnl(unrealized), method)?;
+    }
+    writeln!(out)?;
 
-    if by_category {
+    if let Some(benchmark) = view.benchmark {
+        writeln!(out, "  {} (since {})", "BENCHMARK".bold(), benchmark.since.format("%Y-%m-%d"))?;
+        writeln!(
+            out,
+            "    Portfolio Return:  {}",
+            format_pnl_percent(benchmark.portfolio_return_percent, with_color)
+        )?;
+        writeln!(
+            out,
+            "    {:<18} {}",
+            format!("{} Return:", benchmark.asset),
+            format_pnl_percent(benchmark.benchmark_return_percent(), with_color)
+        )?;
+        writeln!(out, "    Alpha:             {}", format_pnl_percent(benchmark.alpha(), with_color))?;
+        writeln!(out)?;
+    }
+
+    if view.by_category {
         // Group by category
-        let category_summaries = portfolio.by_category();
+        let category_summaries = portfolio.by_category(view.category_order);
 
         for summary in category_summaries {
-            println!("{}", format!("  {} [{}]", summary.category_name, format_usd(summary.total_value)).bold());
+            writeln!(out, "{}", format!("  {} [{}]", summary.category_name, view.money(summary.total_value)).bold())?;
 
             for entry in &summary.accounts {
-                println!("    {} ({})", entry.account_name, format_usd(entry.total_value()));
+                writeln!(out, "    {} ({})", entry.account_name, view.money(entry.total_value()))?;
 
                 for h in &entry.holdings {
-                    print_holding(h, &config, 6);
+                    let fees = (view.position_fees)(&entry.account_id, &h.holding.asset);
+                    let allocation = (
+                        portfolio.allocation_pct(holding_value(h)),
+                        allocation_pct(holding_value(h), summary.total_value),
+                    );
+                    render_holding(out, h, fees, allocation, view, 6)?;
                 }
             }
-            println!();
+            writeln!(out)?;
         }
-    } else if by_account {
+    } else if view.by_account {
         // Group by account
         for entry in &portfolio.entries {
-            println!(
+            writeln!(
+                out,
                 "  {} [{}]",
                 entry.account_name.bold(),
-                format_usd(entry.total_value())
-            );
+                view.money(entry.total_value())
+            )?;
 
             for h in &entry.holdings {
-                print_holding(h, &config, 4);
+                let fees = (view.position_fees)(&entry.account_id, &h.holding.asset);
+                let allocation = (
+                    portfolio.allocation_pct(holding_value(h)),
+                    allocation_pct(holding_value(h), entry.total_value()),
+                );
+                render_holding(out, h, fees, allocation, view, 4)?;
             }
-            println!();
+            writeln!(out)?;
         }
     } else {
         // Default: flat list grouped by account
-        println!("{}", "-".repeat(70));
-        println!(
-            "  {:8}  {:>12}  {:>12}  {:>12}  {:>15}",
-            "Asset", "Quantity", "Price", "Value", "P&L"
-        );
-        println!("{}", "-".repeat(70));
+        let show_fees = view.total_fees.is_some();
+        let width = if show_fees { 96 } else { 82 };
+        writeln!(out, "{}", "-".repeat(width))?;
+        write!(
+            out,
+            "  {:8}  {:>12}  {:>12}  {:>12}  {:>15}  {:>10}",
+            "Asset", "Quantity", "Price", "Value", "P&L", "%Portfolio"
+        )?;
+        if show_fees {
+            write!(out, "  {:>12}", "Fees")?;
+        }
+        writeln!(out)?;
+        writeln!(out, "{}", "-".repeat(width))?;
 
         for entry in &portfolio.entries {
-            println!("  {}", entry.account_name.dimmed());
+            writeln!(out, "  {}", entry.account_name.dimmed())?;
 
             for h in &entry.holdings {
                 let price_str = h.current_price
-                    .map(|p| format_usd(p))
+                    .map(|p| view.money(p))
                     .unwrap_or_else(|| "-".to_string());
 
                 let value_str = h.current_value
-                    .map(|v| format_usd(v))
+                    .map(|v| view.money(v))
                     .unwrap_or_else(|| "-".to_string());
 
                 let pnl_str = match (h.unrealized_pnl, h.unrealized_pnl_percent) {
                     (Some(pnl), Some(pct)) => format!(
                         "{} ({})",
-                        format_pnl(pnl, config.display.color),
-                        format_pnl_percent(pct, config.display.color)
+                        view.pnl(pnl),
+                        format_pnl_percent(pct, with_color)
                     ),
                     _ => "-".to_string(),
                 };
 
-                println!(
-                    "  {:8}  {:>12}  {:>12}  {:>12}  {:>15}",
+                write!(
+                    out,
+                    "  {:8}  {:>12}  {:>12}  {:>12}  {:>15}  {:>10}",
                     h.holding.asset,
                     format_quantity(h.holding.quantity),
                     price_str,
                     value_str,
-                    pnl_str
-                );
+                    pnl_str,
+                    format!("{:.2}%", portfolio.allocation_pct(holding_value(h)))
+                )?;
+                if let Some(fees) = (view.position_fees)(&entry.account_id, &h.holding.asset) {
+                    write!(out, "  {:>12}", view.money(fees))?;
+                }
+                writeln!(out)?;
             }
         }
 
-        println!("{}", "-".repeat(70));
+        writeln!(out, "{}", "-".repeat(width))?;
     }
 
     // Asset totals
     let asset_totals = portfolio.asset_totals();
     if !asset_totals.is_empty() {
-        println!();
-        println!("{}", "ASSET TOTALS".bold());
-        print!(" ");
+        writeln!(out)?;
+        writeln!(out, "{}", "ASSET TOTALS".bold())?;
+        write!(out, " ")?;
         for (i, total) in asset_totals.iter().take(5).enumerate() {
             if i > 0 {
-                print!("  |  ");
+                write!(out, "  |  ")?;
             }
-            print!("{}: {} ({})", total.asset, format_quantity(total.quantity), format_usd(total.value));
+            write!(out, "{}: {} ({})", total.asset, format_quantity(total.quantity), view.money(total.value))?;
         }
-        println!();
+        writeln!(out)?;
     }
 
-    println!();
+    writeln!(out)?;
 
     Ok(())
 }
 
-fn print_holding(h: &HoldingWithPrice, config: &AppConfig, indent: usize) {
+/// Current value of a holding, zero when it has no price
+fn holding_value(h: &HoldingWithPrice) -> Decimal {
+    h.current_value.unwrap_or(Decimal::ZERO)
+}
+
+/// Value of the group an entry is shown under, matching how the text view
+/// groups holdings (category takes precedence over account)
+fn group_value(portfolio: &Portfolio, entry: &PortfolioEntry, by_account: bool, by_category: bool) -> Option<Decimal> {
+    if by_category {
+        Some(portfolio.category_value(&entry.category_id))
+    } else if by_account {
+        Some(entry.total_value())
+    } else {
+        None
+    }
+}
+
+/// `allocation` is the holding's (%Portfolio, %Group)
+fn render_holding(
+    out: &mut String,
+    h: &HoldingWithPrice,
+    fees: Option<Decimal>,
+    allocation: (Decimal, Decimal),
+    view: &PortfolioView,
+    indent: usize,
+) -> std::fmt::Result {
     let spaces = " ".repeat(indent);
 
     let price_str = h.current_price
-        .map(|p| format_usd(p))
+        .map(|p| view.money(p))
         .unwrap_or_else(|| "-".to_string());
 
     let value_str = h.current_value
-        .map(|v| format_usd(v))
+        .map(|v| view.money(v))
         .unwrap_or_else(|| "-".to_string());
 
     let pnl_str = h.unrealized_pnl
-        .map(|pnl| format_pnl(pnl, config.display.color))
+        .map(|pnl| view.pnl(pnl))
         .unwrap_or_else(|| "-".to_string());
 
-    println!(
-        "{}{}: {} @ {} = {} ({})",
+    let fees_str = fees
+        .map(|f| format!(" [fees {}]", view.money(f)))
+        .unwrap_or_default();
+
+    writeln!(
+        out,
+        "{}{}: {} @ {} = {} ({}) [{:.2}% portfolio, {:.2}% group]{}",
         spaces,
         h.holding.asset,
         format_quantity(h.holding.quantity),
         price_str,
         value_str,
-        pnl_str
-    );
+        pnl_str,
+        allocation.0,
+        allocation.1,
+        fees_str
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::holdings::Holding;
+
+    fn entry(account: &str, asset: &str, quantity: i64, cost: Option<i64>, price: Option<i64>) -> PortfolioEntry {
+        let holding = Holding {
+            id: 0,
+            account_id: account.to_lowercase(),
+            asset: asset.to_string(),
+            quantity: Decimal::from(quantity),
+            avg_cost_basis: cost.map(Decimal::from),
+            cost_basis_currency: None,
+            avg_cost_basis_base: None,
+            updated_at: Utc::now(),
+            acquired_at: None,
+        };
+        PortfolioEntry {
+            account_id: account.to_lowercase(),
+            account_name: account.to_string(),
+            category_id: "trading".to_string(),
+            category_name: "Trading, Spot".to_string(),
+            holdings: vec![HoldingWithPrice::from_holding(holding, price.map(Decimal::from))],
+            snapshot_id: None,
+            is_closed: false,
+            display_value: None,
+        }
+    }
+
+    #[test]
+    fn test_render_portfolio_csv() {
+        let portfolio = Portfolio::from_entries(vec![
+            entry("Binance", "BTC", 2, Some(30000), Some(45000)),
+            entry("Ledger", "XYZ", 5, None, None),
+        ]);
+
+        let csv = render_portfolio_csv(&portfolio, true).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], PORTFOLIO_CSV_HEADERS.join(","));
+        assert_eq!(lines[1], "Binance,\"Trading, Spot\",BTC,2,30000,45000,90000,30000,50.00");
+        assert_eq!(lines[2], "Ledger,\"Trading, Spot\",XYZ,5,,,,,");
+
+        let appended = render_portfolio_csv(&portfolio, false).unwrap();
+        assert_eq!(appended.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_price_source_summary() {
+        assert_eq!(price_source_summary(&HashMap::new()), "");
+
+        let sources = HashMap::from([
+            ("PEPE".to_string(), PriceSource::CoinGecko),
+            ("BTC".to_string(), PriceSource::Binance),
+            ("ETH".to_string(), PriceSource::Binance),
+        ]);
+        assert_eq!(price_source_summary(&sources), "binance,coingecko");
+    }
 }
@@ -1,19 +1,29 @@
 use colored::Colorize;
+use schemars::JsonSchema;
 use serde::Serialize;
 use sqlx::SqlitePool;
 
-use crate::cli::output::{format_usd, print_header, print_row, warning};
+use crate::cli::output::{format_currency, print_header, print_row, warning};
 use crate::cli::GlobalOptions;
 use crate::config::AppConfig;
 use crate::error::Result;
-use crate::exchange::{BinanceAlphaClient, BinanceClient, Exchange};
+use crate::exchange::{BinanceAlphaClient, BinanceClient, Exchange, PriceData};
 
-#[derive(Serialize)]
-struct PriceOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PriceOutput {
     symbol: String,
     price: String,
 }
 
+impl From<&PriceData> for PriceOutput {
+    fn from(price: &PriceData) -> Self {
+        Self {
+            symbol: price.symbol.clone(),
+            price: price.price.to_string(),
+        }
+    }
+}
+
 pub async fn handle_price_command(symbols: Vec<String>, _pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
     let config = AppConfig::load()?;
     let use_testnet = opts.testnet || config.general.use_testnet;
@@ -28,44 +38,17 @@ pub async fn handle_price_command(symbols: Vec<String>, _pool: &SqlitePool, opts
         warning("Testnet Mode");
     }
 
-    let symbol_refs: Vec<&str> = symbols.iter().map(|s| s.as_str()).collect();
-    let mut prices = client.get_prices(&symbol_refs).await?;
-    let mut found_symbols: Vec<String> = prices.iter().map(|p| p.symbol.to_uppercase()).collect();
-
-    // Try Binance Alpha for missing symbols
-    let missing_symbols: Vec<&str> = symbols
-        .iter()
-        .filter(|s| !found_symbols.contains(&s.to_uppercase()))
-        .map(|s| s.as_str())
-        .collect();
-
-    if !missing_symbols.is_empty() {
-        let alpha_client = BinanceAlphaClient::new();
-        if let Ok(alpha_prices) = alpha_client.get_prices(&missing_symbols).await {
-            for (symbol, price) in alpha_prices {
-                prices.push(crate::exchange::PriceData {
-                    symbol: symbol.clone(),
-                    price,
-                });
-                found_symbols.push(symbol);
-            }
-        }
-    }
+    let prices = fetch_prices(&client, &symbols).await?;
+    let found_symbols: Vec<String> = prices.iter().map(|p| p.symbol.to_uppercase()).collect();
 
     if opts.json {
         // JSON output
-        let output: Vec<PriceOutput> = prices
-            .iter()
-            .map(|p| PriceOutput {
-                symbol: p.symbol.clone(),
-                price: p.price.to_string(),
-            })
-            .collect();
+        let output: Vec<PriceOutput> = prices.iter().map(PriceOutput::from).collect();
         println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
     } else if symbols.len() == 1 && prices.len() == 1 {
         // Single symbol - simple output
         let price = &prices[0];
-        println!("{}: {}", price.symbol.bold(), format_usd(price.price));
+        println!("{}: {}", price.symbol.bold(), format_currency(price.price));
     } else {
         // Multiple symbols - table output
         print_header(&[("Symbol", 10), ("Price", 15)]);
@@ -73,7 +56,7 @@ pub async fn handle_price_command(symbols: Vec<String>, _pool: &SqlitePool, opts
         for price in &prices {
             print_row(&[
                 (&price.symbol, 10),
-                (&format_usd(price.price), 15),
+                (&format_currency(price.price), 15),
             ]);
         }
 
@@ -87,3 +70,42 @@ pub async fn handle_price_command(symbols: Vec<String>, _pool: &SqlitePool, opts
 
     Ok(())
 }
+
+/// The `price --json` output for `symbols`
+pub(crate) async fn price_output(symbols: &[String], opts: &GlobalOptions) -> Result<Vec<PriceOutput>> {
+    let config = AppConfig::load()?;
+    let client = BinanceClient::new(
+        opts.testnet || config.general.use_testnet,
+        config.binance.api_key.clone(),
+        config.binance.api_secret.clone(),
+    );
+
+    let prices = fetch_prices(&client, symbols).await?;
+    Ok(prices.iter().map(PriceOutput::from).collect())
+}
+
+/// Current USD prices for `symbols`, trying Binance Alpha for those the main
+/// exchange doesn't list. Symbols without a price are omitted.
+pub(crate) async fn fetch_prices(client: &BinanceClient, symbols: &[String]) -> Result<Vec<PriceData>> {
+    let symbol_refs: Vec<&str> = symbols.iter().map(|s| s.as_str()).collect();
+    let mut prices = client.get_prices(&symbol_refs).await?;
+    let found_symbols: Vec<String> = prices.iter().map(|p| p.symbol.to_uppercase()).collect();
+
+    // Try Binance Alpha for missing symbols
+    let missing_symbols: Vec<&str> = symbols
+        .iter()
+        .filter(|s| !found_symbols.contains(&s.to_uppercase()))
+        .map(|s| s.as_str())
+        .collect();
+
+    if !missing_symbols.is_empty() {
+        let alpha_client = BinanceAlphaClient::new();
+        if let Ok(alpha_prices) = alpha_client.get_prices(&missing_symbols).await {
+            for (symbol, price) in alpha_prices {
+                prices.push(PriceData { symbol, price });
+            }
+        }
+    }
+
+    Ok(prices)
+}
@@ -11,6 +11,7 @@ use colored::Colorize;
 use reqwest::Client;
 use std::time::Duration;
 
+use crate::ai::providers::ollama::OllamaTags;
 use crate::cli::notifications::{ProviderStatus, SystemStatus};
 use crate::cli::output::colors_enabled;
 use crate::config::AppConfig;
@@ -52,7 +53,7 @@ pub async fn collect_status(run_checks: bool) -> Result<SystemStatus> {
     let claude_status = check_claude_status(&config, run_checks).await;
 
     // Check Ollama status
-    let ollama_status = check_ollama_status(&config, run_checks).await;
+    let ollama_status = check_ollama_status(&config).await;
 
     // Determine effective provider
     let effective_provider = determine_effective_provider(&ai_mode, &claude_status, &ollama_status);
@@ -136,7 +137,7 @@ async fn test_claude_connection(api_key: &str) -> std::result::Result<(), String
 }
 
 /// Check Ollama status
-async fn check_ollama_status(config: &Option<AppConfig>, run_checks: bool) -> ProviderStatus {
+async fn check_ollama_status(config: &Option<AppConfig>) -> ProviderStatus {
     let base_url = config
         .as_ref()
         .and_then(|c| c.ai.as_ref())
@@ -150,19 +151,27 @@ async fn check_ollama_status(config: &Option<AppConfig>, run_checks: bool) -> Pr
         .and_then(|ai| ai.local_model.clone())
         .unwrap_or_else(|| "llama3.2:3b".to_string());
 
-    if run_checks || true {
-        // Always check Ollama since it's local
-        match test_ollama_connection(&base_url).await {
-            Ok(()) => ProviderStatus::available("Ollama", model),
-            Err(e) => ProviderStatus::unavailable("Ollama", e),
-        }
+    // Always check Ollama since it's local
+    match test_ollama_connection(&base_url).await {
+        Ok(tags) => ollama_model_status(&tags, model),
+        Err(e) => ProviderStatus::unavailable("Ollama", e),
+    }
+}
+
+/// A reachable Ollama only counts as connected once the configured model is pulled
+fn ollama_model_status(tags: &OllamaTags, model: String) -> ProviderStatus {
+    if tags.has_model(&model) {
+        ProviderStatus::available("Ollama", model)
     } else {
-        ProviderStatus::unavailable("Ollama", "Not checked")
+        ProviderStatus::unavailable(
+            "Ollama",
+            format!("model '{}' not installed — run: ollama pull {}", model, model),
+        )
     }
 }
 
-/// Test Ollama connection
-async fn test_ollama_connection(base_url: &str) -> std::result::Result<(), String> {
+/// Test Ollama connection, returning the installed models
+async fn test_ollama_connection(base_url: &str) -> std::result::Result<OllamaTags, String> {
     let client = Client::builder()
         .timeout(Duration::from_secs(5))
         .build()
@@ -184,11 +193,10 @@ async fn test_ollama_connection(base_url: &str) -> std::result::Result<(), Strin
             }
         })?;
 
-    if response.status().is_success() {
-        Ok(())
-    } else {
-        Err(format!("HTTP {}", response.status()))
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
     }
+    response.json().await.map_err(|e| format!("Unexpected response: {}", e))
 }
 
 /// Determine which provider will actually be used
@@ -315,6 +323,23 @@ mod tests {
         assert_eq!(format_ai_mode("none"), "Disabled");
     }
 
+    #[test]
+    fn test_ollama_model_status() {
+        let tags: OllamaTags =
+            serde_json::from_str(r#"{"models":[{"name":"llama3.2:3b"},{"name":"mistral:latest"}]}"#).unwrap();
+
+        assert!(ollama_model_status(&tags, "llama3.2:3b".to_string()).available);
+        // An untagged name is the latest tag
+        assert!(ollama_model_status(&tags, "mistral".to_string()).available);
+
+        let missing = ollama_model_status(&tags, "llama3.1:8b".to_string());
+        assert!(!missing.available);
+        assert_eq!(
+            missing.reason.as_deref(),
+            Some("model 'llama3.1:8b' not installed — run: ollama pull llama3.1:8b")
+        );
+    }
+
     #[test]
     fn test_format_ai_mode_unknown() {
         let result = format_ai_mode("foobar");
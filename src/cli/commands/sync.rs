@@ -1,131 +1,863 @@
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use rust_decimal::Decimal;
+use schemars::JsonSchema;
+use serde::Serialize;
 use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
 
-use crate::cli::output::{format_quantity, info, success, warning};
-use crate::cli::GlobalOptions;
+use crate::cli::output::{error, format_quantity, info, print_header, print_json, print_row, success, warning};
+use crate::cli::{GlobalOptions, SyncCommands};
 use crate::config::AppConfig;
-use crate::core::account::AccountType;
-use crate::core::holdings::Holding;
-use crate::db::{AccountRepository, HoldingRepository};
+use crate::core::account::{Account, AccountType};
+use crate::core::holdings::{Holding, HoldingChangeSource};
+use crate::db::sync_log::{SyncChange, SyncCounts, SyncLogEntry};
+use crate::db::{AccountRepository, HoldingRepository, ListAccountsFilter, SyncLogRepository};
 use crate::error::{CryptofolioError, Result};
-use crate::exchange::{BinanceClient, Exchange};
+use crate::exchange::bitcoin::{self as btc, is_bitcoin, ElectrumClient, EsploraClient};
+use crate::exchange::ethereum::{erc20, is_ethereum, EthereumRpcClient};
+use crate::exchange::models::TokenContract;
+use crate::exchange::{BinanceClient, BlockchainProvider, Exchange, KrakenClient};
 use chrono::Utc;
 
-pub async fn handle_sync_command(account: Option<String>, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
-    let config = AppConfig::load()?;
-    let account_repo = AccountRepository::new(pool);
-    let holding_repo = HoldingRepository::new(pool);
+#[derive(Serialize)]
+struct SyncLogOutput {
+    id: i64,
+    account: String,
+    account_id: String,
+    started_at: String,
+    completed_at: Option<String>,
+    holdings_updated: i64,
+    holdings_added: i64,
+    holdings_removed: i64,
+    error_message: Option<String>,
+    status: String,
+}
 
-    // Check if we have credentials
-    if !config.has_binance_credentials() {
-        return Err(CryptofolioError::AuthRequired(
-            "Binance API credentials not configured. Use 'cryptofolio config set binance.api_key <key>' and 'cryptofolio config set binance.api_secret <secret>'".into()
-        ));
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SyncChangeOutput {
+    asset: String,
+    previous_quantity: Decimal,
+    new_quantity: Decimal,
+    delta: Decimal,
+}
+
+impl From<&SyncChange> for SyncChangeOutput {
+    fn from(change: &SyncChange) -> Self {
+        Self {
+            asset: change.asset.clone(),
+            previous_quantity: change.previous_quantity,
+            new_quantity: change.new_quantity,
+            delta: change.delta(),
+        }
     }
+}
 
-    // Get accounts to sync
-    let accounts = account_repo.list_accounts().await?;
-    let accounts_to_sync: Vec<_> = accounts
-        .into_iter()
-        .filter(|a| {
-            // Filter by account name if specified
-            if let Some(ref name) = account {
-                if a.name.to_lowercase() != name.to_lowercase() {
-                    return false;
-                }
-            }
+#[derive(Serialize)]
+struct SyncHistoryOutput {
+    id: i64,
+    account: String,
+    account_id: String,
+    started_at: String,
+    status: String,
+    changes: Vec<SyncChangeOutput>,
+}
 
-            // Only sync exchange accounts with sync enabled
-            matches!(a.account_type, AccountType::Exchange) && a.sync_enabled
-        })
-        .collect();
+/// What syncing one account changed
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SyncOutput {
+    account: String,
+    holdings_updated: i64,
+    holdings_added: i64,
+    holdings_removed: i64,
+    changes: Vec<SyncChangeOutput>,
+}
+
+impl SyncOutput {
+    fn new(acc: &Account, result: &SyncResult) -> Self {
+        Self {
+            account: acc.name.clone(),
+            holdings_updated: result.counts.updated,
+            holdings_added: result.counts.added,
+            holdings_removed: result.counts.removed,
+            changes: result.changes.iter().map(SyncChangeOutput::from).collect(),
+        }
+    }
+}
+
+/// A progress event printed as one JSON line per event when syncing with `--json`
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum SyncEvent<'a> {
+    Start { account: &'a str, assets: Vec<String> },
+    Balance { asset: &'a str, qty: String },
+    Complete(SyncOutput),
+    Error { account: &'a str, message: String },
+}
+
+fn emit(event: &SyncEvent) -> Result<()> {
+    println!("{}", serde_json::to_string(event)?);
+    Ok(())
+}
+
+pub async fn handle_sync_command(
+    command: Option<SyncCommands>,
+    account: Option<String>,
+    show_changes: bool,
+    dry_run: bool,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    match command {
+        Some(SyncCommands::Log { account, limit }) => return show_sync_log(account, limit, pool, opts).await,
+        Some(SyncCommands::History { account, limit }) => return show_sync_history(account, limit, pool, opts).await,
+        None => {}
+    }
+
+    let config = AppConfig::load()?;
+    let account_repo = AccountRepository::new(pool);
+
+    // Get accounts to sync: exchanges with sync enabled, and wallets with Ethereum or Bitcoin addresses
+    let filter = ListAccountsFilter {
+        account_types: vec![AccountType::Exchange, AccountType::HardwareWallet, AccountType::SoftwareWallet],
+        name: account.clone(),
+        ..Default::default()
+    };
+    let mut accounts_to_sync = Vec::new();
+    for a in account_repo.list_accounts(&filter).await? {
+        if is_syncable(&a, &account_repo).await? {
+            accounts_to_sync.push(a);
+        }
+    }
 
     if accounts_to_sync.is_empty() {
+        if opts.json || opts.quiet {
+            return Ok(());
+        }
         if account.is_some() {
-            warning("Specified account is not an exchange account or sync is not enabled.");
+            warning("Specified account is not a sync-enabled exchange or a wallet with Ethereum or Bitcoin addresses.");
         } else {
-            warning("No exchange accounts with sync enabled found.");
+            warning("No exchange accounts with sync enabled or wallets with Ethereum or Bitcoin addresses found.");
         }
-        println!("Use 'cryptofolio account add <name> --type exchange --category trading --sync' to create one.");
+        println!("Use 'cryptofolio account add <name> --type exchange --category trading --sync' to create one,");
+        println!("or 'cryptofolio account address add <wallet> ethereum <address>' to sync a wallet.");
         return Ok(());
     }
 
+    require_credentials(&accounts_to_sync, &config)?;
+
+    if dry_run {
+        return preview_sync(&accounts_to_sync, &config, pool, opts).await;
+    }
+
+    // Keep going past a failing account; the error returned tells a partial failure from a complete one
+    let total = accounts_to_sync.len();
+    let mut failed = 0;
     for acc in accounts_to_sync {
-        if !opts.quiet {
+        if !opts.quiet && !opts.json {
             info(&format!("Syncing '{}'...", acc.name));
         }
 
+        match run_sync(&acc, &config, pool, opts).await {
+            Ok(result) => {
+                if opts.json {
+                    emit(&SyncEvent::Complete(SyncOutput::new(&acc, &result)))?;
+                } else if show_changes && !opts.quiet {
+                    print_changes(&result.changes);
+                }
+            }
+            Err(e) => {
+                if opts.json {
+                    emit(&SyncEvent::Error { account: &acc.name, message: e.to_string() })?;
+                }
+                // A single account's error is reported as it is
+                if total == 1 {
+                    return Err(e);
+                }
+                if !opts.json {
+                    error(&format!("Failed to sync '{}': {}", acc.name, e));
+                }
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(CryptofolioError::SyncFailed { failed, total });
+    }
+    Ok(())
+}
+
+/// Sync a single account by name, without printing progress
+pub(crate) async fn sync_account_output(account: &str, pool: &SqlitePool, opts: &GlobalOptions) -> Result<SyncOutput> {
+    let config = AppConfig::load()?;
+    let account_repo = AccountRepository::new(pool);
+    let acc = account_repo.require_account(account).await?;
+
+    if !is_syncable(&acc, &account_repo).await? {
+        return Err(CryptofolioError::InvalidInput(format!(
+            "'{}' is not a sync-enabled exchange or a wallet with Ethereum or Bitcoin addresses",
+            acc.name
+        )));
+    }
+    require_credentials(std::slice::from_ref(&acc), &config)?;
+
+    let quiet = GlobalOptions { quiet: true, json: false, ..opts.clone() };
+    let result = run_sync(&acc, &config, pool, &quiet).await?;
+    Ok(SyncOutput::new(&acc, &result))
+}
+
+/// Whether an account has a source to sync from: an exchange with sync
+/// enabled, or a wallet with Ethereum or Bitcoin addresses
+async fn is_syncable(acc: &Account, account_repo: &AccountRepository<'_>) -> Result<bool> {
+    Ok(match acc.account_type {
+        AccountType::Exchange => acc.sync_enabled,
+        AccountType::HardwareWallet | AccountType::SoftwareWallet => account_repo
+            .list_addresses(&acc.id)
+            .await?
+            .iter()
+            .any(|addr| is_ethereum(&addr.blockchain) || is_bitcoin(&addr.blockchain)),
+        _ => false,
+    })
+}
+
+/// Check we have credentials for each exchange being synced
+pub(crate) fn require_credentials(accounts: &[Account], config: &AppConfig) -> Result<()> {
+    let exchanges = accounts.iter().filter(|a| a.account_type == AccountType::Exchange);
+    let (kraken, binance): (Vec<&Account>, Vec<&Account>) = exchanges.partition(|a| a.config.is_kraken());
+    if !binance.is_empty() && !config.has_binance_credentials() {
+        return Err(CryptofolioError::AuthRequired(
+            "Binance API credentials not configured. Use 'cryptofolio config set binance.api_key <key>' and 'cryptofolio config set binance.api_secret <secret>'".into()
+        ));
+    }
+    if !kraken.is_empty() && !config.has_kraken_credentials() {
+        return Err(CryptofolioError::AuthRequired(
+            "Kraken API credentials not configured. Use 'cryptofolio config set-secret kraken.api_key' and 'cryptofolio config set-secret kraken.api_secret'".into()
+        ));
+    }
+    Ok(())
+}
+
+/// Sync an account, recording the run in the sync log
+async fn run_sync(acc: &Account, config: &AppConfig, pool: &SqlitePool, opts: &GlobalOptions) -> Result<SyncResult> {
+    let sync_log = SyncLogRepository::new(pool);
+    let log_id = sync_log.start(&acc.id).await?;
+
+    match sync_account(acc, config, log_id, pool, opts).await {
+        Ok(result) => {
+            sync_log.complete(log_id, result.counts).await?;
+            Ok(result)
+        }
+        Err(e) => {
+            sync_log.fail(log_id, &e.to_string()).await?;
+            Err(e)
+        }
+    }
+}
+
+/// Fetch each account's balances and show how a sync would change its
+/// holdings, without writing anything or recording a sync run
+async fn preview_sync(accounts: &[Account], config: &AppConfig, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let holding_repo = HoldingRepository::new(pool);
+    let human = !opts.quiet && !opts.json;
+
+    let mut rows = Vec::new();
+    for acc in accounts {
+        if human {
+            info(&format!("Fetching balances for '{}'...", acc.name));
+        }
+        let fetched = fetch_balances(acc, config, human, pool, opts).await?;
+        let previous = local_quantities(&holding_repo, &acc.id).await?;
+
+        for planned in plan_sync(&fetched, &acc.config.excluded_assets(), &previous) {
+            let local = planned.local.unwrap_or(Decimal::ZERO);
+            rows.push(SyncPreviewRow {
+                account: acc.name.clone(),
+                asset: planned.asset.to_uppercase(),
+                local_quantity: local,
+                live_quantity: planned.live,
+                delta: planned.live - local,
+                action: planned.action,
+            });
+        }
+    }
+
+    if opts.json {
+        return print_json(&rows);
+    }
+
+    if rows.is_empty() {
+        println!("No balances or holdings to sync.");
+        return Ok(());
+    }
+
+    print_header(&[("Account", 16), ("Asset", 10), ("Local Qty", 18), ("Live Qty", 18), ("Delta", 18), ("Action", 10)]);
+    for row in &rows {
+        print_row(&[
+            (&row.account, 16),
+            (&row.asset, 10),
+            (&format_quantity(row.local_quantity), 18),
+            (&format_quantity(row.live_quantity), 18),
+            (&format_delta(row.delta), 18),
+            (row.action.label(), 10),
+        ]);
+    }
+
+    let count = |action: SyncAction| rows.iter().filter(|r| r.action == action).count();
+    println!();
+    info(&format!(
+        "[DRY RUN] Would set {} and remove {} holding(s); nothing was written",
+        count(SyncAction::Set),
+        count(SyncAction::Remove)
+    ));
+    Ok(())
+}
+
+/// One row of `sync --dry-run`
+#[derive(Serialize)]
+struct SyncPreviewRow {
+    account: String,
+    asset: String,
+    local_quantity: Decimal,
+    live_quantity: Decimal,
+    delta: Decimal,
+    action: SyncAction,
+}
+
+/// What a sync does to one holding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum SyncAction {
+    /// Write the reported balance, adding the holding if it's new
+    Set,
+    /// The reported balance matches the holding
+    NoChange,
+    /// The source no longer reports the asset, so the holding is deleted
+    Remove,
+}
+
+impl SyncAction {
+    fn label(self) -> &'static str {
+        match self {
+            SyncAction::Set => "SET",
+            SyncAction::NoChange => "NO_CHANGE",
+            SyncAction::Remove => "REMOVE",
+        }
+    }
+}
+
+/// A holding's local quantity against the balance its source reports
+struct PlannedHolding {
+    asset: String,
+    /// None when the account doesn't hold the asset yet
+    local: Option<Decimal>,
+    live: Decimal,
+    action: SyncAction,
+}
+
+/// Match fetched balances against an account's holdings (`previous`, keyed by
+/// uppercased asset). Excluded assets and empty balances are skipped; holdings
+/// the source covers but no longer reports are removed.
+fn plan_sync(fetched: &FetchedBalances, excluded_assets: &[String], previous: &HashMap<String, Decimal>) -> Vec<PlannedHolding> {
+    let mut plan = Vec::new();
+    let mut reported = HashSet::new();
+
+    for (asset, quantity) in &fetched.balances {
+        let key = asset.to_uppercase();
+        if excluded_assets.contains(&key) || *quantity <= Decimal::ZERO {
+            continue;
+        }
+
+        let local = previous.get(&key).copied();
+        let action = if local == Some(*quantity) { SyncAction::NoChange } else { SyncAction::Set };
+        plan.push(PlannedHolding { asset: asset.clone(), local, live: *quantity, action });
+        reported.insert(key);
+    }
+
+    let mut removed: Vec<&String> = previous
+        .keys()
+        .filter(|a| !reported.contains(*a) && fetched.covered.as_ref().is_none_or(|c| c.contains(*a)))
+        .collect();
+    removed.sort();
+    for asset in removed {
+        plan.push(PlannedHolding {
+            asset: asset.clone(),
+            local: Some(previous[asset]),
+            live: Decimal::ZERO,
+            action: SyncAction::Remove,
+        });
+    }
+
+    plan
+}
+
+/// An account's holding quantities keyed by uppercased asset
+async fn local_quantities(holding_repo: &HoldingRepository<'_>, account_id: &str) -> Result<HashMap<String, Decimal>> {
+    Ok(holding_repo
+        .list_by_account(account_id)
+        .await?
+        .into_iter()
+        .map(|h| (h.asset.to_uppercase(), h.quantity))
+        .collect())
+}
+
+/// What a sync changed in an account's holdings
+struct SyncResult {
+    counts: SyncCounts,
+    changes: Vec<SyncChange>,
+}
+
+/// Balances read from an account's source, and the assets that source covers
+pub(crate) struct FetchedBalances {
+    pub(crate) balances: Vec<(String, Decimal)>,
+    /// Holdings outside these assets are left alone; None when the source reports everything
+    pub(crate) covered: Option<HashSet<String>>,
+}
+
+fn start_spinner(message: &str) -> ProgressBar {
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::default_spinner()
+        .template("{spinner:.blue} {msg}")
+        .unwrap());
+    pb.set_message(message.to_string());
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    pb
+}
+
+/// Fetch an account's balances from its exchange or its wallet addresses
+async fn fetch_balances(
+    acc: &Account,
+    config: &AppConfig,
+    human: bool,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<FetchedBalances> {
+    match acc.account_type {
+        AccountType::Exchange => fetch_exchange_balances(acc, config, human, opts).await,
+        _ => fetch_wallet_balances(acc, config, human, pool).await,
+    }
+}
+
+/// Fetch an account's current balances from its exchange
+pub(crate) async fn fetch_exchange_balances(acc: &Account, config: &AppConfig, human: bool, opts: &GlobalOptions) -> Result<FetchedBalances> {
+    let client: Box<dyn Exchange> = if acc.config.is_kraken() {
+        // Kraken has no spot testnet, so testnet settings don't apply
+        Box::new(KrakenClient::new(
+            config.get_secret("kraken.api_key")?,
+            config.get_secret("kraken.api_secret")?,
+        ))
+    } else {
         // Use account-specific testnet setting or global (CLI flag takes precedence)
         let is_testnet = opts.testnet || acc.config.is_testnet || config.general.use_testnet;
 
-        if is_testnet && !opts.quiet {
+        if is_testnet && human {
             println!("  {}", "[Testnet Mode]".yellow());
         }
 
-        let client = BinanceClient::new(
+        let mut client = BinanceClient::new(
             is_testnet,
             config.binance.api_key.clone(),
             config.binance.api_secret.clone(),
         );
+        // Respect the account's configured request rate
+        if let Some(rate_limit) = acc.config.rate_limit() {
+            client = client.with_rate_limit(rate_limit);
+        }
+        Box::new(client)
+    };
 
-        // Show progress spinner
-        let spinner = if !opts.quiet {
-            let pb = ProgressBar::new_spinner();
-            pb.set_style(ProgressStyle::default_spinner()
-                .template("{spinner:.blue} {msg}")
-                .unwrap());
-            pb.set_message("Fetching balances...");
-            pb.enable_steady_tick(std::time::Duration::from_millis(100));
-            Some(pb)
-        } else {
-            None
-        };
+    // Show progress spinner
+    let spinner = human.then(|| start_spinner("Fetching balances..."));
 
-        // Fetch balances
-        let balances = client.get_balances().await?;
+    // Fetch balances
+    let balances = client.get_balances().await;
 
+    if let Some(pb) = &spinner {
+        pb.finish_and_clear();
+    }
+
+    Ok(FetchedBalances {
+        balances: balances?.into_iter().map(|b| (b.asset.clone(), b.total())).collect(),
+        covered: None,
+    })
+}
+
+/// Read on-chain balances for every Ethereum and Bitcoin address of a wallet account
+async fn fetch_wallet_balances(acc: &Account, config: &AppConfig, human: bool, pool: &SqlitePool) -> Result<FetchedBalances> {
+    let addresses = AccountRepository::new(pool).list_addresses(&acc.id).await?;
+    let ethereum: Vec<String> = addresses.iter().filter(|a| is_ethereum(&a.blockchain)).map(|a| a.address.clone()).collect();
+    let bitcoin: Vec<String> = addresses.iter().filter(|a| is_bitcoin(&a.blockchain)).map(|a| a.address.clone()).collect();
+
+    let mut balances = Vec::new();
+    let mut covered = HashSet::new();
+
+    if !ethereum.is_empty() {
+        let tokens = erc20::token_list(config.ethereum.tokens.as_deref())?;
+        let provider = EthereumRpcClient::new(config.ethereum.rpc_url.as_str());
+
+        let spinner = human.then(|| start_spinner(&format!("Reading {} Ethereum address(es)...", ethereum.len())));
+        let result = read_address_balances(&provider, &ethereum, &tokens).await;
         if let Some(pb) = &spinner {
             pb.finish_and_clear();
         }
 
-        // Clear existing holdings for this account
-        holding_repo.delete_all_for_account(&acc.id).await?;
-
-        // Insert new holdings
-        let mut synced_count = 0;
-        for balance in balances {
-            if balance.total() > Decimal::ZERO {
-                let holding = Holding {
-                    id: 0,
-                    account_id: acc.id.clone(),
-                    asset: balance.asset.clone(),
-                    quantity: balance.total(),
-                    avg_cost_basis: None, // Exchange doesn't provide cost basis
-                    cost_basis_currency: None,
-                    avg_cost_basis_base: None,
-                    updated_at: Utc::now(),
-                };
-
-                holding_repo.upsert(&holding).await?;
-                synced_count += 1;
-
-                if !opts.quiet {
-                    println!(
-                        "  {} {} {}",
-                        "+".green(),
-                        balance.asset,
-                        format_quantity(balance.total())
-                    );
-                }
+        balances.extend(result?);
+        covered.insert(provider.native_asset().to_string());
+        covered.extend(tokens.into_iter().map(|t| t.symbol));
+    }
+
+    if !bitcoin.is_empty() {
+        let spinner = human.then(|| start_spinner(&format!("Reading {} Bitcoin address(es) and key(s)...", bitcoin.len())));
+        let result = read_bitcoin_balance(config, &bitcoin).await;
+        if let Some(pb) = &spinner {
+            pb.finish_and_clear();
+        }
+
+        balances.push(("BTC".to_string(), btc::sats_to_btc(result?)));
+        covered.insert("BTC".to_string());
+    }
+
+    Ok(FetchedBalances {
+        balances,
+        covered: Some(covered),
+    })
+}
+
+/// Confirmed satoshis across Bitcoin addresses and xpubs, read from the
+/// Electrum server when one is configured and reachable, otherwise from the
+/// block explorer
+async fn read_bitcoin_balance(config: &AppConfig, entries: &[String]) -> Result<u64> {
+    let gap_limit = u32::from(config.bitcoin.gap_limit);
+
+    if let Some(url) = &config.bitcoin.electrum_url {
+        match ElectrumClient::connect(url).await {
+            Ok(client) => return btc::confirmed_balance(&client, entries, gap_limit).await,
+            // Unreachable server: fall back to the explorer
+            Err(CryptofolioError::ExchangeApi(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let client = EsploraClient::new(&config.bitcoin.explorer_url);
+    btc::confirmed_balance(&client, entries, gap_limit).await
+}
+
+/// Total native and token balances across addresses, native coin first
+async fn read_address_balances(
+    provider: &dyn BlockchainProvider,
+    addresses: &[String],
+    tokens: &[TokenContract],
+) -> Result<Vec<(String, Decimal)>> {
+    let mut totals: Vec<(String, Decimal)> = Vec::new();
+    let mut add = |asset: &str, quantity: Decimal| match totals.iter_mut().find(|(a, _)| a == asset) {
+        Some((_, total)) => *total += quantity,
+        None => totals.push((asset.to_string(), quantity)),
+    };
+
+    for address in addresses {
+        add(provider.native_asset(), provider.get_native_balance(address).await?);
+
+        for balance in provider.get_token_balances(address, tokens).await? {
+            add(&balance.asset, balance.quantity);
+        }
+    }
+
+    Ok(totals)
+}
+
+/// Replace an account's holdings with the balances reported by its exchange or blockchain
+async fn sync_account(
+    acc: &Account,
+    config: &AppConfig,
+    log_id: i64,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<SyncResult> {
+    let holding_repo = HoldingRepository::new(pool);
+    // JSON mode replaces all human-readable progress with events
+    let human = !opts.quiet && !opts.json;
+
+    let fetched = fetch_balances(acc, config, human, pool, opts).await?;
+    let excluded_assets = acc.config.excluded_assets();
+
+    if opts.json {
+        let assets = fetched
+            .balances
+            .iter()
+            .filter(|(asset, qty)| *qty > Decimal::ZERO && !excluded_assets.contains(&asset.to_uppercase()))
+            .map(|(asset, _)| asset.clone())
+            .collect();
+        emit(&SyncEvent::Start { account: &acc.name, assets })?;
+    }
+
+    if opts.verbose && human {
+        for (asset, _) in fetched.balances.iter().filter(|(a, _)| excluded_assets.contains(&a.to_uppercase())) {
+            println!("  {} {} (excluded)", "-".dimmed(), asset);
+        }
+    }
+
+    // Remember what was there to report what the sync changed
+    let previous = local_quantities(&holding_repo, &acc.id).await?;
+    let plan = plan_sync(&fetched, &excluded_assets, &previous);
+
+    // Upsert in place so holdings keep their ids (and tags) across syncs
+    let mut synced_count = 0;
+    let mut counts = SyncCounts::default();
+    let mut changes = Vec::new();
+
+    for planned in plan {
+        let change = SyncChange {
+            asset: planned.asset.to_uppercase(),
+            previous_quantity: planned.local.unwrap_or(Decimal::ZERO),
+            new_quantity: planned.live,
+        };
+
+        // Drop holdings the source no longer reports
+        if planned.action == SyncAction::Remove {
+            holding_repo.delete(&acc.id, &change.asset).await?;
+            holding_repo
+                .record_change(&acc.id, &change.asset, change.previous_quantity, Decimal::ZERO, HoldingChangeSource::Sync, Some(log_id))
+                .await?;
+            counts.removed += 1;
+            changes.push(change);
+            continue;
+        }
+
+        let holding = Holding {
+            id: 0,
+            account_id: acc.id.clone(),
+            asset: planned.asset.clone(),
+            quantity: planned.live,
+            avg_cost_basis: None, // Balances don't carry cost basis
+            cost_basis_currency: None,
+            avg_cost_basis_base: None,
+            updated_at: Utc::now(),
+            acquired_at: None,
+        };
+
+        holding_repo.upsert(&holding).await?;
+        synced_count += 1;
+
+        if planned.action == SyncAction::Set {
+            if planned.local.is_none() {
+                counts.added += 1;
+            } else {
+                counts.updated += 1;
             }
+            holding_repo
+                .record_change(
+                    &acc.id,
+                    &change.asset,
+                    change.previous_quantity,
+                    change.new_quantity,
+                    HoldingChangeSource::Sync,
+                    Some(log_id),
+                )
+                .await?;
+            changes.push(change);
         }
 
-        if !opts.quiet {
-            success(&format!("Synced {} assets from '{}'", synced_count, acc.name));
+        if opts.json {
+            emit(&SyncEvent::Balance {
+                asset: &planned.asset,
+                qty: planned.live.to_string(),
+            })?;
+        } else if human {
+            println!(
+                "  {} {} {}",
+                "+".green(),
+                planned.asset,
+                format_quantity(planned.live)
+            );
+        }
+    }
+
+    if human {
+        success(&format!("Synced {} assets from '{}'", synced_count, acc.name));
+    }
+
+    changes.sort_by(|a, b| a.asset.cmp(&b.asset));
+    Ok(SyncResult { counts, changes })
+}
+
+/// Print a before/after table of the quantities a sync changed
+fn print_changes(changes: &[SyncChange]) {
+    if changes.is_empty() {
+        println!("  No changes.");
+        return;
+    }
+
+    print_header(&[("Asset", 10), ("Before", 18), ("After", 18), ("Change", 18)]);
+    for change in changes {
+        print_row(&[
+            (&change.asset, 10),
+            (&format_quantity(change.previous_quantity), 18),
+            (&format_quantity(change.new_quantity), 18),
+            (&format_delta(change.delta()), 18),
+        ]);
+    }
+}
+
+/// A quantity change with its sign, `+0.5` or `-0.5`
+fn format_delta(delta: Decimal) -> String {
+    let sign = if delta.is_sign_negative() { "-" } else { "+" };
+    format!("{}{}", sign, format_quantity(delta.abs()))
+}
+
+/// Show past sync runs, most recent first
+async fn show_sync_log(account: Option<String>, limit: i64, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let account_repo = AccountRepository::new(pool);
+
+    let account_id = match account {
+        Some(name) => Some(account_repo.require_account(&name).await?.id),
+        None => None,
+    };
+
+    let entries = SyncLogRepository::new(pool).list(account_id.as_deref(), limit).await?;
+
+    let account_names = account_names(&account_repo).await?;
+    let name_of = |id: &str| account_names.get(id).cloned().unwrap_or_else(|| "-".to_string());
+
+    if opts.json {
+        let output: Vec<SyncLogOutput> = entries
+            .iter()
+            .map(|e| SyncLogOutput {
+                id: e.id,
+                account: name_of(&e.account_id),
+                account_id: e.account_id.clone(),
+                started_at: e.started_at.to_rfc3339(),
+                completed_at: e.completed_at.map(|d| d.to_rfc3339()),
+                holdings_updated: e.holdings_updated,
+                holdings_added: e.holdings_added,
+                holdings_removed: e.holdings_removed,
+                error_message: e.error_message.clone(),
+                status: e.status.clone(),
+            })
+            .collect();
+        return print_json(&output);
+    }
+
+    if entries.is_empty() {
+        println!("No sync runs recorded.");
+        return Ok(());
+    }
+
+    print_header(&[("Started", 20), ("Account", 16), ("Status", 8), ("Added", 6), ("Updated", 8), ("Removed", 8)]);
+
+    for e in &entries {
+        print_row(&[
+            (&e.started_at.format("%Y-%m-%d %H:%M:%S").to_string(), 20),
+            (&name_of(&e.account_id), 16),
+            (&e.status, 8),
+            (&e.holdings_added.to_string(), 6),
+            (&e.holdings_updated.to_string(), 8),
+            (&e.holdings_removed.to_string(), 8),
+        ]);
+
+        if let Some(ref message) = e.error_message {
+            println!("  {}", message.red());
         }
     }
 
     Ok(())
 }
+
+/// Show past sync runs with the holding changes each one made, most recent first
+async fn show_sync_history(account: Option<String>, limit: i64, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    let account_repo = AccountRepository::new(pool);
+    let sync_log = SyncLogRepository::new(pool);
+
+    let account_id = match account {
+        Some(name) => Some(account_repo.require_account(&name).await?.id),
+        None => None,
+    };
+
+    let mut runs: Vec<(SyncLogEntry, Vec<SyncChange>)> = Vec::new();
+    for entry in sync_log.list(account_id.as_deref(), limit).await? {
+        let changes = sync_log.list_changes(entry.id).await?;
+        runs.push((entry, changes));
+    }
+
+    let account_names = account_names(&account_repo).await?;
+    let name_of = |id: &str| account_names.get(id).cloned().unwrap_or_else(|| "-".to_string());
+
+    if opts.json {
+        let output: Vec<SyncHistoryOutput> = runs
+            .iter()
+            .map(|(e, changes)| SyncHistoryOutput {
+                id: e.id,
+                account: name_of(&e.account_id),
+                account_id: e.account_id.clone(),
+                started_at: e.started_at.to_rfc3339(),
+                status: e.status.clone(),
+                changes: changes.iter().map(SyncChangeOutput::from).collect(),
+            })
+            .collect();
+        return print_json(&output);
+    }
+
+    if runs.is_empty() {
+        println!("No sync runs recorded.");
+        return Ok(());
+    }
+
+    for (e, changes) in &runs {
+        println!(
+            "{}  {}  {}",
+            e.started_at.format("%Y-%m-%d %H:%M:%S").to_string().bold(),
+            name_of(&e.account_id),
+            e.status.dimmed()
+        );
+
+        match e.error_message {
+            Some(ref message) => println!("  {}", message.red()),
+            None => print_changes(changes),
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+async fn account_names(account_repo: &AccountRepository<'_>) -> Result<HashMap<String, String>> {
+    Ok(account_repo
+        .list_accounts(&ListAccountsFilter::default())
+        .await?
+        .into_iter()
+        .map(|a| (a.id, a.name))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_sync_compares_balances_with_holdings() {
+        let fetched = FetchedBalances {
+            balances: vec![
+                ("BTC".to_string(), Decimal::ONE),
+                ("eth".to_string(), Decimal::TWO),
+                ("SOL".to_string(), Decimal::ZERO),
+                ("BNB".to_string(), Decimal::TEN),
+            ],
+            covered: Some(HashSet::from(["BTC".to_string(), "ETH".to_string(), "SOL".to_string(), "BNB".to_string()])),
+        };
+        let previous = HashMap::from([
+            ("BTC".to_string(), Decimal::ONE),
+            ("ETH".to_string(), Decimal::ONE),
+            ("SOL".to_string(), Decimal::TEN),
+            // Not covered by the source, so left alone
+            ("ADA".to_string(), Decimal::ONE),
+        ]);
+
+        let plan = plan_sync(&fetched, &["BNB".to_string()], &previous);
+        let actions: Vec<(&str, SyncAction)> = plan.iter().map(|p| (p.asset.as_str(), p.action)).collect();
+        assert_eq!(
+            actions,
+            vec![("BTC", SyncAction::NoChange), ("eth", SyncAction::Set), ("SOL", SyncAction::Remove)]
+        );
+        assert_eq!(plan[1].local, Some(Decimal::ONE));
+        assert_eq!(plan[2].live, Decimal::ZERO);
+    }
+}
@@ -0,0 +1,212 @@
+use colored::Colorize;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::cli::output::{format_currency, format_pnl, format_quantity, without_color, write_output};
+use crate::cli::{GlobalOptions, OutputFormat, TaxCommands};
+use crate::config::AppConfig;
+use crate::core::pnl::CostBasisMethod;
+use crate::core::tax::{HoldingTerm, TaxReport};
+use crate::db::{AccountRepository, ListAccountsFilter, RealizedGainRepository};
+use crate::error::{CryptofolioError, Result};
+
+/// Columns of `tax report --format csv`, in the order of Form 8949
+const TAX_CSV_HEADERS: &[&str] = &[
+    "description",
+    "date_acquired",
+    "date_sold",
+    "proceeds",
+    "cost_basis",
+    "gain_or_loss",
+    "term",
+    "account",
+    "asset",
+    "quantity",
+];
+
+#[derive(Serialize)]
+struct TaxReportOutput {
+    year: i32,
+    method: String,
+    total_proceeds: String,
+    total_cost_basis: String,
+    total_short_term_gain: String,
+    total_long_term_gain: String,
+    total_gain: String,
+    lots: Vec<TaxLotOutput>,
+}
+
+#[derive(Serialize)]
+struct TaxLotOutput {
+    account_name: String,
+    asset: String,
+    quantity: String,
+    acquired_at: Option<String>,
+    disposed_at: String,
+    proceeds: String,
+    cost_basis: String,
+    gain: String,
+    holding_period_days: Option<i64>,
+    term: HoldingTerm,
+}
+
+pub async fn handle_tax_command(command: TaxCommands, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
+    match command {
+        TaxCommands::Report { year, method, format, output_file } => {
+            let method = CostBasisMethod::parse(&method).ok_or_else(|| {
+                CryptofolioError::InvalidInput(format!(
+                    "Invalid cost basis method: {}. Use fifo, lifo, hifo, or average",
+                    method
+                ))
+            })?;
+
+            let lots = RealizedGainRepository::new(pool).report(method, None, None).await?;
+            let report = TaxReport::from_lot_report(year, &lots);
+
+            let account_names: HashMap<String, String> = AccountRepository::new(pool)
+                .list_accounts(&ListAccountsFilter::default())
+                .await?
+                .into_iter()
+                .map(|a| (a.id, a.name))
+                .collect();
+            let account_name = |id: &str| account_names.get(id).cloned().unwrap_or_else(|| id.to_string());
+
+            let format = if opts.json { OutputFormat::Json } else { format.unwrap_or(OutputFormat::Table) };
+            let out = match format {
+                OutputFormat::Json => render_tax_json(&report, &account_name)?,
+                OutputFormat::Csv => render_tax_csv(&report, &account_name)?,
+                OutputFormat::Table => {
+                    let with_color = AppConfig::load()?.display.color && output_file.is_none();
+                    let mut out = String::new();
+                    without_color(output_file.is_some(), || {
+                        render_tax_table(&mut out, &report, &account_name, with_color)
+                    })
+                    .map_err(|e| CryptofolioError::Other(e.to_string()))?;
+                    out
+                }
+            };
+
+            write_output(&out, output_file.as_deref(), false, opts.quiet)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn render_tax_json(report: &TaxReport, account_name: &dyn Fn(&str) -> String) -> Result<String> {
+    let output = TaxReportOutput {
+        year: report.year,
+        method: report.method.as_str().to_string(),
+        total_proceeds: report.total_proceeds.to_string(),
+        total_cost_basis: report.total_cost_basis.to_string(),
+        total_short_term_gain: report.total_short_term_gain.to_string(),
+        total_long_term_gain: report.total_long_term_gain.to_string(),
+        total_gain: report.total_gain().to_string(),
+        lots: report
+            .lots
+            .iter()
+            .map(|l| TaxLotOutput {
+                account_name: account_name(&l.account_id),
+                asset: l.asset.clone(),
+                quantity: l.quantity.to_string(),
+                acquired_at: l.acquired_at.map(|d| d.to_rfc3339()),
+                disposed_at: l.disposed_at.to_rfc3339(),
+                proceeds: l.proceeds.to_string(),
+                cost_basis: l.cost_basis.to_string(),
+                gain: l.gain.to_string(),
+                holding_period_days: l.holding_period_days,
+                term: l.term,
+            })
+            .collect(),
+    };
+    let mut json = serde_json::to_string_pretty(&output)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// One row per lot piece; amounts are rounded to cents, as tax forms expect
+fn render_tax_csv(report: &TaxReport, account_name: &dyn Fn(&str) -> String) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(TAX_CSV_HEADERS)?;
+
+    for l in &report.lots {
+        let quantity = l.quantity.normalize().to_string();
+        writer.write_record([
+            format!("{} {}", quantity, l.asset),
+            // Average cost has no single acquisition date
+            l.acquired_at.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "VARIOUS".to_string()),
+            l.disposed_at.format("%Y-%m-%d").to_string(),
+            format!("{:.2}", l.proceeds),
+            format!("{:.2}", l.cost_basis),
+            format!("{:.2}", l.gain),
+            l.term.as_str().to_string(),
+            account_name(&l.account_id),
+            l.asset.clone(),
+            quantity,
+        ])?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| CryptofolioError::Other(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| CryptofolioError::Other(e.to_string()))
+}
+
+fn render_tax_table(
+    out: &mut String,
+    report: &TaxReport,
+    account_name: &dyn Fn(&str) -> String,
+    with_color: bool,
+) -> std::fmt::Result {
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{} {} ({})",
+        "CAPITAL GAINS".bold(),
+        report.year,
+        report.method.as_str().to_uppercase()
+    )?;
+    writeln!(out, "{}", "=".repeat(70))?;
+    writeln!(out)?;
+
+    if report.lots.is_empty() {
+        writeln!(out, "  No sales found in {}.", report.year)?;
+        writeln!(out)?;
+        return Ok(());
+    }
+
+    writeln!(out, "{}", "-".repeat(118))?;
+    writeln!(
+        out,
+        "  {:10}  {:10}  {:8}  {:>14}  {:>12}  {:>12}  {:>14}  {:5}  Account",
+        "Sold", "Acquired", "Asset", "Quantity", "Proceeds", "Cost Basis", "Gain/Loss", "Term"
+    )?;
+    writeln!(out, "{}", "-".repeat(118))?;
+
+    for l in &report.lots {
+        writeln!(
+            out,
+            "  {:10}  {:10}  {:8}  {:>14}  {:>12}  {:>12}  {:>14}  {:5}  {}",
+            l.disposed_at.format("%Y-%m-%d"),
+            l.acquired_at.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "various".to_string()),
+            l.asset,
+            format_quantity(l.quantity),
+            format_currency(l.proceeds),
+            format_currency(l.cost_basis),
+            format_pnl(l.gain, with_color),
+            l.term.as_str(),
+            account_name(&l.account_id),
+        )?;
+    }
+
+    writeln!(out, "{}", "-".repeat(118))?;
+    writeln!(out)?;
+    writeln!(out, "  Proceeds:         {}", format_currency(report.total_proceeds))?;
+    writeln!(out, "  Cost basis:       {}", format_currency(report.total_cost_basis))?;
+    writeln!(out, "  Short-term gain:  {}", format_pnl(report.total_short_term_gain, with_color))?;
+    writeln!(out, "  Long-term gain:   {}", format_pnl(report.total_long_term_gain, with_color))?;
+    writeln!(out, "  Total gain:       {}", format_pnl(report.total_gain(), with_color))?;
+    writeln!(out)?;
+
+    Ok(())
+}
@@ -1,19 +1,30 @@
 use chrono::{DateTime, Utc};
+use colored::Colorize;
 use rust_decimal::Decimal;
+use schemars::JsonSchema;
 use serde::Serialize;
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
 use std::fs::File;
+use std::io::{self, Write};
 use std::str::FromStr;
 
 use crate::cli::{TxCommands, GlobalOptions};
-use crate::cli::output::{format_quantity, format_usd, info, print_header, print_row, success};
-use crate::core::transaction::Transaction;
-use crate::core::currency::ExchangeRate;
-use crate::db::{AccountRepository, HoldingRepository, TransactionRepository, currencies};
+use crate::cli::output::{
+    colors_enabled, format_currency, format_page_summary, format_quantity, info, page_count, print_header, print_json,
+    print_row, success,
+};
+use crate::config::AppConfig;
+use crate::core::account::Account;
+use crate::core::transaction::{all_in_cost_per_unit, spot_swap_quantity, HoldingChange, Transaction};
+use crate::core::currency::{is_usd_equivalent, ExchangeRate};
+use crate::core::replay::PortfolioReplay;
+use crate::db::{AccountRepository, HoldingRepository, TransactionFilter, TransactionRepository, TransactionSearchQuery, currencies};
 use crate::error::{CryptofolioError, Result};
+use crate::exchange::{BinanceClient, Exchange, FiatRateProvider, FrankfurterClient};
+use crate::export::exporter_for;
 
-#[derive(Serialize)]
-struct TransactionOutput {
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct TransactionOutput {
     id: i64,
     timestamp: String,
     tx_type: String,
@@ -26,22 +37,28 @@ struct TransactionOutput {
     price_usd: Option<String>,
     fee: Option<String>,
     fee_asset: Option<String>,
+    external_id: Option<String>,
     notes: Option<String>,
+    created_at: String,
 }
 
+/// `tx list --page` output: one page of transactions and where it sits
 #[derive(Serialize)]
-struct CsvExportRecord {
-    date: String,
-    #[serde(rename = "type")]
-    tx_type: String,
-    asset: String,
-    quantity: String,
-    price_usd: String,
-    fee: String,
-    fee_asset: String,
-    notes: String,
-    to_asset: String,
-    to_quantity: String,
+struct TransactionPageOutput {
+    transactions: Vec<TransactionOutput>,
+    total: u64,
+    page: u32,
+    page_size: u32,
+}
+
+/// Transactions per page when only `--page` is given
+const DEFAULT_PAGE_SIZE: u32 = 50;
+
+#[derive(Serialize)]
+struct RunningBalanceOutput {
+    #[serde(flatten)]
+    transaction: TransactionOutput,
+    portfolio_value_after: Option<String>,
 }
 
 pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &GlobalOptions) -> Result<()> {
@@ -51,15 +68,48 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
     let tx_repo = TransactionRepository::new(pool);
 
     match command {
-        TxCommands::List { account, limit } => {
-            let transactions = if let Some(account_name) = account {
-                let acc = account_repo.get_account(&account_name).await?
-                    .ok_or_else(|| CryptofolioError::AccountNotFound(account_name.clone()))?;
-                tx_repo.list_by_account(&acc.id, Some(limit)).await?
-            } else {
-                tx_repo.list(Some(limit)).await?
+        TxCommands::List {
+            account,
+            limit,
+            tx_type,
+            asset,
+            from,
+            to,
+            running_balance,
+            search,
+            case_sensitive,
+            page,
+            page_size,
+        } => {
+            if running_balance {
+                return handle_running_balance_list(account, limit, pool, opts).await;
+            }
+
+            let account_id = match account {
+                Some(account_name) => Some(account_repo.require_account(&account_name).await?.id),
+                None => None,
             };
 
+            let filter = TransactionFilter {
+                account_id,
+                tx_types: tx_type.iter().flat_map(|t| t.transaction_types().iter().copied()).collect(),
+                asset,
+                from: from.as_deref().map(parse_date_filter).transpose()?,
+                to: to.as_deref().map(parse_date_filter).transpose()?,
+                notes: search.clone(),
+                case_sensitive,
+                limit: Some(limit),
+            };
+
+            if page.is_some() || page_size.is_some() {
+                let page = page.unwrap_or(1);
+                let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+                return list_transaction_page(&filter, page, page_size, search.as_deref(), case_sensitive, pool, opts)
+                    .await;
+            }
+
+            let transactions = tx_repo.list(&filter).await?;
+
             if transactions.is_empty() {
                 if opts.json {
                     println!("[]");
@@ -70,43 +120,41 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
             }
 
             if opts.json {
-                let output: Vec<TransactionOutput> = transactions.iter().map(|tx| TransactionOutput {
-                    id: tx.id,
-                    timestamp: tx.timestamp.to_rfc3339(),
-                    tx_type: tx.tx_type.display_name().to_string(),
-                    from_account_id: tx.from_account_id.clone(),
-                    to_account_id: tx.to_account_id.clone(),
-                    from_asset: tx.from_asset.clone(),
-                    from_quantity: tx.from_quantity.map(|q| q.to_string()),
-                    to_asset: tx.to_asset.clone(),
-                    to_quantity: tx.to_quantity.map(|q| q.to_string()),
-                    price_usd: tx.price_usd.map(|p| p.to_string()),
-                    fee: tx.fee.map(|f| f.to_string()),
-                    fee_asset: tx.fee_asset.clone(),
-                    notes: tx.notes.clone(),
-                }).collect();
+                let output: Vec<TransactionOutput> = transactions.iter().map(transaction_list_output).collect();
                 println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
             } else {
-                print_header(&[("Date", 12), ("Type", 10), ("Asset", 8), ("Quantity", 14), ("Price", 12)]);
-
-                for tx in transactions {
-                    let date = tx.timestamp.format("%Y-%m-%d").to_string();
-                    let asset = tx.to_asset.or(tx.from_asset).unwrap_or_else(|| "-".to_string());
-                    let qty = tx.to_quantity.or(tx.from_quantity)
-                        .map(|q| format_quantity(q))
-                        .unwrap_or_else(|| "-".to_string());
-                    let price = tx.price_usd
-                        .map(|p| format_usd(p))
-                        .unwrap_or_else(|| "-".to_string());
-
-                    print_row(&[
-                        (&date, 12),
-                        (tx.tx_type.display_name(), 10),
-                        (&asset, 8),
-                        (&qty, 14),
-                        (&price, 12),
-                    ]);
-                }
+                print_transaction_list(&transactions, search.as_deref(), case_sensitive);
+            }
+        }
+
+        TxCommands::Search { query, notes, asset, account, tx_type, from, to, limit } => {
+            let account_id = match account {
+                Some(account_name) => Some(account_repo.require_account(&account_name).await?.id),
+                None => None,
+            };
+
+            let search = TransactionSearchQuery {
+                text: query,
+                filter: TransactionFilter {
+                    account_id,
+                    tx_types: tx_type.iter().flat_map(|t| t.transaction_types().iter().copied()).collect(),
+                    asset,
+                    from: from.as_deref().map(parse_date_filter).transpose()?,
+                    to: to.as_deref().map(parse_date_filter).transpose()?,
+                    notes,
+                    case_sensitive: false,
+                    limit: Some(limit),
+                },
+            };
+            let transactions = tx_repo.search(&search).await?;
+
+            if opts.json {
+                let output: Vec<TransactionOutput> = transactions.iter().map(transaction_list_output).collect();
+                println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+            } else if transactions.is_empty() {
+                println!("No transactions found.");
+            } else {
+                print_transaction_table(&transactions);
             }
         }
 
@@ -115,44 +163,38 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
             quantity,
             account,
             price,
+            fee,
+            fee_asset,
             notes,
+            allow_high_precision,
             dry_run,
         } => {
-            let acc = account_repo.get_account(&account).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(account.clone()))?;
-
-            let qty = Decimal::from_str(&quantity)
-                .map_err(|_| CryptofolioError::InvalidAmount(quantity.clone()))?;
-
-            let price_usd = Decimal::from_str(&price)
-                .map_err(|_| CryptofolioError::InvalidAmount(price.clone()))?;
+            let max_decimals = precision_limit(allow_high_precision)?;
+            let args = TradeArgs { asset, quantity, account, price, fee, fee_asset, notes, max_decimals };
+            let trade = parse_trade(args, pool, opts).await?;
 
             if dry_run {
                 info(&format!(
-                    "[DRY RUN] Would record buy: {} {} @ {} in '{}' (total: {})",
-                    format_quantity(qty),
-                    asset.to_uppercase(),
-                    format_usd(price_usd),
-                    account,
-                    format_usd(qty * price_usd)
+                    "[DRY RUN] Would record buy: {} {} @ {} in '{}' (total: {}){}",
+                    format_quantity(trade.qty),
+                    trade.asset.to_uppercase(),
+                    format_currency(trade.price_usd),
+                    trade.account.name,
+                    format_currency(trade.qty * trade.cost_per_unit()),
+                    format_fee_note(&trade.fee, trade.fee_usd)
                 ));
                 return Ok(());
             }
 
-            // Update holdings
-            holding_repo.add_quantity(&acc.id, &asset, qty, Some(price_usd)).await?;
-
-            // Record transaction
-            let mut tx = Transaction::new_buy(&acc.id, &asset, qty, price_usd, Utc::now());
-            tx.notes = notes;
-            tx_repo.insert(&tx).await?;
+            record_buy(&trade, pool).await?;
 
             success(&format!(
-                "Recorded buy: {} {} @ {} in '{}'",
-                format_quantity(qty),
-                asset.to_uppercase(),
-                format_usd(price_usd),
-                account
+                "Recorded buy: {} {} @ {} in '{}'{}",
+                format_quantity(trade.qty),
+                trade.asset.to_uppercase(),
+                format_currency(trade.price_usd),
+                trade.account.name,
+                format_fee_note(&trade.fee, trade.fee_usd)
             ));
         }
 
@@ -161,44 +203,38 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
             quantity,
             account,
             price,
+            fee,
+            fee_asset,
             notes,
+            allow_high_precision,
             dry_run,
         } => {
-            let acc = account_repo.get_account(&account).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(account.clone()))?;
-
-            let qty = Decimal::from_str(&quantity)
-                .map_err(|_| CryptofolioError::InvalidAmount(quantity.clone()))?;
-
-            let price_usd = Decimal::from_str(&price)
-                .map_err(|_| CryptofolioError::InvalidAmount(price.clone()))?;
+            let max_decimals = precision_limit(allow_high_precision)?;
+            let args = TradeArgs { asset, quantity, account, price, fee, fee_asset, notes, max_decimals };
+            let trade = parse_trade(args, pool, opts).await?;
 
             if dry_run {
                 info(&format!(
-                    "[DRY RUN] Would record sell: {} {} @ {} from '{}' (total: {})",
-                    format_quantity(qty),
-                    asset.to_uppercase(),
-                    format_usd(price_usd),
-                    account,
-                    format_usd(qty * price_usd)
+                    "[DRY RUN] Would record sell: {} {} @ {} from '{}' (total: {}){}",
+                    format_quantity(trade.qty),
+                    trade.asset.to_uppercase(),
+                    format_currency(trade.price_usd),
+                    trade.account.name,
+                    format_currency(trade.qty * trade.price_usd - trade.fee_usd.unwrap_or(Decimal::ZERO)),
+                    format_fee_note(&trade.fee, trade.fee_usd)
                 ));
                 return Ok(());
             }
 
-            // Update holdings
-            holding_repo.remove_quantity(&acc.id, &asset, qty).await?;
-
-            // Record transaction
-            let mut tx = Transaction::new_sell(&acc.id, &asset, qty, price_usd, Utc::now());
-            tx.notes = notes;
-            tx_repo.insert(&tx).await?;
+            record_sell(&trade, pool).await?;
 
             success(&format!(
-                "Recorded sell: {} {} @ {} from '{}'",
-                format_quantity(qty),
-                asset.to_uppercase(),
-                format_usd(price_usd),
-                account
+                "Recorded sell: {} {} @ {} from '{}'{}",
+                format_quantity(trade.qty),
+                trade.asset.to_uppercase(),
+                format_currency(trade.price_usd),
+                trade.account.name,
+                format_fee_note(&trade.fee, trade.fee_usd)
             ));
         }
 
@@ -209,21 +245,19 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
             to,
             fee,
             notes,
+            allow_high_precision,
             dry_run,
         } => {
-            let from_acc = account_repo.get_account(&from).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(from.clone()))?;
+            let from_acc = account_repo.require_account(&from).await?;
 
-            let to_acc = account_repo.get_account(&to).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(to.clone()))?;
+            let to_acc = account_repo.require_account(&to).await?;
 
-            let qty = Decimal::from_str(&quantity)
-                .map_err(|_| CryptofolioError::InvalidAmount(quantity.clone()))?;
+            let max_decimals = precision_limit(allow_high_precision)?;
+            let qty = parse_decimal_input(&quantity, max_decimals)?;
 
             let fee_amount = fee
-                .map(|f| Decimal::from_str(&f))
-                .transpose()
-                .map_err(|_| CryptofolioError::InvalidAmount("fee".to_string()))?;
+                .map(|f| parse_decimal_input(&f, max_decimals))
+                .transpose()?;
 
             if dry_run {
                 let fee_str = fee_amount
@@ -253,7 +287,7 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
                 qty
             };
 
-            holding_repo.add_quantity(&to_acc.id, &asset, transfer_qty, holding.avg_cost_basis).await?;
+            holding_repo.add_quantity(&to_acc.id, &asset, transfer_qty, holding.avg_cost_basis, holding.acquired_at).await?;
 
             // Record transaction
             let mut tx = Transaction::new_transfer(&from_acc.id, &to_acc.id, &asset, qty, Utc::now());
@@ -278,17 +312,45 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
             to_quantity,
             account,
             rate,
+            fetch_rate,
+            use_spot_rate: _, // clap ensures to_quantity is None exactly when this is set
             notes,
+            yes,
+            allow_high_precision,
             dry_run,
         } => {
-            let acc = account_repo.get_account(&account).await?
-                .ok_or_else(|| CryptofolioError::AccountNotFound(account.clone()))?;
-
-            let from_qty = Decimal::from_str(&from_quantity)
-                .map_err(|_| CryptofolioError::InvalidAmount(from_quantity.clone()))?;
-
-            let to_qty = Decimal::from_str(&to_quantity)
-                .map_err(|_| CryptofolioError::InvalidAmount(to_quantity.clone()))?;
+            let acc = account_repo.require_account(&account).await?;
+
+            let max_decimals = precision_limit(allow_high_precision)?;
+            let from_qty = parse_decimal_input(&from_quantity, max_decimals)?;
+
+            let to_qty = match to_quantity {
+                Some(q) => parse_decimal_input(&q, max_decimals)?,
+                None => {
+                    let to_qty = spot_to_quantity(&from_asset, from_qty, &to_asset, opts).await?;
+
+                    if !yes && !dry_run {
+                        print!(
+                            "Record swap of {} {} -> {} {} at the current spot rate? [y/N] ",
+                            format_quantity(from_qty),
+                            from_asset.to_uppercase(),
+                            format_quantity(to_qty),
+                            to_asset.to_uppercase()
+                        );
+                        io::stdout().flush()?;
+
+                        let mut input = String::new();
+                        io::stdin().read_line(&mut input)?;
+
+                        if !input.trim().eq_ignore_ascii_case("y") {
+                            println!("Cancelled.");
+                            return Ok(());
+                        }
+                    }
+
+                    to_qty
+                }
+            };
 
             if dry_run {
                 info(&format!(
@@ -309,12 +371,21 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
             let is_fiat_swap = from_currency.as_ref().map(|c| c.is_fiat()).unwrap_or(false)
                             && to_currency.as_ref().map(|c| c.is_fiat()).unwrap_or(false);
 
+            if fetch_rate && !is_fiat_swap {
+                return Err(CryptofolioError::InvalidInput(
+                    "--fetch-rate only applies to swaps between fiat currencies".to_string(),
+                ));
+            }
+
             // Calculate and store exchange rate for fiat swaps
             let (exchange_rate, exchange_rate_pair) = if is_fiat_swap {
-                // Use manual rate if provided, otherwise calculate from quantities
+                let provider = FrankfurterClient::new();
+                // Use manual rate if provided, then a fetched one, otherwise calculate from quantities
                 let rate_value = if let Some(ref manual_rate) = rate {
                     Decimal::from_str(manual_rate)
                         .map_err(|_| CryptofolioError::InvalidInput(format!("Invalid rate: {}", manual_rate)))?
+                } else if fetch_rate {
+                    provider.get_rate(&from_asset, &to_asset).await?
                 } else if to_qty > Decimal::ZERO {
                     from_qty / to_qty
                 } else {
@@ -324,12 +395,15 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
                 let pair = format!("{}/{}", from_asset.to_uppercase(), to_asset.to_uppercase());
 
                 // Store the exchange rate for future reference
-                let rate_record = ExchangeRate::new_manual(
+                let mut rate_record = ExchangeRate::new_manual(
                     &from_asset.to_uppercase(),
                     &to_asset.to_uppercase(),
                     rate_value,
                     Utc::now()
                 );
+                if fetch_rate {
+                    rate_record.source = provider.name().to_string();
+                }
                 currencies::add_exchange_rate(pool, &rate_record).await?;
 
                 info(&format!(
@@ -356,7 +430,7 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
                 None
             };
 
-            holding_repo.add_quantity(&acc.id, &to_asset, to_qty, implied_price).await?;
+            holding_repo.add_quantity(&acc.id, &to_asset, to_qty, implied_price, None).await?;
 
             // Record transaction
             let mut tx = Transaction::new_swap(&acc.id, &from_asset, from_qty, &to_asset, to_qty, Utc::now());
@@ -375,15 +449,224 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
             ));
         }
 
+        TxCommands::StakeReward {
+            account,
+            asset,
+            amount,
+            price,
+            notes,
+            allow_high_precision,
+            dry_run,
+        } => {
+            let acc = account_repo.require_account(&account).await?;
+
+            let max_decimals = precision_limit(allow_high_precision)?;
+            let amount_dec = parse_decimal_input(&amount, max_decimals)?;
+            if amount_dec <= Decimal::ZERO {
+                return Err(CryptofolioError::InvalidAmount(amount));
+            }
+
+            let price_usd = match price {
+                Some(price) => parse_decimal_input(&price, max_decimals)?,
+                None => reward_price_usd(&asset, opts).await?,
+            };
+
+            if dry_run {
+                info(&format!(
+                    "[DRY RUN] Would record staking reward: {} {} @ {} to '{}' (value: {})",
+                    format_quantity(amount_dec),
+                    asset.to_uppercase(),
+                    format_currency(price_usd),
+                    account,
+                    format_currency(amount_dec * price_usd)
+                ));
+                return Ok(());
+            }
+
+            // Update holdings
+            holding_repo.add_quantity(&acc.id, &asset, amount_dec, Some(price_usd), None).await?;
+
+            // Record transaction
+            let mut tx = Transaction::new_staking_reward(&acc.id, &asset, amount_dec, Some(price_usd), Utc::now());
+            tx.notes = notes;
+            tx_repo.insert(&tx).await?;
+
+            success(&format!(
+                "Recorded staking reward: {} {} @ {} to '{}'",
+                format_quantity(amount_dec),
+                asset.to_uppercase(),
+                format_currency(price_usd),
+                account
+            ));
+        }
+
+        TxCommands::Fee {
+            account,
+            asset,
+            amount,
+            notes,
+            allow_high_precision,
+            dry_run,
+        } => {
+            let acc = account_repo.require_account(&account).await?;
+
+            let max_decimals = precision_limit(allow_high_precision)?;
+            let amount_dec = parse_decimal_input(&amount, max_decimals)?;
+            if amount_dec <= Decimal::ZERO {
+                return Err(CryptofolioError::InvalidAmount(amount));
+            }
+
+            if dry_run {
+                info(&format!(
+                    "[DRY RUN] Would record fee: {} {} from '{}'",
+                    format_quantity(amount_dec),
+                    asset.to_uppercase(),
+                    account
+                ));
+                return Ok(());
+            }
+
+            // Update holdings
+            holding_repo.remove_quantity(&acc.id, &asset, amount_dec).await?;
+
+            // Record transaction
+            let mut tx = Transaction::new_fee(&acc.id, &asset, amount_dec, Utc::now());
+            tx.notes = notes;
+            tx_repo.insert(&tx).await?;
+
+            success(&format!(
+                "Recorded fee: {} {} from '{}'",
+                format_quantity(amount_dec),
+                asset.to_uppercase(),
+                account
+            ));
+        }
+
         TxCommands::Export {
             file,
+            stdout,
+            format,
             account,
             asset,
             from,
             to,
             limit,
         } => {
-            handle_export_command(file, account, asset, from, to, limit, pool, opts).await?;
+            // `-` as the filename is the usual Unix shorthand for stdout
+            let file = if stdout { None } else { file.filter(|f| f != "-") };
+            handle_export_command(file, format, account, asset, from, to, limit, pool, opts).await?;
+        }
+
+        TxCommands::Delete {
+            id,
+            yes,
+            adjust_holdings,
+            dry_run,
+        } => {
+            let tx = tx_repo
+                .get_by_id(id)
+                .await?
+                .ok_or_else(|| CryptofolioError::NotFound(format!("Transaction #{}", id)))?;
+
+            // Reversing a transaction applies its holding changes in the opposite direction
+            let adjustments: Vec<HoldingChange> = if adjust_holdings {
+                tx.holding_changes()
+                    .into_iter()
+                    .map(|c| HoldingChange { quantity: -c.quantity, ..c })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            let mut account_names = std::collections::HashMap::new();
+            for account_id in tx.from_account_id.iter().chain(tx.to_account_id.iter()) {
+                let name = account_repo
+                    .get_account_by_id(account_id)
+                    .await?
+                    .map(|a| a.name)
+                    .unwrap_or_else(|| account_id.clone());
+                account_names.insert(account_id.clone(), name);
+            }
+
+            println!();
+            println!("{}", format!("Transaction #{}", tx.id).bold());
+            println!("  Date:     {}", tx.timestamp.format("%Y-%m-%d %H:%M:%S"));
+            println!("  Type:     {}", tx.tx_type.display_name());
+            if let (Some(account_id), Some(asset), Some(qty)) = (&tx.from_account_id, &tx.from_asset, tx.from_quantity) {
+                println!("  From:     {} {} ({})", format_quantity(qty), asset, account_names[account_id]);
+            }
+            if let (Some(account_id), Some(asset), Some(qty)) = (&tx.to_account_id, &tx.to_asset, tx.to_quantity) {
+                println!("  To:       {} {} ({})", format_quantity(qty), asset, account_names[account_id]);
+            }
+            if let Some(price) = tx.price_usd {
+                println!("  Price:    {}", format_currency(price));
+            }
+            if let Some(fee) = tx.fee {
+                println!("  Fee:      {} {}", format_quantity(fee), tx.fee_asset.as_deref().unwrap_or("USD"));
+            }
+            if let Some(ref notes) = tx.notes {
+                println!("  Notes:    {}", notes);
+            }
+            println!();
+
+            let describe = |c: &HoldingChange| {
+                let sign = if c.quantity.is_sign_negative() { "-" } else { "+" };
+                format!(
+                    "{}{} {} in '{}'",
+                    sign,
+                    format_quantity(c.quantity.abs()),
+                    c.asset,
+                    account_names.get(&c.account_id).unwrap_or(&c.account_id)
+                )
+            };
+
+            if dry_run {
+                info(&format!("[DRY RUN] Would delete transaction #{}", id));
+                for adjustment in &adjustments {
+                    info(&format!("[DRY RUN] Would adjust holdings: {}", describe(adjustment)));
+                }
+                return Ok(());
+            }
+
+            if !yes {
+                if adjustments.is_empty() {
+                    println!("Holdings will not be changed (use --adjust-holdings to reverse them).");
+                } else {
+                    println!("Holdings will be adjusted:");
+                    for adjustment in &adjustments {
+                        println!("  {}", describe(adjustment));
+                    }
+                }
+                print!("Delete this transaction? [y/N] ");
+                io::stdout().flush()?;
+
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+
+                if !input.trim().eq_ignore_ascii_case("y") {
+                    println!("Cancelled.");
+                    return Ok(());
+                }
+            }
+
+            // Remove quantities first so an insufficient balance fails before anything is added
+            for adjustment in adjustments.iter().filter(|c| c.quantity.is_sign_negative()) {
+                holding_repo
+                    .remove_quantity(&adjustment.account_id, &adjustment.asset, -adjustment.quantity)
+                    .await?;
+            }
+            for adjustment in adjustments.iter().filter(|c| c.quantity.is_sign_positive()) {
+                holding_repo
+                    .add_quantity(&adjustment.account_id, &adjustment.asset, adjustment.quantity, None, None)
+                    .await?;
+            }
+
+            tx_repo.delete_by_id(id).await?;
+
+            success(&format!("Deleted transaction #{}", id));
+            for adjustment in &adjustments {
+                success(&format!("Adjusted holdings: {}", describe(adjustment)));
+            }
         }
     }
 
@@ -391,7 +674,8 @@ pub async fn handle_tx_command(command: TxCommands, pool: &SqlitePool, opts: &Gl
 }
 
 async fn handle_export_command(
-    file: String,
+    file: Option<String>,
+    format: String,
     account_filter: Option<String>,
     asset_filter: Option<String>,
     from_date: Option<String>,
@@ -403,6 +687,13 @@ async fn handle_export_command(
     let account_repo = AccountRepository::new(pool);
     let tx_repo = TransactionRepository::new(pool);
 
+    let exporter = exporter_for(&format).ok_or_else(|| {
+        CryptofolioError::InvalidInput(format!(
+            "Unsupported export format: '{}'. Use csv, json, or sql",
+            format
+        ))
+    })?;
+
     // Parse date filters if provided
     let from_timestamp = if let Some(date_str) = from_date {
         Some(parse_date_filter(&date_str)?)
@@ -418,131 +709,607 @@ async fn handle_export_command(
 
     // Get account ID if filter specified
     let account_id = if let Some(account_name) = &account_filter {
-        let acc = account_repo.get_account(account_name).await?
-            .ok_or_else(|| CryptofolioError::AccountNotFound(account_name.clone()))?;
+        let acc = account_repo.require_account(account_name).await?;
         Some(acc.id)
     } else {
         None
     };
 
     // Fetch transactions
-    let mut transactions = if let Some(acc_id) = &account_id {
-        if limit > 0 {
-            tx_repo.list_by_account(acc_id, Some(limit)).await?
-        } else {
-            tx_repo.list_by_account(acc_id, None).await?
-        }
-    } else {
-        if limit > 0 {
-            tx_repo.list(Some(limit)).await?
-        } else {
-            tx_repo.list(None).await?
+    let filter = TransactionFilter {
+        account_id,
+        asset: asset_filter,
+        from: from_timestamp,
+        to: to_timestamp,
+        limit: (limit > 0).then_some(limit),
+        ..Default::default()
+    };
+    let transactions = tx_repo.list(&filter).await?;
+
+    // Piped output still gets a well-formed (empty) document
+    if transactions.is_empty() && file.is_some() {
+        if !opts.quiet {
+            info("No transactions match the specified filters");
         }
+        return Ok(());
+    }
+
+    // Status messages would corrupt piped output, so only show them for file exports
+    let show_status = file.is_some() && !opts.quiet;
+    let destination = file.clone().unwrap_or_else(|| "stdout".to_string());
+
+    if show_status {
+        info(&format!("Exporting {} transactions to '{}'...", transactions.len(), destination));
+    }
+
+    let mut writer: Box<dyn Write> = match &file {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
     };
 
-    // Apply filters
-    if let Some(from_ts) = from_timestamp {
-        transactions.retain(|tx| tx.timestamp >= from_ts);
+    exporter.export(&transactions, &mut writer)?;
+    writer.flush()?;
+
+    if file.is_some() {
+        success(&format!("Exported {} transactions to '{}'", transactions.len(), destination));
     }
 
-    if let Some(to_ts) = to_timestamp {
-        transactions.retain(|tx| tx.timestamp <= to_ts);
+    Ok(())
+}
+
+/// Parse `--fee`/`--fee-asset` into an amount and an uppercased asset (USD by default)
+/// A buy or sell as entered on the command line
+pub(crate) struct TradeArgs {
+    pub asset: String,
+    pub quantity: String,
+    pub account: String,
+    pub price: String,
+    pub fee: Option<String>,
+    pub fee_asset: Option<String>,
+    pub notes: Option<String>,
+    /// Most decimal places accepted in the amounts; `None` for no limit
+    pub max_decimals: Option<u32>,
+}
+
+/// A validated buy or sell, ready to record
+pub(crate) struct Trade {
+    account: Account,
+    asset: String,
+    qty: Decimal,
+    price_usd: Decimal,
+    fee: Option<(Decimal, String)>,
+    fee_usd: Option<Decimal>,
+    notes: Option<String>,
+}
+
+impl Trade {
+    /// Price per unit with the fee folded in, so P&L reflects the all-in cost
+    fn cost_per_unit(&self) -> Decimal {
+        all_in_cost_per_unit(self.qty, self.price_usd, self.fee_usd)
     }
 
-    if let Some(asset_sym) = &asset_filter {
-        let asset_upper = asset_sym.to_uppercase();
-        transactions.retain(|tx| {
-            tx.from_asset.as_ref().map(|a| a == &asset_upper).unwrap_or(false)
-                || tx.to_asset.as_ref().map(|a| a == &asset_upper).unwrap_or(false)
-        });
+    fn transaction(&self, mut tx: Transaction) -> Transaction {
+        if let Some((amount, fee_asset)) = &self.fee {
+            tx.fee = Some(*amount);
+            tx.fee_asset = Some(fee_asset.clone());
+        }
+        tx.notes = self.notes.clone();
+        tx
     }
+}
 
-    if transactions.is_empty() {
-        if !opts.quiet {
-            info("No transactions match the specified filters");
+/// Resolve the account and parse the amounts of a buy or sell
+pub(crate) async fn parse_trade(args: TradeArgs, pool: &SqlitePool, opts: &GlobalOptions) -> Result<Trade> {
+    let account = AccountRepository::new(pool).require_account(&args.account).await?;
+
+    let qty = parse_decimal_input(&args.quantity, args.max_decimals)?;
+    let price_usd = parse_decimal_input(&args.price, args.max_decimals)?;
+
+    let fee = parse_trade_fee(args.fee, args.fee_asset, args.max_decimals)?;
+    let fee_usd = match &fee {
+        Some((amount, fee_asset)) => {
+            Some(fee_value_usd(*amount, fee_asset, &args.asset, price_usd, opts).await?)
+        }
+        None => None,
+    };
+
+    Ok(Trade {
+        account,
+        asset: args.asset,
+        qty,
+        price_usd,
+        fee,
+        fee_usd,
+        notes: args.notes,
+    })
+}
+
+/// Add a buy to the account's holdings and record its transaction, all in
+/// one database transaction
+pub(crate) async fn record_buy(trade: &Trade, pool: &SqlitePool) -> Result<Transaction> {
+    let mut db_tx = pool.begin().await?;
+
+    match fee_debit(trade) {
+        // A fee in the bought asset comes out of what was received, so the
+        // price paid for the whole quantity is spread over the net quantity
+        Some((fee_asset, fee)) if fee_asset.eq_ignore_ascii_case(&trade.asset) => {
+            let net = trade.qty - fee;
+            if net <= Decimal::ZERO {
+                return Err(CryptofolioError::InvalidInput(format!(
+                    "A fee of {} {} leaves nothing of the {} bought",
+                    format_quantity(fee),
+                    fee_asset,
+                    format_quantity(trade.qty)
+                )));
+            }
+            let cost_per_unit = trade.qty * trade.price_usd / net;
+            HoldingRepository::add_quantity_in(&mut db_tx, &trade.account.id, &trade.asset, net, Some(cost_per_unit), None)
+                .await?;
         }
+        _ => {
+            check_fee_balance(trade, &mut db_tx, Decimal::ZERO).await?;
+            HoldingRepository::add_quantity_in(
+                &mut db_tx,
+                &trade.account.id,
+                &trade.asset,
+                trade.qty,
+                Some(trade.cost_per_unit()),
+                None,
+            )
+            .await?;
+            debit_fee(trade, &mut db_tx).await?;
+        }
+    }
+
+    let tx = Transaction::new_buy(&trade.account.id, &trade.asset, trade.qty, trade.price_usd, Utc::now());
+    let mut tx = trade.transaction(tx);
+    tx.id = TransactionRepository::insert_in(&mut db_tx, &tx).await?;
+    db_tx.commit().await?;
+    Ok(tx)
+}
+
+/// Remove a sale from the account's holdings and record its transaction, all
+/// in one database transaction
+pub(crate) async fn record_sell(trade: &Trade, pool: &SqlitePool) -> Result<Transaction> {
+    let mut db_tx = pool.begin().await?;
+    // A fee in the sold asset has to be covered on top of the sale
+    check_fee_balance(trade, &mut db_tx, -trade.qty).await?;
+
+    HoldingRepository::remove_quantity_in(&mut db_tx, &trade.account.id, &trade.asset, trade.qty).await?;
+    debit_fee(trade, &mut db_tx).await?;
+
+    let tx = Transaction::new_sell(&trade.account.id, &trade.asset, trade.qty, trade.price_usd, Utc::now());
+    let mut tx = trade.transaction(tx);
+    tx.id = TransactionRepository::insert_in(&mut db_tx, &tx).await?;
+    db_tx.commit().await?;
+    Ok(tx)
+}
+
+/// The holding a trade's fee is paid from. Fees in USD (the default) are paid
+/// in cash and only raise the cost basis, so nothing is deducted for them.
+fn fee_debit(trade: &Trade) -> Option<(&str, Decimal)> {
+    let (amount, fee_asset) = trade.fee.as_ref()?;
+    (fee_asset != "USD" && !amount.is_zero()).then_some((fee_asset.as_str(), *amount))
+}
+
+/// Fail before anything is written if the fee asset's holding can't cover the fee.
+/// `traded` is how much the trade itself moves that holding when the fee is paid
+/// in the traded asset (negative for a sell).
+async fn check_fee_balance(trade: &Trade, conn: &mut SqliteConnection, traded: Decimal) -> Result<()> {
+    let Some((fee_asset, amount)) = fee_debit(trade) else {
         return Ok(());
+    };
+
+    let held = HoldingRepository::get_in(conn, &trade.account.id, fee_asset)
+        .await?
+        .map(|h| h.quantity)
+        .unwrap_or(Decimal::ZERO);
+    let available = if fee_asset.eq_ignore_ascii_case(&trade.asset) { held + traded } else { held };
+
+    if available < amount {
+        return Err(CryptofolioError::InvalidInput(format!(
+            "'{}' holds {} {}, not enough to pay a fee of {} {}",
+            trade.account.name,
+            format_quantity(available.max(Decimal::ZERO)),
+            fee_asset,
+            format_quantity(amount),
+            fee_asset
+        )));
     }
 
-    // Convert transactions to CSV format
-    let csv_records: Vec<CsvExportRecord> = transactions.iter()
-        .map(|tx| transaction_to_csv_record(tx))
-        .collect();
+    Ok(())
+}
 
-    // Write to CSV file
-    if !opts.quiet {
-        info(&format!("Exporting {} transactions to '{}'...", csv_records.len(), file));
+/// Deduct a trade's fee from the holding it was paid from
+async fn debit_fee(trade: &Trade, conn: &mut SqliteConnection) -> Result<()> {
+    if let Some((fee_asset, amount)) = fee_debit(trade) {
+        HoldingRepository::remove_quantity_in(conn, &trade.account.id, fee_asset, amount).await?;
     }
+    Ok(())
+}
 
-    let file_handle = File::create(&file)?;
-    let mut writer = csv::Writer::from_writer(file_handle);
+fn parse_trade_fee(
+    fee: Option<String>,
+    fee_asset: Option<String>,
+    max_decimals: Option<u32>,
+) -> Result<Option<(Decimal, String)>> {
+    let Some(fee) = fee else {
+        return Ok(None);
+    };
 
-    for record in csv_records {
-        writer.serialize(&record)?;
+    let amount = parse_decimal_input(&fee, max_decimals)?;
+
+    if amount < Decimal::ZERO {
+        return Err(CryptofolioError::InvalidAmount(fee));
     }
 
-    writer.flush()?;
+    let asset = fee_asset
+        .map(|a| a.to_uppercase())
+        .unwrap_or_else(|| "USD".to_string());
 
-    success(&format!("Exported {} transactions to '{}'", transactions.len(), file));
+    Ok(Some((amount, asset)))
+}
 
-    Ok(())
+/// Decimal places accepted in typed amounts: `display.decimals`, or no limit
+/// with `--allow-high-precision`
+fn precision_limit(allow_high_precision: bool) -> Result<Option<u32>> {
+    if allow_high_precision {
+        return Ok(None);
+    }
+    Ok(Some(AppConfig::load()?.display.decimals as u32))
 }
 
-fn transaction_to_csv_record(tx: &Transaction) -> CsvExportRecord {
-    use crate::core::transaction::TransactionType;
+/// Parse a typed amount, rejecting more decimal places than `max_decimals`
+/// so a value copied from elsewhere isn't silently cut off on display.
+/// Trailing zeros don't count.
+pub(crate) fn parse_decimal_input(value: &str, max_decimals: Option<u32>) -> Result<Decimal> {
+    let amount = Decimal::from_str(value).map_err(|_| CryptofolioError::InvalidAmount(value.to_string()))?;
 
-    // Determine primary asset and quantity based on transaction type
-    let (asset, quantity) = match tx.tx_type {
-        TransactionType::Buy | TransactionType::Receive | TransactionType::TransferIn => {
-            (
-                tx.to_asset.clone().unwrap_or_default(),
-                tx.to_quantity.map(|q| q.to_string()).unwrap_or_default(),
-            )
-        }
-        TransactionType::Sell | TransactionType::TransferOut | TransactionType::Fee => {
-            (
-                tx.from_asset.clone().unwrap_or_default(),
-                tx.from_quantity.map(|q| q.to_string()).unwrap_or_default(),
-            )
+    if let Some(max_decimals) = max_decimals {
+        if amount.normalize().scale() > max_decimals {
+            return Err(CryptofolioError::PrecisionTooHigh { value: value.to_string(), max_decimals });
         }
-        TransactionType::TransferInternal => {
-            // For internal transfers, use the asset being transferred
-            (
-                tx.from_asset.clone().or_else(|| tx.to_asset.clone()).unwrap_or_default(),
-                tx.from_quantity.or(tx.to_quantity).map(|q| q.to_string()).unwrap_or_default(),
-            )
+    }
+
+    Ok(amount)
+}
+
+/// USD value of a trading fee.
+///
+/// Fees in USD or stablecoins are taken at face value, fees in the traded asset
+/// use the trade price, and any other asset is valued at its current price.
+async fn fee_value_usd(
+    amount: Decimal,
+    fee_asset: &str,
+    traded_asset: &str,
+    price_usd: Decimal,
+    opts: &GlobalOptions,
+) -> Result<Decimal> {
+    if is_usd_equivalent(fee_asset) {
+        return Ok(amount);
+    }
+
+    if fee_asset.eq_ignore_ascii_case(traded_asset) {
+        return Ok(amount * price_usd);
+    }
+
+    let client = binance_client(opts)?;
+    let price = client.get_price(fee_asset).await.map_err(|e| {
+        CryptofolioError::ExchangeApi(format!("Could not price fee asset {}: {}", fee_asset, e))
+    })?;
+
+    Ok(amount * price.price)
+}
+
+/// Market price of a reward asset. Binance Earn balances (`LDETH`) have no
+/// market of their own, so they fall back to the underlying asset.
+async fn reward_price_usd(asset: &str, opts: &GlobalOptions) -> Result<Decimal> {
+    let asset = asset.to_uppercase();
+    if is_usd_equivalent(&asset) {
+        return Ok(Decimal::ONE);
+    }
+
+    let client = binance_client(opts)?;
+    let mut result = client.get_price(&asset).await;
+    if let (Err(_), Some(underlying)) = (&result, asset.strip_prefix("LD").filter(|u| !u.is_empty())) {
+        result = client.get_price(underlying).await;
+    }
+
+    result.map(|p| p.price).map_err(|e| {
+        CryptofolioError::ExchangeApi(format!("Could not price {}: {}. Pass --price to set it manually", asset, e))
+    })
+}
+
+/// Work out how much of `to_asset` a swap yields at current Binance spot prices
+async fn spot_to_quantity(from_asset: &str, from_qty: Decimal, to_asset: &str, opts: &GlobalOptions) -> Result<Decimal> {
+    let from_asset = from_asset.to_uppercase();
+    let to_asset = to_asset.to_uppercase();
+
+    let client = binance_client(opts)?;
+    let symbols: Vec<&str> = [from_asset.as_str(), to_asset.as_str()]
+        .into_iter()
+        .filter(|a| !is_usd_equivalent(a))
+        .collect();
+    let prices = client.get_prices(&symbols).await?;
+
+    let price_of = |asset: &str| -> Result<Decimal> {
+        if is_usd_equivalent(asset) {
+            return Ok(Decimal::ONE);
         }
-        TransactionType::Swap => {
-            (
-                tx.from_asset.clone().unwrap_or_default(),
-                tx.from_quantity.map(|q| q.to_string()).unwrap_or_default(),
-            )
+        prices
+            .iter()
+            .find(|p| p.symbol.eq_ignore_ascii_case(asset))
+            .map(|p| p.price)
+            .ok_or_else(|| CryptofolioError::ExchangeApi(format!("No spot price available for {}", asset)))
+    };
+
+    let from_price = price_of(&from_asset)?;
+    let to_price = price_of(&to_asset)?;
+
+    let to_qty = spot_swap_quantity(from_qty, from_price, to_price).ok_or_else(|| {
+        CryptofolioError::ExchangeApi(format!("Spot price for {} is zero", to_asset))
+    })?;
+
+    if !opts.quiet {
+        info(&format!(
+            "Spot rate: 1 {} = {}, 1 {} = {}",
+            from_asset,
+            format_currency(from_price),
+            to_asset,
+            format_currency(to_price)
+        ));
+    }
+
+    Ok(to_qty)
+}
+
+fn binance_client(opts: &GlobalOptions) -> Result<BinanceClient> {
+    let config = AppConfig::load()?;
+    Ok(BinanceClient::new(
+        opts.testnet || config.general.use_testnet,
+        config.binance.api_key.clone(),
+        config.binance.api_secret.clone(),
+    ))
+}
+
+/// Highlight every occurrence of `needle` in `text`, matching the ASCII-only
+/// case folding used by the notes search
+fn highlight_match(text: &str, needle: &str, case_sensitive: bool) -> String {
+    if needle.is_empty() || !colors_enabled() {
+        return text.to_string();
+    }
+
+    let (haystack, needle) = if case_sensitive {
+        (text.to_string(), needle.to_string())
+    } else {
+        (text.to_ascii_lowercase(), needle.to_ascii_lowercase())
+    };
+
+    let mut highlighted = String::new();
+    let mut last = 0;
+    for (start, matched) in haystack.match_indices(&needle) {
+        highlighted.push_str(&text[last..start]);
+        highlighted.push_str(&text[start..start + matched.len()].yellow().bold().to_string());
+        last = start + matched.len();
+    }
+    highlighted.push_str(&text[last..]);
+    highlighted
+}
+
+fn format_fee_note(fee: &Option<(Decimal, String)>, fee_usd: Option<Decimal>) -> String {
+    match (fee, fee_usd) {
+        (Some((amount, asset)), Some(usd)) if !is_usd_equivalent(asset) => {
+            format!(" (fee: {} {} ≈ {})", format_quantity(*amount), asset, format_currency(usd))
         }
+        (Some(_), Some(usd)) => format!(" (fee: {})", format_currency(usd)),
+        _ => String::new(),
+    }
+}
+
+/// List transactions with the portfolio value after each one.
+///
+/// Replays the full history oldest first so holdings are accurate, then shows
+/// the most recent `limit` entries newest first like the plain listing.
+async fn handle_running_balance_list(
+    account: Option<String>,
+    limit: i64,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    let account_repo = AccountRepository::new(pool);
+    let tx_repo = TransactionRepository::new(pool);
+
+    let account_id = if let Some(account_name) = account {
+        let acc = account_repo.require_account(&account_name).await?;
+        Some(acc.id)
+    } else {
+        None
     };
 
-    CsvExportRecord {
-        date: tx.timestamp.to_rfc3339(),
-        tx_type: tx.tx_type.as_str().to_string(),
-        asset,
-        quantity,
-        price_usd: tx.price_usd.map(|p| p.to_string()).unwrap_or_default(),
-        fee: tx.fee.map(|f| f.to_string()).unwrap_or_default(),
-        fee_asset: tx.fee_asset.clone().unwrap_or_default(),
-        notes: tx.notes.clone().unwrap_or_default(),
-        to_asset: if matches!(tx.tx_type, TransactionType::Swap) {
-            tx.to_asset.clone().unwrap_or_default()
+    let history = tx_repo.list_chronological(account_id.as_deref()).await?;
+
+    let mut replay = PortfolioReplay::new(account_id.as_deref());
+    let mut rows: Vec<(Transaction, Option<Decimal>)> = history
+        .into_iter()
+        .map(|tx| {
+            let value = replay.apply(&tx);
+            (tx, value)
+        })
+        .collect();
+    rows.reverse();
+    rows.truncate(limit.max(0) as usize);
+
+    if rows.is_empty() {
+        if opts.json {
+            println!("[]");
         } else {
-            String::new()
-        },
-        to_quantity: if matches!(tx.tx_type, TransactionType::Swap) {
-            tx.to_quantity.map(|q| q.to_string()).unwrap_or_default()
+            println!("No transactions found.");
+        }
+        return Ok(());
+    }
+
+    if opts.json {
+        let output: Vec<RunningBalanceOutput> = rows.iter().map(|(tx, value)| RunningBalanceOutput {
+            transaction: TransactionOutput {
+                tx_type: tx.tx_type.display_name().to_string(),
+                ..transaction_to_output(tx)
+            },
+            portfolio_value_after: value.map(|v| v.round_dp(2).to_string()),
+        }).collect();
+        println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+        return Ok(());
+    }
+
+    print_header(&[("Date", 12), ("Type", 10), ("Asset", 8), ("Quantity", 14), ("Price", 12), ("Value After", 16)]);
+
+    for (tx, value) in rows {
+        let date = tx.timestamp.format("%Y-%m-%d").to_string();
+        let asset = tx.to_asset.or(tx.from_asset).unwrap_or_else(|| "-".to_string());
+        let qty = tx.to_quantity.or(tx.from_quantity)
+            .map(format_quantity)
+            .unwrap_or_else(|| "-".to_string());
+        let price = tx.price_usd
+            .map(format_currency)
+            .unwrap_or_else(|| "-".to_string());
+        let value_after = value
+            .map(format_currency)
+            .unwrap_or_else(|| "—".to_string());
+
+        print_row(&[
+            (&date, 12),
+            (tx.tx_type.display_name(), 10),
+            (&asset, 8),
+            (&qty, 14),
+            (&price, 12),
+            (&value_after, 16),
+        ]);
+    }
+
+    Ok(())
+}
+
+/// JSON shape of a transaction in `tx list` and `account show`
+pub(crate) fn transaction_list_output(tx: &Transaction) -> TransactionOutput {
+    TransactionOutput {
+        tx_type: tx.tx_type.display_name().to_string(),
+        ..transaction_to_output(tx)
+    }
+}
+
+/// Print one page of `tx list`, followed by where it sits in the full list
+async fn list_transaction_page(
+    filter: &TransactionFilter,
+    page: u32,
+    page_size: u32,
+    search: Option<&str>,
+    case_sensitive: bool,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    let (transactions, total) = TransactionRepository::new(pool).list_paginated(filter, page, page_size).await?;
+
+    if opts.json {
+        return print_json(&TransactionPageOutput {
+            transactions: transactions.iter().map(transaction_list_output).collect(),
+            total,
+            page,
+            page_size,
+        });
+    }
+
+    let pages = page_count(total, page_size);
+    if transactions.is_empty() {
+        if total == 0 {
+            println!("No transactions found.");
         } else {
-            String::new()
-        },
+            println!("No transactions on page {}: {} transactions fill {} page(s).", page, total, pages);
+        }
+        return Ok(());
+    }
+
+    print_transaction_list(&transactions, search, case_sensitive);
+
+    let first = u64::from(page - 1) * u64::from(page_size) + 1;
+    let last = first + transactions.len() as u64 - 1;
+    println!();
+    println!("{}", format_page_summary(u64::from(page), pages, first, last, total, "transactions"));
+    Ok(())
+}
+
+/// The `tx list` table; with `search`, notes replace the price column and the match is highlighted
+fn print_transaction_list(transactions: &[Transaction], search: Option<&str>, case_sensitive: bool) {
+    let Some(text) = search else {
+        print_transaction_table(transactions);
+        return;
+    };
+
+    print_header(&[("ID", 6), ("Date", 12), ("Type", 10), ("Asset", 8), ("Quantity", 14), ("Notes", 30)]);
+
+    for tx in transactions {
+        let date = tx.timestamp.format("%Y-%m-%d").to_string();
+        let asset = tx.to_asset.as_deref().or(tx.from_asset.as_deref()).unwrap_or("-");
+        let qty = tx.to_quantity.or(tx.from_quantity)
+            .map(format_quantity)
+            .unwrap_or_else(|| "-".to_string());
+        let notes = highlight_match(tx.notes.as_deref().unwrap_or_default(), text, case_sensitive);
+
+        print_row(&[
+            (&tx.id.to_string(), 6),
+            (&date, 12),
+            (tx.tx_type.display_name(), 10),
+            (asset, 8),
+            (&qty, 14),
+            (&notes, 30),
+        ]);
+    }
+}
+
+/// Print transactions as the `tx list` table
+pub(crate) fn print_transaction_table(transactions: &[Transaction]) {
+    print_header(&[("ID", 6), ("Date", 12), ("Type", 10), ("Asset", 8), ("Quantity", 14), ("Price", 12)]);
+
+    for tx in transactions {
+        let date = tx.timestamp.format("%Y-%m-%d").to_string();
+        let asset = tx.to_asset.as_deref().or(tx.from_asset.as_deref()).unwrap_or("-");
+        let qty = tx.to_quantity.or(tx.from_quantity)
+            .map(format_quantity)
+            .unwrap_or_else(|| "-".to_string());
+        let price = tx.price_usd
+            .map(format_currency)
+            .unwrap_or_else(|| "-".to_string());
+
+        print_row(&[
+            (&tx.id.to_string(), 6),
+            (&date, 12),
+            (tx.tx_type.display_name(), 10),
+            (asset, 8),
+            (&qty, 14),
+            (&price, 12),
+        ]);
     }
 }
 
-fn parse_date_filter(date_str: &str) -> Result<DateTime<Utc>> {
+pub(crate) fn transaction_to_output(tx: &Transaction) -> TransactionOutput {
+    TransactionOutput {
+        id: tx.id,
+        timestamp: tx.timestamp.to_rfc3339(),
+        tx_type: tx.tx_type.as_str().to_string(),
+        from_account_id: tx.from_account_id.clone(),
+        to_account_id: tx.to_account_id.clone(),
+        from_asset: tx.from_asset.clone(),
+        from_quantity: tx.from_quantity.map(|q| q.to_string()),
+        to_asset: tx.to_asset.clone(),
+        to_quantity: tx.to_quantity.map(|q| q.to_string()),
+        price_usd: tx.price_usd.map(|p| p.to_string()),
+        fee: tx.fee.map(|f| f.to_string()),
+        fee_asset: tx.fee_asset.clone(),
+        external_id: tx.external_id.clone(),
+        notes: tx.notes.clone(),
+        created_at: tx.created_at.to_rfc3339(),
+    }
+}
+
+pub(crate) fn parse_date_filter(date_str: &str) -> Result<DateTime<Utc>> {
     // Try RFC3339 format first
     if let Ok(dt) = DateTime::parse_from_rfc3339(date_str) {
         return Ok(dt.with_timezone(&Utc));
@@ -559,3 +1326,21 @@ fn parse_date_filter(date_str: &str) -> Result<DateTime<Utc>> {
         format!("Invalid date format: '{}'. Use YYYY-MM-DD or ISO 8601", date_str)
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_decimal_input_precision() {
+        assert_eq!(parse_decimal_input("0.12345678", Some(8)).unwrap(), Decimal::from_str("0.12345678").unwrap());
+        // Trailing zeros carry no precision
+        assert!(parse_decimal_input("1.500000000000", Some(8)).is_ok());
+
+        let err = parse_decimal_input("0.123456789012345678", Some(8)).unwrap_err();
+        assert!(matches!(err, CryptofolioError::PrecisionTooHigh { max_decimals: 8, .. }));
+
+        assert!(parse_decimal_input("0.123456789012345678", None).is_ok());
+        assert!(matches!(parse_decimal_input("abc", None), Err(CryptofolioError::InvalidAmount(_))));
+    }
+}
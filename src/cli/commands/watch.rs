@@ -0,0 +1,285 @@
+use chrono::Local;
+use colored::Colorize;
+use rust_decimal::Decimal;
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, IsTerminal, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::cli::commands::portfolio::fetch_price_map;
+use crate::cli::output::{colors_enabled, format_currency, format_percent, format_quantity};
+use crate::cli::GlobalOptions;
+use crate::config::AppConfig;
+use crate::core::currency::is_usd_equivalent;
+use crate::db::HoldingRepository;
+use crate::error::{CryptofolioError, Result};
+use crate::exchange::BinanceClient;
+
+/// Switch to the alternate screen and hide the cursor
+const ENTER_SCREEN: &str = "\x1b[?1049h\x1b[?25l";
+/// Show the cursor and return to the normal screen
+const LEAVE_SCREEN: &str = "\x1b[?25h\x1b[?1049l";
+/// Move to the top-left corner and clear the screen
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+const BELL: &str = "\x07";
+
+/// An asset that moved more than the alert threshold between refreshes
+#[derive(Debug, PartialEq)]
+struct PriceAlert {
+    asset: String,
+    change_pct: Decimal,
+}
+
+pub async fn handle_watch_command(
+    interval: u64,
+    alert_pct: Option<String>,
+    pool: &SqlitePool,
+    opts: &GlobalOptions,
+) -> Result<()> {
+    if interval == 0 {
+        return Err(CryptofolioError::InvalidInput("--interval must be at least 1 second".to_string()));
+    }
+    let alert_pct = alert_pct
+        .map(|p| Decimal::from_str(&p).map_err(|_| CryptofolioError::InvalidAmount(p)))
+        .transpose()?;
+
+    let config = AppConfig::load()?;
+    let use_testnet = opts.testnet || config.general.use_testnet;
+    let client = BinanceClient::new(
+        use_testnet,
+        config.binance.api_key.clone(),
+        config.binance.api_secret.clone(),
+    );
+
+    // Piped output gets one frame after another instead of a redrawn screen
+    let interactive = io::stdout().is_terminal();
+    let with_color = config.display.color && colors_enabled();
+    if interactive {
+        print!("{}", ENTER_SCREEN);
+    }
+
+    let result = watch_loop(interval, alert_pct, interactive, with_color, &client, pool).await;
+
+    if interactive {
+        print!("{}", LEAVE_SCREEN);
+        io::stdout().flush()?;
+    }
+    result
+}
+
+/// Redraw every `interval` seconds until Ctrl+C
+async fn watch_loop(
+    interval: u64,
+    alert_pct: Option<Decimal>,
+    interactive: bool,
+    with_color: bool,
+    client: &BinanceClient,
+    pool: &SqlitePool,
+) -> Result<()> {
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+    let mut previous: HashMap<String, Decimal> = HashMap::new();
+
+    // One listener for the whole loop, so Ctrl+C during a fetch isn't missed
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        let refresh = async {
+            ticker.tick().await;
+
+            let quantities = asset_quantities(pool).await?;
+            let assets: Vec<String> = quantities.keys().cloned().collect();
+            let mut prices = fetch_price_map(client, &assets, pool).await.prices;
+            for asset in assets.iter().filter(|a| is_usd_equivalent(a)) {
+                prices.entry(asset.clone()).or_insert(Decimal::ONE);
+            }
+
+            let alerts = alert_pct.map(|pct| price_alerts(&previous, &prices, pct)).unwrap_or_default();
+
+            let mut frame = String::new();
+            if interactive {
+                frame.push_str(CLEAR_SCREEN);
+            }
+            render_frame(&mut frame, &quantities, &prices, &previous, with_color);
+            render_alerts(&mut frame, &alerts, with_color);
+            frame.push_str(&format!(
+                "\nUpdated {} · refreshing every {}s · Ctrl+C to stop\n",
+                Local::now().format("%H:%M:%S"),
+                interval
+            ));
+            if !alerts.is_empty() {
+                frame.push_str(BELL);
+            }
+
+            print!("{}", frame);
+            io::stdout().flush()?;
+            Ok::<_, CryptofolioError>(prices)
+        };
+
+        tokio::select! {
+            _ = &mut ctrl_c => return Ok(()),
+            prices = refresh => previous = prices?,
+        }
+    }
+}
+
+/// Total quantity of each asset across all accounts
+async fn asset_quantities(pool: &SqlitePool) -> Result<BTreeMap<String, Decimal>> {
+    let mut quantities = BTreeMap::new();
+    for holding in HoldingRepository::new(pool).list_all().await? {
+        *quantities.entry(holding.asset.to_uppercase()).or_insert(Decimal::ZERO) += holding.quantity;
+    }
+    quantities.retain(|_, quantity| !quantity.is_zero());
+    Ok(quantities)
+}
+
+/// Percent change from `previous` to `current`, when both prices are known
+fn price_change_pct(previous: &HashMap<String, Decimal>, current: &HashMap<String, Decimal>, asset: &str) -> Option<Decimal> {
+    let before = previous.get(asset).filter(|p| !p.is_zero())?;
+    let now = current.get(asset)?;
+    Some((now - before) / before * Decimal::ONE_HUNDRED)
+}
+
+/// Assets whose price moved by more than `threshold_pct` in either direction
+fn price_alerts(
+    previous: &HashMap<String, Decimal>,
+    current: &HashMap<String, Decimal>,
+    threshold_pct: Decimal,
+) -> Vec<PriceAlert> {
+    let mut alerts: Vec<PriceAlert> = current
+        .keys()
+        .filter_map(|asset| {
+            let change_pct = price_change_pct(previous, current, asset)?;
+            (change_pct.abs() > threshold_pct).then(|| PriceAlert { asset: asset.clone(), change_pct })
+        })
+        .collect();
+    alerts.sort_by(|a, b| a.asset.cmp(&b.asset));
+    alerts
+}
+
+/// Pad `text` to `width`, then color it by the sign of `value`
+fn colored_cell(text: &str, width: usize, value: Decimal, with_color: bool) -> String {
+    let padded = format!("{:>width$}", text, width = width);
+    if !with_color || value.is_zero() {
+        padded
+    } else if value > Decimal::ZERO {
+        padded.green().to_string()
+    } else {
+        padded.red().to_string()
+    }
+}
+
+fn signed_percent(value: Decimal) -> String {
+    if value >= Decimal::ZERO {
+        format!("+{}", format_percent(value.round_dp(2)))
+    } else {
+        format_percent(value.round_dp(2))
+    }
+}
+
+fn render_frame(
+    out: &mut String,
+    quantities: &BTreeMap<String, Decimal>,
+    prices: &HashMap<String, Decimal>,
+    previous: &HashMap<String, Decimal>,
+    with_color: bool,
+) {
+    let title = "PORTFOLIO WATCH";
+    out.push_str(&format!("{}\n\n", if with_color { title.bold().to_string() } else { title.to_string() }));
+
+    if quantities.is_empty() {
+        out.push_str("No holdings found.\n");
+        return;
+    }
+
+    out.push_str(&format!("{:<8} {:>16} {:>14} {:>10} {:>16}\n", "Asset", "Quantity", "Price", "Change", "Value"));
+    out.push_str(&format!("{}\n", "-".repeat(68)));
+
+    let mut total = Decimal::ZERO;
+    let mut total_before = Decimal::ZERO;
+    let mut unpriced = Vec::new();
+    for (asset, quantity) in quantities {
+        let Some(price) = prices.get(asset) else {
+            unpriced.push(asset.as_str());
+            continue;
+        };
+        let value = quantity * price;
+        total += value;
+        total_before += quantity * previous.get(asset).unwrap_or(price);
+
+        let change = match price_change_pct(previous, prices, asset) {
+            Some(pct) => colored_cell(&signed_percent(pct), 10, pct, with_color),
+            None => format!("{:>10}", "-"),
+        };
+        out.push_str(&format!(
+            "{:<8} {:>16} {:>14} {} {:>16}\n",
+            asset,
+            format_quantity(*quantity),
+            format_currency(*price),
+            change,
+            format_currency(value)
+        ));
+    }
+
+    out.push_str(&format!("{}\n", "-".repeat(68)));
+    let total_change = (!previous.is_empty() && !total_before.is_zero())
+        .then(|| (total - total_before) / total_before * Decimal::ONE_HUNDRED);
+    let change = match total_change {
+        Some(pct) => colored_cell(&signed_percent(pct), 10, pct, with_color),
+        None => format!("{:>10}", ""),
+    };
+    out.push_str(&format!("{:<8} {:>16} {:>14} {} {:>16}\n", "Total", "", "", change, format_currency(total)));
+
+    if !unpriced.is_empty() {
+        out.push_str(&format!("\nNo price for: {}\n", unpriced.join(", ")));
+    }
+}
+
+fn render_alerts(out: &mut String, alerts: &[PriceAlert], with_color: bool) {
+    if !alerts.is_empty() {
+        out.push('\n');
+    }
+    for alert in alerts {
+        let message = format!("ALERT: {} moved {} since the last refresh", alert.asset, signed_percent(alert.change_pct));
+        let message = if with_color { message.yellow().bold().to_string() } else { message };
+        out.push_str(&message);
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices(entries: &[(&str, i64)]) -> HashMap<String, Decimal> {
+        entries.iter().map(|(a, p)| (a.to_string(), Decimal::from(*p))).collect()
+    }
+
+    #[test]
+    fn test_price_alerts_above_threshold() {
+        let previous = prices(&[("BTC", 100), ("ETH", 100), ("SOL", 100)]);
+        let current = prices(&[("BTC", 106), ("ETH", 103), ("SOL", 90), ("DOGE", 1)]);
+
+        let alerts = price_alerts(&previous, &current, Decimal::from(5));
+        assert_eq!(
+            alerts,
+            vec![
+                PriceAlert { asset: "BTC".to_string(), change_pct: Decimal::from(6) },
+                PriceAlert { asset: "SOL".to_string(), change_pct: Decimal::from(-10) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_refresh_has_no_changes() {
+        let current = prices(&[("BTC", 100)]);
+        assert!(price_alerts(&HashMap::new(), &current, Decimal::ONE).is_empty());
+
+        let quantities = BTreeMap::from([("BTC".to_string(), Decimal::TWO), ("XYZ".to_string(), Decimal::ONE)]);
+        let mut out = String::new();
+        render_frame(&mut out, &quantities, &current, &HashMap::new(), false);
+        assert!(out.contains("$200.00"));
+        assert!(out.contains("No price for: XYZ"));
+    }
+}
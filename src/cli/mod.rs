@@ -1,11 +1,15 @@
 #![allow(dead_code)]
 
+pub mod color;
 pub mod commands;
 pub mod notifications;
 pub mod output;
 
 use clap::{Parser, Subcommand, ValueEnum};
 
+use crate::core::account::AccountType;
+use crate::core::transaction::TransactionType;
+
 const AFTER_HELP: &str = r#"EXAMPLES:
     cryptofolio price BTC ETH              Get current prices
     cryptofolio portfolio                  View portfolio with P&L
@@ -62,7 +66,10 @@ AUTOMATION EXAMPLE:
 ENVIRONMENT VARIABLES:
     CRYPTOFOLIO_TESTNET     Set to "1" to use testnet mode
     CRYPTOFOLIO_NO_COLOR    Set to disable colored output
-    NO_COLOR                Standard flag to disable colors
+    CRYPTOFOLIO_CONFIG      Path to the config file
+    CRYPTOFOLIO_DB_PATH     Path to the database file
+    NO_COLOR                Standard flag to disable colors (https://no-color.org)
+    TERM=dumb               Also disables colored output
 
 CONFIGURATION:
     Config file: ~/.config/cryptofolio/config.toml
@@ -103,6 +110,14 @@ pub struct Cli {
     /// Enable verbose/debug output
     #[arg(short, long, global = true)]
     pub verbose: bool,
+
+    /// Use this config file instead of ~/.config/cryptofolio/config.toml
+    #[arg(long = "config", global = true, value_name = "PATH")]
+    pub config_path: Option<std::path::PathBuf>,
+
+    /// Fail immediately when Binance rate-limits a request instead of retrying
+    #[arg(long, global = true)]
+    pub no_retry: bool,
 }
 
 #[derive(Subcommand)]
@@ -116,7 +131,7 @@ pub enum Commands {
     },
 
     /// Get detailed market data for a cryptocurrency
-    #[command(after_help = "EXAMPLES:\n    # Get current market price\n    cryptofolio market BTC\n    cryptofolio market ETHUSDT\n\n    # Include 24-hour statistics\n    cryptofolio market BTC --24h\n\n    # JSON output with 24h data\n    cryptofolio market BTCUSDT --24h --json")]
+    #[command(after_help = "EXAMPLES:\n    # Get current market price\n    cryptofolio market BTC\n    cryptofolio market ETHUSDT\n\n    # Include 24-hour statistics\n    cryptofolio market BTC --24h\n\n    # JSON output with 24h data\n    cryptofolio market BTCUSDT --24h --json\n\n    # Tokens not listed on Binance\n    cryptofolio market PNUT --source coingecko\n\n    # Compare prices across Binance, Kraken and CoinGecko\n    cryptofolio market BTC --compare")]
     Market {
         /// Cryptocurrency symbol (e.g., BTC, BTCUSDT)
         symbol: String,
@@ -124,6 +139,14 @@ pub enum Commands {
         /// Show 24-hour statistics (price change, volume, high/low)
         #[arg(long = "24h")]
         show_24h: bool,
+
+        /// Market data source (CoinGecko covers tokens not listed on Binance)
+        #[arg(long, value_enum, default_value = "binance")]
+        source: MarketSourceArg,
+
+        /// Show the price from every source side by side
+        #[arg(long, conflicts_with = "source")]
+        compare: bool,
     },
 
     /// Manage accounts (exchanges, wallets)
@@ -141,15 +164,20 @@ pub enum Commands {
     },
 
     /// Manage holdings across accounts
-    #[command(after_help = "EXAMPLES:\n    # List all holdings\n    cryptofolio holdings list\n    cryptofolio holdings list --account Binance\n    cryptofolio holdings list --json\n\n    # Add holdings with cost basis\n    cryptofolio holdings add BTC 0.5 --account Ledger --cost 45000\n    cryptofolio holdings add ETH 2.0 --account MetaMask --cost 2800\n\n    # Move holdings between accounts\n    cryptofolio holdings move BTC 0.1 --from Binance --to Ledger --yes")]
+    #[command(after_help = "EXAMPLES:\n    # List all holdings\n    cryptofolio holdings list\n    cryptofolio holdings list --account Binance\n    cryptofolio holdings list --json\n\n    # Add holdings with cost basis\n    cryptofolio holdings add BTC 0.5 --account Ledger --cost 45000\n    cryptofolio holdings add ETH 2.0 --account MetaMask --cost 2800\n\n    # Move holdings between accounts\n    cryptofolio holdings move BTC 0.1 --from Binance --to Ledger --yes\n    cryptofolio holdings move BTC 0.1 --from Binance --to Ledger --dry-run\n\n    # Overwrite one asset with the exchange's live balance (no full sync)\n    cryptofolio holdings set BTC --account Binance --from-exchange\n    cryptofolio holdings set BTC --account Binance --from-exchange --dry-run")]
     Holdings {
         #[command(subcommand)]
         command: HoldingsCommands,
     },
 
     /// View portfolio with P&L calculations
-    #[command(after_help = "EXAMPLES:\n    # View full portfolio\n    cryptofolio portfolio\n\n    # Group by category or account\n    cryptofolio portfolio --by-category\n    cryptofolio portfolio --by-account\n\n    # Filter by account or category\n    cryptofolio portfolio --account Binance\n    cryptofolio portfolio --category cold-storage\n\n    # JSON output for automation\n    cryptofolio portfolio --json\n    cryptofolio portfolio --json | jq '.total_value_usd'")]
+    #[command(after_help = "EXAMPLES:\n    # View full portfolio\n    cryptofolio portfolio\n\n    # Group by category or account\n    cryptofolio portfolio --by-category\n    cryptofolio portfolio --by-account\n\n    # Show values in another currency\n    cryptofolio portfolio --currency EUR\n\n    # Filter by account or category\n    cryptofolio portfolio --account Binance\n    cryptofolio portfolio --category cold-storage\n\n    # JSON output for automation\n    cryptofolio portfolio --json\n    cryptofolio portfolio --json | jq '.total_value_usd'\n\n    # CSV for spreadsheets\n    cryptofolio portfolio --format csv > portfolio.csv\n\n    # Save snapshots to disk (e.g., from cron)\n    cryptofolio portfolio --json --output-file ~/portfolio-snapshot.json\n    cryptofolio portfolio --json --output-file ~/portfolio-history.jsonl --append\n\n    # Realized and unrealized gains by lot\n    cryptofolio portfolio --cost-basis fifo\n    cryptofolio portfolio --cost-basis hifo --json\n\n    # Realized or unrealized gains on their own (e.g., for taxes)\n    cryptofolio portfolio --realized-only --from 2025-01-01 --to 2025-12-31\n    cryptofolio portfolio --unrealized-only --account Ledger --cost-basis lifo\n\n    # Compare against holding BTC since the first transaction, or since a snapshot date\n    cryptofolio portfolio --benchmark BTC\n    cryptofolio portfolio --benchmark BTC --from 2025-01-01\n\n    # Track value over time\n    cryptofolio portfolio snapshot\n    cryptofolio portfolio --auto-snapshot\n    cryptofolio portfolio history")]
+    #[command(args_conflicts_with_subcommands = true)]
+    #[command(group(clap::ArgGroup::new("since").multiple(true).args(["unrealized_only", "realized_only", "benchmark"])))]
     Portfolio {
+        #[command(subcommand)]
+        command: Option<PortfolioCommands>,
+
         /// Group by account
         #[arg(long = "by-account")]
         by_account: bool,
@@ -165,6 +193,72 @@ pub enum Commands {
         /// Filter by category name
         #[arg(long)]
         category: Option<String>,
+
+        /// Filter by asset type (fiat, crypto, stablecoin)
+        #[arg(long)]
+        asset_type: Option<String>,
+
+        /// Output format (table, json, csv); --json is the same as --format json
+        #[arg(long, value_enum, conflicts_with = "pnl_only")]
+        format: Option<OutputFormat>,
+
+        /// Write output to a file instead of stdout (parent directories are created)
+        #[arg(long)]
+        output_file: Option<String>,
+
+        /// Append to the output file instead of overwriting (JSON is written one object per line)
+        #[arg(long, requires = "output_file")]
+        append: bool,
+
+        /// Show lifetime amount invested (all buys plus fees) against current value
+        #[arg(long)]
+        total_invested: bool,
+
+        /// Show total trading fees paid per position, in USD
+        #[arg(long)]
+        show_fees: bool,
+
+        /// Include bank accounts, valuing fiat balances with stored exchange rates
+        #[arg(long)]
+        net_worth: bool,
+
+        /// Show values in this currency (e.g., EUR, CRC) using stored exchange rates [default: general.currency].
+        /// CSV output and gain reports stay in USD
+        #[arg(long, value_name = "CODE")]
+        currency: Option<String>,
+
+        /// Show only holdings with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Match sales against lots to report realized and unrealized gains (fifo, lifo, hifo)
+        #[arg(long, value_name = "METHOD")]
+        cost_basis: Option<String>,
+
+        /// Also save a snapshot of the full portfolio, at most once per day
+        #[arg(long, conflicts_with_all = ["account", "category", "asset_type", "tag", "net_worth"])]
+        auto_snapshot: bool,
+
+        /// Show only unrealized gains of held positions, matched to lots with --cost-basis (default fifo)
+        #[arg(long, group = "pnl_only", conflicts_with_all = ["realized_only", "total_invested", "show_fees", "auto_snapshot"])]
+        unrealized_only: bool,
+
+        /// Show only realized gains from sales, matched to lots with --cost-basis (default fifo)
+        #[arg(long, group = "pnl_only", conflicts_with_all = ["asset_type", "tag", "total_invested", "show_fees", "auto_snapshot"])]
+        realized_only: bool,
+
+        /// Compare the portfolio's return with holding this asset over the same period (e.g., BTC)
+        #[arg(long, value_name = "ASSET", conflicts_with_all = ["pnl_only", "account", "category", "asset_type", "tag", "net_worth"])]
+        benchmark: Option<String>,
+
+        /// With --unrealized-only or --realized-only: lots bought or sales made from this date (YYYY-MM-DD or ISO 8601).
+        /// With --benchmark: compare from the first portfolio snapshot on or after this date
+        #[arg(long, requires = "since")]
+        from: Option<String>,
+
+        /// With --unrealized-only or --realized-only: lots bought or sales made up to this date (YYYY-MM-DD or ISO 8601)
+        #[arg(long, requires = "pnl_only")]
+        to: Option<String>,
     },
 
     /// Record and view transactions
@@ -174,27 +268,72 @@ pub enum Commands {
         command: TxCommands,
     },
 
-    /// Sync holdings from exchange accounts
-    #[command(after_help = "EXAMPLES:\n    cryptofolio sync\n    cryptofolio sync --account \"Binance\"")]
+    /// Sync holdings from exchange accounts and Ethereum/Bitcoin wallets
+    #[command(
+        args_conflicts_with_subcommands = true,
+        after_help = "EXAMPLES:\n    cryptofolio sync\n    cryptofolio sync --quiet\n    cryptofolio sync --account \"Binance\"\n    cryptofolio sync --account \"Ledger\"\n    cryptofolio sync --account \"Binance\" --show-changes\n    cryptofolio sync --dry-run\n    cryptofolio sync log --limit 5\n    cryptofolio sync history --account \"Binance\"\n\n    # Wallets are read from their Ethereum addresses and Bitcoin addresses or xpubs\n    cryptofolio config set ethereum.rpc_url https://eth.llamarpc.com\n    cryptofolio config set bitcoin.electrum_url tcp://electrum.example.com:50001\n    cryptofolio account address add Ledger bitcoin zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9x...\n\nEXIT STATUS:\n    0 when every account synced, 1 when none did, 2 when only some failed.\n    With --quiet only errors are printed, which suits cron jobs."
+    )]
     Sync {
-        /// Account to sync (syncs all exchange accounts if not specified)
+        #[command(subcommand)]
+        command: Option<SyncCommands>,
+
+        /// Account to sync (syncs all exchanges and wallets if not specified)
         #[arg(long)]
         account: Option<String>,
+
+        /// Print a table of the quantities each sync changed
+        #[arg(long)]
+        show_changes: bool,
+
+        /// Fetch balances and show what a sync would change, without writing anything
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Import transactions from CSV file
-    #[command(after_help = "EXAMPLES:\n    cryptofolio import transactions.csv --account Ledger\n\nCSV FORMAT:\n    date,type,asset,quantity,price_usd,fee,notes\n    2024-01-15,buy,BTC,0.5,45000,0.001,First purchase")]
+    #[command(
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true,
+        after_help = "EXAMPLES:\n    cryptofolio import transactions.csv --account Ledger\n    cryptofolio import binance_trades.csv --format binance-csv --account Binance\n    cryptofolio import coinbase_export.csv --format coinbase-pro --account Coinbase\n    cryptofolio import ledger_export.csv --format ledger-live --account Ledger\n    cryptofolio import koinly_export.csv --format koinly --account Binance --dry-run\n    cryptofolio import full --input ./backup --merge\n\nCSV FORMAT:\n    date,type,asset,quantity,price_usd,fee,notes\n    2024-01-15,buy,BTC,0.5,45000,0.001,First purchase"
+    )]
     Import {
+        #[command(subcommand)]
+        command: Option<ImportCommands>,
+
         /// Path to CSV file
-        file: String,
+        #[arg(required = true)]
+        file: Option<String>,
 
         /// Account to import into
-        #[arg(long, required = true)]
-        account: String,
+        #[arg(long, required_unless_present = "account_column", conflicts_with = "account_column")]
+        account: Option<String>,
 
-        /// File format (csv)
+        /// CSV column holding each row's account name (for multi-account files)
+        #[arg(long, value_name = "COLUMN")]
+        account_column: Option<String>,
+
+        /// File format (csv, binance-csv, coinbase-pro, ledger-live, koinly)
         #[arg(long, default_value = "csv")]
         format: String,
+
+        /// Preview what would be imported without writing (koinly only)
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Export all data for backup or migration
+    #[command(
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true,
+        after_help = "EXAMPLES:\n    cryptofolio export --all full_backup.zip\n    cryptofolio export full --output ./backup\n\nFILES (--all):\n    transactions.csv, holdings.csv, accounts.json, categories.json, exchange_rates.csv\n    transactions.csv is read by `cryptofolio import`. API credentials are never exported.\n\nFILES (full):\n    accounts.json, wallet_addresses.json, holdings.json, transactions.json,\n    exchange_rates.json, categories.json, manifest.json"
+    )]
+    Export {
+        #[command(subcommand)]
+        command: Option<ExportCommands>,
+
+        /// Write everything to one ZIP archive
+        #[arg(long, value_name = "FILE", required = true)]
+        all: Option<String>,
     },
 
     /// Manage configuration settings
@@ -205,36 +344,339 @@ pub enum Commands {
     },
 
     /// Manage currencies and exchange rates
-    #[command(after_help = "EXAMPLES:\n    # List all currencies\n    cryptofolio currency list\n    cryptofolio currency list --enabled\n    cryptofolio currency list --json\n\n    # Add a new currency\n    cryptofolio currency add MXN --name \"Mexican Peso\" --symbol \"₱\" --decimals 2 --type fiat\n\n    # Show currency details\n    cryptofolio currency show USD\n\n    # Set exchange rate\n    cryptofolio currency set-rate CRC USD 550 --notes \"Bank rate\"\n    cryptofolio currency show-rate CRC USD\n    cryptofolio currency show-rate CRC USD --history")]
+    #[command(after_help = "EXAMPLES:\n    # List all currencies\n    cryptofolio currency list\n    cryptofolio currency list --enabled\n    cryptofolio currency list --json\n\n    # Add a new currency\n    cryptofolio currency add MXN --name \"Mexican Peso\" --symbol \"₱\" --decimals 2 --type fiat\n\n    # Show currency details\n    cryptofolio currency show USD\n\n    # Set exchange rate\n    cryptofolio currency set-rate CRC USD 550 --notes \"Bank rate\"\n    cryptofolio currency show-rate CRC USD\n    cryptofolio currency show-rate CRC USD --history\n\n    # Fetch live rates (ECB reference rates from api.frankfurter.app)\n    cryptofolio currency auto-rate MXN USD\n    cryptofolio currency refresh-rates")]
     Currency {
         #[command(subcommand)]
         command: CurrencyCommands,
     },
 
+    /// Back up or restore the SQLite database
+    #[command(after_help = "EXAMPLES:\n    cryptofolio db backup backup_2024.sqlite\n    cryptofolio db backup backup_2024.sqlite.gz --compress\n    cryptofolio db restore backup_2024.sqlite.gz")]
+    Db {
+        #[command(subcommand)]
+        command: DbCommands,
+    },
+
+    /// Run a command when an asset's price crosses a threshold
+    #[command(after_help = "EXAMPLES:\n    cryptofolio alert add BTC --above 100000 --command \"notify-send 'BTC hit 100k'\"\n    cryptofolio alert add ETH --below 2500\n    cryptofolio alert list\n    cryptofolio alert watch --interval 5\n    cryptofolio alert delete 3")]
+    Alert {
+        #[command(subcommand)]
+        command: AlertCommands,
+    },
+
+    /// Capital gains reports for tax filing
+    #[command(after_help = "EXAMPLES:\n    cryptofolio tax report --year 2024\n    cryptofolio tax report --year 2024 --method hifo\n    cryptofolio tax report --year 2024 --format csv --output-file gains-2024.csv")]
+    Tax {
+        #[command(subcommand)]
+        command: TaxCommands,
+    },
+
     /// Start interactive shell mode
-    #[command(after_help = "EXAMPLES:\n    cryptofolio shell\n\nIn shell mode, you can:\n    - Run commands without typing 'cryptofolio' prefix\n    - Use Tab for auto-completion\n    - Use Up/Down for command history\n    - Type natural language (AI mode)")]
-    Shell,
+    #[command(after_help = "EXAMPLES:\n    cryptofolio shell\n    cryptofolio shell --no-history\n    cryptofolio shell --live-prices\n    cryptofolio shell --no-persist-context\n\nIn shell mode, you can:\n    - Run commands without typing 'cryptofolio' prefix\n    - Use Tab for auto-completion\n    - Use Up/Down for command history\n    - Type natural language (AI mode)\n\nHISTORY:\n    Saved to history.txt in the config directory, or to the file named by\n    CRYPTOFOLIO_HISTORY_FILE. Size is set with display.shell_history_size.\n\nCONTEXT:\n    The last account used is saved to general.default_account on exit and\n    is the starting account of the next session.")]
+    Shell {
+        /// Don't load or save command history for this session
+        #[arg(long)]
+        no_history: bool,
+
+        /// Stream prices for held assets and show the live portfolio value in the prompt
+        #[arg(long)]
+        live_prices: bool,
+
+        /// Don't start from general.default_account or save the last account used to it on exit
+        #[arg(long)]
+        no_persist_context: bool,
+    },
 
     /// Show system status and diagnostics
     ///
     /// Displays information about the current configuration, database location,
     /// AI provider status (Claude API, Ollama), and network mode (testnet/mainnet).
     /// Useful for troubleshooting connectivity issues or verifying setup.
-    #[command(after_help = "EXAMPLES:\n    cryptofolio status\n    cryptofolio status --check\n\nThis command shows:\n    - Configuration file location\n    - Database file location\n    - Testnet/Mainnet mode\n    - Claude API connection status\n    - Ollama local LLM status\n    - Active AI provider")]
+    #[command(after_help = "EXAMPLES:\n    cryptofolio status\n    cryptofolio status --check\n\nThis command shows:\n    - Configuration file location\n    - Database file location\n    - Testnet/Mainnet mode\n    - Claude API connection status\n    - Ollama local LLM status\n    - Active AI provider\n\nOllama is only reported as connected once the model in ai.local_model\n(default llama3.2:3b) has been pulled. To use another model:\n    ollama pull llama3.1:8b\n    cryptofolio config set ai.local_model llama3.1:8b")]
     Status {
         /// Run connectivity checks for AI providers
         #[arg(long)]
         check: bool,
     },
+
+    /// Check how the configured AI provider understands typical requests
+    #[command(after_help = "EXAMPLES:\n    cryptofolio ai test\n    cryptofolio ai test --json\n\nEach check shows the intent the provider picked, its confidence and the\nentities it extracted: green when the intent is the expected one, yellow\nwhen it is but confidence is below 0.7, red when the intent is wrong.")]
+    Ai {
+        #[command(subcommand)]
+        command: AiCommands,
+    },
+
+    /// Watch the portfolio, redrawing it with live prices
+    ///
+    /// Refreshes every --interval seconds and shows each asset's price change
+    /// since the previous refresh. Press Ctrl+C to stop.
+    #[command(after_help = "EXAMPLES:\n    cryptofolio watch\n    cryptofolio watch --interval 10\n    cryptofolio watch --alert-pct 5")]
+    Watch {
+        /// Seconds between refreshes
+        #[arg(long, default_value = "30")]
+        interval: u64,
+
+        /// Ring the bell when an asset moves more than this percent between refreshes
+        #[arg(long, value_name = "PCT")]
+        alert_pct: Option<String>,
+    },
+
+    /// Run an MCP (Model Context Protocol) server exposing portfolio tools
+    ///
+    /// Lets AI assistants read the portfolio, holdings, accounts and prices,
+    /// record buys and sells, and sync accounts. Tools return the same JSON
+    /// as the matching command with --json.
+    #[command(after_help = "EXAMPLES:\n    # Serve over stdio (for Claude Desktop and other local clients)\n    cryptofolio mcp-server\n\n    # Serve over streamable HTTP at http://127.0.0.1:8765/mcp\n    cryptofolio mcp-server --transport http\n    cryptofolio mcp-server --transport http --bind 0.0.0.0:8765\n\nTOOLS:\n    get_portfolio, get_holdings, list_accounts, record_buy, record_sell,\n    sync_account, get_price")]
+    McpServer {
+        /// Transport to serve the protocol over
+        #[arg(long, value_enum, default_value = "stdio")]
+        transport: McpTransport,
+
+        /// Address to listen on with the HTTP transport
+        #[arg(long, default_value = "127.0.0.1:8765")]
+        bind: String,
+    },
 }
 
 #[derive(Subcommand)]
-pub enum AccountCommands {
-    /// List all accounts
+pub enum PortfolioCommands {
+    /// Save the current portfolio valuation for history and returns
+    #[command(after_help = "EXAMPLES:\n    cryptofolio portfolio snapshot\n\n    # Daily from cron\n    0 0 * * * cryptofolio portfolio snapshot --quiet")]
+    Snapshot,
+
+    /// List saved snapshots with 7d, 30d, 90d and 1y returns
+    #[command(after_help = "EXAMPLES:\n    cryptofolio portfolio history\n    cryptofolio portfolio history --from 2024-01-01 --limit 10\n    cryptofolio portfolio history --snapshot 12 --by-account\n    cryptofolio portfolio history --json")]
+    History {
+        /// Maximum number of snapshots to list
+        #[arg(long, default_value = "30")]
+        limit: i64,
+
+        /// Only include snapshots taken from this date (YYYY-MM-DD or ISO 8601)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Only include snapshots taken up to this date (YYYY-MM-DD or ISO 8601)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Show the holdings saved in one snapshot
+        #[arg(long, value_name = "ID")]
+        snapshot: Option<i64>,
+
+        /// Group the snapshot's holdings by account
+        #[arg(long = "by-account", requires = "snapshot")]
+        by_account: bool,
+
+        /// Group the snapshot's holdings by category
+        #[arg(long = "by-category", requires = "snapshot")]
+        by_category: bool,
+    },
+
+    /// Suggest trades that bring the portfolio to a target allocation
+    ///
+    /// Targets are shares of the whole portfolio at current prices. Held assets
+    /// missing from the target are aimed at 0% and sold off.
+    #[command(after_help = "EXAMPLES:\n    cryptofolio portfolio rebalance --target BTC:60,ETH:30,SOL:10 --dry-run\n    cryptofolio portfolio rebalance --target BTC:60,ETH:30,SOL:10 --threshold 2.0 --dry-run\n    cryptofolio portfolio rebalance --target BTC:50,ETH:50 --account Binance")]
+    Rebalance {
+        /// Target allocation as ASSET:PERCENT pairs adding up to 100
+        #[arg(long, required = true, value_name = "ALLOCATION")]
+        target: String,
+
+        /// Only suggest trades for assets more than this many percentage points off target
+        #[arg(long, default_value = "0", value_name = "PCT")]
+        threshold: String,
+
+        /// Account to record the suggested trades in
+        #[arg(long, required_unless_present = "dry_run")]
+        account: Option<String>,
+
+        /// Only show the plan, without recording any trades
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SyncCommands {
+    /// Show the history of sync runs
+    #[command(after_help = "EXAMPLES:\n    cryptofolio sync log\n    cryptofolio sync log --account Binance --limit 5")]
+    Log {
+        /// Only show runs for this account
+        #[arg(long)]
+        account: Option<String>,
+
+        /// Maximum number of runs to show
+        #[arg(long, default_value = "20")]
+        limit: i64,
+    },
+
+    /// Show past sync runs with the quantity changes each one made
+    #[command(after_help = "EXAMPLES:\n    cryptofolio sync history\n    cryptofolio sync history --account Binance --limit 5")]
+    History {
+        /// Only show runs for this account
+        #[arg(long)]
+        account: Option<String>,
+
+        /// Maximum number of runs to show
+        #[arg(long, default_value = "20")]
+        limit: i64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ImportCommands {
+    /// Import a directory created by 'export full'
+    #[command(after_help = "EXAMPLES:\n    cryptofolio import full --input ./backup --merge\n    cryptofolio import full --input ./backup --replace --yes")]
+    Full {
+        /// Directory containing the exported JSON files
+        #[arg(long)]
+        input: String,
+
+        /// Keep existing data and add only missing records
+        #[arg(long, conflicts_with = "replace", required_unless_present = "replace")]
+        merge: bool,
+
+        /// Delete all existing data before importing
+        #[arg(long)]
+        replace: bool,
+
+        /// Skip confirmation prompt for --replace
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ExportCommands {
+    /// Export accounts, holdings, transactions, exchange rates and categories as JSON
+    Full {
+        /// Output directory (created if missing)
+        #[arg(long)]
+        output: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AiCommands {
+    /// Run built-in natural language checks against the configured provider
+    Test,
+}
+
+#[derive(Subcommand)]
+pub enum DbCommands {
+    /// Write a consistent copy of the database, safe while it is in use
+    Backup {
+        /// Backup file to create
+        file: String,
+
+        /// Gzip the backup
+        #[arg(long)]
+        compress: bool,
+    },
+
+    /// Replace the database with a backup (plain or gzipped)
+    Restore {
+        /// Backup file to restore
+        file: String,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AlertCommands {
+    /// Add a price alert
+    #[command(group(clap::ArgGroup::new("threshold").required(true).args(["above", "below"])))]
+    Add {
+        /// Asset symbol (e.g., BTC)
+        asset: String,
+
+        /// Alert when the price rises to this USD value or higher
+        #[arg(long, value_name = "PRICE")]
+        above: Option<String>,
+
+        /// Alert when the price falls to this USD value or lower
+        #[arg(long, value_name = "PRICE")]
+        below: Option<String>,
+
+        /// Shell command to run when the alert goes off (default: a desktop notification on macOS)
+        #[arg(long)]
+        command: Option<String>,
+    },
+
+    /// List alerts, including ones that have already gone off
     List,
 
+    /// Delete an alert
+    Delete {
+        /// Alert ID (shown by 'alert list')
+        id: i64,
+    },
+
+    /// Check prices periodically and run the commands of alerts that go off
+    ///
+    /// Each alert goes off once. The command runs through the shell with
+    /// CRYPTOFOLIO_ALERT_ASSET, CRYPTOFOLIO_ALERT_PRICE and
+    /// CRYPTOFOLIO_ALERT_MESSAGE set. Press Ctrl+C to stop.
+    Watch {
+        /// Minutes between price checks
+        #[arg(long, default_value = "5")]
+        interval: u64,
+
+        /// Check once and exit (e.g., from cron)
+        #[arg(long)]
+        once: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TaxCommands {
+    /// Realized gains of a tax year, lot by lot, with short- and long-term totals
+    Report {
+        /// Calendar year of the sales to report
+        #[arg(long)]
+        year: i32,
+
+        /// Cost basis method: fifo, lifo, hifo or average
+        #[arg(long, default_value = "fifo")]
+        method: String,
+
+        /// Output format (table, json, csv); --json is the same as --format json
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Write the report to a file instead of stdout (parent directories are created)
+        #[arg(long)]
+        output_file: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AccountCommands {
+    /// List accounts
+    #[command(after_help = "EXAMPLES:\n    cryptofolio account list\n    cryptofolio account list --type exchange\n    cryptofolio account list --type hardware-wallet --type software-wallet\n    cryptofolio account list --category cold-storage\n    cryptofolio account list --sync-enabled")]
+    List {
+        /// Only show accounts of this type (repeat to combine types)
+        #[arg(long = "type", value_enum, value_name = "TYPE")]
+        account_type: Vec<AccountTypeArg>,
+
+        /// Only show accounts in this category
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Only show accounts with auto-sync enabled
+        #[arg(long)]
+        sync_enabled: bool,
+    },
+
     /// Add a new account
-    #[command(after_help = "EXAMPLES:\n    cryptofolio account add \"Ledger\" --type hardware_wallet --category cold-storage\n    cryptofolio account add \"Binance\" --type exchange --category trading --sync --testnet")]
+    #[command(after_help = "EXAMPLES:\n    cryptofolio account add \"Ledger\" --type hardware_wallet --category cold-storage\n    cryptofolio account add \"Binance\" --type exchange --category trading --sync --testnet\n    cryptofolio account add \"Kraken\" --type exchange --category trading --sync --exchange kraken")]
     Add {
         /// Account name
         name: String,
@@ -254,6 +696,10 @@ pub enum AccountCommands {
         /// Enable auto-sync (for exchanges)
         #[arg(long)]
         sync: bool,
+
+        /// Exchange to sync from (for exchanges)
+        #[arg(long, value_enum, default_value = "binance")]
+        exchange: ExchangeArg,
     },
 
     /// Remove an account
@@ -267,12 +713,54 @@ pub enum AccountCommands {
         yes: bool,
     },
 
+    /// Rename an account
+    #[command(after_help = "EXAMPLES:\n    cryptofolio account rename \"Old Wallet\" \"Ledger Nano X\"")]
+    Rename {
+        /// Current name
+        old_name: String,
+
+        /// New name
+        new_name: String,
+    },
+
     /// Show account details
     Show {
         /// Account name
         name: String,
     },
 
+    /// Configure per-account sync options
+    #[command(after_help = "EXAMPLES:\n    cryptofolio account sync-settings --account Binance --exclude-assets LDBTC,LDTAO\n    cryptofolio account sync-settings --account Binance --rate-limit 10\n    cryptofolio account sync-settings --account Binance")]
+    SyncSettings {
+        /// Account name
+        #[arg(long, required = true)]
+        account: String,
+
+        /// Comma-separated assets to skip during sync (e.g., Simple Earn tokens)
+        #[arg(long, value_delimiter = ',')]
+        exclude_assets: Option<Vec<String>>,
+
+        /// Maximum Binance API requests per second during sync
+        #[arg(long)]
+        rate_limit: Option<u32>,
+
+        /// Remove all sync settings for the account
+        #[arg(long, conflicts_with_all = ["exclude_assets", "rate_limit"])]
+        clear: bool,
+    },
+
+    /// Compare an exchange account's holdings with its live balances
+    #[command(after_help = "EXAMPLES:\n    cryptofolio account verify --account Binance\n    cryptofolio account verify --account Binance --json\n\n    # Set every mismatched holding to the exchange's balance\n    cryptofolio account verify --account Binance --fix\n\nSTATUS:\n    OK     local quantity matches the exchange\n    OVER   more recorded locally than on the exchange (e.g., a manual buy on top of a sync)\n    UNDER  less recorded locally than on the exchange")]
+    Verify {
+        /// Exchange account name
+        #[arg(long, required = true)]
+        account: String,
+
+        /// Set mismatched holdings to the exchange's balances
+        #[arg(long)]
+        fix: bool,
+    },
+
     /// Manage wallet addresses
     Address {
         #[command(subcommand)]
@@ -299,6 +787,73 @@ impl AccountTypeArg {
             AccountTypeArg::Bank => "bank",
         }
     }
+    pub fn account_type(&self) -> AccountType {
+        match self {
+            AccountTypeArg::Exchange => AccountType::Exchange,
+            AccountTypeArg::HardwareWallet => AccountType::HardwareWallet,
+            AccountTypeArg::SoftwareWallet => AccountType::SoftwareWallet,
+            AccountTypeArg::CustodialService => AccountType::CustodialService,
+            AccountTypeArg::Bank => AccountType::Bank,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum TxTypeArg {
+    Buy,
+    Sell,
+    /// Transfers in, out and between accounts
+    Transfer,
+    Swap,
+    Receive,
+    Fee,
+    StakingReward,
+}
+
+impl TxTypeArg {
+    pub fn transaction_types(self) -> &'static [TransactionType] {
+        match self {
+            TxTypeArg::Buy => &[TransactionType::Buy],
+            TxTypeArg::Sell => &[TransactionType::Sell],
+            TxTypeArg::Transfer => &[
+                TransactionType::TransferIn,
+                TransactionType::TransferOut,
+                TransactionType::TransferInternal,
+            ],
+            TxTypeArg::Swap => &[TransactionType::Swap],
+            TxTypeArg::Receive => &[TransactionType::Receive],
+            TxTypeArg::Fee => &[TransactionType::Fee],
+            TxTypeArg::StakingReward => &[TransactionType::StakingReward],
+        }
+    }
+}
+
+#[derive(Clone, ValueEnum)]
+pub enum ExchangeArg {
+    Binance,
+    Kraken,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum MarketSourceArg {
+    Binance,
+    Coingecko,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    /// Comma-separated rows, for spreadsheets
+    Csv,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum McpTransport {
+    /// JSON-RPC over stdin and stdout
+    Stdio,
+    /// Streamable HTTP, served at /mcp
+    Http,
 }
 
 #[derive(Subcommand)]
@@ -317,6 +872,10 @@ pub enum AddressCommands {
         /// Optional label
         #[arg(long)]
         label: Option<String>,
+
+        /// Store the address without checking its format
+        #[arg(long)]
+        no_validate: bool,
     },
 
     /// List addresses for an account
@@ -355,6 +914,21 @@ pub enum CategoryCommands {
         new_name: String,
     },
 
+    /// Change the order categories are listed in
+    #[command(after_help = "EXAMPLES:\n    # Show Cold Storage first\n    cryptofolio category reorder \"Cold Storage\" 1\n\n    # Type a new order for every category\n    cryptofolio category reorder --interactive")]
+    Reorder {
+        /// Category name
+        #[arg(required_unless_present = "interactive", requires = "position")]
+        name: Option<String>,
+
+        /// New position, starting at 1
+        position: Option<usize>,
+
+        /// Show the categories as a numbered list and prompt for the new order
+        #[arg(short, long, conflicts_with_all = ["name", "position"])]
+        interactive: bool,
+    },
+
     /// Remove a category
     Remove {
         /// Category name
@@ -369,14 +943,47 @@ pub enum CategoryCommands {
 #[derive(Subcommand)]
 pub enum HoldingsCommands {
     /// List holdings
+    #[command(after_help = "EXAMPLES:\n    cryptofolio holdings list\n    cryptofolio holdings list --account Ledger --account Trezor\n    cryptofolio holdings list --top 10\n    cryptofolio holdings list --unrealized-gain-only --top 5\n    cryptofolio holdings list --csv > holdings.csv\n    cryptofolio holdings list --no-prices\n    cryptofolio holdings list --tag long-term --verbose")]
     List {
-        /// Filter by account
+        /// Filter by account (repeat to combine several accounts)
         #[arg(long)]
-        account: Option<String>,
+        account: Vec<String>,
+
+        /// Show only the N largest positions by current value
+        #[arg(long, value_name = "N")]
+        top: Option<usize>,
+
+        /// Sort by current value, largest first (fetches live prices)
+        #[arg(long)]
+        by_value: bool,
+
+        /// Show only positions with a positive unrealized P&L
+        #[arg(long, conflicts_with = "unrealized_loss_only")]
+        unrealized_gain_only: bool,
+
+        /// Show only positions with a negative unrealized P&L
+        #[arg(long)]
+        unrealized_loss_only: bool,
+
+        /// Skip fetching live prices and show quantities and cost basis only
+        #[arg(long, conflicts_with_all = ["top", "by_value", "unrealized_gain_only", "unrealized_loss_only"])]
+        no_prices: bool,
+
+        /// Live prices are now shown by default; kept so existing scripts still work
+        #[arg(long, hide = true, conflicts_with = "no_prices")]
+        with_prices: bool,
+
+        /// Print bare CSV to stdout
+        #[arg(long, conflicts_with = "json")]
+        csv: bool,
+
+        /// Show only holdings with this tag
+        #[arg(long)]
+        tag: Option<String>,
     },
 
     /// Add to holdings
-    #[command(after_help = "EXAMPLES:\n    cryptofolio holdings add BTC 0.5 --account Ledger\n    cryptofolio holdings add BTC 0.5 --account Ledger --cost 45000")]
+    #[command(after_help = "EXAMPLES:\n    cryptofolio holdings add BTC 0.5 --account Ledger\n    cryptofolio holdings add BTC 0.5 --account Ledger --cost 45000\n    cryptofolio holdings add BTC 0.5 --account Ledger --date 2023-06-01")]
     Add {
         /// Asset symbol (e.g., BTC)
         asset: String,
@@ -391,6 +998,11 @@ pub enum HoldingsCommands {
         /// Cost per unit in USD
         #[arg(long)]
         cost: Option<String>,
+
+        /// Acquisition date (YYYY-MM-DD or ISO 8601); without --cost, the price
+        /// comes from the latest portfolio snapshot on or before that date
+        #[arg(long)]
+        date: Option<String>,
     },
 
     /// Remove from holdings
@@ -416,7 +1028,8 @@ pub enum HoldingsCommands {
         asset: String,
 
         /// Exact quantity
-        quantity: String,
+        #[arg(required_unless_present = "from_exchange")]
+        quantity: Option<String>,
 
         /// Account name
         #[arg(long, required = true)]
@@ -425,6 +1038,22 @@ pub enum HoldingsCommands {
         /// Cost per unit in USD
         #[arg(long)]
         cost: Option<String>,
+
+        /// Add the quantity to the current holding instead (same as 'holdings add')
+        #[arg(long)]
+        delta: bool,
+
+        /// Use the exchange account's live balance of the asset as the quantity
+        #[arg(long, conflicts_with_all = ["quantity", "delta"])]
+        from_exchange: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Show the current and new quantity without changing anything
+        #[arg(long, conflicts_with = "delta")]
+        dry_run: bool,
     },
 
     /// Move holdings between accounts
@@ -446,12 +1075,128 @@ pub enum HoldingsCommands {
         /// Skip confirmation prompt
         #[arg(short, long)]
         yes: bool,
+
+        /// Simulate without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Fold one account into another and delete it (e.g., a duplicate created by mistake)
+    #[command(after_help = "EXAMPLES:\n    cryptofolio holdings merge --from \"Binance Auto\" --to Binance --dry-run\n    cryptofolio holdings merge --from \"Binance Auto\" --to Binance --yes\n\nHoldings of the same asset are added together at their weighted-average cost basis.\nTransactions, tax lots, sync history and wallet addresses move to the target account.")]
+    Merge {
+        /// Account to merge and delete
+        #[arg(long, required = true)]
+        from: String,
+
+        /// Account that keeps everything
+        #[arg(long, required = true)]
+        to: String,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Show the merge plan without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Find holdings and transactions of missing accounts, and negative holdings
+    #[command(after_help = "EXAMPLES:\n    cryptofolio holdings check\n    cryptofolio holdings check --fix\n\nWith --fix, holdings and transactions of accounts that no longer exist move to\nan '[Unknown]' account (created if needed) and negative holdings are set to zero.")]
+    Check {
+        /// Repair what the check finds
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Bulk-load holdings from a YAML, TOML or JSON file
+    #[command(after_help = "EXAMPLES:\n    cryptofolio holdings import holdings.yaml\n    cryptofolio holdings import holdings.yaml --skip-existing\n    cryptofolio holdings import backup.toml --merge\n\nFILE FORMAT:\n    accounts:\n      - name: Ledger\n        holdings:\n          - asset: BTC\n            quantity: 0.5\n            cost: 45000\n\nAccounts must already exist. 'cost' is the USD cost per unit and is optional.")]
+    Import {
+        /// Holdings file to read
+        file: String,
+
+        /// File format: yaml, toml or json (default: from the file extension)
+        #[arg(long)]
+        format: Option<String>,
+
+        /// Overwrite holdings that already exist with the file's values
+        #[arg(long, conflicts_with = "skip_existing")]
+        merge: bool,
+
+        /// Leave holdings that already exist untouched
+        #[arg(long)]
+        skip_existing: bool,
+    },
+
+    /// Write all holdings to a YAML, TOML or JSON file for backup or 'holdings import'
+    #[command(after_help = "EXAMPLES:\n    cryptofolio holdings export holdings.yaml\n    cryptofolio holdings export backup.toml\n    cryptofolio holdings export backup.txt --format yaml")]
+    Export {
+        /// File to write
+        file: String,
+
+        /// File format: yaml, toml or json (default: from the file extension)
+        #[arg(long)]
+        format: Option<String>,
+    },
+
+    /// Show how a holding's quantity changed over time
+    #[command(after_help = "EXAMPLES:\n    cryptofolio holdings history --account Binance --asset BTC\n    cryptofolio holdings history --account Ledger --asset ETH --json\n\nLists buys, sells, transfers and swaps alongside syncs and manual edits,\nwith the balance after each one.")]
+    History {
+        /// Account name
+        #[arg(long, required = true)]
+        account: String,
+
+        /// Asset symbol (e.g., BTC)
+        #[arg(long, required = true)]
+        asset: String,
+    },
+
+    /// Report staking rewards and the annualized yield of a holding
+    #[command(after_help = "EXAMPLES:\n    cryptofolio holdings yield --account Binance --asset LDETH\n    cryptofolio holdings yield --account Ledger --asset DOT --json\n\nRewards are transactions recorded with 'tx stake-reward'. The yield is\nannualized against the average quantity held since the holding started.")]
+    Yield {
+        /// Account name
+        #[arg(long, required = true)]
+        account: String,
+
+        /// Asset symbol (e.g., LDETH)
+        #[arg(long, required = true)]
+        asset: String,
+    },
+
+    /// Add tags to a holding
+    #[command(after_help = "EXAMPLES:\n    cryptofolio holdings tag BTC --account Ledger --tags long-term\n    cryptofolio holdings tag ETH --account Binance --tags staking,yield")]
+    Tag {
+        /// Asset symbol (e.g., BTC)
+        asset: String,
+
+        /// Account name
+        #[arg(long, required = true)]
+        account: String,
+
+        /// Comma-separated tags
+        #[arg(long, required = true, value_delimiter = ',')]
+        tags: Vec<String>,
+    },
+
+    /// Remove tags from a holding
+    Untag {
+        /// Asset symbol (e.g., BTC)
+        asset: String,
+
+        /// Account name
+        #[arg(long, required = true)]
+        account: String,
+
+        /// Comma-separated tags
+        #[arg(long, required = true, value_delimiter = ',')]
+        tags: Vec<String>,
     },
 }
 
 #[derive(Subcommand)]
 pub enum TxCommands {
     /// List transactions
+    #[command(after_help = "EXAMPLES:\n    cryptofolio tx list --account Binance\n    cryptofolio tx list --type buy --from 2024-01-01 --to 2024-12-31\n    cryptofolio tx list --type swap --asset ETH --json\n    cryptofolio tx list --search DCA\n    cryptofolio tx list --page 2 --page-size 50")]
     List {
         /// Filter by account
         #[arg(long)]
@@ -460,10 +1205,82 @@ pub enum TxCommands {
         /// Maximum number of transactions
         #[arg(long, default_value = "50")]
         limit: i64,
+
+        /// Only show transactions of this type (repeat to combine types)
+        #[arg(long = "type", value_enum, value_name = "TYPE")]
+        tx_type: Vec<TxTypeArg>,
+
+        /// Only show transactions involving this asset
+        #[arg(long)]
+        asset: Option<String>,
+
+        /// Only show transactions from this date (YYYY-MM-DD or ISO 8601)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Only show transactions up to this date (YYYY-MM-DD or ISO 8601)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Show the portfolio value after each transaction
+        #[arg(long, conflicts_with_all = ["search", "tx_type", "asset", "from", "to"])]
+        running_balance: bool,
+
+        /// Only show transactions whose notes contain this text
+        #[arg(long)]
+        search: Option<String>,
+
+        /// Match --search case-sensitively
+        #[arg(long, requires = "search")]
+        case_sensitive: bool,
+
+        /// Show this page of transactions, newest first (starts at 1)
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["limit", "running_balance"])]
+        page: Option<u32>,
+
+        /// Transactions per page when paging [default: 50]
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["limit", "running_balance"])]
+        page_size: Option<u32>,
+    },
+
+    /// Search transactions by notes and asset
+    #[command(after_help = "EXAMPLES:\n    cryptofolio tx search \"DCA purchase\"\n    cryptofolio tx search eth\n    cryptofolio tx search --notes \"Ledger\" --asset BTC --from 2024-01-01")]
+    Search {
+        /// Text to find in notes or asset symbols (case-insensitive)
+        #[arg(required_unless_present_any = ["notes", "asset", "account", "tx_type", "from", "to"])]
+        query: Option<String>,
+
+        /// Only show transactions whose notes contain this text
+        #[arg(long)]
+        notes: Option<String>,
+
+        /// Only show transactions involving this asset
+        #[arg(long)]
+        asset: Option<String>,
+
+        /// Filter by account
+        #[arg(long)]
+        account: Option<String>,
+
+        /// Only show transactions of this type (repeat to combine types)
+        #[arg(long = "type", value_enum, value_name = "TYPE")]
+        tx_type: Vec<TxTypeArg>,
+
+        /// Only show transactions from this date (YYYY-MM-DD or ISO 8601)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Only show transactions up to this date (YYYY-MM-DD or ISO 8601)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Maximum number of transactions
+        #[arg(long, default_value = "50")]
+        limit: i64,
     },
 
     /// Record a buy transaction
-    #[command(after_help = "EXAMPLES:\n    cryptofolio tx buy BTC 0.1 --account Binance --price 95000\n    cryptofolio tx buy ETH 2.0 --account Binance --price 3200 --notes \"DCA\"")]
+    #[command(after_help = "EXAMPLES:\n    cryptofolio tx buy BTC 0.1 --account Binance --price 95000\n    cryptofolio tx buy ETH 2.0 --account Binance --price 3200 --notes \"DCA\"\n    cryptofolio tx buy BTC 0.1 --account Binance --price 95000 --fee 0.01 --fee-asset BNB")]
     Buy {
         /// Asset symbol (e.g., BTC)
         asset: String,
@@ -479,10 +1296,22 @@ pub enum TxCommands {
         #[arg(long, required = true)]
         price: String,
 
+        /// Trading fee quantity
+        #[arg(long)]
+        fee: Option<String>,
+
+        /// Asset the fee was paid in, deducted from that holding (default: USD, paid in cash)
+        #[arg(long, requires = "fee")]
+        fee_asset: Option<String>,
+
         /// Transaction notes
         #[arg(long)]
         notes: Option<String>,
 
+        /// Accept more decimal places than display.decimals
+        #[arg(long)]
+        allow_high_precision: bool,
+
         /// Simulate without making changes
         #[arg(long)]
         dry_run: bool,
@@ -504,10 +1333,22 @@ pub enum TxCommands {
         #[arg(long, required = true)]
         price: String,
 
+        /// Trading fee quantity
+        #[arg(long)]
+        fee: Option<String>,
+
+        /// Asset the fee was paid in, deducted from that holding (default: USD, paid in cash)
+        #[arg(long, requires = "fee")]
+        fee_asset: Option<String>,
+
         /// Transaction notes
         #[arg(long)]
         notes: Option<String>,
 
+        /// Accept more decimal places than display.decimals
+        #[arg(long)]
+        allow_high_precision: bool,
+
         /// Simulate without making changes
         #[arg(long)]
         dry_run: bool,
@@ -537,6 +1378,10 @@ pub enum TxCommands {
         #[arg(long)]
         notes: Option<String>,
 
+        /// Accept more decimal places than display.decimals
+        #[arg(long)]
+        allow_high_precision: bool,
+
         /// Simulate without making changes
         #[arg(long)]
         dry_run: bool,
@@ -553,8 +1398,9 @@ pub enum TxCommands {
         /// Destination asset (e.g., BTC)
         to_asset: String,
 
-        /// Destination quantity
-        to_quantity: String,
+        /// Destination quantity (omit with --use-spot-rate to look it up)
+        #[arg(required_unless_present = "use_spot_rate")]
+        to_quantity: Option<String>,
 
         /// Account name
         #[arg(long, required = true)]
@@ -564,20 +1410,101 @@ pub enum TxCommands {
         #[arg(long)]
         rate: Option<String>,
 
+        /// For fiat swaps: record the current market rate instead of the one implied by the quantities
+        #[arg(long, conflicts_with = "rate")]
+        fetch_rate: bool,
+
+        /// Compute the destination quantity from current Binance spot prices
+        #[arg(long, conflicts_with = "to_quantity")]
+        use_spot_rate: bool,
+
+        /// Transaction notes
+        #[arg(long)]
+        notes: Option<String>,
+
+        /// Skip confirmation of the spot-rate quantity
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        /// Accept more decimal places than display.decimals
+        #[arg(long)]
+        allow_high_precision: bool,
+
+        /// Simulate without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Record a staking or earn reward
+    #[command(name = "stake-reward", after_help = "EXAMPLES:\n    cryptofolio tx stake-reward --account Binance --asset LDETH --amount 0.0012\n    cryptofolio tx stake-reward --account Ledger --asset DOT --amount 0.35 --price 6.80\n\nThe reward is added to the holding with a cost basis of the market price\nat the time, looked up on Binance unless --price is given.")]
+    StakeReward {
+        /// Account that received the reward
+        #[arg(long, required = true)]
+        account: String,
+
+        /// Asset received (e.g., LDETH)
+        #[arg(long, required = true)]
+        asset: String,
+
+        /// Reward amount
+        #[arg(long, required = true)]
+        amount: String,
+
+        /// Price per unit in USD (default: current market price)
+        #[arg(long)]
+        price: Option<String>,
+
         /// Transaction notes
         #[arg(long)]
         notes: Option<String>,
 
+        /// Accept more decimal places than display.decimals
+        #[arg(long)]
+        allow_high_precision: bool,
+
+        /// Simulate without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Record a standalone fee (gas, staking or DeFi costs)
+    #[command(after_help = "EXAMPLES:\n    cryptofolio tx fee --account MetaMask --asset ETH --amount 0.003 --notes \"Uniswap swap fee\"\n    cryptofolio tx fee --account Ledger --asset BTC --amount 0.0001 --dry-run\n\nThe amount is removed from the account's holding of the asset.")]
+    Fee {
+        /// Account that paid the fee
+        #[arg(long, required = true)]
+        account: String,
+
+        /// Asset the fee was paid in (e.g., ETH)
+        #[arg(long, required = true)]
+        asset: String,
+
+        /// Fee amount
+        #[arg(long, required = true)]
+        amount: String,
+
+        /// Transaction notes
+        #[arg(long)]
+        notes: Option<String>,
+
+        /// Accept more decimal places than display.decimals
+        #[arg(long)]
+        allow_high_precision: bool,
+
         /// Simulate without making changes
         #[arg(long)]
         dry_run: bool,
     },
 
     /// Export transactions to file
-    #[command(after_help = "EXAMPLES:\n    # Export all transactions to CSV\n    cryptofolio tx export transactions.csv\n\n    # Export to JSON format\n    cryptofolio tx export transactions.json --format json\n\n    # Export to SQL format\n    cryptofolio tx export transactions.sql --format sql\n\n    # Export filtered transactions\n    cryptofolio tx export binance-2024.csv --account Binance\n    cryptofolio tx export btc-trades.json --asset BTC --format json\n\n    # Export with date range\n    cryptofolio tx export q1-2024.csv --from 2024-01-01 --to 2024-03-31\n\nFORMATS:\n    csv  - CSV format (default, compatible with import)\n    json - JSON array format\n    sql  - SQL INSERT statements")]
+    #[command(after_help = "EXAMPLES:\n    # Export all transactions to CSV\n    cryptofolio tx export transactions.csv\n\n    # Export to JSON format\n    cryptofolio tx export transactions.json --format json\n\n    # Export to SQL format\n    cryptofolio tx export transactions.sql --format sql\n\n    # Export filtered transactions\n    cryptofolio tx export binance-2024.csv --account Binance\n    cryptofolio tx export btc-trades.json --asset BTC --format json\n\n    # Export with date range\n    cryptofolio tx export q1-2024.csv --from 2024-01-01 --to 2024-03-31\n\n    # Pipe to other tools\n    cryptofolio tx export --stdout --format json | jq '.[] | select(.tx_type == \"buy\")'\n\nFORMATS:\n    csv  - CSV format (default, compatible with import)\n    json - JSON array format\n    sql  - SQL INSERT statements")]
     Export {
-        /// Output file path
-        file: String,
+        /// Output file path (use '-' for stdout)
+        #[arg(required_unless_present = "stdout")]
+        file: Option<String>,
+
+        /// Write to stdout instead of a file
+        #[arg(long, conflicts_with = "file")]
+        stdout: bool,
 
         /// Export format (csv, json, sql)
         #[arg(long, default_value = "csv")]
@@ -603,6 +1530,25 @@ pub enum TxCommands {
         #[arg(long, default_value = "0")]
         limit: i64,
     },
+
+    /// Delete an erroneous transaction record
+    #[command(after_help = "EXAMPLES:\n    cryptofolio tx delete 42\n    cryptofolio tx delete 42 --adjust-holdings --dry-run\n    cryptofolio tx delete 42 --adjust-holdings --yes\n\nTransaction IDs are shown by 'cryptofolio tx list'. Holdings are left\nunchanged unless --adjust-holdings is given.")]
+    Delete {
+        /// Transaction ID
+        id: i64,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Reverse the holding changes made by the transaction
+        #[arg(long)]
+        adjust_holdings: bool,
+
+        /// Show what would be deleted without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -610,8 +1556,19 @@ pub enum ConfigCommands {
     /// Show current configuration
     Show,
 
+    /// Get a single configuration value
+    #[command(after_help = "EXAMPLES:\n    cryptofolio config get general.use_testnet\n    TESTNET=$(cryptofolio config get general.use_testnet --quiet)\n\nUse the same keys as 'config set'. Secret values are masked.")]
+    Get {
+        /// Configuration key (e.g., general.use_testnet)
+        key: String,
+    },
+
+    /// List all configuration keys with their current values and types
+    #[command(name = "list-keys")]
+    ListKeys,
+
     /// Set a configuration value
-    #[command(after_help = "EXAMPLES:\n    cryptofolio config set general.use_testnet true\n    cryptofolio config set display.color false\n    cryptofolio config set display.decimals 6\n    cryptofolio config set display.thousands_separator true\n\n⚠️  WARNING: For API keys/secrets, use 'config set-secret' instead!\n\nKEYS:\n    general.use_testnet          Enable testnet mode (true/false)\n    general.default_account       Default account name\n    display.color                 Enable colors (true/false)\n    display.decimals              Decimal places for quantities (0-18, default: 8)\n    display.price_decimals        Decimal places for prices (0-18, default: 2)\n    display.thousands_separator   Use thousands separator (true/false, default: true)")]
+    #[command(after_help = "EXAMPLES:\n    cryptofolio config set general.use_testnet true\n    cryptofolio config set display.color false\n    cryptofolio config set display.decimals 6\n    cryptofolio config set display.thousands_separator true\n\n⚠️  WARNING: For API keys/secrets, use 'config set-secret' instead!\n\nKEYS:\n    general.use_testnet          Enable testnet mode (true/false)\n    general.default_account       Default account name\n    display.color                 Enable colors (true/false)\n    display.decimals              Decimal places for quantities (0-18, default: 8)\n    display.price_decimals        Decimal places for prices (0-18, default: 2)\n    display.thousands_separator   Use thousands separator (true/false, default: true)\n    display.thousands_separator_char  Thousands separator character (default: ,)\n    display.decimal_separator     Decimal separator character (default: .)\n    display.currency_symbol       Symbol for currency amounts (default: $)\n    display.currency_symbol_after Write the symbol after the amount (true/false, default: false)\n    display.shell_history_size    Commands kept in the shell history (default: 1000)")]
     Set {
         /// Configuration key (e.g., general.use_testnet)
         key: String,
@@ -624,6 +1581,7 @@ pub enum ConfigCommands {
     ///
     /// SECURITY NOTICE (v0.3+):
     ///   On macOS: Secrets are stored in macOS Keychain with Touch ID protection
+    ///   On Linux: Secrets are stored in the Secret Service (GNOME Keyring, KWallet)
     ///   Other platforms: Secrets are stored in plaintext in ~/.config/cryptofolio/config.toml
     ///
     ///   IMPORTANT: Only use READ-ONLY API keys!
@@ -635,7 +1593,7 @@ pub enum ConfigCommands {
     ///   3. Disable: Trading, Withdrawals, Internal Transfer
     ///   4. IP restrictions recommended (optional but safer)
     #[command(name = "set-secret")]
-    #[command(after_help = "EXAMPLES:\n    # Interactive (hidden input)\n    cryptofolio config set-secret binance.api_secret\n\n    # macOS: Store with Touch ID protection\n    cryptofolio config set-secret binance.api_secret --security-level touchid\n\n    # From stdin (for scripts)\n    echo \"secret\" | cryptofolio config set-secret binance.api_secret\n\n    # From file\n    cryptofolio config set-secret binance.api_secret --secret-file ~/.secrets/key\n\n    # From environment variable\n    cryptofolio config set-secret binance.api_secret --from-env MY_SECRET\n\nSECURITY LEVELS (macOS only):\n    standard          Protected by macOS encryption (good for automation)\n    touchid           Require Touch ID or password (recommended)\n    touchid-only      ONLY Touch ID, no password fallback (maximum security)")]
+    #[command(after_help = "EXAMPLES:\n    # Interactive (hidden input)\n    cryptofolio config set-secret binance.api_secret\n\n    # macOS: Store with Touch ID protection\n    cryptofolio config set-secret binance.api_secret --security-level touchid\n\n    # From stdin (for scripts)\n    echo \"secret\" | cryptofolio config set-secret binance.api_secret\n\n    # From file\n    cryptofolio config set-secret binance.api_secret --secret-file ~/.secrets/key\n\n    # From environment variable\n    cryptofolio config set-secret binance.api_secret --from-env MY_SECRET\n\nSECURITY LEVELS (macOS and Linux):\n    standard          Protected by macOS encryption (good for automation)\n    touchid           Require Touch ID or password (recommended)\n    touchid-only      ONLY Touch ID, no password fallback (maximum security)\n\n    On Linux, standard uses the default keyring collection, and touchid\n    and touchid-only use a separate collection that prompts on use.")]
     SetSecret {
         /// Config key (e.g., binance.api_secret)
         key: String,
@@ -648,9 +1606,13 @@ pub enum ConfigCommands {
         #[arg(long)]
         from_env: Option<String>,
 
-        /// Security level for keychain storage (macOS only): standard, touchid, touchid-only
+        /// Security level for keychain storage (macOS and Linux): standard, touchid, touchid-only
         #[arg(long)]
         security_level: Option<String>,
+
+        /// Skip the Binance API key format check
+        #[arg(long)]
+        no_validate: bool,
     },
 
     /// Enable testnet mode
@@ -661,10 +1623,33 @@ pub enum ConfigCommands {
     #[command(name = "use-mainnet")]
     UseMainnet,
 
-    /// Migrate secrets from TOML to macOS Keychain (macOS only)
+    /// Export configuration to a portable TOML file (secrets excluded)
+    #[command(after_help = "EXAMPLES:\n    cryptofolio config export config_backup.toml\n    cryptofolio config export config_backup.toml --include-secrets\n\nWith --include-secrets, configured secrets are written as placeholders\nso the file lists which ones to set again; their values are never exported.")]
+    Export {
+        /// Output file
+        file: std::path::PathBuf,
+
+        /// Mark configured secrets with placeholders
+        #[arg(long)]
+        include_secrets: bool,
+    },
+
+    /// Import configuration from a file written by 'config export'
+    #[command(after_help = "EXAMPLES:\n    cryptofolio config import config_backup.toml\n    cryptofolio config import config_backup.toml --overwrite\n\nValues you have changed from their defaults are only replaced after\nconfirmation, unless --overwrite is given.")]
+    Import {
+        /// File to import
+        file: std::path::PathBuf,
+
+        /// Replace every value without prompting
+        #[arg(long)]
+        overwrite: bool,
+    },
+
+    /// Migrate secrets from TOML to the OS keychain (macOS and Linux)
     ///
     /// This command migrates API keys and secrets from plaintext storage
-    /// in config.toml to encrypted macOS Keychain with optional Touch ID protection.
+    /// in config.toml to encrypted macOS Keychain with optional Touch ID protection,
+    /// or to the Linux Secret Service (GNOME Keyring, KWallet).
     ///
     /// Benefits:
     ///   - OS-level encryption (protected by your Mac login password)
@@ -675,7 +1660,7 @@ pub enum ConfigCommands {
     #[command(after_help = "EXAMPLES:\n    # Migrate all secrets to keychain\n    cryptofolio config migrate-to-keychain\n\n    # The wizard will:\n    #   1. Show all secrets found in config.toml\n    #   2. Let you choose security level (Standard, Touch ID, Touch ID Only)\n    #   3. Create a backup of config.toml\n    #   4. Migrate secrets to keychain\n    #   5. Clear secrets from config.toml\n\nSECURITY LEVELS:\n    Standard          Unlocked with Mac (good for automation)\n    Touch ID          Require Touch ID or password (recommended)\n    Touch ID Only     ONLY biometric, no password fallback")]
     MigrateToKeychain,
 
-    /// Show keychain status and security levels (macOS only)
+    /// Show keychain status and security levels (macOS and Linux)
     ///
     /// Displays all secrets and their storage locations:
     ///   - Keychain (with security level)
@@ -685,7 +1670,7 @@ pub enum ConfigCommands {
     #[command(after_help = "EXAMPLES:\n    # Show all secrets and their locations\n    cryptofolio config keychain-status\n\n    # JSON output\n    cryptofolio config keychain-status --json\n\nOUTPUT:\n    Shows a table with:\n      - Key name (e.g., binance.api_secret)\n      - Storage type (keychain, toml, env)\n      - Security level (for keychain entries)\n      - Last accessed timestamp")]
     KeychainStatus,
 
-    /// Upgrade security level for a keychain entry (macOS only)
+    /// Upgrade security level for a keychain entry (macOS and Linux)
     ///
     /// Increases the security level for an existing keychain secret.
     /// Requires authentication with current security level.
@@ -700,7 +1685,7 @@ pub enum ConfigCommands {
         to: String,
     },
 
-    /// Downgrade security level for a keychain entry (macOS only)
+    /// Downgrade security level for a keychain entry (macOS and Linux)
     ///
     /// Decreases the security level for an existing keychain secret.
     /// Requires authentication with current security level.
@@ -798,6 +1783,24 @@ pub enum CurrencyCommands {
         notes: Option<String>,
     },
 
+    /// Fetch the current exchange rate between two fiat currencies and store it
+    #[command(name = "auto-rate")]
+    AutoRate {
+        /// From currency (e.g., MXN)
+        from: String,
+
+        /// To currency (e.g., USD)
+        to: String,
+
+        /// Optional notes
+        #[arg(long)]
+        notes: Option<String>,
+    },
+
+    /// Fetch current rates for every fiat pair that has a stored rate
+    #[command(name = "refresh-rates")]
+    RefreshRates,
+
     /// Show exchange rate between two currencies
     #[command(name = "show-rate")]
     ShowRate {
@@ -807,9 +1810,17 @@ pub enum CurrencyCommands {
         /// To currency (e.g., USD)
         to: String,
 
-        /// Show all historical rates
+        /// Show historical rates, newest first
         #[arg(long)]
         history: bool,
+
+        /// Maximum number of historical rates to show (default: 10)
+        #[arg(long, default_value = "10", requires = "history")]
+        limit: i64,
+
+        /// Only show historical rates on or after this date (YYYY-MM-DD or ISO 8601)
+        #[arg(long = "from", value_name = "DATE", requires = "history")]
+        from_date: Option<String>,
     },
 }
 
@@ -821,6 +1832,8 @@ pub struct GlobalOptions {
     pub json: bool,
     pub quiet: bool,
     pub verbose: bool,
+    pub config_path: Option<std::path::PathBuf>,
+    pub no_retry: bool,
 }
 
 impl GlobalOptions {
@@ -831,6 +1844,8 @@ impl GlobalOptions {
             json: cli.json,
             quiet: cli.quiet,
             verbose: cli.verbose,
+            config_path: cli.config_path.clone(),
+            no_retry: cli.no_retry,
         }
     }
 }
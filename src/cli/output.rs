@@ -1,29 +1,88 @@
 #![allow(dead_code)]
 
 use colored::Colorize;
-use is_terminal::IsTerminal;
 use rust_decimal::Decimal;
-use std::io::stdout;
 use std::sync::OnceLock;
 
+use super::color::should_use_color;
 use crate::config::settings::DisplayConfig;
 
 /// Global color configuration
 static COLOR_ENABLED: OnceLock<bool> = OnceLock::new();
 
+/// Global number formatting, from the `display` config section
+static NUMBER_FORMAT: OnceLock<NumberFormat> = OnceLock::new();
+
+/// How numbers and currency amounts are written
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormat {
+    pub currency_symbol: String,
+    /// Write the symbol after the amount (`1.234,56 €`) instead of before it
+    pub symbol_after: bool,
+    pub decimal_separator: char,
+    /// `None` when `display.thousands_separator` is off
+    pub thousands_separator: Option<char>,
+    /// Decimal places for currency amounts
+    pub price_decimals: u8,
+}
+
+impl NumberFormat {
+    pub fn from_config(config: &DisplayConfig) -> Self {
+        Self {
+            currency_symbol: config.currency_symbol.clone(),
+            symbol_after: config.currency_symbol_after,
+            decimal_separator: config.decimal_separator,
+            thousands_separator: config.thousands_separator.then_some(config.thousands_separator_char),
+            price_decimals: config.price_decimals,
+        }
+    }
+
+    /// A currency amount with the configured symbol, e.g. `$1,234.56` or `-1.234,56 €`
+    pub fn currency(&self, value: Decimal) -> String {
+        self.currency_with_symbol(value, &self.currency_symbol)
+    }
+
+    /// A currency amount with another currency's symbol
+    pub fn currency_with_symbol(&self, value: Decimal, symbol: &str) -> String {
+        let formatted = format!("{:.prec$}", value, prec = self.price_decimals as usize);
+        let localized = localize(&formatted, self.decimal_separator, self.thousands_separator);
+        let (sign, amount) = match localized.strip_prefix('-') {
+            Some(amount) => ("-", amount),
+            None => ("", localized.as_str()),
+        };
+
+        if self.symbol_after {
+            format!("{}{} {}", sign, amount, symbol)
+        } else {
+            format!("{}{}{}", sign, symbol, amount)
+        }
+    }
+
+    /// A number already formatted with `.` decimals, with the configured decimal separator
+    pub fn decimal(&self, formatted: &str) -> String {
+        localize(formatted, self.decimal_separator, None)
+    }
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::from_config(&DisplayConfig::default())
+    }
+}
+
+/// Set number formatting from the display config. Later calls are ignored.
+pub fn init_number_format(config: &DisplayConfig) {
+    let _ = NUMBER_FORMAT.set(NumberFormat::from_config(config));
+}
+
+/// The number formatting in use; the defaults until `init_number_format` is called
+pub fn number_format() -> &'static NumberFormat {
+    NUMBER_FORMAT.get_or_init(NumberFormat::default)
+}
+
 /// Initialize color settings based on environment and TTY
 pub fn init_color(force_no_color: bool) {
-    let enabled = if force_no_color {
-        false
-    } else if std::env::var("NO_COLOR").is_ok() {
-        false
-    } else if std::env::var("CRYPTOFOLIO_NO_COLOR").is_ok() {
-        false
-    } else if std::env::var("TERM").map(|t| t == "dumb").unwrap_or(false) {
-        false
-    } else {
-        stdout().is_terminal()
-    };
+    let enabled = !force_no_color && should_use_color();
 
     let _ = COLOR_ENABLED.set(enabled);
 
@@ -34,12 +93,23 @@ pub fn init_color(force_no_color: bool) {
 
 /// Check if colors are enabled
 pub fn colors_enabled() -> bool {
-    *COLOR_ENABLED.get_or_init(|| {
-        if std::env::var("NO_COLOR").is_ok() {
-            return false;
-        }
-        stdout().is_terminal()
-    })
+    *COLOR_ENABLED.get_or_init(should_use_color)
+}
+
+/// Run `render` with colors turned off when `plain` is set, e.g. for text
+/// going to a file. Colors are restored afterwards, so later terminal output
+/// (the shell keeps running) is unaffected.
+pub fn without_color<T>(plain: bool, render: impl FnOnce() -> T) -> T {
+    if !plain {
+        return render();
+    }
+
+    colored::control::set_override(false);
+    let result = render();
+    if colors_enabled() {
+        colored::control::unset_override();
+    }
+    result
 }
 
 /// Format a decimal with the specified number of decimal places
@@ -52,66 +122,61 @@ pub fn format_decimal(value: Decimal, decimals: u8) -> String {
     }
 }
 
-/// Format a USD amount
-pub fn format_usd(value: Decimal) -> String {
-    format!("${:.2}", value)
+/// Format a currency amount (USD unless `display.currency_symbol` says otherwise)
+pub fn format_currency(value: Decimal) -> String {
+    number_format().currency(value)
 }
 
-/// Format a USD amount with custom config
-pub fn format_usd_with_config(value: Decimal, config: &DisplayConfig) -> String {
-    let formatted = format!("{:.prec$}", value, prec = config.price_decimals as usize);
-    let with_separator = if config.thousands_separator {
-        add_thousands_separator(&formatted)
-    } else {
-        formatted
-    };
-    format!("${}", with_separator)
+/// Format a currency amount with custom config
+pub fn format_currency_with_config(value: Decimal, config: &DisplayConfig) -> String {
+    NumberFormat::from_config(config).currency(value)
 }
 
 /// Format a quantity with appropriate decimals
 pub fn format_quantity(value: Decimal) -> String {
-    if value >= Decimal::from(1000) {
+    let formatted = if value >= Decimal::from(1000) {
         format!("{:.2}", value)
     } else if value >= Decimal::from(1) {
         format!("{:.4}", value)
     } else {
         format!("{:.8}", value)
-    }
+    };
+    number_format().decimal(&formatted)
 }
 
 /// Format a quantity with custom config
 pub fn format_quantity_with_config(value: Decimal, config: &DisplayConfig) -> String {
+    let format = NumberFormat::from_config(config);
     let formatted = format!("{:.prec$}", value, prec = config.decimals as usize);
-    if config.thousands_separator {
-        add_thousands_separator(&formatted)
-    } else {
-        formatted
-    }
+    localize(&formatted, format.decimal_separator, format.thousands_separator)
 }
 
-/// Add thousands separator to a formatted number string
-fn add_thousands_separator(num_str: &str) -> String {
-    // Split on decimal point
-    let parts: Vec<&str> = num_str.split('.').collect();
-    let integer_part = parts[0];
-
-    // Add commas to integer part
-    let mut result = String::new();
-    let chars: Vec<char> = integer_part.chars().collect();
-    let len = chars.len();
+/// Rewrite a number formatted as `-1234.56` with the given separators
+fn localize(num_str: &str, decimal_separator: char, thousands_separator: Option<char>) -> String {
+    let (sign, digits) = match num_str.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", num_str),
+    };
+    let (integer_part, fraction) = match digits.split_once('.') {
+        Some((integer_part, fraction)) => (integer_part, Some(fraction)),
+        None => (digits, None),
+    };
 
-    for (i, ch) in chars.iter().enumerate() {
-        result.push(*ch);
+    let mut result = String::from(sign);
+    let len = integer_part.len();
+    for (i, ch) in integer_part.chars().enumerate() {
+        result.push(ch);
         let pos = len - i - 1;
-        if pos > 0 && pos % 3 == 0 {
-            result.push(',');
+        if let Some(separator) = thousands_separator {
+            if pos > 0 && pos % 3 == 0 {
+                result.push(separator);
+            }
         }
     }
 
-    // Add decimal part if exists
-    if parts.len() > 1 {
-        result.push('.');
-        result.push_str(parts[1]);
+    if let Some(fraction) = fraction {
+        result.push(decimal_separator);
+        result.push_str(fraction);
     }
 
     result
@@ -119,15 +184,15 @@ fn add_thousands_separator(num_str: &str) -> String {
 
 /// Format a percentage
 pub fn format_percent(value: Decimal) -> String {
-    format!("{:.2}%", value)
+    format!("{}%", number_format().decimal(&format!("{:.2}", value)))
 }
 
 /// Format a P&L value with color
 pub fn format_pnl(value: Decimal, with_color: bool) -> String {
     let formatted = if value >= Decimal::ZERO {
-        format!("+{}", format_usd(value))
+        format!("+{}", format_currency(value))
     } else {
-        format_usd(value)
+        format_currency(value)
     };
 
     if with_color && colors_enabled() {
@@ -146,9 +211,9 @@ pub fn format_pnl(value: Decimal, with_color: bool) -> String {
 /// Format a P&L value with color and custom config
 pub fn format_pnl_with_config(value: Decimal, config: &DisplayConfig) -> String {
     let formatted = if value >= Decimal::ZERO {
-        format!("+{}", format_usd_with_config(value, config))
+        format!("+{}", format_currency_with_config(value, config))
     } else {
-        format_usd_with_config(value, config)
+        format_currency_with_config(value, config)
     };
 
     if config.color && colors_enabled() {
@@ -188,7 +253,7 @@ pub fn format_pnl_percent(value: Decimal, with_color: bool) -> String {
 /// Format a price change with color
 pub fn format_price_change(value: Decimal, percent: Decimal, with_color: bool) -> String {
     let sign = if value >= Decimal::ZERO { "+" } else { "" };
-    let formatted = format!("{}{} ({}{}%)", sign, format_usd(value.abs()), sign, format!("{:.2}", percent));
+    let formatted = format!("{}{} ({}{})", sign, format_currency(value.abs()), sign, format_percent(percent));
 
     if with_color && colors_enabled() {
         if value > Decimal::ZERO {
@@ -239,6 +304,15 @@ pub fn info(message: &str) {
     }
 }
 
+/// Wrap text in an OSC 8 terminal hyperlink, or return it unchanged when colors are off
+pub fn hyperlink(url: &str, text: &str) -> String {
+    if colors_enabled() {
+        format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+    } else {
+        text.to_string()
+    }
+}
+
 /// Print a table header
 pub fn print_header(columns: &[(&str, usize)]) {
     let header: String = columns
@@ -339,9 +413,88 @@ pub fn print_did_you_mean(suggestions: &[&str]) {
     }
 }
 
+/// Print command output to stdout, or write it to a file when a path is given.
+///
+/// Parent directories are created as needed. With `append`, the output is added
+/// to the end of an existing file instead of replacing it.
+pub fn write_output(content: &str, path: Option<&str>, append: bool, quiet: bool) -> crate::error::Result<()> {
+    use std::io::Write;
+
+    let Some(path) = path else {
+        print!("{}", content);
+        return Ok(());
+    };
+
+    let path = std::path::Path::new(path);
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)?;
+    file.write_all(content.as_bytes())?;
+
+    if !quiet {
+        let action = if append { "appended to" } else { "written to" };
+        success(&format!("Output {} {}", action, path.display()));
+    }
+
+    Ok(())
+}
+
+/// Number of pages `total` items fill, at least one
+pub fn page_count(total: u64, page_size: u32) -> u64 {
+    total.div_ceil(u64::from(page_size.max(1))).max(1)
+}
+
+/// Where a page sits in a longer list: `Page 2 of 5 (showing 51-100 of 247 transactions)`
+pub fn format_page_summary(page: u64, pages: u64, first: u64, last: u64, total: u64, noun: &str) -> String {
+    format!("Page {} of {} (showing {}-{} of {} {})", page, pages, first, last, total, noun)
+}
+
 /// Print data as JSON
 pub fn print_json<T: serde::Serialize>(data: &T) -> crate::error::Result<()> {
     let json = serde_json::to_string_pretty(data)?;
     println!("{}", json);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn european() -> NumberFormat {
+        NumberFormat {
+            currency_symbol: "€".to_string(),
+            symbol_after: true,
+            decimal_separator: ',',
+            thousands_separator: Some('.'),
+            price_decimals: 2,
+        }
+    }
+
+    #[test]
+    fn test_currency_uses_configured_separators() {
+        let us = NumberFormat::default();
+        assert_eq!(us.currency(Decimal::new(123456, 2)), "$1,234.56");
+        assert_eq!(us.currency(Decimal::new(-23456, 2)), "-$234.56");
+        assert_eq!(european().currency(Decimal::new(123456789, 2)), "1.234.567,89 €");
+        assert_eq!(european().currency(Decimal::new(-123456, 2)), "-1.234,56 €");
+    }
+
+    #[test]
+    fn test_thousands_separator_off() {
+        let format = NumberFormat {
+            thousands_separator: None,
+            ..NumberFormat::default()
+        };
+        assert_eq!(format.currency(Decimal::from(1234567)), "$1234567.00");
+        assert_eq!(european().decimal("1234.5678"), "1234,5678");
+    }
+}
@@ -5,7 +5,8 @@
 //!
 //! Supported platforms:
 //! - macOS: Keychain with Touch ID support
-//! - Linux/Windows: TOML fallback (keychain support coming in future versions)
+//! - Linux: Secret Service (GNOME Keyring, KWallet) with a prompt-on-use collection
+//! - Windows: TOML fallback (keychain support coming in future versions)
 
 use crate::error::Result;
 use serde::{Deserialize, Serialize};
@@ -76,7 +77,7 @@ impl KeychainSecurityLevel {
 /// - Touch ID/biometric authentication (where supported)
 /// - Session caching to avoid repeated prompts
 /// - Graceful fallback when keychain is unavailable
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 pub trait KeychainStorage {
     /// Store a secret in the keychain with standard security
     fn store(&self, key: &str, secret: &str) -> Result<()>;
@@ -115,9 +116,15 @@ pub fn get_keychain() -> Box<dyn KeychainStorage> {
     Box::new(super::keychain_macos::MacOSKeychain::new())
 }
 
+/// Get the default keychain implementation for this platform
+#[cfg(target_os = "linux")]
+pub fn get_keychain() -> Box<dyn KeychainStorage> {
+    Box::new(super::keychain_linux::LinuxSecretService::new())
+}
+
 /// Check if keychain is available on this platform
 pub fn is_keychain_available() -> bool {
-    cfg!(target_os = "macos")
+    cfg!(any(target_os = "macos", target_os = "linux"))
 }
 
 /// Get platform name for keychain
@@ -125,7 +132,7 @@ pub fn platform_name() -> &'static str {
     if cfg!(target_os = "macos") {
         "macOS Keychain"
     } else if cfg!(target_os = "linux") {
-        "Linux Secret Service (not yet implemented)"
+        "Linux Secret Service"
     } else if cfg!(target_os = "windows") {
         "Windows Credential Manager (not yet implemented)"
     } else {
@@ -180,10 +187,10 @@ mod tests {
 
     #[test]
     fn test_platform_detection() {
-        #[cfg(target_os = "macos")]
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
         assert!(is_keychain_available());
 
-        #[cfg(not(target_os = "macos"))]
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
         assert!(!is_keychain_available());
     }
 }
@@ -0,0 +1,328 @@
+//! Linux Secret Service implementation (GNOME Keyring, KWallet)
+//!
+//! This module stores credentials through the freedesktop.org Secret Service
+//! D-Bus API, using the `secret-service` crate.
+//!
+//! Security levels map onto collections:
+//! - Standard: the default collection, unlocked with the desktop login
+//! - Touch ID Protected / Touch ID Only: a separate "Cryptofolio" collection
+//!   that is locked again after every use, so each new session prompts for
+//!   its password
+//!
+//! Retrieved secrets are cached for 15 minutes to avoid repeated prompts.
+
+use secret_service::blocking::{Collection, Item, SecretService};
+use secret_service::{EncryptionType, Error as SecretServiceError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::keychain::{KeychainSecurityLevel, KeychainStorage};
+use crate::error::{CryptofolioError, Result};
+
+/// Service attribute for Secret Service items
+const SERVICE_NAME: &str = "com.cryptofolio.api-keys";
+
+/// Alias and label of the collection that prompts on use
+const PROTECTED_COLLECTION_ALIAS: &str = "cryptofolio";
+const PROTECTED_COLLECTION_LABEL: &str = "Cryptofolio";
+
+/// Session cache timeout (15 minutes)
+const CACHE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Session cache entry
+#[derive(Clone)]
+struct CacheEntry {
+    value: String,
+    cached_at: Instant,
+}
+
+/// Linux Secret Service implementation
+pub struct LinuxSecretService {
+    /// Session cache to avoid repeated unlock prompts
+    cache: Arc<Mutex<HashMap<String, CacheEntry>>>,
+}
+
+impl LinuxSecretService {
+    /// Create a new Secret Service instance
+    pub fn new() -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Check if a cached value is still valid
+    fn get_cached(&self, key: &str) -> Option<String> {
+        let cache = self.cache.lock().unwrap();
+        if let Some(entry) = cache.get(key) {
+            if entry.cached_at.elapsed() < CACHE_TIMEOUT {
+                return Some(entry.value.clone());
+            }
+        }
+        None
+    }
+
+    /// Store a value in the session cache
+    fn set_cached(&self, key: &str, value: String) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.insert(
+            key.to_string(),
+            CacheEntry {
+                value,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Clear a value from the session cache
+    fn clear_cached(&self, key: &str) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.remove(key);
+    }
+
+    /// Open a session with the Secret Service daemon
+    fn connect(&self) -> Result<SecretService<'static>> {
+        SecretService::connect(EncryptionType::Dh).map_err(|e| {
+            CryptofolioError::Keychain(format!("Secret Service not available: {}", e))
+        })
+    }
+
+    /// Find the stored item for a key, if any
+    fn find_item<'a>(&self, service: &'a SecretService<'a>, key: &str) -> Result<Option<Item<'a>>> {
+        let result = service
+            .search_items(item_attributes(key, None))
+            .map_err(|e| keychain_error(key, "look up", e))?;
+
+        Ok(result.unlocked.into_iter().chain(result.locked).next())
+    }
+
+    /// The collection that holds secrets of the given security level
+    fn collection<'a>(
+        &self,
+        service: &'a SecretService<'a>,
+        level: KeychainSecurityLevel,
+    ) -> std::result::Result<Collection<'a>, SecretServiceError> {
+        if !level.requires_touchid() {
+            return service.get_default_collection();
+        }
+
+        match service.get_collection_by_alias(PROTECTED_COLLECTION_ALIAS) {
+            Err(SecretServiceError::NoResult) => {
+                service.create_collection(PROTECTED_COLLECTION_LABEL, PROTECTED_COLLECTION_ALIAS)
+            }
+            result => result,
+        }
+    }
+}
+
+impl Default for LinuxSecretService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Attributes identifying a key's item, plus its security level when storing
+fn item_attributes(key: &str, level: Option<KeychainSecurityLevel>) -> HashMap<&str, &str> {
+    let mut attributes = HashMap::from([("service", SERVICE_NAME), ("key", key)]);
+    if let Some(level) = level {
+        attributes.insert("security_level", level.as_db_str());
+    }
+    attributes
+}
+
+/// Convert a Secret Service error into a keychain error for `key`
+fn keychain_error(key: &str, action: &str, error: SecretServiceError) -> CryptofolioError {
+    match error {
+        SecretServiceError::Prompt => CryptofolioError::KeychainAuthCancelled(
+            "Secret Service unlock prompt was dismissed".to_string(),
+        ),
+        SecretServiceError::Locked => CryptofolioError::KeychainAccessDenied(format!(
+            "Secret '{}' is in a locked collection",
+            key
+        )),
+        e => CryptofolioError::Keychain(format!("Failed to {} secret '{}': {}", action, key, e)),
+    }
+}
+
+fn not_found(key: &str) -> CryptofolioError {
+    CryptofolioError::Keychain(format!(
+        "Secret '{}' not found in keychain. Use 'config set-secret {}' to configure it.",
+        key, key
+    ))
+}
+
+impl KeychainStorage for LinuxSecretService {
+    fn store(&self, key: &str, secret: &str) -> Result<()> {
+        self.store_with_security(key, secret, KeychainSecurityLevel::Standard)
+    }
+
+    fn store_with_security(
+        &self,
+        key: &str,
+        secret: &str,
+        level: KeychainSecurityLevel,
+    ) -> Result<()> {
+        let service = self.connect()?;
+
+        // The key may live in the other collection, so remove it first
+        if let Some(existing) = self.find_item(&service, key)? {
+            existing
+                .ensure_unlocked()
+                .and_then(|_| existing.delete())
+                .map_err(|e| keychain_error(key, "replace", e))?;
+        }
+
+        let collection = self
+            .collection(&service, level)
+            .map_err(|e| keychain_error(key, "store", e))?;
+        collection
+            .ensure_unlocked()
+            .map_err(|e| keychain_error(key, "store", e))?;
+
+        collection
+            .create_item(
+                &format!("Cryptofolio: {}", key),
+                item_attributes(key, Some(level)),
+                secret.as_bytes(),
+                true,
+                "text/plain",
+            )
+            .map_err(|e| keychain_error(key, "store", e))?;
+
+        // Lock the protected collection so the next use prompts again
+        if level.requires_touchid() {
+            let _ = collection.lock();
+        }
+
+        // Clear cache when updating
+        self.clear_cached(key);
+
+        Ok(())
+    }
+
+    fn retrieve(&self, key: &str) -> Result<String> {
+        // Check cache first
+        if let Some(cached) = self.get_cached(key) {
+            return Ok(cached);
+        }
+
+        let service = self.connect()?;
+        let item = self.find_item(&service, key)?.ok_or_else(|| not_found(key))?;
+
+        // May show an unlock prompt for the protected collection
+        item.ensure_unlocked()
+            .map_err(|e| keychain_error(key, "unlock", e))?;
+
+        let password = item
+            .get_secret()
+            .map_err(|e| keychain_error(key, "retrieve", e))?;
+
+        let secret = String::from_utf8(password).map_err(|e| {
+            CryptofolioError::Keychain(format!("Invalid UTF-8 in stored secret: {}", e))
+        })?;
+
+        let protected = item
+            .get_attributes()
+            .ok()
+            .and_then(|attrs| attrs.get("security_level").and_then(|l| KeychainSecurityLevel::from_str(l)))
+            .is_some_and(|level| level.requires_touchid());
+        if protected {
+            let _ = item.lock();
+        }
+
+        // Cache for session
+        self.set_cached(key, secret.clone());
+
+        Ok(secret)
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        let service = self.connect()?;
+        let item = self
+            .find_item(&service, key)?
+            .ok_or_else(|| CryptofolioError::Keychain(format!("Secret '{}' not found in keychain", key)))?;
+
+        item.ensure_unlocked()
+            .and_then(|_| item.delete())
+            .map_err(|e| keychain_error(key, "delete", e))?;
+
+        // Clear from cache
+        self.clear_cached(key);
+
+        Ok(())
+    }
+
+    fn get_security_level(&self, key: &str) -> Result<KeychainSecurityLevel> {
+        let service = self.connect()?;
+        let item = self
+            .find_item(&service, key)?
+            .ok_or_else(|| CryptofolioError::Keychain(format!("Secret '{}' not found in keychain", key)))?;
+
+        // Items stored before levels were recorded live in the default collection
+        let attributes = item
+            .get_attributes()
+            .map_err(|e| keychain_error(key, "read", e))?;
+        Ok(attributes
+            .get("security_level")
+            .and_then(|l| KeychainSecurityLevel::from_str(l))
+            .unwrap_or(KeychainSecurityLevel::Standard))
+    }
+
+    fn update_security_level(&self, key: &str, level: KeychainSecurityLevel) -> Result<()> {
+        // Retrieve the current secret
+        let secret = self.retrieve(key)?;
+
+        // Re-store in the collection for the new level
+        self.store_with_security(key, &secret, level)
+    }
+
+    fn is_touchid_available(&self) -> bool {
+        // There is no Touch ID on Linux; the protected levels rely on the
+        // keyring's unlock prompt, which needs a graphical session
+        if std::env::var("SSH_CONNECTION").is_ok() || std::env::var("SSH_CLIENT").is_ok() {
+            return false;
+        }
+
+        std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some()
+    }
+
+    fn exists(&self, key: &str) -> bool {
+        self.connect()
+            .and_then(|service| self.find_item(&service, key).map(|item| item.is_some()))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_operations() {
+        let keychain = LinuxSecretService::new();
+        assert!(keychain.get_cached("test_key").is_none());
+
+        keychain.set_cached("test_key", "test_value".to_string());
+        assert_eq!(
+            keychain.get_cached("test_key"),
+            Some("test_value".to_string())
+        );
+
+        keychain.clear_cached("test_key");
+        assert!(keychain.get_cached("test_key").is_none());
+    }
+
+    #[test]
+    fn test_item_attributes() {
+        let lookup = item_attributes("binance.api_key", None);
+        assert_eq!(lookup.len(), 2);
+        assert_eq!(lookup["service"], SERVICE_NAME);
+        assert_eq!(lookup["key"], "binance.api_key");
+
+        let stored = item_attributes("binance.api_key", Some(KeychainSecurityLevel::TouchIdProtected));
+        assert_eq!(stored["security_level"], "touchid");
+    }
+
+    // Note: Integration tests with an actual Secret Service daemon
+    // should be run manually as they may require user interaction
+}
@@ -1,12 +1,13 @@
 //! TOML to Keychain migration tool
 //!
-//! Migrates secrets from plaintext TOML files to encrypted macOS Keychain.
+//! Migrates secrets from plaintext TOML files to the encrypted OS keychain
+//! (macOS Keychain or Linux Secret Service).
 
 use std::fs;
 use std::io::{self, Write};
 
-#[cfg(target_os = "macos")]
-use super::keychain::{get_keychain, KeychainSecurityLevel};
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use super::keychain::{get_keychain, platform_name, KeychainSecurityLevel};
 use super::settings::AppConfig;
 use crate::db::KeychainKeyRepository;
 use crate::error::{CryptofolioError, Result};
@@ -41,6 +42,23 @@ pub fn find_secrets_in_config(config: &AppConfig) -> Vec<SecretToMigrate> {
         });
     }
 
+    // Kraken API key and secret
+    if config.kraken.api_key.is_some() {
+        secrets.push(SecretToMigrate {
+            key: "kraken.api_key".to_string(),
+            display_name: "Kraken API Key".to_string(),
+            has_value: true,
+        });
+    }
+
+    if config.kraken.api_secret.is_some() {
+        secrets.push(SecretToMigrate {
+            key: "kraken.api_secret".to_string(),
+            display_name: "Kraken API Secret".to_string(),
+            has_value: true,
+        });
+    }
+
     // Claude API key
     if let Some(ref ai) = config.ai {
         if ai.claude_api_key.is_some() {
@@ -56,7 +74,7 @@ pub fn find_secrets_in_config(config: &AppConfig) -> Vec<SecretToMigrate> {
 }
 
 /// Prompt user to select security level
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 pub fn prompt_security_level(is_touchid_available: bool) -> Result<KeychainSecurityLevel> {
     println!();
     println!("  🔐 Security Level:");
@@ -64,12 +82,16 @@ pub fn prompt_security_level(is_touchid_available: bool) -> Result<KeychainSecur
 
     if is_touchid_available {
         println!("    [1] Standard");
-        println!("        • Protected by macOS encryption");
-        println!("        • Accessible when Mac is unlocked");
+        println!("        • Protected by {} encryption", platform_name());
+        println!("        • Accessible while you are logged in");
         println!("        • Good for: Automated scripts, cron jobs");
         println!();
         println!("    [2] Touch ID Protected (Recommended)");
-        println!("        • Requires Touch ID or password");
+        if cfg!(target_os = "linux") {
+            println!("        • Requires the Cryptofolio keyring password");
+        } else {
+            println!("        • Requires Touch ID or password");
+        }
         println!("        • Prompts once per terminal session");
         println!("        • Good for: Daily interactive use");
         println!();
@@ -82,8 +104,8 @@ pub fn prompt_security_level(is_touchid_available: bool) -> Result<KeychainSecur
         print!("  Choose level [1-3] (default: 2): ");
     } else {
         println!("    [1] Standard (Only option - Touch ID not available)");
-        println!("        • Protected by macOS encryption");
-        println!("        • Accessible when Mac is unlocked");
+        println!("        • Protected by {} encryption", platform_name());
+        println!("        • Accessible while you are logged in");
         println!();
 
         print!("  Choose level [1]: ");
@@ -130,7 +152,7 @@ pub fn create_backup() -> Result<()> {
 }
 
 /// Migrate a single secret to keychain
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 pub async fn migrate_secret_to_keychain(
     config: &AppConfig,
     secret: &SecretToMigrate,
@@ -141,6 +163,8 @@ pub async fn migrate_secret_to_keychain(
     let value = match secret.key.as_str() {
         "binance.api_key" => config.binance.api_key.clone(),
         "binance.api_secret" => config.binance.api_secret.clone(),
+        "kraken.api_key" => config.kraken.api_key.clone(),
+        "kraken.api_secret" => config.kraken.api_secret.clone(),
         "ai.claude_api_key" => config.ai.as_ref().and_then(|ai| ai.claude_api_key.clone()),
         _ => None,
     };
@@ -183,6 +207,12 @@ pub fn clear_secrets_from_config(config: &mut AppConfig, secrets: &[SecretToMigr
             "binance.api_secret" => {
                 config.binance.api_secret = None;
             }
+            "kraken.api_key" => {
+                config.kraken.api_key = None;
+            }
+            "kraken.api_secret" => {
+                config.kraken.api_secret = None;
+            }
             "ai.claude_api_key" => {
                 if let Some(ref mut ai) = config.ai {
                     ai.claude_api_key = None;
@@ -199,9 +229,10 @@ pub fn clear_secrets_from_config(config: &mut AppConfig, secrets: &[SecretToMigr
 pub fn confirm_migration(secret_count: usize) -> Result<bool> {
     println!();
     print!(
-        "  Migrate {} secret{} to macOS Keychain? [y/N] ",
+        "  Migrate {} secret{} to {}? [y/N] ",
         secret_count,
-        if secret_count == 1 { "" } else { "s" }
+        if secret_count == 1 { "" } else { "s" },
+        super::keychain::platform_name()
     );
     io::stdout().flush().map_err(CryptofolioError::Io)?;
 
@@ -214,11 +245,11 @@ pub fn confirm_migration(secret_count: usize) -> Result<bool> {
 }
 
 /// Full migration workflow
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 pub async fn run_migration(keychain_repo: &KeychainKeyRepository) -> Result<()> {
     println!();
     println!("  ╔═══════════════════════════════════════════════════════════════╗");
-    println!("  ║           MIGRATE SECRETS TO macOS KEYCHAIN                  ║");
+    println!("  ║              MIGRATE SECRETS TO OS KEYCHAIN                   ║");
     println!("  ╚═══════════════════════════════════════════════════════════════╝");
     println!();
 
@@ -283,7 +314,7 @@ pub async fn run_migration(keychain_repo: &KeychainKeyRepository) -> Result<()>
     println!();
     println!("  ✅ Migration complete!");
     println!();
-    println!("  Your secrets are now protected by macOS Keychain.");
+    println!("  Your secrets are now protected by {}.", platform_name());
     if security_level.requires_touchid() {
         println!("  You'll be prompted for Touch ID when accessing them.");
     }
@@ -292,7 +323,7 @@ pub async fn run_migration(keychain_repo: &KeychainKeyRepository) -> Result<()>
     Ok(())
 }
 
-#[cfg(not(target_os = "macos"))]
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
 pub async fn run_migration(_keychain_repo: &KeychainKeyRepository) -> Result<()> {
     Err(CryptofolioError::KeychainNotAvailable)
 }
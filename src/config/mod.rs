@@ -6,4 +6,7 @@ pub mod settings;
 #[cfg(target_os = "macos")]
 pub mod keychain_macos;
 
+#[cfg(target_os = "linux")]
+pub mod keychain_linux;
+
 pub use settings::{AiConfig, AppConfig};
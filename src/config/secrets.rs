@@ -1,9 +1,46 @@
+use std::fmt;
 use std::fs;
 use std::io::{self, Read, Write};
 use std::path::Path;
 
+use super::keychain::KeychainSecurityLevel;
 use crate::error::{CryptofolioError, Result};
 
+/// Secrets with the environment variable each one can be read from
+pub const SECRET_ENV_VARS: &[(&str, &str)] = &[
+    ("binance.api_key", "BINANCE_API_KEY"),
+    ("binance.api_secret", "BINANCE_API_SECRET"),
+    ("kraken.api_key", "KRAKEN_API_KEY"),
+    ("kraken.api_secret", "KRAKEN_API_SECRET"),
+    ("ai.claude_api_key", "ANTHROPIC_API_KEY"),
+];
+
+/// Environment variable a secret can be read from, if any
+pub fn secret_env_var(key: &str) -> Option<&'static str> {
+    SECRET_ENV_VARS.iter().find(|(k, _)| *k == key).map(|(_, var)| *var)
+}
+
+/// Where a secret's value was found
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecretSource {
+    /// Plaintext in config.toml
+    Toml,
+    /// The OS keychain, at this security level
+    Keychain(KeychainSecurityLevel),
+    /// This environment variable
+    Env(String),
+}
+
+impl fmt::Display for SecretSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretSource::Toml => write!(f, "toml"),
+            SecretSource::Keychain(level) => write!(f, "keychain:{}", level.as_db_str()),
+            SecretSource::Env(var) => write!(f, "env: {}", var),
+        }
+    }
+}
+
 /// Check if a config key is a secret/credential
 pub fn is_secret_key(key: &str) -> bool {
     let key_lower = key.to_lowercase();
@@ -21,6 +58,27 @@ pub fn is_api_credential_key(key: &str) -> bool {
     key_lower.contains("api_key") || key_lower.contains("api_secret")
 }
 
+/// Length of Binance API keys and secrets
+const BINANCE_CREDENTIAL_LEN: usize = 64;
+
+/// Check the format of a Binance API key or secret before it is stored.
+/// Keys other than `binance.api_key` and `binance.api_secret` are not checked.
+pub fn validate_binance_credential(key: &str, value: &str) -> Result<()> {
+    let key_lower = key.to_lowercase();
+    if key_lower != "binance.api_key" && key_lower != "binance.api_secret" {
+        return Ok(());
+    }
+
+    let value = value.trim();
+    if value.len() != BINANCE_CREDENTIAL_LEN || !value.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(CryptofolioError::Config(
+            "Invalid Binance API key format: expected 64 alphanumeric characters".into(),
+        ));
+    }
+
+    Ok(())
+}
+
 /// Read secret from stdin (for piped input)
 pub fn read_secret_from_stdin() -> Result<String> {
     let mut secret = String::new();
@@ -214,6 +272,32 @@ mod tests {
         assert!(!is_api_credential_key("general.use_testnet"));
     }
 
+    #[test]
+    fn test_secret_source_display() {
+        assert_eq!(SecretSource::Toml.to_string(), "toml");
+        assert_eq!(
+            SecretSource::Keychain(KeychainSecurityLevel::TouchIdProtected).to_string(),
+            "keychain:touchid"
+        );
+        assert_eq!(SecretSource::Env("BINANCE_API_KEY".into()).to_string(), "env: BINANCE_API_KEY");
+        assert_eq!(secret_env_var("ai.claude_api_key"), Some("ANTHROPIC_API_KEY"));
+        assert_eq!(secret_env_var("display.color"), None);
+    }
+
+    #[test]
+    fn test_validate_binance_credential() {
+        let valid = "a1B2".repeat(16);
+        assert!(validate_binance_credential("binance.api_key", &valid).is_ok());
+        assert!(validate_binance_credential("binance.api_secret", &format!("{}\n", valid)).is_ok());
+
+        assert!(validate_binance_credential("binance.api_key", "too-short").is_err());
+        assert!(validate_binance_credential("binance.api_secret", &"x".repeat(65)).is_err());
+        assert!(validate_binance_credential("binance.api_key", &format!("{}-", &valid[..63])).is_err());
+
+        // Other keys are not Binance credentials
+        assert!(validate_binance_credential("ai.claude_api_key", "sk-ant-123").is_ok());
+    }
+
     #[test]
     fn test_read_secret_from_env() {
         std::env::set_var("TEST_SECRET_123", "my-test-secret");
@@ -1,13 +1,16 @@
 #![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
+use super::secrets::{secret_env_var, SecretSource, SECRET_ENV_VARS};
 use crate::error::{CryptofolioError, Result};
 
-#[cfg(target_os = "macos")]
-use super::keychain::get_keychain;
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use super::keychain::{get_keychain, KeychainSecurityLevel};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -17,11 +20,24 @@ pub struct AppConfig {
     #[serde(default)]
     pub binance: BinanceConfig,
 
+    #[serde(default)]
+    pub kraken: KrakenConfig,
+
     #[serde(default)]
     pub display: DisplayConfig,
 
+    #[serde(default)]
+    pub ethereum: EthereumConfig,
+
+    #[serde(default)]
+    pub bitcoin: BitcoinConfig,
+
     #[serde(default)]
     pub ai: Option<AiConfig>,
+
+    /// Where each secret outside the keychain was found by `load`
+    #[serde(skip)]
+    secret_sources: HashMap<String, SecretSource>,
 }
 
 impl Default for AppConfig {
@@ -29,8 +45,12 @@ impl Default for AppConfig {
         Self {
             general: GeneralConfig::default(),
             binance: BinanceConfig::default(),
+            kraken: KrakenConfig::default(),
             display: DisplayConfig::default(),
+            ethereum: EthereumConfig::default(),
+            bitcoin: BitcoinConfig::default(),
             ai: Some(AiConfig::default()),
+            secret_sources: HashMap::new(),
         }
     }
 }
@@ -126,6 +146,73 @@ impl Default for BinanceConfig {
     }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KrakenConfig {
+    #[serde(default)]
+    pub api_key: Option<String>,
+
+    /// Base64-encoded private key
+    #[serde(default)]
+    pub api_secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthereumConfig {
+    /// JSON-RPC endpoint used to read wallet balances
+    #[serde(default = "default_ethereum_rpc_url")]
+    pub rpc_url: String,
+
+    /// Extra ERC-20 tokens to check, as comma-separated SYMBOL=0xCONTRACT pairs
+    #[serde(default)]
+    pub tokens: Option<String>,
+}
+
+fn default_ethereum_rpc_url() -> String {
+    "https://ethereum-rpc.publicnode.com".to_string()
+}
+
+impl Default for EthereumConfig {
+    fn default() -> Self {
+        Self {
+            rpc_url: default_ethereum_rpc_url(),
+            tokens: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoinConfig {
+    /// Electrum server (tcp://host:port) queried before the block explorer
+    #[serde(default)]
+    pub electrum_url: Option<String>,
+
+    /// Esplora-compatible block explorer API
+    #[serde(default = "default_bitcoin_explorer_url")]
+    pub explorer_url: String,
+
+    /// Consecutive unused addresses after which xpub scanning stops
+    #[serde(default = "default_gap_limit")]
+    pub gap_limit: u8,
+}
+
+fn default_bitcoin_explorer_url() -> String {
+    "https://blockstream.info/api".to_string()
+}
+
+fn default_gap_limit() -> u8 {
+    20
+}
+
+impl Default for BitcoinConfig {
+    fn default() -> Self {
+        Self {
+            electrum_url: None,
+            explorer_url: default_bitcoin_explorer_url(),
+            gap_limit: default_gap_limit(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DisplayConfig {
     /// Enable colored output
@@ -143,6 +230,26 @@ pub struct DisplayConfig {
     /// Use thousands separator in numbers (e.g., 1,234.56)
     #[serde(default = "default_thousands_separator")]
     pub thousands_separator: bool,
+
+    /// Character between groups of thousands when `thousands_separator` is on
+    #[serde(default = "default_thousands_separator_char")]
+    pub thousands_separator_char: char,
+
+    /// Character before the decimal places
+    #[serde(default = "default_decimal_separator")]
+    pub decimal_separator: char,
+
+    /// Symbol written with currency amounts
+    #[serde(default = "default_currency_symbol")]
+    pub currency_symbol: String,
+
+    /// Write the currency symbol after the amount (e.g., 1.234,56 €)
+    #[serde(default)]
+    pub currency_symbol_after: bool,
+
+    /// Maximum number of commands kept in the shell history
+    #[serde(default = "default_shell_history_size")]
+    pub shell_history_size: usize,
 }
 
 fn default_color() -> bool {
@@ -161,6 +268,22 @@ fn default_thousands_separator() -> bool {
     true
 }
 
+fn default_thousands_separator_char() -> char {
+    ','
+}
+
+fn default_decimal_separator() -> char {
+    '.'
+}
+
+fn default_currency_symbol() -> String {
+    "$".to_string()
+}
+
+fn default_shell_history_size() -> usize {
+    1000
+}
+
 impl Default for DisplayConfig {
     fn default() -> Self {
         Self {
@@ -168,10 +291,168 @@ impl Default for DisplayConfig {
             decimals: default_decimals(),
             price_decimals: default_price_decimals(),
             thousands_separator: default_thousands_separator(),
+            thousands_separator_char: default_thousands_separator_char(),
+            decimal_separator: default_decimal_separator(),
+            currency_symbol: default_currency_symbol(),
+            currency_symbol_after: false,
+            shell_history_size: default_shell_history_size(),
+        }
+    }
+}
+
+/// Type of a configuration value, used to validate `config set`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigValueType {
+    Bool,
+    String,
+    U8,
+    Usize,
+    Char,
+}
+
+impl ConfigValueType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigValueType::Bool => "bool",
+            ConfigValueType::String => "string",
+            ConfigValueType::U8 => "u8",
+            ConfigValueType::Usize => "usize",
+            ConfigValueType::Char => "char",
+        }
+    }
+
+    /// Check that `value` parses as this type
+    pub fn validate(&self, value: &str) -> Result<()> {
+        match self {
+            ConfigValueType::Bool => value
+                .parse::<bool>()
+                .map(|_| ())
+                .map_err(|_| CryptofolioError::Config("Invalid boolean value".into())),
+            ConfigValueType::U8 => value
+                .parse::<u8>()
+                .map(|_| ())
+                .map_err(|_| CryptofolioError::Config("Invalid number value".into())),
+            ConfigValueType::Usize => value
+                .parse::<usize>()
+                .map(|_| ())
+                .map_err(|_| CryptofolioError::Config("Invalid number value".into())),
+            ConfigValueType::Char => value
+                .parse::<char>()
+                .map(|_| ())
+                .map_err(|_| CryptofolioError::Config("Invalid value: expected a single character".into())),
+            ConfigValueType::String => Ok(()),
         }
     }
 }
 
+/// A key accepted by `config set` and `config get`
+#[derive(Debug, Serialize)]
+pub struct ConfigKey {
+    pub key: &'static str,
+    #[serde(rename = "type")]
+    pub value_type: ConfigValueType,
+    pub description: &'static str,
+}
+
+/// Every valid configuration key path
+pub const CONFIG_KEYS: &[ConfigKey] = &[
+    ConfigKey { key: "general.default_account", value_type: ConfigValueType::String, description: "Default account name" },
+    ConfigKey { key: "general.use_testnet", value_type: ConfigValueType::Bool, description: "Use the Binance testnet" },
+    ConfigKey { key: "general.currency", value_type: ConfigValueType::String, description: "Base currency for values" },
+    ConfigKey { key: "binance.api_key", value_type: ConfigValueType::String, description: "Binance API key (use set-secret)" },
+    ConfigKey { key: "binance.api_secret", value_type: ConfigValueType::String, description: "Binance API secret (use set-secret)" },
+    ConfigKey { key: "kraken.api_key", value_type: ConfigValueType::String, description: "Kraken API key (use set-secret)" },
+    ConfigKey { key: "kraken.api_secret", value_type: ConfigValueType::String, description: "Kraken private key (use set-secret)" },
+    ConfigKey { key: "display.color", value_type: ConfigValueType::Bool, description: "Enable colored output" },
+    ConfigKey { key: "display.decimals", value_type: ConfigValueType::U8, description: "Decimal places for quantities" },
+    ConfigKey { key: "display.price_decimals", value_type: ConfigValueType::U8, description: "Decimal places for prices" },
+    ConfigKey { key: "display.thousands_separator", value_type: ConfigValueType::Bool, description: "Use thousands separator in numbers" },
+    ConfigKey { key: "display.thousands_separator_char", value_type: ConfigValueType::Char, description: "Thousands separator character" },
+    ConfigKey { key: "display.decimal_separator", value_type: ConfigValueType::Char, description: "Decimal separator character" },
+    ConfigKey { key: "display.currency_symbol", value_type: ConfigValueType::String, description: "Symbol for currency amounts" },
+    ConfigKey { key: "display.currency_symbol_after", value_type: ConfigValueType::Bool, description: "Write the currency symbol after the amount" },
+    ConfigKey { key: "display.shell_history_size", value_type: ConfigValueType::Usize, description: "Commands kept in the shell history" },
+    ConfigKey { key: "ethereum.rpc_url", value_type: ConfigValueType::String, description: "Ethereum JSON-RPC endpoint for wallet sync" },
+    ConfigKey { key: "ethereum.tokens", value_type: ConfigValueType::String, description: "Extra ERC-20 tokens to sync (SYMBOL=0xCONTRACT,...)" },
+    ConfigKey { key: "bitcoin.electrum_url", value_type: ConfigValueType::String, description: "Electrum server for wallet sync (tcp://host:port)" },
+    ConfigKey { key: "bitcoin.explorer_url", value_type: ConfigValueType::String, description: "Esplora block explorer API for wallet sync" },
+    ConfigKey { key: "bitcoin.gap_limit", value_type: ConfigValueType::U8, description: "Unused addresses to scan past when deriving from an xpub" },
+    ConfigKey { key: "ai.mode", value_type: ConfigValueType::String, description: "AI mode: online, offline, hybrid, disabled" },
+    ConfigKey { key: "ai.claude_api_key", value_type: ConfigValueType::String, description: "Claude API key (use set-secret)" },
+    ConfigKey { key: "ai.claude_model", value_type: ConfigValueType::String, description: "Claude model to use" },
+    ConfigKey { key: "ai.local_model", value_type: ConfigValueType::String, description: "Local model for Ollama" },
+    ConfigKey { key: "ai.ollama_url", value_type: ConfigValueType::String, description: "Ollama server URL" },
+];
+
+/// Look up a configuration key, failing for unknown keys
+pub fn config_key(key: &str) -> Result<&'static ConfigKey> {
+    CONFIG_KEYS
+        .iter()
+        .find(|k| k.key == key)
+        .ok_or_else(|| CryptofolioError::Config(format!("Unknown config key: {}", key)))
+}
+
+/// Convert a config value to TOML using the key's declared type
+fn typed_toml_value(value_type: ConfigValueType, value: &str) -> Result<toml::Value> {
+    let invalid = || CryptofolioError::Config(format!("Invalid {} value: {}", value_type.as_str(), value));
+    Ok(match value_type {
+        ConfigValueType::Bool => toml::Value::Boolean(value.parse().map_err(|_| invalid())?),
+        ConfigValueType::U8 | ConfigValueType::Usize => toml::Value::Integer(value.parse().map_err(|_| invalid())?),
+        ConfigValueType::String | ConfigValueType::Char => toml::Value::String(value.to_string()),
+    })
+}
+
+/// Written in place of configured secrets by `config export --include-secrets`
+pub const SECRET_PLACEHOLDER: &str = "<secret: set with config set-secret>";
+
+/// An imported value that would replace a value the user changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigConflict {
+    pub key: &'static str,
+    pub current: String,
+    pub incoming: String,
+}
+
+/// Environment variable that overrides the config file location
+pub const CONFIG_PATH_ENV: &str = "CRYPTOFOLIO_CONFIG";
+
+/// Environment variable that overrides the database file location
+pub const DB_PATH_ENV: &str = "CRYPTOFOLIO_DB_PATH";
+
+/// Environment variable that overrides the shell history file location
+pub const HISTORY_FILE_ENV: &str = "CRYPTOFOLIO_HISTORY_FILE";
+
+/// Config file location given with `--config`
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Record the `--config` path for this process. Only the first call takes effect.
+pub fn init_config_path(path: Option<PathBuf>) {
+    if let Some(path) = path {
+        let _ = CONFIG_PATH_OVERRIDE.set(path);
+    }
+}
+
+/// Pick the config file: the `--config` flag, then `CRYPTOFOLIO_CONFIG`,
+/// then the default location
+fn resolve_config_path(flag: Option<&Path>, env: Option<String>) -> Result<PathBuf> {
+    if let Some(path) = flag {
+        return Ok(path.to_path_buf());
+    }
+    match env {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Ok(AppConfig::config_dir()?.join("config.toml")),
+    }
+}
+
+/// Pick the database file: `CRYPTOFOLIO_DB_PATH`, then the default location
+fn resolve_database_path(env: Option<String>) -> Result<PathBuf> {
+    match env {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Ok(AppConfig::config_dir()?.join("database.sqlite")),
+    }
+}
+
 impl AppConfig {
     /// Get the config directory path
     pub fn config_dir() -> Result<PathBuf> {
@@ -182,33 +463,81 @@ impl AppConfig {
 
     /// Get the config file path
     pub fn config_path() -> Result<PathBuf> {
-        Ok(Self::config_dir()?.join("config.toml"))
+        resolve_config_path(
+            CONFIG_PATH_OVERRIDE.get().map(|p| p.as_path()),
+            std::env::var(CONFIG_PATH_ENV).ok(),
+        )
+    }
+
+    /// Get the shell history file path, honoring `CRYPTOFOLIO_HISTORY_FILE`
+    pub fn history_path() -> Result<PathBuf> {
+        match std::env::var(HISTORY_FILE_ENV) {
+            Ok(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+            _ => Ok(Self::config_dir()?.join("history.txt")),
+        }
     }
 
-    /// Get the database file path
+    /// Get the database file path, honoring `CRYPTOFOLIO_DB_PATH`
     pub fn database_path() -> Result<PathBuf> {
-        Ok(Self::config_dir()?.join("database.sqlite"))
+        resolve_database_path(std::env::var(DB_PATH_ENV).ok())
     }
 
     /// Load config from file, or create default if not exists
     pub fn load() -> Result<Self> {
         let config_path = Self::config_path()?;
 
-        if config_path.exists() {
+        let mut config = if config_path.exists() {
             let content = fs::read_to_string(&config_path)?;
-            let config: AppConfig = toml::from_str(&content)?;
-            Ok(config)
+            toml::from_str(&content)?
         } else {
-            Ok(Self::default())
+            Self::default()
+        };
+
+        config.record_secret_sources(|var| std::env::var(var).ok());
+        Ok(config)
+    }
+
+    /// Note which secrets come from config.toml and which only from the
+    /// environment; the file wins when both are set
+    fn record_secret_sources(&mut self, env: impl Fn(&str) -> Option<String>) {
+        for (key, var) in SECRET_ENV_VARS {
+            let source = if matches!(self.get(key), Ok(Some(_))) {
+                Some(SecretSource::Toml)
+            } else if env(var).is_some_and(|v| !v.trim().is_empty()) {
+                Some(SecretSource::Env(var.to_string()))
+            } else {
+                None
+            };
+
+            match source {
+                Some(source) => self.secret_sources.insert(key.to_string(), source),
+                None => self.secret_sources.remove(*key),
+            };
+        }
+    }
+
+    /// Where `get_secret` finds a secret: the keychain, then config.toml,
+    /// then the environment. None when it isn't set anywhere.
+    pub fn secret_source(&self, key: &str) -> Option<SecretSource> {
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        {
+            let keychain = get_keychain();
+            if keychain.exists(key) {
+                let level = keychain.get_security_level(key).unwrap_or(KeychainSecurityLevel::Standard);
+                return Some(SecretSource::Keychain(level));
+            }
         }
+
+        self.secret_sources.get(key).cloned()
     }
 
     /// Save config to file
     pub fn save(&self) -> Result<()> {
-        let config_dir = Self::config_dir()?;
-        fs::create_dir_all(&config_dir)?;
-
         let config_path = Self::config_path()?;
+        if let Some(parent) = config_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
         let content = toml::to_string_pretty(self)
             .map_err(|e| CryptofolioError::Config(format!("Failed to serialize config: {}", e)))?;
 
@@ -218,6 +547,8 @@ impl AppConfig {
 
     /// Set a config value by key path (e.g., "binance.api_key")
     pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        config_key(key)?.value_type.validate(value)?;
+
         match key {
             "general.default_account" => {
                 self.general.default_account = Some(value.to_string());
@@ -236,6 +567,12 @@ impl AppConfig {
             "binance.api_secret" => {
                 self.binance.api_secret = Some(value.to_string());
             }
+            "kraken.api_key" => {
+                self.kraken.api_key = Some(value.to_string());
+            }
+            "kraken.api_secret" => {
+                self.kraken.api_secret = Some(value.to_string());
+            }
             "display.color" => {
                 self.display.color = value.parse().map_err(|_| {
                     CryptofolioError::Config("Invalid boolean value".into())
@@ -256,6 +593,46 @@ impl AppConfig {
                     CryptofolioError::Config("Invalid boolean value".into())
                 })?;
             }
+            "display.thousands_separator_char" => {
+                self.display.thousands_separator_char = value.parse().map_err(|_| {
+                    CryptofolioError::Config("Invalid value: expected a single character".into())
+                })?;
+            }
+            "display.decimal_separator" => {
+                self.display.decimal_separator = value.parse().map_err(|_| {
+                    CryptofolioError::Config("Invalid value: expected a single character".into())
+                })?;
+            }
+            "display.currency_symbol" => {
+                self.display.currency_symbol = value.to_string();
+            }
+            "display.currency_symbol_after" => {
+                self.display.currency_symbol_after = value.parse().map_err(|_| {
+                    CryptofolioError::Config("Invalid boolean value".into())
+                })?;
+            }
+            "display.shell_history_size" => {
+                self.display.shell_history_size = value.parse().map_err(|_| {
+                    CryptofolioError::Config("Invalid number value".into())
+                })?;
+            }
+            "ethereum.rpc_url" => {
+                self.ethereum.rpc_url = value.to_string();
+            }
+            "ethereum.tokens" => {
+                self.ethereum.tokens = Some(value.to_string());
+            }
+            "bitcoin.electrum_url" => {
+                self.bitcoin.electrum_url = Some(value.to_string());
+            }
+            "bitcoin.explorer_url" => {
+                self.bitcoin.explorer_url = value.to_string();
+            }
+            "bitcoin.gap_limit" => {
+                self.bitcoin.gap_limit = value.parse().map_err(|_| {
+                    CryptofolioError::Config("Invalid number value".into())
+                })?;
+            }
             "ai.mode" => {
                 self.ensure_ai_config();
                 if let Some(ref mut ai) = self.ai {
@@ -293,6 +670,135 @@ impl AppConfig {
         Ok(())
     }
 
+    /// Get a configuration value by key, using the same keys as `set`.
+    /// Returns None for keys that are valid but unset.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        let ai = self.ai.as_ref();
+        let value = match key {
+            "general.default_account" => self.general.default_account.clone(),
+            "general.use_testnet" => Some(self.general.use_testnet.to_string()),
+            "general.currency" => Some(self.general.currency.clone()),
+            "binance.api_key" => self.binance.api_key.clone(),
+            "binance.api_secret" => self.binance.api_secret.clone(),
+            "kraken.api_key" => self.kraken.api_key.clone(),
+            "kraken.api_secret" => self.kraken.api_secret.clone(),
+            "display.color" => Some(self.display.color.to_string()),
+            "display.decimals" => Some(self.display.decimals.to_string()),
+            "display.price_decimals" => Some(self.display.price_decimals.to_string()),
+            "display.thousands_separator" => Some(self.display.thousands_separator.to_string()),
+            "display.thousands_separator_char" => Some(self.display.thousands_separator_char.to_string()),
+            "display.decimal_separator" => Some(self.display.decimal_separator.to_string()),
+            "display.currency_symbol" => Some(self.display.currency_symbol.clone()),
+            "display.currency_symbol_after" => Some(self.display.currency_symbol_after.to_string()),
+            "display.shell_history_size" => Some(self.display.shell_history_size.to_string()),
+            "ethereum.rpc_url" => Some(self.ethereum.rpc_url.clone()),
+            "ethereum.tokens" => self.ethereum.tokens.clone(),
+            "bitcoin.electrum_url" => self.bitcoin.electrum_url.clone(),
+            "bitcoin.explorer_url" => Some(self.bitcoin.explorer_url.clone()),
+            "bitcoin.gap_limit" => Some(self.bitcoin.gap_limit.to_string()),
+            "ai.mode" => ai.and_then(|a| a.mode.clone()),
+            "ai.claude_api_key" => ai.and_then(|a| a.claude_api_key.clone()),
+            "ai.claude_model" => ai.and_then(|a| a.claude_model.clone()),
+            "ai.local_model" => ai.and_then(|a| a.local_model.clone()),
+            "ai.ollama_url" => ai.and_then(|a| a.ollama_url.clone()),
+            _ => {
+                return Err(CryptofolioError::Config(format!("Unknown config key: {}", key)));
+            }
+        };
+        Ok(value)
+    }
+
+    /// Write every non-secret value to a portable TOML file. Secrets are left
+    /// out; with `include_secrets`, configured secrets are written as
+    /// [`SECRET_PLACEHOLDER`] so the file lists what needs to be set again.
+    pub fn export_to_file(&self, path: &Path, include_secrets: bool) -> Result<()> {
+        let mut root = toml::Table::new();
+
+        for key in CONFIG_KEYS {
+            let value = if super::secrets::is_secret_key(key.key) {
+                if !include_secrets || self.get_secret(key.key)?.is_none() {
+                    continue;
+                }
+                toml::Value::String(SECRET_PLACEHOLDER.to_string())
+            } else {
+                match self.get(key.key)? {
+                    Some(value) => typed_toml_value(key.value_type, &value)?,
+                    None => continue,
+                }
+            };
+
+            let (section, name) = key.key.split_once('.').unwrap_or(("general", key.key));
+            if let toml::Value::Table(table) = root
+                .entry(section)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            {
+                table.insert(name.to_string(), value);
+            }
+        }
+
+        let body = toml::to_string_pretty(&root)
+            .map_err(|e| CryptofolioError::Config(format!("Failed to serialize config: {}", e)))?;
+        let header = if include_secrets {
+            "# Cryptofolio configuration export\n\
+             # Secrets are replaced with placeholders; set them again with 'cryptofolio config set-secret'.\n\n"
+        } else {
+            "# Cryptofolio configuration export\n\
+             # Secrets (API keys) are not included; set them with 'cryptofolio config set-secret'.\n\n"
+        };
+
+        fs::write(path, format!("{}{}", header, body))?;
+        Ok(())
+    }
+
+    /// Apply values from a file written by [`export_to_file`](Self::export_to_file).
+    ///
+    /// Secret placeholders are skipped. With `merge`, values that would
+    /// replace a setting the user changed from its default are left alone
+    /// and returned as conflicts for the caller to resolve; without it every
+    /// value is applied. The config is not saved.
+    pub fn import_from_file(&mut self, path: &Path, merge: bool) -> Result<Vec<ConfigConflict>> {
+        let content = fs::read_to_string(path)?;
+        let root: toml::Table = toml::from_str(&content)?;
+        let defaults = AppConfig::default();
+
+        let mut values = Vec::new();
+        for (section, table) in &root {
+            let toml::Value::Table(table) = table else {
+                return Err(CryptofolioError::Config(format!("Expected a [{}] section", section)));
+            };
+            for (name, value) in table {
+                let key = config_key(&format!("{}.{}", section, name))?;
+                let value = match value {
+                    toml::Value::String(s) => s.clone(),
+                    toml::Value::Boolean(b) => b.to_string(),
+                    toml::Value::Integer(i) => i.to_string(),
+                    _ => {
+                        return Err(CryptofolioError::Config(format!("Unsupported value for {}", key.key)));
+                    }
+                };
+                if value == SECRET_PLACEHOLDER {
+                    continue;
+                }
+                key.value_type.validate(&value)?;
+                values.push((key.key, value));
+            }
+        }
+
+        let mut conflicts = Vec::new();
+        for (key, incoming) in values {
+            let current = self.get(key)?;
+            let changed_by_user = current.is_some() && current != defaults.get(key)?;
+            match current {
+                Some(current) if merge && changed_by_user && current != incoming => {
+                    conflicts.push(ConfigConflict { key, current, incoming });
+                }
+                _ => self.set(key, &incoming)?,
+            }
+        }
+
+        Ok(conflicts)
+    }
+
     /// Ensure AI config exists
     fn ensure_ai_config(&mut self) {
         if self.ai.is_none() {
@@ -310,23 +816,27 @@ impl AppConfig {
             return true;
         }
 
-        // Otherwise, check keychain (macOS only)
-        #[cfg(target_os = "macos")]
+        // Otherwise, check keychain (macOS and Linux only)
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
         {
             let keychain = get_keychain();
-            let has_keychain_creds = keychain.exists("binance.api_key")
-                && keychain.exists("binance.api_secret");
-            return has_keychain_creds;
+            keychain.exists("binance.api_key") && keychain.exists("binance.api_secret")
         }
 
-        #[cfg(not(target_os = "macos"))]
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
         false
     }
 
+    /// Check if Kraken API credentials are configured (keychain or TOML)
+    pub fn has_kraken_credentials(&self) -> bool {
+        matches!(self.get_secret("kraken.api_key"), Ok(Some(_)))
+            && matches!(self.get_secret("kraken.api_secret"), Ok(Some(_)))
+    }
+
     /// Get a secret value (checks keychain first, then TOML)
     pub fn get_secret(&self, key: &str) -> Result<Option<String>> {
-        // Try keychain first (macOS only)
-        #[cfg(target_os = "macos")]
+        // Try keychain first (macOS and Linux only)
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
         {
             let keychain = get_keychain();
             if keychain.exists(key) {
@@ -345,10 +855,18 @@ impl AppConfig {
         let value = match key {
             "binance.api_key" => self.binance.api_key.clone(),
             "binance.api_secret" => self.binance.api_secret.clone(),
+            "kraken.api_key" => self.kraken.api_key.clone(),
+            "kraken.api_secret" => self.kraken.api_secret.clone(),
             "ai.claude_api_key" => self.ai.as_ref().and_then(|ai| ai.claude_api_key.clone()),
             _ => None,
         };
 
+        // Then the environment
+        let value = value.or_else(|| {
+            let var = secret_env_var(key)?;
+            std::env::var(var).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+        });
+
         Ok(value)
     }
 
@@ -376,3 +894,141 @@ impl AppConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_secret_sources_prefers_toml() {
+        let mut config = AppConfig::default();
+        config.set("binance.api_key", "from-toml").unwrap();
+
+        let env = |var: &str| match var {
+            "BINANCE_API_KEY" | "BINANCE_API_SECRET" => Some("from-env".to_string()),
+            "KRAKEN_API_KEY" => Some("  ".to_string()),
+            _ => None,
+        };
+        config.record_secret_sources(env);
+
+        assert_eq!(config.secret_sources.get("binance.api_key"), Some(&SecretSource::Toml));
+        assert_eq!(
+            config.secret_sources.get("binance.api_secret"),
+            Some(&SecretSource::Env("BINANCE_API_SECRET".to_string()))
+        );
+        assert_eq!(config.secret_sources.get("kraken.api_key"), None);
+    }
+
+    #[test]
+    fn test_get_returns_value_set() {
+        let mut config = AppConfig::default();
+        config.set("general.use_testnet", "true").unwrap();
+        config.set("display.decimals", "4").unwrap();
+
+        assert_eq!(config.get("general.use_testnet").unwrap(), Some("true".to_string()));
+        assert_eq!(config.get("display.decimals").unwrap(), Some("4".to_string()));
+    }
+
+    #[test]
+    fn test_config_keys_cover_get_and_set() {
+        let mut config = AppConfig::default();
+
+        for key in CONFIG_KEYS {
+            let value = match key.value_type {
+                ConfigValueType::Bool => "true",
+                ConfigValueType::U8 | ConfigValueType::Usize => "3",
+                ConfigValueType::String => "value",
+                ConfigValueType::Char => "'",
+            };
+            config.set(key.key, value).unwrap();
+            assert_eq!(config.get(key.key).unwrap(), Some(value.to_string()), "{}", key.key);
+        }
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let path = std::env::temp_dir().join(format!("cryptofolio-export-{}.toml", std::process::id()));
+
+        let mut config = AppConfig::default();
+        config.set("display.decimals", "4").unwrap();
+        config.set("general.use_testnet", "true").unwrap();
+        config.set("binance.api_secret", "hunter2").unwrap();
+        config.export_to_file(&path, true).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("hunter2"));
+        assert!(content.contains(SECRET_PLACEHOLDER));
+
+        let mut imported = AppConfig::default();
+        assert!(imported.import_from_file(&path, true).unwrap().is_empty());
+        assert_eq!(imported.get("display.decimals").unwrap(), Some("4".to_string()));
+        assert_eq!(imported.get("general.use_testnet").unwrap(), Some("true".to_string()));
+        assert_eq!(imported.get("binance.api_secret").unwrap(), None);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_import_merge_reports_conflicts() {
+        let path = std::env::temp_dir().join(format!("cryptofolio-merge-{}.toml", std::process::id()));
+        fs::write(&path, "[display]\ndecimals = 4\ncolor = false\n").unwrap();
+
+        let mut config = AppConfig::default();
+        config.set("display.decimals", "6").unwrap();
+        let conflicts = config.import_from_file(&path, true).unwrap();
+
+        // Default values are replaced silently, user changes are reported
+        assert_eq!(config.get("display.color").unwrap(), Some("false".to_string()));
+        assert_eq!(
+            conflicts,
+            vec![ConfigConflict { key: "display.decimals", current: "6".to_string(), incoming: "4".to_string() }]
+        );
+        assert_eq!(config.get("display.decimals").unwrap(), Some("6".to_string()));
+
+        config.import_from_file(&path, false).unwrap();
+        assert_eq!(config.get("display.decimals").unwrap(), Some("4".to_string()));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_set_rejects_wrong_type() {
+        let mut config = AppConfig::default();
+
+        assert!(config.set("display.decimals", "many").is_err());
+        assert!(config.set("display.color", "yes please").is_err());
+        assert!(config.set("display.nonexistent", "1").is_err());
+    }
+
+    #[test]
+    fn test_get_unset_and_unknown_keys() {
+        let config = AppConfig::default();
+
+        assert_eq!(config.get("general.default_account").unwrap(), None);
+        assert!(config.get("general.nonexistent").is_err());
+    }
+
+    #[test]
+    fn test_resolve_config_path_precedence() {
+        let flag = Path::new("/tmp/flag.toml");
+
+        let path = resolve_config_path(Some(flag), Some("/tmp/env.toml".into())).unwrap();
+        assert_eq!(path, PathBuf::from("/tmp/flag.toml"));
+
+        let path = resolve_config_path(None, Some("/tmp/env.toml".into())).unwrap();
+        assert_eq!(path, PathBuf::from("/tmp/env.toml"));
+
+        let path = resolve_config_path(None, Some(String::new())).unwrap();
+        assert_eq!(path, AppConfig::config_dir().unwrap().join("config.toml"));
+    }
+
+    #[test]
+    fn test_resolve_database_path() {
+        let path = resolve_database_path(Some("/data/cryptofolio.sqlite".into())).unwrap();
+        assert_eq!(path, PathBuf::from("/data/cryptofolio.sqlite"));
+
+        let default = AppConfig::config_dir().unwrap().join("database.sqlite");
+        assert_eq!(resolve_database_path(None).unwrap(), default);
+        assert_eq!(resolve_database_path(Some(String::new())).unwrap(), default);
+    }
+}
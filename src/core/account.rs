@@ -2,6 +2,7 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -50,17 +51,65 @@ impl AccountType {
 pub struct AccountConfig {
     #[serde(default)]
     pub is_testnet: bool,
+    /// Exchange an exchange account syncs from; Binance when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exchange: Option<String>,
+    /// Per-account sync options (e.g. "exclude_assets", "rate_limit")
+    #[serde(default)]
+    pub sync_settings: HashMap<String, serde_json::Value>,
 }
 
 impl Default for AccountConfig {
     fn default() -> Self {
-        Self { is_testnet: false }
+        Self {
+            is_testnet: false,
+            exchange: None,
+            sync_settings: HashMap::new(),
+        }
+    }
+}
+
+impl AccountConfig {
+    pub const EXCLUDE_ASSETS: &'static str = "exclude_assets";
+    pub const RATE_LIMIT: &'static str = "rate_limit";
+    pub const KRAKEN: &'static str = "kraken";
+
+    /// Whether this account syncs from Kraken rather than Binance
+    pub fn is_kraken(&self) -> bool {
+        self.exchange.as_deref().is_some_and(|e| e.eq_ignore_ascii_case(Self::KRAKEN))
+    }
+
+    /// Assets that should be skipped when syncing this account
+    pub fn excluded_assets(&self) -> Vec<String> {
+        self.sync_settings
+            .get(Self::EXCLUDE_ASSETS)
+            .and_then(|v| v.as_array())
+            .map(|assets| {
+                assets
+                    .iter()
+                    .filter_map(|a| a.as_str())
+                    .map(|a| a.to_uppercase())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Maximum API requests per second when syncing this account
+    pub fn rate_limit(&self) -> Option<u32> {
+        self.sync_settings
+            .get(Self::RATE_LIMIT)
+            .and_then(|v| v.as_u64())
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v > 0)
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Account {
+    /// Random UUID assigned when the account is created. Other tables refer to
+    /// the account by this id, so renaming only touches `name`.
     pub id: String,
+    /// Display name, unique ignoring case; commands look accounts up by it
     pub name: String,
     pub category_id: String,
     pub account_type: AccountType,
@@ -97,3 +146,40 @@ impl Category {
         }
     }
 }
+
+/// Maximum edit distance for an account name to be suggested as a correction
+pub const MAX_NAME_SUGGESTION_DISTANCE: usize = 3;
+
+/// Find the known name closest to `input` by Levenshtein distance (case-insensitive),
+/// if it is within `MAX_NAME_SUGGESTION_DISTANCE` edits
+pub fn closest_name(input: &str, candidates: &[String]) -> Option<String> {
+    let input_lower = input.to_lowercase();
+
+    candidates
+        .iter()
+        .map(|c| (c, strsim::levenshtein(&input_lower, &c.to_lowercase())))
+        .filter(|(_, distance)| *distance <= MAX_NAME_SUGGESTION_DISTANCE)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(c, _)| c.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Vec<String> {
+        vec!["Binance".to_string(), "Ledger".to_string(), "Coinbase".to_string()]
+    }
+
+    #[test]
+    fn test_closest_name_suggests_typo() {
+        assert_eq!(closest_name("Binannce", &names()), Some("Binance".to_string()));
+        assert_eq!(closest_name("ledgr", &names()), Some("Ledger".to_string()));
+    }
+
+    #[test]
+    fn test_closest_name_too_far() {
+        assert_eq!(closest_name("Kraken", &names()), None);
+        assert_eq!(closest_name("Binance", &[]), None);
+    }
+}
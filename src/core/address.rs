@@ -0,0 +1,271 @@
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+use crate::error::{CryptofolioError, Result};
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BECH32_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Checksum constants for witness v0 (BIP-173) and v1+ (BIP-350) addresses
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+/// Base58Check version bytes for P2PKH/P2SH on mainnet and testnet
+const BASE58_VERSIONS: &[u8] = &[0x00, 0x05, 0x6f, 0xc4];
+
+/// Version bytes of mainnet xpub, ypub and zpub extended public keys
+const EXTENDED_KEY_VERSIONS: &[[u8; 4]] = &[
+    [0x04, 0x88, 0xb2, 0x1e],
+    [0x04, 0x9d, 0x7c, 0xb2],
+    [0x04, 0xb2, 0x47, 0x46],
+];
+
+/// Human-readable parts for mainnet, testnet and regtest segwit addresses
+const BECH32_HRPS: &[&str] = &["bc", "tb", "bcrt"];
+
+/// Check a wallet address against the format of its blockchain.
+/// Blockchains without a known format are accepted as-is.
+pub fn validate_address(blockchain: &str, address: &str) -> Result<()> {
+    match blockchain.to_lowercase().as_str() {
+        "ethereum" | "eth" => validate_ethereum_address(address),
+        "bitcoin" | "btc" => validate_bitcoin_address(address),
+        _ => Ok(()),
+    }
+}
+
+/// Validate a 0x-prefixed Ethereum address, verifying the EIP-55 checksum
+/// when the address is mixed case
+pub fn validate_ethereum_address(address: &str) -> Result<()> {
+    let hex = address
+        .strip_prefix("0x")
+        .filter(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| {
+            CryptofolioError::InvalidInput(format!(
+                "Invalid Ethereum address '{}': expected 0x followed by 40 hex characters",
+                address
+            ))
+        })?;
+
+    let has_lower = hex.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = hex.chars().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper && eip55_checksum(hex) != hex {
+        return Err(CryptofolioError::InvalidInput(format!(
+            "Invalid Ethereum address '{}': checksum does not match (check for typos)",
+            address
+        )));
+    }
+
+    Ok(())
+}
+
+/// Apply EIP-55 capitalization to 40 hex characters
+fn eip55_checksum(hex: &str) -> String {
+    let lower = hex.to_ascii_lowercase();
+    let hash = Keccak256::digest(lower.as_bytes());
+
+    lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Validate a Bitcoin address in legacy Base58Check or segwit Bech32/Bech32m
+/// form, or an xpub/ypub/zpub extended public key that wallet sync derives
+/// addresses from
+pub fn validate_bitcoin_address(address: &str) -> Result<()> {
+    let valid = if is_bech32_candidate(address) {
+        is_valid_segwit_address(address)
+    } else if ["xpub", "ypub", "zpub"].iter().any(|p| address.starts_with(p)) {
+        is_valid_extended_public_key(address)
+    } else {
+        is_valid_base58check_address(address)
+    };
+
+    if !valid {
+        return Err(CryptofolioError::InvalidInput(format!(
+            "Invalid Bitcoin address '{}': not a valid Base58Check or Bech32 address or extended public key",
+            address
+        )));
+    }
+
+    Ok(())
+}
+
+fn is_bech32_candidate(address: &str) -> bool {
+    let lower = address.to_ascii_lowercase();
+    BECH32_HRPS.iter().any(|hrp| lower.starts_with(&format!("{}1", hrp)))
+}
+
+fn is_valid_base58check_address(address: &str) -> bool {
+    let Some(bytes) = base58_decode(address) else {
+        return false;
+    };
+    if bytes.len() != 25 || !BASE58_VERSIONS.contains(&bytes[0]) {
+        return false;
+    }
+
+    let (payload, checksum) = bytes.split_at(21);
+    let hash = Sha256::digest(Sha256::digest(payload));
+    hash[..4] == *checksum
+}
+
+fn is_valid_extended_public_key(key: &str) -> bool {
+    let Some(bytes) = base58_decode(key) else {
+        return false;
+    };
+    // 78 serialized bytes plus a 4-byte checksum
+    if bytes.len() != 82 || !EXTENDED_KEY_VERSIONS.iter().any(|v| bytes[..4] == *v) {
+        return false;
+    }
+
+    let (payload, checksum) = bytes.split_at(78);
+    let hash = Sha256::digest(Sha256::digest(payload));
+    hash[..4] == *checksum
+}
+
+fn base58_decode(input: &str) -> Option<Vec<u8>> {
+    // Big-endian base-256 accumulator
+    let mut bytes: Vec<u8> = Vec::new();
+
+    for c in input.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut().rev() {
+            carry += *byte as u32 * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, (carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    // Each leading '1' encodes a leading zero byte
+    let leading_zeros = input.bytes().take_while(|&c| c == b'1').count();
+    let mut decoded = vec![0u8; leading_zeros];
+    decoded.extend(bytes);
+    Some(decoded)
+}
+
+fn is_valid_segwit_address(address: &str) -> bool {
+    // Mixed case is never valid in Bech32
+    if address.chars().any(|c| c.is_ascii_lowercase()) && address.chars().any(|c| c.is_ascii_uppercase()) {
+        return false;
+    }
+    let address = address.to_ascii_lowercase();
+
+    let Some((hrp, data)) = address.rsplit_once('1') else {
+        return false;
+    };
+    if !BECH32_HRPS.contains(&hrp) || data.len() < 7 {
+        return false;
+    }
+
+    let Some(values) = data
+        .bytes()
+        .map(|c| BECH32_CHARSET.iter().position(|&a| a == c).map(|v| v as u8))
+        .collect::<Option<Vec<u8>>>()
+    else {
+        return false;
+    };
+
+    let witness_version = values[0];
+    let expected_const = match witness_version {
+        0 => BECH32_CONST,
+        1..=16 => BECH32M_CONST,
+        _ => return false,
+    };
+    if bech32_polymod(hrp, &values) != expected_const {
+        return false;
+    }
+
+    // Witness program excludes the version and the six checksum characters
+    let program_len = (values.len() - 7) * 5 / 8;
+    match witness_version {
+        0 => program_len == 20 || program_len == 32,
+        _ => (2..=40).contains(&program_len),
+    }
+}
+
+fn bech32_polymod(hrp: &str, values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+
+    let expanded_hrp = hrp
+        .bytes()
+        .map(|b| b >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.bytes().map(|b| b & 0x1f));
+
+    let mut checksum: u32 = 1;
+    for value in expanded_hrp.chain(values.iter().copied()) {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x01ff_ffff) << 5) ^ value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ethereum_address() {
+        // EIP-55 reference addresses
+        assert!(validate_ethereum_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_ok());
+        assert!(validate_ethereum_address("0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359").is_ok());
+        assert!(validate_ethereum_address("0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB").is_ok());
+
+        // Single-case addresses carry no checksum
+        assert!(validate_ethereum_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").is_ok());
+
+        // One letter's case flipped
+        assert!(validate_ethereum_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err());
+        assert!(validate_ethereum_address("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+        assert!(validate_ethereum_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA").is_err());
+        assert!(validate_ethereum_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeg").is_err());
+    }
+
+    #[test]
+    fn test_validate_bitcoin_address() {
+        // Legacy P2PKH and P2SH
+        assert!(validate_bitcoin_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2").is_ok());
+        assert!(validate_bitcoin_address("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy").is_ok());
+        // BIP-173 and BIP-350 test vectors
+        assert!(validate_bitcoin_address("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4").is_ok());
+        assert!(validate_bitcoin_address(
+            "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y"
+        )
+        .is_ok());
+
+        // Typo in the last character breaks the checksum
+        assert!(validate_bitcoin_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3").is_err());
+        assert!(validate_bitcoin_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5").is_err());
+        // v1 address with a Bech32 (not Bech32m) checksum
+        assert!(validate_bitcoin_address("bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7k7grplx").is_err());
+        assert!(validate_bitcoin_address("not-an-address").is_err());
+
+        // Extended public keys are accepted for xpub scanning
+        let zpub = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+        assert!(validate_bitcoin_address(zpub).is_ok());
+        assert!(validate_bitcoin_address(&zpub.replace("Ys", "Yt")).is_err());
+    }
+
+    #[test]
+    fn test_validate_address_unknown_chain() {
+        assert!(validate_address("solana", "anything").is_ok());
+        assert!(validate_address("ETH", "0x123").is_err());
+    }
+}
@@ -1,5 +1,10 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::db::currencies;
+use crate::error::{CryptofolioError, Result};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -36,6 +41,14 @@ impl AssetType {
     }
 }
 
+/// Assets valued at exactly $1 without a price lookup
+const USD_EQUIVALENTS: &[&str] = &["USD", "USDT", "USDC", "BUSD", "DAI"];
+
+/// Whether an asset code is USD or a USD-pegged stablecoin
+pub fn is_usd_equivalent(code: &str) -> bool {
+    USD_EQUIVALENTS.iter().any(|c| c.eq_ignore_ascii_case(code))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Currency {
     pub code: String,          // "USD", "CRC", "BTC"
@@ -148,6 +161,58 @@ impl ExchangeRate {
     pub fn pair(&self) -> String {
         format!("{}/{}", self.from_currency, self.to_currency)
     }
+
+    /// Price of one unit of `code` in the other currency of the pair. Rates
+    /// are stored as FROM per 1 TO, so the from side is priced at `1 / rate`.
+    pub fn price_of(&self, code: &str) -> Option<rust_decimal::Decimal> {
+        if self.rate.is_zero() {
+            return None;
+        }
+        if self.from_currency.eq_ignore_ascii_case(code) {
+            Some(rust_decimal::Decimal::ONE / self.rate)
+        } else if self.to_currency.eq_ignore_ascii_case(code) {
+            Some(self.rate)
+        } else {
+            None
+        }
+    }
+}
+
+/// Converts amounts between currencies with the latest stored exchange rates
+pub struct CurrencyConverter;
+
+impl CurrencyConverter {
+    /// `amount` of `from` expressed in `to`. Uses a rate stored for the pair
+    /// itself when there is one, otherwise each currency's rate against USD.
+    pub async fn convert(amount: Decimal, from: &str, to: &str, pool: &SqlitePool) -> Result<Decimal> {
+        let (from, to) = (from.to_uppercase(), to.to_uppercase());
+        if from == to {
+            return Ok(amount);
+        }
+
+        let direct = match currencies::get_latest_exchange_rate(pool, &from, &to).await? {
+            Some(rate) => Some(rate),
+            None => currencies::get_latest_exchange_rate(pool, &to, &from).await?,
+        };
+        if let Some(price) = direct.and_then(|rate| rate.price_of(&from)) {
+            return Ok(amount * price);
+        }
+
+        match (Self::usd_price(&from, pool).await?, Self::usd_price(&to, pool).await?) {
+            (Some(from_usd), Some(to_usd)) if !to_usd.is_zero() => Ok(amount * from_usd / to_usd),
+            _ => Err(CryptofolioError::NotFound(format!(
+                "Exchange rate for {}/{}. Set one with 'cryptofolio currency set-rate' or 'cryptofolio currency auto-rate'",
+                from, to
+            ))),
+        }
+    }
+
+    async fn usd_price(code: &str, pool: &SqlitePool) -> Result<Option<Decimal>> {
+        if is_usd_equivalent(code) {
+            return Ok(Some(Decimal::ONE));
+        }
+        currencies::get_usd_price(pool, code).await
+    }
 }
 
 #[cfg(test)]
@@ -294,4 +359,14 @@ mod tests {
         let currency = Currency::new("btc", "Bitcoin", "₿", 8, AssetType::Crypto);
         assert_eq!(currency.code, "BTC");
     }
+
+    #[test]
+    fn test_exchange_rate_price_of() {
+        // 500 CRC per 1 USD
+        let rate = ExchangeRate::new_manual("CRC", "USD", Decimal::from(500), Utc::now());
+
+        assert_eq!(rate.price_of("CRC"), Some(Decimal::from_str("0.002").unwrap()));
+        assert_eq!(rate.price_of("usd"), Some(Decimal::from(500)));
+        assert_eq!(rate.price_of("EUR"), None);
+    }
 }
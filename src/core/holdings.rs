@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+use crate::core::transaction::{Transaction, TransactionType};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Holding {
     pub id: i64,
@@ -12,12 +14,40 @@ pub struct Holding {
     pub cost_basis_currency: Option<String>,  // Currency for avg_cost_basis
     pub avg_cost_basis_base: Option<Decimal>, // Cost basis in base currency (USD)
     pub updated_at: DateTime<Utc>,
+    /// When the earliest lot was acquired; None for synced holdings and those added before it was tracked
+    #[serde(default)]
+    pub acquired_at: Option<DateTime<Utc>>,
 }
 
 impl Holding {
     pub fn cost_basis_total(&self) -> Option<Decimal> {
         self.avg_cost_basis.map(|cost| cost * self.quantity)
     }
+
+    /// This holding with `other`'s quantity added: the cost basis is the
+    /// quantity-weighted average when both have one, otherwise whichever is
+    /// known, and the acquisition date is the earlier of the two
+    pub fn merged_with(&self, other: &Holding) -> Holding {
+        let quantity = self.quantity + other.quantity;
+        let avg_cost_basis = match (self.avg_cost_basis, other.avg_cost_basis) {
+            (Some(a), Some(b)) if quantity > Decimal::ZERO => {
+                Some((a * self.quantity + b * other.quantity) / quantity)
+            }
+            (a, b) => a.or(b),
+        };
+        let acquired_at = match (self.acquired_at, other.acquired_at) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        Holding {
+            quantity,
+            avg_cost_basis,
+            avg_cost_basis_base: avg_cost_basis,
+            acquired_at,
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,3 +81,375 @@ impl HoldingWithPrice {
         }
     }
 }
+
+/// Keep only positions on one side of break-even
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PnlFilter {
+    Gains,
+    Losses,
+}
+
+impl PnlFilter {
+    /// Whether the holding's unrealized P&L falls on this side; holdings without a known P&L never match
+    pub fn matches(&self, holding: &HoldingWithPrice) -> bool {
+        match (self, holding.unrealized_pnl) {
+            (PnlFilter::Gains, Some(pnl)) => pnl > Decimal::ZERO,
+            (PnlFilter::Losses, Some(pnl)) => pnl < Decimal::ZERO,
+            (_, None) => false,
+        }
+    }
+}
+
+/// Sort holdings by current value, largest first (unpriced holdings last),
+/// keeping only the first `top` when given
+pub fn rank_by_value(holdings: &mut Vec<HoldingWithPrice>, top: Option<usize>) {
+    holdings.sort_by_key(|h| std::cmp::Reverse(h.current_value));
+    if let Some(n) = top {
+        holdings.truncate(n);
+    }
+}
+
+/// Normalize holding tags: trimmed, lowercased, de-duplicated and in input order.
+/// Empty tags are dropped.
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Where a recorded holding change came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoldingChangeSource {
+    Sync,
+    Manual,
+}
+
+impl HoldingChangeSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HoldingChangeSource::Sync => "sync",
+            HoldingChangeSource::Manual => "manual",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "sync" => Some(HoldingChangeSource::Sync),
+            "manual" => Some(HoldingChangeSource::Manual),
+            _ => None,
+        }
+    }
+}
+
+/// A holding quantity overwritten outside of a transaction, by a sync or a
+/// manual edit
+#[derive(Debug, Clone)]
+pub struct RecordedHoldingChange {
+    pub source: HoldingChangeSource,
+    /// The sync run that made the change
+    pub sync_log_id: Option<i64>,
+    pub previous_quantity: Decimal,
+    pub new_quantity: Decimal,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// What changed a holding at one point in its history
+#[derive(Debug, Clone, PartialEq)]
+pub enum HoldingEvent {
+    Transaction { id: i64, tx_type: TransactionType },
+    Sync { sync_log_id: Option<i64> },
+    Manual,
+}
+
+impl HoldingEvent {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            HoldingEvent::Transaction { tx_type, .. } => tx_type.display_name(),
+            HoldingEvent::Sync { .. } => "Sync",
+            HoldingEvent::Manual => "Manual Edit",
+        }
+    }
+
+    /// Transaction or sync run behind the event
+    pub fn source(&self) -> String {
+        match self {
+            HoldingEvent::Transaction { id, .. } => format!("tx #{}", id),
+            HoldingEvent::Sync { sync_log_id: Some(id) } => format!("sync #{}", id),
+            HoldingEvent::Sync { sync_log_id: None } => "sync".to_string(),
+            HoldingEvent::Manual => "manual".to_string(),
+        }
+    }
+}
+
+/// One row of a holding's history
+#[derive(Debug, Clone)]
+pub struct HoldingHistoryEntry {
+    pub timestamp: DateTime<Utc>,
+    pub event: HoldingEvent,
+    pub delta: Decimal,
+    /// Quantity held after the event
+    pub balance: Decimal,
+}
+
+/// Merge the transactions and recorded changes of one holding into a
+/// time-ordered history. Transactions move the running balance by their
+/// delta; syncs and manual edits set it to the recorded quantity.
+pub fn build_holding_history(
+    account_id: &str,
+    asset: &str,
+    transactions: &[Transaction],
+    recorded: &[RecordedHoldingChange],
+) -> Vec<HoldingHistoryEntry> {
+    enum Step<'a> {
+        Delta(Decimal),
+        Set(&'a RecordedHoldingChange),
+    }
+
+    let asset = asset.to_uppercase();
+    let mut steps: Vec<(DateTime<Utc>, HoldingEvent, Step)> = Vec::new();
+
+    for tx in transactions {
+        let delta: Decimal = tx
+            .holding_changes()
+            .into_iter()
+            .filter(|c| c.account_id == account_id && c.asset.to_uppercase() == asset)
+            .map(|c| c.quantity)
+            .sum();
+        if !delta.is_zero() {
+            steps.push((tx.timestamp, HoldingEvent::Transaction { id: tx.id, tx_type: tx.tx_type }, Step::Delta(delta)));
+        }
+    }
+    for change in recorded {
+        let event = match change.source {
+            HoldingChangeSource::Sync => HoldingEvent::Sync { sync_log_id: change.sync_log_id },
+            HoldingChangeSource::Manual => HoldingEvent::Manual,
+        };
+        steps.push((change.recorded_at, event, Step::Set(change)));
+    }
+
+    // Stable, so events at the same instant keep transactions first
+    steps.sort_by_key(|(timestamp, _, _)| *timestamp);
+
+    let mut balance = Decimal::ZERO;
+    steps
+        .into_iter()
+        .map(|(timestamp, event, step)| {
+            let delta;
+            (delta, balance) = match step {
+                Step::Delta(delta) => (delta, balance + delta),
+                Step::Set(change) => (change.new_quantity - change.previous_quantity, change.new_quantity),
+            };
+            HoldingHistoryEntry { timestamp, event, delta, balance }
+        })
+        .collect()
+}
+
+/// Staking rewards received on one holding and the yield they represent
+#[derive(Debug, Clone, Serialize)]
+pub struct YieldSummary {
+    pub total_rewards: Decimal,
+    pub reward_count: usize,
+    pub first_reward: Option<DateTime<Utc>>,
+    pub last_reward: Option<DateTime<Utc>>,
+    /// Time-weighted average quantity held since the holding's first event
+    pub average_holding: Decimal,
+    pub days_held: Decimal,
+    /// Rewards over the average holding, scaled to a year (simple, not compounded)
+    pub annualized_yield_percent: Option<Decimal>,
+}
+
+impl YieldSummary {
+    /// Summarize a holding history from [`build_holding_history`] up to `now`
+    pub fn from_history(history: &[HoldingHistoryEntry], now: DateTime<Utc>) -> Self {
+        let rewards: Vec<&HoldingHistoryEntry> = history
+            .iter()
+            .filter(|e| {
+                matches!(e.event, HoldingEvent::Transaction { tx_type: TransactionType::StakingReward, .. })
+            })
+            .collect();
+        let total_rewards: Decimal = rewards.iter().map(|e| e.delta).sum();
+
+        // Integrate the balance over time, in seconds, from the first event until now
+        let start = history.first().map(|e| e.timestamp).unwrap_or(now);
+        let mut weighted = Decimal::ZERO;
+        for (i, entry) in history.iter().enumerate() {
+            let until = history.get(i + 1).map(|next| next.timestamp).unwrap_or(now);
+            let seconds = (until - entry.timestamp).num_seconds().max(0);
+            weighted += entry.balance * Decimal::from(seconds);
+        }
+
+        let seconds_held = Decimal::from((now - start).num_seconds().max(0));
+        let average_holding = if seconds_held.is_zero() {
+            Decimal::ZERO
+        } else {
+            weighted / seconds_held
+        };
+        let days_held = seconds_held / Decimal::from(86_400);
+
+        // Less than a day of history says little about a yearly rate
+        let annualized_yield_percent = (days_held >= Decimal::ONE && average_holding > Decimal::ZERO).then(|| {
+            (total_rewards / average_holding * Decimal::from(365) / days_held * Decimal::from(100)).round_dp(2)
+        });
+
+        Self {
+            total_rewards,
+            reward_count: rewards.len(),
+            first_reward: rewards.first().map(|e| e.timestamp),
+            last_reward: rewards.last().map(|e| e.timestamp),
+            average_holding,
+            days_held: days_held.round_dp(1),
+            annualized_yield_percent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(asset: &str, quantity: i64, price: Option<i64>) -> HoldingWithPrice {
+        holding_with_cost(asset, quantity, price, None)
+    }
+
+    fn holding_with_cost(asset: &str, quantity: i64, price: Option<i64>, cost: Option<i64>) -> HoldingWithPrice {
+        let holding = Holding {
+            id: 0,
+            account_id: "acc".to_string(),
+            asset: asset.to_string(),
+            quantity: Decimal::from(quantity),
+            avg_cost_basis: cost.map(Decimal::from),
+            cost_basis_currency: None,
+            avg_cost_basis_base: None,
+            updated_at: Utc::now(),
+            acquired_at: None,
+        };
+        HoldingWithPrice::from_holding(holding, price.map(Decimal::from))
+    }
+
+    #[test]
+    fn test_merged_with_weights_cost_basis() {
+        let a = holding_with_cost("BTC", 1, None, Some(30000)).holding;
+        let b = holding_with_cost("BTC", 3, None, Some(50000)).holding;
+        let merged = a.merged_with(&b);
+        assert_eq!(merged.quantity, Decimal::from(4));
+        assert_eq!(merged.avg_cost_basis, Some(Decimal::from(45000)));
+
+        let unknown = holding_with_cost("BTC", 2, None, None).holding;
+        assert_eq!(unknown.merged_with(&a).avg_cost_basis, Some(Decimal::from(30000)));
+        assert_eq!(unknown.merged_with(&unknown).avg_cost_basis, None);
+    }
+
+    #[test]
+    fn test_rank_by_value_top() {
+        let mut holdings = vec![
+            holding("SOL", 10, Some(150)),
+            holding("XYZ", 1000, None),
+            holding("BTC", 1, Some(60000)),
+            holding("ETH", 2, Some(3000)),
+        ];
+
+        rank_by_value(&mut holdings, Some(2));
+
+        let assets: Vec<&str> = holdings.iter().map(|h| h.holding.asset.as_str()).collect();
+        assert_eq!(assets, vec!["BTC", "ETH"]);
+    }
+
+    #[test]
+    fn test_rank_by_value_unpriced_last() {
+        let mut holdings = vec![holding("XYZ", 1000, None), holding("SOL", 10, Some(150))];
+
+        rank_by_value(&mut holdings, None);
+
+        assert_eq!(holdings[0].holding.asset, "SOL");
+        assert_eq!(holdings.len(), 2);
+    }
+
+    #[test]
+    fn test_pnl_filter() {
+        let winner = holding_with_cost("BTC", 1, Some(60000), Some(40000));
+        let loser = holding_with_cost("ETH", 1, Some(2000), Some(3000));
+        let unknown = holding("SOL", 1, Some(150));
+
+        assert!(PnlFilter::Gains.matches(&winner));
+        assert!(!PnlFilter::Gains.matches(&loser));
+        assert!(PnlFilter::Losses.matches(&loser));
+        assert!(!PnlFilter::Losses.matches(&unknown));
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        let tags: Vec<String> = [" Long-Term", "staking", "long-term", ""].iter().map(|s| s.to_string()).collect();
+        assert_eq!(normalize_tags(&tags), vec!["long-term", "staking"]);
+    }
+
+    #[test]
+    fn test_build_holding_history() {
+        use chrono::TimeZone;
+
+        let day = |d: u32| Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0).unwrap();
+
+        let mut buy = Transaction::new_buy("ledger", "BTC", Decimal::ONE, Decimal::from(40000), day(1));
+        buy.id = 1;
+        let mut sell = Transaction::new_sell("ledger", "BTC", Decimal::new(3, 1), Decimal::from(45000), day(3));
+        sell.id = 2;
+        let mut other = Transaction::new_buy("ledger", "ETH", Decimal::ONE, Decimal::from(3000), day(4));
+        other.id = 3;
+
+        let sync = RecordedHoldingChange {
+            source: HoldingChangeSource::Sync,
+            sync_log_id: Some(7),
+            previous_quantity: Decimal::ONE,
+            new_quantity: Decimal::new(12, 1),
+            recorded_at: day(2),
+        };
+
+        let history = build_holding_history("ledger", "btc", &[sell, other, buy], &[sync]);
+
+        let rows: Vec<(String, Decimal, Decimal)> = history
+            .iter()
+            .map(|e| (e.event.source(), e.delta, e.balance))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("tx #1".to_string(), Decimal::ONE, Decimal::ONE),
+                ("sync #7".to_string(), Decimal::new(2, 1), Decimal::new(12, 1)),
+                ("tx #2".to_string(), Decimal::new(-3, 1), Decimal::new(9, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_yield_summary() {
+        let now = Utc::now();
+        let entry = |days_ago: i64, tx_type: TransactionType, delta: i64, balance: i64| HoldingHistoryEntry {
+            timestamp: now - chrono::Duration::days(days_ago),
+            event: HoldingEvent::Transaction { id: 0, tx_type },
+            delta: Decimal::from(delta),
+            balance: Decimal::from(balance),
+        };
+
+        // 100 held for a year, 5 earned halfway through
+        let history = vec![
+            entry(365, TransactionType::Buy, 100, 100),
+            entry(182, TransactionType::StakingReward, 5, 105),
+        ];
+        let summary = YieldSummary::from_history(&history, now);
+
+        assert_eq!(summary.total_rewards, Decimal::from(5));
+        assert_eq!(summary.reward_count, 1);
+        assert_eq!(summary.days_held, Decimal::from(365));
+        assert!(summary.average_holding > Decimal::from(102) && summary.average_holding < Decimal::from(103));
+        let apy = summary.annualized_yield_percent.unwrap();
+        assert!(apy > Decimal::from(4) && apy < Decimal::from(5), "{}", apy);
+
+        // Too little history to annualize
+        let recent = vec![entry(0, TransactionType::StakingReward, 1, 1)];
+        assert!(YieldSummary::from_history(&recent, now).annualized_yield_percent.is_none());
+    }
+}
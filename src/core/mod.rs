@@ -1,7 +1,11 @@
 pub mod account;
+pub mod address;
 pub mod currency;
 pub mod holdings;
 pub mod pnl;
 pub mod portfolio;
+pub mod rebalance;
+pub mod replay;
+pub mod tax;
 pub mod transaction;
 
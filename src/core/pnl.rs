@@ -1,7 +1,18 @@
 #![allow(dead_code)]
 
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+
+use super::portfolio::fee_value_usd;
+use super::replay::PortfolioReplay;
+use super::transaction::{Transaction, TransactionType};
+use crate::db::TransactionRepository;
+use crate::error::Result;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum CostBasisMethod {
@@ -9,10 +20,33 @@ pub enum CostBasisMethod {
     Fifo,
     /// Last In, First Out
     Lifo,
+    /// Highest In, First Out
+    Hifo,
     /// Average cost of all purchases
     AverageCost,
 }
 
+impl CostBasisMethod {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "fifo" => Some(CostBasisMethod::Fifo),
+            "lifo" => Some(CostBasisMethod::Lifo),
+            "hifo" => Some(CostBasisMethod::Hifo),
+            "average" | "avg" | "average-cost" => Some(CostBasisMethod::AverageCost),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CostBasisMethod::Fifo => "fifo",
+            CostBasisMethod::Lifo => "lifo",
+            CostBasisMethod::Hifo => "hifo",
+            CostBasisMethod::AverageCost => "average",
+        }
+    }
+}
+
 impl Default for CostBasisMethod {
     fn default() -> Self {
         CostBasisMethod::Fifo
@@ -47,3 +81,494 @@ impl Default for PnLSummary {
         Self::new()
     }
 }
+
+/// The unsold remainder of one acquisition
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OpenLot {
+    pub account_id: String,
+    pub asset: String,
+    pub quantity: Decimal,
+    pub cost_per_unit: Decimal,
+    pub acquired_at: DateTime<Utc>,
+    pub acquisition_tx_id: i64,
+}
+
+impl OpenLot {
+    pub fn unrealized_gain(&self, price: Decimal) -> Decimal {
+        (price - self.cost_per_unit) * self.quantity
+    }
+}
+
+/// The part of a sale matched against a single lot
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LotDisposal {
+    pub account_id: String,
+    pub asset: String,
+    pub quantity: Decimal,
+    pub proceeds: Decimal,
+    pub cost_basis: Decimal,
+    pub gain: Decimal,
+    /// None for average cost, or for quantity sold beyond the recorded lots
+    pub acquired_at: Option<DateTime<Utc>>,
+    pub disposed_at: DateTime<Utc>,
+    pub holding_period_days: Option<i64>,
+    pub disposal_tx_id: i64,
+}
+
+/// Lot-level realized and unrealized gains for one cost basis method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LotReport {
+    pub method: CostBasisMethod,
+    pub disposals: Vec<LotDisposal>,
+    pub open_lots: Vec<OpenLot>,
+}
+
+/// Gains of one account's position in an asset, summed over its lots or disposals
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionGain {
+    pub account_id: String,
+    pub asset: String,
+    pub quantity: Decimal,
+    pub cost_basis: Decimal,
+    /// Sale proceeds, or current value for open lots; None when unpriced
+    pub value: Option<Decimal>,
+    pub gain: Option<Decimal>,
+}
+
+/// A slice of a lot taken out by a sale or transfer
+struct LotPiece {
+    quantity: Decimal,
+    cost_per_unit: Decimal,
+    acquired_at: Option<DateTime<Utc>>,
+    acquisition_tx_id: Option<i64>,
+}
+
+impl LotReport {
+    /// Rebuild lots by replaying transactions oldest first.
+    ///
+    /// Buys, receives and incoming transfers open lots at their USD cost (buy
+    /// fees included). Sells and swaps close lots in `method` order and realize
+    /// a gain; a swap opens a lot of the received asset at the value of what was
+    /// given up, or carries the old cost over when no price is known. Internal
+    /// transfers move lots between accounts without realizing anything, and
+    /// outgoing transfers and fees simply close them.
+    pub fn from_transactions<'a>(
+        method: CostBasisMethod,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+    ) -> Self {
+        let mut replay = PortfolioReplay::new(None);
+        let mut lots: HashMap<(String, String), Vec<OpenLot>> = HashMap::new();
+        let mut disposals = Vec::new();
+
+        for tx in transactions {
+            // Prices as of this transaction, including those it implies
+            replay.apply(tx);
+            let fee_usd = fee_value_usd(tx, replay.prices()).unwrap_or(Decimal::ZERO);
+
+            match tx.tx_type {
+                TransactionType::Buy | TransactionType::Receive | TransactionType::StakingReward | TransactionType::TransferIn => {
+                    let (Some(account), Some(asset), Some(qty)) =
+                        (tx.to_account_id.as_deref(), tx.to_asset.as_deref(), tx.to_quantity)
+                    else {
+                        continue;
+                    };
+                    let mut cost = qty * tx.price_usd.unwrap_or(Decimal::ZERO);
+                    if tx.tx_type == TransactionType::Buy {
+                        cost += fee_usd;
+                    }
+                    open_lot(&mut lots, account, asset, qty, cost, tx);
+                }
+                TransactionType::Sell => {
+                    let (Some(account), Some(asset), Some(qty), Some(price)) =
+                        (tx.from_account_id.as_deref(), tx.from_asset.as_deref(), tx.from_quantity, tx.price_usd)
+                    else {
+                        continue;
+                    };
+                    let pieces = take_lots(&mut lots, account, asset, qty, method);
+                    disposals.extend(dispose(pieces, account, asset, qty * price - fee_usd, tx));
+                }
+                TransactionType::Swap => {
+                    let (Some(account), Some(from_asset), Some(from_qty), Some(to_asset), Some(to_qty)) = (
+                        tx.from_account_id.as_deref(),
+                        tx.from_asset.as_deref(),
+                        tx.from_quantity,
+                        tx.to_asset.as_deref(),
+                        tx.to_quantity,
+                    ) else {
+                        continue;
+                    };
+                    let pieces = take_lots(&mut lots, account, from_asset, from_qty, method);
+                    let to_account = tx.to_account_id.as_deref().unwrap_or(account);
+
+                    match replay.price(from_asset) {
+                        Some(price) => {
+                            let value = from_qty * price;
+                            disposals.extend(dispose(pieces, account, from_asset, value - fee_usd, tx));
+                            open_lot(&mut lots, to_account, to_asset, to_qty, value, tx);
+                        }
+                        None => {
+                            let carried: Decimal = pieces.iter().map(|p| p.quantity * p.cost_per_unit).sum();
+                            open_lot(&mut lots, to_account, to_asset, to_qty, carried, tx);
+                        }
+                    }
+                }
+                TransactionType::TransferInternal => {
+                    let (Some(from_account), Some(to_account), Some(asset), Some(qty)) = (
+                        tx.from_account_id.as_deref(),
+                        tx.to_account_id.as_deref(),
+                        tx.from_asset.as_deref(),
+                        tx.from_quantity,
+                    ) else {
+                        continue;
+                    };
+                    let moved = take_lots(&mut lots, from_account, asset, qty, method);
+                    let destination = lots.entry((to_account.to_string(), asset.to_uppercase())).or_default();
+                    for piece in moved {
+                        destination.push(OpenLot {
+                            account_id: to_account.to_string(),
+                            asset: asset.to_uppercase(),
+                            quantity: piece.quantity,
+                            cost_per_unit: piece.cost_per_unit,
+                            acquired_at: piece.acquired_at.unwrap_or(tx.timestamp),
+                            acquisition_tx_id: piece.acquisition_tx_id.unwrap_or(tx.id),
+                        });
+                    }
+                }
+                TransactionType::TransferOut | TransactionType::Fee => {
+                    if let (Some(account), Some(asset), Some(qty)) =
+                        (tx.from_account_id.as_deref(), tx.from_asset.as_deref(), tx.from_quantity)
+                    {
+                        take_lots(&mut lots, account, asset, qty, method);
+                    }
+                }
+            }
+        }
+
+        let mut open_lots: Vec<OpenLot> = lots.into_values().flatten().collect();
+        open_lots.sort_by(|a, b| {
+            (&a.account_id, &a.asset, a.acquired_at).cmp(&(&b.account_id, &b.asset, b.acquired_at))
+        });
+
+        Self {
+            method,
+            disposals,
+            open_lots,
+        }
+    }
+
+    /// Keep only the lots and disposals of one account
+    pub fn for_account(mut self, account_id: &str) -> Self {
+        self.disposals.retain(|d| d.account_id == account_id);
+        self.open_lots.retain(|l| l.account_id == account_id);
+        self
+    }
+
+    pub fn realized_gain(&self) -> Decimal {
+        self.disposals.iter().map(|d| d.gain).sum()
+    }
+
+    /// Realized gains per account and asset
+    pub fn realized_by_position(&self) -> Vec<PositionGain> {
+        let mut positions: BTreeMap<(String, String), PositionGain> = BTreeMap::new();
+        for d in &self.disposals {
+            let position = positions
+                .entry((d.account_id.clone(), d.asset.clone()))
+                .or_insert_with(|| PositionGain::empty(&d.account_id, &d.asset));
+            position.quantity += d.quantity;
+            position.cost_basis += d.cost_basis;
+            position.value = position.value.map(|v| v + d.proceeds);
+            position.gain = position.gain.map(|g| g + d.gain);
+        }
+        positions.into_values().collect()
+    }
+
+    /// Unrealized gains per account and asset at `prices`; unpriced positions have no value or gain
+    pub fn unrealized_by_position(&self, prices: &HashMap<String, Decimal>) -> Vec<PositionGain> {
+        let mut positions: BTreeMap<(String, String), PositionGain> = BTreeMap::new();
+        for lot in &self.open_lots {
+            let position = positions
+                .entry((lot.account_id.clone(), lot.asset.clone()))
+                .or_insert_with(|| PositionGain::empty(&lot.account_id, &lot.asset));
+            let price = prices.get(&lot.asset).copied();
+            position.quantity += lot.quantity;
+            position.cost_basis += lot.quantity * lot.cost_per_unit;
+            position.value = position.value.zip(price).map(|(v, p)| v + lot.quantity * p);
+            position.gain = position.gain.zip(price).map(|(g, p)| g + lot.unrealized_gain(p));
+        }
+        positions.into_values().collect()
+    }
+
+    /// Unrealized gain across open lots; lots without a price in `prices` are skipped
+    pub fn unrealized_gain(&self, prices: &HashMap<String, Decimal>) -> Decimal {
+        self.open_lots
+            .iter()
+            .filter_map(|lot| prices.get(&lot.asset).map(|price| lot.unrealized_gain(*price)))
+            .sum()
+    }
+}
+
+impl PositionGain {
+    fn empty(account_id: &str, asset: &str) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            asset: asset.to_string(),
+            quantity: Decimal::ZERO,
+            cost_basis: Decimal::ZERO,
+            value: Some(Decimal::ZERO),
+            gain: Some(Decimal::ZERO),
+        }
+    }
+}
+
+/// Realized gains of one account, matching each sale against lots in `method` order
+pub async fn calculate_realized_gains(
+    method: CostBasisMethod,
+    account_id: &str,
+    pool: &SqlitePool,
+) -> Result<Vec<LotDisposal>> {
+    // Lots can arrive from other accounts, so replay everything and filter afterwards
+    let transactions = TransactionRepository::new(pool).list_chronological(None).await?;
+    Ok(LotReport::from_transactions(method, &transactions).for_account(account_id).disposals)
+}
+
+fn open_lot(
+    lots: &mut HashMap<(String, String), Vec<OpenLot>>,
+    account_id: &str,
+    asset: &str,
+    quantity: Decimal,
+    total_cost: Decimal,
+    tx: &Transaction,
+) {
+    if quantity <= Decimal::ZERO {
+        return;
+    }
+    lots.entry((account_id.to_string(), asset.to_uppercase()))
+        .or_default()
+        .push(OpenLot {
+            account_id: account_id.to_string(),
+            asset: asset.to_uppercase(),
+            quantity,
+            cost_per_unit: total_cost / quantity,
+            acquired_at: tx.timestamp,
+            acquisition_tx_id: tx.id,
+        });
+}
+
+/// Remove `quantity` from an account's lots in `method` order. Any quantity
+/// beyond the recorded lots comes back as a zero-cost piece with no lot.
+fn take_lots(
+    lots: &mut HashMap<(String, String), Vec<OpenLot>>,
+    account_id: &str,
+    asset: &str,
+    quantity: Decimal,
+    method: CostBasisMethod,
+) -> Vec<LotPiece> {
+    let open = lots.entry((account_id.to_string(), asset.to_uppercase())).or_default();
+    let held: Decimal = open.iter().map(|l| l.quantity).sum();
+    let matched = quantity.min(held);
+    let mut pieces = Vec::new();
+
+    if method == CostBasisMethod::AverageCost {
+        if matched > Decimal::ZERO {
+            let total_cost: Decimal = open.iter().map(|l| l.quantity * l.cost_per_unit).sum();
+            // Shrink every lot by the same fraction so the average stays put
+            let keep = (held - matched) / held;
+            for lot in open.iter_mut() {
+                lot.quantity *= keep;
+            }
+            pieces.push(LotPiece {
+                quantity: matched,
+                cost_per_unit: total_cost / held,
+                acquired_at: None,
+                acquisition_tx_id: None,
+            });
+        }
+    } else {
+        match method {
+            CostBasisMethod::Lifo => open.sort_by_key(|l| Reverse(l.acquired_at)),
+            CostBasisMethod::Hifo => open.sort_by_key(|l| Reverse(l.cost_per_unit)),
+            _ => open.sort_by_key(|l| l.acquired_at),
+        }
+
+        let mut remaining = matched;
+        for lot in open.iter_mut() {
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+            let taken = remaining.min(lot.quantity);
+            lot.quantity -= taken;
+            remaining -= taken;
+            pieces.push(LotPiece {
+                quantity: taken,
+                cost_per_unit: lot.cost_per_unit,
+                acquired_at: Some(lot.acquired_at),
+                acquisition_tx_id: Some(lot.acquisition_tx_id),
+            });
+        }
+    }
+
+    open.retain(|l| l.quantity > Decimal::ZERO);
+
+    if quantity > matched {
+        pieces.push(LotPiece {
+            quantity: quantity - matched,
+            cost_per_unit: Decimal::ZERO,
+            acquired_at: None,
+            acquisition_tx_id: None,
+        });
+    }
+
+    pieces
+}
+
+/// Split a sale's proceeds across the pieces it consumed, pro rata by quantity
+fn dispose(pieces: Vec<LotPiece>, account_id: &str, asset: &str, proceeds: Decimal, tx: &Transaction) -> Vec<LotDisposal> {
+    let total: Decimal = pieces.iter().map(|p| p.quantity).sum();
+    if total <= Decimal::ZERO {
+        return Vec::new();
+    }
+
+    pieces
+        .into_iter()
+        .map(|piece| {
+            let piece_proceeds = proceeds * piece.quantity / total;
+            let cost_basis = piece.quantity * piece.cost_per_unit;
+            LotDisposal {
+                account_id: account_id.to_string(),
+                asset: asset.to_uppercase(),
+                quantity: piece.quantity,
+                proceeds: piece_proceeds,
+                cost_basis,
+                gain: piece_proceeds - cost_basis,
+                acquired_at: piece.acquired_at,
+                disposed_at: tx.timestamp,
+                holding_period_days: piece.acquired_at.map(|at| (tx.timestamp - at).num_days()),
+                disposal_tx_id: tx.id,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn day(n: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::days(n)
+    }
+
+    /// Three BTC buys at 100, 300 and 200, then one sell of 1.5 at 400
+    fn history() -> Vec<Transaction> {
+        vec![
+            Transaction::new_buy("acc", "BTC", dec("1"), dec("100"), day(0)),
+            Transaction::new_buy("acc", "BTC", dec("1"), dec("300"), day(1)),
+            Transaction::new_buy("acc", "BTC", dec("1"), dec("200"), day(2)),
+            Transaction::new_sell("acc", "BTC", dec("1.5"), dec("400"), day(10)),
+        ]
+    }
+
+    #[test]
+    fn test_cost_basis_method_parse() {
+        assert_eq!(CostBasisMethod::parse("HIFO"), Some(CostBasisMethod::Hifo));
+        assert_eq!(CostBasisMethod::parse("average"), Some(CostBasisMethod::AverageCost));
+        assert_eq!(CostBasisMethod::parse("specific"), None);
+    }
+
+    #[test]
+    fn test_lot_matching_order() {
+        let fifo = LotReport::from_transactions(CostBasisMethod::Fifo, &history());
+        // 1 @ 100 + 0.5 @ 300
+        assert_eq!(fifo.realized_gain(), dec("600") - dec("250"));
+        assert_eq!(fifo.disposals.len(), 2);
+        assert_eq!(fifo.disposals[0].holding_period_days, Some(10));
+
+        let lifo = LotReport::from_transactions(CostBasisMethod::Lifo, &history());
+        // 1 @ 200 + 0.5 @ 300
+        assert_eq!(lifo.realized_gain(), dec("600") - dec("350"));
+
+        let hifo = LotReport::from_transactions(CostBasisMethod::Hifo, &history());
+        // 1 @ 300 + 0.5 @ 200
+        assert_eq!(hifo.realized_gain(), dec("600") - dec("400"));
+
+        let remaining: Decimal = hifo.open_lots.iter().map(|l| l.quantity).sum();
+        assert_eq!(remaining, dec("1.5"));
+    }
+
+    #[test]
+    fn test_average_cost() {
+        let report = LotReport::from_transactions(CostBasisMethod::AverageCost, &history());
+        // 1.5 at an average of 200
+        assert_eq!(report.realized_gain(), dec("300"));
+        assert!(report.disposals[0].acquired_at.is_none());
+
+        let prices = HashMap::from([("BTC".to_string(), dec("400"))]);
+        assert_eq!(report.unrealized_gain(&prices), dec("300"));
+    }
+
+    #[test]
+    fn test_gains_by_position() {
+        let mut transactions = history();
+        transactions.push(Transaction::new_buy("acc", "ETH", dec("2"), dec("1000"), day(3)));
+        let report = LotReport::from_transactions(CostBasisMethod::Fifo, &transactions);
+
+        let realized = report.realized_by_position();
+        assert_eq!(realized.len(), 1);
+        assert_eq!(realized[0].quantity, dec("1.5"));
+        assert_eq!(realized[0].cost_basis, dec("250"));
+        assert_eq!(realized[0].value, Some(dec("600")));
+        assert_eq!(realized[0].gain, Some(dec("350")));
+
+        let prices = HashMap::from([("BTC".to_string(), dec("400"))]);
+        let unrealized = report.unrealized_by_position(&prices);
+        assert_eq!(unrealized[0].asset, "BTC");
+        // 0.5 @ 300 + 1 @ 200
+        assert_eq!(unrealized[0].cost_basis, dec("350"));
+        assert_eq!(unrealized[0].gain, Some(dec("250")));
+        assert_eq!(unrealized[1].asset, "ETH");
+        assert_eq!(unrealized[1].gain, None);
+    }
+
+    #[test]
+    fn test_internal_transfer_carries_cost_basis() {
+        let transactions = vec![
+            Transaction::new_buy("exchange", "ETH", dec("2"), dec("1000"), day(0)),
+            Transaction::new_transfer("exchange", "ledger", "ETH", dec("2"), day(5)),
+            Transaction::new_sell("ledger", "ETH", dec("1"), dec("1500"), day(400)),
+        ];
+
+        let report = LotReport::from_transactions(CostBasisMethod::Fifo, &transactions).for_account("ledger");
+        assert_eq!(report.realized_gain(), dec("500"));
+        // The holding period runs from the original purchase
+        assert_eq!(report.disposals[0].holding_period_days, Some(400));
+        assert_eq!(report.open_lots[0].cost_per_unit, dec("1000"));
+    }
+
+    #[test]
+    fn test_swap_realizes_gain_and_opens_lot() {
+        let transactions = vec![
+            Transaction::new_buy("acc", "ETH", dec("1"), dec("2000"), day(0)),
+            Transaction::new_buy("acc", "ETH", dec("0"), dec("3000"), day(1)),
+            Transaction::new_swap("acc", "ETH", dec("1"), "SOL", dec("30"), day(2)),
+        ];
+
+        let report = LotReport::from_transactions(CostBasisMethod::Fifo, &transactions);
+        assert_eq!(report.realized_gain(), dec("1000"));
+        assert_eq!(report.open_lots[0].asset, "SOL");
+        assert_eq!(report.open_lots[0].cost_per_unit, dec("100"));
+    }
+
+    #[test]
+    fn test_sell_beyond_lots_has_zero_basis() {
+        let transactions = vec![Transaction::new_sell("acc", "BTC", dec("1"), dec("500"), day(0))];
+
+        let report = LotReport::from_transactions(CostBasisMethod::Fifo, &transactions);
+        assert_eq!(report.realized_gain(), dec("500"));
+        assert!(report.disposals[0].acquired_at.is_none());
+    }
+}
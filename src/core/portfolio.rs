@@ -1,9 +1,13 @@
 #![allow(dead_code)]
 
+use chrono::{DateTime, Duration, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
+use super::currency::is_usd_equivalent;
 use super::holdings::HoldingWithPrice;
+use super::transaction::{Transaction, TransactionType};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortfolioEntry {
@@ -12,6 +16,15 @@ pub struct PortfolioEntry {
     pub category_id: String,
     pub category_name: String,
     pub holdings: Vec<HoldingWithPrice>,
+    /// Snapshot this entry was loaded from; None for live valuations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_id: Option<i64>,
+    /// Every position has been sold off; the entry is only listed for its realized gains
+    #[serde(default)]
+    pub is_closed: bool,
+    /// Total value in the display currency; None when shown in USD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_value: Option<Decimal>,
 }
 
 impl PortfolioEntry {
@@ -66,9 +79,23 @@ impl Portfolio {
         }
     }
 
-    pub fn by_category(&self) -> Vec<CategorySummary> {
-        use std::collections::HashMap;
+    /// Total value of the accounts in a category
+    pub fn category_value(&self, category_id: &str) -> Decimal {
+        self.entries
+            .iter()
+            .filter(|e| e.category_id == category_id)
+            .map(|e| e.total_value())
+            .sum()
+    }
 
+    /// Share of the total portfolio value held in `value`, as a percentage
+    pub fn allocation_pct(&self, value: Decimal) -> Decimal {
+        allocation_pct(value, self.total_value_usd)
+    }
+
+    /// Group entries by category, in the order of `category_order` (category ids);
+    /// categories not listed follow, largest first
+    pub fn by_category(&self, category_order: &[String]) -> Vec<CategorySummary> {
         let mut categories: HashMap<String, CategorySummary> = HashMap::new();
 
         for entry in &self.entries {
@@ -87,14 +114,17 @@ impl Portfolio {
             summary.total_cost_basis += entry.total_cost_basis();
         }
 
+        let rank = |id: &str| category_order.iter().position(|o| o == id).unwrap_or(usize::MAX);
         let mut result: Vec<_> = categories.into_values().collect();
-        result.sort_by(|a, b| b.total_value.cmp(&a.total_value));
+        result.sort_by(|a, b| {
+            rank(&a.category_id)
+                .cmp(&rank(&b.category_id))
+                .then_with(|| b.total_value.cmp(&a.total_value))
+        });
         result
     }
 
     pub fn asset_totals(&self) -> Vec<AssetTotal> {
-        use std::collections::HashMap;
-
         let mut assets: HashMap<String, AssetTotal> = HashMap::new();
 
         for entry in &self.entries {
@@ -124,6 +154,16 @@ impl Portfolio {
     }
 }
 
+/// `value` as a percentage of `total`, rounded to two places; zero when the
+/// total is zero
+pub fn allocation_pct(value: Decimal, total: Decimal) -> Decimal {
+    if total > Decimal::ZERO {
+        (value / total * Decimal::from(100)).round_dp(2)
+    } else {
+        Decimal::ZERO
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategorySummary {
     pub category_id: String,
@@ -160,3 +200,296 @@ impl AssetTotal {
         self.value - self.cost_basis
     }
 }
+
+/// Lifetime money put into the portfolio compared with what it is worth now
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvestmentSummary {
+    pub total_invested: Decimal,
+    pub current_value: Decimal,
+}
+
+impl InvestmentSummary {
+    /// Sum every buy's `quantity * price_usd + fee_usd` across all time.
+    /// Fees are valued as in [`fee_value_usd`] and skipped when no price is known.
+    pub fn from_transactions<'a>(
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+        prices: &HashMap<String, Decimal>,
+        current_value: Decimal,
+    ) -> Self {
+        let mut total_invested = Decimal::ZERO;
+
+        for tx in transactions {
+            if tx.tx_type != TransactionType::Buy {
+                continue;
+            }
+            let (Some(quantity), Some(price)) = (tx.to_quantity, tx.price_usd) else {
+                continue;
+            };
+            total_invested += quantity * price;
+            total_invested += fee_value_usd(tx, prices).unwrap_or(Decimal::ZERO);
+        }
+
+        Self {
+            total_invested,
+            current_value,
+        }
+    }
+
+    pub fn total_return(&self) -> Decimal {
+        self.current_value - self.total_invested
+    }
+
+    pub fn total_return_percent(&self) -> Decimal {
+        if self.total_invested > Decimal::ZERO {
+            (self.total_return() / self.total_invested) * Decimal::from(100)
+        } else {
+            Decimal::ZERO
+        }
+    }
+}
+
+/// Saved valuation of the whole portfolio at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    pub id: i64,
+    pub total_value_usd: Decimal,
+    pub total_cost_basis: Decimal,
+    pub taken_at: DateTime<Utc>,
+}
+
+/// Periods reported by `portfolio history`, in days
+pub const RETURN_PERIODS: &[(&str, i64)] = &[("7d", 7), ("30d", 30), ("90d", 90), ("1y", 365)];
+
+/// Change in portfolio value over a period, between two snapshots
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodReturn {
+    pub period: &'static str,
+    pub start_value: Decimal,
+    pub end_value: Decimal,
+    pub change: Decimal,
+    pub change_percent: Option<Decimal>,
+}
+
+/// Returns over each of [`RETURN_PERIODS`], measured from the newest snapshot
+/// back to the newest one taken at least that long before it.
+/// Periods without a snapshot old enough are omitted.
+pub fn period_returns(snapshots: &[PortfolioSnapshot]) -> Vec<PeriodReturn> {
+    let Some(latest) = snapshots.iter().max_by_key(|s| s.taken_at) else {
+        return Vec::new();
+    };
+
+    RETURN_PERIODS
+        .iter()
+        .filter_map(|&(period, days)| {
+            let cutoff = latest.taken_at - Duration::days(days);
+            let start = snapshots
+                .iter()
+                .filter(|s| s.taken_at <= cutoff)
+                .max_by_key(|s| s.taken_at)?;
+
+            let change = latest.total_value_usd - start.total_value_usd;
+            let change_percent = (start.total_value_usd > Decimal::ZERO)
+                .then(|| change / start.total_value_usd * Decimal::from(100));
+
+            Some(PeriodReturn {
+                period,
+                start_value: start.total_value_usd,
+                end_value: latest.total_value_usd,
+                change,
+                change_percent,
+            })
+        })
+        .collect()
+}
+
+/// Portfolio return set against simply holding one benchmark asset since `since`
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkComparison {
+    pub asset: String,
+    pub since: DateTime<Utc>,
+    pub portfolio_return_percent: Decimal,
+    pub benchmark_start_price: Decimal,
+    pub benchmark_price: Decimal,
+}
+
+impl BenchmarkComparison {
+    pub fn benchmark_return_percent(&self) -> Decimal {
+        if self.benchmark_start_price > Decimal::ZERO {
+            (self.benchmark_price - self.benchmark_start_price) / self.benchmark_start_price * Decimal::from(100)
+        } else {
+            Decimal::ZERO
+        }
+    }
+
+    /// Percentage points the portfolio beat the benchmark by; negative when it lagged
+    pub fn alpha(&self) -> Decimal {
+        self.portfolio_return_percent - self.benchmark_return_percent()
+    }
+}
+
+/// The account and asset a transaction's fee is charged against: the side
+/// being disposed of for sells and outgoing transfers, otherwise the side
+/// being received.
+fn fee_position(tx: &Transaction) -> Option<(&str, &str)> {
+    match tx.tx_type {
+        TransactionType::Sell | TransactionType::TransferOut | TransactionType::Fee => {
+            Some((tx.from_account_id.as_deref()?, tx.from_asset.as_deref()?))
+        }
+        _ => Some((tx.to_account_id.as_deref()?, tx.to_asset.as_deref()?)),
+    }
+}
+
+/// USD value of a transaction's fee.
+///
+/// Fees paid in a USD stablecoin count at face value and fees paid in the
+/// traded asset at the trade price; any other fee asset is valued with
+/// `prices`. Returns None when there is no fee or no price for it.
+pub fn fee_value_usd(tx: &Transaction, prices: &HashMap<String, Decimal>) -> Option<Decimal> {
+    let (fee, fee_asset) = (tx.fee?, tx.fee_asset.as_deref()?);
+
+    let traded_asset = fee_position(tx).map(|(_, asset)| asset);
+    let fee_price = if is_usd_equivalent(fee_asset) {
+        Some(Decimal::ONE)
+    } else if traded_asset.is_some_and(|a| a.eq_ignore_ascii_case(fee_asset)) && tx.price_usd.is_some() {
+        tx.price_usd
+    } else {
+        prices.get(&fee_asset.to_uppercase()).copied()
+    };
+
+    Some(fee * fee_price?)
+}
+
+/// Total USD fees paid per position, keyed by `(account_id, ASSET)`
+pub fn fees_by_position<'a>(
+    transactions: impl IntoIterator<Item = &'a Transaction>,
+    prices: &HashMap<String, Decimal>,
+) -> HashMap<(String, String), Decimal> {
+    let mut fees: HashMap<(String, String), Decimal> = HashMap::new();
+
+    for tx in transactions {
+        let (Some((account_id, asset)), Some(fee_usd)) = (fee_position(tx), fee_value_usd(tx, prices)) else {
+            continue;
+        };
+        *fees
+            .entry((account_id.to_string(), asset.to_uppercase()))
+            .or_insert(Decimal::ZERO) += fee_usd;
+    }
+
+    fees
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_benchmark_alpha() {
+        let comparison = BenchmarkComparison {
+            asset: "BTC".to_string(),
+            since: Utc::now(),
+            portfolio_return_percent: dec("25"),
+            benchmark_start_price: dec("40000"),
+            benchmark_price: dec("60000"),
+        };
+
+        assert_eq!(comparison.benchmark_return_percent(), dec("50"));
+        assert_eq!(comparison.alpha(), dec("-25"));
+    }
+
+    #[test]
+    fn test_total_invested_includes_fees() {
+        let mut buy_btc = Transaction::new_buy("acc", "BTC", dec("0.5"), dec("40000"), Utc::now());
+        buy_btc.fee = Some(dec("10"));
+        buy_btc.fee_asset = Some("USDT".to_string());
+
+        let mut buy_eth = Transaction::new_buy("acc", "ETH", dec("1"), dec("3000"), Utc::now());
+        buy_eth.fee = Some(dec("0.01"));
+        buy_eth.fee_asset = Some("BNB".to_string());
+
+        // Sells reduce holdings but never what was put in
+        let sell = Transaction::new_sell("acc", "BTC", dec("0.5"), dec("60000"), Utc::now());
+
+        let prices = HashMap::from([("BNB".to_string(), dec("500"))]);
+        let summary = InvestmentSummary::from_transactions(&[buy_btc, buy_eth, sell], &prices, dec("4000"));
+
+        // 20000 + 10 + 3000 + 0.01 * 500
+        assert_eq!(summary.total_invested, dec("23015"));
+        assert_eq!(summary.total_return(), dec("-19015"));
+    }
+
+    #[test]
+    fn test_allocation_pct() {
+        assert_eq!(allocation_pct(dec("250"), dec("1000")), dec("25"));
+        assert_eq!(allocation_pct(dec("1"), dec("3")), dec("33.33"));
+        assert_eq!(allocation_pct(dec("5"), Decimal::ZERO), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_total_return_percent_without_investment() {
+        let summary = InvestmentSummary::from_transactions(&[], &HashMap::new(), dec("100"));
+        assert_eq!(summary.total_return_percent(), Decimal::ZERO);
+    }
+
+    fn snapshot(id: i64, days_ago: i64, value: &str) -> PortfolioSnapshot {
+        PortfolioSnapshot {
+            id,
+            total_value_usd: dec(value),
+            total_cost_basis: Decimal::ZERO,
+            taken_at: Utc::now() - Duration::days(days_ago),
+        }
+    }
+
+    #[test]
+    fn test_period_returns() {
+        let snapshots = vec![
+            snapshot(1, 100, "8000"),
+            snapshot(2, 40, "9000"),
+            snapshot(3, 7, "10000"),
+            snapshot(4, 0, "12000"),
+        ];
+        let returns = period_returns(&snapshots);
+
+        // No snapshot is a year old, so 1y is left out
+        let periods: Vec<&str> = returns.iter().map(|r| r.period).collect();
+        assert_eq!(periods, vec!["7d", "30d", "90d"]);
+
+        assert_eq!(returns[0].start_value, dec("10000"));
+        assert_eq!(returns[0].change_percent, Some(dec("20")));
+        assert_eq!(returns[1].start_value, dec("9000"));
+        assert_eq!(returns[2].change, dec("4000"));
+        assert_eq!(returns[2].change_percent, Some(dec("50")));
+    }
+
+    #[test]
+    fn test_fees_by_position() {
+        let mut buy = Transaction::new_buy("acc", "BTC", dec("1"), dec("40000"), Utc::now());
+        buy.fee = Some(dec("0.001"));
+        buy.fee_asset = Some("BTC".to_string());
+
+        let mut sell = Transaction::new_sell("acc", "BTC", dec("0.5"), dec("50000"), Utc::now());
+        sell.fee = Some(dec("5"));
+        sell.fee_asset = Some("USDC".to_string());
+
+        let mut swap = Transaction::new_swap("acc", "BTC", dec("0.1"), "ETH", dec("2"), Utc::now());
+        swap.fee = Some(dec("0.01"));
+        swap.fee_asset = Some("BNB".to_string());
+
+        let mut unpriced = Transaction::new_buy("acc", "ETH", dec("1"), dec("3000"), Utc::now());
+        unpriced.fee = Some(dec("1"));
+        unpriced.fee_asset = Some("XYZ".to_string());
+
+        let prices = HashMap::from([("BNB".to_string(), dec("500"))]);
+        let fees = fees_by_position(&[buy, sell, swap, unpriced], &prices);
+
+        // 0.001 BTC at the 40000 trade price, plus 5 USDC at face value
+        assert_eq!(fees[&("acc".to_string(), "BTC".to_string())], dec("45"));
+        // Swap fees land on the received asset; unpriced fees are skipped
+        assert_eq!(fees[&("acc".to_string(), "ETH".to_string())], dec("5"));
+    }
+}
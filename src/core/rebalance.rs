@@ -0,0 +1,266 @@
+//! Trades that move a portfolio toward a target allocation.
+//!
+//! Targets are shares of the whole portfolio. Held assets left out of the
+//! target are aimed at 0%, so the plan sells them off.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use super::portfolio::{allocation_pct, AssetTotal};
+use crate::error::{CryptofolioError, Result};
+
+/// The share of the portfolio an asset should make up
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetAllocation {
+    pub asset: String,
+    pub percent: Decimal,
+}
+
+/// Parse a target like `BTC:60,ETH:30,SOL:10`. The percentages must add up to 100.
+pub fn parse_targets(spec: &str) -> Result<Vec<TargetAllocation>> {
+    let mut targets: Vec<TargetAllocation> = Vec::new();
+
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (asset, percent) = part.split_once(':').ok_or_else(|| {
+            CryptofolioError::InvalidInput(format!("Invalid target '{}'. Use ASSET:PERCENT, e.g. BTC:60", part))
+        })?;
+
+        let asset = asset.trim().to_uppercase();
+        let percent = Decimal::from_str(percent.trim())
+            .map_err(|_| CryptofolioError::InvalidAmount(percent.trim().to_string()))?;
+
+        if asset.is_empty() || percent < Decimal::ZERO || percent > Decimal::ONE_HUNDRED {
+            return Err(CryptofolioError::InvalidInput(format!(
+                "Invalid target '{}'. Percentages run from 0 to 100",
+                part
+            )));
+        }
+        if targets.iter().any(|t| t.asset == asset) {
+            return Err(CryptofolioError::InvalidInput(format!("{} is listed more than once", asset)));
+        }
+
+        targets.push(TargetAllocation { asset, percent });
+    }
+
+    if targets.is_empty() {
+        return Err(CryptofolioError::InvalidInput(
+            "No target given. Use ASSET:PERCENT pairs, e.g. BTC:60,ETH:30,SOL:10".into(),
+        ));
+    }
+
+    let total: Decimal = targets.iter().map(|t| t.percent).sum();
+    if total != Decimal::ONE_HUNDRED {
+        return Err(CryptofolioError::InvalidInput(format!(
+            "Target percentages add up to {}%, not 100%",
+            total.normalize()
+        )));
+    }
+
+    Ok(targets)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+impl TradeSide {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TradeSide::Buy => "BUY",
+            TradeSide::Sell => "SELL",
+        }
+    }
+}
+
+/// An asset's current share of the portfolio against its target
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetAllocation {
+    pub asset: String,
+    pub quantity: Decimal,
+    pub value_usd: Decimal,
+    pub current_pct: Decimal,
+    pub target_pct: Decimal,
+}
+
+impl AssetAllocation {
+    /// Percentage points above (positive) or below the target
+    pub fn drift(&self) -> Decimal {
+        self.current_pct - self.target_pct
+    }
+}
+
+/// A suggested trade, sized at current prices
+#[derive(Debug, Clone, Serialize)]
+pub struct RebalanceTrade {
+    pub asset: String,
+    pub side: TradeSide,
+    /// Approximate USD value to buy or sell
+    pub amount_usd: Decimal,
+    /// Quantity at `price_usd`; None when the asset has no price
+    pub quantity: Option<Decimal>,
+    pub price_usd: Option<Decimal>,
+}
+
+/// Where the portfolio stands against a target allocation and the trades to get there
+#[derive(Debug, Clone, Serialize)]
+pub struct RebalancePlan {
+    pub total_value_usd: Decimal,
+    pub threshold_pct: Decimal,
+    pub allocations: Vec<AssetAllocation>,
+    pub trades: Vec<RebalanceTrade>,
+}
+
+impl RebalancePlan {
+    /// Plan trades from the portfolio's per-asset totals and current prices
+    /// (keyed by uppercased asset). Assets no more than `threshold_pct`
+    /// percentage points off target are left alone.
+    pub fn new(
+        assets: &[AssetTotal],
+        prices: &HashMap<String, Decimal>,
+        targets: &[TargetAllocation],
+        threshold_pct: Decimal,
+    ) -> Self {
+        let held: HashMap<String, &AssetTotal> = assets.iter().map(|a| (a.asset.to_uppercase(), a)).collect();
+        let total: Decimal = assets.iter().map(|a| a.value).sum();
+
+        let allocation = |asset: &str, target_pct: Decimal| {
+            let (quantity, value_usd) = held.get(asset).map(|a| (a.quantity, a.value)).unwrap_or_default();
+            AssetAllocation {
+                asset: asset.to_string(),
+                quantity,
+                value_usd,
+                current_pct: allocation_pct(value_usd, total),
+                target_pct,
+            }
+        };
+
+        let mut allocations: Vec<AssetAllocation> = targets.iter().map(|t| allocation(&t.asset, t.percent)).collect();
+        let mut untargeted: Vec<&String> = held
+            .iter()
+            .filter(|(asset, a)| a.value > Decimal::ZERO && !targets.iter().any(|t| &t.asset == *asset))
+            .map(|(asset, _)| asset)
+            .collect();
+        untargeted.sort();
+        allocations.extend(untargeted.into_iter().map(|asset| allocation(asset, Decimal::ZERO)));
+
+        let mut trades: Vec<RebalanceTrade> = allocations
+            .iter()
+            .filter(|a| total > Decimal::ZERO && a.drift().abs() > threshold_pct)
+            .filter_map(|a| {
+                let delta = (total * a.target_pct / Decimal::ONE_HUNDRED - a.value_usd).round_dp(2);
+                if delta.is_zero() {
+                    return None;
+                }
+
+                let price = prices.get(&a.asset).copied().filter(|p| *p > Decimal::ZERO);
+                // Selling out entirely takes the whole holding rather than an estimate of it
+                let quantity = if a.target_pct.is_zero() {
+                    Some(a.quantity)
+                } else {
+                    price.map(|p| (delta.abs() / p).round_dp(8))
+                };
+
+                Some(RebalanceTrade {
+                    asset: a.asset.clone(),
+                    side: if delta > Decimal::ZERO { TradeSide::Buy } else { TradeSide::Sell },
+                    amount_usd: delta.abs(),
+                    quantity,
+                    price_usd: price,
+                })
+            })
+            .collect();
+
+        // Largest first; on equal amounts sell first, so the proceeds fund the
+        // buys instead of adding cash and paying fees on both sides
+        trades.sort_by(|a, b| {
+            b.amount_usd
+                .cmp(&a.amount_usd)
+                .then_with(|| (a.side == TradeSide::Buy).cmp(&(b.side == TradeSide::Buy)))
+        });
+
+        Self {
+            total_value_usd: total,
+            threshold_pct,
+            allocations,
+            trades,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(symbol: &str, quantity: i64, value: i64) -> AssetTotal {
+        AssetTotal {
+            asset: symbol.to_string(),
+            quantity: Decimal::from(quantity),
+            value: Decimal::from(value),
+            cost_basis: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_parse_targets() {
+        let targets = parse_targets("btc:60, ETH:30,SOL:10").unwrap();
+        assert_eq!(targets.len(), 3);
+        assert_eq!(targets[0], TargetAllocation { asset: "BTC".to_string(), percent: Decimal::from(60) });
+
+        assert!(parse_targets("BTC:60,ETH:30").is_err());
+        assert!(parse_targets("BTC:50,BTC:50").is_err());
+        assert!(parse_targets("BTC").is_err());
+        assert!(parse_targets("BTC:abc").is_err());
+        assert!(parse_targets("").is_err());
+    }
+
+    #[test]
+    fn test_plan_trades_toward_target() {
+        // $10,000 split 70/20/10 between BTC, ETH and DOGE, no SOL yet
+        let assets = vec![asset("BTC", 1, 7000), asset("ETH", 4, 2000), asset("DOGE", 5000, 1000)];
+        let prices = HashMap::from([
+            ("BTC".to_string(), Decimal::from(7000)),
+            ("ETH".to_string(), Decimal::from(500)),
+            ("SOL".to_string(), Decimal::from(100)),
+        ]);
+        let targets = parse_targets("BTC:60,ETH:30,SOL:10").unwrap();
+
+        let plan = RebalancePlan::new(&assets, &prices, &targets, Decimal::ZERO);
+        assert_eq!(plan.total_value_usd, Decimal::from(10000));
+        assert_eq!(plan.allocations.len(), 4);
+
+        let trades: Vec<(&str, TradeSide, Decimal)> =
+            plan.trades.iter().map(|t| (t.asset.as_str(), t.side, t.amount_usd)).collect();
+        assert_eq!(
+            trades,
+            vec![
+                // Equal amounts: the sells come first
+                ("BTC", TradeSide::Sell, Decimal::from(1000)),
+                ("DOGE", TradeSide::Sell, Decimal::from(1000)),
+                ("ETH", TradeSide::Buy, Decimal::from(1000)),
+                ("SOL", TradeSide::Buy, Decimal::from(1000)),
+            ]
+        );
+
+        // DOGE has no target, so all of it is sold even without a price
+        assert_eq!(plan.trades[1].quantity, Some(Decimal::from(5000)));
+        assert_eq!(plan.trades[3].quantity, Some(Decimal::from(10)));
+    }
+
+    #[test]
+    fn test_plan_skips_assets_within_threshold() {
+        let assets = vec![asset("BTC", 1, 6150), asset("ETH", 1, 3850)];
+        let targets = parse_targets("BTC:60,ETH:40").unwrap();
+
+        let plan = RebalancePlan::new(&assets, &HashMap::new(), &targets, Decimal::from(2));
+        assert!(plan.trades.is_empty());
+
+        let plan = RebalancePlan::new(&assets, &HashMap::new(), &targets, Decimal::ONE);
+        assert_eq!(plan.trades.len(), 2);
+        assert!(plan.trades.iter().all(|t| t.quantity.is_none()));
+    }
+}
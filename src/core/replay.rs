@@ -0,0 +1,222 @@
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use super::currency::is_usd_equivalent;
+use super::transaction::{Transaction, TransactionType};
+
+/// Replays transactions in chronological order, tracking holdings and the
+/// last known USD price of each asset.
+///
+/// Prices come from the transactions themselves (buy/sell prices, and the
+/// implied price of the other leg of a swap), so the value after a
+/// transaction is only known once every held asset has been priced.
+pub struct PortfolioReplay {
+    account_id: Option<String>,
+    quantities: HashMap<String, Decimal>,
+    prices: HashMap<String, Decimal>,
+}
+
+impl PortfolioReplay {
+    /// Create a replay over the whole portfolio, or a single account when given
+    pub fn new(account_id: Option<&str>) -> Self {
+        Self {
+            account_id: account_id.map(|s| s.to_string()),
+            quantities: HashMap::new(),
+            prices: HashMap::new(),
+        }
+    }
+
+    /// Apply a transaction and return the portfolio value right after it
+    pub fn apply(&mut self, tx: &Transaction) -> Option<Decimal> {
+        self.record_prices(tx);
+
+        let is_from = self.involves(tx.from_account_id.as_deref());
+        let is_to = self.involves(tx.to_account_id.as_deref());
+
+        match tx.tx_type {
+            TransactionType::Buy | TransactionType::Receive | TransactionType::StakingReward | TransactionType::TransferIn => {
+                self.credit(tx.to_asset.as_deref(), tx.to_quantity);
+            }
+            TransactionType::Sell | TransactionType::TransferOut | TransactionType::Fee => {
+                self.debit(tx.from_asset.as_deref(), tx.from_quantity);
+            }
+            TransactionType::TransferInternal => {
+                if is_from {
+                    self.debit(tx.from_asset.as_deref(), tx.from_quantity);
+                }
+                if is_to {
+                    self.credit(tx.to_asset.as_deref(), tx.to_quantity);
+                }
+            }
+            TransactionType::Swap => {
+                self.debit(tx.from_asset.as_deref(), tx.from_quantity);
+                self.credit(tx.to_asset.as_deref(), tx.to_quantity);
+            }
+        }
+
+        // Transfer fees are taken from what the destination receives
+        let pays_fee = match tx.tx_type {
+            TransactionType::TransferInternal => is_to,
+            _ => true,
+        };
+        if pays_fee {
+            self.debit(tx.fee_asset.as_deref(), tx.fee);
+        }
+
+        self.total_value()
+    }
+
+    /// Current value of all replayed holdings, or None if any held asset has no known price
+    pub fn total_value(&self) -> Option<Decimal> {
+        let mut total = Decimal::ZERO;
+
+        for (asset, quantity) in &self.quantities {
+            if *quantity == Decimal::ZERO {
+                continue;
+            }
+            total += *quantity * self.price_of(asset)?;
+        }
+
+        Some(total)
+    }
+
+    /// Current replayed quantity of an asset
+    pub fn quantity(&self, asset: &str) -> Decimal {
+        self.quantities
+            .get(&asset.to_uppercase())
+            .copied()
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// Last known USD price of an asset
+    pub fn price(&self, asset: &str) -> Option<Decimal> {
+        self.price_of(&asset.to_uppercase())
+    }
+
+    /// Last known USD prices, keyed by uppercase asset
+    pub fn prices(&self) -> &HashMap<String, Decimal> {
+        &self.prices
+    }
+
+    fn price_of(&self, asset: &str) -> Option<Decimal> {
+        if is_usd_equivalent(asset) {
+            return Some(Decimal::ONE);
+        }
+        self.prices.get(asset).copied()
+    }
+
+    fn involves(&self, account_id: Option<&str>) -> bool {
+        match &self.account_id {
+            Some(filter) => account_id == Some(filter.as_str()),
+            None => true,
+        }
+    }
+
+    fn record_prices(&mut self, tx: &Transaction) {
+        let price = match tx.price_usd {
+            Some(p) if p > Decimal::ZERO => Some(p),
+            _ => None,
+        };
+
+        match tx.tx_type {
+            TransactionType::Buy => {
+                if let (Some(asset), Some(p)) = (&tx.to_asset, price) {
+                    self.prices.insert(asset.to_uppercase(), p);
+                }
+            }
+            TransactionType::Sell => {
+                if let (Some(asset), Some(p)) = (&tx.from_asset, price) {
+                    self.prices.insert(asset.to_uppercase(), p);
+                }
+            }
+            TransactionType::Swap => {
+                let (Some(from_asset), Some(from_qty), Some(to_asset), Some(to_qty)) =
+                    (&tx.from_asset, tx.from_quantity, &tx.to_asset, tx.to_quantity)
+                else {
+                    return;
+                };
+                let from_asset = from_asset.to_uppercase();
+                let to_asset = to_asset.to_uppercase();
+
+                if from_qty <= Decimal::ZERO || to_qty <= Decimal::ZERO {
+                    return;
+                }
+
+                // Infer the price of whichever leg is unknown from the other
+                if let Some(from_price) = self.price_of(&from_asset) {
+                    self.prices.insert(to_asset, from_price * from_qty / to_qty);
+                } else if let Some(to_price) = self.price_of(&to_asset) {
+                    self.prices.insert(from_asset, to_price * to_qty / from_qty);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn credit(&mut self, asset: Option<&str>, quantity: Option<Decimal>) {
+        if let (Some(asset), Some(qty)) = (asset, quantity) {
+            *self.quantities.entry(asset.to_uppercase()).or_insert(Decimal::ZERO) += qty;
+        }
+    }
+
+    fn debit(&mut self, asset: Option<&str>, quantity: Option<Decimal>) {
+        if let (Some(asset), Some(qty)) = (asset, quantity) {
+            *self.quantities.entry(asset.to_uppercase()).or_insert(Decimal::ZERO) -= qty;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_replay_buy_and_sell() {
+        let mut replay = PortfolioReplay::new(None);
+
+        let buy = Transaction::new_buy("acc", "BTC", dec("1"), dec("40000"), Utc::now());
+        assert_eq!(replay.apply(&buy), Some(dec("40000")));
+
+        // Selling half at a higher price revalues the remaining position
+        let sell = Transaction::new_sell("acc", "BTC", dec("0.5"), dec("50000"), Utc::now());
+        assert_eq!(replay.apply(&sell), Some(dec("25000")));
+    }
+
+    #[test]
+    fn test_replay_unknown_price() {
+        let mut replay = PortfolioReplay::new(None);
+
+        let mut receive = Transaction::new_buy("acc", "XYZ", dec("10"), dec("0"), Utc::now());
+        receive.price_usd = None;
+        assert_eq!(replay.apply(&receive), None);
+    }
+
+    #[test]
+    fn test_replay_swap_infers_price() {
+        let mut replay = PortfolioReplay::new(None);
+
+        let buy = Transaction::new_buy("acc", "ETH", dec("2"), dec("3000"), Utc::now());
+        replay.apply(&buy);
+
+        let swap = Transaction::new_swap("acc", "ETH", dec("2"), "SOL", dec("40"), Utc::now());
+        assert_eq!(replay.apply(&swap), Some(dec("6000")));
+        assert_eq!(replay.quantity("SOL"), dec("40"));
+    }
+
+    #[test]
+    fn test_replay_account_scoped_transfer() {
+        let mut replay = PortfolioReplay::new(Some("a"));
+
+        let buy = Transaction::new_buy("a", "BTC", dec("1"), dec("30000"), Utc::now());
+        replay.apply(&buy);
+
+        let transfer = Transaction::new_transfer("a", "b", "BTC", dec("0.4"), Utc::now());
+        assert_eq!(replay.apply(&transfer), Some(dec("18000")));
+    }
+}
@@ -0,0 +1,170 @@
+//! Capital gains summaries for a tax year.
+//!
+//! Built from the lot matching in [`LotReport`]: every sale or swap is split
+//! into one [`TaxLot`] per purchase lot it consumed, so a sale that takes part
+//! of a lot, or spans several, is reported piece by piece.
+
+use chrono::{DateTime, Datelike, Utc};
+use rust_decimal::Decimal;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::pnl::{CostBasisMethod, LotDisposal, LotReport};
+
+/// Holding periods longer than this many days are long-term
+pub const LONG_TERM_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HoldingTerm {
+    Short,
+    Long,
+}
+
+impl HoldingTerm {
+    /// Disposals without a known acquisition date (average cost, or more sold
+    /// than was recorded) count as short-term
+    pub fn from_days(holding_period_days: Option<i64>) -> Self {
+        match holding_period_days {
+            Some(days) if days > LONG_TERM_DAYS => HoldingTerm::Long,
+            _ => HoldingTerm::Short,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HoldingTerm::Short => "short",
+            HoldingTerm::Long => "long",
+        }
+    }
+}
+
+/// The part of one disposal matched against a single purchase lot
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TaxLot {
+    pub account_id: String,
+    pub asset: String,
+    pub quantity: Decimal,
+    pub proceeds: Decimal,
+    pub cost_basis: Decimal,
+    pub gain: Decimal,
+    pub acquired_at: Option<DateTime<Utc>>,
+    pub disposed_at: DateTime<Utc>,
+    pub holding_period_days: Option<i64>,
+    pub term: HoldingTerm,
+    pub disposal_tx_id: i64,
+}
+
+impl From<&LotDisposal> for TaxLot {
+    fn from(d: &LotDisposal) -> Self {
+        Self {
+            account_id: d.account_id.clone(),
+            asset: d.asset.clone(),
+            quantity: d.quantity,
+            proceeds: d.proceeds,
+            cost_basis: d.cost_basis,
+            gain: d.gain,
+            acquired_at: d.acquired_at,
+            disposed_at: d.disposed_at,
+            holding_period_days: d.holding_period_days,
+            term: HoldingTerm::from_days(d.holding_period_days),
+            disposal_tx_id: d.disposal_tx_id,
+        }
+    }
+}
+
+/// Realized gains of one calendar year, split into short- and long-term
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxReport {
+    pub year: i32,
+    pub method: CostBasisMethod,
+    pub lots: Vec<TaxLot>,
+    pub total_proceeds: Decimal,
+    pub total_cost_basis: Decimal,
+    pub total_short_term_gain: Decimal,
+    pub total_long_term_gain: Decimal,
+}
+
+impl TaxReport {
+    /// Keep the disposals of `report` made during `year`, oldest first
+    pub fn from_lot_report(year: i32, report: &LotReport) -> Self {
+        let mut lots: Vec<TaxLot> = report
+            .disposals
+            .iter()
+            .filter(|d| d.disposed_at.year() == year)
+            .map(TaxLot::from)
+            .collect();
+        lots.sort_by(|a, b| (a.disposed_at, &a.asset).cmp(&(b.disposed_at, &b.asset)));
+
+        let term_gain = |term: HoldingTerm| lots.iter().filter(|l| l.term == term).map(|l| l.gain).sum();
+        Self {
+            year,
+            method: report.method,
+            total_proceeds: lots.iter().map(|l| l.proceeds).sum(),
+            total_cost_basis: lots.iter().map(|l| l.cost_basis).sum(),
+            total_short_term_gain: term_gain(HoldingTerm::Short),
+            total_long_term_gain: term_gain(HoldingTerm::Long),
+            lots,
+        }
+    }
+
+    pub fn total_gain(&self) -> Decimal {
+        self.total_short_term_gain + self.total_long_term_gain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::transaction::Transaction;
+    use chrono::TimeZone;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_holding_term() {
+        assert_eq!(HoldingTerm::from_days(Some(365)), HoldingTerm::Short);
+        assert_eq!(HoldingTerm::from_days(Some(366)), HoldingTerm::Long);
+        assert_eq!(HoldingTerm::from_days(None), HoldingTerm::Short);
+    }
+
+    #[test]
+    fn test_report_splits_partial_lots_by_term() {
+        let transactions = vec![
+            Transaction::new_buy("acc", "BTC", dec("1"), dec("20000"), date(2022, 6, 1)),
+            Transaction::new_buy("acc", "BTC", dec("1"), dec("40000"), date(2024, 1, 10)),
+            Transaction::new_sell("acc", "BTC", dec("0.5"), dec("30000"), date(2023, 3, 1)),
+            // Takes the rest of the 2022 lot and half of the 2024 one
+            Transaction::new_sell("acc", "BTC", dec("1"), dec("60000"), date(2024, 7, 1)),
+        ];
+        let lots = LotReport::from_transactions(CostBasisMethod::Fifo, &transactions);
+
+        let report = TaxReport::from_lot_report(2024, &lots);
+        assert_eq!(report.lots.len(), 2);
+
+        assert_eq!(report.lots[0].quantity, dec("0.5"));
+        assert_eq!(report.lots[0].term, HoldingTerm::Long);
+        assert_eq!(report.lots[0].gain, dec("20000"));
+
+        assert_eq!(report.lots[1].quantity, dec("0.5"));
+        assert_eq!(report.lots[1].term, HoldingTerm::Short);
+        assert_eq!(report.lots[1].gain, dec("10000"));
+
+        assert_eq!(report.total_proceeds, dec("60000"));
+        assert_eq!(report.total_cost_basis, dec("30000"));
+        assert_eq!(report.total_long_term_gain, dec("20000"));
+        assert_eq!(report.total_short_term_gain, dec("10000"));
+        assert_eq!(report.total_gain(), dec("30000"));
+
+        // The 2023 sale belongs to its own year
+        assert_eq!(TaxReport::from_lot_report(2023, &lots).total_short_term_gain, dec("5000"));
+        assert!(TaxReport::from_lot_report(2025, &lots).lots.is_empty());
+    }
+}
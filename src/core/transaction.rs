@@ -13,6 +13,7 @@ pub enum TransactionType {
     Swap,
     Receive,
     Fee,
+    StakingReward,
 }
 
 impl TransactionType {
@@ -26,6 +27,7 @@ impl TransactionType {
             TransactionType::Swap => "swap",
             TransactionType::Receive => "receive",
             TransactionType::Fee => "fee",
+            TransactionType::StakingReward => "staking_reward",
         }
     }
 
@@ -39,6 +41,7 @@ impl TransactionType {
             "swap" | "trade" => Some(TransactionType::Swap),
             "receive" | "airdrop" | "reward" => Some(TransactionType::Receive),
             "fee" => Some(TransactionType::Fee),
+            "staking_reward" | "stake_reward" | "staking" => Some(TransactionType::StakingReward),
             _ => None,
         }
     }
@@ -53,6 +56,7 @@ impl TransactionType {
             TransactionType::Swap => "Swap",
             TransactionType::Receive => "Receive",
             TransactionType::Fee => "Fee",
+            TransactionType::StakingReward => "Staking Reward",
         }
     }
 }
@@ -154,6 +158,62 @@ impl Transaction {
         }
     }
 
+    /// A standalone fee, such as gas for a DeFi interaction, paid out of `account_id`
+    pub fn new_fee(account_id: &str, asset: &str, amount: Decimal, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            id: 0,
+            tx_type: TransactionType::Fee,
+            from_account_id: Some(account_id.to_string()),
+            from_asset: Some(asset.to_string()),
+            from_quantity: Some(amount),
+            to_account_id: None,
+            to_asset: None,
+            to_quantity: None,
+            price_usd: None,
+            price_currency: None,
+            price_amount: None,
+            exchange_rate: None,
+            exchange_rate_pair: None,
+            fee: Some(amount),
+            fee_asset: Some(asset.to_string()),
+            external_id: None,
+            notes: None,
+            timestamp,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Yield from staking or an earn product, valued at `price_usd` when received
+    pub fn new_staking_reward(
+        account_id: &str,
+        asset: &str,
+        quantity: Decimal,
+        price_usd: Option<Decimal>,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: 0,
+            tx_type: TransactionType::StakingReward,
+            from_account_id: None,
+            from_asset: None,
+            from_quantity: None,
+            to_account_id: Some(account_id.to_string()),
+            to_asset: Some(asset.to_string()),
+            to_quantity: Some(quantity),
+            price_usd,
+            price_currency: None,
+            price_amount: None,
+            exchange_rate: None,
+            exchange_rate_pair: None,
+            fee: None,
+            fee_asset: None,
+            external_id: None,
+            notes: None,
+            timestamp,
+            created_at: Utc::now(),
+        }
+    }
+
     pub fn new_transfer(
         from_account_id: &str,
         to_account_id: &str,
@@ -215,3 +275,162 @@ impl Transaction {
         }
     }
 }
+
+/// A change to one holding's quantity; positive adds, negative removes
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoldingChange {
+    pub account_id: String,
+    pub asset: String,
+    pub quantity: Decimal,
+}
+
+impl Transaction {
+    /// Holding changes made when this transaction was recorded. Negate them
+    /// to undo the transaction. Transfer fees come out of what the destination
+    /// receives, matching `tx transfer`; buy and sell fees come out of the fee
+    /// asset's holding unless they were paid in USD, matching `tx buy`/`tx sell`.
+    pub fn holding_changes(&self) -> Vec<HoldingChange> {
+        let leg = |account_id: &Option<String>, asset: &Option<String>, quantity: Option<Decimal>| {
+            Some(HoldingChange {
+                account_id: account_id.clone()?,
+                asset: asset.as_ref()?.to_uppercase(),
+                quantity: quantity?,
+            })
+        };
+        let outgoing = leg(&self.from_account_id, &self.from_asset, self.from_quantity.map(|q| -q));
+        let incoming = leg(&self.to_account_id, &self.to_asset, self.to_quantity);
+
+        // A fee in the traded asset is folded into that leg, so undoing the
+        // trade never needs more than the holding has
+        let with_fee = |trade: Option<HoldingChange>| -> Vec<Option<HoldingChange>> {
+            let fee = match (self.fee, &self.fee_asset) {
+                (Some(fee), Some(fee_asset)) if !fee.is_zero() && !fee_asset.eq_ignore_ascii_case("USD") => {
+                    Some((fee, fee_asset.to_uppercase()))
+                }
+                _ => None,
+            };
+            match (trade, fee) {
+                (Some(mut trade), Some((fee, fee_asset))) if trade.asset == fee_asset => {
+                    trade.quantity -= fee;
+                    vec![Some(trade)]
+                }
+                (Some(trade), Some((fee, fee_asset))) => {
+                    let account_id = trade.account_id.clone();
+                    vec![Some(trade), Some(HoldingChange { account_id, asset: fee_asset, quantity: -fee })]
+                }
+                (trade, _) => vec![trade],
+            }
+        };
+
+        let changes = match self.tx_type {
+            TransactionType::Buy => with_fee(incoming),
+            TransactionType::Sell => with_fee(outgoing),
+            TransactionType::Receive | TransactionType::TransferIn | TransactionType::StakingReward => vec![incoming],
+            TransactionType::TransferOut | TransactionType::Fee => vec![outgoing],
+            TransactionType::Swap => vec![outgoing, incoming],
+            TransactionType::TransferInternal => {
+                let incoming = incoming.map(|mut change| {
+                    if let (Some(fee), Some(fee_asset)) = (self.fee, &self.fee_asset) {
+                        if fee_asset.eq_ignore_ascii_case(&change.asset) {
+                            change.quantity -= fee;
+                        }
+                    }
+                    change
+                });
+                vec![outgoing, incoming]
+            }
+        };
+
+        changes.into_iter().flatten().collect()
+    }
+}
+
+/// Cost per unit of an acquisition with its fee folded in:
+/// `(quantity * price + fee_usd) / quantity`
+pub fn all_in_cost_per_unit(quantity: Decimal, price_usd: Decimal, fee_usd: Option<Decimal>) -> Decimal {
+    let fee_usd = fee_usd.unwrap_or(Decimal::ZERO);
+    if quantity.is_zero() {
+        return price_usd;
+    }
+    (quantity * price_usd + fee_usd) / quantity
+}
+
+/// Quantity of the destination asset received for a swap at spot prices:
+/// `from_quantity * from_price / to_price`
+pub fn spot_swap_quantity(from_quantity: Decimal, from_price_usd: Decimal, to_price_usd: Decimal) -> Option<Decimal> {
+    if to_price_usd <= Decimal::ZERO {
+        return None;
+    }
+    Some(from_quantity * from_price_usd / to_price_usd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_all_in_cost_per_unit_with_fee() {
+        let qty = Decimal::from_str("0.5").unwrap();
+        let price = Decimal::from_str("40000").unwrap();
+        let fee = Decimal::from_str("20").unwrap();
+
+        // (0.5 * 40000 + 20) / 0.5 = 40040
+        assert_eq!(all_in_cost_per_unit(qty, price, Some(fee)), Decimal::from_str("40040").unwrap());
+    }
+
+    #[test]
+    fn test_all_in_cost_per_unit_without_fee() {
+        let qty = Decimal::from_str("2").unwrap();
+        let price = Decimal::from_str("3000").unwrap();
+
+        assert_eq!(all_in_cost_per_unit(qty, price, None), price);
+        assert_eq!(all_in_cost_per_unit(Decimal::ZERO, price, Some(Decimal::ONE)), price);
+    }
+
+    #[test]
+    fn test_spot_swap_quantity() {
+        let from_qty = Decimal::from_str("1").unwrap();
+        let eth = Decimal::from_str("3000").unwrap();
+        let btc = Decimal::from_str("60000").unwrap();
+
+        assert_eq!(spot_swap_quantity(from_qty, eth, btc), Some(Decimal::from_str("0.05").unwrap()));
+        assert_eq!(spot_swap_quantity(from_qty, eth, Decimal::ZERO), None);
+    }
+
+    #[test]
+    fn test_holding_changes() {
+        let d = |s: &str| Decimal::from_str(s).unwrap();
+        let change = |account: &str, asset: &str, quantity: &str| HoldingChange {
+            account_id: account.to_string(),
+            asset: asset.to_string(),
+            quantity: d(quantity),
+        };
+
+        let mut buy = Transaction::new_buy("a", "btc", d("0.5"), d("40000"), Utc::now());
+        assert_eq!(buy.holding_changes(), vec![change("a", "BTC", "0.5")]);
+
+        // Fees come out of the fee asset, or out of the trade when paid in the traded asset
+        buy.fee = Some(d("0.01"));
+        buy.fee_asset = Some("bnb".to_string());
+        assert_eq!(buy.holding_changes(), vec![change("a", "BTC", "0.5"), change("a", "BNB", "-0.01")]);
+        buy.fee_asset = Some("BTC".to_string());
+        assert_eq!(buy.holding_changes(), vec![change("a", "BTC", "0.49")]);
+        buy.fee_asset = Some("USD".to_string());
+        assert_eq!(buy.holding_changes(), vec![change("a", "BTC", "0.5")]);
+
+        let mut sell = Transaction::new_sell("a", "ETH", d("1"), d("3000"), Utc::now());
+        sell.fee = Some(d("0.001"));
+        sell.fee_asset = Some("ETH".to_string());
+        assert_eq!(sell.holding_changes(), vec![change("a", "ETH", "-1.001")]);
+
+        let swap = Transaction::new_swap("a", "ETH", d("1"), "SOL", d("20"), Utc::now());
+        assert_eq!(swap.holding_changes(), vec![change("a", "ETH", "-1"), change("a", "SOL", "20")]);
+
+        // The destination of a transfer receives the quantity less the fee
+        let mut transfer = Transaction::new_transfer("a", "b", "BTC", d("1"), Utc::now());
+        transfer.fee = Some(d("0.001"));
+        transfer.fee_asset = Some("BTC".to_string());
+        assert_eq!(transfer.holding_changes(), vec![change("a", "BTC", "-1"), change("b", "BTC", "0.999")]);
+    }
+}
@@ -1,9 +1,20 @@
 use chrono::{DateTime, Utc};
-use sqlx::SqlitePool;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
 
-use crate::core::account::{Account, AccountConfig, AccountType, Category, WalletAddress};
+use crate::core::account::{closest_name, Account, AccountConfig, AccountType, Category, WalletAddress};
 use crate::error::{CryptofolioError, Result};
 
+/// Conditions for [`AccountRepository::list_accounts`]; unset fields match every account
+#[derive(Debug, Clone, Default)]
+pub struct ListAccountsFilter {
+    /// Any of these types
+    pub account_types: Vec<AccountType>,
+    pub category_id: Option<String>,
+    pub sync_enabled: Option<bool>,
+    /// This name, ignoring case
+    pub name: Option<String>,
+}
+
 pub struct AccountRepository<'a> {
     pool: &'a SqlitePool,
 }
@@ -37,11 +48,16 @@ impl<'a> AccountRepository<'a> {
     }
 
     pub async fn get_category(&self, id: &str) -> Result<Option<Category>> {
+        Self::get_category_in(&mut *self.pool.acquire().await?, id).await
+    }
+
+    /// [`get_category`](Self::get_category) on `conn`
+    pub async fn get_category_in(conn: &mut SqliteConnection, id: &str) -> Result<Option<Category>> {
         let row = sqlx::query_as::<_, (String, String, i32, String)>(
             "SELECT id, name, sort_order, created_at FROM categories WHERE id = ?"
         )
         .bind(id)
-        .fetch_optional(self.pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         match row {
@@ -58,11 +74,16 @@ impl<'a> AccountRepository<'a> {
     }
 
     pub async fn get_category_by_name(&self, name: &str) -> Result<Option<Category>> {
+        Self::get_category_by_name_in(&mut *self.pool.acquire().await?, name).await
+    }
+
+    /// [`get_category_by_name`](Self::get_category_by_name) on `conn`
+    pub async fn get_category_by_name_in(conn: &mut SqliteConnection, name: &str) -> Result<Option<Category>> {
         let row = sqlx::query_as::<_, (String, String, i32, String)>(
             "SELECT id, name, sort_order, created_at FROM categories WHERE LOWER(name) = LOWER(?)"
         )
         .bind(name)
-        .fetch_optional(self.pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         match row {
@@ -79,10 +100,15 @@ impl<'a> AccountRepository<'a> {
     }
 
     pub async fn create_category(&self, id: &str, name: &str) -> Result<()> {
+        Self::create_category_in(&mut *self.pool.acquire().await?, id, name).await
+    }
+
+    /// [`create_category`](Self::create_category) on `conn`
+    pub async fn create_category_in(conn: &mut SqliteConnection, id: &str, name: &str) -> Result<()> {
         let max_order: Option<(i32,)> = sqlx::query_as(
             "SELECT MAX(sort_order) FROM categories"
         )
-        .fetch_optional(self.pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         let sort_order = max_order.map(|(o,)| o + 1).unwrap_or(1);
@@ -93,7 +119,7 @@ impl<'a> AccountRepository<'a> {
         .bind(id)
         .bind(name)
         .bind(sort_order)
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await?;
 
         Ok(())
@@ -115,6 +141,23 @@ impl<'a> AccountRepository<'a> {
         Ok(())
     }
 
+    /// Set a category's position in listings; lower values come first
+    pub async fn set_category_order(&self, name: &str, order: i32) -> Result<()> {
+        let result = sqlx::query(
+            "UPDATE categories SET sort_order = ? WHERE LOWER(name) = LOWER(?)"
+        )
+        .bind(order)
+        .bind(name)
+        .execute(self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(CryptofolioError::CategoryNotFound(name.to_string()));
+        }
+
+        Ok(())
+    }
+
     pub async fn delete_category(&self, name: &str) -> Result<()> {
         let result = sqlx::query(
             "DELETE FROM categories WHERE LOWER(name) = LOWER(?)"
@@ -132,12 +175,35 @@ impl<'a> AccountRepository<'a> {
 
     // === Accounts ===
 
-    pub async fn list_accounts(&self) -> Result<Vec<Account>> {
-        let rows = sqlx::query_as::<_, (String, String, String, String, Option<String>, bool, String)>(
-            "SELECT id, name, category_id, account_type, config, sync_enabled, created_at FROM accounts ORDER BY name"
-        )
-        .fetch_all(self.pool)
-        .await?;
+    /// List accounts matching `filter`, by name
+    pub async fn list_accounts(&self, filter: &ListAccountsFilter) -> Result<Vec<Account>> {
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT id, name, category_id, account_type, config, sync_enabled, created_at FROM accounts WHERE 1 = 1",
+        );
+
+        if !filter.account_types.is_empty() {
+            query.push(" AND account_type IN (");
+            let mut types = query.separated(", ");
+            for account_type in &filter.account_types {
+                types.push_bind(account_type.as_str());
+            }
+            query.push(")");
+        }
+        if let Some(category_id) = &filter.category_id {
+            query.push(" AND category_id = ").push_bind(category_id.clone());
+        }
+        if let Some(sync_enabled) = filter.sync_enabled {
+            query.push(" AND sync_enabled = ").push_bind(sync_enabled);
+        }
+        if let Some(name) = &filter.name {
+            query.push(" AND LOWER(name) = LOWER(").push_bind(name.clone()).push(")");
+        }
+        query.push(" ORDER BY name");
+
+        let rows = query
+            .build_query_as::<(String, String, String, String, Option<String>, bool, String)>()
+            .fetch_all(self.pool)
+            .await?;
 
         rows.into_iter()
             .map(|(id, name, category_id, account_type, config, sync_enabled, created_at)| {
@@ -165,11 +231,16 @@ impl<'a> AccountRepository<'a> {
     }
 
     pub async fn get_account(&self, name: &str) -> Result<Option<Account>> {
+        Self::get_account_in(&mut *self.pool.acquire().await?, name).await
+    }
+
+    /// [`get_account`](Self::get_account) on `conn`
+    pub async fn get_account_in(conn: &mut SqliteConnection, name: &str) -> Result<Option<Account>> {
         let row = sqlx::query_as::<_, (String, String, String, String, Option<String>, bool, String)>(
             "SELECT id, name, category_id, account_type, config, sync_enabled, created_at FROM accounts WHERE LOWER(name) = LOWER(?)"
         )
         .bind(name)
-        .fetch_optional(self.pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         match row {
@@ -198,12 +269,31 @@ impl<'a> AccountRepository<'a> {
         }
     }
 
+    /// Get an account by name, failing with a "did you mean" suggestion if it does not exist
+    pub async fn require_account(&self, name: &str) -> Result<Account> {
+        if let Some(account) = self.get_account(name).await? {
+            return Ok(account);
+        }
+
+        let names: Vec<String> = self.list_accounts(&ListAccountsFilter::default()).await?.into_iter().map(|a| a.name).collect();
+
+        Err(CryptofolioError::AccountNotFound {
+            name: name.to_string(),
+            suggestion: closest_name(name, &names),
+        })
+    }
+
     pub async fn get_account_by_id(&self, id: &str) -> Result<Option<Account>> {
+        Self::get_account_by_id_in(&mut *self.pool.acquire().await?, id).await
+    }
+
+    /// [`get_account_by_id`](Self::get_account_by_id) on `conn`
+    pub async fn get_account_by_id_in(conn: &mut SqliteConnection, id: &str) -> Result<Option<Account>> {
         let row = sqlx::query_as::<_, (String, String, String, String, Option<String>, bool, String)>(
             "SELECT id, name, category_id, account_type, config, sync_enabled, created_at FROM accounts WHERE id = ?"
         )
         .bind(id)
-        .fetch_optional(self.pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         match row {
@@ -233,6 +323,11 @@ impl<'a> AccountRepository<'a> {
     }
 
     pub async fn create_account(&self, account: &Account) -> Result<()> {
+        Self::create_account_in(&mut *self.pool.acquire().await?, account).await
+    }
+
+    /// [`create_account`](Self::create_account) on `conn`
+    pub async fn create_account_in(conn: &mut SqliteConnection, account: &Account) -> Result<()> {
         let config_json = serde_json::to_string(&account.config)?;
 
         sqlx::query(
@@ -244,16 +339,63 @@ impl<'a> AccountRepository<'a> {
         .bind(account.account_type.as_str())
         .bind(&config_json)
         .bind(account.sync_enabled)
-        .execute(self.pool)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Change an account's display name. Holdings, transactions and addresses
+    /// reference the account by id, so nothing else needs to move.
+    pub async fn update_account_name(&self, old: &str, new_name: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let id: Option<String> = sqlx::query_scalar("SELECT id FROM accounts WHERE LOWER(name) = LOWER(?)")
+            .bind(old)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Some(id) = id else {
+            drop(tx);
+            return self.require_account(old).await.map(|_| ());
+        };
+
+        // A case-only rename of the same account is fine
+        let taken: Option<String> = sqlx::query_scalar(
+            "SELECT id FROM accounts WHERE LOWER(name) = LOWER(?) AND id != ?"
+        )
+        .bind(new_name)
+        .bind(&id)
+        .fetch_optional(&mut *tx)
         .await?;
+        if taken.is_some() {
+            return Err(CryptofolioError::AccountAlreadyExists(new_name.to_string()));
+        }
+
+        sqlx::query("UPDATE accounts SET name = ? WHERE id = ?")
+            .bind(new_name)
+            .bind(&id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn update_config(&self, account_id: &str, config: &AccountConfig) -> Result<()> {
+        let config_json = serde_json::to_string(config)?;
+
+        sqlx::query("UPDATE accounts SET config = ? WHERE id = ?")
+            .bind(&config_json)
+            .bind(account_id)
+            .execute(self.pool)
+            .await?;
 
         Ok(())
     }
 
     pub async fn delete_account(&self, name: &str) -> Result<()> {
         // First, get the account ID
-        let account = self.get_account(name).await?
-            .ok_or_else(|| CryptofolioError::AccountNotFound(name.to_string()))?;
+        let account = self.require_account(name).await?;
 
         // Delete related records in order (respecting foreign keys)
         // 1. Delete wallet addresses
@@ -262,7 +404,12 @@ impl<'a> AccountRepository<'a> {
             .execute(self.pool)
             .await?;
 
-        // 2. Delete holdings
+        // 2. Delete holdings and their tags
+        sqlx::query("DELETE FROM holding_tags WHERE holding_id IN (SELECT id FROM holdings WHERE account_id = ?)")
+            .bind(&account.id)
+            .execute(self.pool)
+            .await?;
+
         sqlx::query("DELETE FROM holdings WHERE account_id = ?")
             .bind(&account.id)
             .execute(self.pool)
@@ -275,7 +422,13 @@ impl<'a> AccountRepository<'a> {
             .execute(self.pool)
             .await?;
 
-        // 4. Finally delete the account
+        // 4. Delete sync history
+        sqlx::query("DELETE FROM sync_log WHERE account_id = ?")
+            .bind(&account.id)
+            .execute(self.pool)
+            .await?;
+
+        // 5. Finally delete the account
         sqlx::query("DELETE FROM accounts WHERE id = ?")
             .bind(&account.id)
             .execute(self.pool)
@@ -287,11 +440,16 @@ impl<'a> AccountRepository<'a> {
     // === Wallet Addresses ===
 
     pub async fn list_addresses(&self, account_id: &str) -> Result<Vec<WalletAddress>> {
+        Self::list_addresses_in(&mut *self.pool.acquire().await?, account_id).await
+    }
+
+    /// [`list_addresses`](Self::list_addresses) on `conn`
+    pub async fn list_addresses_in(conn: &mut SqliteConnection, account_id: &str) -> Result<Vec<WalletAddress>> {
         let rows = sqlx::query_as::<_, (i64, String, String, String, Option<String>, String)>(
             "SELECT id, account_id, blockchain, address, label, created_at FROM wallet_addresses WHERE account_id = ? ORDER BY blockchain"
         )
         .bind(account_id)
-        .fetch_all(self.pool)
+        .fetch_all(&mut *conn)
         .await?;
 
         rows.into_iter()
@@ -310,8 +468,16 @@ impl<'a> AccountRepository<'a> {
             .collect()
     }
 
-    pub async fn add_address(
-        &self,
+    pub async fn add_address(&self, account_id: &str,
+        blockchain: &str,
+        address: &str,
+        label: Option<&str>) -> Result<()> {
+        Self::add_address_in(&mut *self.pool.acquire().await?, account_id, blockchain, address, label).await
+    }
+
+    /// [`add_address`](Self::add_address) on `conn`
+    pub async fn add_address_in(
+        conn: &mut SqliteConnection,
         account_id: &str,
         blockchain: &str,
         address: &str,
@@ -324,7 +490,7 @@ impl<'a> AccountRepository<'a> {
         .bind(blockchain)
         .bind(address)
         .bind(label)
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await?;
 
         Ok(())
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::SqlitePool;
+use std::str::FromStr;
+
+use crate::error::{CryptofolioError, Result};
+
+/// Which side of the threshold sets an alert off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertDirection {
+    Above,
+    Below,
+}
+
+impl AlertDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertDirection::Above => "above",
+            AlertDirection::Below => "below",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "above" => Some(AlertDirection::Above),
+            "below" => Some(AlertDirection::Below),
+            _ => None,
+        }
+    }
+}
+
+/// A price threshold that runs a command the first time it is crossed
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub id: i64,
+    pub asset: String,
+    pub direction: AlertDirection,
+    pub threshold: Decimal,
+    /// Shell command to run; None uses the platform's default notification
+    pub command: Option<String>,
+    pub triggered_at: Option<DateTime<Utc>>,
+    pub active: bool,
+}
+
+impl Alert {
+    /// Whether `price` is on the alerting side of the threshold (reaching it counts)
+    pub fn is_crossed(&self, price: Decimal) -> bool {
+        match self.direction {
+            AlertDirection::Above => price >= self.threshold,
+            AlertDirection::Below => price <= self.threshold,
+        }
+    }
+}
+
+pub struct AlertRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> AlertRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn insert(
+        &self,
+        asset: &str,
+        direction: AlertDirection,
+        threshold: Decimal,
+        command: Option<&str>,
+    ) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO alerts (asset, direction, threshold, command) VALUES (UPPER(?), ?, ?, ?)"
+        )
+        .bind(asset)
+        .bind(direction.as_str())
+        .bind(threshold.to_string())
+        .bind(command)
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Every alert, or only those not yet triggered, by id
+    pub async fn list(&self, active_only: bool) -> Result<Vec<Alert>> {
+        let rows = sqlx::query_as::<_, AlertRow>(
+            r#"
+            SELECT id, asset, direction, threshold, command, triggered_at, active
+            FROM alerts
+            WHERE active = 1 OR ? = 0
+            ORDER BY id
+            "#
+        )
+        .bind(active_only)
+        .fetch_all(self.pool)
+        .await?;
+
+        rows.into_iter().map(Alert::try_from).collect()
+    }
+
+    /// Record that an alert went off; it stays listed but is no longer checked
+    pub async fn mark_triggered(&self, id: i64, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE alerts SET triggered_at = ?, active = 0 WHERE id = ?")
+            .bind(at.to_rfc3339())
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: i64) -> Result<()> {
+        let result = sqlx::query("DELETE FROM alerts WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(CryptofolioError::NotFound(format!("Alert #{}", id)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AlertRow {
+    id: i64,
+    asset: String,
+    direction: String,
+    threshold: String,
+    command: Option<String>,
+    triggered_at: Option<String>,
+    active: bool,
+}
+
+impl TryFrom<AlertRow> for Alert {
+    type Error = CryptofolioError;
+
+    fn try_from(row: AlertRow) -> Result<Self> {
+        Ok(Alert {
+            id: row.id,
+            asset: row.asset,
+            direction: AlertDirection::parse(&row.direction)
+                .ok_or_else(|| CryptofolioError::Other(format!("Invalid alert direction: {}", row.direction)))?,
+            threshold: Decimal::from_str(&row.threshold)
+                .map_err(|_| CryptofolioError::Other(format!("Invalid alert threshold: {}", row.threshold)))?,
+            command: row.command,
+            triggered_at: row
+                .triggered_at
+                .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+                .map(|t| t.with_timezone(&Utc)),
+            active: row.active,
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+
+use crate::error::{CryptofolioError, Result};
+use crate::market::AssetMetadata;
+
+/// Rows per INSERT, keeping the bind count well under SQLite's limit
+const INSERT_BATCH_SIZE: usize = 500;
+
+pub struct AssetMetadataRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> AssetMetadataRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Replace the cached metadata with a freshly downloaded coin list
+    pub async fn replace_all(&self, metadata: &[AssetMetadata]) -> Result<()> {
+        let updated_at = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM asset_metadata").execute(&mut *tx).await?;
+
+        for batch in metadata.chunks(INSERT_BATCH_SIZE) {
+            let mut query: QueryBuilder<Sqlite> =
+                QueryBuilder::new("INSERT OR IGNORE INTO asset_metadata (symbol, name, coingecko_id, updated_at) ");
+            query.push_values(batch, |mut row, m| {
+                row.push_bind(m.symbol.to_uppercase())
+                    .push_bind(&m.name)
+                    .push_bind(&m.coingecko_id)
+                    .push_bind(&updated_at);
+            });
+            query.build().execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// All cached coins using a ticker symbol
+    pub async fn find_by_symbol(&self, symbol: &str) -> Result<Vec<AssetMetadata>> {
+        let rows = sqlx::query_as::<_, AssetMetadataRow>(
+            "SELECT symbol, name, coingecko_id FROM asset_metadata WHERE symbol = ? ORDER BY coin_id"
+        )
+        .bind(symbol.to_uppercase())
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| AssetMetadata {
+                symbol: r.symbol,
+                name: r.name,
+                coingecko_id: r.coingecko_id,
+            })
+            .collect())
+    }
+
+    /// When the cache was last refreshed, or `None` if it is empty
+    pub async fn last_updated(&self) -> Result<Option<DateTime<Utc>>> {
+        let updated_at: Option<String> = sqlx::query_scalar("SELECT MAX(updated_at) FROM asset_metadata")
+            .fetch_one(self.pool)
+            .await?;
+
+        updated_at
+            .map(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|_| CryptofolioError::Other(format!("Invalid metadata timestamp: {}", s)))
+            })
+            .transpose()
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AssetMetadataRow {
+    symbol: String,
+    name: String,
+    coingecko_id: String,
+}
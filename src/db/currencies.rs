@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
 
 use crate::core::currency::{AssetType, Currency, ExchangeRate};
 use crate::error::Result;
@@ -174,6 +174,11 @@ pub async fn currency_exists(pool: &SqlitePool, code: &str) -> Result<bool> {
 
 /// Add or update an exchange rate
 pub async fn add_exchange_rate(pool: &SqlitePool, rate: &ExchangeRate) -> Result<i64> {
+    add_exchange_rate_in(&mut *pool.acquire().await?, rate).await
+}
+
+/// [`add_exchange_rate`] on `conn`
+pub async fn add_exchange_rate_in(conn: &mut SqliteConnection, rate: &ExchangeRate) -> Result<i64> {
     let rate_str = rate.rate.to_string();
     let timestamp = rate.timestamp.to_rfc3339();
     let created_at = rate.created_at.to_rfc3339();
@@ -195,7 +200,7 @@ pub async fn add_exchange_rate(pool: &SqlitePool, rate: &ExchangeRate) -> Result
         rate.notes,
         created_at
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
     Ok(result.last_insert_rowid())
@@ -289,48 +294,86 @@ pub async fn get_exchange_rate_at_time(
     }))
 }
 
-/// List all exchange rates for a currency pair
+/// USD price of one unit of `code` from the latest stored rate against USD,
+/// in either direction
+pub async fn get_usd_price(pool: &SqlitePool, code: &str) -> Result<Option<Decimal>> {
+    let code = code.to_uppercase();
+    if let Some(rate) = get_latest_exchange_rate(pool, &code, "USD").await? {
+        return Ok(rate.price_of(&code));
+    }
+    if let Some(rate) = get_latest_exchange_rate(pool, "USD", &code).await? {
+        return Ok(rate.price_of(&code));
+    }
+    Ok(None)
+}
+
+/// List exchange rates for a currency pair, newest first, optionally only
+/// those at or after `since` and at most `limit` of them
 pub async fn list_exchange_rates(
     pool: &SqlitePool,
     from_currency: &str,
     to_currency: &str,
+    since: Option<DateTime<Utc>>,
+    limit: Option<i64>,
 ) -> Result<Vec<ExchangeRate>> {
-    let rows = sqlx::query!(
+    let since = since.map(|dt| dt.to_rfc3339());
+
+    // A negative LIMIT means no limit in SQLite
+    let rows = sqlx::query_as::<_, ExchangeRateRow>(
         r#"
-        SELECT id as "id!", from_currency as "from_currency!",
-               to_currency as "to_currency!", rate as "rate!",
-               timestamp as "timestamp: String",
-               source, notes,
-               created_at as "created_at: String"
+        SELECT id, from_currency, to_currency, rate, timestamp, source, notes, created_at
         FROM exchange_rates
-        WHERE from_currency = ? AND to_currency = ?
+        WHERE from_currency = ? AND to_currency = ? AND (? IS NULL OR timestamp >= ?)
         ORDER BY timestamp DESC
-        "#,
-        from_currency,
-        to_currency
+        LIMIT ?
+        "#
     )
+    .bind(from_currency)
+    .bind(to_currency)
+    .bind(&since)
+    .bind(&since)
+    .bind(limit.unwrap_or(-1))
     .fetch_all(pool)
     .await?;
 
-    let rates = rows
-        .into_iter()
-        .map(|row| ExchangeRate {
-            id: row.id,
-            from_currency: row.from_currency,
-            to_currency: row.to_currency,
-            rate: row.rate.parse().unwrap_or_else(|_| Decimal::ZERO),
-            timestamp: DateTime::parse_from_rfc3339(&row.timestamp)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
-            source: row.source.unwrap_or_else(|| "manual".to_string()),
-            notes: row.notes,
-            created_at: row.created_at
-                .as_deref()
-                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(Utc::now),
-        })
-        .collect();
+    Ok(rows.into_iter().map(exchange_rate_from_row).collect())
+}
+
+/// List every stored exchange rate, oldest first
+pub async fn list_all_exchange_rates(pool: &SqlitePool) -> Result<Vec<ExchangeRate>> {
+    let rows = sqlx::query_as::<_, ExchangeRateRow>(
+        r#"
+        SELECT id, from_currency, to_currency, rate, timestamp, source, notes, created_at
+        FROM exchange_rates
+        ORDER BY timestamp ASC, id ASC
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
 
-    Ok(rates)
+    Ok(rows.into_iter().map(exchange_rate_from_row).collect())
+}
+
+/// `id, from_currency, to_currency, rate, timestamp, source, notes, created_at`
+type ExchangeRateRow = (i64, String, String, String, String, Option<String>, Option<String>, Option<String>);
+
+fn exchange_rate_from_row(row: ExchangeRateRow) -> ExchangeRate {
+    let (id, from_currency, to_currency, rate, timestamp, source, notes, created_at) = row;
+
+    ExchangeRate {
+        id,
+        from_currency,
+        to_currency,
+        rate: rate.parse().unwrap_or(Decimal::ZERO),
+        timestamp: DateTime::parse_from_rfc3339(&timestamp)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+        source: source.unwrap_or_else(|| "manual".to_string()),
+        notes,
+        created_at: created_at
+            .as_deref()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(Utc::now),
+    }
 }
@@ -0,0 +1,81 @@
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::Result;
+
+pub struct HoldingTagRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> HoldingTagRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Tag a holding, ignoring tags it already has. Returns how many were new.
+    pub async fn add(&self, holding_id: i64, tags: &[String]) -> Result<u64> {
+        let mut added = 0;
+        for tag in tags {
+            let result = sqlx::query("INSERT OR IGNORE INTO holding_tags (holding_id, tag) VALUES (?, ?)")
+                .bind(holding_id)
+                .bind(tag)
+                .execute(self.pool)
+                .await?;
+            added += result.rows_affected();
+        }
+
+        Ok(added)
+    }
+
+    /// Remove tags from a holding. Returns how many were removed.
+    pub async fn remove(&self, holding_id: i64, tags: &[String]) -> Result<u64> {
+        let mut removed = 0;
+        for tag in tags {
+            let result = sqlx::query("DELETE FROM holding_tags WHERE holding_id = ? AND tag = ?")
+                .bind(holding_id)
+                .bind(tag)
+                .execute(self.pool)
+                .await?;
+            removed += result.rows_affected();
+        }
+
+        Ok(removed)
+    }
+
+    pub async fn list_for_holding(&self, holding_id: i64) -> Result<Vec<String>> {
+        let rows = sqlx::query_as::<_, (String,)>(
+            "SELECT tag FROM holding_tags WHERE holding_id = ? ORDER BY tag"
+        )
+        .bind(holding_id)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|(tag,)| tag).collect())
+    }
+
+    /// Every holding's tags, sorted, keyed by holding id
+    pub async fn list_all(&self) -> Result<HashMap<i64, Vec<String>>> {
+        let rows = sqlx::query_as::<_, (i64, String)>(
+            "SELECT holding_id, tag FROM holding_tags ORDER BY holding_id, tag"
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        for (holding_id, tag) in rows {
+            tags.entry(holding_id).or_default().push(tag);
+        }
+
+        Ok(tags)
+    }
+
+    /// Ids of the holdings carrying `tag`
+    pub async fn holding_ids_with_tag(&self, tag: &str) -> Result<HashSet<i64>> {
+        let rows = sqlx::query_as::<_, (i64,)>("SELECT holding_id FROM holding_tags WHERE tag = ?")
+            .bind(tag)
+            .fetch_all(self.pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+}
@@ -1,11 +1,18 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
 use std::str::FromStr;
 
-use crate::core::holdings::Holding;
+use crate::core::currency::AssetType;
+use crate::core::holdings::{
+    build_holding_history, Holding, HoldingChangeSource, HoldingHistoryEntry, RecordedHoldingChange,
+};
+use crate::db::transactions::{TransactionFilter, TransactionRepository};
 use crate::error::{CryptofolioError, Result};
 
+/// A holdings row: id, account_id, asset, quantity, avg_cost_basis, updated_at, acquired_at
+type HoldingRow = (i64, String, String, String, Option<String>, String, Option<String>);
+
 pub struct HoldingRepository<'a> {
     pool: &'a SqlitePool,
 }
@@ -16,60 +23,172 @@ impl<'a> HoldingRepository<'a> {
     }
 
     pub async fn list_all(&self) -> Result<Vec<Holding>> {
-        let rows = sqlx::query_as::<_, (i64, String, String, String, Option<String>, String)>(
-            "SELECT id, account_id, asset, quantity, avg_cost_basis, updated_at FROM holdings ORDER BY asset"
+        let rows = sqlx::query_as::<_, HoldingRow>(
+            "SELECT id, account_id, asset, quantity, avg_cost_basis, updated_at, acquired_at FROM holdings ORDER BY asset"
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Self::parse_holdings(rows)
+    }
+
+    /// Holdings whose account no longer exists
+    pub async fn find_orphans(&self) -> Result<Vec<Holding>> {
+        let rows = sqlx::query_as::<_, HoldingRow>(
+            "SELECT id, account_id, asset, quantity, avg_cost_basis, updated_at, acquired_at FROM holdings WHERE account_id IS NULL OR account_id NOT IN (SELECT id FROM accounts) ORDER BY account_id, asset"
         )
         .fetch_all(self.pool)
         .await?;
 
-        self.parse_holdings(rows)
+        Self::parse_holdings(rows)
+    }
+
+    /// Holdings with a quantity below zero, which only an accounting error produces
+    pub async fn find_negative(&self) -> Result<Vec<Holding>> {
+        Ok(self
+            .list_all()
+            .await?
+            .into_iter()
+            .filter(|h| h.quantity < Decimal::ZERO)
+            .collect())
+    }
+
+    /// Move a holding to another account, adding it to that account's holding
+    /// of the same asset (at the weighted-average cost) if there is one
+    pub async fn move_to_account(&self, holding: &Holding, account_id: &str) -> Result<()> {
+        match self.get(account_id, &holding.asset).await? {
+            Some(existing) => {
+                self.upsert(&existing.merged_with(holding)).await?;
+                sqlx::query("DELETE FROM holding_tags WHERE holding_id = ?")
+                    .bind(holding.id)
+                    .execute(self.pool)
+                    .await?;
+                sqlx::query("DELETE FROM holdings WHERE id = ?")
+                    .bind(holding.id)
+                    .execute(self.pool)
+                    .await?;
+            }
+            None => {
+                sqlx::query("UPDATE holdings SET account_id = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+                    .bind(account_id)
+                    .bind(holding.id)
+                    .execute(self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Distinct held asset symbols, uppercased and sorted
+    pub async fn list_assets(&self) -> Result<Vec<String>> {
+        let rows = sqlx::query_as::<_, (String,)>("SELECT DISTINCT UPPER(asset) FROM holdings ORDER BY 1")
+            .fetch_all(self.pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|(asset,)| asset).collect())
     }
 
     pub async fn list_by_account(&self, account_id: &str) -> Result<Vec<Holding>> {
-        let rows = sqlx::query_as::<_, (i64, String, String, String, Option<String>, String)>(
-            "SELECT id, account_id, asset, quantity, avg_cost_basis, updated_at FROM holdings WHERE account_id = ? ORDER BY asset"
+        let rows = sqlx::query_as::<_, HoldingRow>(
+            "SELECT id, account_id, asset, quantity, avg_cost_basis, updated_at, acquired_at FROM holdings WHERE account_id = ? ORDER BY asset"
+        )
+        .bind(account_id)
+        .fetch_all(self.pool)
+        .await?;
+
+        Self::parse_holdings(rows)
+    }
+
+    /// List an account's holdings of one asset type. Assets missing from the
+    /// currencies table count as crypto.
+    pub async fn list_by_account_and_asset_type(&self, account_id: &str, asset_type: &AssetType) -> Result<Vec<Holding>> {
+        let rows = sqlx::query_as::<_, HoldingRow>(
+            r#"
+            SELECT h.id, h.account_id, h.asset, h.quantity, h.avg_cost_basis, h.updated_at, h.acquired_at
+            FROM holdings h
+            LEFT JOIN currencies c ON UPPER(c.code) = UPPER(h.asset)
+            WHERE h.account_id = ? AND COALESCE(c.asset_type, 'crypto') = ?
+            ORDER BY h.asset
+            "#
         )
         .bind(account_id)
+        .bind(asset_type.as_str())
         .fetch_all(self.pool)
         .await?;
 
-        self.parse_holdings(rows)
+        Self::parse_holdings(rows)
+    }
+
+    /// List holdings across a subset of accounts
+    pub async fn list_by_accounts(&self, account_ids: &[String]) -> Result<Vec<Holding>> {
+        if account_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = vec!["?"; account_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT id, account_id, asset, quantity, avg_cost_basis, updated_at, acquired_at FROM holdings WHERE account_id IN ({}) ORDER BY asset",
+            placeholders
+        );
+
+        let mut query = sqlx::query_as::<_, HoldingRow>(&sql);
+        for id in account_ids {
+            query = query.bind(id);
+        }
+
+        let rows = query.fetch_all(self.pool).await?;
+
+        Self::parse_holdings(rows)
     }
 
     pub async fn get(&self, account_id: &str, asset: &str) -> Result<Option<Holding>> {
-        let row = sqlx::query_as::<_, (i64, String, String, String, Option<String>, String)>(
-            "SELECT id, account_id, asset, quantity, avg_cost_basis, updated_at FROM holdings WHERE account_id = ? AND UPPER(asset) = UPPER(?)"
+        Self::get_in(&mut *self.pool.acquire().await?, account_id, asset).await
+    }
+
+    /// [`get`](Self::get) on `conn`, e.g. inside a transaction shared with other writes
+    pub async fn get_in(conn: &mut SqliteConnection, account_id: &str, asset: &str) -> Result<Option<Holding>> {
+        let row = sqlx::query_as::<_, HoldingRow>(
+            "SELECT id, account_id, asset, quantity, avg_cost_basis, updated_at, acquired_at FROM holdings WHERE account_id = ? AND UPPER(asset) = UPPER(?)"
         )
         .bind(account_id)
         .bind(asset)
-        .fetch_optional(self.pool)
+        .fetch_optional(conn)
         .await?;
 
         match row {
-            Some(r) => Ok(Some(self.parse_holding(r)?)),
+            Some(r) => Ok(Some(Self::parse_holding(r)?)),
             None => Ok(None),
         }
     }
 
+    /// Insert or update a holding. An unset acquisition date keeps the stored one.
     pub async fn upsert(&self, holding: &Holding) -> Result<()> {
+        Self::upsert_in(&mut *self.pool.acquire().await?, holding).await
+    }
+
+    /// [`upsert`](Self::upsert) on `conn`
+    pub async fn upsert_in(conn: &mut SqliteConnection, holding: &Holding) -> Result<()> {
         let quantity_str = holding.quantity.to_string();
         let cost_basis_str = holding.avg_cost_basis.map(|d| d.to_string());
 
         sqlx::query(
             r#"
-            INSERT INTO holdings (account_id, asset, quantity, avg_cost_basis, updated_at)
-            VALUES (?, UPPER(?), ?, ?, CURRENT_TIMESTAMP)
+            INSERT INTO holdings (account_id, asset, quantity, avg_cost_basis, updated_at, acquired_at)
+            VALUES (?, UPPER(?), ?, ?, CURRENT_TIMESTAMP, ?)
             ON CONFLICT(account_id, asset) DO UPDATE SET
                 quantity = excluded.quantity,
                 avg_cost_basis = excluded.avg_cost_basis,
-                updated_at = CURRENT_TIMESTAMP
+                updated_at = CURRENT_TIMESTAMP,
+                acquired_at = COALESCE(excluded.acquired_at, holdings.acquired_at)
             "#
         )
         .bind(&holding.account_id)
         .bind(&holding.asset)
         .bind(&quantity_str)
         .bind(&cost_basis_str)
-        .execute(self.pool)
+        .bind(holding.acquired_at.map(|d| d.to_rfc3339()))
+        .execute(conn)
         .await?;
 
         Ok(())
@@ -81,11 +200,30 @@ impl<'a> HoldingRepository<'a> {
         asset: &str,
         quantity: Decimal,
         cost_per_unit: Option<Decimal>,
+        acquired_at: Option<DateTime<Utc>>,
     ) -> Result<()> {
-        let existing = self.get(account_id, asset).await?;
+        let mut conn = self.pool.acquire().await?;
+        Self::add_quantity_in(&mut conn, account_id, asset, quantity, cost_per_unit, acquired_at).await
+    }
+
+    /// [`add_quantity`](Self::add_quantity) on `conn`
+    pub async fn add_quantity_in(
+        conn: &mut SqliteConnection,
+        account_id: &str,
+        asset: &str,
+        quantity: Decimal,
+        cost_per_unit: Option<Decimal>,
+        acquired_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let existing = Self::get_in(conn, account_id, asset).await?;
 
         match existing {
             Some(mut holding) => {
+                // A holding was acquired when its earliest lot was
+                if let Some(at) = acquired_at {
+                    holding.acquired_at = Some(holding.acquired_at.map_or(at, |existing| existing.min(at)));
+                }
+
                 // Calculate new average cost basis if both old and new have cost basis
                 if let (Some(old_cost), Some(new_cost)) = (holding.avg_cost_basis, cost_per_unit) {
                     let old_total = old_cost * holding.quantity;
@@ -100,7 +238,7 @@ impl<'a> HoldingRepository<'a> {
                 }
 
                 holding.quantity += quantity;
-                self.upsert(&holding).await
+                Self::upsert_in(conn, &holding).await
             }
             None => {
                 let holding = Holding {
@@ -112,8 +250,9 @@ impl<'a> HoldingRepository<'a> {
                     cost_basis_currency: Some("USD".to_string()),
                     avg_cost_basis_base: cost_per_unit,
                     updated_at: Utc::now(),
+                    acquired_at: Some(acquired_at.unwrap_or_else(Utc::now)),
                 };
-                self.upsert(&holding).await
+                Self::upsert_in(conn, &holding).await
             }
         }
     }
@@ -124,7 +263,17 @@ impl<'a> HoldingRepository<'a> {
         asset: &str,
         quantity: Decimal,
     ) -> Result<()> {
-        let existing = self.get(account_id, asset).await?;
+        Self::remove_quantity_in(&mut *self.pool.acquire().await?, account_id, asset, quantity).await
+    }
+
+    /// [`remove_quantity`](Self::remove_quantity) on `conn`
+    pub async fn remove_quantity_in(
+        conn: &mut SqliteConnection,
+        account_id: &str,
+        asset: &str,
+        quantity: Decimal,
+    ) -> Result<()> {
+        let existing = Self::get_in(conn, account_id, asset).await?;
 
         match existing {
             Some(mut holding) => {
@@ -138,51 +287,155 @@ impl<'a> HoldingRepository<'a> {
                 holding.quantity -= quantity;
 
                 if holding.quantity == Decimal::ZERO {
-                    self.delete(account_id, asset).await
+                    Self::delete_in(conn, account_id, asset).await
                 } else {
-                    self.upsert(&holding).await
+                    Self::upsert_in(conn, &holding).await
                 }
             }
             None => Err(CryptofolioError::AssetNotFound(asset.to_string())),
         }
     }
 
+    /// Overwrite a holding's quantity. With `expected_quantity`, fail instead
+    /// if the stored quantity (zero when absent) no longer matches it. The
+    /// check is part of the write, so pass a quantity read from the holding.
     pub async fn set_quantity(
         &self,
         account_id: &str,
         asset: &str,
         quantity: Decimal,
         cost_per_unit: Option<Decimal>,
+        expected_quantity: Option<Decimal>,
     ) -> Result<()> {
-        if quantity == Decimal::ZERO {
-            // Delete if setting to zero
-            return self.delete(account_id, asset).await;
-        }
+        let Some(expected) = expected_quantity else {
+            if quantity == Decimal::ZERO {
+                // Delete if setting to zero
+                return self.delete(account_id, asset).await;
+            }
 
-        let holding = Holding {
-            id: 0,
-            account_id: account_id.to_string(),
-            asset: asset.to_uppercase(),
-            quantity,
-            avg_cost_basis: cost_per_unit,
-            cost_basis_currency: Some("USD".to_string()),
-            avg_cost_basis_base: cost_per_unit,
-            updated_at: Utc::now(),
+            let holding = Holding {
+                id: 0,
+                account_id: account_id.to_string(),
+                asset: asset.to_uppercase(),
+                quantity,
+                avg_cost_basis: cost_per_unit,
+                cost_basis_currency: Some("USD".to_string()),
+                avg_cost_basis_base: cost_per_unit,
+                updated_at: Utc::now(),
+                acquired_at: None,
+            };
+            return self.upsert(&holding).await;
         };
-        self.upsert(&holding).await
+
+        let written = match (expected.is_zero(), quantity.is_zero()) {
+            // Nothing is held and nothing should be: only check it stayed that way
+            (true, true) => self.get(account_id, asset).await?.is_none_or(|h| h.quantity.is_zero()),
+            // A new holding, unless one appeared in the meantime
+            (true, false) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO holdings (account_id, asset, quantity, avg_cost_basis, updated_at, acquired_at)
+                    VALUES (?, UPPER(?), ?, ?, CURRENT_TIMESTAMP, NULL)
+                    ON CONFLICT(account_id, asset) DO UPDATE SET
+                        quantity = excluded.quantity,
+                        avg_cost_basis = excluded.avg_cost_basis,
+                        updated_at = CURRENT_TIMESTAMP
+                    WHERE holdings.quantity = ?
+                    "#,
+                )
+                .bind(account_id)
+                .bind(asset)
+                .bind(quantity.to_string())
+                .bind(cost_per_unit.map(|d| d.to_string()))
+                .bind(expected.to_string())
+                .execute(self.pool)
+                .await?
+                .rows_affected()
+                    > 0
+            }
+            (false, true) => {
+                let mut tx = self.pool.begin().await?;
+                sqlx::query(
+                    "DELETE FROM holding_tags WHERE holding_id IN (SELECT id FROM holdings WHERE account_id = ? AND UPPER(asset) = UPPER(?) AND quantity = ?)"
+                )
+                .bind(account_id)
+                .bind(asset)
+                .bind(expected.to_string())
+                .execute(&mut *tx)
+                .await?;
+
+                let deleted = sqlx::query("DELETE FROM holdings WHERE account_id = ? AND UPPER(asset) = UPPER(?) AND quantity = ?")
+                    .bind(account_id)
+                    .bind(asset)
+                    .bind(expected.to_string())
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected()
+                    > 0;
+                if deleted {
+                    tx.commit().await?;
+                }
+                deleted
+            }
+            (false, false) => {
+                sqlx::query(
+                    r#"
+                    UPDATE holdings SET quantity = ?, avg_cost_basis = ?, updated_at = CURRENT_TIMESTAMP
+                    WHERE account_id = ? AND UPPER(asset) = UPPER(?) AND quantity = ?
+                    "#,
+                )
+                .bind(quantity.to_string())
+                .bind(cost_per_unit.map(|d| d.to_string()))
+                .bind(account_id)
+                .bind(asset)
+                .bind(expected.to_string())
+                .execute(self.pool)
+                .await?
+                .rows_affected()
+                    > 0
+            }
+        };
+
+        if !written {
+            let actual = self.get(account_id, asset).await?.map(|h| h.quantity).unwrap_or(Decimal::ZERO);
+            return Err(CryptofolioError::HoldingChanged {
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            });
+        }
+
+        Ok(())
     }
 
     pub async fn delete(&self, account_id: &str, asset: &str) -> Result<()> {
+        Self::delete_in(&mut *self.pool.acquire().await?, account_id, asset).await
+    }
+
+    /// [`delete`](Self::delete) on `conn`
+    pub async fn delete_in(conn: &mut SqliteConnection, account_id: &str, asset: &str) -> Result<()> {
+        sqlx::query(
+            "DELETE FROM holding_tags WHERE holding_id IN (SELECT id FROM holdings WHERE account_id = ? AND UPPER(asset) = UPPER(?))"
+        )
+        .bind(account_id)
+        .bind(asset)
+        .execute(&mut *conn)
+        .await?;
+
         sqlx::query("DELETE FROM holdings WHERE account_id = ? AND UPPER(asset) = UPPER(?)")
             .bind(account_id)
             .bind(asset)
-            .execute(self.pool)
+            .execute(&mut *conn)
             .await?;
 
         Ok(())
     }
 
     pub async fn delete_all_for_account(&self, account_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM holding_tags WHERE holding_id IN (SELECT id FROM holdings WHERE account_id = ?)")
+            .bind(account_id)
+            .execute(self.pool)
+            .await?;
+
         sqlx::query("DELETE FROM holdings WHERE account_id = ?")
             .bind(account_id)
             .execute(self.pool)
@@ -191,16 +444,90 @@ impl<'a> HoldingRepository<'a> {
         Ok(())
     }
 
-    fn parse_holdings(
+    /// Record a quantity change made outside of a transaction
+    pub async fn record_change(
         &self,
-        rows: Vec<(i64, String, String, String, Option<String>, String)>,
+        account_id: &str,
+        asset: &str,
+        previous_quantity: Decimal,
+        new_quantity: Decimal,
+        source: HoldingChangeSource,
+        sync_log_id: Option<i64>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO holdings_sync_log (
+                sync_log_id, account_id, asset, previous_quantity, new_quantity, source, recorded_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(sync_log_id)
+        .bind(account_id)
+        .bind(asset.to_uppercase())
+        .bind(previous_quantity.to_string())
+        .bind(new_quantity.to_string())
+        .bind(source.as_str())
+        .bind(Utc::now().to_rfc3339())
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Changes recorded for one holding by syncs and manual edits, oldest first
+    pub async fn list_recorded_changes(&self, account_id: &str, asset: &str) -> Result<Vec<RecordedHoldingChange>> {
+        let rows = sqlx::query_as::<_, (String, Option<i64>, String, String, String)>(
+            r#"
+            SELECT source, sync_log_id, previous_quantity, new_quantity, recorded_at
+            FROM holdings_sync_log
+            WHERE account_id = ? AND asset = UPPER(?)
+            ORDER BY recorded_at, id
+            "#
+        )
+        .bind(account_id)
+        .bind(asset)
+        .fetch_all(self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(source, sync_log_id, previous_quantity, new_quantity, recorded_at)| {
+                Ok(RecordedHoldingChange {
+                    source: HoldingChangeSource::parse(&source)
+                        .ok_or_else(|| CryptofolioError::Other(format!("Unknown holding change source: {}", source)))?,
+                    sync_log_id,
+                    previous_quantity: Decimal::from_str(&previous_quantity)
+                        .map_err(|_| CryptofolioError::InvalidAmount(previous_quantity))?,
+                    new_quantity: Decimal::from_str(&new_quantity)
+                        .map_err(|_| CryptofolioError::InvalidAmount(new_quantity))?,
+                    recorded_at: DateTime::parse_from_rfc3339(&recorded_at)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .map_err(|_| CryptofolioError::Other(format!("Invalid timestamp: {}", recorded_at)))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Every event that changed a holding, with the balance after each one
+    pub async fn history(&self, account_id: &str, asset: &str) -> Result<Vec<HoldingHistoryEntry>> {
+        let filter = TransactionFilter {
+            account_id: Some(account_id.to_string()),
+            asset: Some(asset.to_string()),
+            ..Default::default()
+        };
+        let transactions = TransactionRepository::new(self.pool).list(&filter).await?;
+        let recorded = self.list_recorded_changes(account_id, asset).await?;
+
+        Ok(build_holding_history(account_id, asset, &transactions, &recorded))
+    }
+
+    fn parse_holdings(
+        rows: Vec<HoldingRow>,
     ) -> Result<Vec<Holding>> {
-        rows.into_iter().map(|r| self.parse_holding(r)).collect()
+        rows.into_iter().map(Self::parse_holding).collect()
     }
 
     fn parse_holding(
-        &self,
-        (id, account_id, asset, quantity, avg_cost_basis, updated_at): (i64, String, String, String, Option<String>, String),
+        (id, account_id, asset, quantity, avg_cost_basis, updated_at, acquired_at): HoldingRow,
     ) -> Result<Holding> {
         let quantity = Decimal::from_str(&quantity)
             .map_err(|_| CryptofolioError::InvalidAmount(quantity))?;
@@ -221,6 +548,9 @@ impl<'a> HoldingRepository<'a> {
             updated_at: DateTime::parse_from_rfc3339(&updated_at)
                 .map(|dt| dt.with_timezone(&Utc))
                 .unwrap_or_else(|_| Utc::now()),
+            acquired_at: acquired_at
+                .and_then(|a| DateTime::parse_from_rfc3339(&a).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
         })
     }
 }
@@ -0,0 +1,159 @@
+use sqlx::SqlitePool;
+
+use crate::core::account::Account;
+use crate::core::holdings::Holding;
+use crate::db::holdings::HoldingRepository;
+use crate::error::{CryptofolioError, Result};
+
+/// What merging one account into another changes
+#[derive(Debug)]
+pub struct MergePlan {
+    /// One entry per holding of the merged account, by asset
+    pub holdings: Vec<HoldingMerge>,
+    /// Transactions from or to the merged account
+    pub transactions: i64,
+    /// Wallet addresses of the merged account
+    pub addresses: i64,
+}
+
+/// Where a holding of the merged account ends up
+#[derive(Debug)]
+pub struct HoldingMerge {
+    pub source: Holding,
+    /// The target's holding of the same asset, if it has one
+    pub existing: Option<Holding>,
+    /// The target's holding after the merge
+    pub merged: Holding,
+}
+
+/// Folds one account into another: holdings, transactions and history move
+/// to the target and the source account is deleted, all in one transaction
+pub struct MergeRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> MergeRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Work out the merge of `from` into `to` without changing anything
+    pub async fn plan(&self, from: &Account, to: &Account) -> Result<MergePlan> {
+        if from.id == to.id {
+            return Err(CryptofolioError::InvalidInput(format!(
+                "Cannot merge '{}' into itself",
+                from.name
+            )));
+        }
+
+        let holding_repo = HoldingRepository::new(self.pool);
+        let target = holding_repo.list_by_account(&to.id).await?;
+        let holdings = holding_repo
+            .list_by_account(&from.id)
+            .await?
+            .into_iter()
+            .map(|source| {
+                let existing = target.iter().find(|h| h.asset.eq_ignore_ascii_case(&source.asset)).cloned();
+                let merged = match &existing {
+                    Some(existing) => existing.merged_with(&source),
+                    None => Holding {
+                        account_id: to.id.clone(),
+                        ..source.clone()
+                    },
+                };
+                HoldingMerge { source, existing, merged }
+            })
+            .collect();
+
+        let transactions: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM transactions WHERE from_account_id = ? OR to_account_id = ?")
+                .bind(&from.id)
+                .bind(&from.id)
+                .fetch_one(self.pool)
+                .await?;
+        let addresses: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM wallet_addresses WHERE account_id = ?")
+            .bind(&from.id)
+            .fetch_one(self.pool)
+            .await?;
+
+        Ok(MergePlan {
+            holdings,
+            transactions,
+            addresses,
+        })
+    }
+
+    /// Merge `from` into `to` and delete `from`. Nothing is changed if any step fails.
+    pub async fn merge(&self, from: &Account, to: &Account) -> Result<MergePlan> {
+        let plan = self.plan(from, to).await?;
+        let mut tx = self.pool.begin().await?;
+
+        for holding in &plan.holdings {
+            let merged = &holding.merged;
+            match &holding.existing {
+                Some(existing) => {
+                    sqlx::query(
+                        "UPDATE holdings SET quantity = ?, avg_cost_basis = ?, avg_cost_basis_base = ?, acquired_at = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+                    )
+                    .bind(merged.quantity.to_string())
+                    .bind(merged.avg_cost_basis.map(|c| c.to_string()))
+                    .bind(merged.avg_cost_basis_base.map(|c| c.to_string()))
+                    .bind(merged.acquired_at.map(|d| d.to_rfc3339()))
+                    .bind(existing.id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                    // Keep the merged holding's tags on the one it was folded into
+                    sqlx::query(
+                        "INSERT OR IGNORE INTO holding_tags (holding_id, tag, created_at) SELECT ?, tag, created_at FROM holding_tags WHERE holding_id = ?",
+                    )
+                    .bind(existing.id)
+                    .bind(holding.source.id)
+                    .execute(&mut *tx)
+                    .await?;
+                    sqlx::query("DELETE FROM holding_tags WHERE holding_id = ?")
+                        .bind(holding.source.id)
+                        .execute(&mut *tx)
+                        .await?;
+                    sqlx::query("DELETE FROM holdings WHERE id = ?")
+                        .bind(holding.source.id)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                // Moving the row keeps its id, and with it its tags
+                None => {
+                    sqlx::query("UPDATE holdings SET account_id = ? WHERE id = ?")
+                        .bind(&to.id)
+                        .bind(holding.source.id)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+        }
+
+        for sql in [
+            "UPDATE transactions SET from_account_id = ? WHERE from_account_id = ?",
+            "UPDATE transactions SET to_account_id = ? WHERE to_account_id = ?",
+            "UPDATE tax_lots SET account_id = ? WHERE account_id = ?",
+            "UPDATE realized_pnl SET account_id = ? WHERE account_id = ?",
+            "UPDATE sync_log SET account_id = ? WHERE account_id = ?",
+            "UPDATE holdings_sync_log SET account_id = ? WHERE account_id = ?",
+            // Addresses the target already has stay behind and are deleted with the account
+            "UPDATE OR IGNORE wallet_addresses SET account_id = ? WHERE account_id = ?",
+        ] {
+            sqlx::query(sql).bind(&to.id).bind(&from.id).execute(&mut *tx).await?;
+        }
+
+        sqlx::query("DELETE FROM wallet_addresses WHERE account_id = ?")
+            .bind(&from.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM accounts WHERE id = ?")
+            .bind(&from.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(plan)
+    }
+}
@@ -213,6 +213,114 @@ CREATE INDEX IF NOT EXISTS idx_keychain_keys_name ON keychain_keys(key_name);
 CREATE INDEX IF NOT EXISTS idx_keychain_keys_storage ON keychain_keys(storage_type);
 "#;
 
+const MIGRATION_006: &str = r#"
+-- Audit log of sync runs
+CREATE TABLE IF NOT EXISTS sync_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id TEXT NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    started_at DATETIME NOT NULL,
+    completed_at DATETIME,
+    holdings_updated INTEGER NOT NULL DEFAULT 0,
+    holdings_added INTEGER NOT NULL DEFAULT 0,
+    holdings_removed INTEGER NOT NULL DEFAULT 0,
+    error_message TEXT,
+    status TEXT NOT NULL CHECK(status IN ('running', 'success', 'failed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_log_account ON sync_log(account_id);
+CREATE INDEX IF NOT EXISTS idx_sync_log_started_at ON sync_log(started_at);
+"#;
+
+const MIGRATION_007: &str = r#"
+-- Free-form tags on individual holdings
+CREATE TABLE IF NOT EXISTS holding_tags (
+    holding_id INTEGER NOT NULL REFERENCES holdings(id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (holding_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_holding_tags_tag ON holding_tags(tag);
+"#;
+
+const MIGRATION_008: &str = r#"
+-- Saved portfolio valuations for history and period returns
+CREATE TABLE IF NOT EXISTS portfolio_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    total_value_usd TEXT NOT NULL,
+    total_cost_basis TEXT NOT NULL,
+    taken_at DATETIME NOT NULL
+);
+
+-- Per-account, per-asset valuation within a snapshot (kept if the account is deleted)
+CREATE TABLE IF NOT EXISTS portfolio_snapshot_holdings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    snapshot_id INTEGER NOT NULL REFERENCES portfolio_snapshots(id) ON DELETE CASCADE,
+    account_id TEXT NOT NULL,
+    account_name TEXT NOT NULL,
+    category_id TEXT NOT NULL,
+    category_name TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    quantity TEXT NOT NULL,
+    avg_cost_basis TEXT,
+    price_usd TEXT,
+    value_usd TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_portfolio_snapshots_taken_at ON portfolio_snapshots(taken_at);
+CREATE INDEX IF NOT EXISTS idx_portfolio_snapshot_holdings_snapshot ON portfolio_snapshot_holdings(snapshot_id);
+"#;
+
+const MIGRATION_009: &str = r#"
+-- Cached CoinGecko coin list used to resolve symbols to coin ids
+CREATE TABLE IF NOT EXISTS asset_metadata (
+    coin_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    symbol TEXT NOT NULL,
+    name TEXT NOT NULL,
+    coingecko_id TEXT NOT NULL UNIQUE,
+    updated_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_asset_metadata_symbol ON asset_metadata(symbol);
+"#;
+
+const MIGRATION_010: &str = r#"
+-- Holding quantities set by syncs and manual edits, which leave no transaction behind
+CREATE TABLE IF NOT EXISTS holdings_sync_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    sync_log_id INTEGER REFERENCES sync_log(id) ON DELETE SET NULL,
+    account_id TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    previous_quantity TEXT NOT NULL,
+    new_quantity TEXT NOT NULL,
+    source TEXT NOT NULL,
+    recorded_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_holdings_sync_log_holding ON holdings_sync_log(account_id, asset);
+"#;
+
+const MIGRATION_011: &str = r#"
+-- Acquisition date of a holding's earliest lot, for backdated additions
+ALTER TABLE holdings ADD COLUMN acquired_at TEXT;
+"#;
+
+const MIGRATION_012: &str = r#"
+-- Price thresholds that run a command once when crossed
+CREATE TABLE IF NOT EXISTS alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    asset TEXT NOT NULL,
+    direction TEXT NOT NULL CHECK (direction IN ('above', 'below')),
+    threshold TEXT NOT NULL,
+    command TEXT,
+    triggered_at DATETIME,
+    active BOOLEAN NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_alerts_active ON alerts(active);
+"#;
+
 pub async fn run(pool: &SqlitePool) -> Result<()> {
     // Check if migration 1 has been applied
     let migration_exists: Option<(i64,)> = sqlx::query_as(
@@ -290,5 +398,138 @@ pub async fn run(pool: &SqlitePool) -> Result<()> {
             .await?;
     }
 
+    // Check if migration 6 has been applied
+    let migration_6_exists: Option<(i64,)> = sqlx::query_as(
+        "SELECT id FROM _migrations WHERE id = 6"
+    )
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten();
+
+    if migration_6_exists.is_none() {
+        // Apply migration 6
+        sqlx::raw_sql(MIGRATION_006).execute(pool).await?;
+
+        // Mark migration as applied
+        sqlx::query("INSERT OR IGNORE INTO _migrations (id) VALUES (6)")
+            .execute(pool)
+            .await?;
+    }
+
+    // Check if migration 7 has been applied
+    let migration_7_exists: Option<(i64,)> = sqlx::query_as(
+        "SELECT id FROM _migrations WHERE id = 7"
+    )
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten();
+
+    if migration_7_exists.is_none() {
+        // Apply migration 7
+        sqlx::raw_sql(MIGRATION_007).execute(pool).await?;
+
+        // Mark migration as applied
+        sqlx::query("INSERT OR IGNORE INTO _migrations (id) VALUES (7)")
+            .execute(pool)
+            .await?;
+    }
+
+    // Check if migration 8 has been applied
+    let migration_8_exists: Option<(i64,)> = sqlx::query_as(
+        "SELECT id FROM _migrations WHERE id = 8"
+    )
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten();
+
+    if migration_8_exists.is_none() {
+        // Apply migration 8
+        sqlx::raw_sql(MIGRATION_008).execute(pool).await?;
+
+        // Mark migration as applied
+        sqlx::query("INSERT OR IGNORE INTO _migrations (id) VALUES (8)")
+            .execute(pool)
+            .await?;
+    }
+
+    // Check if migration 9 has been applied
+    let migration_9_exists: Option<(i64,)> = sqlx::query_as(
+        "SELECT id FROM _migrations WHERE id = 9"
+    )
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten();
+
+    if migration_9_exists.is_none() {
+        // Apply migration 9
+        sqlx::raw_sql(MIGRATION_009).execute(pool).await?;
+
+        // Mark migration as applied
+        sqlx::query("INSERT OR IGNORE INTO _migrations (id) VALUES (9)")
+            .execute(pool)
+            .await?;
+    }
+
+    // Check if migration 10 has been applied
+    let migration_10_exists: Option<(i64,)> = sqlx::query_as(
+        "SELECT id FROM _migrations WHERE id = 10"
+    )
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten();
+
+    if migration_10_exists.is_none() {
+        // Apply migration 10
+        sqlx::raw_sql(MIGRATION_010).execute(pool).await?;
+
+        // Mark migration as applied
+        sqlx::query("INSERT OR IGNORE INTO _migrations (id) VALUES (10)")
+            .execute(pool)
+            .await?;
+    }
+
+    // Check if migration 11 has been applied
+    let migration_11_exists: Option<(i64,)> = sqlx::query_as(
+        "SELECT id FROM _migrations WHERE id = 11"
+    )
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten();
+
+    if migration_11_exists.is_none() {
+        // Apply migration 11
+        sqlx::raw_sql(MIGRATION_011).execute(pool).await?;
+
+        // Mark migration as applied
+        sqlx::query("INSERT OR IGNORE INTO _migrations (id) VALUES (11)")
+            .execute(pool)
+            .await?;
+    }
+
+    // Check if migration 12 has been applied
+    let migration_12_exists: Option<(i64,)> = sqlx::query_as(
+        "SELECT id FROM _migrations WHERE id = 12"
+    )
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten();
+
+    if migration_12_exists.is_none() {
+        // Apply migration 12
+        sqlx::raw_sql(MIGRATION_012).execute(pool).await?;
+
+        // Mark migration as applied
+        sqlx::query("INSERT OR IGNORE INTO _migrations (id) VALUES (12)")
+            .execute(pool)
+            .await?;
+    }
+
     Ok(())
 }
@@ -1,30 +1,48 @@
 #![allow(dead_code)]
 
 pub mod accounts;
+pub mod alerts;
+pub mod asset_metadata;
 pub mod currencies;
+pub mod holding_tags;
 pub mod holdings;
 pub mod keychain;
+pub mod merge;
 pub mod migrations;
+pub mod portfolio;
+pub mod realized_gains;
 pub mod realized_pnl;
+pub mod sync_log;
 pub mod tax_lots;
 pub mod transactions;
 
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use sqlx::sqlite::{SqliteConnection, SqlitePool, SqlitePoolOptions};
+use std::path::Path;
 
 use crate::config::AppConfig;
 use crate::error::Result;
 
-pub use accounts::AccountRepository;
+pub use accounts::{AccountRepository, ListAccountsFilter};
+pub use alerts::AlertRepository;
+pub use asset_metadata::AssetMetadataRepository;
+pub use holding_tags::HoldingTagRepository;
 pub use holdings::HoldingRepository;
 pub use keychain::KeychainKeyRepository;
+pub use merge::MergeRepository;
+pub use portfolio::PortfolioRepository;
+pub use realized_gains::RealizedGainRepository;
 pub use realized_pnl::RealizedPnlRepository;
+pub use sync_log::SyncLogRepository;
 pub use tax_lots::TaxLotRepository;
-pub use transactions::TransactionRepository;
+pub use transactions::{TransactionFilter, TransactionRepository, TransactionSearchQuery};
 
 /// Initialize the database connection pool
 pub async fn init_pool() -> Result<SqlitePool> {
-    let db_path = AppConfig::database_path()?;
+    open_pool(&AppConfig::database_path()?).await
+}
 
+/// Open the database at `db_path`, creating it if needed, and run migrations
+pub async fn open_pool(db_path: &Path) -> Result<SqlitePool> {
     // Ensure parent directory exists
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent)?;
@@ -43,6 +61,36 @@ pub async fn init_pool() -> Result<SqlitePool> {
     Ok(pool)
 }
 
+/// Delete all user portfolio data (categories, accounts, holdings, transactions,
+/// exchange rates) on `conn`. Used inside the transaction of a full replace
+/// import, so a failed import leaves the old data in place.
+///
+/// Alerts, currencies, the asset metadata cache and keychain settings are not
+/// part of a full export, so the import could not restore them; they are kept.
+pub async fn clear_portfolio_data_in(conn: &mut SqliteConnection) -> Result<()> {
+    for table in [
+        "portfolio_snapshot_holdings",
+        "portfolio_snapshots",
+        "holdings_sync_log",
+        "sync_log",
+        "holding_tags",
+        "realized_pnl",
+        "tax_lots",
+        "transactions",
+        "holdings",
+        "wallet_addresses",
+        "accounts",
+        "exchange_rates",
+        "categories",
+    ] {
+        sqlx::query(&format!("DELETE FROM {}", table))
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
 /// Initialize an in-memory database (for testing)
 pub async fn init_memory_pool() -> Result<SqlitePool> {
     let pool = SqlitePoolOptions::new()
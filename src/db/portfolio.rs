@@ -0,0 +1,220 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::SqlitePool;
+use std::str::FromStr;
+
+use crate::core::holdings::{Holding, HoldingWithPrice};
+use crate::core::portfolio::{Portfolio, PortfolioEntry, PortfolioSnapshot};
+use crate::error::{CryptofolioError, Result};
+
+pub struct PortfolioRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> PortfolioRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Save a portfolio valuation with its per-account holdings, returning the snapshot id
+    pub async fn save_snapshot(&self, portfolio: &Portfolio, taken_at: DateTime<Utc>) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+
+        let snapshot_id = sqlx::query(
+            "INSERT INTO portfolio_snapshots (total_value_usd, total_cost_basis, taken_at) VALUES (?, ?, ?)"
+        )
+        .bind(portfolio.total_value_usd.to_string())
+        .bind(portfolio.total_cost_basis.to_string())
+        .bind(taken_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        for entry in &portfolio.entries {
+            for h in &entry.holdings {
+                sqlx::query(
+                    r#"
+                    INSERT INTO portfolio_snapshot_holdings (
+                        snapshot_id, account_id, account_name, category_id, category_name,
+                        asset, quantity, avg_cost_basis, price_usd, value_usd
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    "#
+                )
+                .bind(snapshot_id)
+                .bind(&entry.account_id)
+                .bind(&entry.account_name)
+                .bind(&entry.category_id)
+                .bind(&entry.category_name)
+                .bind(&h.holding.asset)
+                .bind(h.holding.quantity.to_string())
+                .bind(h.holding.avg_cost_basis.map(|d| d.to_string()))
+                .bind(h.current_price.map(|d| d.to_string()))
+                .bind(h.current_value.map(|d| d.to_string()))
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(snapshot_id)
+    }
+
+    /// List snapshots taken within `from..=to`, most recent first
+    pub async fn list_snapshots(
+        &self,
+        limit: Option<i64>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<PortfolioSnapshot>> {
+        let from = from.map(|d| d.to_rfc3339());
+        let to = to.map(|d| d.to_rfc3339());
+
+        let rows = sqlx::query_as::<_, SnapshotRow>(
+            r#"
+            SELECT id, total_value_usd, total_cost_basis, taken_at
+            FROM portfolio_snapshots
+            WHERE (? IS NULL OR taken_at >= ?) AND (? IS NULL OR taken_at <= ?)
+            ORDER BY taken_at DESC, id DESC
+            LIMIT ?
+            "#
+        )
+        .bind(&from)
+        .bind(&from)
+        .bind(&to)
+        .bind(&to)
+        .bind(limit.unwrap_or(-1))
+        .fetch_all(self.pool)
+        .await?;
+
+        rows.into_iter().map(|r| self.parse_snapshot(r)).collect()
+    }
+
+    pub async fn get_snapshot(&self, id: i64) -> Result<Option<PortfolioSnapshot>> {
+        let row = sqlx::query_as::<_, SnapshotRow>(
+            "SELECT id, total_value_usd, total_cost_basis, taken_at FROM portfolio_snapshots WHERE id = ?"
+        )
+        .bind(id)
+        .fetch_optional(self.pool)
+        .await?;
+
+        row.map(|r| self.parse_snapshot(r)).transpose()
+    }
+
+    /// The USD price an asset had in the latest snapshot taken before `before`,
+    /// with that snapshot's timestamp
+    pub async fn price_before(
+        &self,
+        asset: &str,
+        before: DateTime<Utc>,
+    ) -> Result<Option<(Decimal, DateTime<Utc>)>> {
+        let row: Option<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT h.price_usd, s.taken_at
+            FROM portfolio_snapshot_holdings h
+            JOIN portfolio_snapshots s ON s.id = h.snapshot_id
+            WHERE UPPER(h.asset) = UPPER(?) AND h.price_usd IS NOT NULL AND s.taken_at < ?
+            ORDER BY s.taken_at DESC
+            LIMIT 1
+            "#
+        )
+        .bind(asset)
+        .bind(before.to_rfc3339())
+        .fetch_optional(self.pool)
+        .await?;
+
+        row.map(|(price, taken_at)| {
+            let taken_at = DateTime::parse_from_rfc3339(&taken_at)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|_| CryptofolioError::Other(format!("Invalid snapshot timestamp: {}", taken_at)))?;
+            Ok((parse_decimal(price)?, taken_at))
+        })
+        .transpose()
+    }
+
+    /// Portfolio entries as they were valued in a snapshot
+    pub async fn list_snapshot_entries(&self, snapshot_id: i64) -> Result<Vec<PortfolioEntry>> {
+        let rows = sqlx::query_as::<_, SnapshotHoldingRow>(
+            r#"
+            SELECT account_id, account_name, category_id, category_name,
+                   asset, quantity, avg_cost_basis, price_usd
+            FROM portfolio_snapshot_holdings
+            WHERE snapshot_id = ?
+            ORDER BY id
+            "#
+        )
+        .bind(snapshot_id)
+        .fetch_all(self.pool)
+        .await?;
+
+        let mut entries: Vec<PortfolioEntry> = Vec::new();
+        for row in rows {
+            let holding = Holding {
+                id: 0,
+                account_id: row.account_id.clone(),
+                asset: row.asset,
+                quantity: parse_decimal(row.quantity)?,
+                avg_cost_basis: row.avg_cost_basis.map(parse_decimal).transpose()?,
+                cost_basis_currency: None,
+                avg_cost_basis_base: None,
+                updated_at: Utc::now(),
+                acquired_at: None,
+            };
+            let price = row.price_usd.map(parse_decimal).transpose()?;
+            let holding = HoldingWithPrice::from_holding(holding, price);
+
+            match entries.iter_mut().find(|e| e.account_id == row.account_id) {
+                Some(entry) => entry.holdings.push(holding),
+                None => entries.push(PortfolioEntry {
+                    account_id: row.account_id,
+                    account_name: row.account_name,
+                    category_id: row.category_id,
+                    category_name: row.category_name,
+                    holdings: vec![holding],
+                    snapshot_id: Some(snapshot_id),
+                    is_closed: false,
+                    display_value: None,
+                }),
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn parse_snapshot(&self, row: SnapshotRow) -> Result<PortfolioSnapshot> {
+        let taken_at = DateTime::parse_from_rfc3339(&row.taken_at)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|_| CryptofolioError::Other(format!("Invalid snapshot timestamp: {}", row.taken_at)))?;
+
+        Ok(PortfolioSnapshot {
+            id: row.id,
+            total_value_usd: parse_decimal(row.total_value_usd)?,
+            total_cost_basis: parse_decimal(row.total_cost_basis)?,
+            taken_at,
+        })
+    }
+}
+
+fn parse_decimal(value: String) -> Result<Decimal> {
+    Decimal::from_str(&value).map_err(|_| CryptofolioError::InvalidAmount(value))
+}
+
+#[derive(sqlx::FromRow)]
+struct SnapshotRow {
+    id: i64,
+    total_value_usd: String,
+    total_cost_basis: String,
+    taken_at: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct SnapshotHoldingRow {
+    account_id: String,
+    account_name: String,
+    category_id: String,
+    category_name: String,
+    asset: String,
+    quantity: String,
+    avg_cost_basis: Option<String>,
+    price_usd: Option<String>,
+}
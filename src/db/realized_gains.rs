@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+use crate::core::pnl::{CostBasisMethod, LotReport};
+use crate::db::TransactionRepository;
+use crate::error::Result;
+
+/// Realized and unrealized gains computed from the transactions table
+pub struct RealizedGainRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> RealizedGainRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Match every sale against purchase lots in `method` order, keeping
+    /// disposals made and open lots acquired between `from` and `to`
+    pub async fn report(
+        &self,
+        method: CostBasisMethod,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<LotReport> {
+        // Lots can arrive from other accounts, so replay the full history before filtering
+        let transactions = TransactionRepository::new(self.pool).list_chronological(None).await?;
+        let mut report = LotReport::from_transactions(method, &transactions);
+
+        let in_range = |at: DateTime<Utc>| from.is_none_or(|f| at >= f) && to.is_none_or(|t| at <= t);
+        report.disposals.retain(|d| in_range(d.disposed_at));
+        report.open_lots.retain(|l| in_range(l.acquired_at));
+
+        Ok(report)
+    }
+}
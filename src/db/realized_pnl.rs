@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::SqlitePool;
+use std::str::FromStr;
+
+use crate::error::{CryptofolioError, Result};
+
+/// A realized gain or loss produced by disposing of (part of) a tax lot
+#[derive(Debug, Clone)]
+pub struct RealizedPnl {
+    pub id: i64,
+    pub account_id: String,
+    pub asset: String,
+    pub disposal_date: DateTime<Utc>,
+    pub disposal_tx_id: Option<i64>,
+    pub quantity: Decimal,
+    pub proceeds: Decimal,
+    pub cost_basis: Decimal,
+    pub realized_gain: Decimal,
+    pub holding_period_days: Option<i64>,
+    pub tax_lot_id: Option<i64>,
+    pub cost_basis_method: String,
+}
+
+pub struct RealizedPnlRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> RealizedPnlRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn insert(&self, entry: &RealizedPnl) -> Result<i64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO realized_pnl (
+                account_id, asset, disposal_date, disposal_tx_id, quantity, proceeds,
+                cost_basis, realized_gain, holding_period_days, tax_lot_id, cost_basis_method
+            ) VALUES (?, UPPER(?), ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&entry.account_id)
+        .bind(&entry.asset)
+        .bind(entry.disposal_date.to_rfc3339())
+        .bind(entry.disposal_tx_id)
+        .bind(entry.quantity.to_string())
+        .bind(entry.proceeds.to_string())
+        .bind(entry.cost_basis.to_string())
+        .bind(entry.realized_gain.to_string())
+        .bind(entry.holding_period_days)
+        .bind(entry.tax_lot_id)
+        .bind(&entry.cost_basis_method)
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn list(&self) -> Result<Vec<RealizedPnl>> {
+        let rows = sqlx::query_as::<_, RealizedPnlRow>(
+            r#"
+            SELECT id, account_id, asset, disposal_date, disposal_tx_id, quantity, proceeds,
+                   cost_basis, realized_gain, holding_period_days, tax_lot_id, cost_basis_method
+            FROM realized_pnl
+            ORDER BY disposal_date ASC, id ASC
+            "#
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        rows.into_iter().map(|r| self.parse_entry(r)).collect()
+    }
+
+    pub async fn list_by_account(&self, account_id: &str) -> Result<Vec<RealizedPnl>> {
+        let rows = sqlx::query_as::<_, RealizedPnlRow>(
+            r#"
+            SELECT id, account_id, asset, disposal_date, disposal_tx_id, quantity, proceeds,
+                   cost_basis, realized_gain, holding_period_days, tax_lot_id, cost_basis_method
+            FROM realized_pnl
+            WHERE account_id = ?
+            ORDER BY disposal_date ASC, id ASC
+            "#
+        )
+        .bind(account_id)
+        .fetch_all(self.pool)
+        .await?;
+
+        rows.into_iter().map(|r| self.parse_entry(r)).collect()
+    }
+
+    /// Sum of realized gains across all disposals
+    pub async fn total_realized(&self) -> Result<Decimal> {
+        Ok(self.list().await?.iter().map(|e| e.realized_gain).sum())
+    }
+
+    pub async fn delete_all_for_account(&self, account_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM realized_pnl WHERE account_id = ?")
+            .bind(account_id)
+            .execute(self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    fn parse_entry(&self, row: RealizedPnlRow) -> Result<RealizedPnl> {
+        let parse_decimal = |s: String| -> Result<Decimal> {
+            Decimal::from_str(&s).map_err(|_| CryptofolioError::InvalidAmount(s))
+        };
+
+        Ok(RealizedPnl {
+            id: row.id,
+            account_id: row.account_id,
+            asset: row.asset,
+            disposal_date: DateTime::parse_from_rfc3339(&row.disposal_date)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            disposal_tx_id: row.disposal_tx_id,
+            quantity: parse_decimal(row.quantity)?,
+            proceeds: parse_decimal(row.proceeds)?,
+            cost_basis: parse_decimal(row.cost_basis)?,
+            realized_gain: parse_decimal(row.realized_gain)?,
+            holding_period_days: row.holding_period_days,
+            tax_lot_id: row.tax_lot_id,
+            cost_basis_method: row.cost_basis_method,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct RealizedPnlRow {
+    id: i64,
+    account_id: String,
+    asset: String,
+    disposal_date: String,
+    disposal_tx_id: Option<i64>,
+    quantity: String,
+    proceeds: String,
+    cost_basis: String,
+    realized_gain: String,
+    holding_period_days: Option<i64>,
+    tax_lot_id: Option<i64>,
+    cost_basis_method: String,
+}
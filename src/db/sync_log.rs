@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::SqlitePool;
+use std::str::FromStr;
+
+use crate::error::{CryptofolioError, Result};
+
+pub const SYNC_STATUS_RUNNING: &str = "running";
+pub const SYNC_STATUS_SUCCESS: &str = "success";
+pub const SYNC_STATUS_FAILED: &str = "failed";
+
+/// One run of `sync` for a single account
+#[derive(Debug, Clone)]
+pub struct SyncLogEntry {
+    pub id: i64,
+    pub account_id: String,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub holdings_updated: i64,
+    pub holdings_added: i64,
+    pub holdings_removed: i64,
+    pub error_message: Option<String>,
+    pub status: String,
+}
+
+/// Holding changes made by a completed sync
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncCounts {
+    pub updated: i64,
+    pub added: i64,
+    pub removed: i64,
+}
+
+/// A holding quantity changed by a sync run
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncChange {
+    pub asset: String,
+    pub previous_quantity: Decimal,
+    pub new_quantity: Decimal,
+}
+
+impl SyncChange {
+    pub fn delta(&self) -> Decimal {
+        self.new_quantity - self.previous_quantity
+    }
+}
+
+pub struct SyncLogRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> SyncLogRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Record the start of a sync, returning the log entry id
+    pub async fn start(&self, account_id: &str) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO sync_log (account_id, started_at, status) VALUES (?, ?, ?)"
+        )
+        .bind(account_id)
+        .bind(Utc::now().to_rfc3339())
+        .bind(SYNC_STATUS_RUNNING)
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn complete(&self, id: i64, counts: SyncCounts) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE sync_log
+            SET completed_at = ?, holdings_updated = ?, holdings_added = ?, holdings_removed = ?, status = ?
+            WHERE id = ?
+            "#
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(counts.updated)
+        .bind(counts.added)
+        .bind(counts.removed)
+        .bind(SYNC_STATUS_SUCCESS)
+        .bind(id)
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn fail(&self, id: i64, error_message: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE sync_log SET completed_at = ?, error_message = ?, status = ? WHERE id = ?"
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(error_message)
+        .bind(SYNC_STATUS_FAILED)
+        .bind(id)
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// List sync runs, most recent first
+    pub async fn list(&self, account_id: Option<&str>, limit: i64) -> Result<Vec<SyncLogEntry>> {
+        let rows = sqlx::query_as::<_, SyncLogRow>(
+            r#"
+            SELECT id, account_id, started_at, completed_at, holdings_updated, holdings_added,
+                   holdings_removed, error_message, status
+            FROM sync_log
+            WHERE ? IS NULL OR account_id = ?
+            ORDER BY started_at DESC, id DESC
+            LIMIT ?
+            "#
+        )
+        .bind(account_id)
+        .bind(account_id)
+        .bind(limit)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| self.parse_entry(r)).collect())
+    }
+
+    /// Holding changes made by one sync run, in asset order
+    pub async fn list_changes(&self, sync_log_id: i64) -> Result<Vec<SyncChange>> {
+        let rows = sqlx::query_as::<_, (String, String, String)>(
+            r#"
+            SELECT asset, previous_quantity, new_quantity
+            FROM holdings_sync_log
+            WHERE sync_log_id = ?
+            ORDER BY asset, id
+            "#
+        )
+        .bind(sync_log_id)
+        .fetch_all(self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(asset, previous_quantity, new_quantity)| {
+                Ok(SyncChange {
+                    asset,
+                    previous_quantity: Decimal::from_str(&previous_quantity)
+                        .map_err(|_| CryptofolioError::InvalidAmount(previous_quantity))?,
+                    new_quantity: Decimal::from_str(&new_quantity)
+                        .map_err(|_| CryptofolioError::InvalidAmount(new_quantity))?,
+                })
+            })
+            .collect()
+    }
+
+    fn parse_entry(&self, row: SyncLogRow) -> SyncLogEntry {
+        let parse_date = |s: &str| DateTime::parse_from_rfc3339(s).map(|dt| dt.with_timezone(&Utc));
+
+        SyncLogEntry {
+            id: row.id,
+            account_id: row.account_id,
+            started_at: parse_date(&row.started_at).unwrap_or_else(|_| Utc::now()),
+            completed_at: row.completed_at.as_deref().and_then(|s| parse_date(s).ok()),
+            holdings_updated: row.holdings_updated,
+            holdings_added: row.holdings_added,
+            holdings_removed: row.holdings_removed,
+            error_message: row.error_message,
+            status: row.status,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SyncLogRow {
+    id: i64,
+    account_id: String,
+    started_at: String,
+    completed_at: Option<String>,
+    holdings_updated: i64,
+    holdings_added: i64,
+    holdings_removed: i64,
+    error_message: Option<String>,
+    status: String,
+}
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::SqlitePool;
+use std::str::FromStr;
+
+use crate::error::{CryptofolioError, Result};
+
+/// A single acquisition lot used for cost basis tracking
+#[derive(Debug, Clone)]
+pub struct TaxLot {
+    pub id: i64,
+    pub account_id: String,
+    pub asset: String,
+    pub quantity: Decimal,
+    pub remaining_quantity: Decimal,
+    pub acquisition_price: Decimal,
+    pub acquisition_date: DateTime<Utc>,
+    pub acquisition_tx_id: Option<i64>,
+    pub cost_basis_method: String,
+    pub fully_disposed: bool,
+}
+
+pub struct TaxLotRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> TaxLotRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn insert(&self, lot: &TaxLot) -> Result<i64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO tax_lots (
+                account_id, asset, quantity, remaining_quantity, acquisition_price,
+                acquisition_date, acquisition_tx_id, cost_basis_method, fully_disposed
+            ) VALUES (?, UPPER(?), ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&lot.account_id)
+        .bind(&lot.asset)
+        .bind(lot.quantity.to_string())
+        .bind(lot.remaining_quantity.to_string())
+        .bind(lot.acquisition_price.to_string())
+        .bind(lot.acquisition_date.to_rfc3339())
+        .bind(lot.acquisition_tx_id)
+        .bind(&lot.cost_basis_method)
+        .bind(lot.fully_disposed)
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// List lots that still have a remaining quantity, oldest first
+    pub async fn list_open(&self, account_id: &str, asset: &str) -> Result<Vec<TaxLot>> {
+        let rows = sqlx::query_as::<_, TaxLotRow>(
+            r#"
+            SELECT id, account_id, asset, quantity, remaining_quantity, acquisition_price,
+                   acquisition_date, acquisition_tx_id, cost_basis_method, fully_disposed
+            FROM tax_lots
+            WHERE account_id = ? AND UPPER(asset) = UPPER(?) AND fully_disposed = FALSE
+            ORDER BY acquisition_date ASC, id ASC
+            "#
+        )
+        .bind(account_id)
+        .bind(asset)
+        .fetch_all(self.pool)
+        .await?;
+
+        rows.into_iter().map(|r| self.parse_lot(r)).collect()
+    }
+
+    pub async fn list_by_account(&self, account_id: &str) -> Result<Vec<TaxLot>> {
+        let rows = sqlx::query_as::<_, TaxLotRow>(
+            r#"
+            SELECT id, account_id, asset, quantity, remaining_quantity, acquisition_price,
+                   acquisition_date, acquisition_tx_id, cost_basis_method, fully_disposed
+            FROM tax_lots
+            WHERE account_id = ?
+            ORDER BY acquisition_date ASC, id ASC
+            "#
+        )
+        .bind(account_id)
+        .fetch_all(self.pool)
+        .await?;
+
+        rows.into_iter().map(|r| self.parse_lot(r)).collect()
+    }
+
+    /// Update the remaining quantity of a lot, marking it disposed when it reaches zero
+    pub async fn update_remaining(&self, id: i64, remaining_quantity: Decimal) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE tax_lots
+            SET remaining_quantity = ?, fully_disposed = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#
+        )
+        .bind(remaining_quantity.to_string())
+        .bind(remaining_quantity <= Decimal::ZERO)
+        .bind(id)
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_all_for_account(&self, account_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM tax_lots WHERE account_id = ?")
+            .bind(account_id)
+            .execute(self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    fn parse_lot(&self, row: TaxLotRow) -> Result<TaxLot> {
+        let parse_decimal = |s: String| -> Result<Decimal> {
+            Decimal::from_str(&s).map_err(|_| CryptofolioError::InvalidAmount(s))
+        };
+
+        Ok(TaxLot {
+            id: row.id,
+            account_id: row.account_id,
+            asset: row.asset,
+            quantity: parse_decimal(row.quantity)?,
+            remaining_quantity: parse_decimal(row.remaining_quantity)?,
+            acquisition_price: parse_decimal(row.acquisition_price)?,
+            acquisition_date: DateTime::parse_from_rfc3339(&row.acquisition_date)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            acquisition_tx_id: row.acquisition_tx_id,
+            cost_basis_method: row.cost_basis_method,
+            fully_disposed: row.fully_disposed,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct TaxLotRow {
+    id: i64,
+    account_id: String,
+    asset: String,
+    quantity: String,
+    remaining_quantity: String,
+    acquisition_price: String,
+    acquisition_date: String,
+    acquisition_tx_id: Option<i64>,
+    cost_basis_method: String,
+    fully_disposed: bool,
+}
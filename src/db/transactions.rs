@@ -1,11 +1,101 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use rust_decimal::Decimal;
-use sqlx::SqlitePool;
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
 use std::str::FromStr;
 
 use crate::core::transaction::{Transaction, TransactionType};
 use crate::error::{CryptofolioError, Result};
 
+/// Conditions for [`TransactionRepository::list`]; unset fields match every transaction
+#[derive(Debug, Clone, Default)]
+pub struct TransactionFilter {
+    /// Transactions moving funds into or out of this account
+    pub account_id: Option<String>,
+    /// Any of these types
+    pub tx_types: Vec<TransactionType>,
+    /// Transactions with this asset on either side
+    pub asset: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Notes containing this text
+    pub notes: Option<String>,
+    /// Match `notes` case-sensitively
+    pub case_sensitive: bool,
+    /// Maximum number of transactions; None returns all
+    pub limit: Option<i64>,
+}
+
+/// Free-text search for [`TransactionRepository::search`], narrowed by `filter`
+#[derive(Debug, Clone, Default)]
+pub struct TransactionSearchQuery {
+    /// Text to find in notes or either asset, ignoring ASCII case
+    pub text: Option<String>,
+    pub filter: TransactionFilter,
+}
+
+/// Columns of a transaction row, followed by the filters' `AND` clauses
+const SELECT_TRANSACTIONS: &str = r#"
+    SELECT id, tx_type, from_account_id, from_asset, from_quantity,
+           to_account_id, to_asset, to_quantity, price_usd, fee, fee_asset,
+           external_id, notes, timestamp, created_at
+    FROM transactions
+    WHERE 1 = 1
+"#;
+
+/// Append `filter`'s conditions, and the free-text `text` match, to a query ending in a WHERE clause
+fn push_conditions<'q>(query: &mut QueryBuilder<'q, Sqlite>, filter: &'q TransactionFilter, text: Option<&str>) {
+    if let Some(account_id) = &filter.account_id {
+        query
+            .push(" AND (from_account_id = ")
+            .push_bind(account_id)
+            .push(" OR to_account_id = ")
+            .push_bind(account_id)
+            .push(")");
+    }
+    if !filter.tx_types.is_empty() {
+        query.push(" AND tx_type IN (");
+        let mut types = query.separated(", ");
+        for tx_type in &filter.tx_types {
+            types.push_bind(tx_type.as_str());
+        }
+        query.push(")");
+    }
+    if let Some(asset) = &filter.asset {
+        let asset = asset.to_uppercase();
+        query
+            .push(" AND (UPPER(from_asset) = ")
+            .push_bind(asset.clone())
+            .push(" OR UPPER(to_asset) = ")
+            .push_bind(asset)
+            .push(")");
+    }
+    if let Some(from) = filter.from {
+        query.push(" AND timestamp >= ").push_bind(from.to_rfc3339());
+    }
+    if let Some(to) = filter.to {
+        query.push(" AND timestamp <= ").push_bind(to.to_rfc3339());
+    }
+    // SQLite only folds ASCII case, so case-insensitive matching is ASCII-only
+    if let Some(text) = &filter.notes {
+        if filter.case_sensitive {
+            query.push(" AND instr(notes, ").push_bind(text).push(") > 0");
+        } else {
+            query.push(" AND instr(LOWER(notes), LOWER(").push_bind(text).push(")) > 0");
+        }
+    }
+
+    if let Some(text) = text {
+        query
+            .push(" AND (instr(LOWER(notes), LOWER(")
+            .push_bind(text.to_string())
+            .push(")) > 0 OR instr(LOWER(from_asset), LOWER(")
+            .push_bind(text.to_string())
+            .push(")) > 0 OR instr(LOWER(to_asset), LOWER(")
+            .push_bind(text.to_string())
+            .push(")) > 0)");
+    }
+}
+
 pub struct TransactionRepository<'a> {
     pool: &'a SqlitePool,
 }
@@ -15,50 +105,215 @@ impl<'a> TransactionRepository<'a> {
         Self { pool }
     }
 
-    pub async fn list(&self, limit: Option<i64>) -> Result<Vec<Transaction>> {
-        let limit = limit.unwrap_or(50);
+    /// List transactions matching `filter`, newest first
+    pub async fn list(&self, filter: &TransactionFilter) -> Result<Vec<Transaction>> {
+        self.query(filter, None).await
+    }
+
+    /// Transactions whose notes, from asset or to asset contain `query.text`
+    /// and that match `query.filter`, newest first
+    pub async fn search(&self, query: &TransactionSearchQuery) -> Result<Vec<Transaction>> {
+        self.query(&query.filter, query.text.as_deref()).await
+    }
+
+    /// One page of the transactions matching `filter`, newest first, along with
+    /// how many match in total. Pages start at 1; `filter.limit` is ignored.
+    pub async fn list_paginated(
+        &self,
+        filter: &TransactionFilter,
+        page: u32,
+        page_size: u32,
+    ) -> Result<(Vec<Transaction>, u64)> {
+        let mut count = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM transactions WHERE 1 = 1");
+        push_conditions(&mut count, filter, None);
+        let total: i64 = count.build_query_scalar().fetch_one(self.pool).await?;
+
+        let mut query = QueryBuilder::<Sqlite>::new(SELECT_TRANSACTIONS);
+        push_conditions(&mut query, filter, None);
+        let offset = i64::from(page.saturating_sub(1)) * i64::from(page_size);
+        query
+            .push(" ORDER BY timestamp DESC LIMIT ")
+            .push_bind(i64::from(page_size))
+            .push(" OFFSET ")
+            .push_bind(offset);
+
+        let rows = query.build_query_as::<TransactionRow>().fetch_all(self.pool).await?;
+        let transactions = rows.into_iter().map(Self::parse_transaction).collect::<Result<_>>()?;
+        Ok((transactions, total as u64))
+    }
+
+    async fn query(&self, filter: &TransactionFilter, text: Option<&str>) -> Result<Vec<Transaction>> {
+        let mut query = QueryBuilder::<Sqlite>::new(SELECT_TRANSACTIONS);
+        push_conditions(&mut query, filter, text);
+        query.push(" ORDER BY timestamp DESC LIMIT ").push_bind(filter.limit.unwrap_or(-1));
 
+        let rows = query.build_query_as::<TransactionRow>().fetch_all(self.pool).await?;
+
+        rows.into_iter().map(Self::parse_transaction).collect()
+    }
+
+    /// List every transaction that charged a fee, oldest first
+    pub async fn list_with_fees(&self) -> Result<Vec<Transaction>> {
         let rows = sqlx::query_as::<_, TransactionRow>(
             r#"
             SELECT id, tx_type, from_account_id, from_asset, from_quantity,
                    to_account_id, to_asset, to_quantity, price_usd, fee, fee_asset,
                    external_id, notes, timestamp, created_at
             FROM transactions
-            ORDER BY timestamp DESC
-            LIMIT ?
+            WHERE fee IS NOT NULL AND fee_asset IS NOT NULL
+            ORDER BY timestamp ASC
             "#
         )
-        .bind(limit)
         .fetch_all(self.pool)
         .await?;
 
-        rows.into_iter().map(|r| self.parse_transaction(r)).collect()
+        rows.into_iter().map(Self::parse_transaction).collect()
     }
 
-    pub async fn list_by_account(&self, account_id: &str, limit: Option<i64>) -> Result<Vec<Transaction>> {
-        let limit = limit.unwrap_or(50);
+    /// List every transaction oldest first, optionally limited to one account
+    pub async fn list_chronological(&self, account_id: Option<&str>) -> Result<Vec<Transaction>> {
+        Self::list_chronological_in(&mut *self.pool.acquire().await?, account_id).await
+    }
+
+    /// [`list_chronological`](Self::list_chronological) on `conn`
+    pub async fn list_chronological_in(conn: &mut SqliteConnection, account_id: Option<&str>) -> Result<Vec<Transaction>> {
+        let rows = if let Some(account_id) = account_id {
+            sqlx::query_as::<_, TransactionRow>(
+                r#"
+                SELECT id, tx_type, from_account_id, from_asset, from_quantity,
+                       to_account_id, to_asset, to_quantity, price_usd, fee, fee_asset,
+                       external_id, notes, timestamp, created_at
+                FROM transactions
+                WHERE from_account_id = ? OR to_account_id = ?
+                ORDER BY timestamp ASC, id ASC
+                "#
+            )
+            .bind(account_id)
+            .bind(account_id)
+            .fetch_all(&mut *conn)
+            .await?
+        } else {
+            sqlx::query_as::<_, TransactionRow>(
+                r#"
+                SELECT id, tx_type, from_account_id, from_asset, from_quantity,
+                       to_account_id, to_asset, to_quantity, price_usd, fee, fee_asset,
+                       external_id, notes, timestamp, created_at
+                FROM transactions
+                ORDER BY timestamp ASC, id ASC
+                "#
+            )
+            .fetch_all(&mut *conn)
+            .await?
+        };
+
+        rows.into_iter().map(Self::parse_transaction).collect()
+    }
 
+    /// Transactions whose from or to account no longer exists
+    pub async fn find_orphans(&self) -> Result<Vec<Transaction>> {
         let rows = sqlx::query_as::<_, TransactionRow>(
             r#"
             SELECT id, tx_type, from_account_id, from_asset, from_quantity,
                    to_account_id, to_asset, to_quantity, price_usd, fee, fee_asset,
                    external_id, notes, timestamp, created_at
             FROM transactions
-            WHERE from_account_id = ? OR to_account_id = ?
-            ORDER BY timestamp DESC
-            LIMIT ?
+            WHERE (from_account_id IS NOT NULL AND from_account_id NOT IN (SELECT id FROM accounts))
+               OR (to_account_id IS NOT NULL AND to_account_id NOT IN (SELECT id FROM accounts))
+            ORDER BY timestamp ASC, id ASC
             "#
         )
-        .bind(account_id)
-        .bind(account_id)
-        .bind(limit)
         .fetch_all(self.pool)
         .await?;
 
-        rows.into_iter().map(|r| self.parse_transaction(r)).collect()
+        rows.into_iter().map(Self::parse_transaction).collect()
+    }
+
+    /// Point every reference to a missing account at `account_id`, returning
+    /// the number of transactions changed
+    pub async fn reassign_orphans(&self, account_id: &str) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let ids: Vec<i64> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM transactions
+            WHERE (from_account_id IS NOT NULL AND from_account_id NOT IN (SELECT id FROM accounts))
+               OR (to_account_id IS NOT NULL AND to_account_id NOT IN (SELECT id FROM accounts))
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for column in ["from_account_id", "to_account_id"] {
+            sqlx::query(&format!(
+                "UPDATE transactions SET {column} = ? WHERE {column} IS NOT NULL AND {column} NOT IN (SELECT id FROM accounts)"
+            ))
+            .bind(account_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(ids.len() as u64)
+    }
+
+    pub async fn get_by_id(&self, id: i64) -> Result<Option<Transaction>> {
+        let row = sqlx::query_as::<_, TransactionRow>(
+            r#"
+            SELECT id, tx_type, from_account_id, from_asset, from_quantity,
+                   to_account_id, to_asset, to_quantity, price_usd, fee, fee_asset,
+                   external_id, notes, timestamp, created_at
+            FROM transactions
+            WHERE id = ?
+            "#
+        )
+        .bind(id)
+        .fetch_optional(self.pool)
+        .await?;
+
+        row.map(Self::parse_transaction).transpose()
+    }
+
+    /// Delete a transaction, detaching any tax lots and realized P&L records that reference it
+    pub async fn delete_by_id(&self, id: i64) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE tax_lots SET acquisition_tx_id = NULL WHERE acquisition_tx_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE realized_pnl SET disposal_tx_id = NULL WHERE disposal_tx_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        let result = sqlx::query("DELETE FROM transactions WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(CryptofolioError::NotFound(format!("Transaction #{}", id)));
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Whether a transaction with this external ID has already been recorded
+    pub async fn external_id_exists(&self, external_id: &str) -> Result<bool> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transactions WHERE external_id = ?")
+            .bind(external_id)
+            .fetch_one(self.pool)
+            .await?;
+
+        Ok(count > 0)
     }
 
     pub async fn insert(&self, tx: &Transaction) -> Result<i64> {
+        Self::insert_in(&mut *self.pool.acquire().await?, tx).await
+    }
+
+    /// [`insert`](Self::insert) on `conn`, e.g. inside a transaction that also updates holdings
+    pub async fn insert_in(conn: &mut SqliteConnection, tx: &Transaction) -> Result<i64> {
         let result = sqlx::query(
             r#"
             INSERT INTO transactions (
@@ -81,13 +336,13 @@ impl<'a> TransactionRepository<'a> {
         .bind(&tx.external_id)
         .bind(&tx.notes)
         .bind(tx.timestamp.to_rfc3339())
-        .execute(self.pool)
+        .execute(conn)
         .await?;
 
         Ok(result.last_insert_rowid())
     }
 
-    fn parse_transaction(&self, row: TransactionRow) -> Result<Transaction> {
+    fn parse_transaction(row: TransactionRow) -> Result<Transaction> {
         let tx_type = TransactionType::from_str(&row.tx_type)
             .ok_or_else(|| CryptofolioError::Other(format!("Invalid transaction type: {}", row.tx_type)))?;
 
@@ -117,8 +372,10 @@ impl<'a> TransactionRepository<'a> {
             timestamp: DateTime::parse_from_rfc3339(&row.timestamp)
                 .map(|dt| dt.with_timezone(&Utc))
                 .unwrap_or_else(|_| Utc::now()),
+            // Defaulted by SQLite's CURRENT_TIMESTAMP, which isn't RFC 3339
             created_at: DateTime::parse_from_rfc3339(&row.created_at)
                 .map(|dt| dt.with_timezone(&Utc))
+                .or_else(|_| NaiveDateTime::parse_from_str(&row.created_at, "%Y-%m-%d %H:%M:%S").map(|dt| dt.and_utc()))
                 .unwrap_or_else(|_| Utc::now()),
         })
     }
@@ -25,14 +25,23 @@ pub enum CryptofolioError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("ZIP archive error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
     #[error("Date parsing error: {0}")]
     DateParse(#[from] chrono::ParseError),
 
     #[error("Decimal parsing error: {0}")]
     DecimalParse(#[from] rust_decimal::Error),
 
-    #[error("Account not found: {0}")]
-    AccountNotFound(String),
+    #[error("Account '{name}' not found{}", did_you_mean(.suggestion))]
+    AccountNotFound {
+        name: String,
+        suggestion: Option<String>,
+    },
+
+    #[error("Account '{0}' already exists")]
+    AccountAlreadyExists(String),
 
     #[error("Category not found: {0}")]
     CategoryNotFound(String),
@@ -46,9 +55,21 @@ pub enum CryptofolioError {
         required: String,
     },
 
+    #[error("Holding changed since it was read: expected {expected}, found {actual}")]
+    HoldingChanged {
+        expected: String,
+        actual: String,
+    },
+
     #[error("Invalid amount: {0}")]
     InvalidAmount(String),
 
+    #[error("{value} has more than {max_decimals} decimal places (display.decimals); pass --allow-high-precision to keep it")]
+    PrecisionTooHigh {
+        value: String,
+        max_decimals: u32,
+    },
+
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
@@ -73,13 +94,16 @@ pub enum CryptofolioError {
     #[error("AI error: {0}")]
     Ai(String),
 
+    #[error("MCP server error: {0}")]
+    Mcp(String),
+
     #[error("Operation cancelled by user")]
     OperationCancelled,
 
     #[error("Keychain error: {0}")]
     Keychain(String),
 
-    #[error("Keychain not available on this platform. Supported: macOS")]
+    #[error("Keychain not available on this platform. Supported: macOS, Linux")]
     KeychainNotAvailable,
 
     #[error("Touch ID not available: {0}")]
@@ -94,8 +118,33 @@ pub enum CryptofolioError {
     #[error("Keychain access denied: {0}")]
     KeychainAccessDenied(String),
 
+    #[error("{failed} of {total} accounts failed to sync")]
+    SyncFailed {
+        failed: usize,
+        total: usize,
+    },
+
     #[error("{0}")]
     Other(String),
 }
 
+impl CryptofolioError {
+    /// Process exit status: 2 when a sync only partly failed, so cron jobs can
+    /// tell it from a complete failure, otherwise 1
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CryptofolioError::SyncFailed { failed, total } if failed < total => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Format an optional "did you mean" suffix for not-found errors
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(name) => format!(". Did you mean '{}'?", name),
+        None => String::new(),
+    }
+}
+
 pub type Result<T> = std::result::Result<T, CryptofolioError>;
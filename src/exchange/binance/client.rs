@@ -1,21 +1,28 @@
 #![allow(dead_code)]
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::{future, Stream, StreamExt};
 use hmac::{Hmac, Mac};
-use reqwest::Client;
+use rust_decimal::Decimal;
 use sha2::Sha256;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
 
 use super::endpoints::*;
 use super::models::*;
+use super::rate_limit::{RateLimitedClient, DEFAULT_MAX_RETRIES};
 use crate::error::{CryptofolioError, Result};
 use crate::exchange::models::{AccountBalance, MarketData, PriceData, Ticker24h};
 use crate::exchange::traits::Exchange;
 
 type HmacSha256 = Hmac<Sha256>;
 
+const HOUR_MILLIS: i64 = 60 * 60 * 1000;
+
 pub struct BinanceClient {
-    client: Client,
+    client: RateLimitedClient,
     base_url: String,
     api_key: Option<String>,
     api_secret: Option<String>,
@@ -31,7 +38,7 @@ impl BinanceClient {
         };
 
         Self {
-            client: Client::new(),
+            client: RateLimitedClient::new(DEFAULT_MAX_RETRIES),
             base_url,
             api_key,
             api_secret,
@@ -39,6 +46,12 @@ impl BinanceClient {
         }
     }
 
+    /// Limit the client to `per_second` requests a second
+    pub fn with_rate_limit(mut self, per_second: u32) -> Self {
+        self.client.set_requests_per_second(per_second);
+        self
+    }
+
     fn get_timestamp() -> u64 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -61,10 +74,7 @@ impl BinanceClient {
     async fn get_public<T: serde::de::DeserializeOwned>(&self, endpoint: &str) -> Result<T> {
         let url = format!("{}{}", self.base_url, endpoint);
 
-        let response = self.client
-            .get(&url)
-            .send()
-            .await?;
+        let response = self.client.send(|client| Ok(client.get(&url))).await?;
 
         if !response.status().is_success() {
             let error: BinanceError = response.json().await
@@ -82,11 +92,7 @@ impl BinanceClient {
     ) -> Result<T> {
         let url = format!("{}{}", self.base_url, endpoint);
 
-        let response = self.client
-            .get(&url)
-            .query(params)
-            .send()
-            .await?;
+        let response = self.client.send(|client| Ok(client.get(&url).query(params))).await?;
 
         if !response.status().is_success() {
             let error: BinanceError = response.json().await
@@ -101,17 +107,13 @@ impl BinanceClient {
         let api_key = self.api_key.as_ref()
             .ok_or_else(|| CryptofolioError::AuthRequired("API key not configured".into()))?;
 
-        let timestamp = Self::get_timestamp();
-        let query = format!("timestamp={}", timestamp);
-        let signature = self.sign(&query)?;
-
-        let url = format!("{}{}?{}&signature={}", self.base_url, endpoint, query, signature);
-
-        let response = self.client
-            .get(&url)
-            .header("X-MBX-APIKEY", api_key)
-            .send()
-            .await?;
+        // Signed per attempt, since a retry may come after the timestamp's window
+        let response = self.client.send(|client| {
+            let query = format!("timestamp={}", Self::get_timestamp());
+            let signature = self.sign(&query)?;
+            let url = format!("{}{}?{}&signature={}", self.base_url, endpoint, query, signature);
+            Ok(client.get(&url).header("X-MBX-APIKEY", api_key))
+        }).await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -139,8 +141,83 @@ impl BinanceClient {
         }
     }
 
+    /// Stream live prices for `symbols` from the combined mini ticker WebSocket.
+    ///
+    /// Each update is reported under the base asset, like `get_prices`. The
+    /// stream ends when the connection drops; callers reconnect as needed.
+    pub async fn subscribe_prices(&self, symbols: &[&str]) -> Result<impl Stream<Item = PriceData>> {
+        if symbols.is_empty() {
+            return Err(CryptofolioError::InvalidInput("No symbols to subscribe to".into()));
+        }
+
+        let streams: Vec<String> = symbols
+            .iter()
+            .map(|s| format!("{}@miniTicker", self.normalize_symbol(s).to_lowercase()))
+            .collect();
+        let base_url = if self.is_testnet { TESTNET_STREAM_URL } else { MAINNET_STREAM_URL };
+        let url = format!("{}?streams={}", base_url, streams.join("/"));
+
+        let (socket, _) = connect_async(url.as_str())
+            .await
+            .map_err(|e| CryptofolioError::ExchangeApi(format!("WebSocket connection failed: {}", e)))?;
+
+        Ok(socket
+            .take_while(|message| future::ready(message.is_ok()))
+            .filter_map(|message| {
+                let price = match message {
+                    Ok(Message::Text(text)) => {
+                        serde_json::from_str::<BinanceStreamMessage<BinanceMiniTicker>>(&text)
+                            .ok()
+                            .map(|event| PriceData {
+                                symbol: Self::extract_base_asset(&event.data.symbol),
+                                price: event.data.close_price,
+                            })
+                    }
+                    _ => None,
+                };
+                future::ready(price)
+            }))
+    }
+
+    /// Price of `symbol` in USDT at `date`: the open of the hourly candle containing it.
+    ///
+    /// Fails with `NotFound` when Binance has no candle that close to `date`,
+    /// e.g. because the pair was listed later.
+    pub async fn get_historical_price(&self, symbol: &str, date: DateTime<Utc>) -> Result<Decimal> {
+        let normalized = self.normalize_symbol(symbol);
+        let start = date.timestamp_millis() - date.timestamp_millis().rem_euclid(HOUR_MILLIS);
+        let start = start.to_string();
+
+        let klines: Vec<BinanceKline> = self.get_public_with_params(
+            KLINES,
+            &[("symbol", &normalized), ("interval", "1h"), ("startTime", &start), ("limit", "1")],
+        ).await?;
+
+        // Binance answers with the first candle after startTime, however far off it is
+        match klines.first() {
+            Some(kline) if kline.0 - date.timestamp_millis() < HOUR_MILLIS => Ok(kline.1),
+            _ => Err(CryptofolioError::NotFound(format!(
+                "{} price on {}",
+                normalized,
+                date.format("%Y-%m-%d %H:%M UTC")
+            ))),
+        }
+    }
+
+    /// Total (free plus locked) balance of one asset; zero when the account holds none
+    pub async fn get_single_balance(&self, asset: &str) -> Result<Decimal> {
+        let response: BinanceAccountResponse = self.get_signed(ACCOUNT).await?;
+
+        Ok(response
+            .balances
+            .into_iter()
+            .find(|b| b.asset.eq_ignore_ascii_case(asset))
+            .map(|b| b.free + b.locked)
+            .unwrap_or(Decimal::ZERO))
+    }
+
     /// Extract base asset from symbol
-    fn extract_base_asset(&self, symbol: &str) -> String {
+    fn extract_base_asset(symbol: &str) -> String {
         let symbol = symbol.to_uppercase();
         if symbol.ends_with("USDT") {
             symbol.trim_end_matches("USDT").to_string()
@@ -177,7 +254,7 @@ impl Exchange for BinanceClient {
         ).await?;
 
         Ok(PriceData {
-            symbol: self.extract_base_asset(&response.symbol),
+            symbol: Self::extract_base_asset(&response.symbol),
             price: response.price,
         })
     }
@@ -199,7 +276,7 @@ impl Exchange for BinanceClient {
             .into_iter()
             .filter(|p| normalized_symbols.contains(&p.symbol))
             .map(|p| PriceData {
-                symbol: self.extract_base_asset(&p.symbol),
+                symbol: Self::extract_base_asset(&p.symbol),
                 price: p.price,
             })
             .collect();
@@ -216,7 +293,7 @@ impl Exchange for BinanceClient {
         ).await?;
 
         Ok(Ticker24h {
-            symbol: self.extract_base_asset(&response.symbol),
+            symbol: Self::extract_base_asset(&response.symbol),
             price: response.last_price,
             price_change: response.price_change,
             price_change_percent: response.price_change_percent,
@@ -244,7 +321,7 @@ impl Exchange for BinanceClient {
 
         Ok(MarketData {
             symbol: normalized.clone(),
-            base_asset: self.extract_base_asset(&normalized),
+            base_asset: Self::extract_base_asset(&normalized),
             quote_asset: quote_asset.to_string(),
             price: ticker.price,
             ticker_24h: Some(ticker),
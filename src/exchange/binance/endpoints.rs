@@ -3,9 +3,14 @@
 pub const MAINNET_BASE_URL: &str = "https://api.binance.com";
 pub const TESTNET_BASE_URL: &str = "https://testnet.binance.vision";
 
+// Combined WebSocket streams
+pub const MAINNET_STREAM_URL: &str = "wss://stream.binance.com/stream";
+pub const TESTNET_STREAM_URL: &str = "wss://stream.testnet.binance.vision/stream";
+
 // Public endpoints
 pub const TICKER_PRICE: &str = "/api/v3/ticker/price";
 pub const TICKER_24H: &str = "/api/v3/ticker/24hr";
+pub const KLINES: &str = "/api/v3/klines";
 pub const EXCHANGE_INFO: &str = "/api/v3/exchangeInfo";
 
 // Private endpoints (require authentication)
@@ -2,6 +2,8 @@ mod alpha;
 mod client;
 mod endpoints;
 mod models;
+mod rate_limit;
 
 pub use alpha::BinanceAlphaClient;
 pub use client::BinanceClient;
+pub use rate_limit::set_retry_enabled;
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 use rust_decimal::Decimal;
+use serde::de::IgnoredAny;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -58,6 +59,41 @@ pub struct BinanceSymbolInfo {
     pub status: String,
 }
 
+/// Envelope of a message from a combined stream
+#[derive(Debug, Deserialize)]
+pub struct BinanceStreamMessage<T> {
+    pub stream: String,
+    pub data: T,
+}
+
+/// `<symbol>@miniTicker` stream event
+#[derive(Debug, Deserialize)]
+pub struct BinanceMiniTicker {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "c", deserialize_with = "deserialize_decimal")]
+    pub close_price: Decimal,
+}
+
+/// One `/api/v3/klines` candle, sent as an array; only the open time and price are kept
+#[derive(Debug, Deserialize)]
+pub struct BinanceKline(
+    /// Open time in milliseconds since the epoch
+    pub i64,
+    /// Open price
+    #[serde(deserialize_with = "deserialize_decimal")] pub Decimal,
+    IgnoredAny, // high
+    IgnoredAny, // low
+    IgnoredAny, // close
+    IgnoredAny, // volume
+    IgnoredAny, // close time
+    IgnoredAny, // quote volume
+    IgnoredAny, // trades
+    IgnoredAny, // taker buy base volume
+    IgnoredAny, // taker buy quote volume
+    IgnoredAny, // unused
+);
+
 #[derive(Debug, Deserialize)]
 pub struct BinanceError {
     pub code: i32,
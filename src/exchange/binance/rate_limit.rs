@@ -0,0 +1,242 @@
+//! Retries and pacing for Binance REST requests.
+//!
+//! Binance answers 429 when a client goes over its request weight, and 418
+//! (an IP ban) if it keeps going after that. Every response reports the
+//! weight used so far in the current minute, so requests also pause before
+//! reaching the limit rather than after. An account's `rate_limit` sync
+//! setting additionally spaces out every request the client sends.
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::error::{CryptofolioError, Result};
+
+/// Retries after a 429 before giving up
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// First wait when Binance sends no Retry-After; doubles with each retry
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest wait worth sitting through; a longer Retry-After fails right away
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Request weight Binance allows per IP per minute
+const WEIGHT_LIMIT_1M: u32 = 6000;
+
+/// Percent of the minute's weight after which requests wait for the next minute
+const WEIGHT_SLOWDOWN_PCT: u32 = 90;
+
+const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-1m";
+
+static RETRY_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Turn retrying of rate-limited requests on or off for this process (`--no-retry`)
+pub fn set_retry_enabled(enabled: bool) {
+    RETRY_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// HTTP client that retries 429 responses and paces requests by used weight
+pub(crate) struct RateLimitedClient {
+    client: Client,
+    max_retries: u32,
+    /// Weight Binance last reported as used, and the minute it was reported in
+    used_weight: AtomicU32,
+    weight_minute: AtomicU64,
+    /// Shortest gap between two requests, when the caller set a request rate
+    min_interval: Option<Duration>,
+    /// When the last request went out; held while waiting so requests queue
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl RateLimitedClient {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            client: Client::new(),
+            max_retries,
+            used_weight: AtomicU32::new(0),
+            weight_minute: AtomicU64::new(0),
+            min_interval: None,
+            last_request: Mutex::new(None),
+        }
+    }
+
+    /// Send at most `per_second` requests a second, retries included
+    pub fn set_requests_per_second(&mut self, per_second: u32) {
+        self.min_interval = (per_second > 0).then(|| Duration::from_secs(1) / per_second);
+    }
+
+    /// Send the request made by `build`, which runs again for each retry so
+    /// signed requests get a fresh timestamp
+    pub async fn send(&self, build: impl Fn(&Client) -> Result<RequestBuilder>) -> Result<Response> {
+        let max_retries = if RETRY_ENABLED.load(Ordering::Relaxed) { self.max_retries } else { 0 };
+        let mut attempt = 0;
+
+        loop {
+            let reported = (self.used_weight.load(Ordering::Relaxed), self.weight_minute.load(Ordering::Relaxed));
+            if let Some(pause) = weight_pause(reported, now_millis()) {
+                tokio::time::sleep(pause).await;
+            }
+            self.pace().await;
+
+            let response = build(&self.client)?.send().await?;
+            self.record_weight(response.headers());
+
+            match response.status() {
+                StatusCode::TOO_MANY_REQUESTS if attempt < max_retries => {
+                    let delay = retry_delay(response.headers(), attempt).ok_or(CryptofolioError::RateLimitExceeded)?;
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                StatusCode::TOO_MANY_REQUESTS => return Err(CryptofolioError::RateLimitExceeded),
+                StatusCode::IM_A_TEAPOT => {
+                    let wait = retry_after(response.headers())
+                        .map(|d| format!("; retry after {}s", d.as_secs()))
+                        .unwrap_or_default();
+                    return Err(CryptofolioError::ExchangeApi(format!(
+                        "Binance has temporarily banned this IP for exceeding rate limits{}",
+                        wait
+                    )));
+                }
+                _ => return Ok(response),
+            }
+        }
+    }
+
+    /// Wait until `min_interval` has passed since the previous request
+    async fn pace(&self) {
+        let Some(interval) = self.min_interval else {
+            return;
+        };
+        let mut last = self.last_request.lock().await;
+        if let Some(last) = *last {
+            tokio::time::sleep_until(last + interval).await;
+        }
+        *last = Some(Instant::now());
+    }
+
+    fn record_weight(&self, headers: &HeaderMap) {
+        let used = headers
+            .get(USED_WEIGHT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+        if let Some(used) = used {
+            self.used_weight.store(used, Ordering::Relaxed);
+            self.weight_minute.store(now_millis() / 60_000, Ordering::Relaxed);
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+/// How long to wait before retry number `attempt + 1`: Retry-After when
+/// given, exponential backoff otherwise. `None` when that is too long to wait.
+fn retry_delay(headers: &HeaderMap, attempt: u32) -> Option<Duration> {
+    let delay = retry_after(headers).unwrap_or_else(|| BASE_RETRY_DELAY * 2u32.saturating_pow(attempt));
+    (delay <= MAX_RETRY_DELAY).then_some(delay)
+}
+
+/// Time left in the current minute once the weight reported during it is
+/// close to the limit; Binance resets the count every minute
+fn weight_pause((used, minute): (u32, u64), now_ms: u64) -> Option<Duration> {
+    let near_limit = used >= WEIGHT_LIMIT_1M * WEIGHT_SLOWDOWN_PCT / 100;
+    (near_limit && minute == now_ms / 60_000).then(|| Duration::from_millis(60_000 - now_ms % 60_000))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use std::sync::Arc;
+
+    fn headers(retry_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(retry_after).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(&headers("7"), 0), Some(Duration::from_secs(7)));
+        assert_eq!(retry_delay(&HeaderMap::new(), 0), Some(Duration::from_secs(1)));
+        assert_eq!(retry_delay(&HeaderMap::new(), 2), Some(Duration::from_secs(4)));
+        assert_eq!(retry_delay(&headers("600"), 0), None);
+    }
+
+    #[test]
+    fn test_weight_pause() {
+        let now_ms = 5 * 60_000 + 45_000;
+        assert_eq!(weight_pause((5900, 5), now_ms), Some(Duration::from_secs(15)));
+        assert_eq!(weight_pause((100, 5), now_ms), None);
+        // Weight reported in an earlier minute has already been reset
+        assert_eq!(weight_pause((5900, 4), now_ms), None);
+    }
+
+    #[tokio::test]
+    async fn test_retries_429_then_succeeds() {
+        use axum::response::IntoResponse;
+        use std::sync::atomic::AtomicUsize;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::get(move || {
+                let counter = counter.clone();
+                async move {
+                    if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                        (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, "0")]).into_response()
+                    } else {
+                        "ok".into_response()
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = RateLimitedClient::new(DEFAULT_MAX_RETRIES);
+        let response = client.send(|c| Ok(c.get(&url))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let no_retries = RateLimitedClient::new(0);
+        calls.store(0, Ordering::SeqCst);
+        let err = no_retries.send(|c| Ok(c.get(&url))).await.unwrap_err();
+        assert!(matches!(err, CryptofolioError::RateLimitExceeded));
+    }
+
+    #[tokio::test]
+    async fn test_requests_per_second_spaces_requests() {
+        let app = axum::Router::new().route("/", axum::routing::get(|| async { "ok" }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut client = RateLimitedClient::new(0);
+        client.set_requests_per_second(20);
+        let start = Instant::now();
+        for _ in 0..3 {
+            client.send(|c| Ok(c.get(&url))).await.unwrap();
+        }
+        // The second and third requests each wait 50ms
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,124 @@
+//! Minimal Electrum protocol client over plain TCP
+//!
+//! Electrum servers speak newline-delimited JSON-RPC and index outputs by
+//! "script hash": the SHA256 of the output script, byte-reversed and hex encoded.
+
+use async_trait::async_trait;
+use bitcoin::Address;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+use super::{AddressActivity, BitcoinBackend};
+use crate::error::{CryptofolioError, Result};
+
+/// Protocol version sent in the `server.version` handshake
+const PROTOCOL_VERSION: &str = "1.4";
+
+#[derive(Debug, Deserialize)]
+struct ElectrumResponse {
+    result: Option<Value>,
+    error: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScripthashBalance {
+    confirmed: i64,
+    unconfirmed: i64,
+}
+
+pub struct ElectrumClient {
+    stream: Mutex<BufReader<TcpStream>>,
+}
+
+impl ElectrumClient {
+    /// Connect to `host:port`, optionally prefixed with `tcp://`
+    pub async fn connect(url: &str) -> Result<Self> {
+        if url.starts_with("ssl://") {
+            return Err(CryptofolioError::Config(
+                "SSL Electrum connections are not supported; use a tcp:// server".into(),
+            ));
+        }
+        let address = url.trim_start_matches("tcp://");
+
+        let stream = TcpStream::connect(address).await.map_err(|e| {
+            CryptofolioError::ExchangeApi(format!("Could not connect to Electrum server {}: {}", address, e))
+        })?;
+
+        let client = Self {
+            stream: Mutex::new(BufReader::new(stream)),
+        };
+        client
+            .call("server.version", json!([concat!("cryptofolio ", env!("CARGO_PKG_VERSION")), PROTOCOL_VERSION]))
+            .await?;
+
+        Ok(client)
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let mut stream = self.stream.lock().await;
+
+        let mut request = serde_json::to_string(&json!({ "jsonrpc": "2.0", "id": 0, "method": method, "params": params }))?;
+        request.push('\n');
+        stream.get_mut().write_all(request.as_bytes()).await?;
+
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 {
+            return Err(CryptofolioError::ExchangeApi("Electrum server closed the connection".into()));
+        }
+
+        let response: ElectrumResponse = serde_json::from_str(&line)?;
+        if let Some(error) = response.error {
+            return Err(CryptofolioError::ExchangeApi(format!("Electrum error in {}: {}", method, error)));
+        }
+        Ok(response.result.unwrap_or(Value::Null))
+    }
+}
+
+/// Electrum's index key for an address's output script
+pub fn script_hash(address: &Address) -> String {
+    let mut hash = Sha256::digest(address.script_pubkey().as_bytes()).to_vec();
+    hash.reverse();
+    hex::encode(hash)
+}
+
+#[async_trait]
+impl BitcoinBackend for ElectrumClient {
+    async fn address_activity(&self, address: &Address) -> Result<AddressActivity> {
+        let hash = script_hash(address);
+
+        let balance: ScripthashBalance =
+            serde_json::from_value(self.call("blockchain.scripthash.get_balance", json!([hash])).await?)?;
+        let confirmed_sats = u64::try_from(balance.confirmed).unwrap_or(0);
+
+        let used = if balance.confirmed != 0 || balance.unconfirmed != 0 {
+            true
+        } else {
+            let history = self.call("blockchain.scripthash.get_history", json!([hash])).await?;
+            history.as_array().is_some_and(|h| !h.is_empty())
+        };
+
+        Ok(AddressActivity { confirmed_sats, used })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_script_hash() {
+        // Example from the Electrum protocol documentation
+        let address = Address::from_str("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa")
+            .unwrap()
+            .assume_checked();
+        assert_eq!(
+            script_hash(&address),
+            "8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161"
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+use bitcoin::Address;
+use reqwest::Client;
+use serde::Deserialize;
+
+use super::{AddressActivity, BitcoinBackend};
+use crate::error::Result;
+
+#[derive(Debug, Deserialize)]
+struct Utxo {
+    value: u64,
+    status: UtxoStatus,
+}
+
+#[derive(Debug, Deserialize)]
+struct UtxoStatus {
+    confirmed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddressStats {
+    chain_stats: TxStats,
+    mempool_stats: TxStats,
+}
+
+#[derive(Debug, Deserialize)]
+struct TxStats {
+    tx_count: u64,
+}
+
+/// Client for an Esplora block explorer API such as Blockstream.info
+pub struct EsploraClient {
+    client: Client,
+    base_url: String,
+}
+
+impl EsploraClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}{}", self.base_url, path);
+        Ok(self.client.get(&url).send().await?.error_for_status()?.json().await?)
+    }
+}
+
+#[async_trait]
+impl BitcoinBackend for EsploraClient {
+    async fn address_activity(&self, address: &Address) -> Result<AddressActivity> {
+        let utxos: Vec<Utxo> = self.get(&format!("/address/{}/utxo", address)).await?;
+
+        let confirmed_sats = utxos.iter().filter(|u| u.status.confirmed).map(|u| u.value).sum();
+        if !utxos.is_empty() {
+            return Ok(AddressActivity { confirmed_sats, used: true });
+        }
+
+        // No unspent outputs: the address is either fresh or fully spent
+        let stats: AddressStats = self.get(&format!("/address/{}", address)).await?;
+        Ok(AddressActivity {
+            confirmed_sats: 0,
+            used: stats.chain_stats.tx_count + stats.mempool_stats.tx_count > 0,
+        })
+    }
+}
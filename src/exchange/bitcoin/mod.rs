@@ -0,0 +1,134 @@
+//! Bitcoin on-chain support: confirmed balances for addresses and extended public keys
+
+pub mod electrum;
+pub mod esplora;
+pub mod xpub;
+
+use async_trait::async_trait;
+use bitcoin::{Address, Network};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+pub use electrum::ElectrumClient;
+pub use esplora::EsploraClient;
+pub use xpub::{is_extended_public_key, ExtendedPublicKey};
+
+use crate::error::{CryptofolioError, Result};
+
+/// Satoshis per bitcoin, as a decimal scale
+const BTC_DECIMALS: u32 = 8;
+
+/// What a backend knows about one address
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AddressActivity {
+    pub confirmed_sats: u64,
+    /// Whether the address has ever appeared in a transaction
+    pub used: bool,
+}
+
+/// A source of on-chain address data (an Electrum server or a block explorer)
+#[async_trait]
+pub trait BitcoinBackend: Send + Sync {
+    async fn address_activity(&self, address: &Address) -> Result<AddressActivity>;
+}
+
+/// Whether a wallet address's blockchain name refers to Bitcoin
+pub fn is_bitcoin(blockchain: &str) -> bool {
+    matches!(blockchain.to_lowercase().as_str(), "bitcoin" | "btc")
+}
+
+/// Convert satoshis to BTC
+pub fn sats_to_btc(sats: u64) -> Decimal {
+    Decimal::from_i128_with_scale(sats as i128, BTC_DECIMALS).normalize()
+}
+
+/// Total confirmed balance in satoshis of plain mainnet addresses and extended
+/// public keys. Each key's receive and change chains are scanned until
+/// `gap_limit` consecutive addresses have never been used.
+pub async fn confirmed_balance(backend: &dyn BitcoinBackend, entries: &[String], gap_limit: u32) -> Result<u64> {
+    let mut total = 0u64;
+
+    for entry in entries {
+        if is_extended_public_key(entry) {
+            let key = ExtendedPublicKey::parse(entry)?;
+
+            for chain in [0, 1] {
+                let mut unused = 0;
+                let mut index = 0;
+                while unused < gap_limit {
+                    let activity = backend.address_activity(&key.derive_address(chain, index)?).await?;
+                    total += activity.confirmed_sats;
+                    unused = if activity.used { 0 } else { unused + 1 };
+                    index += 1;
+                }
+            }
+        } else {
+            total += backend.address_activity(&parse_address(entry)?).await?.confirmed_sats;
+        }
+    }
+
+    Ok(total)
+}
+
+/// Parse a mainnet Bitcoin address
+pub fn parse_address(address: &str) -> Result<Address> {
+    Address::from_str(address.trim())
+        .and_then(|a| a.require_network(Network::Bitcoin))
+        .map_err(|e| CryptofolioError::InvalidInput(format!("Invalid Bitcoin address '{}': {}", address, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    /// Serves fixed activity per address; everything else is unused
+    struct FixedBackend(HashMap<String, AddressActivity>);
+
+    #[async_trait]
+    impl BitcoinBackend for FixedBackend {
+        async fn address_activity(&self, address: &Address) -> Result<AddressActivity> {
+            Ok(self.0.get(&address.to_string()).copied().unwrap_or_default())
+        }
+    }
+
+    const ZPUB: &str = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+
+    #[test]
+    fn test_sats_to_btc() {
+        assert_eq!(sats_to_btc(150_000_000), Decimal::from_str("1.5").unwrap());
+        assert_eq!(sats_to_btc(1), Decimal::from_str("0.00000001").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_balance_scans_past_used_addresses() {
+        let key = ExtendedPublicKey::parse(ZPUB).unwrap();
+        let used = |sats| AddressActivity { confirmed_sats: sats, used: true };
+
+        // A funded address beyond a short gap, and an emptied one before it
+        let backend = FixedBackend(HashMap::from([
+            (key.derive_address(0, 1).unwrap().to_string(), used(0)),
+            (key.derive_address(0, 3).unwrap().to_string(), used(50_000)),
+            (key.derive_address(1, 0).unwrap().to_string(), used(25_000)),
+            // Beyond the gap limit, so never reached
+            (key.derive_address(0, 9).unwrap().to_string(), used(1_000_000)),
+        ]));
+
+        let total = confirmed_balance(&backend, &[ZPUB.to_string()], 3).await.unwrap();
+        assert_eq!(total, 75_000);
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_balance_plain_addresses() {
+        let backend = FixedBackend(HashMap::from([(
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu".to_string(),
+            AddressActivity { confirmed_sats: 10_000, used: true },
+        )]));
+
+        let entries = vec!["bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu".to_string()];
+        assert_eq!(confirmed_balance(&backend, &entries, 20).await.unwrap(), 10_000);
+
+        assert!(confirmed_balance(&backend, &["not-an-address".to_string()], 20).await.is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! Address derivation from extended public keys (BIP32)
+//!
+//! The key's version prefix picks the address type, following the common
+//! wallet convention: xpub for legacy BIP44 (P2PKH), ypub for BIP49
+//! (P2SH-wrapped segwit) and zpub for BIP84 (native segwit).
+
+use bitcoin::base58;
+use bitcoin::bip32::{ChildNumber, Xpub};
+use bitcoin::secp256k1::{Secp256k1, VerifyOnly};
+use bitcoin::{Address, Network};
+
+use crate::error::{CryptofolioError, Result};
+
+const XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+const YPUB_VERSION: [u8; 4] = [0x04, 0x9d, 0x7c, 0xb2];
+const ZPUB_VERSION: [u8; 4] = [0x04, 0xb2, 0x47, 0x46];
+
+/// Serialized extended keys are 78 bytes before the checksum
+const EXTENDED_KEY_LEN: usize = 78;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptType {
+    /// BIP44 legacy addresses (1...)
+    P2pkh,
+    /// BIP49 segwit wrapped in P2SH (3...)
+    P2shP2wpkh,
+    /// BIP84 native segwit (bc1q...)
+    P2wpkh,
+}
+
+/// A mainnet account-level extended public key
+pub struct ExtendedPublicKey {
+    xpub: Xpub,
+    script_type: ScriptType,
+    secp: Secp256k1<VerifyOnly>,
+}
+
+/// Whether `value` looks like an xpub, ypub or zpub rather than an address
+pub fn is_extended_public_key(value: &str) -> bool {
+    ["xpub", "ypub", "zpub"].iter().any(|p| value.trim().starts_with(p))
+}
+
+impl ExtendedPublicKey {
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            CryptofolioError::InvalidInput(format!("Invalid extended public key: {}", reason))
+        };
+
+        let mut data = base58::decode_check(value.trim()).map_err(|_| invalid("bad Base58Check encoding"))?;
+        if data.len() != EXTENDED_KEY_LEN {
+            return Err(invalid("unexpected length"));
+        }
+
+        let script_type = match [data[0], data[1], data[2], data[3]] {
+            XPUB_VERSION => ScriptType::P2pkh,
+            YPUB_VERSION => ScriptType::P2shP2wpkh,
+            ZPUB_VERSION => ScriptType::P2wpkh,
+            _ => return Err(invalid("expected a mainnet xpub, ypub or zpub")),
+        };
+
+        // ypub/zpub differ from xpub only in the version bytes
+        data[..4].copy_from_slice(&XPUB_VERSION);
+        let xpub = Xpub::decode(&data).map_err(|e| invalid(&e.to_string()))?;
+
+        Ok(Self {
+            xpub,
+            script_type,
+            secp: Secp256k1::verification_only(),
+        })
+    }
+
+    pub fn script_type(&self) -> ScriptType {
+        self.script_type
+    }
+
+    /// Derive the address at `chain/index` (chain 0 receives, chain 1 is change)
+    pub fn derive_address(&self, chain: u32, index: u32) -> Result<Address> {
+        let path = [chain, index]
+            .into_iter()
+            .map(ChildNumber::from_normal_idx)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| CryptofolioError::InvalidInput(e.to_string()))?;
+
+        let child = self
+            .xpub
+            .derive_pub(&self.secp, &path)
+            .map_err(|e| CryptofolioError::InvalidInput(format!("Key derivation failed: {}", e)))?;
+        let public_key = child.to_pub();
+
+        Ok(match self.script_type {
+            ScriptType::P2pkh => Address::p2pkh(public_key.pubkey_hash(), Network::Bitcoin),
+            ScriptType::P2shP2wpkh => Address::p2shwpkh(&public_key, Network::Bitcoin),
+            ScriptType::P2wpkh => Address::p2wpkh(&public_key, Network::Bitcoin),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Account keys for the "abandon ... about" test mnemonic
+    const XPUB: &str = "xpub6BosfCnifzxcFwrSzQiqu2DBVTshkCXacvNsWGYJVVhhawA7d4R5WSWGFNbi8Aw6ZRc1brxMyWMzG3DSSSSoekkudhUd9yLb6qx39T9nMdj";
+    const YPUB: &str = "ypub6Ww3ibxVfGzLrAH1PNcjyAWenMTbbAosGNB6VvmSEgytSER9azLDWCxoJwW7Ke7icmizBMXrzBx9979FfaHxHcrArf3zbeJJJUZPf663zsP";
+    const ZPUB: &str = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+
+    #[test]
+    fn test_derive_addresses_for_each_script_type() {
+        let xpub = ExtendedPublicKey::parse(XPUB).unwrap();
+        assert_eq!(xpub.derive_address(0, 0).unwrap().to_string(), "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA");
+
+        let ypub = ExtendedPublicKey::parse(YPUB).unwrap();
+        assert_eq!(ypub.derive_address(0, 0).unwrap().to_string(), "37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf");
+
+        let zpub = ExtendedPublicKey::parse(ZPUB).unwrap();
+        assert_eq!(zpub.script_type(), ScriptType::P2wpkh);
+        assert_eq!(zpub.derive_address(0, 0).unwrap().to_string(), "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu");
+        assert_eq!(zpub.derive_address(1, 0).unwrap().to_string(), "bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el");
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_keys() {
+        assert!(is_extended_public_key(ZPUB));
+        assert!(!is_extended_public_key("bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"));
+
+        // Checksum broken by changing the last character
+        let mut broken = ZPUB.to_string();
+        broken.pop();
+        broken.push('t');
+        assert!(ExtendedPublicKey::parse(&broken).is_err());
+        assert!(ExtendedPublicKey::parse("xpub123").is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! ERC-20 token list and `eth_call` encoding for `balanceOf` lookups
+
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+
+use crate::error::{CryptofolioError, Result};
+use crate::exchange::models::TokenContract;
+
+/// Widely held ERC-20 tokens on Ethereum mainnet, checked on every wallet sync
+/// as (symbol, contract address)
+pub const DEFAULT_ERC20_TOKENS: &[(&str, &str)] = &[
+    ("USDT", "0xdAC17F958D2ee523a2206206994597C13D831ec7"),
+    ("USDC", "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+    ("DAI", "0x6B175474E89094C44Da98b954EedeAC495271d0F"),
+    ("WETH", "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+    ("LINK", "0x514910771AF9Ca656af840dff83E8264EcF986CA"),
+    ("WBTC", "0x2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599"),
+    ("STETH", "0xae7ab96520DE3A18E5e111B5EaAb095312D7fE84"),
+    ("UNI", "0x1f9840a85d5aF5bf1D1762F925BDADdC4201F984"),
+    ("SHIB", "0x95aD61b0a150d79219dCF64E1E6Cc01f0B64C4cE"),
+    ("PEPE", "0x6982508145454Ce325dDbE47a25d4ec3d2311933"),
+    ("BNB", "0xB8c77482e45F1F44dE1745F52C74426C631bDD52"),
+    ("MATIC", "0x7D1AfA7B718fb893dB30A3aBc0Cfc608AaCfeBB0"),
+    ("AAVE", "0x7Fc66500c84A76Ad7e9c93437bFc5Ac33E2DDaE9"),
+    ("MKR", "0x9f8F72aA9304c8B593d555F12eF6589cC3A579A2"),
+    ("LDO", "0x5A98FcBEA516Cf06857215779Fd812CA3beF1B32"),
+    ("ARB", "0xB50721BCf8d664c30412Cfbc6cf7a15145234ad1"),
+    ("CRV", "0xD533a949740bb3306d119CC777fa900bA034cd52"),
+    ("GRT", "0xc944E90C64B2c07662A292be6244BDf05Cda44a7"),
+    ("APE", "0x4d224452801ACEd8B2F0aebE155379bb5D594381"),
+    ("TUSD", "0x0000000000085d4780B73119b644AE5ecd22b376"),
+];
+
+/// Function selector for `balanceOf(address)`
+pub const BALANCE_OF_SELECTOR: &str = "0x70a08231";
+
+/// Function selector for `decimals()`
+pub const DECIMALS_SELECTOR: &str = "0x313ce567";
+
+/// Check that `address` is a 0x-prefixed, 20-byte hex Ethereum address
+pub fn is_valid_address(address: &str) -> bool {
+    address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// The default token list plus any configured `SYMBOL=0xCONTRACT` entries.
+/// A configured symbol replaces the default contract for that symbol.
+pub fn token_list(configured: Option<&str>) -> Result<Vec<TokenContract>> {
+    let mut tokens: Vec<TokenContract> = DEFAULT_ERC20_TOKENS
+        .iter()
+        .map(|(symbol, address)| TokenContract {
+            symbol: symbol.to_string(),
+            address: address.to_string(),
+        })
+        .collect();
+
+    for entry in configured.unwrap_or_default().split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (symbol, address) = entry
+            .split_once('=')
+            .map(|(s, a)| (s.trim().to_uppercase(), a.trim().to_string()))
+            .filter(|(s, a)| !s.is_empty() && is_valid_address(a))
+            .ok_or_else(|| {
+                CryptofolioError::Config(format!(
+                    "Invalid ethereum.tokens entry '{}': expected SYMBOL=0xCONTRACT",
+                    entry
+                ))
+            })?;
+
+        tokens.retain(|t| t.symbol != symbol);
+        tokens.push(TokenContract { symbol, address });
+    }
+
+    Ok(tokens)
+}
+
+/// Build the `eth_call` data for `balanceOf(owner)`
+pub fn balance_of_calldata(owner: &str) -> Result<String> {
+    if !is_valid_address(owner) {
+        return Err(CryptofolioError::InvalidInput(format!("Invalid Ethereum address: {}", owner)));
+    }
+
+    // The address argument is left-padded to a 32-byte word
+    Ok(format!("{}{:0>64}", BALANCE_OF_SELECTOR, owner[2..].to_lowercase()))
+}
+
+/// Decode a hex `uint256` returned by `eth_call`
+pub fn decode_uint(hex: &str) -> Result<u128> {
+    let digits = hex.trim_start_matches("0x").trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+
+    u128::from_str_radix(digits, 16)
+        .map_err(|_| CryptofolioError::InvalidAmount(format!("Unexpected uint256 value: {}", hex)))
+}
+
+/// Convert a raw token amount into whole units using the token's decimals
+pub fn to_token_amount(raw: u128, decimals: u32) -> Result<Decimal> {
+    let unit = 10u128
+        .checked_pow(decimals)
+        .ok_or_else(|| CryptofolioError::InvalidAmount(format!("Unsupported token decimals: {}", decimals)))?;
+
+    // Split first so large 18-decimal balances stay within Decimal's 96-bit range
+    let whole = Decimal::from_u128(raw / unit)
+        .ok_or_else(|| CryptofolioError::InvalidAmount(raw.to_string()))?;
+    let fraction = Decimal::try_from_i128_with_scale((raw % unit) as i128, decimals)
+        .map_err(|_| CryptofolioError::InvalidAmount(raw.to_string()))?;
+
+    Ok((whole + fraction).normalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_default_tokens_are_valid_addresses() {
+        assert!(DEFAULT_ERC20_TOKENS.len() >= 20);
+        for (symbol, address) in DEFAULT_ERC20_TOKENS {
+            assert!(is_valid_address(address), "{} has an invalid address", symbol);
+        }
+    }
+
+    #[test]
+    fn test_token_list_with_configured_tokens() {
+        let tokens = token_list(None).unwrap();
+        assert_eq!(tokens.len(), DEFAULT_ERC20_TOKENS.len());
+
+        let tokens = token_list(Some("ens=0xC18360217D8F7Ab5e7c516566761Ea12Ce7F9D72, USDC=0x0000000000000000000000000000000000000001")).unwrap();
+        assert_eq!(tokens.len(), DEFAULT_ERC20_TOKENS.len() + 1);
+        assert!(tokens.iter().any(|t| t.symbol == "ENS"));
+        let usdc: Vec<_> = tokens.iter().filter(|t| t.symbol == "USDC").collect();
+        assert_eq!(usdc.len(), 1);
+        assert_eq!(usdc[0].address, "0x0000000000000000000000000000000000000001");
+
+        assert!(token_list(Some("ENS")).is_err());
+        assert!(token_list(Some("ENS=0x1234")).is_err());
+    }
+
+    #[test]
+    fn test_balance_of_calldata() {
+        let data = balance_of_calldata("0xAbCdEf0123456789abcdef0123456789ABCDEF01").unwrap();
+        assert_eq!(
+            data,
+            "0x70a08231000000000000000000000000abcdef0123456789abcdef0123456789abcdef01"
+        );
+        assert!(balance_of_calldata("0x1234").is_err());
+    }
+
+    #[test]
+    fn test_decode_and_scale_balance() {
+        // 1.5 USDC with 6 decimals
+        let raw = decode_uint("0x000000000000000000000000000000000000000000000000000000000016e360").unwrap();
+        assert_eq!(raw, 1_500_000);
+        assert_eq!(to_token_amount(raw, 6).unwrap(), Decimal::from_str("1.5").unwrap());
+
+        assert_eq!(decode_uint("0x").unwrap(), 0);
+
+        // Large 18-decimal balances exceed Decimal's mantissa before scaling
+        let shib = 500_000_000_000_000u128 * 10u128.pow(18);
+        assert_eq!(to_token_amount(shib, 18).unwrap(), Decimal::from(500_000_000_000_000u64));
+    }
+}
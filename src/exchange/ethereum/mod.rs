@@ -0,0 +1,12 @@
+//! Ethereum on-chain support
+
+pub mod erc20;
+pub mod rpc;
+
+pub use erc20::DEFAULT_ERC20_TOKENS;
+pub use rpc::EthereumRpcClient;
+
+/// Whether a wallet address's blockchain name refers to Ethereum mainnet
+pub fn is_ethereum(blockchain: &str) -> bool {
+    matches!(blockchain.to_lowercase().as_str(), "ethereum" | "eth")
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::erc20::{balance_of_calldata, decode_uint, to_token_amount, DECIMALS_SELECTOR};
+use crate::error::{CryptofolioError, Result};
+use crate::exchange::models::{ChainBalance, TokenContract};
+use crate::exchange::traits::BlockchainProvider;
+
+/// Ether is denominated in wei, 10^18 per ETH
+const ETH_DECIMALS: u32 = 18;
+
+#[derive(Serialize)]
+struct RpcRequest<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<String>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+/// Client for an Ethereum JSON-RPC endpoint
+pub struct EthereumRpcClient {
+    client: Client,
+    rpc_url: String,
+}
+
+impl EthereumRpcClient {
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            rpc_url: rpc_url.into(),
+        }
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<String> {
+        let request = RpcRequest {
+            jsonrpc: "2.0",
+            id: 1,
+            method,
+            params,
+        };
+
+        let response: RpcResponse = self
+            .client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.error {
+            return Err(CryptofolioError::ExchangeApi(format!(
+                "Ethereum RPC error {} in {}: {}",
+                error.code, method, error.message
+            )));
+        }
+
+        response
+            .result
+            .ok_or_else(|| CryptofolioError::ExchangeApi(format!("Ethereum RPC returned no result for {}", method)))
+    }
+
+    /// Call a read-only contract function at the latest block
+    async fn eth_call(&self, contract: &str, data: &str) -> Result<String> {
+        self.call("eth_call", json!([{ "to": contract, "data": data }, "latest"]))
+            .await
+    }
+
+    async fn token_decimals(&self, contract: &str) -> Result<u32> {
+        let raw = decode_uint(&self.eth_call(contract, DECIMALS_SELECTOR).await?)?;
+        u32::try_from(raw)
+            .map_err(|_| CryptofolioError::InvalidAmount(format!("Unexpected decimals for {}: {}", contract, raw)))
+    }
+}
+
+#[async_trait]
+impl BlockchainProvider for EthereumRpcClient {
+    fn native_asset(&self) -> &str {
+        "ETH"
+    }
+
+    async fn get_native_balance(&self, address: &str) -> Result<Decimal> {
+        let raw = decode_uint(&self.call("eth_getBalance", json!([address, "latest"])).await?)?;
+        to_token_amount(raw, ETH_DECIMALS)
+    }
+
+    async fn get_token_balances(&self, address: &str, tokens: &[TokenContract]) -> Result<Vec<ChainBalance>> {
+        let calldata = balance_of_calldata(address)?;
+        let mut balances = Vec::new();
+
+        for token in tokens {
+            let raw = decode_uint(&self.eth_call(&token.address, &calldata).await?)?;
+            // Most tokens are never held, so only look up decimals when needed
+            if raw == 0 {
+                continue;
+            }
+
+            let decimals = self.token_decimals(&token.address).await?;
+            balances.push(ChainBalance {
+                asset: token.symbol.clone(),
+                quantity: to_token_amount(raw, decimals)?,
+            });
+        }
+
+        Ok(balances)
+    }
+}
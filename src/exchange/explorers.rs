@@ -0,0 +1,55 @@
+//! Block explorer URLs for wallet addresses
+
+/// Explorer address-page URL prefixes keyed by blockchain name or ticker
+const EXPLORERS: &[(&[&str], &str)] = &[
+    (&["bitcoin", "btc"], "https://mempool.space/address/"),
+    (&["ethereum", "eth"], "https://etherscan.io/address/"),
+    (&["solana", "sol"], "https://solscan.io/account/"),
+    (&["polygon", "matic", "pol"], "https://polygonscan.com/address/"),
+    (&["bsc", "bnb", "binance-smart-chain", "bnb-chain"], "https://bscscan.com/address/"),
+    (&["arbitrum", "arb"], "https://arbiscan.io/address/"),
+    (&["optimism", "op"], "https://optimistic.etherscan.io/address/"),
+    (&["base"], "https://basescan.org/address/"),
+    (&["avalanche", "avax"], "https://snowtrace.io/address/"),
+    (&["litecoin", "ltc"], "https://litecoinspace.org/address/"),
+    (&["dogecoin", "doge"], "https://blockchair.com/dogecoin/address/"),
+    (&["cardano", "ada"], "https://cardanoscan.io/address/"),
+    (&["tron", "trx"], "https://tronscan.org/#/address/"),
+    (&["xrp", "ripple"], "https://xrpscan.com/account/"),
+];
+
+/// URL of the explorer page for an address, if the blockchain is known
+pub fn address_url(blockchain: &str, address: &str) -> Option<String> {
+    let blockchain = blockchain.trim().to_lowercase();
+
+    EXPLORERS
+        .iter()
+        .find(|(names, _)| names.contains(&blockchain.as_str()))
+        .map(|(_, prefix)| format!("{}{}", prefix, address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_url_known_chains() {
+        assert_eq!(
+            address_url("ethereum", "0xabc"),
+            Some("https://etherscan.io/address/0xabc".to_string())
+        );
+        assert_eq!(
+            address_url("BTC", "bc1q"),
+            Some("https://mempool.space/address/bc1q".to_string())
+        );
+        assert_eq!(
+            address_url("Solana", "So1"),
+            Some("https://solscan.io/account/So1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_address_url_unknown_chain() {
+        assert_eq!(address_url("unknownchain", "addr"), None);
+    }
+}
@@ -0,0 +1,106 @@
+//! Live exchange rates between fiat currencies.
+//!
+//! Rates follow the convention of `currency set-rate`: how many units of the
+//! first currency buy one unit of the second (550 CRC per 1 USD).
+
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::error::{CryptofolioError, Result};
+
+const FRANKFURTER_API_URL: &str = "https://api.frankfurter.app";
+
+#[async_trait]
+pub trait FiatRateProvider: Send + Sync {
+    /// Name stored as the `source` of fetched rates
+    fn name(&self) -> &str;
+
+    /// How many `from` per 1 `to`
+    async fn get_rate(&self, from: &str, to: &str) -> Result<Decimal>;
+}
+
+/// Reference rates published by the European Central Bank, via Frankfurter
+pub struct FrankfurterClient {
+    client: Client,
+    base_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LatestRates {
+    rates: HashMap<String, f64>,
+}
+
+impl FrankfurterClient {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            base_url: FRANKFURTER_API_URL.to_string(),
+        }
+    }
+}
+
+impl Default for FrankfurterClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl FiatRateProvider for FrankfurterClient {
+    fn name(&self) -> &str {
+        "frankfurter"
+    }
+
+    async fn get_rate(&self, from: &str, to: &str) -> Result<Decimal> {
+        let (from, to) = (from.to_uppercase(), to.to_uppercase());
+        if from == to {
+            return Ok(Decimal::ONE);
+        }
+
+        // Quoted per one unit of the base, so the base is our `to`
+        let response = self
+            .client
+            .get(format!("{}/latest", self.base_url))
+            .query(&[("from", to.as_str()), ("to", from.as_str())])
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => rate_from_response(response.json().await?, &from, &to),
+            StatusCode::NOT_FOUND | StatusCode::UNPROCESSABLE_ENTITY => Err(CryptofolioError::NotFound(format!(
+                "Exchange rate for {}/{} (Frankfurter only covers ECB reference currencies)",
+                from, to
+            ))),
+            status => Err(CryptofolioError::ExchangeApi(format!("Frankfurter API error: {}", status))),
+        }
+    }
+}
+
+fn rate_from_response(response: LatestRates, from: &str, to: &str) -> Result<Decimal> {
+    response
+        .rates
+        .get(from)
+        .and_then(|r| Decimal::from_f64(*r))
+        .filter(|r| *r > Decimal::ZERO)
+        .ok_or_else(|| CryptofolioError::NotFound(format!("Exchange rate for {}/{}", from, to)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_from_response() {
+        let response: LatestRates =
+            serde_json::from_str(r#"{"amount":1.0,"base":"USD","date":"2025-06-02","rates":{"MXN":19.25}}"#).unwrap();
+
+        assert_eq!(rate_from_response(response, "MXN", "USD").unwrap(), Decimal::from_f64(19.25).unwrap());
+
+        let response: LatestRates = serde_json::from_str(r#"{"rates":{}}"#).unwrap();
+        assert!(matches!(rate_from_response(response, "MXN", "USD"), Err(CryptofolioError::NotFound(_))));
+    }
+}
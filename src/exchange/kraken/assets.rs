@@ -0,0 +1,108 @@
+//! Kraken asset code normalization
+//!
+//! Kraken uses its own codes for many assets: XBT for bitcoin, XDG for
+//! dogecoin, and legacy X/Z prefixes on older crypto and fiat codes (XXBT,
+//! XETH, ZUSD, ZEUR). Balances may also carry suffixes for staked or earning
+//! funds (e.g. `DOT.S`, `XBT.F`).
+
+/// Kraken asset codes and the symbols used elsewhere in the app
+const ASSET_MAP: &[(&str, &str)] = &[
+    ("XXBT", "BTC"),
+    ("XBT", "BTC"),
+    ("XETH", "ETH"),
+    ("XXDG", "DOGE"),
+    ("XDG", "DOGE"),
+    ("XLTC", "LTC"),
+    ("XXRP", "XRP"),
+    ("XXLM", "XLM"),
+    ("XETC", "ETC"),
+    ("XZEC", "ZEC"),
+    ("XXMR", "XMR"),
+    ("XREP", "REP"),
+    ("XMLN", "MLN"),
+    ("ZUSD", "USD"),
+    ("ZEUR", "EUR"),
+    ("ZGBP", "GBP"),
+    ("ZCAD", "CAD"),
+    ("ZJPY", "JPY"),
+    ("ZAUD", "AUD"),
+];
+
+/// Quote currencies recognized when splitting a Kraken pair name, longest first
+const QUOTE_ASSETS: &[&str] = &[
+    "ZUSD", "ZEUR", "ZGBP", "ZCAD", "ZJPY", "ZAUD", "USDT", "USDC", "XXBT", "XETH", "USD", "EUR", "GBP", "CAD",
+    "JPY", "AUD", "CHF", "XBT", "ETH", "DAI",
+];
+
+/// Convert a Kraken asset code to the common symbol, keeping any `.S`/`.F`
+/// style suffix (e.g. `XXBT` -> `BTC`, `XBT.F` -> `BTC.F`)
+pub fn normalize_asset(code: &str) -> String {
+    let code = code.to_uppercase();
+    let (base, suffix) = match code.split_once('.') {
+        Some((base, suffix)) => (base, Some(suffix)),
+        None => (code.as_str(), None),
+    };
+
+    let base = ASSET_MAP
+        .iter()
+        .find(|(kraken, _)| *kraken == base)
+        .map(|(_, symbol)| symbol.to_string())
+        .unwrap_or_else(|| base.to_string());
+
+    match suffix {
+        Some(suffix) => format!("{}.{}", base, suffix),
+        None => base,
+    }
+}
+
+/// Convert a common symbol to the code Kraken accepts in pair names (BTC -> XBT)
+pub fn to_kraken_asset(symbol: &str) -> String {
+    match symbol.to_uppercase().as_str() {
+        "BTC" => "XBT".to_string(),
+        "DOGE" => "XDG".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Split a Kraken pair name into normalized base and quote symbols
+/// (e.g. `XXBTZUSD` -> `("BTC", "USD")`, `SOLEUR` -> `("SOL", "EUR")`)
+pub fn split_pair(pair: &str) -> Option<(String, String)> {
+    let pair = pair.to_uppercase();
+
+    QUOTE_ASSETS.iter().find_map(|quote| {
+        pair.strip_suffix(quote)
+            .filter(|base| !base.is_empty())
+            .map(|base| (normalize_asset(base), normalize_asset(quote)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_asset() {
+        assert_eq!(normalize_asset("XXBT"), "BTC");
+        assert_eq!(normalize_asset("XBT"), "BTC");
+        assert_eq!(normalize_asset("ZEUR"), "EUR");
+        assert_eq!(normalize_asset("XXDG"), "DOGE");
+        assert_eq!(normalize_asset("SOL"), "SOL");
+        assert_eq!(normalize_asset("XBT.F"), "BTC.F");
+        assert_eq!(normalize_asset("dot.s"), "DOT.S");
+    }
+
+    #[test]
+    fn test_split_pair() {
+        assert_eq!(split_pair("XXBTZUSD"), Some(("BTC".into(), "USD".into())));
+        assert_eq!(split_pair("XETHXXBT"), Some(("ETH".into(), "BTC".into())));
+        assert_eq!(split_pair("SOLEUR"), Some(("SOL".into(), "EUR".into())));
+        assert_eq!(split_pair("USDTUSD"), Some(("USDT".into(), "USD".into())));
+        assert_eq!(split_pair("USD"), None);
+    }
+
+    #[test]
+    fn test_to_kraken_asset() {
+        assert_eq!(to_kraken_asset("btc"), "XBT");
+        assert_eq!(to_kraken_asset("ETH"), "ETH");
+    }
+}
@@ -0,0 +1,338 @@
+#![allow(dead_code)]
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::assets::{normalize_asset, split_pair, to_kraken_asset};
+use super::endpoints::*;
+use super::models::*;
+use crate::error::{CryptofolioError, Result};
+use crate::exchange::models::{AccountBalance, MarketData, PriceData, Ticker24h, Trade};
+use crate::exchange::traits::Exchange;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Kraken returns at most this many trades per TradesHistory page
+const TRADES_PAGE_SIZE: usize = 50;
+
+pub struct KrakenClient {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+    api_secret: Option<String>,
+}
+
+impl KrakenClient {
+    pub fn new(api_key: Option<String>, api_secret: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: BASE_URL.to_string(),
+            api_key,
+            api_secret,
+        }
+    }
+
+    /// Nonces must strictly increase per API key, so use microseconds
+    fn get_nonce() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_micros() as u64
+    }
+
+    /// API-Sign header: base64(HMAC-SHA512(path + SHA256(nonce + body), base64-decoded secret))
+    fn sign(&self, path: &str, nonce: u64, body: &str) -> Result<String> {
+        let secret = self.api_secret.as_ref()
+            .ok_or_else(|| CryptofolioError::AuthRequired("Kraken API secret not configured".into()))?;
+
+        let key = BASE64.decode(secret.trim())
+            .map_err(|_| CryptofolioError::Config("Kraken API secret is not valid base64".into()))?;
+
+        let digest = Sha256::digest(format!("{}{}", nonce, body).as_bytes());
+
+        let mut mac = HmacSha512::new_from_slice(&key)
+            .map_err(|e| CryptofolioError::Other(format!("HMAC error: {}", e)))?;
+        mac.update(path.as_bytes());
+        mac.update(&digest);
+
+        Ok(BASE64.encode(mac.finalize().into_bytes()))
+    }
+
+    fn into_result<T>(response: KrakenResponse<T>) -> Result<T> {
+        if !response.error.is_empty() {
+            return Err(CryptofolioError::ExchangeApi(format!("Kraken: {}", response.error.join(", "))));
+        }
+
+        response.result
+            .ok_or_else(|| CryptofolioError::ExchangeApi("Kraken: empty response".into()))
+    }
+
+    async fn get_public<T: serde::de::DeserializeOwned>(&self, endpoint: &str, params: &[(&str, &str)]) -> Result<T> {
+        let url = format!("{}{}", self.base_url, endpoint);
+
+        let response: KrakenResponse<T> = self.client
+            .get(&url)
+            .query(params)
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        Self::into_result(response)
+    }
+
+    async fn post_private<T: serde::de::DeserializeOwned>(&self, endpoint: &str, params: &[(&str, String)]) -> Result<T> {
+        let api_key = self.api_key.as_ref()
+            .ok_or_else(|| CryptofolioError::AuthRequired("Kraken API key not configured".into()))?;
+
+        let nonce = Self::get_nonce();
+        // Parameters are plain codes and numbers, so no form escaping is needed
+        let body = std::iter::once(format!("nonce={}", nonce))
+            .chain(params.iter().map(|(k, v)| format!("{}={}", k, v)))
+            .collect::<Vec<_>>()
+            .join("&");
+        let signature = self.sign(endpoint, nonce, &body)?;
+
+        let url = format!("{}{}", self.base_url, endpoint);
+
+        let response: KrakenResponse<T> = self.client
+            .post(&url)
+            .header("API-Key", api_key)
+            .header("API-Sign", signature)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(body)
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        Self::into_result(response)
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<KrakenTicker> {
+        let pair = format!("{}USD", to_kraken_asset(symbol));
+        let tickers: HashMap<String, KrakenTicker> = self.get_public(TICKER, &[("pair", &pair)]).await?;
+
+        tickers.into_values().next()
+            .ok_or_else(|| CryptofolioError::ExchangeApi(format!("Kraken: no ticker for {}", pair)))
+    }
+
+    /// Account equity and margin, valued in `asset` (e.g. "USD" or "EUR")
+    pub async fn get_trade_balance(&self, asset: &str) -> Result<KrakenTradeBalance> {
+        let asset = to_kraken_asset(asset);
+        self.post_private(TRADE_BALANCE, &[("asset", asset)]).await
+    }
+
+    /// Full trade history, oldest first
+    pub async fn get_trades(&self) -> Result<Vec<Trade>> {
+        let mut trades = Vec::new();
+
+        loop {
+            let page: KrakenTradesHistory = self
+                .post_private(TRADES_HISTORY, &[("ofs", trades.len().to_string())])
+                .await?;
+
+            let fetched = page.trades.len();
+            trades.extend(page.trades.into_iter().map(|(id, t)| trade_from_kraken(id, t)));
+
+            if fetched < TRADES_PAGE_SIZE || trades.len() as u64 >= page.count {
+                break;
+            }
+        }
+
+        trades.sort_by_key(|t| t.time);
+        Ok(trades)
+    }
+}
+
+fn parse_decimal(value: Option<&String>) -> Result<Decimal> {
+    value
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| CryptofolioError::ExchangeApi(format!("Kraken: unexpected number {:?}", value)))
+}
+
+fn trade_from_kraken(id: String, trade: KrakenTrade) -> Trade {
+    let (base, quote) = split_pair(&trade.pair).unwrap_or_else(|| (trade.pair.clone(), String::new()));
+
+    Trade {
+        id,
+        symbol: format!("{}{}", base, quote),
+        price: trade.price,
+        quantity: trade.vol,
+        quote_quantity: trade.cost,
+        commission: trade.fee,
+        // Kraken charges fees in the quote currency by default
+        commission_asset: quote,
+        time: (trade.time * 1000.0) as i64,
+        is_buyer: trade.side == "buy",
+        is_maker: trade.maker,
+    }
+}
+
+#[async_trait]
+impl Exchange for KrakenClient {
+    fn name(&self) -> &str {
+        "Kraken"
+    }
+
+    fn is_testnet(&self) -> bool {
+        false
+    }
+
+    fn has_credentials(&self) -> bool {
+        self.api_key.is_some() && self.api_secret.is_some()
+    }
+
+    async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+        let ticker = self.get_ticker(symbol).await?;
+
+        Ok(PriceData {
+            symbol: symbol.to_uppercase(),
+            price: parse_decimal(ticker.c.first())?,
+        })
+    }
+
+    async fn get_prices(&self, symbols: &[&str]) -> Result<Vec<PriceData>> {
+        if symbols.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let pairs = symbols
+            .iter()
+            .map(|s| format!("{}USD", to_kraken_asset(s)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        // Kraken rejects the whole batch if any pair is unknown, so fall back to one at a time
+        match self.get_public::<HashMap<String, KrakenTicker>>(TICKER, &[("pair", &pairs)]).await {
+            Ok(tickers) => Ok(tickers
+                .into_iter()
+                .filter_map(|(pair, ticker)| {
+                    let (base, _) = split_pair(&pair)?;
+                    let price = parse_decimal(ticker.c.first()).ok()?;
+                    Some(PriceData { symbol: base, price })
+                })
+                .collect()),
+            Err(_) => {
+                let mut prices = Vec::new();
+                for symbol in symbols {
+                    if let Ok(price) = self.get_price(symbol).await {
+                        prices.push(price);
+                    }
+                }
+                Ok(prices)
+            }
+        }
+    }
+
+    async fn get_ticker_24h(&self, symbol: &str) -> Result<Ticker24h> {
+        let ticker = self.get_ticker(symbol).await?;
+
+        let price = parse_decimal(ticker.c.first())?;
+        let open = parse_decimal(Some(&ticker.o))?;
+        let volume = parse_decimal(ticker.v.get(1))?;
+        let price_change_percent = if open.is_zero() {
+            Decimal::ZERO
+        } else {
+            (price - open) / open * Decimal::ONE_HUNDRED
+        };
+
+        Ok(Ticker24h {
+            symbol: symbol.to_uppercase(),
+            price,
+            price_change: price - open,
+            price_change_percent,
+            high_24h: parse_decimal(ticker.h.get(1))?,
+            low_24h: parse_decimal(ticker.l.get(1))?,
+            volume,
+            // Volume-weighted average price times volume
+            quote_volume: volume * parse_decimal(ticker.p.get(1))?,
+        })
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        let ticker = self.get_ticker_24h(symbol).await?;
+
+        Ok(MarketData {
+            symbol: format!("{}USD", symbol.to_uppercase()),
+            base_asset: symbol.to_uppercase(),
+            quote_asset: "USD".to_string(),
+            price: ticker.price,
+            ticker_24h: Some(ticker),
+        })
+    }
+
+    async fn get_balances(&self) -> Result<Vec<AccountBalance>> {
+        let response: HashMap<String, String> = self.post_private(BALANCE, &[]).await?;
+
+        let mut balances: Vec<AccountBalance> = Vec::new();
+        for (code, amount) in response {
+            let amount: Decimal = parse_decimal(Some(&amount))?;
+            if amount <= Decimal::ZERO {
+                continue;
+            }
+
+            // XXBT and XBT can both appear; combine them under one symbol
+            let asset = normalize_asset(&code);
+            match balances.iter_mut().find(|b| b.asset == asset) {
+                Some(existing) => existing.free += amount,
+                None => balances.push(AccountBalance {
+                    asset,
+                    free: amount,
+                    locked: Decimal::ZERO,
+                }),
+            }
+        }
+
+        balances.sort_by(|a, b| a.asset.cmp(&b.asset));
+        Ok(balances)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_matches_kraken_example() {
+        // Example from Kraken's REST authentication documentation
+        let client = KrakenClient::new(
+            Some("key".into()),
+            Some("kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==".into()),
+        );
+        let body = "nonce=1616492376594&ordertype=limit&pair=XBTUSD&price=37500&type=buy&volume=1.25";
+
+        let signature = client.sign("/0/private/AddOrder", 1616492376594, body).unwrap();
+        assert_eq!(
+            signature,
+            "4/dpxb3iT4tp/ZCVEwSnEsLxx0bqyhLpdfOpc6fn7OR8+UClSV5n9E6aSS8MPtnRfp32bAb0nmbRn6H8ndwLUQ=="
+        );
+    }
+
+    #[test]
+    fn test_sign_rejects_invalid_secret() {
+        let client = KrakenClient::new(Some("key".into()), Some("not base64!".into()));
+        assert!(client.sign("/0/private/Balance", 1, "nonce=1").is_err());
+    }
+
+    #[test]
+    fn test_trade_from_kraken() {
+        let json = r#"{"ordertxid":"O1","pair":"XXBTZUSD","time":1700000000.5,"type":"buy",
+            "ordertype":"limit","price":"35000.0","cost":"3500.0","fee":"5.6","vol":"0.1","maker":true}"#;
+        let trade: KrakenTrade = serde_json::from_str(json).unwrap();
+
+        let trade = trade_from_kraken("T1".into(), trade);
+        assert_eq!(trade.symbol, "BTCUSD");
+        assert_eq!(trade.commission_asset, "USD");
+        assert_eq!(trade.time, 1_700_000_000_500);
+        assert!(trade.is_buyer);
+        assert!(trade.is_maker);
+    }
+}
@@ -0,0 +1,11 @@
+#![allow(dead_code)]
+
+pub const BASE_URL: &str = "https://api.kraken.com";
+
+// Public endpoints
+pub const TICKER: &str = "/0/public/Ticker";
+
+// Private endpoints (require authentication)
+pub const BALANCE: &str = "/0/private/Balance";
+pub const TRADE_BALANCE: &str = "/0/private/TradeBalance";
+pub const TRADES_HISTORY: &str = "/0/private/TradesHistory";
@@ -0,0 +1,8 @@
+mod assets;
+mod client;
+mod endpoints;
+mod models;
+
+pub use assets::{normalize_asset, to_kraken_asset};
+pub use client::KrakenClient;
+pub use models::KrakenTradeBalance;
@@ -0,0 +1,83 @@
+#![allow(dead_code)]
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Envelope around every Kraken REST response
+#[derive(Debug, Deserialize)]
+pub struct KrakenResponse<T> {
+    #[serde(default)]
+    pub error: Vec<String>,
+    pub result: Option<T>,
+}
+
+/// Ticker fields are arrays of strings: `c` is [last price, lot volume], while
+/// `v`, `p`, `l` and `h` are [today, last 24 hours]
+#[derive(Debug, Deserialize)]
+pub struct KrakenTicker {
+    pub c: Vec<String>,
+    pub v: Vec<String>,
+    pub p: Vec<String>,
+    pub l: Vec<String>,
+    pub h: Vec<String>,
+    pub o: String,
+}
+
+/// Margin account summary, valued in the requested asset (USD by default)
+#[derive(Debug, Clone, Deserialize)]
+pub struct KrakenTradeBalance {
+    /// Equivalent balance: all balances combined
+    #[serde(rename = "eb", deserialize_with = "deserialize_decimal")]
+    pub equivalent_balance: Decimal,
+    /// Trade balance: equity currencies combined
+    #[serde(rename = "tb", deserialize_with = "deserialize_decimal")]
+    pub trade_balance: Decimal,
+    /// Margin used by open positions
+    #[serde(rename = "m", deserialize_with = "deserialize_decimal")]
+    pub margin: Decimal,
+    /// Unrealized P&L of open positions
+    #[serde(rename = "n", deserialize_with = "deserialize_decimal")]
+    pub unrealized_pnl: Decimal,
+    /// Equity: trade balance plus unrealized P&L
+    #[serde(rename = "e", deserialize_with = "deserialize_decimal")]
+    pub equity: Decimal,
+    /// Free margin: equity minus initial margin
+    #[serde(rename = "mf", deserialize_with = "deserialize_decimal")]
+    pub free_margin: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KrakenTradesHistory {
+    pub trades: HashMap<String, KrakenTrade>,
+    pub count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KrakenTrade {
+    pub ordertxid: String,
+    pub pair: String,
+    /// Unix time in seconds, with fractional part
+    pub time: f64,
+    #[serde(rename = "type")]
+    pub side: String,
+    #[serde(deserialize_with = "deserialize_decimal")]
+    pub price: Decimal,
+    #[serde(deserialize_with = "deserialize_decimal")]
+    pub cost: Decimal,
+    #[serde(deserialize_with = "deserialize_decimal")]
+    pub fee: Decimal,
+    #[serde(deserialize_with = "deserialize_decimal")]
+    pub vol: Decimal,
+    #[serde(default)]
+    pub maker: bool,
+}
+
+// Custom deserializer for Decimal from string
+fn deserialize_decimal<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(serde::de::Error::custom)
+}
@@ -1,7 +1,14 @@
 pub mod binance;
+pub mod bitcoin;
+pub mod ethereum;
+pub mod explorers;
+pub mod fiat;
+pub mod kraken;
 pub mod models;
 pub mod traits;
 
 pub use binance::{BinanceAlphaClient, BinanceClient};
+pub use fiat::{FiatRateProvider, FrankfurterClient};
+pub use kraken::KrakenClient;
 pub use models::PriceData;
-pub use traits::Exchange;
+pub use traits::{BlockchainProvider, Exchange};
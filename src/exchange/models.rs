@@ -43,6 +43,20 @@ impl AccountBalance {
     }
 }
 
+/// A token contract to query on a blockchain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenContract {
+    pub symbol: String,
+    pub address: String,
+}
+
+/// Balance of one asset held at an on-chain address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainBalance {
+    pub asset: String,
+    pub quantity: Decimal,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
     pub id: String,
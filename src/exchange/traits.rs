@@ -1,8 +1,9 @@
 #![allow(dead_code)]
 
 use async_trait::async_trait;
+use rust_decimal::Decimal;
 
-use super::models::{AccountBalance, MarketData, PriceData, Ticker24h};
+use super::models::{AccountBalance, ChainBalance, MarketData, PriceData, Ticker24h, TokenContract};
 use crate::error::Result;
 
 #[async_trait]
@@ -31,3 +32,16 @@ pub trait Exchange: Send + Sync {
     /// Check if the client has authentication configured
     fn has_credentials(&self) -> bool;
 }
+
+/// Reads balances held at addresses on a blockchain
+#[async_trait]
+pub trait BlockchainProvider: Send + Sync {
+    /// Symbol of the chain's native coin
+    fn native_asset(&self) -> &str;
+
+    /// Get the native coin balance of an address
+    async fn get_native_balance(&self, address: &str) -> Result<Decimal>;
+
+    /// Get the non-zero balances of `tokens` held by an address
+    async fn get_token_balances(&self, address: &str, tokens: &[TokenContract]) -> Result<Vec<ChainBalance>>;
+}
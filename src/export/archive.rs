@@ -0,0 +1,182 @@
+use chrono::{Datelike, Local, Timelike};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::{CsvTransactionExporter, Exporter};
+use crate::core::currency::ExchangeRate;
+use crate::core::holdings::Holding;
+use crate::core::transaction::Transaction;
+use crate::error::Result;
+
+pub const ARCHIVE_TRANSACTIONS_FILE: &str = "transactions.csv";
+pub const ARCHIVE_HOLDINGS_FILE: &str = "holdings.csv";
+pub const ARCHIVE_ACCOUNTS_FILE: &str = "accounts.json";
+pub const ARCHIVE_CATEGORIES_FILE: &str = "categories.json";
+pub const ARCHIVE_EXCHANGE_RATES_FILE: &str = "exchange_rates.csv";
+
+/// A ZIP archive for `export --all`, one file per kind of data
+pub struct ExportArchive<W: Write + Seek> {
+    zip: ZipWriter<W>,
+    options: SimpleFileOptions,
+}
+
+impl<W: Write + Seek> ExportArchive<W> {
+    pub fn new(writer: W) -> Self {
+        let mut options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        // Without this every entry is dated 1980, the earliest time ZIP can store
+        let now = Local::now();
+        if let Ok(modified) = zip::DateTime::from_date_and_time(
+            now.year() as u16,
+            now.month() as u8,
+            now.day() as u8,
+            now.hour() as u8,
+            now.minute() as u8,
+            now.second() as u8,
+        ) {
+            options = options.last_modified_time(modified);
+        }
+
+        Self {
+            zip: ZipWriter::new(writer),
+            options,
+        }
+    }
+
+    /// Add a file whose contents are written by `write`
+    pub fn add_file(&mut self, name: &str, write: impl FnOnce(&mut dyn Write) -> Result<()>) -> Result<()> {
+        self.zip.start_file(name, self.options)?;
+        write(&mut self.zip)
+    }
+
+    /// Add `data` as a pretty-printed JSON file
+    pub fn add_json<T: Serialize>(&mut self, name: &str, data: &T) -> Result<()> {
+        self.add_file(name, |writer| {
+            serde_json::to_writer_pretty(&mut *writer, data)?;
+            writeln!(writer)?;
+            Ok(())
+        })
+    }
+
+    /// Add transactions in the `tx export` CSV format, which `import` reads back
+    pub fn add_transactions(&mut self, transactions: &[Transaction]) -> Result<()> {
+        self.add_file(ARCHIVE_TRANSACTIONS_FILE, |writer| {
+            CsvTransactionExporter.export(transactions, writer)
+        })
+    }
+
+    /// Add holdings as CSV, naming each holding's account (`account_names` maps id to name)
+    pub fn add_holdings(&mut self, holdings: &[Holding], account_names: &HashMap<String, String>) -> Result<()> {
+        self.add_file(ARCHIVE_HOLDINGS_FILE, |writer| {
+            let mut csv_writer = ::csv::Writer::from_writer(writer);
+            for holding in holdings {
+                csv_writer.serialize(HoldingRecord {
+                    account: account_names
+                        .get(&holding.account_id)
+                        .cloned()
+                        .unwrap_or_else(|| holding.account_id.clone()),
+                    asset: holding.asset.clone(),
+                    quantity: holding.quantity.to_string(),
+                    avg_cost_basis: holding.avg_cost_basis.map(|c| c.to_string()).unwrap_or_default(),
+                    last_updated: holding.updated_at.to_rfc3339(),
+                })?;
+            }
+            csv_writer.flush()?;
+            Ok(())
+        })
+    }
+
+    /// Add exchange rates as CSV, oldest first as given
+    pub fn add_exchange_rates(&mut self, rates: &[ExchangeRate]) -> Result<()> {
+        self.add_file(ARCHIVE_EXCHANGE_RATES_FILE, |writer| {
+            let mut csv_writer = ::csv::Writer::from_writer(writer);
+            for rate in rates {
+                csv_writer.serialize(ExchangeRateRecord {
+                    from_currency: rate.from_currency.clone(),
+                    to_currency: rate.to_currency.clone(),
+                    rate: rate.rate.to_string(),
+                    timestamp: rate.timestamp.to_rfc3339(),
+                    source: rate.source.clone(),
+                    notes: rate.notes.clone().unwrap_or_default(),
+                })?;
+            }
+            csv_writer.flush()?;
+            Ok(())
+        })
+    }
+
+    /// Write the archive's directory and return the underlying writer
+    pub fn finish(self) -> Result<W> {
+        Ok(self.zip.finish()?)
+    }
+}
+
+#[derive(Serialize)]
+struct HoldingRecord {
+    account: String,
+    asset: String,
+    quantity: String,
+    avg_cost_basis: String,
+    last_updated: String,
+}
+
+#[derive(Serialize)]
+struct ExchangeRateRecord {
+    from_currency: String,
+    to_currency: String,
+    rate: String,
+    timestamp: String,
+    source: String,
+    notes: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use std::io::{Cursor, Read};
+
+    fn read_file(archive: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+        let mut contents = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut contents).unwrap();
+        contents
+    }
+
+    #[test]
+    fn test_archive_holds_one_file_per_kind() {
+        let holding = Holding {
+            id: 1,
+            account_id: "ledger-id".to_string(),
+            asset: "BTC".to_string(),
+            quantity: Decimal::new(5, 1),
+            avg_cost_basis: Some(Decimal::from(30000)),
+            cost_basis_currency: None,
+            avg_cost_basis_base: None,
+            updated_at: Utc::now(),
+            acquired_at: None,
+        };
+        let names = HashMap::from([("ledger-id".to_string(), "Ledger".to_string())]);
+        let tx = Transaction::new_buy("ledger-id", "BTC", Decimal::ONE, Decimal::from(30000), Utc::now());
+
+        let mut archive = ExportArchive::new(Cursor::new(Vec::new()));
+        archive.add_transactions(&[tx]).unwrap();
+        archive.add_holdings(&[holding], &names).unwrap();
+        archive.add_exchange_rates(&[]).unwrap();
+        archive.add_json(ARCHIVE_CATEGORIES_FILE, &Vec::<String>::new()).unwrap();
+        let bytes = archive.finish().unwrap().into_inner();
+
+        let mut zip = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(zip.len(), 4);
+
+        let holdings = read_file(&mut zip, ARCHIVE_HOLDINGS_FILE);
+        let mut lines = holdings.lines();
+        assert_eq!(lines.next(), Some("account,asset,quantity,avg_cost_basis,last_updated"));
+        assert!(lines.next().unwrap().starts_with("Ledger,BTC,0.5,30000,"));
+
+        assert!(read_file(&mut zip, ARCHIVE_TRANSACTIONS_FILE).contains(",buy,BTC,1,30000,"));
+        assert_eq!(read_file(&mut zip, ARCHIVE_CATEGORIES_FILE).trim(), "[]");
+    }
+}
@@ -0,0 +1,111 @@
+use serde::Serialize;
+use std::io::Write;
+
+use super::Exporter;
+use crate::core::transaction::{Transaction, TransactionType};
+use crate::error::Result;
+
+/// One CSV row per transaction, readable by `cryptofolio import`
+pub struct CsvTransactionExporter;
+
+impl Exporter for CsvTransactionExporter {
+    fn export(&self, transactions: &[Transaction], writer: &mut dyn Write) -> Result<()> {
+        let mut csv_writer = ::csv::Writer::from_writer(writer);
+        for tx in transactions {
+            csv_writer.serialize(transaction_to_csv_record(tx))?;
+        }
+        csv_writer.flush()?;
+        Ok(())
+    }
+}
+
+/// One row of the CSV export, in the column order `import` reads
+#[derive(Serialize)]
+struct CsvExportRecord {
+    date: String,
+    #[serde(rename = "type")]
+    tx_type: String,
+    asset: String,
+    quantity: String,
+    price_usd: String,
+    fee: String,
+    fee_asset: String,
+    notes: String,
+    to_asset: String,
+    to_quantity: String,
+}
+
+fn transaction_to_csv_record(tx: &Transaction) -> CsvExportRecord {
+    // Determine primary asset and quantity based on transaction type
+    let (asset, quantity) = match tx.tx_type {
+        TransactionType::Buy | TransactionType::Receive | TransactionType::StakingReward | TransactionType::TransferIn => {
+            (
+                tx.to_asset.clone().unwrap_or_default(),
+                tx.to_quantity.map(|q| q.to_string()).unwrap_or_default(),
+            )
+        }
+        TransactionType::Sell | TransactionType::TransferOut | TransactionType::Fee => {
+            (
+                tx.from_asset.clone().unwrap_or_default(),
+                tx.from_quantity.map(|q| q.to_string()).unwrap_or_default(),
+            )
+        }
+        TransactionType::TransferInternal => {
+            // For internal transfers, use the asset being transferred
+            (
+                tx.from_asset.clone().or_else(|| tx.to_asset.clone()).unwrap_or_default(),
+                tx.from_quantity.or(tx.to_quantity).map(|q| q.to_string()).unwrap_or_default(),
+            )
+        }
+        TransactionType::Swap => {
+            (
+                tx.from_asset.clone().unwrap_or_default(),
+                tx.from_quantity.map(|q| q.to_string()).unwrap_or_default(),
+            )
+        }
+    };
+
+    CsvExportRecord {
+        date: tx.timestamp.to_rfc3339(),
+        tx_type: tx.tx_type.as_str().to_string(),
+        asset,
+        quantity,
+        price_usd: tx.price_usd.map(|p| p.to_string()).unwrap_or_default(),
+        fee: tx.fee.map(|f| f.to_string()).unwrap_or_default(),
+        fee_asset: tx.fee_asset.clone().unwrap_or_default(),
+        notes: tx.notes.clone().unwrap_or_default(),
+        to_asset: if matches!(tx.tx_type, TransactionType::Swap) {
+            tx.to_asset.clone().unwrap_or_default()
+        } else {
+            String::new()
+        },
+        to_quantity: if matches!(tx.tx_type, TransactionType::Swap) {
+            tx.to_quantity.map(|q| q.to_string()).unwrap_or_default()
+        } else {
+            String::new()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_csv_export_uses_import_columns() {
+        let tx = Transaction::new_buy("ledger", "BTC", Decimal::ONE, Decimal::from(30000), Utc::now());
+
+        let mut out = Vec::new();
+        CsvTransactionExporter.export(&[tx], &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("date,type,asset,quantity,price_usd,fee,fee_asset,notes,to_asset,to_quantity")
+        );
+        assert!(lines.next().unwrap().contains(",buy,BTC,1,30000,"));
+    }
+}
@@ -0,0 +1,42 @@
+use std::io::Write;
+
+use super::Exporter;
+use crate::cli::commands::tx::{transaction_to_output, TransactionOutput};
+use crate::core::transaction::Transaction;
+use crate::error::Result;
+
+/// A pretty-printed JSON array of `TransactionOutput` records
+pub struct JsonTransactionExporter;
+
+impl Exporter for JsonTransactionExporter {
+    fn export(&self, transactions: &[Transaction], writer: &mut dyn Write) -> Result<()> {
+        let records: Vec<TransactionOutput> = transactions.iter().map(transaction_to_output).collect();
+        serde_json::to_writer_pretty(&mut *writer, &records)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_json_export_is_an_array_of_full_records() {
+        let mut tx = Transaction::new_buy("ledger", "BTC", Decimal::ONE, Decimal::from(30000), Utc::now());
+        tx.external_id = Some("abc-1".to_string());
+
+        let mut out = Vec::new();
+        JsonTransactionExporter.export(&[tx], &mut out).unwrap();
+
+        let records: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let record = &records.as_array().unwrap()[0];
+        assert_eq!(record["tx_type"], "buy");
+        assert_eq!(record["to_asset"], "BTC");
+        assert_eq!(record["price_usd"], "30000");
+        assert_eq!(record["external_id"], "abc-1");
+        assert!(record["created_at"].is_string());
+    }
+}
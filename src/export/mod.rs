@@ -0,0 +1,51 @@
+//! Transaction exporters for `tx export`, and the ZIP archive for `export --all`
+//!
+//! Each format implements [`Exporter`]; [`exporter_for`] picks one from the
+//! `--format` value.
+
+pub mod archive;
+mod csv;
+mod json;
+mod sql;
+
+use std::io::Write;
+
+use crate::core::transaction::Transaction;
+use crate::error::Result;
+
+pub use self::archive::ExportArchive;
+pub use self::csv::CsvTransactionExporter;
+pub use self::json::JsonTransactionExporter;
+pub use self::sql::SqlTransactionExporter;
+
+/// Formats accepted by `tx export --format`
+pub const EXPORT_FORMATS: &[&str] = &["csv", "json", "sql"];
+
+/// Writes a list of transactions in one export format
+pub trait Exporter {
+    fn export(&self, transactions: &[Transaction], writer: &mut dyn Write) -> Result<()>;
+}
+
+/// The exporter for a `--format` value (case-insensitive), if supported
+pub fn exporter_for(format: &str) -> Option<Box<dyn Exporter>> {
+    match format.to_lowercase().as_str() {
+        "csv" => Some(Box::new(CsvTransactionExporter)),
+        "json" => Some(Box::new(JsonTransactionExporter)),
+        "sql" => Some(Box::new(SqlTransactionExporter)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exporter_for_every_format() {
+        for format in EXPORT_FORMATS {
+            assert!(exporter_for(format).is_some());
+        }
+        assert!(exporter_for("JSON").is_some());
+        assert!(exporter_for("xml").is_none());
+    }
+}
@@ -0,0 +1,74 @@
+use std::io::Write;
+
+use super::Exporter;
+use crate::core::transaction::Transaction;
+use crate::error::Result;
+
+/// `INSERT INTO transactions` statements for restoring into another database
+pub struct SqlTransactionExporter;
+
+impl Exporter for SqlTransactionExporter {
+    fn export(&self, transactions: &[Transaction], writer: &mut dyn Write) -> Result<()> {
+        for tx in transactions {
+            writeln!(writer, "{}", transaction_to_sql_insert(tx))?;
+        }
+        Ok(())
+    }
+}
+
+/// Quote a value as a SQL string literal, or NULL
+fn sql_value(value: Option<String>) -> String {
+    match value {
+        Some(v) => format!("'{}'", v.replace('\'', "''")),
+        None => "NULL".to_string(),
+    }
+}
+
+fn transaction_to_sql_insert(tx: &Transaction) -> String {
+    format!(
+        "INSERT INTO transactions (tx_type, from_account_id, from_asset, from_quantity, \
+         to_account_id, to_asset, to_quantity, price_usd, price_currency, price_amount, \
+         exchange_rate, exchange_rate_pair, fee, fee_asset, external_id, notes, timestamp, created_at) \
+         VALUES ({}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {});",
+        sql_value(Some(tx.tx_type.as_str().to_string())),
+        sql_value(tx.from_account_id.clone()),
+        sql_value(tx.from_asset.clone()),
+        sql_value(tx.from_quantity.map(|q| q.to_string())),
+        sql_value(tx.to_account_id.clone()),
+        sql_value(tx.to_asset.clone()),
+        sql_value(tx.to_quantity.map(|q| q.to_string())),
+        sql_value(tx.price_usd.map(|p| p.to_string())),
+        sql_value(tx.price_currency.clone()),
+        sql_value(tx.price_amount.map(|p| p.to_string())),
+        sql_value(tx.exchange_rate.map(|r| r.to_string())),
+        sql_value(tx.exchange_rate_pair.clone()),
+        sql_value(tx.fee.map(|f| f.to_string())),
+        sql_value(tx.fee_asset.clone()),
+        sql_value(tx.external_id.clone()),
+        sql_value(tx.notes.clone()),
+        sql_value(Some(tx.timestamp.to_rfc3339())),
+        sql_value(Some(tx.created_at.to_rfc3339())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_sql_export_escapes_quotes() {
+        let mut tx = Transaction::new_buy("ledger", "BTC", Decimal::ONE, Decimal::from(30000), Utc::now());
+        tx.notes = Some("Bob's DCA".to_string());
+
+        let mut out = Vec::new();
+        SqlTransactionExporter.export(&[tx], &mut out).unwrap();
+        let sql = String::from_utf8(out).unwrap();
+
+        assert!(sql.starts_with("INSERT INTO transactions (tx_type,"));
+        assert!(sql.contains("'Bob''s DCA'"));
+        assert!(sql.trim_end().ends_with(");"));
+        assert_eq!(sql.lines().count(), 1);
+    }
+}
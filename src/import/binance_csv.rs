@@ -0,0 +1,298 @@
+//! Parser for the trade history CSV exported from the Binance website.
+//!
+//! Rows look like:
+//!
+//! ```text
+//! Date(UTC),Pair,Side,Price,Executed,Amount,Fee
+//! 2024-01-15 10:30:45,BTCUSDT,BUY,42500.00,0.00100000BTC,42.50000000USDT,0.00000100BTC
+//! ```
+//!
+//! `Executed`, `Amount` and `Fee` carry their asset as a suffix, which is
+//! also how the pair is split into base and quote assets.
+
+use chrono::{NaiveDateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::core::currency::is_usd_equivalent;
+use crate::core::transaction::Transaction;
+use crate::error::{CryptofolioError, Result};
+
+/// Prefix of the external IDs given to imported trades
+const EXTERNAL_ID_PREFIX: &str = "binance-csv";
+
+#[derive(Debug, Deserialize)]
+struct BinanceCsvRow {
+    #[serde(rename = "Date(UTC)")]
+    date: String,
+    #[serde(rename = "Pair")]
+    pair: String,
+    #[serde(rename = "Side")]
+    side: String,
+    #[serde(rename = "Price")]
+    price: String,
+    #[serde(rename = "Executed")]
+    executed: String,
+    #[serde(rename = "Amount")]
+    amount: String,
+    #[serde(rename = "Fee")]
+    fee: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// A single fill from a Binance trade history export
+#[derive(Debug, Clone)]
+pub struct BinanceTrade {
+    pub timestamp: chrono::DateTime<Utc>,
+    pub pair: String,
+    pub side: TradeSide,
+    /// Price in the quote asset
+    pub price: Decimal,
+    pub base_asset: String,
+    pub base_quantity: Decimal,
+    pub quote_asset: String,
+    pub quote_quantity: Decimal,
+    pub fee: Decimal,
+    pub fee_asset: String,
+    /// Identity used to skip trades that were already imported
+    pub external_id: String,
+}
+
+impl BinanceTrade {
+    /// Build the transaction recorded for this trade.
+    ///
+    /// Trades quoted in USD or a stablecoin become buys and sells priced in
+    /// USD; trades between two crypto assets become swaps.
+    pub fn to_transaction(&self, account_id: &str) -> Transaction {
+        let mut tx = if is_usd_equivalent(&self.quote_asset) {
+            match self.side {
+                TradeSide::Buy => Transaction::new_buy(
+                    account_id,
+                    &self.base_asset,
+                    self.base_quantity,
+                    self.price,
+                    self.timestamp,
+                ),
+                TradeSide::Sell => Transaction::new_sell(
+                    account_id,
+                    &self.base_asset,
+                    self.base_quantity,
+                    self.price,
+                    self.timestamp,
+                ),
+            }
+        } else {
+            match self.side {
+                TradeSide::Buy => Transaction::new_swap(
+                    account_id,
+                    &self.quote_asset,
+                    self.quote_quantity,
+                    &self.base_asset,
+                    self.base_quantity,
+                    self.timestamp,
+                ),
+                TradeSide::Sell => Transaction::new_swap(
+                    account_id,
+                    &self.base_asset,
+                    self.base_quantity,
+                    &self.quote_asset,
+                    self.quote_quantity,
+                    self.timestamp,
+                ),
+            }
+        };
+
+        if !self.fee.is_zero() {
+            tx.fee = Some(self.fee);
+            tx.fee_asset = Some(self.fee_asset.clone());
+        }
+        tx.external_id = Some(self.external_id.clone());
+        tx.notes = Some(format!("Binance {} {}", self.pair, if self.side == TradeSide::Buy { "buy" } else { "sell" }));
+        tx
+    }
+}
+
+/// Parse a Binance trade history CSV.
+///
+/// Returns one entry per data row, in file order, so callers can report
+/// errors by line while still importing the rows that parsed.
+pub fn parse_trades(contents: &[u8]) -> Result<Vec<Result<BinanceTrade>>> {
+    let mut reader = csv::Reader::from_reader(contents);
+    let headers = reader.headers()?.clone();
+
+    for column in ["Date(UTC)", "Pair", "Side", "Price", "Executed", "Amount", "Fee"] {
+        if !headers.iter().any(|h| h == column) {
+            return Err(CryptofolioError::InvalidInput(format!(
+                "Not a Binance trade history export: missing '{}' column",
+                column
+            )));
+        }
+    }
+
+    // Identical fills (same second, pair, side and price) are numbered so
+    // each one gets its own external ID
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+
+    let mut trades = Vec::new();
+    for record in reader.records() {
+        let trade = record
+            .and_then(|r| r.deserialize::<BinanceCsvRow>(Some(&headers)))
+            .map_err(CryptofolioError::Csv)
+            .and_then(|row| parse_row(&row))
+            .map(|mut trade| {
+                let count = occurrences.entry(trade.external_id.clone()).or_insert(0);
+                *count += 1;
+                if *count > 1 {
+                    trade.external_id = format!("{}#{}", trade.external_id, count);
+                }
+                trade
+            });
+        trades.push(trade);
+    }
+
+    Ok(trades)
+}
+
+fn parse_row(row: &BinanceCsvRow) -> Result<BinanceTrade> {
+    let timestamp = NaiveDateTime::parse_from_str(row.date.trim(), "%Y-%m-%d %H:%M:%S")
+        .map(|dt| dt.and_utc())
+        .map_err(|_| CryptofolioError::InvalidInput(format!("Invalid date format: {}", row.date)))?;
+
+    let side = match row.side.trim().to_uppercase().as_str() {
+        "BUY" => TradeSide::Buy,
+        "SELL" => TradeSide::Sell,
+        other => return Err(CryptofolioError::InvalidInput(format!("Invalid side: {}", other))),
+    };
+
+    let pair = row.pair.trim().to_uppercase();
+    let price = parse_number(&row.price)?;
+    let (base_quantity, base_asset) = parse_amount(&row.executed)?;
+    let (quote_quantity, quote_asset) = parse_amount(&row.amount)?;
+    let (fee, fee_asset) = parse_amount(&row.fee)?;
+
+    if format!("{}{}", base_asset, quote_asset) != pair {
+        return Err(CryptofolioError::InvalidInput(format!(
+            "Pair {} does not match executed asset {} and amount asset {}",
+            pair, base_asset, quote_asset
+        )));
+    }
+
+    let external_id = format!(
+        "{}:{}:{}:{}:{}",
+        EXTERNAL_ID_PREFIX,
+        timestamp.format("%Y-%m-%dT%H:%M:%SZ"),
+        pair,
+        if side == TradeSide::Buy { "BUY" } else { "SELL" },
+        price.normalize()
+    );
+
+    Ok(BinanceTrade {
+        timestamp,
+        pair,
+        side,
+        price,
+        base_asset,
+        base_quantity,
+        quote_asset,
+        quote_quantity,
+        fee,
+        fee_asset,
+        external_id,
+    })
+}
+
+/// Split a value like `0.00100000BTC` into its quantity and asset
+fn parse_amount(value: &str) -> Result<(Decimal, String)> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic())
+        .filter(|&i| i > 0)
+        .ok_or_else(|| CryptofolioError::InvalidAmount(value.to_string()))?;
+
+    let (number, asset) = value.split_at(split);
+    Ok((parse_number(number)?, asset.to_uppercase()))
+}
+
+/// Parse a number, allowing the thousands separators Binance uses for large values
+fn parse_number(value: &str) -> Result<Decimal> {
+    let cleaned = value.trim().replace(',', "");
+    Decimal::from_str(&cleaned).map_err(|_| CryptofolioError::InvalidAmount(value.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::transaction::TransactionType;
+
+    const HEADER: &str = "Date(UTC),Pair,Side,Price,Executed,Amount,Fee\n";
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("0.00100000BTC").unwrap(), (dec("0.001"), "BTC".to_string()));
+        assert_eq!(parse_amount("1,234.5USDT").unwrap(), (dec("1234.5"), "USDT".to_string()));
+        assert!(parse_amount("BTC").is_err());
+        assert!(parse_amount("0.5").is_err());
+    }
+
+    #[test]
+    fn test_parse_trades_maps_pairs() {
+        let csv = format!(
+            "{}{}{}",
+            HEADER,
+            "2024-01-15 10:30:45,BTCUSDT,BUY,42500.00,0.00100000BTC,42.50000000USDT,0.00000100BTC\n",
+            "2024-02-01 08:00:00,ETHBTC,SELL,0.055,2.0ETH,0.11BTC,0.0001BNB\n"
+        );
+        let trades: Vec<BinanceTrade> = parse_trades(csv.as_bytes())
+            .unwrap()
+            .into_iter()
+            .collect::<Result<_>>()
+            .unwrap();
+
+        let buy = trades[0].to_transaction("acc");
+        assert_eq!(buy.tx_type, TransactionType::Buy);
+        assert_eq!(buy.to_asset.as_deref(), Some("BTC"));
+        assert_eq!(buy.to_quantity, Some(dec("0.001")));
+        assert_eq!(buy.price_usd, Some(dec("42500")));
+        assert_eq!(buy.fee_asset.as_deref(), Some("BTC"));
+
+        let sell = trades[1].to_transaction("acc");
+        assert_eq!(sell.tx_type, TransactionType::Swap);
+        assert_eq!(sell.from_asset.as_deref(), Some("ETH"));
+        assert_eq!(sell.from_quantity, Some(dec("2")));
+        assert_eq!(sell.to_asset.as_deref(), Some("BTC"));
+        assert_eq!(sell.to_quantity, Some(dec("0.11")));
+        assert_eq!(sell.fee_asset.as_deref(), Some("BNB"));
+    }
+
+    #[test]
+    fn test_identical_fills_get_distinct_ids() {
+        let row = "2024-01-15 10:30:45,BTCUSDT,BUY,42500,0.001BTC,42.5USDT,0BTC\n";
+        let csv = format!("{}{}{}", HEADER, row, row);
+        let trades = parse_trades(csv.as_bytes()).unwrap();
+
+        let first = trades[0].as_ref().unwrap();
+        let second = trades[1].as_ref().unwrap();
+        assert_eq!(first.external_id, "binance-csv:2024-01-15T10:30:45Z:BTCUSDT:BUY:42500");
+        assert_eq!(second.external_id, format!("{}#2", first.external_id));
+        assert_eq!(first.to_transaction("acc").fee, None);
+    }
+
+    #[test]
+    fn test_rejects_other_formats() {
+        assert!(parse_trades(b"date,type,asset,quantity\n2024-01-15,buy,BTC,1\n").is_err());
+
+        let csv = format!("{}{}", HEADER, "2024-01-15 10:30:45,BTCUSDT,HOLD,1,1BTC,1USDT,0BTC\n");
+        assert!(parse_trades(csv.as_bytes()).unwrap()[0].is_err());
+    }
+}
@@ -0,0 +1,377 @@
+//! Parser for the account statement CSV exported from Coinbase Pro (now
+//! Coinbase Advanced Trade).
+//!
+//! Rows look like:
+//!
+//! ```text
+//! portfolio,type,time,amount,balance,amount/balance unit,transfer id,trade id,order id
+//! default,deposit,2024-01-10T09:00:00.000Z,1000.00,1000.00,USD,8a2f...,,
+//! default,match,2024-01-15T10:30:45.123Z,0.01,0.01,BTC,,4521337,d50ec984-...
+//! default,match,2024-01-15T10:30:45.123Z,-425.00,575.00,USD,,4521337,d50ec984-...
+//! default,fee,2024-01-15T10:30:45.123Z,-2.125,572.875,USD,,4521337,d50ec984-...
+//! ```
+//!
+//! Every fill is written as one `match` row per side, sharing a trade and
+//! order id. They are paired back into a single trade here.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::core::currency::is_usd_equivalent;
+use crate::core::transaction::{Transaction, TransactionType};
+use crate::error::{CryptofolioError, Result};
+
+/// Prefix of the external IDs given to imported entries
+const EXTERNAL_ID_PREFIX: &str = "coinbase-pro";
+
+const REQUIRED_COLUMNS: &[&str] = &[
+    "type",
+    "time",
+    "amount",
+    "amount/balance unit",
+    "transfer id",
+    "trade id",
+    "order id",
+];
+
+/// One row of the statement, before fills are paired
+#[derive(Debug, Clone)]
+struct StatementRow {
+    kind: String,
+    timestamp: DateTime<Utc>,
+    amount: Decimal,
+    unit: String,
+    transfer_id: String,
+    trade_id: String,
+    order_id: String,
+}
+
+/// A trade, transfer or fee from a Coinbase Pro statement
+#[derive(Debug, Clone)]
+pub struct CoinbaseProEntry {
+    pub timestamp: DateTime<Utc>,
+    /// Buy, Sell or Swap for fills, TransferIn/TransferOut for deposits and
+    /// withdrawals, Fee for fees
+    pub tx_type: TransactionType,
+    /// Quantity and asset that left the account
+    pub sent: Option<(Decimal, String)>,
+    /// Quantity and asset that arrived in the account
+    pub received: Option<(Decimal, String)>,
+    /// Identity used to skip entries that were already imported
+    pub external_id: String,
+}
+
+impl CoinbaseProEntry {
+    /// Build the transaction recorded for this entry.
+    ///
+    /// Fills against USD or a stablecoin become buys and sells priced in
+    /// USD; fills between two crypto assets become swaps.
+    pub fn to_transaction(&self, account_id: &str) -> Transaction {
+        let mut tx = match (self.tx_type, &self.sent, &self.received) {
+            (TransactionType::Buy, Some((spent, _)), Some((quantity, asset))) => {
+                Transaction::new_buy(account_id, asset, *quantity, *spent / *quantity, self.timestamp)
+            }
+            (TransactionType::Sell, Some((quantity, asset)), Some((proceeds, _))) => {
+                Transaction::new_sell(account_id, asset, *quantity, *proceeds / *quantity, self.timestamp)
+            }
+            (TransactionType::Swap, Some((from_quantity, from_asset)), Some((to_quantity, to_asset))) => {
+                Transaction::new_swap(account_id, from_asset, *from_quantity, to_asset, *to_quantity, self.timestamp)
+            }
+            (TransactionType::Fee, Some((amount, asset)), _) => {
+                Transaction::new_fee(account_id, asset, *amount, self.timestamp)
+            }
+            _ => {
+                let incoming = self.received.is_some();
+                let account = Some(account_id.to_string());
+                let (quantity, asset) = self
+                    .received
+                    .clone()
+                    .or_else(|| self.sent.clone())
+                    .map(|(q, a)| (Some(q), Some(a)))
+                    .unwrap_or_default();
+                Transaction {
+                    id: 0,
+                    tx_type: self.tx_type,
+                    from_account_id: if incoming { None } else { account.clone() },
+                    from_asset: if incoming { None } else { asset.clone() },
+                    from_quantity: if incoming { None } else { quantity },
+                    to_account_id: if incoming { account } else { None },
+                    to_asset: if incoming { asset } else { None },
+                    to_quantity: if incoming { quantity } else { None },
+                    price_usd: None,
+                    price_currency: None,
+                    price_amount: None,
+                    exchange_rate: None,
+                    exchange_rate_pair: None,
+                    fee: None,
+                    fee_asset: None,
+                    external_id: None,
+                    notes: None,
+                    timestamp: self.timestamp,
+                    created_at: Utc::now(),
+                }
+            }
+        };
+
+        tx.external_id = Some(self.external_id.clone());
+        tx.notes = Some(format!("Coinbase Pro {}", self.tx_type.display_name().to_lowercase()));
+        tx
+    }
+}
+
+/// Parse a Coinbase Pro account statement CSV.
+///
+/// Returns one entry per trade, transfer or fee, in file order, each with
+/// the line it starts on so callers can report errors by line while still
+/// importing the entries that parsed.
+pub fn parse_entries(contents: &[u8]) -> Result<Vec<(usize, Result<CoinbaseProEntry>)>> {
+    let mut reader = csv::Reader::from_reader(contents);
+    let headers = reader.headers()?.clone();
+
+    let mut indices = Vec::new();
+    for name in REQUIRED_COLUMNS {
+        let index = headers.iter().position(|h| h.trim() == *name).ok_or_else(|| {
+            CryptofolioError::InvalidInput(format!("Not a Coinbase Pro account statement: missing '{}' column", name))
+        })?;
+        indices.push(index);
+    }
+
+    let mut rows = Vec::new();
+    for (line_num, record) in reader.records().enumerate() {
+        let line = line_num + 2; // +1 for header, +1 for 1-based
+        let row = record.map_err(CryptofolioError::Csv).and_then(|r| {
+            let fields: Vec<&str> = indices.iter().map(|&i| r.get(i).unwrap_or_default().trim()).collect();
+            parse_row(&fields)
+        });
+        rows.push((line, row));
+    }
+
+    // Both sides of a fill, keyed by order and trade id
+    let mut fills: HashMap<(String, String), Vec<StatementRow>> = HashMap::new();
+    for row in rows.iter().filter_map(|(_, row)| row.as_ref().ok()) {
+        if row.kind == "match" {
+            fills.entry(fill_key(row)).or_default().push(row.clone());
+        }
+    }
+
+    let mut entries = Vec::new();
+    for (line, row) in rows {
+        let row = match row {
+            Ok(row) => row,
+            Err(e) => {
+                entries.push((line, Err(e)));
+                continue;
+            }
+        };
+
+        let entry = match row.kind.as_str() {
+            // The fill is built from its first row; the other side was consumed with it
+            "match" => match fills.remove(&fill_key(&row)) {
+                Some(sides) => pair_fill(&sides),
+                None => continue,
+            },
+            "fee" => fee_entry(&row),
+            "deposit" | "withdrawal" => transfer_entry(&row),
+            other => Err(CryptofolioError::InvalidInput(format!("Unsupported entry type: {}", other))),
+        };
+        entries.push((line, entry));
+    }
+
+    Ok(entries)
+}
+
+/// `fields` holds the values of [`REQUIRED_COLUMNS`], in order
+fn parse_row(fields: &[&str]) -> Result<StatementRow> {
+    let &[kind, time, amount, unit, transfer_id, trade_id, order_id] = fields else {
+        return Err(CryptofolioError::InvalidInput("Row is missing columns".to_string()));
+    };
+
+    let timestamp = DateTime::parse_from_rfc3339(time)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| CryptofolioError::InvalidInput(format!("Invalid date format: {}", time)))?;
+    let amount = Decimal::from_str(amount)
+        .or_else(|_| Decimal::from_scientific(amount))
+        .map_err(|_| CryptofolioError::InvalidAmount(amount.to_string()))?;
+    if unit.is_empty() {
+        return Err(CryptofolioError::InvalidInput("Missing amount/balance unit".to_string()));
+    }
+
+    Ok(StatementRow {
+        kind: kind.to_lowercase(),
+        timestamp,
+        amount,
+        unit: unit.to_uppercase(),
+        transfer_id: transfer_id.to_string(),
+        trade_id: trade_id.to_string(),
+        order_id: order_id.to_string(),
+    })
+}
+
+fn fill_key(row: &StatementRow) -> (String, String) {
+    (row.order_id.clone(), row.trade_id.clone())
+}
+
+/// Combine the two `match` rows of a fill into a trade
+fn pair_fill(sides: &[StatementRow]) -> Result<CoinbaseProEntry> {
+    let first = &sides[0];
+    if first.trade_id.is_empty() {
+        return Err(CryptofolioError::InvalidInput("Match row has no trade id".to_string()));
+    }
+
+    let (sent, received) = match sides {
+        [a, b] if a.amount.is_sign_negative() && b.amount.is_sign_positive() => (a, b),
+        [a, b] if a.amount.is_sign_positive() && b.amount.is_sign_negative() => (b, a),
+        [_, _] => {
+            return Err(CryptofolioError::InvalidInput(format!(
+                "Trade {} does not have one incoming and one outgoing side",
+                first.trade_id
+            )))
+        }
+        _ => {
+            return Err(CryptofolioError::InvalidInput(format!(
+                "Trade {} has {} match rows, expected 2",
+                first.trade_id,
+                sides.len()
+            )))
+        }
+    };
+    if sent.amount.is_zero() || received.amount.is_zero() {
+        return Err(CryptofolioError::InvalidInput(format!("Trade {} has a zero amount", first.trade_id)));
+    }
+
+    let tx_type = if is_usd_equivalent(&sent.unit) {
+        TransactionType::Buy
+    } else if is_usd_equivalent(&received.unit) {
+        TransactionType::Sell
+    } else {
+        TransactionType::Swap
+    };
+
+    Ok(CoinbaseProEntry {
+        timestamp: first.timestamp,
+        tx_type,
+        sent: Some((sent.amount.abs(), sent.unit.clone())),
+        received: Some((received.amount, received.unit.clone())),
+        external_id: format!("{}:trade:{}:{}", EXTERNAL_ID_PREFIX, first.order_id, first.trade_id),
+    })
+}
+
+fn fee_entry(row: &StatementRow) -> Result<CoinbaseProEntry> {
+    if row.trade_id.is_empty() && row.order_id.is_empty() {
+        return Err(CryptofolioError::InvalidInput("Fee row has no trade or order id".to_string()));
+    }
+
+    Ok(CoinbaseProEntry {
+        timestamp: row.timestamp,
+        tx_type: TransactionType::Fee,
+        sent: Some((row.amount.abs(), row.unit.clone())),
+        received: None,
+        external_id: format!("{}:fee:{}:{}", EXTERNAL_ID_PREFIX, row.order_id, row.trade_id),
+    })
+}
+
+fn transfer_entry(row: &StatementRow) -> Result<CoinbaseProEntry> {
+    if row.transfer_id.is_empty() {
+        return Err(CryptofolioError::InvalidInput(format!("{} row has no transfer id", row.kind)));
+    }
+
+    let amount = Some((row.amount.abs(), row.unit.clone()));
+    let incoming = row.kind == "deposit";
+    Ok(CoinbaseProEntry {
+        timestamp: row.timestamp,
+        tx_type: if incoming { TransactionType::TransferIn } else { TransactionType::TransferOut },
+        sent: if incoming { None } else { amount.clone() },
+        received: if incoming { amount } else { None },
+        external_id: format!("{}:transfer:{}", EXTERNAL_ID_PREFIX, row.transfer_id),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "portfolio,type,time,amount,balance,amount/balance unit,transfer id,trade id,order id\n";
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn parse(rows: &str) -> Vec<(usize, Result<CoinbaseProEntry>)> {
+        parse_entries(format!("{}{}", HEADER, rows).as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_pairs_match_rows_into_trades() {
+        let entries = parse(concat!(
+            "default,match,2024-01-15T10:30:45.123Z,0.01,0.01,BTC,,101,order-a\n",
+            "default,match,2024-01-15T10:30:45.123Z,-425.00,575.00,USD,,101,order-a\n",
+            "default,fee,2024-01-15T10:30:45.123Z,-2.125,572.875,USD,,101,order-a\n",
+            "default,match,2024-02-01T08:00:00Z,-1.5,0,ETH,,7,order-b\n",
+            "default,match,2024-02-01T08:00:00Z,0.08,0.09,BTC,,7,order-b\n",
+            "default,match,2024-03-01T08:00:00Z,-0.09,0,BTC,,8,order-c\n",
+            "default,match,2024-03-01T08:00:00Z,5400,5400,USDC,,8,order-c\n",
+        ));
+        let lines: Vec<usize> = entries.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![2, 4, 5, 7]);
+        let entries: Vec<CoinbaseProEntry> = entries.into_iter().map(|(_, e)| e.unwrap()).collect();
+
+        let buy = entries[0].to_transaction("acc");
+        assert_eq!(buy.tx_type, TransactionType::Buy);
+        assert_eq!(buy.to_asset.as_deref(), Some("BTC"));
+        assert_eq!(buy.to_quantity, Some(dec("0.01")));
+        assert_eq!(buy.price_usd, Some(dec("42500")));
+        assert_eq!(buy.external_id.as_deref(), Some("coinbase-pro:trade:order-a:101"));
+
+        let fee = entries[1].to_transaction("acc");
+        assert_eq!(fee.tx_type, TransactionType::Fee);
+        assert_eq!(fee.fee, Some(dec("2.125")));
+        assert_eq!(fee.fee_asset.as_deref(), Some("USD"));
+        assert_eq!(fee.external_id.as_deref(), Some("coinbase-pro:fee:order-a:101"));
+
+        let swap = entries[2].to_transaction("acc");
+        assert_eq!(swap.tx_type, TransactionType::Swap);
+        assert_eq!(swap.from_asset.as_deref(), Some("ETH"));
+        assert_eq!(swap.from_quantity, Some(dec("1.5")));
+        assert_eq!(swap.to_asset.as_deref(), Some("BTC"));
+
+        let sell = entries[3].to_transaction("acc");
+        assert_eq!(sell.tx_type, TransactionType::Sell);
+        assert_eq!(sell.from_quantity, Some(dec("0.09")));
+        assert_eq!(sell.price_usd, Some(dec("60000")));
+    }
+
+    #[test]
+    fn test_transfers() {
+        let entries = parse(concat!(
+            "default,deposit,2024-01-10T09:00:00Z,1000.00,1000.00,USD,t-1,,\n",
+            "default,withdrawal,2024-01-20T09:00:00Z,-0.5,0.5,BTC,t-2,,\n",
+        ));
+
+        let deposit = entries[0].1.as_ref().unwrap().to_transaction("acc");
+        assert_eq!(deposit.tx_type, TransactionType::TransferIn);
+        assert_eq!(deposit.to_account_id.as_deref(), Some("acc"));
+        assert_eq!(deposit.to_quantity, Some(dec("1000")));
+        assert_eq!(deposit.external_id.as_deref(), Some("coinbase-pro:transfer:t-1"));
+
+        let withdrawal = entries[1].1.as_ref().unwrap().to_transaction("acc");
+        assert_eq!(withdrawal.tx_type, TransactionType::TransferOut);
+        assert_eq!(withdrawal.from_account_id.as_deref(), Some("acc"));
+        assert_eq!(withdrawal.from_asset.as_deref(), Some("BTC"));
+        assert_eq!(withdrawal.from_quantity, Some(dec("0.5")));
+    }
+
+    #[test]
+    fn test_reports_bad_rows() {
+        let entries = parse(concat!(
+            "default,match,2024-01-15T10:30:45Z,0.01,0.01,BTC,,101,order-a\n",
+            "default,conversion,2024-01-16T10:30:45Z,10,10,USDC,,,\n",
+            "default,deposit,yesterday,1,1,BTC,t-1,,\n",
+        ));
+
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|(_, e)| e.is_err()));
+        assert!(parse_entries(b"date,type,asset,quantity\n2024-01-15,buy,BTC,1\n").is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! Holdings snapshot files for bulk manual entry, backup and restore.
+//!
+//! The same structure is read and written as YAML, TOML or JSON:
+//!
+//! ```text
+//! accounts:
+//!   - name: Ledger
+//!     holdings:
+//!       - asset: BTC
+//!         quantity: 0.5
+//!         cost: 45000
+//! ```
+//!
+//! `cost` is the average cost per unit in USD and may be left out.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::error::{CryptofolioError, Result};
+
+/// Formats accepted by `holdings import` and `holdings export`
+pub const HOLDINGS_FILE_FORMATS: &[&str] = &["yaml", "toml", "json"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoldingsFileFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl HoldingsFileFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "yaml" | "yml" => Some(HoldingsFileFormat::Yaml),
+            "toml" => Some(HoldingsFileFormat::Toml),
+            "json" => Some(HoldingsFileFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// Use `--format` when given, otherwise go by the file extension,
+    /// defaulting to YAML
+    pub fn resolve(format: Option<&str>, path: &Path) -> Result<Self> {
+        match format {
+            Some(name) => Self::from_name(name).ok_or_else(|| {
+                CryptofolioError::InvalidInput(format!(
+                    "Unknown holdings file format '{}'. Supported: {}",
+                    name,
+                    HOLDINGS_FILE_FORMATS.join(", ")
+                ))
+            }),
+            None => Ok(path
+                .extension()
+                .and_then(|e| e.to_str())
+                .and_then(Self::from_name)
+                .unwrap_or(HoldingsFileFormat::Yaml)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HoldingsFile {
+    #[serde(default)]
+    pub accounts: Vec<AccountHoldings>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountHoldings {
+    pub name: String,
+    #[serde(default)]
+    pub holdings: Vec<HoldingEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HoldingEntry {
+    pub asset: String,
+    pub quantity: Decimal,
+    /// Average cost per unit in USD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<Decimal>,
+}
+
+impl HoldingsFile {
+    pub fn parse(content: &str, format: HoldingsFileFormat) -> Result<Self> {
+        let file: HoldingsFile = match format {
+            HoldingsFileFormat::Yaml => serde_norway::from_str(content)
+                .map_err(|e| CryptofolioError::InvalidInput(format!("Invalid holdings YAML: {}", e)))?,
+            HoldingsFileFormat::Toml => toml::from_str(content)?,
+            HoldingsFileFormat::Json => serde_json::from_str(content)
+                .map_err(|e| CryptofolioError::InvalidInput(format!("Invalid holdings JSON: {}", e)))?,
+        };
+        file.validate()?;
+        Ok(file)
+    }
+
+    pub fn to_string(&self, format: HoldingsFileFormat) -> Result<String> {
+        match format {
+            HoldingsFileFormat::Yaml => serde_norway::to_string(self)
+                .map_err(|e| CryptofolioError::Other(format!("Failed to write holdings YAML: {}", e))),
+            HoldingsFileFormat::Toml => toml::to_string(self)
+                .map_err(|e| CryptofolioError::Other(format!("Failed to write holdings TOML: {}", e))),
+            HoldingsFileFormat::Json => Ok(serde_json::to_string_pretty(self)? + "\n"),
+        }
+    }
+
+    /// Reject negative amounts and an asset listed twice for one account
+    fn validate(&self) -> Result<()> {
+        let mut seen = HashSet::new();
+        for account in &self.accounts {
+            for entry in &account.holdings {
+                if entry.quantity < Decimal::ZERO {
+                    return Err(CryptofolioError::InvalidAmount(entry.quantity.to_string()));
+                }
+                if entry.cost.is_some_and(|c| c < Decimal::ZERO) {
+                    return Err(CryptofolioError::InvalidAmount(format!("cost {}", entry.cost.unwrap_or_default())));
+                }
+                if !seen.insert((account.name.to_lowercase(), entry.asset.to_uppercase())) {
+                    return Err(CryptofolioError::InvalidInput(format!(
+                        "{} is listed more than once for account '{}'",
+                        entry.asset.to_uppercase(),
+                        account.name
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const YAML: &str = r#"
+accounts:
+  - name: Ledger
+    holdings:
+      - asset: BTC
+        quantity: 0.5
+        cost: 45000
+      - asset: ETH
+        quantity: "2.125"
+  - name: Binance
+"#;
+
+    #[test]
+    fn test_parse_yaml() {
+        let file = HoldingsFile::parse(YAML, HoldingsFileFormat::Yaml).unwrap();
+        assert_eq!(file.accounts.len(), 2);
+
+        let ledger = &file.accounts[0];
+        assert_eq!(ledger.name, "Ledger");
+        assert_eq!(ledger.holdings[0].quantity, Decimal::from_str("0.5").unwrap());
+        assert_eq!(ledger.holdings[0].cost, Some(Decimal::from(45000)));
+        assert_eq!(ledger.holdings[1].quantity, Decimal::from_str("2.125").unwrap());
+        assert_eq!(ledger.holdings[1].cost, None);
+        assert!(file.accounts[1].holdings.is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_both_formats() {
+        let file = HoldingsFile::parse(YAML, HoldingsFileFormat::Yaml).unwrap();
+        for format in [HoldingsFileFormat::Yaml, HoldingsFileFormat::Toml, HoldingsFileFormat::Json] {
+            let written = file.to_string(format).unwrap();
+            assert_eq!(HoldingsFile::parse(&written, format).unwrap(), file);
+        }
+    }
+
+    #[test]
+    fn test_rejects_duplicates_and_negative_quantities() {
+        let duplicate = "accounts:\n  - name: Ledger\n    holdings:\n      - {asset: BTC, quantity: 1}\n      - {asset: btc, quantity: 2}\n";
+        assert!(HoldingsFile::parse(duplicate, HoldingsFileFormat::Yaml).is_err());
+
+        let negative = "[[accounts]]\nname = \"Ledger\"\n[[accounts.holdings]]\nasset = \"BTC\"\nquantity = \"-1\"\n";
+        assert!(HoldingsFile::parse(negative, HoldingsFileFormat::Toml).is_err());
+    }
+
+    #[test]
+    fn test_resolve_format() {
+        let path = Path::new("holdings.toml");
+        assert_eq!(HoldingsFileFormat::resolve(None, path).unwrap(), HoldingsFileFormat::Toml);
+        assert_eq!(HoldingsFileFormat::resolve(Some("yaml"), path).unwrap(), HoldingsFileFormat::Yaml);
+        assert_eq!(HoldingsFileFormat::resolve(None, Path::new("h.json")).unwrap(), HoldingsFileFormat::Json);
+        assert_eq!(HoldingsFileFormat::resolve(None, Path::new("backup")).unwrap(), HoldingsFileFormat::Yaml);
+        assert!(HoldingsFileFormat::resolve(Some("xml"), path).is_err());
+    }
+}
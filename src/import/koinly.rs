@@ -0,0 +1,393 @@
+//! Parser for the transaction CSV exported from Koinly.
+//!
+//! Rows look like:
+//!
+//! ```text
+//! Date,Sent Amount,Sent Currency,Received Amount,Received Currency,Fee Amount,Fee Currency,Net Worth Amount,Net Worth Currency,Label,Description,TxHash
+//! 2024-01-15 10:30:45 UTC,1000,USD,0.025,BTC,1.5,USD,1000,USD,,Coinbase buy,
+//! 2024-02-01 08:00:00 UTC,,,0.01,ETH,,,25.4,USD,staking,Lido reward,0x3f9a...
+//! ```
+//!
+//! A row with both a sent and a received side is a trade; rows with only
+//! one side are deposits, withdrawals and income, refined by `Label`.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::core::currency::is_usd_equivalent;
+use crate::core::transaction::{Transaction, TransactionType};
+use crate::error::{CryptofolioError, Result};
+
+/// Prefix of the external IDs given to imported transactions
+const EXTERNAL_ID_PREFIX: &str = "koinly";
+
+const REQUIRED_COLUMNS: &[&str] = &[
+    "Date",
+    "Sent Amount",
+    "Sent Currency",
+    "Received Amount",
+    "Received Currency",
+];
+
+/// Labels of received-only rows that are income rather than deposits
+const INCOME_LABELS: &[&str] = &["staking", "reward", "mining", "lending interest", "income", "other income"];
+
+/// Labels of sent-only rows that are costs rather than withdrawals
+const COST_LABELS: &[&str] = &["cost", "fee"];
+
+#[derive(Debug, Deserialize)]
+struct KoinlyCsvRow {
+    #[serde(rename = "Date")]
+    date: String,
+    #[serde(rename = "Sent Amount")]
+    sent_amount: String,
+    #[serde(rename = "Sent Currency")]
+    sent_currency: String,
+    #[serde(rename = "Received Amount")]
+    received_amount: String,
+    #[serde(rename = "Received Currency")]
+    received_currency: String,
+    #[serde(rename = "Fee Amount", default)]
+    fee_amount: String,
+    #[serde(rename = "Fee Currency", default)]
+    fee_currency: String,
+    #[serde(rename = "Net Worth Amount", default)]
+    net_worth_amount: String,
+    #[serde(rename = "Net Worth Currency", default)]
+    net_worth_currency: String,
+    #[serde(rename = "Label", default)]
+    label: String,
+    #[serde(rename = "Description", default)]
+    description: String,
+    #[serde(rename = "TxHash", default)]
+    tx_hash: String,
+}
+
+/// An amount together with its asset
+pub type Amount = (Decimal, String);
+
+/// A single row from a Koinly export
+#[derive(Debug, Clone)]
+pub struct KoinlyTransaction {
+    pub timestamp: DateTime<Utc>,
+    pub tx_type: TransactionType,
+    pub sent: Option<Amount>,
+    pub received: Option<Amount>,
+    pub fee: Option<Amount>,
+    /// Value of the transaction when it happened
+    pub net_worth: Option<Amount>,
+    pub label: Option<String>,
+    pub description: Option<String>,
+    pub tx_hash: Option<String>,
+    /// Identity used to skip transactions that were already imported
+    pub external_id: String,
+}
+
+impl KoinlyTransaction {
+    /// Per-unit value of the sent or received side in the net worth currency,
+    /// or in USD from the other side of a trade against a USD-equivalent
+    fn unit_price(&self, (quantity, _): &Amount, other: Option<&Amount>) -> Option<(Decimal, String)> {
+        if quantity.is_zero() {
+            return None;
+        }
+        match (&self.net_worth, other) {
+            (Some((value, currency)), _) => Some((value / quantity, currency.clone())),
+            (None, Some((other_quantity, other_asset))) if is_usd_equivalent(other_asset) => {
+                Some((other_quantity / quantity, "USD".to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    /// USD cost per unit of the received asset: `Net Worth Amount / Received Amount`
+    pub fn cost_basis(&self) -> Option<Decimal> {
+        let received = self.received.as_ref()?;
+        self.unit_price(received, self.sent.as_ref())
+            .filter(|(_, currency)| is_usd_equivalent(currency))
+            .map(|(price, _)| price)
+    }
+
+    /// Build the transaction recorded for this row
+    pub fn to_transaction(&self, account_id: &str) -> Transaction {
+        // Validated in `parse_row`: every type has the side it needs
+        let side = |amount: &Option<Amount>| amount.clone().unwrap_or_default();
+        let (priced, other) = match self.tx_type {
+            TransactionType::Sell | TransactionType::TransferOut | TransactionType::Fee => {
+                (side(&self.sent), self.received.as_ref())
+            }
+            _ => (side(&self.received), self.sent.as_ref()),
+        };
+        let (price_usd, price_currency, price_amount) = match self.unit_price(&priced, other) {
+            Some((price, currency)) if is_usd_equivalent(&currency) => (Some(price), None, None),
+            Some((price, currency)) => (None, Some(currency), Some(price)),
+            None => (None, None, None),
+        };
+        let (quantity, asset) = priced;
+
+        let mut tx = match self.tx_type {
+            TransactionType::Buy => {
+                Transaction::new_buy(account_id, &asset, quantity, Decimal::ZERO, self.timestamp)
+            }
+            TransactionType::Sell => {
+                Transaction::new_sell(account_id, &asset, quantity, Decimal::ZERO, self.timestamp)
+            }
+            TransactionType::Swap => {
+                let (sent_quantity, sent_asset) = side(&self.sent);
+                Transaction::new_swap(account_id, &sent_asset, sent_quantity, &asset, quantity, self.timestamp)
+            }
+            TransactionType::Fee => Transaction::new_fee(account_id, &asset, quantity, self.timestamp),
+            TransactionType::TransferOut => Transaction {
+                tx_type: TransactionType::TransferOut,
+                fee: None,
+                fee_asset: None,
+                ..Transaction::new_fee(account_id, &asset, quantity, self.timestamp)
+            },
+            tx_type => Transaction {
+                tx_type,
+                ..Transaction::new_staking_reward(account_id, &asset, quantity, None, self.timestamp)
+            },
+        };
+
+        tx.price_usd = price_usd;
+        tx.price_currency = price_currency;
+        tx.price_amount = price_amount;
+        if let Some((fee, fee_asset)) = &self.fee {
+            tx.fee = Some(*fee);
+            tx.fee_asset = Some(fee_asset.clone());
+        }
+        tx.external_id = Some(self.external_id.clone());
+        tx.notes = Some(match (&self.description, &self.label) {
+            (Some(description), _) => format!("Koinly: {}", description),
+            (None, Some(label)) => format!("Koinly {}", label),
+            (None, None) => format!("Koinly {}", self.tx_type.display_name()),
+        });
+        tx
+    }
+}
+
+/// Parse a Koinly transaction CSV.
+///
+/// Returns one entry per data row, in file order, so callers can report
+/// errors by line while still importing the rows that parsed.
+pub fn parse_transactions(contents: &[u8]) -> Result<Vec<Result<KoinlyTransaction>>> {
+    let mut reader = csv::Reader::from_reader(contents);
+    let headers = reader.headers()?.clone();
+
+    for column in REQUIRED_COLUMNS {
+        if !headers.iter().any(|h| h.trim() == *column) {
+            return Err(CryptofolioError::InvalidInput(format!(
+                "Not a Koinly export: missing '{}' column",
+                column
+            )));
+        }
+    }
+
+    // Rows sharing a hash (or, without one, a timestamp and amounts) are
+    // numbered so each one gets its own external ID
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+
+    let mut transactions = Vec::new();
+    for record in reader.records() {
+        let transaction = record
+            .and_then(|r| r.deserialize::<KoinlyCsvRow>(Some(&headers)))
+            .map_err(CryptofolioError::Csv)
+            .and_then(|row| parse_row(&row))
+            .map(|mut transaction| {
+                let count = occurrences.entry(transaction.external_id.clone()).or_insert(0);
+                *count += 1;
+                if *count > 1 {
+                    transaction.external_id = format!("{}#{}", transaction.external_id, count);
+                }
+                transaction
+            });
+        transactions.push(transaction);
+    }
+
+    Ok(transactions)
+}
+
+fn parse_row(row: &KoinlyCsvRow) -> Result<KoinlyTransaction> {
+    let timestamp = parse_date(row.date.trim())?;
+
+    let sent = parse_amount(&row.sent_amount, &row.sent_currency)?;
+    let received = parse_amount(&row.received_amount, &row.received_currency)?;
+    let fee = parse_amount(&row.fee_amount, &row.fee_currency)?.filter(|(fee, _)| !fee.is_zero());
+    let net_worth = parse_amount(&row.net_worth_amount, &row.net_worth_currency)?;
+    let label = non_empty(&row.label).map(|l| l.to_lowercase());
+
+    let tx_type = match (&sent, &received) {
+        (Some((_, sent_asset)), Some(_)) if is_usd_equivalent(sent_asset) => TransactionType::Buy,
+        (Some(_), Some((_, received_asset))) if is_usd_equivalent(received_asset) => TransactionType::Sell,
+        (Some(_), Some(_)) => TransactionType::Swap,
+        (None, Some(_)) if label.as_deref().is_some_and(|l| INCOME_LABELS.contains(&l)) => {
+            TransactionType::StakingReward
+        }
+        (None, Some(_)) => TransactionType::Receive,
+        (Some(_), None) if label.as_deref().is_some_and(|l| COST_LABELS.contains(&l)) => TransactionType::Fee,
+        (Some(_), None) => TransactionType::TransferOut,
+        (None, None) => {
+            return Err(CryptofolioError::InvalidInput(
+                "Row has neither a sent nor a received amount".to_string(),
+            ))
+        }
+    };
+
+    let tx_hash = non_empty(&row.tx_hash);
+    let external_id = match &tx_hash {
+        Some(hash) => format!("{}:{}", EXTERNAL_ID_PREFIX, hash),
+        None => {
+            let amount = |a: &Option<Amount>| {
+                a.as_ref().map(|(q, asset)| format!("{}{}", q.normalize(), asset)).unwrap_or_default()
+            };
+            format!(
+                "{}:{}:{}:{}",
+                EXTERNAL_ID_PREFIX,
+                timestamp.format("%Y-%m-%dT%H:%M:%SZ"),
+                amount(&sent),
+                amount(&received)
+            )
+        }
+    };
+
+    Ok(KoinlyTransaction {
+        timestamp,
+        tx_type,
+        sent,
+        received,
+        fee,
+        net_worth,
+        label,
+        description: non_empty(&row.description),
+        tx_hash,
+        external_id,
+    })
+}
+
+/// Koinly writes `2024-01-15 10:30:45 UTC`; plain and RFC 3339 timestamps are accepted too
+fn parse_date(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    let naive = value.strip_suffix("UTC").unwrap_or(value).trim();
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(naive, format).ok())
+        .map(|dt| dt.and_utc())
+        .ok_or_else(|| CryptofolioError::InvalidInput(format!("Invalid date format: {}", value)))
+}
+
+/// An amount and its currency, or None when the amount is blank
+fn parse_amount(amount: &str, currency: &str) -> Result<Option<Amount>> {
+    let Some(amount) = non_empty(amount) else {
+        return Ok(None);
+    };
+    let currency = non_empty(currency)
+        .ok_or_else(|| CryptofolioError::InvalidInput(format!("Amount {} has no currency", amount)))?;
+
+    let quantity = Decimal::from_str(&amount).map_err(|_| CryptofolioError::InvalidAmount(amount.clone()))?;
+    Ok(Some((quantity.abs(), currency.to_uppercase())))
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "Date,Sent Amount,Sent Currency,Received Amount,Received Currency,Fee Amount,Fee Currency,Net Worth Amount,Net Worth Currency,Label,Description,TxHash\n";
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn parse(rows: &str) -> Vec<KoinlyTransaction> {
+        parse_transactions(format!("{}{}", HEADER, rows).as_bytes())
+            .unwrap()
+            .into_iter()
+            .collect::<Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_maps_row_shapes_to_types() {
+        let rows = parse(concat!(
+            "2024-01-15 10:30:45 UTC,1000,USD,0.025,BTC,1.5,USD,1000,USD,,Coinbase buy,\n",
+            "2024-01-20 09:00:00 UTC,0.01,BTC,420,USDT,,,420,USD,,,\n",
+            "2024-01-21 09:00:00 UTC,1,ETH,0.05,BTC,,,2500,USD,,,\n",
+            "2024-02-01 08:00:00 UTC,,,0.01,ETH,,,25.4,USD,staking,,0xabc\n",
+            "2024-02-02 08:00:00 UTC,,,0.5,ETH,,,,,,,0xdef\n",
+            "2024-02-03 08:00:00 UTC,0.1,ETH,,,,,,,,,0x123\n",
+            "2024-02-04 08:00:00 UTC,0.002,ETH,,,,,,,cost,,0x456\n",
+        ));
+        let types: Vec<TransactionType> = rows.iter().map(|r| r.tx_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                TransactionType::Buy,
+                TransactionType::Sell,
+                TransactionType::Swap,
+                TransactionType::StakingReward,
+                TransactionType::Receive,
+                TransactionType::TransferOut,
+                TransactionType::Fee,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cost_basis_from_net_worth() {
+        let rows = parse(concat!(
+            "2024-01-15 10:30:45 UTC,1000,USD,0.025,BTC,1.5,USD,1000,USD,,Coinbase buy,\n",
+            "2024-01-21 09:00:00 UTC,1,ETH,0.05,BTC,,,2500,USD,,,\n",
+            "2024-02-02 08:00:00 UTC,,,0.5,ETH,,,900,EUR,,,0xdef\n",
+        ));
+
+        assert_eq!(rows[0].cost_basis(), Some(dec("40000")));
+        let buy = rows[0].to_transaction("acc");
+        assert_eq!(buy.to_asset.as_deref(), Some("BTC"));
+        assert_eq!(buy.to_quantity, Some(dec("0.025")));
+        assert_eq!(buy.price_usd, Some(dec("40000")));
+        assert_eq!(buy.fee, Some(dec("1.5")));
+        assert_eq!(buy.notes.as_deref(), Some("Koinly: Coinbase buy"));
+
+        assert_eq!(rows[1].cost_basis(), Some(dec("50000")));
+        let swap = rows[1].to_transaction("acc");
+        assert_eq!(swap.from_asset.as_deref(), Some("ETH"));
+        assert_eq!(swap.to_quantity, Some(dec("0.05")));
+
+        // Valued in another currency: no USD cost basis
+        assert_eq!(rows[2].cost_basis(), None);
+        let receive = rows[2].to_transaction("acc");
+        assert_eq!(receive.price_usd, None);
+        assert_eq!(receive.price_currency.as_deref(), Some("EUR"));
+        assert_eq!(receive.price_amount, Some(dec("1800")));
+    }
+
+    #[test]
+    fn test_external_ids_use_tx_hash() {
+        let rows = parse(concat!(
+            "2024-02-01 08:00:00 UTC,,,0.01,ETH,,,,,,,0xabc\n",
+            "2024-02-01 08:00:00 UTC,,,5,LDO,,,,,,,0xabc\n",
+            "2024-01-15 10:30:45 UTC,1000,USD,0.025,BTC,,,,,,,\n",
+        ));
+
+        assert_eq!(rows[0].external_id, "koinly:0xabc");
+        assert_eq!(rows[1].external_id, "koinly:0xabc#2");
+        assert_eq!(rows[2].external_id, "koinly:2024-01-15T10:30:45Z:1000USD:0.025BTC");
+    }
+
+    #[test]
+    fn test_rejects_other_formats() {
+        assert!(parse_transactions(b"date,type,asset,quantity\n2024-01-15,buy,BTC,1\n").is_err());
+
+        let csv = format!("{}{}", HEADER, "2024-01-15 10:30:45 UTC,,,,,,,,,,,\n");
+        assert!(parse_transactions(csv.as_bytes()).unwrap()[0].is_err());
+    }
+}
@@ -0,0 +1,282 @@
+//! Parser for the operations CSV exported from Ledger Live.
+//!
+//! Rows look like:
+//!
+//! ```text
+//! Operation Date,Currency Ticker,Operation Type,Operation Amount,Operation Fees,Operation Hash,Account Name,Account xpub,Countervalue Ticker,Countervalue at Operation Date(EUR)
+//! 2024-01-15T10:30:45.000Z,BTC,IN,0.01,0.00001,3f9a...,Bitcoin 1,xpub6C...,EUR,389.52
+//! ```
+//!
+//! The countervalue column is named after the currency chosen in Ledger
+//! Live, so columns are looked up by name rather than position.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use crate::core::currency::is_usd_equivalent;
+use crate::core::transaction::{Transaction, TransactionType};
+use crate::error::{CryptofolioError, Result};
+
+/// Prefix of the external IDs given to imported operations
+const EXTERNAL_ID_PREFIX: &str = "ledger-live";
+
+const REQUIRED_COLUMNS: &[&str] = &[
+    "Operation Date",
+    "Currency Ticker",
+    "Operation Type",
+    "Operation Amount",
+    "Operation Fees",
+    "Operation Hash",
+];
+
+/// Prefix of the countervalue column, followed by the currency in parentheses
+const COUNTERVALUE_COLUMN: &str = "Countervalue at Operation Date";
+
+/// A single operation from a Ledger Live export
+#[derive(Debug, Clone)]
+pub struct LedgerOperation {
+    pub timestamp: DateTime<Utc>,
+    pub asset: String,
+    /// Receive for IN, TransferOut for OUT and Fee for FEES
+    pub tx_type: TransactionType,
+    pub quantity: Decimal,
+    pub fee: Option<Decimal>,
+    pub hash: String,
+    pub account_name: Option<String>,
+    /// Value of the operation in `countervalue_currency` when it happened
+    pub countervalue: Option<Decimal>,
+    pub countervalue_currency: Option<String>,
+    /// Identity used to skip operations that were already imported
+    pub external_id: String,
+}
+
+impl LedgerOperation {
+    /// Build the transaction recorded for this operation
+    pub fn to_transaction(&self, account_id: &str) -> Transaction {
+        let incoming = self.tx_type == TransactionType::Receive;
+        let account = Some(account_id.to_string());
+        let asset = Some(self.asset.clone());
+
+        // Per-unit price from the countervalue, in USD when the export was in a USD currency
+        let price = self
+            .countervalue
+            .filter(|_| !self.quantity.is_zero())
+            .map(|value| value / self.quantity);
+        let (price_usd, price_currency, price_amount) = match (&self.countervalue_currency, price) {
+            (Some(currency), Some(price)) if is_usd_equivalent(currency) => (Some(price), None, None),
+            (Some(currency), Some(price)) => (None, Some(currency.clone()), Some(price)),
+            _ => (None, None, None),
+        };
+
+        let (fee, fee_asset) = match (self.tx_type, self.fee) {
+            (TransactionType::TransferOut, Some(fee)) if !fee.is_zero() => (Some(fee), Some(self.asset.clone())),
+            (TransactionType::Fee, _) => (Some(self.quantity), Some(self.asset.clone())),
+            _ => (None, None),
+        };
+
+        let notes = match &self.account_name {
+            Some(name) => format!("Ledger Live {} ({})", self.tx_type.display_name(), name),
+            None => format!("Ledger Live {}", self.tx_type.display_name()),
+        };
+
+        Transaction {
+            id: 0,
+            tx_type: self.tx_type,
+            from_account_id: if incoming { None } else { account.clone() },
+            from_asset: if incoming { None } else { asset.clone() },
+            from_quantity: if incoming { None } else { Some(self.quantity) },
+            to_account_id: if incoming { account } else { None },
+            to_asset: if incoming { asset } else { None },
+            to_quantity: if incoming { Some(self.quantity) } else { None },
+            price_usd,
+            price_currency,
+            price_amount,
+            exchange_rate: None,
+            exchange_rate_pair: None,
+            fee,
+            fee_asset,
+            external_id: Some(self.external_id.clone()),
+            notes: Some(notes),
+            timestamp: self.timestamp,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Parse a Ledger Live operations CSV.
+///
+/// Returns one entry per data row, in file order, so callers can report
+/// errors by line while still importing the rows that parsed.
+pub fn parse_operations(contents: &[u8]) -> Result<Vec<Result<LedgerOperation>>> {
+    let mut reader = csv::Reader::from_reader(contents);
+    let headers = reader.headers()?.clone();
+
+    let column = |name: &str| headers.iter().position(|h| h.trim() == name);
+    let mut indices = Vec::new();
+    for name in REQUIRED_COLUMNS {
+        let index = column(name).ok_or_else(|| {
+            CryptofolioError::InvalidInput(format!("Not a Ledger Live export: missing '{}' column", name))
+        })?;
+        indices.push(index);
+    }
+    let account_index = column("Account Name");
+    let countervalue = headers
+        .iter()
+        .position(|h| h.trim().starts_with(COUNTERVALUE_COLUMN))
+        .map(|i| (i, countervalue_currency(&headers[i])));
+
+    let mut operations = Vec::new();
+    for record in reader.records() {
+        let operation = record.map_err(CryptofolioError::Csv).and_then(|r| {
+            let field = |i: usize| r.get(i).unwrap_or_default().trim();
+            let fields: Vec<&str> = indices.iter().map(|&i| field(i)).collect();
+            parse_row(
+                &fields,
+                account_index.map(field).filter(|s| !s.is_empty()),
+                countervalue.as_ref().map(|(i, currency)| (field(*i), currency.as_deref())),
+            )
+        });
+        operations.push(operation);
+    }
+
+    Ok(operations)
+}
+
+/// `fields` holds the values of [`REQUIRED_COLUMNS`], in order
+fn parse_row(
+    fields: &[&str],
+    account_name: Option<&str>,
+    countervalue: Option<(&str, Option<&str>)>,
+) -> Result<LedgerOperation> {
+    let [date, ticker, op_type, amount, fees, hash] = fields else {
+        return Err(CryptofolioError::InvalidInput("Incomplete row".to_string()));
+    };
+
+    let timestamp = DateTime::parse_from_rfc3339(date)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| CryptofolioError::InvalidInput(format!("Invalid date format: {}", date)))?;
+
+    let tx_type = match op_type.to_uppercase().as_str() {
+        "IN" => TransactionType::Receive,
+        "OUT" => TransactionType::TransferOut,
+        "FEES" => TransactionType::Fee,
+        other => {
+            return Err(CryptofolioError::InvalidInput(format!(
+                "Unsupported operation type: {} (expected IN, OUT or FEES)",
+                other
+            )))
+        }
+    };
+
+    if hash.is_empty() {
+        return Err(CryptofolioError::InvalidInput("Missing operation hash".to_string()));
+    }
+
+    let asset = ticker.to_uppercase();
+    let quantity = parse_number(amount)?.abs();
+    let fee = (!fees.is_empty()).then(|| parse_number(fees)).transpose()?.map(|f| f.abs());
+    let (countervalue, countervalue_currency) = match countervalue {
+        Some((value, currency)) if !value.is_empty() => {
+            (Some(parse_number(value)?.abs()), currency.map(|c| c.to_string()))
+        }
+        _ => (None, None),
+    };
+
+    // One transaction hash can cover several operations, such as a token
+    // transfer and the fee paid for it in the chain's coin
+    let external_id = format!("{}:{}:{}:{}", EXTERNAL_ID_PREFIX, hash, asset, op_type.to_uppercase());
+
+    Ok(LedgerOperation {
+        timestamp,
+        asset,
+        tx_type,
+        quantity,
+        fee,
+        hash: hash.to_string(),
+        account_name: account_name.map(|s| s.to_string()),
+        countervalue,
+        countervalue_currency,
+        external_id,
+    })
+}
+
+/// Currency of a `Countervalue at Operation Date(EUR)` header
+fn countervalue_currency(header: &str) -> Option<String> {
+    let (_, rest) = header.split_once('(')?;
+    let currency = rest.strip_suffix(')')?.trim();
+    (!currency.is_empty()).then(|| currency.to_uppercase())
+}
+
+fn parse_number(value: &str) -> Result<Decimal> {
+    Decimal::from_str(value.trim()).map_err(|_| CryptofolioError::InvalidAmount(value.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "Operation Date,Currency Ticker,Operation Type,Operation Amount,Operation Fees,Operation Hash,Account Name,Account xpub,Countervalue Ticker,Countervalue at Operation Date(EUR)\n";
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn parse(rows: &str) -> Vec<LedgerOperation> {
+        parse_operations(format!("{}{}", HEADER, rows).as_bytes())
+            .unwrap()
+            .into_iter()
+            .collect::<Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_operation_types() {
+        let ops = parse(concat!(
+            "2024-01-15T10:30:45.000Z,BTC,IN,0.01,0.00001,aaa,Bitcoin 1,xpub1,EUR,400\n",
+            "2024-02-01T08:00:00.000Z,BTC,OUT,0.004,0.00002,bbb,Bitcoin 1,xpub1,EUR,160\n",
+            "2024-03-01T08:00:00.000Z,ETH,FEES,0.002,0.002,ccc,Ethereum 1,xpub2,EUR,5\n",
+        ));
+
+        let receive = ops[0].to_transaction("ledger");
+        assert_eq!(receive.tx_type, TransactionType::Receive);
+        assert_eq!(receive.to_quantity, Some(dec("0.01")));
+        assert_eq!(receive.fee, None);
+        assert_eq!(receive.price_currency.as_deref(), Some("EUR"));
+        assert_eq!(receive.price_amount, Some(dec("40000")));
+        assert_eq!(receive.external_id.as_deref(), Some("ledger-live:aaa:BTC:IN"));
+
+        let send = ops[1].to_transaction("ledger");
+        assert_eq!(send.tx_type, TransactionType::TransferOut);
+        assert_eq!(send.from_quantity, Some(dec("0.004")));
+        assert_eq!(send.fee, Some(dec("0.00002")));
+        assert_eq!(send.fee_asset.as_deref(), Some("BTC"));
+
+        let fee = ops[2].to_transaction("ledger");
+        assert_eq!(fee.tx_type, TransactionType::Fee);
+        assert_eq!(fee.from_asset.as_deref(), Some("ETH"));
+        assert_eq!(fee.from_quantity, Some(dec("0.002")));
+    }
+
+    #[test]
+    fn test_usd_countervalue_sets_usd_price() {
+        let csv = HEADER.replace("(EUR)", "(USD)") + "2024-01-15T10:30:45.000Z,BTC,IN,0.5,,aaa,,,USD,21000\n";
+        let ops: Vec<LedgerOperation> = parse_operations(csv.as_bytes())
+            .unwrap()
+            .into_iter()
+            .collect::<Result<_>>()
+            .unwrap();
+
+        let tx = ops[0].to_transaction("ledger");
+        assert_eq!(tx.price_usd, Some(dec("42000")));
+        assert_eq!(tx.price_currency, None);
+    }
+
+    #[test]
+    fn test_rejects_other_formats_and_types() {
+        assert!(parse_operations(b"date,type,asset,quantity\n2024-01-15,buy,BTC,1\n").is_err());
+
+        let csv = format!("{}{}", HEADER, "2024-01-15T10:30:45.000Z,ATOM,DELEGATE,10,0.01,ddd,Cosmos 1,,EUR,80\n");
+        assert!(parse_operations(csv.as_bytes()).unwrap()[0].is_err());
+    }
+}
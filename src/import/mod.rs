@@ -0,0 +1,8 @@
+//! Importers for transaction exports from third-party platforms, and the
+//! holdings snapshot files used by `holdings import` and `holdings export`
+
+pub mod binance_csv;
+pub mod coinbase_pro;
+pub mod holdings_file;
+pub mod koinly;
+pub mod ledger_live;
@@ -5,4 +5,8 @@ pub mod core;
 pub mod db;
 pub mod error;
 pub mod exchange;
+pub mod export;
+pub mod import;
+pub mod market;
+pub mod mcp;
 pub mod shell;
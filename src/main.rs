@@ -1,20 +1,24 @@
 use clap::Parser;
 
 use cryptofolio::cli::commands::{
-    handle_account_command, handle_category_command, handle_config_command,
-    handle_currency_command, handle_holdings_command, handle_import_command,
-    handle_market_command, handle_portfolio_command, handle_price_command,
-    handle_status_command, handle_sync_command, handle_tx_command,
+    handle_account_command, handle_ai_command, handle_alert_command, handle_category_command, handle_config_command,
+    handle_currency_command, handle_db_command, handle_export_command, handle_holdings_command,
+    handle_import_command, handle_import_full_command, handle_market_command,
+    handle_portfolio_command, handle_portfolio_subcommand, handle_price_command,
+    handle_status_command, handle_sync_command, handle_tax_command, handle_tx_command, handle_watch_command,
+    ImportTarget, PortfolioOptions,
 };
-use cryptofolio::cli::output::init_color;
-use cryptofolio::cli::{Cli, Commands, GlobalOptions};
+use cryptofolio::cli::output::{init_color, init_number_format};
+use cryptofolio::cli::{Cli, Commands, GlobalOptions, ImportCommands};
+use cryptofolio::config::settings::{init_config_path, AppConfig};
+use cryptofolio::exchange::binance::set_retry_enabled;
 use cryptofolio::error::Result;
 
 #[tokio::main]
 async fn main() {
     if let Err(e) = run().await {
         cryptofolio::cli::output::error(&e.to_string());
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     }
 }
 
@@ -27,9 +31,19 @@ async fn run() -> Result<()> {
     // Initialize global options
     let opts = GlobalOptions::from_cli(&cli);
 
+    // Point config loading at --config before anything reads it
+    init_config_path(opts.config_path.clone());
+
     // Initialize color settings
     init_color(opts.no_color);
 
+    // Currency symbol and separators; commands that need the config report load errors themselves
+    if let Ok(config) = AppConfig::load() {
+        init_number_format(&config.display);
+    }
+
+    set_retry_enabled(!opts.no_retry);
+
     // Initialize database
     let pool = cryptofolio::db::init_pool().await?;
 
@@ -38,8 +52,8 @@ async fn run() -> Result<()> {
             handle_price_command(symbols, &pool, &opts).await?;
         }
 
-        Commands::Market { symbol, show_24h } => {
-            handle_market_command(symbol, show_24h, &pool, &opts).await?;
+        Commands::Market { symbol, show_24h, source, compare } => {
+            handle_market_command(symbol, show_24h, source, compare, &pool, &opts).await?;
         }
 
         Commands::Account { command } => {
@@ -54,29 +68,90 @@ async fn run() -> Result<()> {
             handle_holdings_command(command, &pool, &opts).await?;
         }
 
+        Commands::Portfolio { command: Some(command), .. } => {
+            handle_portfolio_subcommand(command, &pool, &opts).await?;
+        }
+
         Commands::Portfolio {
+            command: None,
             by_account,
             by_category,
             account,
             category,
+            asset_type,
+            format,
+            output_file,
+            append,
+            total_invested,
+            show_fees,
+            net_worth,
+            currency,
+            tag,
+            cost_basis,
+            auto_snapshot,
+            unrealized_only,
+            realized_only,
+            benchmark,
+            from,
+            to,
         } => {
-            handle_portfolio_command(by_account, by_category, account, category, &pool, &opts).await?;
+            let options = PortfolioOptions {
+                by_account,
+                by_category,
+                account,
+                category,
+                asset_type,
+                format,
+                output_file,
+                append,
+                total_invested,
+                show_fees,
+                net_worth,
+                currency,
+                tag,
+                cost_basis,
+                auto_snapshot,
+                unrealized_only,
+                realized_only,
+                benchmark,
+                from,
+                to,
+            };
+            handle_portfolio_command(options, &pool, &opts).await?;
         }
 
         Commands::Tx { command } => {
             handle_tx_command(command, &pool, &opts).await?;
         }
 
-        Commands::Sync { account } => {
-            handle_sync_command(account, &pool, &opts).await?;
+        Commands::Sync { command, account, show_changes, dry_run } => {
+            handle_sync_command(command, account, show_changes, dry_run, &pool, &opts).await?;
         }
 
         Commands::Import {
+            command: Some(ImportCommands::Full { input, merge: _, replace, yes }),
+            ..
+        } => {
+            handle_import_full_command(input, replace, yes, &pool, &opts).await?;
+        }
+
+        Commands::Import {
+            command: None,
             file,
             account,
+            account_column,
             format,
+            dry_run,
         } => {
-            handle_import_command(file, account, format, &pool, &opts).await?;
+            let target = match account_column {
+                Some(column) => ImportTarget::Column(column),
+                None => ImportTarget::Account(account.unwrap_or_default()),
+            };
+            handle_import_command(file.unwrap_or_default(), target, format, dry_run, &pool, &opts).await?;
+        }
+
+        Commands::Export { command, all } => {
+            handle_export_command(command, all, &pool, &opts).await?;
         }
 
         Commands::Config { command } => {
@@ -87,14 +162,39 @@ async fn run() -> Result<()> {
             handle_currency_command(&pool, command).await?;
         }
 
-        Commands::Shell => {
-            let mut shell = cryptofolio::shell::Shell::new(pool, opts).await?;
+        Commands::Db { command } => {
+            handle_db_command(command, &pool, &opts).await?;
+        }
+
+        Commands::Alert { command } => {
+            handle_alert_command(command, &pool, &opts).await?;
+        }
+
+        Commands::Tax { command } => {
+            handle_tax_command(command, &pool, &opts).await?;
+        }
+
+        Commands::Shell { no_history, live_prices, no_persist_context } => {
+            let mut shell =
+                cryptofolio::shell::Shell::new(pool, opts, no_history, live_prices, !no_persist_context).await?;
             shell.run().await?;
         }
 
         Commands::Status { check } => {
             handle_status_command(check).await?;
         }
+
+        Commands::Ai { command } => {
+            handle_ai_command(command, &opts).await?;
+        }
+
+        Commands::Watch { interval, alert_pct } => {
+            handle_watch_command(interval, alert_pct, &pool, &opts).await?;
+        }
+
+        Commands::McpServer { transport, bind } => {
+            cryptofolio::mcp::serve(transport, &bind, pool, opts).await?;
+        }
     }
 
     Ok(())
@@ -0,0 +1,243 @@
+//! CoinGecko public API client for pricing assets that aren't listed on Binance.
+//!
+//! CoinGecko identifies coins by id (`bitcoin`) rather than ticker, so the
+//! full coin list is cached in the `asset_metadata` table and symbols are
+//! resolved locally. The list is only downloaded again once it is older than
+//! [`METADATA_TTL_DAYS`] and a symbol can't be found, which keeps each lookup
+//! to a single price request under the public rate limit.
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use reqwest::{Client, StatusCode};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use super::{AssetMetadata, MarketDataProvider};
+use crate::db::AssetMetadataRepository;
+use crate::error::{CryptofolioError, Result};
+use crate::exchange::models::{MarketData, PriceData};
+
+const COINGECKO_API_URL: &str = "https://api.coingecko.com/api/v3";
+
+/// Age after which the cached coin list is refreshed on a lookup miss
+pub const METADATA_TTL_DAYS: i64 = 7;
+
+#[derive(Debug, Deserialize)]
+struct CoinListEntry {
+    id: String,
+    symbol: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SimplePrice {
+    usd: Option<f64>,
+}
+
+pub struct CoinGeckoClient {
+    client: Client,
+    base_url: String,
+    pool: SqlitePool,
+}
+
+impl CoinGeckoClient {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: COINGECKO_API_URL.to_string(),
+            pool,
+        }
+    }
+
+    /// Resolve a symbol to a CoinGecko coin, refreshing a stale cache on a miss
+    pub async fn resolve(&self, symbol: &str) -> Result<Option<AssetMetadata>> {
+        let repo = AssetMetadataRepository::new(&self.pool);
+
+        let candidates = repo.find_by_symbol(symbol).await?;
+        if !candidates.is_empty() {
+            return Ok(pick_coin(candidates));
+        }
+
+        let stale = match repo.last_updated().await? {
+            Some(updated_at) => Utc::now() - updated_at > Duration::days(METADATA_TTL_DAYS),
+            None => true,
+        };
+        if !stale {
+            return Ok(None);
+        }
+
+        self.refresh_metadata().await?;
+        Ok(pick_coin(repo.find_by_symbol(symbol).await?))
+    }
+
+    /// Download the full coin list and replace the cached metadata
+    pub async fn refresh_metadata(&self) -> Result<usize> {
+        let coins: Vec<CoinListEntry> = self.get_json("/coins/list", &[]).await?;
+
+        let metadata: Vec<AssetMetadata> = coins
+            .into_iter()
+            .map(|c| AssetMetadata {
+                symbol: c.symbol.to_uppercase(),
+                name: c.name,
+                coingecko_id: c.id,
+            })
+            .collect();
+
+        AssetMetadataRepository::new(&self.pool).replace_all(&metadata).await?;
+        Ok(metadata.len())
+    }
+
+    /// Fetch USD prices keyed by CoinGecko id
+    async fn get_simple_prices(&self, ids: &[&str]) -> Result<HashMap<String, SimplePrice>> {
+        let ids = ids.join(",");
+        self.get_json("/simple/price", &[("ids", ids.as_str()), ("vs_currencies", "usd")]).await
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T> {
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .query(query)
+            .header("Accept", "application/json")
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(response.json().await?),
+            StatusCode::TOO_MANY_REQUESTS => Err(CryptofolioError::ExchangeApi(
+                "CoinGecko rate limit reached, try again in a minute".to_string(),
+            )),
+            status => Err(CryptofolioError::ExchangeApi(format!("CoinGecko API error: {}", status))),
+        }
+    }
+
+    /// Resolve symbols, returning (symbol, metadata) for the ones CoinGecko knows
+    async fn resolve_all(&self, symbols: &[&str]) -> Result<Vec<(String, AssetMetadata)>> {
+        let mut resolved = Vec::new();
+        for symbol in symbols {
+            if let Some(metadata) = self.resolve(symbol).await? {
+                resolved.push((symbol.to_uppercase(), metadata));
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for CoinGeckoClient {
+    fn name(&self) -> &str {
+        "CoinGecko"
+    }
+
+    async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+        self.get_prices(&[symbol])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| CryptofolioError::AssetNotFound(symbol.to_uppercase()))
+    }
+
+    async fn get_prices(&self, symbols: &[&str]) -> Result<Vec<PriceData>> {
+        let resolved = self.resolve_all(symbols).await?;
+        if resolved.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<&str> = resolved.iter().map(|(_, m)| m.coingecko_id.as_str()).collect();
+        let prices = self.get_simple_prices(&ids).await?;
+
+        Ok(resolved
+            .into_iter()
+            .filter_map(|(symbol, metadata)| {
+                let price = prices.get(&metadata.coingecko_id)?.usd.and_then(Decimal::from_f64)?;
+                Some(PriceData { symbol, price })
+            })
+            .collect())
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        let symbol = symbol.to_uppercase();
+        let metadata = self
+            .resolve(&symbol)
+            .await?
+            .ok_or_else(|| CryptofolioError::AssetNotFound(symbol.clone()))?;
+
+        let prices = self.get_simple_prices(&[&metadata.coingecko_id]).await?;
+        let price = prices
+            .get(&metadata.coingecko_id)
+            .and_then(|p| p.usd)
+            .and_then(Decimal::from_f64)
+            .ok_or_else(|| CryptofolioError::AssetNotFound(symbol.clone()))?;
+
+        // The simple price endpoint has no 24h high/low, so no ticker is reported
+        Ok(MarketData {
+            symbol: format!("{}USD", symbol),
+            base_asset: symbol,
+            quote_asset: "USD".to_string(),
+            price,
+            ticker_24h: None,
+        })
+    }
+
+    async fn get_asset_metadata(&self, symbol: &str) -> Result<Option<AssetMetadata>> {
+        self.resolve(symbol).await
+    }
+}
+
+/// Choose between coins sharing a ticker. Bridged and wrapped copies usually
+/// have longer ids than the original (`ethereum` vs `ethereum-wormhole`), so
+/// prefer an id matching the coin's name, then the shortest id.
+fn pick_coin(mut candidates: Vec<AssetMetadata>) -> Option<AssetMetadata> {
+    candidates.sort_by(|a, b| {
+        let a_named = a.coingecko_id == a.name.to_lowercase();
+        let b_named = b.coingecko_id == b.name.to_lowercase();
+        b_named
+            .cmp(&a_named)
+            .then(a.coingecko_id.len().cmp(&b.coingecko_id.len()))
+            .then(a.coingecko_id.cmp(&b.coingecko_id))
+    });
+    candidates.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coin(symbol: &str, name: &str, id: &str) -> AssetMetadata {
+        AssetMetadata {
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            coingecko_id: id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_pick_coin_prefers_canonical_id() {
+        let picked = pick_coin(vec![
+            coin("ETH", "Ethereum (Wormhole)", "ethereum-wormhole"),
+            coin("ETH", "Ethereum", "ethereum"),
+            coin("ETH", "Bridged Ether", "eth"),
+        ]);
+        assert_eq!(picked.unwrap().coingecko_id, "ethereum");
+
+        let picked = pick_coin(vec![
+            coin("PNUT", "Peanut the Squirrel Token", "peanut-the-squirrel-token"),
+            coin("PNUT", "Peanut", "peanut"),
+        ]);
+        assert_eq!(picked.unwrap().coingecko_id, "peanut");
+
+        assert!(pick_coin(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_parse_simple_price() {
+        let body = r#"{"bitcoin":{"usd":67187.33},"unlisted-coin":{}}"#;
+        let prices: HashMap<String, SimplePrice> = serde_json::from_str(body).unwrap();
+        let price = prices["bitcoin"].usd.and_then(Decimal::from_f64).unwrap();
+        assert_eq!(price.round_dp(2).to_string(), "67187.33");
+        assert!(prices["unlisted-coin"].usd.is_none());
+    }
+}
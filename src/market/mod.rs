@@ -0,0 +1,85 @@
+//! Market data providers used to price assets outside the exchange clients
+
+pub mod coingecko;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::exchange::models::{MarketData, PriceData};
+use crate::exchange::{BinanceClient, Exchange, KrakenClient};
+
+pub use coingecko::CoinGeckoClient;
+
+/// Name and provider identity of an asset symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetMetadata {
+    pub symbol: String,
+    pub name: String,
+    pub coingecko_id: String,
+}
+
+#[async_trait]
+pub trait MarketDataProvider: Send + Sync {
+    /// Get the provider name
+    fn name(&self) -> &str;
+
+    /// Get current USD price for a symbol
+    async fn get_price(&self, symbol: &str) -> Result<PriceData>;
+
+    /// Get USD prices for multiple symbols, omitting symbols without a price
+    async fn get_prices(&self, symbols: &[&str]) -> Result<Vec<PriceData>>;
+
+    /// Get full market data for a symbol
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData>;
+
+    /// Look up the name and provider identity of a symbol
+    async fn get_asset_metadata(&self, symbol: &str) -> Result<Option<AssetMetadata>>;
+}
+
+#[async_trait]
+impl MarketDataProvider for BinanceClient {
+    fn name(&self) -> &str {
+        Exchange::name(self)
+    }
+
+    async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+        Exchange::get_price(self, symbol).await
+    }
+
+    async fn get_prices(&self, symbols: &[&str]) -> Result<Vec<PriceData>> {
+        Exchange::get_prices(self, symbols).await
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        Exchange::get_market_data(self, symbol).await
+    }
+
+    async fn get_asset_metadata(&self, _symbol: &str) -> Result<Option<AssetMetadata>> {
+        // Binance symbols carry no metadata beyond the ticker
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for KrakenClient {
+    fn name(&self) -> &str {
+        Exchange::name(self)
+    }
+
+    async fn get_price(&self, symbol: &str) -> Result<PriceData> {
+        Exchange::get_price(self, symbol).await
+    }
+
+    async fn get_prices(&self, symbols: &[&str]) -> Result<Vec<PriceData>> {
+        Exchange::get_prices(self, symbols).await
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        Exchange::get_market_data(self, symbol).await
+    }
+
+    async fn get_asset_metadata(&self, _symbol: &str) -> Result<Option<AssetMetadata>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,56 @@
+//! MCP (Model Context Protocol) server exposing portfolio tools
+//!
+//! Tools delegate to the command handlers in `cli::commands` and return the
+//! same JSON those commands print with `--json`.
+
+mod server;
+
+pub use server::CryptofolioServer;
+
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::{stdio, StreamableHttpServerConfig, StreamableHttpService};
+use rmcp::ServiceExt;
+use sqlx::SqlitePool;
+
+use crate::cli::output::info;
+use crate::cli::{GlobalOptions, McpTransport};
+use crate::error::{CryptofolioError, Result};
+
+/// Path the HTTP transport serves the protocol at
+const HTTP_PATH: &str = "/mcp";
+
+/// Serve the portfolio tools until the client disconnects (stdio) or the
+/// process is interrupted (HTTP)
+pub async fn serve(transport: McpTransport, bind: &str, pool: SqlitePool, opts: GlobalOptions) -> Result<()> {
+    match transport {
+        McpTransport::Stdio => {
+            let service = CryptofolioServer::new(pool, opts)
+                .serve(stdio())
+                .await
+                .map_err(|e| CryptofolioError::Mcp(e.to_string()))?;
+            service.waiting().await.map_err(|e| CryptofolioError::Mcp(e.to_string()))?;
+        }
+        McpTransport::Http => {
+            let opts_quiet = opts.quiet;
+            let service: StreamableHttpService<CryptofolioServer, LocalSessionManager> = StreamableHttpService::new(
+                move || Ok(CryptofolioServer::new(pool.clone(), opts.clone())),
+                Default::default(),
+                StreamableHttpServerConfig::default(),
+            );
+            let router = axum::Router::new().nest_service(HTTP_PATH, service);
+            let listener = tokio::net::TcpListener::bind(bind).await?;
+
+            if !opts_quiet {
+                info(&format!("MCP server listening on http://{}{}", listener.local_addr()?, HTTP_PATH));
+            }
+
+            axum::serve(listener, router)
+                .with_graceful_shutdown(async {
+                    let _ = tokio::signal::ctrl_c().await;
+                })
+                .await?;
+        }
+    }
+
+    Ok(())
+}
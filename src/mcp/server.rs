@@ -0,0 +1,226 @@
+use rmcp::handler::server::router::tool::ToolRouter;
+use rmcp::handler::server::wrapper::{Json, Parameters};
+use rmcp::model::{Implementation, ServerCapabilities, ServerInfo};
+use rmcp::{tool, tool_handler, tool_router, ServerHandler};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::cli::commands::account::{account_list_output, AccountListOutput};
+use crate::cli::commands::holdings::{holdings_output, HoldingOutput};
+use crate::cli::commands::portfolio::{portfolio_output, PortfolioOutput};
+use crate::cli::commands::price::{price_output, PriceOutput};
+use crate::cli::commands::sync::{sync_account_output, SyncOutput};
+use crate::cli::commands::tx::{parse_trade, record_buy, record_sell, transaction_to_output, TradeArgs, TransactionOutput};
+use crate::cli::GlobalOptions;
+use crate::db::{AccountRepository, ListAccountsFilter};
+use crate::error::CryptofolioError;
+
+#[derive(Deserialize, JsonSchema)]
+struct PortfolioParams {
+    /// Only value this account
+    account: Option<String>,
+    /// Only value accounts in this category
+    category: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct HoldingsParams {
+    /// Only list this account's holdings
+    account: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct TradeParams {
+    /// Asset symbol, e.g. BTC
+    asset: String,
+    /// Quantity as a decimal string
+    quantity: String,
+    /// Account name
+    account: String,
+    /// Price per unit in USD, as a decimal string
+    price: String,
+    /// Trading fee quantity, as a decimal string
+    fee: Option<String>,
+    /// Asset the fee was paid in (default: USD)
+    fee_asset: Option<String>,
+    /// Transaction notes
+    notes: Option<String>,
+}
+
+impl From<TradeParams> for TradeArgs {
+    fn from(params: TradeParams) -> Self {
+        Self {
+            asset: params.asset,
+            quantity: params.quantity,
+            account: params.account,
+            price: params.price,
+            fee: params.fee,
+            fee_asset: params.fee_asset,
+            notes: params.notes,
+            max_decimals: None,
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct SyncParams {
+    /// Name of an exchange account with sync enabled, or a wallet with addresses
+    account: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct PriceParams {
+    /// Asset symbols, e.g. ["BTC", "ETH"]
+    symbols: Vec<String>,
+}
+
+/// Tool outputs must be JSON objects, so lists are wrapped
+#[derive(Serialize, JsonSchema)]
+struct HoldingsResult {
+    holdings: Vec<HoldingOutput>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct AccountsResult {
+    accounts: Vec<AccountListOutput>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct PricesResult {
+    prices: Vec<PriceOutput>,
+}
+
+/// Tool errors are reported to the client as text
+fn tool_error(e: CryptofolioError) -> String {
+    e.to_string()
+}
+
+/// MCP server exposing the portfolio over the command handlers
+#[derive(Clone)]
+pub struct CryptofolioServer {
+    pool: SqlitePool,
+    opts: GlobalOptions,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl CryptofolioServer {
+    pub fn new(pool: SqlitePool, opts: GlobalOptions) -> Self {
+        Self {
+            pool,
+            opts,
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Portfolio valued at current prices, with cost basis and unrealized P&L per holding")]
+    async fn get_portfolio(&self, Parameters(params): Parameters<PortfolioParams>) -> Result<Json<PortfolioOutput>, String> {
+        portfolio_output(params.account.as_deref(), params.category.as_deref(), &self.pool, &self.opts)
+            .await
+            .map_err(tool_error)?
+            .map(Json)
+            .ok_or_else(|| "No accounts configured".to_string())
+    }
+
+    #[tool(description = "Holdings across accounts with quantities, cost basis and tags")]
+    async fn get_holdings(&self, Parameters(params): Parameters<HoldingsParams>) -> Result<Json<HoldingsResult>, String> {
+        let holdings = holdings_output(params.account.as_deref(), &self.pool).await.map_err(tool_error)?;
+        Ok(Json(HoldingsResult { holdings }))
+    }
+
+    #[tool(description = "Accounts with their type, category and sync settings")]
+    async fn list_accounts(&self) -> Result<Json<AccountsResult>, String> {
+        let repo = AccountRepository::new(&self.pool);
+        let accounts = repo.list_accounts(&ListAccountsFilter::default()).await.map_err(tool_error)?;
+        let accounts = account_list_output(accounts, &repo).await.map_err(tool_error)?;
+        Ok(Json(AccountsResult { accounts }))
+    }
+
+    #[tool(description = "Record a purchase, adding it to the account's holdings")]
+    async fn record_buy(&self, Parameters(params): Parameters<TradeParams>) -> Result<Json<TransactionOutput>, String> {
+        let trade = parse_trade(params.into(), &self.pool, &self.opts).await.map_err(tool_error)?;
+        let tx = record_buy(&trade, &self.pool).await.map_err(tool_error)?;
+        Ok(Json(transaction_to_output(&tx)))
+    }
+
+    #[tool(description = "Record a sale, removing it from the account's holdings")]
+    async fn record_sell(&self, Parameters(params): Parameters<TradeParams>) -> Result<Json<TransactionOutput>, String> {
+        let trade = parse_trade(params.into(), &self.pool, &self.opts).await.map_err(tool_error)?;
+        let tx = record_sell(&trade, &self.pool).await.map_err(tool_error)?;
+        Ok(Json(transaction_to_output(&tx)))
+    }
+
+    #[tool(description = "Replace an account's holdings with the balances reported by its exchange or blockchain")]
+    async fn sync_account(&self, Parameters(params): Parameters<SyncParams>) -> Result<Json<SyncOutput>, String> {
+        sync_account_output(&params.account, &self.pool, &self.opts)
+            .await
+            .map(Json)
+            .map_err(tool_error)
+    }
+
+    #[tool(description = "Current USD prices for one or more assets")]
+    async fn get_price(&self, Parameters(params): Parameters<PriceParams>) -> Result<Json<PricesResult>, String> {
+        let prices = price_output(&params.symbols, &self.opts).await.map_err(tool_error)?;
+        Ok(Json(PricesResult { prices }))
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for CryptofolioServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: Implementation {
+                name: "cryptofolio".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                ..Implementation::from_build_env()
+            },
+            instructions: Some(
+                "Crypto portfolio across exchanges and wallets. Quantities and prices are decimal strings in USD."
+                    .to_string(),
+            ),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tools_have_object_output_schemas() {
+        let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
+        let opts = GlobalOptions {
+            no_color: true,
+            testnet: false,
+            json: false,
+            quiet: true,
+            verbose: false,
+            config_path: None,
+            no_retry: false,
+        };
+        let server = CryptofolioServer::new(pool, opts);
+
+        let mut names: Vec<String> = server.tool_router.list_all().into_iter().map(|t| t.name.to_string()).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "get_holdings",
+                "get_portfolio",
+                "get_price",
+                "list_accounts",
+                "record_buy",
+                "record_sell",
+                "sync_account"
+            ]
+        );
+
+        for tool in server.tool_router.list_all() {
+            let schema = tool.output_schema.expect("every tool returns structured output");
+            assert_eq!(schema.get("type").and_then(|t| t.as_str()), Some("object"), "{}", tool.name);
+        }
+    }
+}
@@ -5,9 +5,30 @@ use rustyline::validate::Validator;
 use rustyline::{Context, Helper};
 use sqlx::SqlitePool;
 
-use crate::db::AccountRepository;
+use crate::cli::commands::import::IMPORT_FORMATS;
+use crate::export::EXPORT_FORMATS;
+use crate::import::holdings_file::HOLDINGS_FILE_FORMATS;
+use crate::db::{AccountRepository, HoldingRepository, ListAccountsFilter};
 use crate::error::Result;
 
+/// Common cryptocurrency symbols, offered even when not held
+const COMMON_ASSETS: &[&str] = &[
+    "BTC", "ETH", "SOL", "BNB", "XRP", "ADA", "DOGE", "DOT", "MATIC", "LINK", "AVAX", "UNI", "ATOM", "LTC", "USDT",
+    "USDC",
+];
+
+/// Merge held asset symbols into the common ones, uppercased, deduped and sorted
+fn merge_assets(common: &[&str], held: Vec<String>) -> Vec<String> {
+    let mut assets: Vec<String> = common
+        .iter()
+        .map(|a| a.to_string())
+        .chain(held.into_iter().map(|a| a.to_uppercase()))
+        .collect();
+    assets.sort();
+    assets.dedup();
+    assets
+}
+
 /// Tab completion for cryptofolio commands
 pub struct CryptofolioCompleter {
     commands: Vec<String>,
@@ -29,8 +50,15 @@ impl CryptofolioCompleter {
             "tx".to_string(),
             "sync".to_string(),
             "import".to_string(),
+            "export".to_string(),
+            "watch".to_string(),
+            "db".to_string(),
+            "alert".to_string(),
+            "tax".to_string(),
+            "ai".to_string(),
             "config".to_string(),
             "help".to_string(),
+            "history".to_string(),
             "clear".to_string(),
             "exit".to_string(),
         ];
@@ -45,6 +73,14 @@ impl CryptofolioCompleter {
                 "remove".to_string(),
                 "set".to_string(),
                 "move".to_string(),
+                "merge".to_string(),
+                "check".to_string(),
+                "history".to_string(),
+                "yield".to_string(),
+                "tag".to_string(),
+                "untag".to_string(),
+                "import".to_string(),
+                "export".to_string(),
             ],
         );
         subcommands.insert(
@@ -53,7 +89,10 @@ impl CryptofolioCompleter {
                 "list".to_string(),
                 "add".to_string(),
                 "remove".to_string(),
+                "rename".to_string(),
                 "show".to_string(),
+                "sync-settings".to_string(),
+                "verify".to_string(),
                 "address".to_string(),
             ],
         );
@@ -64,65 +103,69 @@ impl CryptofolioCompleter {
                 "add".to_string(),
                 "remove".to_string(),
                 "rename".to_string(),
+                "reorder".to_string(),
             ],
         );
         subcommands.insert(
             "tx".to_string(),
             vec![
                 "list".to_string(),
+                "search".to_string(),
                 "buy".to_string(),
                 "sell".to_string(),
                 "transfer".to_string(),
                 "swap".to_string(),
+                "stake-reward".to_string(),
+                "fee".to_string(),
+                "export".to_string(),
+                "delete".to_string(),
             ],
         );
         subcommands.insert(
             "config".to_string(),
             vec![
                 "show".to_string(),
+                "get".to_string(),
+                "list-keys".to_string(),
                 "set".to_string(),
                 "use-testnet".to_string(),
                 "use-mainnet".to_string(),
+                "export".to_string(),
+                "import".to_string(),
             ],
         );
+        subcommands.insert("db".to_string(), vec!["backup".to_string(), "restore".to_string()]);
+        subcommands.insert("ai".to_string(), vec!["test".to_string()]);
+        subcommands.insert("export".to_string(), vec!["full".to_string()]);
+        subcommands.insert(
+            "alert".to_string(),
+            vec!["add".to_string(), "list".to_string(), "delete".to_string(), "watch".to_string()],
+        );
+        subcommands.insert("tax".to_string(), vec!["report".to_string()]);
+        subcommands.insert("history".to_string(), vec!["search".to_string()]);
         subcommands.insert(
             "portfolio".to_string(),
             vec![
                 "--by-account".to_string(),
                 "--by-category".to_string(),
                 "--json".to_string(),
+                "rebalance".to_string(),
             ],
         );
 
         // Fetch accounts from database
         let account_repo = AccountRepository::new(pool);
         let accounts = account_repo
-            .list_accounts()
+            .list_accounts(&ListAccountsFilter::default())
             .await
             .unwrap_or_default()
             .into_iter()
             .map(|a| a.name)
             .collect();
 
-        // Common cryptocurrency symbols
-        let assets = vec![
-            "BTC".to_string(),
-            "ETH".to_string(),
-            "SOL".to_string(),
-            "BNB".to_string(),
-            "XRP".to_string(),
-            "ADA".to_string(),
-            "DOGE".to_string(),
-            "DOT".to_string(),
-            "MATIC".to_string(),
-            "LINK".to_string(),
-            "AVAX".to_string(),
-            "UNI".to_string(),
-            "ATOM".to_string(),
-            "LTC".to_string(),
-            "USDT".to_string(),
-            "USDC".to_string(),
-        ];
+        // Held assets, so exotic tokens complete too; the common symbols cover an empty or unreadable database
+        let held = HoldingRepository::new(pool).list_assets().await.unwrap_or_default();
+        let assets = merge_assets(COMMON_ASSETS, held);
 
         Ok(Self {
             commands,
@@ -186,6 +229,25 @@ impl CryptofolioCompleter {
                 let partial = words.last().unwrap_or(&"").to_lowercase();
                 let is_complete_word = line.ends_with(' ');
 
+                // Values for `--format` depend on the command being run
+                let previous = if is_complete_word {
+                    words.last()
+                } else {
+                    words.iter().nth_back(1)
+                };
+                if previous == Some(&"--format") {
+                    let prefix = if is_complete_word { "" } else { partial.as_str() };
+                    for format in Self::format_values(&words) {
+                        if format.starts_with(prefix) {
+                            completions.push(Pair {
+                                display: format.to_string(),
+                                replacement: format.to_string(),
+                            });
+                        }
+                    }
+                    return completions;
+                }
+
                 // Suggest based on context
                 if is_complete_word || partial.is_empty() {
                     // Suggest based on previous words
@@ -266,6 +328,17 @@ impl CryptofolioCompleter {
 
         completions
     }
+
+    /// Accepted `--format` values for the command in `words`
+    fn format_values(words: &[&str]) -> &'static [&'static str] {
+        match (words.first().copied(), words.get(1).copied()) {
+            (Some("tx"), Some("export")) => EXPORT_FORMATS,
+            (Some("holdings"), Some("import" | "export")) => HOLDINGS_FILE_FORMATS,
+            (Some("import"), _) => IMPORT_FORMATS,
+            (Some("tax"), Some("report")) => &["table", "json", "csv"],
+            _ => &[],
+        }
+    }
 }
 
 impl Completer for CryptofolioCompleter {
@@ -340,3 +413,47 @@ impl Highlighter for CryptofolioCompleter {
 impl Validator for CryptofolioCompleter {}
 
 impl Helper for CryptofolioCompleter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completer() -> CryptofolioCompleter {
+        CryptofolioCompleter {
+            commands: Vec::new(),
+            subcommands: std::collections::HashMap::new(),
+            accounts: vec!["Binance".to_string()],
+            assets: vec!["BTC".to_string()],
+        }
+    }
+
+    fn displays(line: &str) -> Vec<String> {
+        completer()
+            .complete_command(line, line.len())
+            .into_iter()
+            .map(|p| p.display)
+            .collect()
+    }
+
+    #[test]
+    fn test_complete_tx_export_format() {
+        assert_eq!(displays("tx export out.csv --format "), EXPORT_FORMATS);
+        assert_eq!(displays("tx export out.csv --format j"), vec!["json"]);
+    }
+
+    #[test]
+    fn test_merge_assets() {
+        let merged = merge_assets(&["ETH", "BTC"], vec!["pepe".to_string(), "BTC".to_string()]);
+        assert_eq!(merged, vec!["BTC", "ETH", "PEPE"]);
+    }
+
+    #[test]
+    fn test_complete_holdings_file_format() {
+        assert_eq!(displays("holdings export backup.yaml --format "), HOLDINGS_FILE_FORMATS);
+    }
+
+    #[test]
+    fn test_complete_import_format() {
+        assert_eq!(displays("import trades.csv --account Binance --format "), IMPORT_FORMATS);
+    }
+}
@@ -25,9 +25,11 @@ impl ShellContext {
 
     /// Update context from a command that was just executed
     pub fn update_from_command(&mut self, args: &[String]) {
-        // Look for --account flag
+        // Look for --account flag; --from and --to only name accounts when moving
+        // funds, elsewhere they are dates
+        let moves_funds = args.iter().any(|a| a == "transfer" || a == "move");
         for (i, arg) in args.iter().enumerate() {
-            if arg == "--account" || arg == "--from" || arg == "--to" {
+            if arg == "--account" || (moves_funds && (arg == "--from" || arg == "--to")) {
                 if let Some(account) = args.get(i + 1) {
                     self.last_account = Some(account.trim_matches('"').to_string());
                 }
@@ -127,4 +129,22 @@ mod tests {
         assert_eq!(ctx.last_account, Some("Binance".to_string()));
         assert_eq!(ctx.last_asset, Some("BTC".to_string()));
     }
+
+    #[test]
+    fn test_date_filters_are_not_accounts() {
+        let mut ctx = ShellContext::new();
+        let args: Vec<String> = ["cryptofolio", "tx", "list", "--from", "2024-01-01"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        ctx.update_from_command(&args);
+        assert_eq!(ctx.last_account, None);
+
+        let args: Vec<String> = ["cryptofolio", "tx", "transfer", "BTC", "0.1", "--from", "Binance", "--to", "Ledger"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        ctx.update_from_command(&args);
+        assert_eq!(ctx.last_account, Some("Ledger".to_string()));
+    }
 }
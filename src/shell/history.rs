@@ -0,0 +1,100 @@
+use std::ops::Range;
+
+/// Find history entries containing `pattern` (case-insensitive).
+///
+/// Returns 1-based line numbers alongside each matching entry, in history order.
+pub fn search_history<'a, I>(entries: I, pattern: &str) -> Vec<(usize, &'a str)>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let pattern = pattern.to_lowercase();
+
+    entries
+        .into_iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.to_lowercase().contains(&pattern))
+        .map(|(i, entry)| (i + 1, entry.as_str()))
+        .collect()
+}
+
+/// Entries shown per page by the `history` command
+pub const HISTORY_PAGE_SIZE: usize = 20;
+
+/// Parse what follows `history`: an optional `search <pattern>` and `--page N`.
+///
+/// Returns the pattern (empty to match everything) and the page, or `None` if
+/// the arguments don't make sense.
+pub fn parse_history_args(args: &[&str]) -> Option<(String, usize)> {
+    let mut args = args.to_vec();
+    let mut page = 1;
+    if let Some(i) = args.iter().position(|a| *a == "--page") {
+        page = args.get(i + 1)?.parse().ok().filter(|p| *p > 0)?;
+        args.drain(i..i + 2);
+    }
+
+    match args.split_first() {
+        None => Some((String::new(), page)),
+        Some((&"search", pattern)) if !pattern.is_empty() => Some((pattern.join(" "), page)),
+        _ => None,
+    }
+}
+
+/// Positions of the entries on a 1-based `page`, counting back from the most
+/// recent so page 1 is the latest `page_size` entries, kept in history order
+pub fn history_page(len: usize, page: usize, page_size: usize) -> Range<usize> {
+    let end = len.saturating_sub(page.saturating_sub(1) * page_size);
+    end.saturating_sub(page_size)..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_history_args() {
+        assert_eq!(parse_history_args(&[]), Some((String::new(), 1)));
+        assert_eq!(parse_history_args(&["--page", "3"]), Some((String::new(), 3)));
+        assert_eq!(
+            parse_history_args(&["search", "tx", "buy", "--page", "2"]),
+            Some(("tx buy".to_string(), 2))
+        );
+        assert_eq!(parse_history_args(&["search"]), None);
+        assert_eq!(parse_history_args(&["--page", "0"]), None);
+        assert_eq!(parse_history_args(&["--page"]), None);
+    }
+
+    #[test]
+    fn test_history_page_counts_back_from_latest() {
+        assert_eq!(history_page(45, 1, 20), 25..45);
+        assert_eq!(history_page(45, 2, 20), 5..25);
+        assert_eq!(history_page(45, 3, 20), 0..5);
+        assert!(history_page(45, 4, 20).is_empty());
+    }
+
+    #[test]
+    fn test_search_history_matches_with_line_numbers() {
+        let entries = vec![
+            "portfolio".to_string(),
+            "tx buy BTC 0.1 --account Binance --price 95000".to_string(),
+            "holdings list".to_string(),
+            "tx sell btc 0.05 --account Binance --price 99000".to_string(),
+        ];
+
+        let matches = search_history(&entries, "BTC");
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].0, 2);
+        assert_eq!(matches[1].0, 4);
+    }
+
+    #[test]
+    fn test_search_history_empty_pattern_matches_all() {
+        let entries = vec!["portfolio".to_string(), "sync".to_string()];
+        assert_eq!(search_history(&entries, "").len(), 2);
+    }
+
+    #[test]
+    fn test_search_history_no_match() {
+        let entries = vec!["portfolio".to_string()];
+        assert!(search_history(&entries, "swap").is_empty());
+    }
+}
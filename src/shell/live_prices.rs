@@ -0,0 +1,192 @@
+//! Background price feed for `shell --live-prices`.
+//!
+//! A task keeps a Binance WebSocket subscription open for the held crypto
+//! assets Binance lists and writes each update into a shared cache, which the
+//! prompt reads to show a live portfolio value. The subscription is replaced
+//! whenever the set of held assets changes.
+
+use futures_util::StreamExt;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::core::currency::is_usd_equivalent;
+use crate::exchange::{BinanceClient, Exchange};
+
+/// Latest USD price per asset
+pub type PriceCache = Arc<RwLock<HashMap<String, Decimal>>>;
+
+/// Delay before reconnecting after the stream drops or fails to connect
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Portfolio value at cached prices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiveValue {
+    pub total: Decimal,
+    /// Assets with no price yet, or none on Binance; left out of `total`
+    pub unpriced: usize,
+}
+
+pub struct LivePriceFeed {
+    client: Arc<BinanceClient>,
+    cache: PriceCache,
+    /// The assets last passed to `watch`, uppercased and sorted
+    assets: Vec<String>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl LivePriceFeed {
+    /// Spawn the feed for `assets`. USD-equivalent assets are priced at $1
+    /// and never subscribed to.
+    pub fn spawn(client: BinanceClient, assets: Vec<String>) -> Self {
+        let mut feed = Self {
+            client: Arc::new(client),
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            assets: Vec::new(),
+            task: None,
+        };
+        feed.watch(assets);
+        feed
+    }
+
+    /// Follow a new set of held assets, resubscribing only when it changed
+    pub fn watch(&mut self, assets: Vec<String>) {
+        let mut assets: Vec<String> = assets.into_iter().map(|a| a.to_uppercase()).collect();
+        assets.sort();
+        assets.dedup();
+        if self.task.is_some() && assets == self.assets {
+            return;
+        }
+
+        let mut symbols = Vec::new();
+        for asset in &assets {
+            if is_usd_equivalent(asset) {
+                if let Ok(mut cache) = self.cache.write() {
+                    cache.insert(asset.clone(), Decimal::ONE);
+                }
+            } else {
+                symbols.push(asset.clone());
+            }
+        }
+
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        self.task = Some(tokio::spawn(run_feed(self.client.clone(), symbols, self.cache.clone())));
+        self.assets = assets;
+    }
+
+    /// Shared handle to the price cache
+    pub fn cache(&self) -> PriceCache {
+        self.cache.clone()
+    }
+
+    /// Total value of the priced `holdings`, counting the assets without a
+    /// price. `None` until at least one holding is priced.
+    pub fn value<'a>(&self, holdings: impl IntoIterator<Item = (&'a str, Decimal)>) -> Option<LiveValue> {
+        let prices = self.cache.read().ok()?;
+        let mut total = Decimal::ZERO;
+        let mut priced = false;
+        let mut unpriced = HashSet::new();
+        for (asset, quantity) in holdings {
+            let asset = asset.to_uppercase();
+            match prices.get(&asset) {
+                Some(price) => {
+                    total += quantity * price;
+                    priced = true;
+                }
+                None => {
+                    unpriced.insert(asset);
+                }
+            }
+        }
+
+        priced.then_some(LiveValue { total, unpriced: unpriced.len() })
+    }
+}
+
+impl Drop for LivePriceFeed {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+/// Keep a subscription open for as long as the shell runs
+async fn run_feed(client: Arc<BinanceClient>, symbols: Vec<String>, cache: PriceCache) {
+    if symbols.is_empty() {
+        return;
+    }
+    let symbol_refs: Vec<&str> = symbols.iter().map(|s| s.as_str()).collect();
+
+    // Only symbols Binance has a price for are streamed; the rest stay unpriced
+    let listed: Vec<String> = loop {
+        match client.get_prices(&symbol_refs).await {
+            Ok(prices) => {
+                let listed = prices.iter().map(|p| p.symbol.clone()).collect();
+                if let Ok(mut cache) = cache.write() {
+                    cache.extend(prices.into_iter().map(|p| (p.symbol, p.price)));
+                }
+                break listed;
+            }
+            Err(_) => tokio::time::sleep(RECONNECT_DELAY).await,
+        }
+    };
+    if listed.is_empty() {
+        return;
+    }
+    let listed_refs: Vec<&str> = listed.iter().map(|s| s.as_str()).collect();
+
+    loop {
+        if let Ok(stream) = client.subscribe_prices(&listed_refs).await {
+            let mut stream = Box::pin(stream);
+            while let Some(update) = stream.next().await {
+                if let Ok(mut cache) = cache.write() {
+                    cache.insert(update.symbol, update.price);
+                }
+            }
+        }
+
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_value_counts_unpriced_assets() {
+        let feed = LivePriceFeed::spawn(BinanceClient::new(false, None, None), Vec::new());
+        assert_eq!(feed.value([("BTC", Decimal::ONE)]), None);
+
+        feed.cache().write().unwrap().insert("BTC".to_string(), Decimal::from(60000));
+
+        let btc = Decimal::from_str("0.5").unwrap();
+        assert_eq!(
+            feed.value([("btc", btc)]),
+            Some(LiveValue { total: Decimal::from(30000), unpriced: 0 })
+        );
+        assert_eq!(
+            feed.value([("BTC", btc), ("ETH", Decimal::ONE), ("eth", Decimal::ONE)]),
+            Some(LiveValue { total: Decimal::from(30000), unpriced: 1 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stablecoins_priced_without_subscription() {
+        let mut feed = LivePriceFeed::spawn(BinanceClient::new(false, None, None), vec!["usdc".to_string()]);
+        assert_eq!(
+            feed.value([("USDC", Decimal::from(250))]),
+            Some(LiveValue { total: Decimal::from(250), unpriced: 0 })
+        );
+
+        // Stablecoins bought later are priced as soon as the feed is told about them
+        feed.watch(vec!["USDC".to_string(), "DAI".to_string()]);
+        assert_eq!(feed.value([("DAI", Decimal::from(10))]).map(|v| v.total), Some(Decimal::from(10)));
+    }
+}
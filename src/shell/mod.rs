@@ -1,27 +1,83 @@
 mod completer;
 mod context;
+mod history;
+pub mod live_prices;
 mod shortcuts;
 
+use std::collections::HashSet;
 use std::io::{self, Write};
+use std::path::PathBuf;
 
 use clap::Parser;
 use rustyline::error::ReadlineError;
-use rustyline::history::DefaultHistory;
-use rustyline::{Config, Editor};
+use rustyline::history::{DefaultHistory, History};
+use rustyline::{Cmd, Config, Editor, KeyEvent};
 use sqlx::SqlitePool;
 
 use crate::ai::{AiService, ConversationAction, ConversationManager};
-use crate::cli::output::{colors_enabled, format_usd, init_color};
-use crate::cli::GlobalOptions;
+use crate::cli::output::{colors_enabled, format_currency, format_page_summary, init_color, page_count};
+use crate::cli::{DbCommands, GlobalOptions};
 use crate::config::AppConfig;
-use crate::db::HoldingRepository;
+use crate::core::account::AccountType;
+use crate::core::holdings::Holding;
+use crate::db::{currencies, AccountRepository, HoldingRepository, ListAccountsFilter};
 use crate::error::Result;
 use crate::exchange::{BinanceClient, Exchange};
 
 use completer::CryptofolioCompleter;
 use context::ShellContext;
+use history::{history_page, parse_history_args, search_history, HISTORY_PAGE_SIZE};
+use live_prices::{LivePriceFeed, LiveValue};
 use shortcuts::expand_shortcuts;
 
+/// Prompt shown while a command continues over several lines
+const CONTINUATION_PROMPT: &str = "  \\ ";
+
+/// Add `line` to a command being entered over several lines. A trailing `\`
+/// continues the command on the next line and returns `None`; otherwise the
+/// whole command is returned and `pending` is reset.
+fn continue_line(pending: &mut String, line: &str) -> Option<String> {
+    let line = line.trim();
+    if let Some(part) = line.strip_suffix('\\') {
+        pending.push_str(part.trim_end());
+        pending.push(' ');
+        return None;
+    }
+
+    pending.push_str(line);
+    Some(std::mem::take(pending))
+}
+
+/// Holdings the live value covers: everything outside bank accounts, as in `portfolio`
+async fn live_holdings(pool: &SqlitePool) -> Result<Vec<Holding>> {
+    let bank_filter = ListAccountsFilter {
+        account_types: vec![AccountType::Bank],
+        ..Default::default()
+    };
+    let bank_ids: HashSet<String> = AccountRepository::new(pool)
+        .list_accounts(&bank_filter)
+        .await?
+        .into_iter()
+        .map(|a| a.id)
+        .collect();
+
+    let mut holdings = HoldingRepository::new(pool).list_all().await?;
+    holdings.retain(|h| !bank_ids.contains(&h.account_id));
+    Ok(holdings)
+}
+
+/// Held assets worth streaming; fiat is valued from exchange rates, not Binance
+async fn streamed_assets(pool: &SqlitePool, holdings: &[Holding]) -> Vec<String> {
+    let mut assets = Vec::new();
+    for holding in holdings {
+        let is_fiat = matches!(currencies::get_currency(pool, &holding.asset).await, Ok(Some(c)) if c.is_fiat());
+        if !is_fiat {
+            assets.push(holding.asset.clone());
+        }
+    }
+    assets
+}
+
 /// Interactive shell for cryptofolio
 pub struct Shell {
     pool: SqlitePool,
@@ -30,12 +86,34 @@ pub struct Shell {
     context: ShellContext,
     ai_service: Option<AiService>,
     conversation: ConversationManager,
+    /// Where history is loaded from and saved to; `None` when disabled
+    history_path: Option<PathBuf>,
+    /// Streaming prices for the prompt; `None` unless `--live-prices`
+    live_prices: Option<LivePriceFeed>,
+    /// Whether the last account is restored from and saved to `general.default_account`
+    persist_context: bool,
+    /// `general.default_account` at startup, to only save the account when it changed
+    saved_account: Option<String>,
 }
 
 impl Shell {
-    /// Create a new interactive shell
-    pub async fn new(pool: SqlitePool, opts: GlobalOptions) -> Result<Self> {
+    /// Create a new interactive shell. With `no_history`, history is kept
+    /// for this session only. With `live_prices`, prices for held assets are
+    /// streamed in the background and the prompt shows the portfolio value.
+    /// With `persist_context`, the session starts from `general.default_account`
+    /// and the last account used is written back to it on exit.
+    pub async fn new(
+        pool: SqlitePool,
+        opts: GlobalOptions,
+        no_history: bool,
+        live_prices: bool,
+        persist_context: bool,
+    ) -> Result<Self> {
+        let app_config = AppConfig::load()?;
+
         let config = Config::builder()
+            .max_history_size(app_config.display.shell_history_size)
+            .map_err(|e| crate::error::CryptofolioError::Shell(e.to_string()))?
             .history_ignore_space(true)
             .completion_type(rustyline::CompletionType::List)
             .edit_mode(rustyline::EditMode::Emacs)
@@ -48,21 +126,41 @@ impl Shell {
         let completer = CryptofolioCompleter::new(&pool).await?;
         editor.set_helper(Some(completer));
 
+        // Reverse incremental history search
+        editor.bind_sequence(KeyEvent::ctrl('R'), Cmd::ReverseSearchHistory);
+
         // Load history
-        let history_path = AppConfig::config_dir()?.join("history.txt");
-        let _ = editor.load_history(&history_path);
+        let history_path = if no_history { None } else { Some(AppConfig::history_path()?) };
+        if let Some(path) = &history_path {
+            let _ = editor.load_history(path);
+        }
 
         // Initialize AI service
-        let app_config = AppConfig::load()?;
         let ai_service = AiService::new(&app_config).ok();
 
+        let live_prices = if live_prices {
+            let assets = streamed_assets(&pool, &live_holdings(&pool).await?).await;
+            let client = BinanceClient::new(opts.testnet || app_config.general.use_testnet, None, None);
+            Some(LivePriceFeed::spawn(client, assets))
+        } else {
+            None
+        };
+
+        let saved_account = if persist_context { app_config.general.default_account.clone() } else { None };
+        let mut context = ShellContext::new();
+        context.last_account = saved_account.clone();
+
         Ok(Self {
             pool,
             opts,
             editor,
-            context: ShellContext::new(),
+            context,
             ai_service,
             conversation: ConversationManager::new(),
+            history_path,
+            live_prices,
+            persist_context,
+            saved_account,
         })
     }
 
@@ -74,12 +172,22 @@ impl Shell {
         // Print welcome message
         self.print_welcome().await?;
 
+        let mut clean_exit = true;
+        // Lines ending in `\` collected so far, joined into one command
+        let mut pending = String::new();
         loop {
             // Build prompt with status
-            let prompt = self.build_prompt().await;
+            let prompt = if pending.is_empty() {
+                self.build_prompt().await
+            } else {
+                CONTINUATION_PROMPT.to_string()
+            };
 
             match self.editor.readline(&prompt) {
                 Ok(line) => {
+                    let Some(line) = continue_line(&mut pending, &line) else {
+                        continue;
+                    };
                     let line = line.trim();
 
                     if line.is_empty() {
@@ -107,13 +215,24 @@ impl Shell {
                         continue;
                     }
 
+                    // Handle history listing and search
+                    let words: Vec<&str> = line.split_whitespace().collect();
+                    if words.first() == Some(&"history") {
+                        match parse_history_args(&words[1..]) {
+                            Some((pattern, page)) => self.print_history(&pattern, page),
+                            None => println!("Usage: history [search <pattern>] [--page N]"),
+                        }
+                        continue;
+                    }
+
                     // Execute the command
                     if let Err(e) = self.execute_input(line).await {
                         crate::cli::output::error(&e.to_string());
                     }
                 }
                 Err(ReadlineError::Interrupted) => {
-                    // Cancel current operation
+                    // Cancel current operation, including a half-entered command
+                    pending.clear();
                     self.conversation.state_mut().clear_operation();
                     println!("^C");
                     continue;
@@ -124,18 +243,36 @@ impl Shell {
                 }
                 Err(err) => {
                     eprintln!("Error: {:?}", err);
+                    clean_exit = false;
                     break;
                 }
             }
         }
 
+        if clean_exit && self.persist_context && self.context.last_account != self.saved_account {
+            if let Err(e) = self.save_context() {
+                crate::cli::output::warning(&format!("Could not save the last account: {}", e));
+            }
+        }
+
         // Save history
-        let history_path = AppConfig::config_dir()?.join("history.txt");
-        let _ = self.editor.save_history(&history_path);
+        if let Some(path) = &self.history_path {
+            if let Some(parent) = path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            let _ = self.editor.save_history(path);
+        }
 
         Ok(())
     }
 
+    /// Remember the last account used as `general.default_account`
+    fn save_context(&self) -> Result<()> {
+        let mut config = AppConfig::load()?;
+        config.general.default_account = self.context.last_account.clone();
+        config.save()
+    }
+
     /// Print welcome message with portfolio summary
     async fn print_welcome(&self) -> Result<()> {
         println!();
@@ -165,9 +302,9 @@ impl Shell {
     }
 
     /// Build the prompt string
-    async fn build_prompt(&self) -> String {
+    async fn build_prompt(&mut self) -> String {
         // Show different prompt during conversation
-        if self.conversation.state().confirmation_pending {
+        if self.conversation.state().operation.confirmation_pending {
             if colors_enabled() {
                 return "\x1b[1;33m[Y/n]\x1b[0m ".to_string();
             } else {
@@ -175,7 +312,7 @@ impl Shell {
             }
         }
 
-        if self.conversation.state().current_intent.is_some() {
+        if self.conversation.state().operation.current_intent.is_some() {
             if colors_enabled() {
                 return "\x1b[1;34m  >\x1b[0m ".to_string();
             } else {
@@ -183,21 +320,48 @@ impl Shell {
             }
         }
 
+        let value = match self.live_portfolio_value().await {
+            Some(value) => {
+                let mut text = format_currency(value.total);
+                if value.unpriced > 0 {
+                    text.push_str(&format!(", {} unpriced", value.unpriced));
+                }
+                if colors_enabled() {
+                    format!("\x1b[36m[{}]\x1b[0m ", text)
+                } else {
+                    format!("[{}] ", text)
+                }
+            }
+            None => String::new(),
+        };
+
         if colors_enabled() {
-            "\x1b[1;32myou>\x1b[0m ".to_string()
+            format!("{}\x1b[1;32myou>\x1b[0m ", value)
         } else {
-            "you> ".to_string()
+            format!("{}you> ", value)
         }
     }
 
+    /// Portfolio value at streamed prices, once any held asset has one.
+    /// Assets bought or sold since the last prompt update the subscription.
+    async fn live_portfolio_value(&mut self) -> Option<LiveValue> {
+        self.live_prices.as_ref()?;
+        let holdings = live_holdings(&self.pool).await.ok()?;
+        let assets = streamed_assets(&self.pool, &holdings).await;
+
+        let feed = self.live_prices.as_mut()?;
+        feed.watch(assets);
+        feed.value(holdings.iter().map(|h| (h.asset.as_str(), h.quantity)))
+    }
+
     /// Execute user input - either as CLI command or natural language
     async fn execute_input(&mut self, input: &str) -> Result<()> {
         // Check if we're in the middle of a conversation
-        if self.conversation.state().confirmation_pending {
+        if self.conversation.state().operation.confirmation_pending {
             return self.handle_confirmation(input).await;
         }
 
-        if self.conversation.state().current_intent.is_some() {
+        if self.conversation.state().operation.current_intent.is_some() {
             return self.handle_conversation_input(input).await;
         }
 
@@ -213,7 +377,8 @@ impl Shell {
             let first_word = args[0].to_lowercase();
             let cli_commands = [
                 "price", "market", "portfolio", "holdings", "account",
-                "category", "tx", "sync", "import", "config", "status",
+                "category", "tx", "sync", "import", "config", "status", "watch",
+                "db", "alert", "tax", "ai",
             ];
 
             if cli_commands.contains(&first_word.as_str()) {
@@ -244,8 +409,8 @@ impl Shell {
         };
 
         // Update conversation context from shell context
-        self.conversation.state_mut().last_account = self.context.last_account.clone();
-        self.conversation.state_mut().last_asset = self.context.last_asset.clone();
+        self.conversation.state_mut().context.last_account = self.context.last_account.clone();
+        self.conversation.state_mut().context.last_asset = self.context.last_asset.clone();
 
         // Parse with AI
         let parsed = ai.parse_input(input, self.conversation.state()).await?;
@@ -256,8 +421,22 @@ impl Shell {
         self.handle_conversation_action(action).await
     }
 
+    /// Carry accounts and assets the conversation learned into the shell
+    /// context, whether or not its operation went ahead
+    fn sync_context_from_conversation(&mut self) {
+        let context = &self.conversation.state().context;
+        if let Some(ref account) = context.last_account {
+            self.context.last_account = Some(account.clone());
+        }
+        if let Some(ref asset) = context.last_asset {
+            self.context.last_asset = Some(asset.clone());
+        }
+    }
+
     /// Handle a conversation action
     async fn handle_conversation_action(&mut self, action: ConversationAction) -> Result<()> {
+        self.sync_context_from_conversation();
+
         match action {
             ConversationAction::Clarify { question, field: _, suggestions } => {
                 println!();
@@ -287,14 +466,6 @@ impl Shell {
                 io::stdout().flush().ok();
             }
             ConversationAction::Execute { command } => {
-                // Update shell context
-                if let Some(ref account) = self.conversation.state().last_account {
-                    self.context.last_account = Some(account.clone());
-                }
-                if let Some(ref asset) = self.conversation.state().last_asset {
-                    self.context.last_asset = Some(asset.clone());
-                }
-
                 // Execute the command
                 self.execute_cli_command(&command).await?;
             }
@@ -345,19 +516,19 @@ impl Shell {
         let state = self.conversation.state();
 
         // Determine what field we're collecting
-        if let Some(field) = state.missing_entities.first() {
+        if let Some(field) = state.operation.missing_entities.first() {
             let field = field.clone();
             if let Some(entity) = self.conversation.handle_entity_input(input, &field) {
                 // Add entity to collected
-                self.conversation.state_mut().collected_entities.insert(field.clone(), entity);
-                self.conversation.state_mut().missing_entities.retain(|f| f != &field);
+                self.conversation.state_mut().collect(&field, entity);
+                self.sync_context_from_conversation();
 
                 // Check if we have everything
-                if self.conversation.state().missing_entities.is_empty() {
+                if self.conversation.state().operation.missing_entities.is_empty() {
                     // All collected, show confirmation
-                    if let Some(ref intent) = self.conversation.state().current_intent.clone() {
+                    if let Some(ref intent) = self.conversation.state().operation.current_intent.clone() {
                         if intent.requires_confirmation() {
-                            self.conversation.state_mut().confirmation_pending = true;
+                            self.conversation.state_mut().operation.confirmation_pending = true;
                             let (summary, details) = self.build_confirmation(&intent);
                             println!();
                             println!("  {}", summary);
@@ -377,7 +548,7 @@ impl Shell {
                     }
                 } else {
                     // Ask for next missing field
-                    let next_field = self.conversation.state().missing_entities[0].clone();
+                    let next_field = self.conversation.state().operation.missing_entities[0].clone();
                     let question = self.get_question_for_field(&next_field);
                     println!();
                     if colors_enabled() {
@@ -410,30 +581,30 @@ impl Shell {
             _ => "EXECUTE",
         };
 
-        if let Some(Entity::String(asset)) = state.collected_entities.get("asset") {
+        if let Some(Entity::String(asset)) = state.operation.collected_entities.get("asset") {
             details.push(("Asset".to_string(), asset.clone()));
         }
-        if let Some(Entity::Number(qty)) = state.collected_entities.get("quantity") {
+        if let Some(Entity::Number(qty)) = state.operation.collected_entities.get("quantity") {
             details.push(("Quantity".to_string(), format!("{}", qty)));
         }
-        if let Some(Entity::Number(price)) = state.collected_entities.get("price") {
+        if let Some(Entity::Number(price)) = state.operation.collected_entities.get("price") {
             details.push(("Price".to_string(), format!("${:.2}", price)));
         }
-        if let Some(Entity::String(account)) = state.collected_entities.get("account") {
+        if let Some(Entity::String(account)) = state.operation.collected_entities.get("account") {
             details.push(("Account".to_string(), account.clone()));
         }
-        if let Some(Entity::String(from)) = state.collected_entities.get("from_account") {
+        if let Some(Entity::String(from)) = state.operation.collected_entities.get("from_account") {
             details.push(("From".to_string(), from.clone()));
         }
-        if let Some(Entity::String(to)) = state.collected_entities.get("to_account") {
+        if let Some(Entity::String(to)) = state.operation.collected_entities.get("to_account") {
             details.push(("To".to_string(), to.clone()));
         }
 
         // Calculate total for buy/sell
         if matches!(intent, crate::ai::Intent::TxBuy | crate::ai::Intent::TxSell) {
             if let (Some(Entity::Number(qty)), Some(Entity::Number(price))) = (
-                state.collected_entities.get("quantity"),
-                state.collected_entities.get("price"),
+                state.operation.collected_entities.get("quantity"),
+                state.operation.collected_entities.get("price"),
             ) {
                 let total = qty * price;
                 details.push(("Total".to_string(), format!("${:.2}", total)));
@@ -449,7 +620,7 @@ impl Shell {
 
         let parsed = ParsedInput {
             intent: intent.clone(),
-            entities: self.conversation.state().collected_entities.clone(),
+            entities: self.conversation.state().operation.collected_entities.clone(),
             missing: vec![],
             confidence: 1.0,
             raw_input: String::new(),
@@ -506,6 +677,9 @@ impl Shell {
         if self.opts.testnet {
             full_args.push("--testnet".to_string());
         }
+        if self.opts.no_retry {
+            full_args.push("--no-retry".to_string());
+        }
 
         // Parse and execute using clap
         match crate::cli::Cli::try_parse_from(&full_args) {
@@ -544,16 +718,17 @@ impl Shell {
     /// Run a parsed CLI command
     async fn run_cli_command(&self, cli: crate::cli::Cli) -> Result<()> {
         use crate::cli::commands::*;
-        use crate::cli::Commands;
+        use crate::cli::{Commands, ImportCommands};
 
         let opts = GlobalOptions::from_cli(&cli);
+        crate::exchange::binance::set_retry_enabled(!opts.no_retry);
 
         match cli.command {
             Commands::Price { symbols } => {
                 handle_price_command(symbols, &self.pool, &opts).await?;
             }
-            Commands::Market { symbol, show_24h } => {
-                handle_market_command(symbol, show_24h, &self.pool, &opts).await?;
+            Commands::Market { symbol, show_24h, source, compare } => {
+                handle_market_command(symbol, show_24h, source, compare, &self.pool, &opts).await?;
             }
             Commands::Account { command } => {
                 handle_account_command(command, &self.pool, &opts).await?;
@@ -564,26 +739,84 @@ impl Shell {
             Commands::Holdings { command } => {
                 handle_holdings_command(command, &self.pool, &opts).await?;
             }
+            Commands::Portfolio { command: Some(command), .. } => {
+                handle_portfolio_subcommand(command, &self.pool, &opts).await?;
+            }
             Commands::Portfolio {
+                command: None,
                 by_account,
                 by_category,
                 account,
                 category,
+                asset_type,
+                format,
+                output_file,
+                append,
+                total_invested,
+                show_fees,
+                net_worth,
+                currency,
+                tag,
+                cost_basis,
+                auto_snapshot,
+                unrealized_only,
+                realized_only,
+                benchmark,
+                from,
+                to,
             } => {
-                handle_portfolio_command(by_account, by_category, account, category, &self.pool, &opts).await?;
+                let options = PortfolioOptions {
+                    by_account,
+                    by_category,
+                    account,
+                    category,
+                    asset_type,
+                    format,
+                    output_file,
+                    append,
+                    total_invested,
+                    show_fees,
+                    net_worth,
+                    currency,
+                    tag,
+                    cost_basis,
+                    auto_snapshot,
+                    unrealized_only,
+                    realized_only,
+                    benchmark,
+                    from,
+                    to,
+                };
+                handle_portfolio_command(options, &self.pool, &opts).await?;
             }
             Commands::Tx { command } => {
                 handle_tx_command(command, &self.pool, &opts).await?;
             }
-            Commands::Sync { account } => {
-                handle_sync_command(account, &self.pool, &opts).await?;
+            Commands::Sync { command, account, show_changes, dry_run } => {
+                handle_sync_command(command, account, show_changes, dry_run, &self.pool, &opts).await?;
+            }
+            Commands::Import {
+                command: Some(ImportCommands::Full { input, merge: _, replace, yes }),
+                ..
+            } => {
+                handle_import_full_command(input, replace, yes, &self.pool, &opts).await?;
             }
             Commands::Import {
+                command: None,
                 file,
                 account,
+                account_column,
                 format,
+                dry_run,
             } => {
-                handle_import_command(file, account, format, &self.pool, &opts).await?;
+                let target = match account_column {
+                    Some(column) => ImportTarget::Column(column),
+                    None => ImportTarget::Account(account.unwrap_or_default()),
+                };
+                handle_import_command(file.unwrap_or_default(), target, format, dry_run, &self.pool, &opts).await?;
+            }
+            Commands::Export { command, all } => {
+                handle_export_command(command, all, &self.pool, &opts).await?;
             }
             Commands::Config { command } => {
                 handle_config_command(command, &self.pool, &opts).await?;
@@ -591,12 +824,36 @@ impl Shell {
             Commands::Currency { command } => {
                 handle_currency_command(&self.pool, command).await?;
             }
-            Commands::Shell => {
+            Commands::Db {
+                command: DbCommands::Restore { .. },
+            } => {
+                // Restoring closes the database pool the shell keeps using
+                println!("Run 'cryptofolio db restore' outside the shell.");
+            }
+            Commands::Db { command } => {
+                handle_db_command(command, &self.pool, &opts).await?;
+            }
+            Commands::Alert { command } => {
+                handle_alert_command(command, &self.pool, &opts).await?;
+            }
+            Commands::Tax { command } => {
+                handle_tax_command(command, &self.pool, &opts).await?;
+            }
+            Commands::Shell { .. } => {
                 println!("Already in shell mode.");
             }
             Commands::Status { check } => {
                 handle_status_command(check).await?;
             }
+            Commands::Ai { command } => {
+                handle_ai_command(command, &opts).await?;
+            }
+            Commands::Watch { interval, alert_pct } => {
+                handle_watch_command(interval, alert_pct, &self.pool, &opts).await?;
+            }
+            Commands::McpServer { .. } => {
+                println!("Run 'cryptofolio mcp-server' outside the shell.");
+            }
         }
 
         Ok(())
@@ -662,24 +919,54 @@ impl Shell {
 
         let pnl_str = if pnl >= rust_decimal::Decimal::ZERO {
             if colors_enabled() {
-                format!("\x1b[32m+{} (+{:.2}%)\x1b[0m", format_usd(pnl), pnl_percent)
+                format!("\x1b[32m+{} (+{:.2}%)\x1b[0m", format_currency(pnl), pnl_percent)
             } else {
-                format!("+{} (+{:.2}%)", format_usd(pnl), pnl_percent)
+                format!("+{} (+{:.2}%)", format_currency(pnl), pnl_percent)
             }
         } else {
             if colors_enabled() {
-                format!("\x1b[31m{} ({:.2}%)\x1b[0m", format_usd(pnl), pnl_percent)
+                format!("\x1b[31m{} ({:.2}%)\x1b[0m", format_currency(pnl), pnl_percent)
             } else {
-                format!("{} ({:.2}%)", format_usd(pnl), pnl_percent)
+                format!("{} ({:.2}%)", format_currency(pnl), pnl_percent)
             }
         };
 
         Ok(PortfolioSummary {
-            total_value: format_usd(total_value),
+            total_value: format_currency(total_value),
             pnl: pnl_str,
         })
     }
 
+    /// Print a page of history entries matching a pattern with their line numbers,
+    /// page 1 being the most recent
+    fn print_history(&self, pattern: &str, page: usize) {
+        let history = self.editor.history();
+        // Leave out the entry for the history command that was just run
+        let entries = history.iter().take(history.len().saturating_sub(1));
+        let matches = search_history(entries, pattern);
+
+        let shown = history_page(matches.len(), page, HISTORY_PAGE_SIZE);
+        let pages = page_count(matches.len() as u64, HISTORY_PAGE_SIZE as u32);
+        if shown.is_empty() {
+            if page > 1 {
+                println!("No history on page {}: {} entries fill {} page(s).", page, matches.len(), pages);
+            }
+            return;
+        }
+
+        for (line_no, entry) in &matches[shown.clone()] {
+            println!("{:>5}  {}", line_no, entry);
+        }
+
+        if pages > 1 {
+            println!();
+            println!(
+                "{}",
+                format_page_summary(page as u64, pages, shown.start as u64 + 1, shown.end as u64, matches.len() as u64, "entries")
+            );
+        }
+    }
+
     /// Print help message
     fn print_help(&self) {
         println!();
@@ -713,9 +1000,11 @@ impl Shell {
         println!();
         println!("  \x1b[36mhelp\x1b[0m                   Show this help");
         println!("  \x1b[36mclear\x1b[0m                  Clear screen");
+        println!("  \x1b[36mhistory\x1b[0m search <text>  Search command history (--page N for older)");
         println!("  \x1b[36mexit\x1b[0m                   Exit shell");
         println!();
-        println!("  Use Tab for completion, Up/Down for history.");
+        println!("  Use Tab for completion, Up/Down for history, Ctrl+R to search history.");
+        println!("  End a line with \\ to continue a long command on the next line.");
 
         // Show current context if any
         if let Some(ctx_summary) = self.context.summary() {
@@ -731,3 +1020,27 @@ struct PortfolioSummary {
     total_value: String,
     pnl: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_continue_line_joins_backslash_lines() {
+        let mut pending = String::new();
+        assert_eq!(continue_line(&mut pending, "tx swap --from-asset ETH \\"), None);
+        assert_eq!(continue_line(&mut pending, "  --to-asset BTC\\  "), None);
+        assert_eq!(
+            continue_line(&mut pending, "  --notes \"arbitrage\""),
+            Some("tx swap --from-asset ETH --to-asset BTC --notes \"arbitrage\"".to_string())
+        );
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_continue_line_passes_single_lines_through() {
+        let mut pending = String::new();
+        assert_eq!(continue_line(&mut pending, "portfolio"), Some("portfolio".to_string()));
+        assert!(pending.is_empty());
+    }
+}
@@ -121,22 +121,27 @@ pub fn get_all_commands() -> Vec<&'static str> {
         "price",
         "market",
         "portfolio",
+        "portfolio rebalance",
         "holdings",
         "holdings list",
         "holdings add",
         "holdings remove",
         "holdings set",
         "holdings move",
+        "holdings import",
+        "holdings export",
         "account",
         "account list",
         "account add",
         "account remove",
+        "account rename",
         "account show",
         "category",
         "category list",
         "category add",
         "tx",
         "tx list",
+        "tx search",
         "tx buy",
         "tx sell",
         "tx transfer",
@@ -147,6 +152,17 @@ pub fn get_all_commands() -> Vec<&'static str> {
         "config show",
         "config set",
         "status",
+        "watch",
+        "db",
+        "db backup",
+        "db restore",
+        "alert",
+        "alert add",
+        "alert list",
+        "alert delete",
+        "alert watch",
+        "tax report",
+        "ai test",
         "help",
         "clear",
         "exit",
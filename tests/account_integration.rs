@@ -0,0 +1,125 @@
+use chrono::Utc;
+use cryptofolio::cli::commands::handle_import_full_command;
+use cryptofolio::core::account::{Account, AccountConfig, AccountType};
+use cryptofolio::core::transaction::Transaction;
+use cryptofolio::db::{AccountRepository, HoldingRepository, ListAccountsFilter};
+use cryptofolio::error::{CryptofolioError, Result};
+use rust_decimal::Decimal;
+
+mod common;
+
+#[tokio::test]
+async fn test_rename_account_keeps_holdings() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    HoldingRepository::new(&pool).add_quantity("ledger", "BTC", Decimal::ONE, None, None).await?;
+
+    let repo = AccountRepository::new(&pool);
+    repo.update_account_name("ledger", "Ledger Nano X").await?;
+
+    assert!(repo.get_account("Ledger").await?.is_none());
+    let account = repo.require_account("Ledger Nano X").await?;
+    assert_eq!(account.id, "ledger");
+
+    let holdings = HoldingRepository::new(&pool).list_by_account(&account.id).await?;
+    assert_eq!(holdings.len(), 1);
+
+    // Changing only the case of the same account is allowed
+    repo.update_account_name("Ledger Nano X", "LEDGER NANO X").await?;
+    assert_eq!(repo.require_account("ledger nano x").await?.name, "LEDGER NANO X");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rename_account_rejects_existing_name() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    common::create_account(&pool, "trezor", "Trezor").await?;
+
+    let repo = AccountRepository::new(&pool);
+    let err = repo.update_account_name("Ledger", "trezor").await.unwrap_err();
+    assert!(matches!(err, CryptofolioError::AccountAlreadyExists(_)));
+
+    let err = repo.update_account_name("Missing", "Other").await.unwrap_err();
+    assert!(matches!(err, CryptofolioError::AccountNotFound { .. }));
+
+    assert_eq!(repo.require_account("Ledger").await?.id, "ledger");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_accounts_with_filter() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+
+    let repo = AccountRepository::new(&pool);
+    repo.create_account(&Account {
+        id: "binance".to_string(),
+        name: "Binance".to_string(),
+        category_id: "trading".to_string(),
+        account_type: AccountType::Exchange,
+        config: AccountConfig::default(),
+        sync_enabled: true,
+        created_at: Utc::now(),
+    })
+    .await?;
+
+    let names = |accounts: Vec<Account>| accounts.into_iter().map(|a| a.name).collect::<Vec<_>>();
+
+    assert_eq!(names(repo.list_accounts(&ListAccountsFilter::default()).await?), vec!["Binance", "Ledger"]);
+
+    let filter = ListAccountsFilter { account_types: vec![AccountType::Exchange], ..Default::default() };
+    assert_eq!(names(repo.list_accounts(&filter).await?), vec!["Binance"]);
+
+    let filter = ListAccountsFilter { category_id: Some("cold-storage".to_string()), ..Default::default() };
+    assert_eq!(names(repo.list_accounts(&filter).await?), vec!["Ledger"]);
+
+    let filter = ListAccountsFilter {
+        sync_enabled: Some(true),
+        account_types: vec![AccountType::HardwareWallet, AccountType::SoftwareWallet],
+        ..Default::default()
+    };
+    assert!(repo.list_accounts(&filter).await?.is_empty());
+
+    let filter = ListAccountsFilter { name: Some("LEDGER".to_string()), ..Default::default() };
+    assert_eq!(names(repo.list_accounts(&filter).await?), vec!["Ledger"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_replace_import_keeps_existing_data() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    HoldingRepository::new(&pool).add_quantity("ledger", "BTC", Decimal::ONE, None, None).await?;
+
+    let dir = std::env::temp_dir().join(format!("cryptofolio-import-full-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let hot = Account {
+        id: "hot".to_string(),
+        name: "Hot Wallet".to_string(),
+        category_id: "cold-storage".to_string(),
+        account_type: AccountType::SoftwareWallet,
+        config: AccountConfig::default(),
+        sync_enabled: false,
+        created_at: Utc::now(),
+    };
+    std::fs::write(dir.join("accounts.json"), serde_json::to_string(&vec![hot])?)?;
+    // The buy points at an account that is not in the export, so its insert fails
+    let buy = Transaction::new_buy("ghost", "ETH", Decimal::ONE, Decimal::from(3000), Utc::now());
+    std::fs::write(dir.join("transactions.json"), serde_json::to_string(&vec![buy])?)?;
+
+    let result = handle_import_full_command(dir.display().to_string(), true, true, &pool, &common::quiet_options()).await;
+    std::fs::remove_dir_all(&dir)?;
+    assert!(result.is_err());
+
+    let repo = AccountRepository::new(&pool);
+    assert!(repo.get_account("Hot Wallet").await?.is_none());
+    repo.require_account("Ledger").await?;
+    let btc = HoldingRepository::new(&pool).get("ledger", "BTC").await?.expect("holding kept");
+    assert_eq!(btc.quantity, Decimal::ONE);
+
+    Ok(())
+}
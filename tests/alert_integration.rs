@@ -0,0 +1,36 @@
+use chrono::Utc;
+use cryptofolio::db::alerts::AlertDirection;
+use cryptofolio::db::AlertRepository;
+use cryptofolio::error::{CryptofolioError, Result};
+use rust_decimal::Decimal;
+
+mod common;
+
+#[tokio::test]
+async fn test_alert_lifecycle() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    let repo = AlertRepository::new(&pool);
+
+    let btc = repo.insert("btc", AlertDirection::Above, Decimal::from(100_000), Some("echo hi")).await?;
+    let eth = repo.insert("ETH", AlertDirection::Below, Decimal::from(2_500), None).await?;
+
+    let alerts = repo.list(true).await?;
+    assert_eq!(alerts.len(), 2);
+    assert_eq!(alerts[0].asset, "BTC");
+    assert_eq!(alerts[0].command.as_deref(), Some("echo hi"));
+    assert!(alerts[0].is_crossed(Decimal::from(100_000)));
+    assert!(!alerts[1].is_crossed(Decimal::from(2_501)));
+
+    // A triggered alert is kept but no longer active
+    repo.mark_triggered(btc, Utc::now()).await?;
+    let active = repo.list(true).await?;
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].id, eth);
+    let all = repo.list(false).await?;
+    assert!(all[0].triggered_at.is_some() && !all[0].active);
+
+    repo.delete(eth).await?;
+    assert!(matches!(repo.delete(eth).await, Err(CryptofolioError::NotFound(_))));
+
+    Ok(())
+}
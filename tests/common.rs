@@ -1,4 +1,7 @@
-use cryptofolio::db::migrations;
+use chrono::Utc;
+use cryptofolio::cli::{GlobalOptions, HoldingsCommands, TxCommands};
+use cryptofolio::core::account::{Account, AccountConfig, AccountType};
+use cryptofolio::db::{migrations, AccountRepository};
 use cryptofolio::error::Result;
 use sqlx::SqlitePool;
 
@@ -8,3 +11,95 @@ pub async fn setup_test_db() -> Result<SqlitePool> {
     migrations::run(&pool).await?;
     Ok(pool)
 }
+
+/// Creates a hardware wallet account with the given id and name
+#[allow(dead_code)]
+pub async fn create_account(pool: &SqlitePool, id: &str, name: &str) -> Result<()> {
+    let account = Account {
+        id: id.to_string(),
+        name: name.to_string(),
+        category_id: "cold-storage".to_string(),
+        account_type: AccountType::HardwareWallet,
+        config: AccountConfig::default(),
+        sync_enabled: false,
+        created_at: Utc::now(),
+    };
+    AccountRepository::new(pool).create_account(&account).await
+}
+
+/// Global options with all output suppressed
+#[allow(dead_code)]
+pub fn quiet_options() -> GlobalOptions {
+    GlobalOptions {
+        no_color: true,
+        testnet: false,
+        json: false,
+        quiet: true,
+        verbose: false,
+        config_path: None,
+        no_retry: false,
+    }
+}
+
+/// `holdings move` of 0.4 BTC from Ledger to Trezor
+#[allow(dead_code)]
+pub fn move_command(dry_run: bool) -> HoldingsCommands {
+    HoldingsCommands::Move {
+        asset: "btc".to_string(),
+        quantity: "0.4".to_string(),
+        from: "Ledger".to_string(),
+        to: "Trezor".to_string(),
+        yes: true,
+        dry_run,
+    }
+}
+
+/// `holdings merge` of Binance Auto into Binance
+#[allow(dead_code)]
+pub fn merge_command(dry_run: bool) -> HoldingsCommands {
+    HoldingsCommands::Merge {
+        from: "Binance Auto".to_string(),
+        to: "Binance".to_string(),
+        yes: true,
+        dry_run,
+    }
+}
+
+/// `holdings add` of BTC to Ledger on `date`
+#[allow(dead_code)]
+pub fn add_command(quantity: &str, date: &str) -> HoldingsCommands {
+    HoldingsCommands::Add {
+        asset: "BTC".to_string(),
+        quantity: quantity.to_string(),
+        account: "Ledger".to_string(),
+        cost: None,
+        date: Some(date.to_string()),
+    }
+}
+
+/// `holdings import` of `file`
+#[allow(dead_code)]
+pub fn import_command(file: &std::path::Path, merge: bool, skip_existing: bool) -> HoldingsCommands {
+    HoldingsCommands::Import {
+        file: file.display().to_string(),
+        format: None,
+        merge,
+        skip_existing,
+    }
+}
+
+/// `tx buy` into Ledger paying `fee` in `fee_asset`
+#[allow(dead_code)]
+pub fn buy_with_fee(asset: &str, quantity: &str, price: &str, fee: &str, fee_asset: &str) -> TxCommands {
+    TxCommands::Buy {
+        asset: asset.to_string(),
+        quantity: quantity.to_string(),
+        account: "Ledger".to_string(),
+        price: price.to_string(),
+        fee: Some(fee.to_string()),
+        fee_asset: Some(fee_asset.to_string()),
+        notes: None,
+        allow_high_precision: true,
+        dry_run: false,
+    }
+}
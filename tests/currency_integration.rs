@@ -1,6 +1,6 @@
-use cryptofolio::core::currency::{AssetType, Currency, ExchangeRate};
+use cryptofolio::core::currency::{AssetType, Currency, CurrencyConverter, ExchangeRate};
 use cryptofolio::db::currencies;
-use cryptofolio::error::Result;
+use cryptofolio::error::{CryptofolioError, Result};
 use rust_decimal::Decimal;
 use std::str::FromStr;
 use chrono::Utc;
@@ -278,7 +278,7 @@ async fn test_list_exchange_rates() -> Result<()> {
         currencies::add_exchange_rate(&pool, &rate).await?;
     }
 
-    let rates = currencies::list_exchange_rates(&pool, "CRC", "USD").await?;
+    let rates = currencies::list_exchange_rates(&pool, "CRC", "USD", None, None).await?;
     assert_eq!(rates.len(), 5);
 
     // Should be ordered by timestamp DESC
@@ -287,6 +287,36 @@ async fn test_list_exchange_rates() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_list_exchange_rates_with_limit_and_since() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    let now = Utc::now();
+
+    for days in 0..5 {
+        let rate = ExchangeRate {
+            id: 0,
+            from_currency: "CRC".to_string(),
+            to_currency: "USD".to_string(),
+            rate: Decimal::from_str(&format!("0.0019{}", days)).unwrap(),
+            timestamp: now - chrono::Duration::days(days),
+            source: "manual".to_string(),
+            notes: None,
+            created_at: now,
+        };
+        currencies::add_exchange_rate(&pool, &rate).await?;
+    }
+
+    let rates = currencies::list_exchange_rates(&pool, "CRC", "USD", None, Some(2)).await?;
+    assert_eq!(rates.len(), 2);
+    assert_eq!(rates[0].rate, Decimal::from_str("0.00190").unwrap());
+
+    let since = now - chrono::Duration::days(2) - chrono::Duration::hours(1);
+    let rates = currencies::list_exchange_rates(&pool, "CRC", "USD", Some(since), None).await?;
+    assert_eq!(rates.len(), 3);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_exchange_rate_upsert_on_conflict() -> Result<()> {
     let pool = common::setup_test_db().await?;
@@ -322,7 +352,7 @@ async fn test_exchange_rate_upsert_on_conflict() -> Result<()> {
     currencies::add_exchange_rate(&pool, &rate2).await?;
 
     // Should only have one rate for this timestamp
-    let rates = currencies::list_exchange_rates(&pool, "CRC", "USD").await?;
+    let rates = currencies::list_exchange_rates(&pool, "CRC", "USD", None, None).await?;
     assert_eq!(rates.len(), 1);
 
     // Should have the updated value
@@ -386,3 +416,41 @@ async fn test_currency_ordering() -> Result<()> {
 
     Ok(())
 }
+
+fn manual_rate(from: &str, to: &str, rate: &str) -> ExchangeRate {
+    ExchangeRate {
+        id: 0,
+        from_currency: from.to_string(),
+        to_currency: to.to_string(),
+        rate: Decimal::from_str(rate).unwrap(),
+        timestamp: Utc::now(),
+        source: "manual".to_string(),
+        notes: None,
+        created_at: Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn test_currency_converter() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+
+    // 550 CRC per 1 USD, 0.92 EUR per 1 USD
+    currencies::add_exchange_rate(&pool, &manual_rate("CRC", "USD", "550")).await?;
+    currencies::add_exchange_rate(&pool, &manual_rate("EUR", "USD", "0.92")).await?;
+
+    let amount = Decimal::from(100);
+    assert_eq!(CurrencyConverter::convert(amount, "USD", "USD", &pool).await?, amount);
+    assert_eq!(CurrencyConverter::convert(amount, "usd", "CRC", &pool).await?, Decimal::from(55000));
+    let dollars = CurrencyConverter::convert(Decimal::from(1100), "CRC", "USD", &pool).await?;
+    assert_eq!(dollars.round_dp(8), Decimal::from(2));
+    assert_eq!(CurrencyConverter::convert(amount, "USD", "EUR", &pool).await?, Decimal::from(92));
+
+    // No CRC/EUR rate, so it goes through USD
+    let euros = CurrencyConverter::convert(Decimal::from(55000), "CRC", "EUR", &pool).await?;
+    assert_eq!(euros.round_dp(8), Decimal::from(92));
+
+    let missing = CurrencyConverter::convert(amount, "USD", "GBP", &pool).await;
+    assert!(matches!(missing, Err(CryptofolioError::NotFound(_))));
+
+    Ok(())
+}
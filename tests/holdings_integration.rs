@@ -0,0 +1,448 @@
+use cryptofolio::cli::commands::handle_holdings_command;
+use cryptofolio::cli::HoldingsCommands;
+use cryptofolio::core::transaction::{Transaction, TransactionType};
+use cryptofolio::db::{
+    AccountRepository, HoldingRepository, HoldingTagRepository, TransactionFilter, TransactionRepository,
+};
+use cryptofolio::error::Result;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+mod common;
+
+#[tokio::test]
+async fn test_list_holdings_by_multiple_accounts() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    common::create_account(&pool, "trezor", "Trezor").await?;
+    common::create_account(&pool, "binance", "Binance").await?;
+
+    let repo = HoldingRepository::new(&pool);
+    repo.add_quantity("ledger", "BTC", Decimal::ONE, None, None).await?;
+    repo.add_quantity("trezor", "ETH", Decimal::TWO, None, None).await?;
+    repo.add_quantity("binance", "SOL", Decimal::TEN, None, None).await?;
+
+    let holdings = repo
+        .list_by_accounts(&["ledger".to_string(), "trezor".to_string()])
+        .await?;
+
+    let assets: Vec<&str> = holdings.iter().map(|h| h.asset.as_str()).collect();
+    assert_eq!(assets, vec!["BTC", "ETH"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_holdings_by_no_accounts_is_empty() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+
+    let holdings = HoldingRepository::new(&pool).list_by_accounts(&[]).await?;
+    assert!(holdings.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_holding_tags_follow_the_holding() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+
+    let repo = HoldingRepository::new(&pool);
+    let tags = HoldingTagRepository::new(&pool);
+    repo.add_quantity("ledger", "BTC", Decimal::ONE, None, None).await?;
+    repo.add_quantity("ledger", "ETH", Decimal::TWO, None, None).await?;
+    let btc = repo.get("ledger", "BTC").await?.unwrap();
+
+    let added = tags.add(btc.id, &["long-term".to_string(), "gift".to_string()]).await?;
+    assert_eq!(added, 2);
+    // Re-adding an existing tag is a no-op
+    assert_eq!(tags.add(btc.id, &["gift".to_string()]).await?, 0);
+
+    assert_eq!(tags.list_for_holding(btc.id).await?, vec!["gift", "long-term"]);
+    assert!(tags.holding_ids_with_tag("long-term").await?.contains(&btc.id));
+
+    // Updating the quantity keeps the holding and its tags
+    repo.add_quantity("ledger", "BTC", Decimal::ONE, None, None).await?;
+    assert_eq!(tags.list_for_holding(btc.id).await?.len(), 2);
+
+    // Deleting the holding drops its tags
+    repo.delete("ledger", "BTC").await?;
+    assert!(tags.list_all().await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_move_records_inbound_transfer() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    common::create_account(&pool, "trezor", "Trezor").await?;
+    HoldingRepository::new(&pool)
+        .add_quantity("ledger", "BTC", Decimal::ONE, Some(Decimal::from(30000)), None)
+        .await?;
+
+    handle_holdings_command(common::move_command(false), &pool, &common::quiet_options()).await?;
+
+    // Same query as `tx list --account Trezor`
+    let filter = TransactionFilter {
+        account_id: Some("trezor".to_string()),
+        ..Default::default()
+    };
+    let transactions = TransactionRepository::new(&pool).list(&filter).await?;
+    assert_eq!(transactions.len(), 1);
+
+    let tx = &transactions[0];
+    assert_eq!(tx.tx_type, TransactionType::TransferInternal);
+    assert_eq!(tx.from_account_id.as_deref(), Some("ledger"));
+    assert_eq!(tx.to_asset.as_deref(), Some("BTC"));
+    assert_eq!(tx.to_quantity, Some(Decimal::from_str("0.4").unwrap()));
+    assert_eq!(tx.price_usd, Some(Decimal::from(30000)));
+
+    let moved = HoldingRepository::new(&pool).get("trezor", "BTC").await?.unwrap();
+    assert_eq!(moved.avg_cost_basis, Some(Decimal::from(30000)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_move_dry_run_changes_nothing() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    common::create_account(&pool, "trezor", "Trezor").await?;
+    HoldingRepository::new(&pool).add_quantity("ledger", "BTC", Decimal::ONE, None, None).await?;
+
+    handle_holdings_command(common::move_command(true), &pool, &common::quiet_options()).await?;
+
+    assert!(HoldingRepository::new(&pool).get("trezor", "BTC").await?.is_none());
+    let transactions = TransactionRepository::new(&pool).list(&TransactionFilter::default()).await?;
+    assert!(transactions.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_merge_combines_holdings_and_deletes_source() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "binance", "Binance").await?;
+    common::create_account(&pool, "auto", "Binance Auto").await?;
+    let holdings = HoldingRepository::new(&pool);
+    holdings
+        .add_quantity("binance", "BTC", Decimal::ONE, Some(Decimal::from(30000)), None)
+        .await?;
+    holdings
+        .add_quantity("auto", "BTC", Decimal::from(3), Some(Decimal::from(50000)), None)
+        .await?;
+    holdings.add_quantity("auto", "ETH", Decimal::from(2), None, None).await?;
+    TransactionRepository::new(&pool)
+        .insert(&Transaction::new_buy("auto", "BTC", Decimal::from(3), Decimal::from(50000), chrono::Utc::now()))
+        .await?;
+
+    handle_holdings_command(common::merge_command(false), &pool, &common::quiet_options()).await?;
+
+    let btc = holdings.get("binance", "BTC").await?.unwrap();
+    assert_eq!(btc.quantity, Decimal::from(4));
+    assert_eq!(btc.avg_cost_basis, Some(Decimal::from(45000)));
+    let eth = holdings.get("binance", "ETH").await?.unwrap();
+    assert_eq!(eth.quantity, Decimal::from(2));
+    assert!(holdings.list_by_account("auto").await?.is_empty());
+
+    let filter = TransactionFilter {
+        account_id: Some("binance".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(TransactionRepository::new(&pool).list(&filter).await?.len(), 1);
+    assert!(AccountRepository::new(&pool).get_account("Binance Auto").await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_merge_dry_run_changes_nothing() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "binance", "Binance").await?;
+    common::create_account(&pool, "auto", "Binance Auto").await?;
+    HoldingRepository::new(&pool).add_quantity("auto", "BTC", Decimal::ONE, None, None).await?;
+
+    handle_holdings_command(common::merge_command(true), &pool, &common::quiet_options()).await?;
+
+    assert!(HoldingRepository::new(&pool).get("binance", "BTC").await?.is_none());
+    assert!(AccountRepository::new(&pool).get_account("Binance Auto").await?.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_check_fix_moves_orphans_and_zeroes_negatives() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    common::create_account(&pool, "deleted", "Deleted").await?;
+    let holdings = HoldingRepository::new(&pool);
+    holdings.add_quantity("deleted", "BTC", Decimal::ONE, None, None).await?;
+    holdings.set_quantity("ledger", "ETH", Decimal::from(-2), None, None).await?;
+    let tx_repo = TransactionRepository::new(&pool);
+    tx_repo
+        .insert(&Transaction::new_buy("deleted", "BTC", Decimal::ONE, Decimal::from(30000), chrono::Utc::now()))
+        .await?;
+
+    // As left behind by a migration that ran without foreign key checks
+    let mut conn = pool.acquire().await?;
+    sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await?;
+    sqlx::query("DELETE FROM accounts WHERE id = 'deleted'").execute(&mut *conn).await?;
+    sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await?;
+    drop(conn);
+
+    assert_eq!(holdings.find_orphans().await?.len(), 1);
+    assert_eq!(holdings.find_negative().await?.len(), 1);
+    assert_eq!(tx_repo.find_orphans().await?.len(), 1);
+
+    handle_holdings_command(HoldingsCommands::Check { fix: true }, &pool, &common::quiet_options()).await?;
+
+    let unknown = AccountRepository::new(&pool).get_account("[Unknown]").await?.unwrap();
+    assert_eq!(holdings.get(&unknown.id, "BTC").await?.unwrap().quantity, Decimal::ONE);
+    assert!(holdings.get("ledger", "ETH").await?.is_none());
+    assert!(holdings.find_orphans().await?.is_empty());
+    assert!(tx_repo.find_orphans().await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_holding_history_merges_transactions_and_syncs() -> Result<()> {
+    use cryptofolio::core::holdings::HoldingChangeSource;
+    use cryptofolio::core::transaction::Transaction;
+
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "binance", "Binance").await?;
+
+    let repo = HoldingRepository::new(&pool);
+    let tx_repo = TransactionRepository::new(&pool);
+
+    let bought_at = chrono::Utc::now() - chrono::Duration::days(1);
+    tx_repo
+        .insert(&Transaction::new_buy("binance", "BTC", Decimal::ONE, Decimal::from(40000), bought_at))
+        .await?;
+    repo.record_change("binance", "btc", Decimal::ONE, Decimal::from_str("1.5")?, HoldingChangeSource::Sync, None)
+        .await?;
+
+    let history = repo.history("binance", "BTC").await?;
+    let rows: Vec<(&str, Decimal, Decimal)> = history
+        .iter()
+        .map(|e| (e.event.display_name(), e.delta, e.balance))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            ("Buy", Decimal::ONE, Decimal::ONE),
+            ("Sync", Decimal::from_str("0.5")?, Decimal::from_str("1.5")?),
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_quantity_rejects_stale_expected_quantity() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+
+    let repo = HoldingRepository::new(&pool);
+    repo.add_quantity("ledger", "BTC", Decimal::from_str("1.5").unwrap(), None, None).await?;
+
+    let half = Decimal::from_str("0.5").unwrap();
+    assert!(repo.set_quantity("ledger", "BTC", half, None, Some(Decimal::ONE)).await.is_err());
+    assert_eq!(repo.get("ledger", "BTC").await?.unwrap().quantity, Decimal::from_str("1.5").unwrap());
+
+    repo.set_quantity("ledger", "BTC", half, None, Some(Decimal::from_str("1.5").unwrap())).await?;
+    assert_eq!(repo.get("ledger", "BTC").await?.unwrap().quantity, half);
+
+    // Zero stands for "not held", both as the expected and the new quantity
+    assert!(repo.set_quantity("ledger", "BTC", Decimal::ONE, None, Some(Decimal::ZERO)).await.is_err());
+    assert!(repo.set_quantity("ledger", "BTC", Decimal::ZERO, None, Some(Decimal::ONE)).await.is_err());
+    assert_eq!(repo.get("ledger", "BTC").await?.unwrap().quantity, half);
+
+    repo.set_quantity("ledger", "BTC", Decimal::ZERO, None, Some(half)).await?;
+    assert!(repo.get("ledger", "BTC").await?.is_none());
+    repo.set_quantity("ledger", "ETH", Decimal::TWO, None, Some(Decimal::ZERO)).await?;
+    assert_eq!(repo.get("ledger", "ETH").await?.unwrap().quantity, Decimal::TWO);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_with_delta_adds_to_holding() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    HoldingRepository::new(&pool).add_quantity("ledger", "BTC", Decimal::ONE, None, None).await?;
+
+    let command = HoldingsCommands::Set {
+        asset: "BTC".to_string(),
+        quantity: Some("0.5".to_string()),
+        account: "Ledger".to_string(),
+        cost: None,
+        delta: true,
+        from_exchange: false,
+        yes: false,
+        dry_run: false,
+    };
+    handle_holdings_command(command, &pool, &common::quiet_options()).await?;
+
+    let holding = HoldingRepository::new(&pool).get("ledger", "BTC").await?.unwrap();
+    assert_eq!(holding.quantity, Decimal::from_str("1.5").unwrap());
+    let transactions = TransactionRepository::new(&pool).list(&TransactionFilter::default()).await?;
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].tx_type, TransactionType::Buy);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_dry_run_leaves_holding() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    HoldingRepository::new(&pool).add_quantity("ledger", "BTC", Decimal::ONE, None, None).await?;
+
+    let command = HoldingsCommands::Set {
+        asset: "BTC".to_string(),
+        quantity: Some("2".to_string()),
+        account: "Ledger".to_string(),
+        cost: None,
+        delta: false,
+        from_exchange: false,
+        yes: true,
+        dry_run: true,
+    };
+    handle_holdings_command(command, &pool, &common::quiet_options()).await?;
+
+    let holding = HoldingRepository::new(&pool).get("ledger", "BTC").await?.unwrap();
+    assert_eq!(holding.quantity, Decimal::ONE);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_assets_is_distinct_and_sorted() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    common::create_account(&pool, "trezor", "Trezor").await?;
+
+    let repo = HoldingRepository::new(&pool);
+    repo.add_quantity("ledger", "pepe", Decimal::ONE, None, None).await?;
+    repo.add_quantity("ledger", "BTC", Decimal::ONE, None, None).await?;
+    repo.add_quantity("trezor", "BTC", Decimal::TWO, None, None).await?;
+
+    assert_eq!(repo.list_assets().await?, vec!["BTC", "PEPE"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_backdated_add_uses_snapshot_price() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+
+    for (taken_at, price) in [("2023-05-01T12:00:00+00:00", "28000"), ("2023-06-01T18:00:00+00:00", "27000")] {
+        let snapshot_id = sqlx::query(
+            "INSERT INTO portfolio_snapshots (total_value_usd, total_cost_basis, taken_at) VALUES ('0', '0', ?)",
+        )
+        .bind(taken_at)
+        .execute(&pool)
+        .await?
+        .last_insert_rowid();
+        sqlx::query(
+            r#"
+            INSERT INTO portfolio_snapshot_holdings
+                (snapshot_id, account_id, account_name, category_id, category_name, asset, quantity, price_usd)
+            VALUES (?, 'ledger', 'Ledger', 'hardware', 'Hardware', 'BTC', '1', ?)
+            "#,
+        )
+        .bind(snapshot_id)
+        .bind(price)
+        .execute(&pool)
+        .await?;
+    }
+
+    // The snapshot taken later on the same day still counts
+    handle_holdings_command(common::add_command("0.5", "2023-06-01"), &pool, &common::quiet_options()).await?;
+
+    let holding = HoldingRepository::new(&pool).get("ledger", "BTC").await?.unwrap();
+    assert_eq!(holding.avg_cost_basis, Some(Decimal::from(27000)));
+    assert_eq!(holding.acquired_at.unwrap().to_rfc3339(), "2023-06-01T00:00:00+00:00");
+
+    let transactions = TransactionRepository::new(&pool).list(&TransactionFilter::default()).await?;
+    assert_eq!(transactions[0].timestamp.to_rfc3339(), "2023-06-01T00:00:00+00:00");
+    assert_eq!(transactions[0].price_usd, Some(Decimal::from(27000)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_backdated_add_keeps_earliest_acquisition() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+
+    handle_holdings_command(common::add_command("1", "2022-01-15"), &pool, &common::quiet_options()).await?;
+    handle_holdings_command(common::add_command("1", "2023-03-10"), &pool, &common::quiet_options()).await?;
+
+    let holding = HoldingRepository::new(&pool).get("ledger", "BTC").await?.unwrap();
+    assert_eq!(holding.quantity, Decimal::TWO);
+    assert_eq!(holding.avg_cost_basis, None);
+    assert_eq!(holding.acquired_at.unwrap().to_rfc3339(), "2022-01-15T00:00:00+00:00");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_holdings_import_conflicts_merge_and_skip() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    let repo = HoldingRepository::new(&pool);
+    repo.add_quantity("ledger", "BTC", Decimal::ONE, Some(Decimal::from(30000)), None).await?;
+
+    let file = std::env::temp_dir().join(format!("cryptofolio-holdings-{}.yaml", std::process::id()));
+    std::fs::write(
+        &file,
+        "accounts:\n  - name: Ledger\n    holdings:\n      - {asset: BTC, quantity: 0.5, cost: 45000}\n      - {asset: ETH, quantity: 2}\n",
+    )?;
+
+    // An existing holding without --merge or --skip-existing changes nothing
+    assert!(handle_holdings_command(common::import_command(&file, false, false), &pool, &common::quiet_options()).await.is_err());
+    assert!(repo.get("ledger", "ETH").await?.is_none());
+
+    handle_holdings_command(common::import_command(&file, false, true), &pool, &common::quiet_options()).await?;
+    assert_eq!(repo.get("ledger", "BTC").await?.unwrap().quantity, Decimal::ONE);
+    assert_eq!(repo.get("ledger", "ETH").await?.unwrap().quantity, Decimal::TWO);
+
+    handle_holdings_command(common::import_command(&file, true, false), &pool, &common::quiet_options()).await?;
+    let btc = repo.get("ledger", "BTC").await?.unwrap();
+    assert_eq!(btc.quantity, Decimal::from_str("0.5").unwrap());
+    assert_eq!(btc.avg_cost_basis, Some(Decimal::from(45000)));
+
+    std::fs::remove_file(&file)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_holdings_export_then_import_restores() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    common::create_account(&pool, "binance", "Binance").await?;
+    let repo = HoldingRepository::new(&pool);
+    repo.add_quantity("ledger", "BTC", Decimal::from_str("0.25").unwrap(), Some(Decimal::from(40000)), None).await?;
+    repo.add_quantity("binance", "SOL", Decimal::TEN, None, None).await?;
+
+    let file = std::env::temp_dir().join(format!("cryptofolio-backup-{}.toml", std::process::id()));
+    let export = HoldingsCommands::Export { file: file.display().to_string(), format: None };
+    handle_holdings_command(export, &pool, &common::quiet_options()).await?;
+
+    repo.delete("ledger", "BTC").await?;
+    repo.set_quantity("binance", "SOL", Decimal::ONE, None, None).await?;
+
+    handle_holdings_command(common::import_command(&file, true, false), &pool, &common::quiet_options()).await?;
+    let btc = repo.get("ledger", "BTC").await?.unwrap();
+    assert_eq!(btc.quantity, Decimal::from_str("0.25").unwrap());
+    assert_eq!(btc.avg_cost_basis, Some(Decimal::from(40000)));
+    assert_eq!(repo.get("binance", "SOL").await?.unwrap().quantity, Decimal::TEN);
+
+    std::fs::remove_file(&file)?;
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use cryptofolio::db::AssetMetadataRepository;
+use cryptofolio::error::Result;
+use cryptofolio::market::AssetMetadata;
+
+mod common;
+
+fn coin(symbol: &str, name: &str, id: &str) -> AssetMetadata {
+    AssetMetadata {
+        symbol: symbol.to_string(),
+        name: name.to_string(),
+        coingecko_id: id.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_asset_metadata_cache() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    let repo = AssetMetadataRepository::new(&pool);
+
+    assert!(repo.last_updated().await?.is_none());
+
+    repo.replace_all(&[
+        coin("btc", "Bitcoin", "bitcoin"),
+        coin("pnut", "Peanut the Squirrel", "peanut-the-squirrel"),
+        coin("PNUT", "Peanut", "peanut"),
+    ])
+    .await?;
+
+    assert!(repo.last_updated().await?.is_some());
+    assert_eq!(repo.find_by_symbol("pnut").await?.len(), 2);
+
+    // A refresh replaces the previous list
+    repo.replace_all(&[coin("eth", "Ethereum", "ethereum")]).await?;
+    assert!(repo.find_by_symbol("BTC").await?.is_empty());
+    assert_eq!(repo.find_by_symbol("ETH").await?, vec![coin("ETH", "Ethereum", "ethereum")]);
+
+    Ok(())
+}
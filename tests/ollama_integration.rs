@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use cryptofolio::ai::conversation::ConversationState;
+use cryptofolio::ai::conversation::{ConversationContext, ConversationState};
 use cryptofolio::ai::intent::{Entity, Intent};
 use cryptofolio::ai::providers::ollama::OllamaProvider;
 use cryptofolio::ai::providers::AiProvider;
@@ -278,6 +278,79 @@ fn test_extract_quantity_none() {
     assert_eq!(p.extract_quantity("bought some bitcoin"), None);
 }
 
+#[test]
+fn test_extract_quantity_written_numbers() {
+    let p = default_provider();
+    let cases = [
+        ("bought half a bitcoin", 0.5),
+        ("bought a hundred ETH", 100.0),
+        ("sold two thousand five hundred doge", 2500.0),
+        ("bought twenty-five sol", 25.0),
+        ("bought one and a half btc", 1.5),
+        ("bought a quarter of an eth", 0.25),
+        ("sold three quarters of my btc", 0.75),
+        ("bought ten link", 10.0),
+        ("bought a million doge", 1_000_000.0),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(p.extract_quantity(input), Some(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_extract_quantity_fractions() {
+    let p = default_provider();
+    let cases = [
+        ("bought ¼ BTC", 0.25),
+        ("bought ½ eth", 0.5),
+        ("bought 1½ eth", 1.5),
+        ("sold ¾btc", 0.75),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(p.extract_quantity(input), Some(expected), "{}", input);
+    }
+
+    let third = p.extract_quantity("bought a third of a bitcoin").unwrap();
+    assert!((third - 1.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_extract_quantity_dollars_worth() {
+    let p = default_provider();
+    let cases = [
+        ("bought $500 worth of btc", Some(50_000.0), Some(0.01)),
+        ("bought two thousand dollars worth of eth", Some(2_000.0), Some(1.0)),
+        ("bought $1,000 worth of sol", Some(250.0), Some(4.0)),
+        ("bought $5k worth of btc", Some(100_000.0), Some(0.05)),
+        // The dollar amount is not a quantity without a price
+        ("bought $500 worth of btc", None, None),
+    ];
+    for (input, price, expected) in cases {
+        assert_eq!(p.extract_quantity_with_price(input, price), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_rule_based_fallback_dollars_worth_at_price() {
+    let p = default_provider();
+    let parsed = p.rule_based_fallback("bought $500 worth of btc at 50000").unwrap();
+    assert_eq!(parsed.intent, Intent::TxBuy);
+    assert!(matches!(parsed.entities.get("quantity"), Some(Entity::Number(q)) if (*q - 0.01).abs() < 1e-9));
+    assert!(matches!(parsed.entities.get("price"), Some(Entity::Number(p)) if *p == 50000.0));
+}
+
+#[test]
+fn test_rule_based_fallback_dollars_worth_is_not_a_price() {
+    let p = default_provider();
+    for input in ["bought 500 dollars worth of btc", "bought two thousand dollars worth of ETH"] {
+        let parsed = p.rule_based_fallback(input).unwrap();
+        assert_eq!(parsed.intent, Intent::TxBuy, "{}", input);
+        assert!(!parsed.entities.contains_key("price"), "{}", input);
+        assert!(!parsed.entities.contains_key("quantity"), "{}", input);
+        assert!(parsed.missing.contains(&"price".to_string()), "{}", input);
+    }
+}
+
 // ---- extract_price --------------------------------------------------------
 
 #[test]
@@ -461,8 +534,10 @@ fn test_build_prompt_basic() {
 fn test_build_prompt_with_context() {
     let p = default_provider();
     let ctx = ConversationState {
-        last_account: Some("Binance".to_string()),
-        last_asset: Some("BTC".to_string()),
+        context: ConversationContext {
+            last_account: Some("Binance".to_string()),
+            last_asset: Some("BTC".to_string()),
+        },
         ..ConversationState::default()
     };
     let prompt = p.build_prompt("how much?", &ctx);
@@ -571,8 +646,10 @@ async fn test_ollama_parse_input_portfolio_view() {
 async fn test_ollama_parse_input_with_context() {
     let provider = default_provider();
     let ctx = ConversationState {
-        last_account: Some("Coinbase".to_string()),
-        last_asset: Some("ETH".to_string()),
+        context: ConversationContext {
+            last_account: Some("Coinbase".to_string()),
+            last_asset: Some("ETH".to_string()),
+        },
         ..ConversationState::default()
     };
     let result = provider
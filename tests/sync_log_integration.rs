@@ -0,0 +1,85 @@
+use cryptofolio::core::holdings::HoldingChangeSource;
+use cryptofolio::db::sync_log::{
+    SyncChange, SyncCounts, SYNC_STATUS_FAILED, SYNC_STATUS_RUNNING, SYNC_STATUS_SUCCESS,
+};
+use cryptofolio::db::{HoldingRepository, SyncLogRepository};
+use cryptofolio::error::Result;
+use rust_decimal::Decimal;
+
+mod common;
+
+#[tokio::test]
+async fn test_sync_log_records_completion() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "binance", "Binance").await?;
+
+    let repo = SyncLogRepository::new(&pool);
+    let id = repo.start("binance").await?;
+
+    let running = repo.list(None, 20).await?;
+    assert_eq!(running[0].status, SYNC_STATUS_RUNNING);
+    assert!(running[0].completed_at.is_none());
+
+    repo.complete(id, SyncCounts { updated: 2, added: 1, removed: 3 }).await?;
+
+    let entries = repo.list(Some("binance"), 20).await?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].status, SYNC_STATUS_SUCCESS);
+    assert_eq!(entries[0].holdings_updated, 2);
+    assert_eq!(entries[0].holdings_added, 1);
+    assert_eq!(entries[0].holdings_removed, 3);
+    assert!(entries[0].completed_at.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_log_records_failure_and_filters() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "binance", "Binance").await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+
+    let repo = SyncLogRepository::new(&pool);
+    let id = repo.start("binance").await?;
+    repo.fail(id, "API key rejected").await?;
+    repo.start("ledger").await?;
+
+    let entries = repo.list(Some("binance"), 20).await?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].status, SYNC_STATUS_FAILED);
+    assert_eq!(entries[0].error_message.as_deref(), Some("API key rejected"));
+
+    assert_eq!(repo.list(None, 1).await?.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_log_lists_changes_per_run() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "binance", "Binance").await?;
+
+    let sync_log = SyncLogRepository::new(&pool);
+    let holdings = HoldingRepository::new(&pool);
+
+    let first = sync_log.start("binance").await?;
+    holdings.record_change("binance", "btc", Decimal::ZERO, Decimal::from(2), HoldingChangeSource::Sync, Some(first)).await?;
+    let second = sync_log.start("binance").await?;
+    holdings.record_change("binance", "eth", Decimal::from(5), Decimal::ZERO, HoldingChangeSource::Sync, Some(second)).await?;
+    holdings.record_change("binance", "btc", Decimal::from(2), Decimal::from(3), HoldingChangeSource::Sync, Some(second)).await?;
+
+    let changes = sync_log.list_changes(second).await?;
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].asset, "BTC");
+    assert_eq!(changes[0].delta(), Decimal::ONE);
+    assert_eq!(changes[1].asset, "ETH");
+    assert_eq!(changes[1].delta(), Decimal::from(-5));
+
+    assert_eq!(sync_log.list_changes(first).await?, vec![SyncChange {
+        asset: "BTC".to_string(),
+        previous_quantity: Decimal::ZERO,
+        new_quantity: Decimal::from(2),
+    }]);
+
+    Ok(())
+}
@@ -0,0 +1,278 @@
+use chrono::{TimeZone, Utc};
+use cryptofolio::cli::commands::handle_tx_command;
+use cryptofolio::cli::TxCommands;
+use cryptofolio::core::pnl::CostBasisMethod;
+use cryptofolio::core::transaction::{Transaction, TransactionType};
+use cryptofolio::db::{HoldingRepository, RealizedGainRepository, TransactionFilter, TransactionRepository, TransactionSearchQuery};
+use cryptofolio::error::Result;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+mod common;
+
+#[tokio::test]
+async fn test_list_transactions_with_filter() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    common::create_account(&pool, "trezor", "Trezor").await?;
+
+    let repo = TransactionRepository::new(&pool);
+    let jan = Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap();
+    let jun = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+    let dec = Utc.with_ymd_and_hms(2024, 12, 1, 0, 0, 0).unwrap();
+
+    repo.insert(&Transaction::new_buy("ledger", "BTC", Decimal::ONE, Decimal::from(40000), jan)).await?;
+    repo.insert(&Transaction::new_buy("ledger", "ETH", Decimal::TWO, Decimal::from(3000), jun)).await?;
+    repo.insert(&Transaction::new_swap("ledger", "ETH", Decimal::ONE, "SOL", Decimal::TEN, dec)).await?;
+    repo.insert(&Transaction::new_transfer("ledger", "trezor", "BTC", Decimal::ONE, dec)).await?;
+
+    // Buys in the first half of the year
+    let filter = TransactionFilter {
+        tx_types: vec![TransactionType::Buy],
+        from: Some(jan),
+        to: Some(jun),
+        ..Default::default()
+    };
+    let buys = repo.list(&filter).await?;
+    assert_eq!(buys.len(), 2);
+
+    // Asset matches either side, newest first
+    let filter = TransactionFilter {
+        asset: Some("eth".to_string()),
+        ..Default::default()
+    };
+    let types: Vec<TransactionType> = repo.list(&filter).await?.iter().map(|tx| tx.tx_type).collect();
+    assert_eq!(types, vec![TransactionType::Swap, TransactionType::Buy]);
+
+    // Transfers count for both accounts
+    let filter = TransactionFilter {
+        account_id: Some("trezor".to_string()),
+        limit: Some(5),
+        ..Default::default()
+    };
+    let transfers = repo.list(&filter).await?;
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].tx_type, TransactionType::TransferInternal);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_transactions() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+
+    let repo = TransactionRepository::new(&pool);
+    let jan = Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap();
+    let jun = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+
+    let mut dca = Transaction::new_buy("ledger", "BTC", Decimal::ONE, Decimal::from(40000), jan);
+    dca.notes = Some("Monthly DCA purchase".to_string());
+    repo.insert(&dca).await?;
+    let mut dca = Transaction::new_buy("ledger", "ETH", Decimal::TWO, Decimal::from(3000), jun);
+    dca.notes = Some("dca purchase via Ledger".to_string());
+    repo.insert(&dca).await?;
+    repo.insert(&Transaction::new_swap("ledger", "ETH", Decimal::ONE, "SOL", Decimal::TEN, jun)).await?;
+
+    // Notes match regardless of case
+    let query = TransactionSearchQuery { text: Some("DCA Purchase".to_string()), ..Default::default() };
+    assert_eq!(repo.search(&query).await?.len(), 2);
+
+    // Asset symbols match too, on either side
+    let query = TransactionSearchQuery { text: Some("sol".to_string()), ..Default::default() };
+    let found = repo.search(&query).await?;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].tx_type, TransactionType::Swap);
+
+    // Filters narrow the text match
+    let query = TransactionSearchQuery {
+        text: Some("dca".to_string()),
+        filter: TransactionFilter {
+            asset: Some("ETH".to_string()),
+            from: Some(jun),
+            notes: Some("ledger".to_string()),
+            ..Default::default()
+        },
+    };
+    let found = repo.search(&query).await?;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].to_asset.as_deref(), Some("ETH"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_transaction() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+
+    let repo = TransactionRepository::new(&pool);
+    let id = repo
+        .insert(&Transaction::new_buy("ledger", "BTC", Decimal::ONE, Decimal::from(40000), Utc::now()))
+        .await?;
+    assert!(repo.get_by_id(id).await?.is_some());
+
+    repo.delete_by_id(id).await?;
+    assert!(repo.get_by_id(id).await?.is_none());
+
+    // Deleting again reports the missing transaction
+    assert!(repo.delete_by_id(id).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_record_fee_transaction() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "metamask", "MetaMask").await?;
+
+    let holdings = HoldingRepository::new(&pool);
+    holdings.add_quantity("metamask", "ETH", Decimal::ONE, None, None).await?;
+
+    let fee = Decimal::new(3, 3);
+    holdings.remove_quantity("metamask", "ETH", fee).await?;
+    let repo = TransactionRepository::new(&pool);
+    let id = repo.insert(&Transaction::new_fee("metamask", "ETH", fee, Utc::now())).await?;
+
+    let tx = repo.get_by_id(id).await?.unwrap();
+    assert_eq!(tx.tx_type, TransactionType::Fee);
+    assert_eq!(tx.from_quantity, Some(fee));
+    assert_eq!(tx.fee_asset.as_deref(), Some("ETH"));
+
+    let eth = holdings.get("metamask", "ETH").await?.unwrap();
+    assert_eq!(eth.quantity, Decimal::ONE - fee);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_realized_gains_filtered_by_date() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+
+    let repo = TransactionRepository::new(&pool);
+    let jan = Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap();
+    let jun = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+    let feb_next = Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap();
+
+    repo.insert(&Transaction::new_buy("ledger", "BTC", Decimal::TWO, Decimal::from(40000), jan)).await?;
+    repo.insert(&Transaction::new_sell("ledger", "BTC", Decimal::ONE, Decimal::from(60000), jun)).await?;
+    repo.insert(&Transaction::new_sell("ledger", "BTC", Decimal::ONE, Decimal::from(50000), feb_next)).await?;
+
+    let gains = RealizedGainRepository::new(&pool);
+    let all = gains.report(CostBasisMethod::Fifo, None, None).await?;
+    assert_eq!(all.realized_gain(), Decimal::from(30000));
+    assert!(all.open_lots.is_empty());
+
+    let year_2024 = Utc.with_ymd_and_hms(2024, 12, 31, 0, 0, 0).unwrap();
+    let report = gains.report(CostBasisMethod::Fifo, None, Some(year_2024)).await?;
+    assert_eq!(report.disposals.len(), 1);
+    assert_eq!(report.realized_gain(), Decimal::from(20000));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_buy_fee_is_deducted_from_fee_asset() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    let opts = common::quiet_options();
+
+    let holdings = HoldingRepository::new(&pool);
+    holdings.add_quantity("ledger", "USDT", Decimal::from(100), None, None).await?;
+
+    handle_tx_command(common::buy_with_fee("BTC", "0.1", "30000", "15", "usdt"), &pool, &opts).await?;
+    assert_eq!(holdings.get("ledger", "USDT").await?.unwrap().quantity, Decimal::from(85));
+    assert_eq!(holdings.get("ledger", "BTC").await?.unwrap().quantity, Decimal::new(1, 1));
+
+    // A fee in the bought asset comes out of the purchase
+    handle_tx_command(common::buy_with_fee("BTC", "0.1", "30000", "0.001", "BTC"), &pool, &opts).await?;
+    assert_eq!(holdings.get("ledger", "BTC").await?.unwrap().quantity, Decimal::new(199, 3));
+
+    // A fee the account can't cover records nothing
+    assert!(handle_tx_command(common::buy_with_fee("BTC", "0.1", "30000", "1", "SOL"), &pool, &opts).await.is_err());
+    assert_eq!(holdings.get("ledger", "BTC").await?.unwrap().quantity, Decimal::new(199, 3));
+    assert!(holdings.get("ledger", "SOL").await?.is_none());
+
+    let buys = TransactionRepository::new(&pool).list(&TransactionFilter::default()).await?;
+    assert_eq!(buys.len(), 2);
+    assert!(buys.iter().any(|tx| tx.fee_asset.as_deref() == Some("USDT") && tx.fee == Some(Decimal::from(15))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_transactions_paginated() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+
+    let repo = TransactionRepository::new(&pool);
+    for day in 1..=7 {
+        let timestamp = Utc.with_ymd_and_hms(2024, 3, day, 0, 0, 0).unwrap();
+        repo.insert(&Transaction::new_buy("ledger", "BTC", Decimal::from(day), Decimal::from(60000), timestamp)).await?;
+    }
+    repo.insert(&Transaction::new_buy("ledger", "ETH", Decimal::ONE, Decimal::from(3000), Utc::now())).await?;
+
+    let filter = TransactionFilter {
+        asset: Some("BTC".to_string()),
+        // Ignored when paging
+        limit: Some(1),
+        ..Default::default()
+    };
+
+    // Newest first, so the second page of three starts at day 4
+    let (page, total) = repo.list_paginated(&filter, 2, 3).await?;
+    assert_eq!(total, 7);
+    let quantities: Vec<Decimal> = page.iter().filter_map(|tx| tx.to_quantity).collect();
+    assert_eq!(quantities, vec![Decimal::from(4), Decimal::from(3), Decimal::from(2)]);
+
+    let (last, _) = repo.list_paginated(&filter, 3, 3).await?;
+    assert_eq!(last.len(), 1);
+
+    let (past_end, total) = repo.list_paginated(&filter, 4, 3).await?;
+    assert!(past_end.is_empty());
+    assert_eq!(total, 7);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fee_in_bought_asset_spreads_cost_over_net_quantity() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+
+    handle_tx_command(common::buy_with_fee("ETH", "2", "3000", "0.01", "ETH"), &pool, &common::quiet_options()).await?;
+
+    // The 0.01 ETH fee is taken from the 2 ETH, so $6,000 buys 1.99 ETH
+    let eth = HoldingRepository::new(&pool).get("ledger", "ETH").await?.unwrap();
+    assert_eq!(eth.quantity, Decimal::from_str("1.99").unwrap());
+    assert_eq!(eth.avg_cost_basis.unwrap().round_dp(2), Decimal::from_str("3015.08").unwrap());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_with_adjust_holdings_reverses_fee() -> Result<()> {
+    let pool = common::setup_test_db().await?;
+    common::create_account(&pool, "ledger", "Ledger").await?;
+    let opts = common::quiet_options();
+
+    let holdings = HoldingRepository::new(&pool);
+    holdings.add_quantity("ledger", "USDT", Decimal::from(100), None, None).await?;
+
+    handle_tx_command(common::buy_with_fee("ETH", "2", "3000", "0.01", "ETH"), &pool, &opts).await?;
+    handle_tx_command(common::buy_with_fee("ETH", "1", "3000", "5", "USDT"), &pool, &opts).await?;
+    assert_eq!(holdings.get("ledger", "USDT").await?.unwrap().quantity, Decimal::from(95));
+
+    let repo = TransactionRepository::new(&pool);
+    for tx in repo.list(&TransactionFilter::default()).await? {
+        let delete = TxCommands::Delete { id: tx.id, yes: true, adjust_holdings: true, dry_run: false };
+        handle_tx_command(delete, &pool, &opts).await?;
+    }
+
+    // Both buys are undone, and the USDT fee is credited back
+    assert!(holdings.get("ledger", "ETH").await?.is_none());
+    assert_eq!(holdings.get("ledger", "USDT").await?.unwrap().quantity, Decimal::from(100));
+
+    Ok(())
+}